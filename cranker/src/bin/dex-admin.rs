@@ -0,0 +1,347 @@
+use std::fs;
+
+use agnostic_orderbook::state::MARKET_STATE_LEN;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use dex_cranker::{
+    inspect::MarketDecimals,
+    market_template::{MarketParameters, MarketTemplate},
+};
+use dex_v4::{
+    instruction_auto::{
+        create_market, set_crank_fee_share, set_create_market_config, set_min_resting_slots,
+        set_volatility_fee,
+    },
+    state::{CreateMarketConfig, DEX_STATE_LEN},
+};
+use mpl_token_metadata::pda::find_metadata_account;
+use solana_clap_utils::{
+    fee_payer::{fee_payer_arg, FEE_PAYER_ARG},
+    input_parsers::{keypair_of, pubkey_of},
+    input_validators::{is_keypair, is_pubkey},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    instruction::Instruction, program_pack::Pack, pubkey::Pubkey, system_instruction,
+    system_program,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+
+fn main() {
+    let matches = App::new("dex-admin")
+        .version("0.1")
+        .author("Bonfida")
+        .about("Administers the markets of a serum dex v4 deployment")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("url")
+                .short("u")
+                .long("url")
+                .help("A Solana RPC endpoint url")
+                .takes_value(true)
+                .global(true),
+        )
+        .subcommand(
+            SubCommand::with_name("create-market")
+                .about("Creates a market from a TOML market template")
+                .arg(fee_payer_arg().required(true))
+                .arg(
+                    Arg::with_name("template")
+                        .short("t")
+                        .long("template")
+                        .help("The TOML market template to apply")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("program_id")
+                        .short("p")
+                        .long("program-id")
+                        .help("The pubkey of the dex program, defaults to the mainnet deployment")
+                        .takes_value(true)
+                        .validator(is_pubkey),
+                )
+                .arg(
+                    Arg::with_name("base_mint")
+                        .long("base-mint")
+                        .help("The mint of the base token")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("quote_mint")
+                        .long("quote-mint")
+                        .help("The mint of the quote token")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("market_admin")
+                        .long("market-admin")
+                        .help("The keypair of the market admin, defaults to the fee payer")
+                        .takes_value(true)
+                        .validator(is_keypair),
+                )
+                .arg(
+                    Arg::with_name("base_symbol")
+                        .long("base-symbol")
+                        .help("The symbol of the base token")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("quote_symbol")
+                        .long("quote-symbol")
+                        .help("The symbol of the quote token")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("market_name")
+                        .long("market-name")
+                        .help("The display name of the market")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("dry_run").long("dry-run").help(
+                        "Only validates the template and prints the derived market parameters",
+                    ),
+                ),
+        )
+        .get_matches();
+    let endpoint = matches
+        .value_of("url")
+        .unwrap_or("https://solana-api.projectserum.com")
+        .to_owned();
+    let connection = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
+
+    if let ("create-market", Some(matches)) = matches.subcommand() {
+        create_market_from_template(&connection, matches)
+    }
+}
+
+fn create_market_from_template(connection: &RpcClient, matches: &ArgMatches) {
+    let template_path = matches.value_of("template").unwrap();
+    let template = fs::read_to_string(template_path).expect("Failed to read the market template");
+    let template = MarketTemplate::from_toml(&template).unwrap_or_else(|e| panic!("{}", e));
+    let program_id = pubkey_of(matches, "program_id").unwrap_or(dex_v4::ID);
+    let base_mint = pubkey_of(matches, "base_mint").expect("Invalid base mint Pubkey");
+    let quote_mint = pubkey_of(matches, "quote_mint").expect("Invalid quote mint Pubkey");
+    let fee_payer = keypair_of(matches, FEE_PAYER_ARG.name).unwrap();
+    let market_admin = keypair_of(matches, "market_admin");
+    let market_admin = market_admin.as_ref().unwrap_or(&fee_payer);
+
+    let mint_decimals = |mint: &Pubkey| {
+        let data = connection
+            .get_account_data(mint)
+            .expect("Failed to fetch the mint");
+        spl_token::state::Mint::unpack(&data)
+            .expect("Invalid mint")
+            .decimals
+    };
+    let decimals = MarketDecimals {
+        base: mint_decimals(&base_mint),
+        quote: mint_decimals(&quote_mint),
+    };
+    let parameters = template
+        .validate(decimals)
+        .unwrap_or_else(|e| panic!("Invalid {} template: {}", template.name, e));
+    println!("Applying the {} template: {:#?}", template.name, parameters);
+    if matches.is_present("dry_run") {
+        return;
+    }
+
+    let market = Keypair::new();
+    let orderbook = Keypair::new();
+    let event_queue = Keypair::new();
+    let bids = Keypair::new();
+    let asks = Keypair::new();
+    let (market_signer, signer_nonce) =
+        Pubkey::find_program_address(&[&market.pubkey().to_bytes()], &program_id);
+    let base_vault = get_associated_token_address(&market_signer, &base_mint);
+    let quote_vault = get_associated_token_address(&market_signer, &quote_mint);
+
+    let create_program_account = |account: &Keypair, space: usize| {
+        system_instruction::create_account(
+            &fee_payer.pubkey(),
+            &account.pubkey(),
+            connection
+                .get_minimum_balance_for_rent_exemption(space)
+                .unwrap(),
+            space as u64,
+            &program_id,
+        )
+    };
+    send(
+        connection,
+        &[
+            create_program_account(&market, DEX_STATE_LEN),
+            create_program_account(&orderbook, MARKET_STATE_LEN),
+            create_program_account(&event_queue, parameters.event_queue_space),
+        ],
+        &[&fee_payer, &market, &orderbook, &event_queue],
+    );
+    send(
+        connection,
+        &[
+            create_program_account(&bids, parameters.slab_space),
+            create_program_account(&asks, parameters.slab_space),
+        ],
+        &[&fee_payer, &bids, &asks],
+    );
+
+    // The listing fee of the deployment, if any, is paid by the fee payer
+    let (create_market_config, _) = Pubkey::find_program_address(
+        &[set_create_market_config::CREATE_MARKET_CONFIG_SEED],
+        &program_id,
+    );
+    let listing_fee = connection
+        .get_account(&create_market_config)
+        .ok()
+        .and_then(|mut account| {
+            CreateMarketConfig::from_buffer(&mut account.data)
+                .ok()
+                .copied()
+        })
+        .filter(|config| config.listing_fee != 0);
+    let listing_fee_accounts = listing_fee.map(|config| {
+        if config.is_lamports_fee() {
+            println!("Paying a listing fee of {} lamports", config.listing_fee);
+            (fee_payer.pubkey(), config.treasury, system_program::ID)
+        } else {
+            println!(
+                "Paying a listing fee of {} tokens of mint {}",
+                config.listing_fee, config.fee_mint
+            );
+            (
+                get_associated_token_address(&fee_payer.pubkey(), &config.fee_mint),
+                config.treasury,
+                spl_token::ID,
+            )
+        }
+    });
+    let fee_payer_key = fee_payer.pubkey();
+
+    let MarketParameters {
+        tick_size,
+        base_currency_multiplier,
+        quote_currency_multiplier,
+        min_base_order_size,
+        min_order_tick_distance,
+        ..
+    } = parameters;
+    send(
+        connection,
+        &[
+            create_associated_token_account(&fee_payer.pubkey(), &market_signer, &base_mint),
+            create_associated_token_account(&fee_payer.pubkey(), &market_signer, &quote_mint),
+            create_market(
+                program_id,
+                create_market::Accounts {
+                    market: &market.pubkey(),
+                    orderbook: &orderbook.pubkey(),
+                    base_vault: &base_vault,
+                    quote_vault: &quote_vault,
+                    market_admin: &market_admin.pubkey(),
+                    event_queue: &event_queue.pubkey(),
+                    asks: &asks.pubkey(),
+                    bids: &bids.pubkey(),
+                    token_metadata: &find_metadata_account(&base_mint).0,
+                    create_market_config: &create_market_config,
+                    fee_payer: listing_fee_accounts.as_ref().map(|_| &fee_payer_key),
+                    fee_source: listing_fee_accounts.as_ref().map(|(source, _, _)| source),
+                    treasury: listing_fee_accounts
+                        .as_ref()
+                        .map(|(_, treasury, _)| treasury),
+                    fee_program: listing_fee_accounts.as_ref().map(|(_, _, program)| program),
+                },
+                create_market::Params {
+                    signer_nonce: signer_nonce as u64,
+                    min_base_order_size,
+                    tick_size,
+                    base_currency_multiplier,
+                    quote_currency_multiplier,
+                    min_order_tick_distance,
+                    base_symbol: encode_fixed_string(matches.value_of("base_symbol")),
+                    quote_symbol: encode_fixed_string(matches.value_of("quote_symbol")),
+                    market_name: encode_fixed_string(matches.value_of("market_name")),
+                },
+            ),
+        ],
+        &[&fee_payer],
+    );
+
+    // The fee settings are only sent when the template departs from the defaults of a new market
+    let fees = &template.fees;
+    let mut fee_instructions = vec![];
+    if fees.crank_fee_share_bps != 0 {
+        fee_instructions.push(set_crank_fee_share(
+            program_id,
+            set_crank_fee_share::Accounts {
+                market: &market.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_crank_fee_share::Params {
+                crank_fee_share_bps: fees.crank_fee_share_bps,
+            },
+        ));
+    }
+    if fees.volatility_window_slots != 0 {
+        fee_instructions.push(set_volatility_fee(
+            program_id,
+            set_volatility_fee::Accounts {
+                market: &market.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_volatility_fee::Params {
+                window_slots: fees.volatility_window_slots,
+                threshold_bps: fees.volatility_threshold_bps,
+                max_multiplier: fees.max_volatility_multiplier,
+            },
+        ));
+    }
+    if fees.min_resting_slots != 0 {
+        fee_instructions.push(set_min_resting_slots(
+            program_id,
+            set_min_resting_slots::Accounts {
+                market: &market.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_min_resting_slots::Params {
+                min_resting_slots: fees.min_resting_slots,
+            },
+        ));
+    }
+    if !fee_instructions.is_empty() {
+        send(connection, &fee_instructions, &[&fee_payer, market_admin]);
+    }
+
+    println!("Market address {}", market.pubkey());
+}
+
+fn send(connection: &RpcClient, instructions: &[Instruction], signers: &[&Keypair]) {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+    let (recent_blockhash, _) = connection.get_recent_blockhash().unwrap();
+    transaction.sign(signers, recent_blockhash);
+    let signature = connection
+        .send_and_confirm_transaction(&transaction)
+        .unwrap();
+    println!("{}", signature);
+}
+
+fn encode_fixed_string<const N: usize>(s: Option<&str>) -> [u8; N] {
+    let mut bytes = [0; N];
+    let s = s.unwrap_or_default().as_bytes();
+    assert!(
+        s.len() <= N,
+        "{} is longer than {} bytes",
+        String::from_utf8_lossy(s),
+        N
+    );
+    bytes[..s.len()].copy_from_slice(s);
+    bytes
+}
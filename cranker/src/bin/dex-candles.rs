@@ -0,0 +1,201 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::{App, Arg};
+use dex_cranker::candles::{candles_to_json, parse_fill_logs, CandleBuilder, TimedFill};
+use solana_clap_utils::{input_parsers::pubkey_of, input_validators::is_pubkey};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const SIGNATURES_PAGE_SIZE: usize = 1_000;
+
+fn main() {
+    let matches = App::new("dex-candles")
+        .version("0.1")
+        .author("Bonfida")
+        .about("Serves OHLCV candles of a serum dex v4 market")
+        .arg(
+            Arg::with_name("url")
+                .short("u")
+                .long("url")
+                .help("A Solana RPC endpoint url")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("market")
+                .short("m")
+                .long("market")
+                .help("The pubkey of the dex market to build candles for")
+                .takes_value(true)
+                .validator(is_pubkey)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("interval")
+                .short("i")
+                .long("interval")
+                .help("The candle interval in seconds")
+                .takes_value(true)
+                .default_value("60"),
+        )
+        .arg(
+            Arg::with_name("history")
+                .long("history")
+                .help("The number of seconds of history to keep")
+                .takes_value(true)
+                .default_value("86400"),
+        )
+        .arg(
+            Arg::with_name("port")
+                .long("port")
+                .help("The port of the candles HTTP server")
+                .takes_value(true)
+                .default_value("8080"),
+        )
+        .get_matches();
+    let endpoint = matches
+        .value_of("url")
+        .unwrap_or("https://solana-api.projectserum.com")
+        .to_owned();
+    let market = pubkey_of(&matches, "market").expect("Invalid market Pubkey");
+    let interval = i64::from_str(matches.value_of("interval").unwrap()).expect("Invalid interval");
+    let history = i64::from_str(matches.value_of("history").unwrap()).expect("Invalid history");
+    let port = u16::from_str(matches.value_of("port").unwrap()).expect("Invalid port");
+
+    let builder = Arc::new(Mutex::new(CandleBuilder::new(interval)));
+
+    let indexer_builder = Arc::clone(&builder);
+    thread::spawn(move || index_fills(endpoint, market, history, indexer_builder));
+
+    let listener = TcpListener::bind(("0.0.0.0", port)).unwrap();
+    println!("Serving candles on port {}", port);
+    for stream in listener.incoming().flatten() {
+        if let Err(e) = serve(stream, &builder) {
+            println!("Failed to serve request: {:?}", e);
+        }
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+fn index_fills(endpoint: String, market: Pubkey, history: i64, builder: Arc<Mutex<CandleBuilder>>) {
+    let connection = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
+    let mut last_signature: Option<Signature> = None;
+    loop {
+        match fetch_new_fills(&connection, &market, last_signature, now() - history) {
+            Ok((fills, latest)) => {
+                let mut builder = builder.lock().unwrap();
+                for f in fills.iter() {
+                    builder.push(f);
+                }
+                builder.prune(now() - history);
+                last_signature = latest.or(last_signature);
+            }
+            Err(e) => println!("Failed to fetch fills: {:?}", e),
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Fetches the fills of the transactions which were confirmed after `until`, in chronological order,
+/// as well as the most recent transaction signature
+fn fetch_new_fills(
+    connection: &RpcClient,
+    market: &Pubkey,
+    until: Option<Signature>,
+    oldest_timestamp: i64,
+) -> Result<(Vec<TimedFill>, Option<Signature>), Box<dyn std::error::Error>> {
+    let mut signatures = vec![];
+    let mut before = None;
+    'pages: loop {
+        let page = connection.get_signatures_for_address_with_config(
+            market,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(SIGNATURES_PAGE_SIZE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let page_len = page.len();
+        for s in page {
+            if s.block_time.unwrap_or(0) < oldest_timestamp {
+                break 'pages;
+            }
+            let signature = Signature::from_str(&s.signature)?;
+            before = Some(signature);
+            signatures.push((signature, s.block_time.unwrap_or(0), s.err.is_none()));
+        }
+        if page_len < SIGNATURES_PAGE_SIZE {
+            break;
+        }
+    }
+    let latest = signatures.first().map(|(s, _, _)| *s);
+
+    let mut fills = vec![];
+    for (signature, block_time, _) in signatures.iter().rev().filter(|(_, _, ok)| *ok) {
+        let transaction = connection.get_transaction(signature, UiTransactionEncoding::Json)?;
+        let logs = transaction
+            .transaction
+            .meta
+            .and_then(|m| m.log_messages)
+            .unwrap_or_default();
+        fills.extend(
+            parse_fill_logs(market, &logs)
+                .into_iter()
+                .map(|fill| TimedFill {
+                    timestamp: *block_time,
+                    fill,
+                }),
+        );
+    }
+    Ok((fills, latest))
+}
+
+/// Answers `GET /candles?from=<timestamp>&to=<timestamp>` with a JSON array of candles
+fn serve(mut stream: TcpStream, builder: &Mutex<CandleBuilder>) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (route, query) = path.split_at(path.find('?').unwrap_or_else(|| path.len()));
+
+    if route != "/candles" {
+        return stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+    }
+
+    let mut from = i64::MIN;
+    let mut to = i64::MAX;
+    for (key, value) in query
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|p| p.split_once('='))
+    {
+        match (key, i64::from_str(value)) {
+            ("from", Ok(v)) => from = v,
+            ("to", Ok(v)) => to = v,
+            _ => {}
+        }
+    }
+
+    let body = candles_to_json(builder.lock().unwrap().candles(from, to));
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
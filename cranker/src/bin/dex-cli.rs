@@ -0,0 +1,212 @@
+use std::str::FromStr;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use dex_cranker::{
+    inspect::{MarketDecimals, MarketView, UserAccountView},
+    receipt::{verify_receipt, OrderReceipt},
+};
+use dex_v4::state::{DexState, UserAccount, DEX_STATE_LEN};
+use solana_clap_utils::{
+    input_parsers::{keypair_of, pubkey_of},
+    input_validators::{is_keypair, is_pubkey},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+
+fn main() {
+    let address_arg = |help| {
+        Arg::with_name("address")
+            .help(help)
+            .takes_value(true)
+            .validator(is_pubkey)
+            .required(true)
+    };
+    let matches = App::new("dex-cli")
+        .version("0.1")
+        .author("Bonfida")
+        .about("Inspects the accounts and fills of a serum dex v4 deployment")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("url")
+                .short("u")
+                .long("url")
+                .help("A Solana RPC endpoint url")
+                .takes_value(true)
+                .global(true),
+        )
+        .subcommand(
+            SubCommand::with_name("inspect")
+                .about("Prints the decoded state of an account")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("market")
+                        .about("Prints a dex market")
+                        .arg(address_arg("The pubkey of the dex market")),
+                )
+                .subcommand(
+                    SubCommand::with_name("account")
+                        .about("Prints a user account and its open orders")
+                        .arg(address_arg("The pubkey of the user account")),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("receipt")
+                .about("Issues and verifies signed receipts of fills")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("issue")
+                        .about("Prints a signed receipt of a fill, encoded in base64")
+                        .arg(
+                            Arg::with_name("signature")
+                                .help("The signature of the transaction which logged the fill")
+                                .takes_value(true)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("market")
+                                .long("market")
+                                .help("The pubkey of the dex market")
+                                .takes_value(true)
+                                .validator(is_pubkey)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("issuer")
+                                .long("issuer")
+                                .help("The keypair signing the receipt")
+                                .takes_value(true)
+                                .validator(is_keypair)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("fill_index")
+                                .long("fill-index")
+                                .help("The index of the fill among the fills of the market logged by the transaction")
+                                .takes_value(true)
+                                .default_value("0"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("verify")
+                        .about("Checks a receipt against the chain")
+                        .arg(
+                            Arg::with_name("receipt")
+                                .help("The receipt, encoded in base64")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                ),
+        )
+        .get_matches();
+    let endpoint = matches
+        .value_of("url")
+        .unwrap_or("https://solana-api.projectserum.com")
+        .to_owned();
+    let connection = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
+
+    match matches.subcommand() {
+        ("inspect", Some(matches)) => match matches.subcommand() {
+            ("market", Some(matches)) => inspect_market(&connection, matches),
+            ("account", Some(matches)) => inspect_account(&connection, matches),
+            _ => unreachable!(),
+        },
+        ("receipt", Some(matches)) => match matches.subcommand() {
+            ("issue", Some(matches)) => issue_receipt(&connection, matches),
+            ("verify", Some(matches)) => check_receipt(&connection, matches),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+fn inspect_market(connection: &RpcClient, matches: &ArgMatches) {
+    let address = pubkey_of(matches, "address").expect("Invalid market Pubkey");
+    let data = connection
+        .get_account_data(&address)
+        .expect("Failed to fetch the market");
+    let state = parse_market(&data);
+    let decimals = get_decimals(connection, state);
+    println!(
+        "{}",
+        MarketView {
+            address,
+            state,
+            decimals
+        }
+    );
+}
+
+fn inspect_account(connection: &RpcClient, matches: &ArgMatches) {
+    let address = pubkey_of(matches, "address").expect("Invalid user account Pubkey");
+    let mut data = connection
+        .get_account_data(&address)
+        .expect("Failed to fetch the user account");
+    let user_account = UserAccount::from_buffer(&mut data).expect("Not a user account");
+    let market_data = connection
+        .get_account_data(&user_account.header.market)
+        .expect("Failed to fetch the market");
+    let market_state = parse_market(&market_data);
+    let decimals = get_decimals(connection, market_state);
+    println!(
+        "{}",
+        UserAccountView {
+            address,
+            header: user_account.header,
+            orders: user_account.orders(),
+            market_state,
+            decimals
+        }
+    );
+}
+
+fn issue_receipt(connection: &RpcClient, matches: &ArgMatches) {
+    let signature = Signature::from_str(matches.value_of("signature").unwrap())
+        .expect("Invalid transaction signature");
+    let market = pubkey_of(matches, "market").expect("Invalid market Pubkey");
+    let issuer = keypair_of(matches, "issuer").expect("Invalid issuer keypair");
+    let fill_index = matches
+        .value_of("fill_index")
+        .unwrap()
+        .parse()
+        .expect("Invalid fill index");
+    let receipt = OrderReceipt::fetch(connection, &signature, &market, fill_index, &issuer)
+        .expect("Failed to issue the receipt");
+    println!("{}", receipt.to_base64());
+}
+
+fn check_receipt(connection: &RpcClient, matches: &ArgMatches) {
+    let receipt = OrderReceipt::from_base64(matches.value_of("receipt").unwrap())
+        .expect("Failed to decode the receipt");
+    match verify_receipt(connection, &receipt) {
+        Ok(()) => println!(
+            "Valid receipt issued by {} for a fill of {} base at slot {}",
+            receipt.issuer, receipt.fill.base_qty, receipt.slot
+        ),
+        Err(e) => {
+            eprintln!("Invalid receipt: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_market(data: &[u8]) -> &DexState {
+    data.get(..DEX_STATE_LEN)
+        .and_then(|d| bytemuck::try_from_bytes::<DexState>(d).ok())
+        .expect("Not a dex market")
+}
+
+fn get_decimals(connection: &RpcClient, state: &DexState) -> MarketDecimals {
+    let mint_decimals = |mint: &Pubkey| {
+        let data = connection
+            .get_account_data(mint)
+            .expect("Failed to fetch the mint");
+        spl_token::state::Mint::unpack(&data)
+            .expect("Invalid mint")
+            .decimals
+    };
+    MarketDecimals {
+        base: mint_decimals(&state.base_mint),
+        quote: mint_decimals(&state.quote_mint),
+    }
+}
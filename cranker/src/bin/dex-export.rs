@@ -0,0 +1,161 @@
+use std::{collections::HashSet, fs, str::FromStr};
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use dex_cranker::fees::{parse_fee_logs, FeeReport};
+use solana_clap_utils::{
+    input_parsers::{pubkey_of, pubkeys_of},
+    input_validators::is_pubkey,
+};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+
+const SIGNATURES_PAGE_SIZE: usize = 1_000;
+
+fn main() {
+    let matches = App::new("dex-export")
+        .version("0.1")
+        .author("Bonfida")
+        .about("Exports reports built from the transactions of a serum dex v4 deployment")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("url")
+                .short("u")
+                .long("url")
+                .help("A Solana RPC endpoint url")
+                .takes_value(true)
+                .global(true),
+        )
+        .subcommand(
+            SubCommand::with_name("fees")
+                .about("Writes the fees and referral fees accrued per market and per referrer as CSV")
+                .arg(
+                    Arg::with_name("program_id")
+                        .short("p")
+                        .long("program-id")
+                        .help("The pubkey of the dex program, whose transactions are exported when no market is given")
+                        .takes_value(true)
+                        .validator(is_pubkey),
+                )
+                .arg(
+                    Arg::with_name("market")
+                        .short("m")
+                        .long("market")
+                        .help("The pubkey of a dex market to export, can be repeated")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .validator(is_pubkey),
+                )
+                .arg(
+                    Arg::with_name("from_slot")
+                        .long("from-slot")
+                        .help("The first slot of the exported range")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("to_slot")
+                        .long("to-slot")
+                        .help("The last slot of the exported range, defaults to the current slot")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .help("The CSV file to write, defaults to the standard output")
+                        .takes_value(true),
+                ),
+        )
+        .get_matches();
+    let endpoint = matches
+        .value_of("url")
+        .unwrap_or("https://solana-api.projectserum.com")
+        .to_owned();
+    let connection = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
+
+    if let ("fees", Some(matches)) = matches.subcommand() {
+        export_fees(&connection, matches)
+    }
+}
+
+fn export_fees(connection: &RpcClient, matches: &ArgMatches) {
+    let markets = pubkeys_of(matches, "market").unwrap_or_default();
+    let program_id = pubkey_of(matches, "program_id").unwrap_or(dex_v4::ID);
+    let from_slot = u64::from_str(matches.value_of("from_slot").unwrap()).expect("Invalid slot");
+    let to_slot = match matches.value_of("to_slot") {
+        Some(s) => u64::from_str(s).expect("Invalid slot"),
+        None => connection.get_slot().unwrap(),
+    };
+
+    // The fees of a whole deployment are found through the program's transactions
+    let addresses = if markets.is_empty() {
+        vec![program_id]
+    } else {
+        markets.clone()
+    };
+    let mut signatures = HashSet::new();
+    for address in addresses.iter() {
+        signatures.extend(fetch_signatures(connection, address, from_slot, to_slot).unwrap());
+    }
+
+    let mut report = FeeReport::new();
+    for signature in signatures.iter() {
+        let transaction = connection
+            .get_transaction(signature, UiTransactionEncoding::Json)
+            .unwrap();
+        let logs = transaction
+            .transaction
+            .meta
+            .and_then(|m| m.log_messages)
+            .unwrap_or_default();
+        for fee in parse_fee_logs(&markets, &logs) {
+            report.push(&fee);
+        }
+    }
+
+    let csv = report.to_csv();
+    match matches.value_of("output") {
+        Some(path) => fs::write(path, csv).unwrap(),
+        None => print!("{}", csv),
+    }
+}
+
+/// Fetches the signatures of the successful transactions of an address in a slot range
+fn fetch_signatures(
+    connection: &RpcClient,
+    address: &Pubkey,
+    from_slot: u64,
+    to_slot: u64,
+) -> Result<Vec<Signature>, Box<dyn std::error::Error>> {
+    let mut signatures = vec![];
+    let mut before = None;
+    'pages: loop {
+        let page = connection.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(SIGNATURES_PAGE_SIZE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let page_len = page.len();
+        for s in page {
+            if s.slot < from_slot {
+                break 'pages;
+            }
+            let signature = Signature::from_str(&s.signature)?;
+            before = Some(signature);
+            if s.slot <= to_slot && s.err.is_none() {
+                signatures.push(signature);
+            }
+        }
+        if page_len < SIGNATURES_PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(signatures)
+}
@@ -0,0 +1,119 @@
+//! Client-side request budgeting, which keeps the cranker within the rate limits of public RPC endpoints
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use solana_client::{client_error::ClientError, rpc_client::RpcClient};
+use solana_sdk::commitment_config::CommitmentConfig;
+
+/// The number of seconds of budget which can be spent in a burst
+const BURST_SECONDS: f64 = 2.0;
+
+/// A token bucket refilled at a constant rate of requests per second
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    requests_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(requests_per_second: f64) -> Self {
+        let capacity = (requests_per_second * BURST_SECONDS).max(1.0);
+        Self {
+            capacity,
+            tokens: capacity,
+            requests_per_second,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.requests_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Takes the given number of tokens, or returns how long to wait until they are available
+    pub fn try_acquire(&mut self, requests: u32, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        // A reservation larger than the bucket is granted once the bucket is full
+        let requests = (requests as f64).min(self.capacity);
+        if self.tokens >= requests {
+            self.tokens -= requests;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (requests - self.tokens) / self.requests_per_second,
+            ))
+        }
+    }
+
+    /// Empties the bucket, for an endpoint which has started rejecting requests
+    pub fn drain(&mut self, now: Instant) {
+        self.refill(now);
+        self.tokens = -self.capacity;
+    }
+}
+
+/// An ordered list of RPC endpoints, each with its own request budget. Requests go to the first endpoint with
+/// enough budget, so that the later endpoints act as fallbacks.
+pub struct RpcPool {
+    endpoints: Vec<(RpcClient, Option<TokenBucket>)>,
+}
+
+impl RpcPool {
+    /// A budget of `None` leaves the endpoints unlimited
+    pub fn new(endpoints: &[String], requests_per_second: Option<f64>) -> Self {
+        Self {
+            endpoints: endpoints
+                .iter()
+                .map(|e| {
+                    (
+                        RpcClient::new_with_commitment(e.clone(), CommitmentConfig::confirmed()),
+                        requests_per_second.map(TokenBucket::new),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Reserves the budget of a sequence of requests up-front, so that it is not interrupted by the rate limit.
+    ///
+    /// Blocks until an endpoint has enough budget and returns its index.
+    pub fn reserve(&mut self, requests: u32) -> usize {
+        loop {
+            let now = Instant::now();
+            let mut wait: Option<Duration> = None;
+            for (i, (_, bucket)) in self.endpoints.iter_mut().enumerate() {
+                match bucket.as_mut().map(|b| b.try_acquire(requests, now)) {
+                    None | Some(Ok(())) => return i,
+                    Some(Err(d)) => wait = Some(wait.map_or(d, |w| w.min(d))),
+                }
+            }
+            thread::sleep(wait.unwrap_or_default());
+        }
+    }
+
+    pub fn client(&self, index: usize) -> &RpcClient {
+        &self.endpoints[index].0
+    }
+
+    /// Backs off from an endpoint when an error shows that it is rate limiting the cranker
+    pub fn report_error(&mut self, index: usize, error: &ClientError) {
+        if is_rate_limited(error) {
+            println!("Endpoint {} is rate limiting, backing off", index);
+            if let Some(bucket) = self.endpoints[index].1.as_mut() {
+                bucket.drain(Instant::now());
+            }
+        }
+    }
+}
+
+fn is_rate_limited(error: &ClientError) -> bool {
+    let message = error.to_string();
+    message.contains("429") || message.contains("Too Many Requests")
+}
@@ -0,0 +1,129 @@
+//! OHLCV candles built from the fills logged by the dex program
+use std::collections::BTreeMap;
+
+use borsh::BorshDeserialize;
+use dex_v4::state::FillLog;
+use solana_program::pubkey::Pubkey;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// A fill with the timestamp of the block which included it
+#[derive(Debug, Clone, Copy)]
+pub struct TimedFill {
+    pub timestamp: i64,
+    pub fill: FillLog,
+}
+
+impl TimedFill {
+    /// The fill price, as a raw quote amount per raw base amount
+    pub fn price(&self) -> f64 {
+        self.fill.quote_qty as f64 / self.fill.base_qty as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    /// The unix timestamp of the start of the candle
+    pub start: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// The traded raw base amount
+    pub base_volume: u64,
+    /// The traded raw quote amount
+    pub quote_volume: u64,
+}
+
+impl Candle {
+    fn new(start: i64, price: f64) -> Self {
+        Self {
+            start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            base_volume: 0,
+            quote_volume: 0,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"start":{},"open":{},"high":{},"low":{},"close":{},"baseVolume":{},"quoteVolume":{}}}"#,
+            self.start,
+            self.open,
+            self.high,
+            self.low,
+            self.close,
+            self.base_volume,
+            self.quote_volume
+        )
+    }
+}
+
+/// Aggregates fills into candles of a fixed interval
+pub struct CandleBuilder {
+    interval: i64,
+    candles: BTreeMap<i64, Candle>,
+}
+
+impl CandleBuilder {
+    /// Creates a candle builder for an interval in seconds
+    pub fn new(interval: i64) -> Self {
+        assert!(interval > 0, "The candle interval should be positive");
+        Self {
+            interval,
+            candles: BTreeMap::new(),
+        }
+    }
+
+    pub fn interval(&self) -> i64 {
+        self.interval
+    }
+
+    /// Adds a fill to its candle. Fills don't need to be pushed in chronological order,
+    /// but fills sharing a timestamp are assumed to be pushed in execution order.
+    pub fn push(&mut self, fill: &TimedFill) {
+        if fill.fill.base_qty == 0 {
+            return;
+        }
+        let price = fill.price();
+        let start = fill.timestamp - fill.timestamp.rem_euclid(self.interval);
+        let candle = self
+            .candles
+            .entry(start)
+            .or_insert_with(|| Candle::new(start, price));
+        candle.high = candle.high.max(price);
+        candle.low = candle.low.min(price);
+        candle.close = price;
+        candle.base_volume += fill.fill.base_qty;
+        candle.quote_volume += fill.fill.quote_qty;
+    }
+
+    /// Returns the candles which start in the `[from, to)` range, in chronological order
+    pub fn candles(&self, from: i64, to: i64) -> impl Iterator<Item = &Candle> {
+        self.candles.range(from..to.max(from)).map(|(_, c)| c)
+    }
+
+    /// Drops the candles which start before the given timestamp
+    pub fn prune(&mut self, before: i64) {
+        self.candles = self.candles.split_off(&before);
+    }
+}
+
+/// Extracts the fills of a market from the log messages of a transaction
+pub fn parse_fill_logs(market: &Pubkey, logs: &[String]) -> Vec<FillLog> {
+    logs.iter()
+        .filter_map(|l| l.strip_prefix(PROGRAM_DATA_PREFIX))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter_map(|bytes| FillLog::try_from_slice(&bytes).ok())
+        .filter(|f| &f.market == market)
+        .collect()
+}
+
+/// Serializes a list of candles into a JSON array
+pub fn candles_to_json<'a>(candles: impl Iterator<Item = &'a Candle>) -> String {
+    let candles = candles.map(|c| c.to_json()).collect::<Vec<_>>();
+    format!("[{}]", candles.join(","))
+}
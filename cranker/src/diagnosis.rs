@@ -0,0 +1,95 @@
+use std::fmt;
+
+use dex_v4::error::{DexError, ACCOUNT_CHECK_FAILED_LOG};
+use solana_client::{client_error::ClientError, rpc_client::RpcClient};
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+const PROGRAM_LOG_PREFIX: &str = "Program log: ";
+
+/// A human-readable description of why a transaction fails
+#[derive(Clone, Debug)]
+pub struct FailureDiagnosis {
+    /// The error returned by the simulation
+    pub error: TransactionError,
+    /// The index of the failed instruction, if the failure is tied to an instruction
+    pub instruction_index: Option<usize>,
+    /// The DEX error matching the custom error code of the failed instruction
+    pub dex_error: Option<DexError>,
+    /// The account which failed a check, as its index in the failed instruction's accounts and its key
+    pub failed_account: Option<(usize, Pubkey)>,
+    /// The program logs of the simulation
+    pub logs: Vec<String>,
+}
+
+impl fmt::Display for FailureDiagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.instruction_index {
+            Some(i) => write!(f, "Instruction {} failed", i)?,
+            None => write!(f, "The transaction failed")?,
+        }
+        match &self.dex_error {
+            Some(e) => write!(f, ": {}", e)?,
+            None => write!(f, ": {}", self.error)?,
+        }
+        if let Some((index, key)) = &self.failed_account {
+            write!(f, " (account #{} {} failed a check)", index, key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulates a transaction and explains why it fails.
+///
+/// Custom error codes are interpreted as DEX errors. Returns `None` when the simulation succeeds.
+pub fn explain_failure(
+    rpc: &RpcClient,
+    tx: &Transaction,
+) -> Result<Option<FailureDiagnosis>, ClientError> {
+    let result = rpc.simulate_transaction(tx)?.value;
+    let error = match result.err {
+        Some(e) => e,
+        None => return Ok(None),
+    };
+    let logs = result.logs.unwrap_or_default();
+
+    let (instruction_index, dex_error) = match &error {
+        TransactionError::InstructionError(i, e) => {
+            let dex_error = match e {
+                InstructionError::Custom(code) => DexError::from_code(*code),
+                _ => None,
+            };
+            (Some(*i as usize), dex_error)
+        }
+        _ => (None, None),
+    };
+
+    let failed_account = instruction_index.and_then(|i| {
+        let key = failed_account_key(&logs)?;
+        let instruction = tx.message.instructions.get(i)?;
+        instruction
+            .accounts
+            .iter()
+            .position(|&a| tx.message.account_keys.get(a as usize) == Some(&key))
+            .map(|index| (index, key))
+    });
+
+    Ok(Some(FailureDiagnosis {
+        error,
+        instruction_index,
+        dex_error,
+        failed_account,
+        logs,
+    }))
+}
+
+/// Returns the key of the last account reported as failing a check in the program logs
+fn failed_account_key(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().rev().find_map(|log| {
+        log.strip_prefix(PROGRAM_LOG_PREFIX)?
+            .strip_prefix(ACCOUNT_CHECK_FAILED_LOG)?
+            .trim()
+            .parse()
+            .ok()
+    })
+}
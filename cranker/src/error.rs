@@ -1,3 +1,4 @@
+use solana_sdk::signature::Signature;
 use thiserror::Error;
 #[derive(Clone, Debug, Eq, Error, PartialEq)]
 pub enum CrankError {
@@ -5,4 +6,63 @@ pub enum CrankError {
     ConnectionError,
     #[error("The parsed market state is invalid")]
     InvalidMarketState,
+    #[error("The transaction exceeds the packet size limit ({size} bytes) at instruction {instruction_index}")]
+    TransactionTooLarge {
+        instruction_index: usize,
+        size: usize,
+    },
+    #[error(
+        "The transaction locks too many accounts ({accounts}) at instruction {instruction_index}"
+    )]
+    TooManyAccountLocks {
+        instruction_index: usize,
+        accounts: usize,
+    },
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum TemplateError {
+    #[error("The market template could not be parsed: {0}")]
+    Parse(String),
+    #[error("The {0} should be nonzero")]
+    Zero(&'static str),
+    #[error("The minimum base order size ({min_base_order_size}) is not a multiple of the base lot size ({base_lot_size})")]
+    MinOrderSizeNotLotMultiple {
+        min_base_order_size: u64,
+        base_lot_size: u64,
+    },
+    #[error("The tick size {0} cannot be accurately represented as a FP32 amount of quote lots per base lot")]
+    TickSizeTooSmall(f64),
+    #[error("A tick on a minimum size order is worth less than one quote lot")]
+    TickBelowQuoteLot,
+    #[error("The minimum order tick distance ({0}) should be at most {}", u16::MAX)]
+    TickDistanceTooLarge(u64),
+    #[error("The crank fee share ({0} bps) should be at most 10000 bps")]
+    CrankFeeShareTooLarge(u64),
+    #[error("The volatility threshold should be nonzero and the maximum multiplier should be between 1 and {max}")]
+    InvalidVolatilityFee { max: u64 },
+    #[error("The {account} account would take {size} bytes, above the {max} bytes limit")]
+    AccountTooLarge {
+        account: &'static str,
+        size: usize,
+        max: usize,
+    },
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ReceiptError {
+    #[error("The transaction {0} could not be fetched")]
+    TransactionNotFound(Signature),
+    #[error("The transaction {0} failed")]
+    TransactionFailed(Signature),
+    #[error("The block time of slot {0} is not available")]
+    MissingBlockTime(u64),
+    #[error("The transaction did not log a fill of the market at index {0}")]
+    FillNotFound(u32),
+    #[error("The {0} of the receipt does not match the transaction")]
+    Mismatch(&'static str),
+    #[error("The receipt is not signed by its issuer")]
+    InvalidIssuerSignature,
+    #[error("The receipt could not be decoded")]
+    InvalidEncoding,
 }
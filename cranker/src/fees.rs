@@ -0,0 +1,93 @@
+//! Fee and referral accruals built from the fee records logged by the dex program
+use std::collections::BTreeMap;
+
+use borsh::BorshDeserialize;
+use dex_v4::state::FeeLog;
+use solana_program::pubkey::Pubkey;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+pub const CSV_HEADER: &str = "market,referrer,fills,taker_fees,royalties_fees,referral_fees";
+
+/// The fees accrued by a market, or by one of the referrers of a market
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeeAccrual {
+    /// The number of taker fills
+    pub fills: u64,
+    /// The raw quote amount of taker fees, referral fees included
+    pub taker_fees: u64,
+    /// The raw quote amount of royalties fees
+    pub royalties_fees: u64,
+    /// The raw quote amount paid out to referrers
+    pub referral_fees: u64,
+}
+
+impl FeeAccrual {
+    fn push(&mut self, fee: &FeeLog) {
+        self.fills += 1;
+        self.taker_fees += fee.taker_fee;
+        self.royalties_fees += fee.royalties_fee;
+        self.referral_fees += fee.referral_fee;
+    }
+
+    fn to_csv_row(&self, market: &Pubkey, referrer: Option<&Pubkey>) -> String {
+        format!(
+            "{},{},{},{},{},{}",
+            market,
+            referrer.map(|r| r.to_string()).unwrap_or_default(),
+            self.fills,
+            self.taker_fees,
+            self.royalties_fees,
+            self.referral_fees
+        )
+    }
+}
+
+/// Aggregates fee records per market and per referrer
+#[derive(Default)]
+pub struct FeeReport {
+    markets: BTreeMap<Pubkey, FeeAccrual>,
+    referrers: BTreeMap<(Pubkey, Pubkey), FeeAccrual>,
+}
+
+impl FeeReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, fee: &FeeLog) {
+        self.markets.entry(fee.market).or_default().push(fee);
+        if let Some(referrer) = fee.referrer {
+            self.referrers
+                .entry((fee.market, referrer))
+                .or_default()
+                .push(fee);
+        }
+    }
+
+    /// Serializes the report as CSV. Each market's total has an empty referrer column and is followed by the
+    /// accruals of the market's referrers.
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![CSV_HEADER.to_owned()];
+        for (market, accrual) in self.markets.iter() {
+            rows.push(accrual.to_csv_row(market, None));
+            rows.extend(
+                self.referrers
+                    .range((*market, Pubkey::default())..)
+                    .take_while(|((m, _), _)| m == market)
+                    .map(|((m, referrer), a)| a.to_csv_row(m, Some(referrer))),
+            );
+        }
+        rows.join("\n") + "\n"
+    }
+}
+
+/// Extracts the fee records from the log messages of a transaction, optionally restricted to a set of markets
+pub fn parse_fee_logs(markets: &[Pubkey], logs: &[String]) -> Vec<FeeLog> {
+    logs.iter()
+        .filter_map(|l| l.strip_prefix(PROGRAM_DATA_PREFIX))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter_map(|bytes| FeeLog::try_from_slice(&bytes).ok())
+        .filter(|f| markets.is_empty() || markets.contains(&f.market))
+        .collect()
+}
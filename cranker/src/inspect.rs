@@ -0,0 +1,322 @@
+//! Human-readable views of the dex state accounts, with amounts and prices converted to UI units
+use std::fmt;
+
+use agnostic_orderbook::state::get_side_from_order_id;
+use dex_v4::state::{
+    DexState, MarketStatus, Order, UserAccountHeader, HALT_ASKS_MASK, HALT_BIDS_MASK,
+    ORDER_FLOW_WINDOW_SLOTS,
+};
+use num_traits::FromPrimitive;
+use solana_program::pubkey::Pubkey;
+
+/// The decimals of the base and quote mints of a market
+#[derive(Debug, Clone, Copy)]
+pub struct MarketDecimals {
+    pub base: u8,
+    pub quote: u8,
+}
+
+impl MarketDecimals {
+    /// Converts a raw base amount to UI units
+    pub fn ui_base(&self, raw: u64) -> f64 {
+        raw as f64 / 10f64.powi(self.base as i32)
+    }
+
+    /// Converts a raw quote amount to UI units
+    pub fn ui_quote(&self, raw: u64) -> f64 {
+        raw as f64 / 10f64.powi(self.quote as i32)
+    }
+
+    /// Converts a FP32 price of a market to UI units
+    pub fn ui_price(&self, state: &DexState, price_fp32: u64) -> f64 {
+        price_fp32 as f64 / (1u64 << 32) as f64 * state.quote_currency_multiplier as f64
+            / state.base_currency_multiplier as f64
+            * 10f64.powi(self.base as i32 - self.quote as i32)
+    }
+}
+
+fn decode_fixed_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn optional_key(key: &Pubkey) -> String {
+    if key == &Pubkey::default() {
+        "none".to_owned()
+    } else {
+        key.to_string()
+    }
+}
+
+/// A labeled view of a market state
+pub struct MarketView<'a> {
+    pub address: Pubkey,
+    pub state: &'a DexState,
+    pub decimals: MarketDecimals,
+}
+
+impl fmt::Display for MarketView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.state;
+        let d = &self.decimals;
+        let base_symbol = decode_fixed_string(&s.base_symbol);
+        let quote_symbol = decode_fixed_string(&s.quote_symbol);
+        let status = MarketStatus::from_u8(s.status)
+            .map(|s| format!("{:?}", s))
+            .unwrap_or_else(|| format!("Unknown ({})", s.status));
+
+        writeln!(f, "Market {}", self.address)?;
+        writeln!(
+            f,
+            "  Name:                    {}",
+            decode_fixed_string(&s.market_name)
+        )?;
+        writeln!(f, "  Status:                  {}", status)?;
+        if s.halt_flags != 0 {
+            writeln!(
+                f,
+                "  Halted sides:            bids {}, asks {}",
+                s.halt_flags & HALT_BIDS_MASK != 0,
+                s.halt_flags & HALT_ASKS_MASK != 0
+            )?;
+        }
+        writeln!(
+            f,
+            "  Base mint:               {} ({}, {} decimals)",
+            s.base_mint, base_symbol, d.base
+        )?;
+        writeln!(
+            f,
+            "  Quote mint:              {} ({}, {} decimals)",
+            s.quote_mint, quote_symbol, d.quote
+        )?;
+        writeln!(f, "  Base vault:              {}", s.base_vault)?;
+        writeln!(f, "  Quote vault:             {}", s.quote_vault)?;
+        writeln!(f, "  Orderbook:               {}", s.orderbook)?;
+        writeln!(f, "  Admin:                   {}", s.admin)?;
+        if s.pending_admin != Pubkey::default() {
+            writeln!(f, "  Nominated admin:         {}", s.pending_admin)?;
+        }
+        writeln!(f, "  Oracle:                  {}", optional_key(&s.oracle))?;
+        writeln!(f, "  Created at:              {}", s.creation_timestamp)?;
+        writeln!(
+            f,
+            "  Base volume:             {} {}",
+            d.ui_base(s.base_volume),
+            base_symbol
+        )?;
+        writeln!(
+            f,
+            "  Quote volume:            {} {}",
+            d.ui_quote(s.quote_volume),
+            quote_symbol
+        )?;
+        writeln!(
+            f,
+            "  Accumulated fees:        {} {}",
+            d.ui_quote(s.accumulated_fees),
+            quote_symbol
+        )?;
+        writeln!(
+            f,
+            "  Accumulated royalties:   {} {}",
+            d.ui_quote(s.accumulated_royalties),
+            quote_symbol
+        )?;
+        writeln!(f, "  Royalties:               {} bps", s.royalties_bps)?;
+        writeln!(
+            f,
+            "  Min base order size:     {} {}",
+            d.ui_base(s.min_base_order_size),
+            base_symbol
+        )?;
+        writeln!(
+            f,
+            "  Currency multipliers:    base {}, quote {}",
+            s.base_currency_multiplier, s.quote_currency_multiplier
+        )?;
+        writeln!(
+            f,
+            "  Fee type:                {}",
+            if s.fee_type == 0 { "Default" } else { "Stable" }
+        )?;
+        writeln!(
+            f,
+            "  Min order tick distance: {}",
+            s.min_order_tick_distance
+        )?;
+        writeln!(f, "  Min resting slots:       {}", s.min_resting_slots)?;
+        writeln!(
+            f,
+            "  Hide fill accounts:      {}",
+            s.hide_fill_accounts != 0
+        )?;
+        writeln!(f, "  Batch slots:             {}", s.batch_slots)?;
+        writeln!(
+            f,
+            "  Crank fee share:         {} bps",
+            s.crank_fee_share_bps
+        )?;
+        writeln!(
+            f,
+            "  Crank reward scaling:    every {} slots, max multiplier {}",
+            s.crank_reward_age_slots, s.max_crank_reward_multiplier
+        )?;
+        writeln!(f, "  Referral share:          {} bps", s.referral_share_bps)?;
+        writeln!(f, "  Maker rebate:            {} bps", s.maker_rebate_bps)?;
+        writeln!(
+            f,
+            "  Volatility fee:          window {} slots, threshold {} bps, max multiplier {}",
+            s.volatility_window_slots, s.volatility_threshold_bps, s.max_volatility_multiplier
+        )?;
+        writeln!(
+            f,
+            "  Reference price:         {} at slot {}",
+            d.ui_price(s, s.reference_price),
+            s.reference_slot
+        )?;
+        writeln!(
+            f,
+            "  Last price:              {}",
+            d.ui_price(s, s.last_price)
+        )?;
+        let top_of_book_price = |price: u64| {
+            if price == 0 {
+                "none".to_owned()
+            } else {
+                d.ui_price(s, price).to_string()
+            }
+        };
+        writeln!(
+            f,
+            "  Top of book:             bid {}, ask {} at slot {}",
+            top_of_book_price(s.best_bid),
+            top_of_book_price(s.best_ask),
+            s.top_of_book_slot
+        )?;
+        if s.emergency_authority != Pubkey::default() {
+            writeln!(
+                f,
+                "  Emergency authority:     {}, last halt at {}",
+                s.emergency_authority, s.emergency_halt_timestamp
+            )?;
+        }
+        if s.cancel_fee != 0 {
+            writeln!(
+                f,
+                "  Cancel fee:              {} within {} slots of placement",
+                d.ui_quote(s.cancel_fee),
+                s.cancel_fee_slots
+            )?;
+        }
+        if s.status == MarketStatus::Migrating as u8 {
+            writeln!(
+                f,
+                "  Migration:               market {} of program {}, unlocked at {}",
+                s.migration_market, s.migration_program, s.migration_unlock_timestamp
+            )?;
+        }
+        let flow = &s.order_flow;
+        write!(
+            f,
+            "  Order flow:              window {} (from slot {}): {} orders, {} cancels, {} fills; previous window: {} orders, {} cancels, {} fills",
+            flow.window,
+            flow.window * ORDER_FLOW_WINDOW_SLOTS,
+            flow.new_orders,
+            flow.cancels,
+            flow.fills,
+            flow.previous_new_orders,
+            flow.previous_cancels,
+            flow.previous_fills
+        )
+    }
+}
+
+/// A labeled view of a user account and its open orders
+pub struct UserAccountView<'a> {
+    pub address: Pubkey,
+    pub header: &'a UserAccountHeader,
+    pub orders: &'a [Order],
+    pub market_state: &'a DexState,
+    pub decimals: MarketDecimals,
+}
+
+impl fmt::Display for UserAccountView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let h = self.header;
+        let d = &self.decimals;
+        let base_symbol = decode_fixed_string(&self.market_state.base_symbol);
+        let quote_symbol = decode_fixed_string(&self.market_state.quote_symbol);
+
+        writeln!(f, "User account {}", self.address)?;
+        writeln!(f, "  Market:                {}", h.market)?;
+        writeln!(f, "  Owner:                 {}", h.owner)?;
+        writeln!(
+            f,
+            "  Base free:             {} {}",
+            d.ui_base(h.base_token_free),
+            base_symbol
+        )?;
+        writeln!(
+            f,
+            "  Base locked:           {} {}",
+            d.ui_base(h.base_token_locked),
+            base_symbol
+        )?;
+        writeln!(
+            f,
+            "  Quote free:            {} {}",
+            d.ui_quote(h.quote_token_free),
+            quote_symbol
+        )?;
+        writeln!(
+            f,
+            "  Quote locked:          {} {}",
+            d.ui_quote(h.quote_token_locked),
+            quote_symbol
+        )?;
+        writeln!(
+            f,
+            "  Accumulated rebates:   {} {}",
+            d.ui_quote(h.accumulated_rebates),
+            quote_symbol
+        )?;
+        writeln!(
+            f,
+            "  Maker volume:          {} {} / {} {}",
+            d.ui_base(h.accumulated_maker_base_volume),
+            base_symbol,
+            d.ui_quote(h.accumulated_maker_quote_volume),
+            quote_symbol
+        )?;
+        writeln!(
+            f,
+            "  Taker volume:          {} {} / {} {}",
+            d.ui_base(h.accumulated_taker_base_volume),
+            base_symbol,
+            d.ui_quote(h.accumulated_taker_quote_volume),
+            quote_symbol
+        )?;
+        writeln!(f, "  Last active slot:      {}", h.last_active_slot)?;
+        write!(f, "  Open orders:           {}", self.orders.len())?;
+        for o in self.orders {
+            write!(
+                f,
+                "\n    {:?} at {} (order id {}, client id {}, placed at slot {}",
+                get_side_from_order_id(o.id),
+                d.ui_price(self.market_state, (o.id >> 64) as u64),
+                o.id,
+                o.client_id,
+                o.placed_slot
+            )?;
+            if o.expiry_timestamp != 0 {
+                write!(f, ", expires at {}", o.expiry_timestamp)?;
+            }
+            if o.hidden_base_qty != 0 {
+                write!(f, ", {} hidden", d.ui_base(o.hidden_base_qty))?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
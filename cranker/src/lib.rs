@@ -1,46 +1,97 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    thread,
+    time::{Duration, Instant},
+};
 
 use agnostic_orderbook::state::{
     Event, EventQueue, EventQueueHeader, MarketState, MARKET_STATE_LEN,
 };
 use borsh::BorshDeserialize;
-use dex_v4::instruction_auto::consume_events;
+use budget::RpcPool;
+use dex_v4::instruction_auto::{consume_events, settle_to_associated_accounts};
 use dex_v4::{
-    instruction_auto::consume_events::Accounts,
-    state::{CallBackInfo, DexState, DEX_STATE_LEN},
+    instruction_auto::{consume_events::Accounts, create_settle_allowlist::SETTLE_ALLOWLIST_SEED},
+    state::{CallBackInfo, DexState, UserAccount, DEX_STATE_LEN},
     CALLBACK_INFO_LEN,
 };
 use error::CrankError;
+use reconciliation::VaultReconciliation;
+use simulation::{SimulationCounters, SimulationOutcome};
 use solana_client::{
-    client_error::ClientError, rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig,
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::RpcSendTransactionConfig,
 };
-use solana_program::pubkey::Pubkey;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_program, sysvar};
 use solana_sdk::{
-    commitment_config::{CommitmentConfig, CommitmentLevel},
+    commitment_config::CommitmentLevel,
     signature::{Keypair, Signature},
     signer::Signer,
     transaction::Transaction,
 };
+use spl_associated_token_account::get_associated_token_address;
 
+pub mod budget;
+pub mod candles;
+pub mod diagnosis;
 pub mod error;
+pub mod fees;
+pub mod inspect;
+pub mod market_template;
+pub mod receipt;
+pub mod reconciliation;
+pub mod simulation;
 pub mod utils;
 
+pub use dex_v4::common::{OrderType, QuantityMode, SelfTradeBehavior, Side};
+
 pub struct Context {
     pub program_id: Pubkey,
     pub market: Pubkey,
     pub reward_target: Pubkey,
     pub fee_payer: Keypair,
     pub endpoint: String,
+    /// The endpoints used when the main endpoint is out of request budget or rate limiting the cranker
+    pub fallback_endpoints: Vec<String>,
+    /// The request budget of each endpoint, unlimited if `None`
+    pub requests_per_second: Option<f64>,
+    /// The owners of the user accounts which are settled as soon as the cranker has consumed their events
+    pub settle_owners: Vec<Keypair>,
+    /// The interval between two reconciliations of the vault balances, disabled if `None`
+    pub reconcile_interval: Option<Duration>,
+    /// The file to which the reconciliation and simulation metrics are written, in the Prometheus text format
+    pub metrics_file: Option<String>,
+    /// Whether the consume_events transactions are simulated first, and dropped when the program rejects them
+    pub simulate_before_send: bool,
+    /// The user accounts cranked in self-crank mode, in which the events of other user accounts are left in the
+    /// queue. All the user accounts are cranked when empty.
+    pub self_crank_accounts: Vec<Pubkey>,
 }
 
 pub const MAX_ITERATIONS: u64 = 10;
 pub const MAX_NUMBER_OF_USER_ACCOUNTS: usize = 20;
 
+/// The RPC requests made to read the event queue
+const EVENT_QUEUE_REQUESTS: u32 = 1;
+/// The RPC requests made to send a consume_events transaction
+const CONSUME_EVENTS_REQUESTS: u32 = 2;
+/// The most RPC requests made to settle a managed user account
+const SETTLE_REQUESTS_PER_ACCOUNT: u32 = 3;
+
+const MIN_IDLE_DELAY: Duration = Duration::from_millis(200);
+const MAX_IDLE_DELAY: Duration = Duration::from_secs(5);
+
 impl Context {
     pub fn crank(self) {
-        let connection =
-            RpcClient::new_with_commitment(self.endpoint.clone(), CommitmentConfig::confirmed());
+        let endpoints = std::iter::once(self.endpoint.clone())
+            .chain(self.fallback_endpoints.iter().cloned())
+            .collect::<Vec<_>>();
+        let mut pool = RpcPool::new(&endpoints, self.requests_per_second);
 
+        let endpoint = pool.reserve(2);
+        let connection = pool.client(endpoint);
         let market_state_data = connection
             .get_account_data(&self.market)
             .map_err(|_| CrankError::ConnectionError)
@@ -53,9 +104,115 @@ impl Context {
             .unwrap();
         let orderbook =
             bytemuck::try_from_bytes::<MarketState>(&orderbook_data[..MARKET_STATE_LEN]).unwrap();
+        let mut idle_delay = MIN_IDLE_DELAY;
+        let mut last_reconciliation: Option<Instant> = None;
+        let mut reconciliation: Option<VaultReconciliation> = None;
+        let mut counters = SimulationCounters::default();
         loop {
-            let res = self.consume_events_iteration(&connection, &orderbook, &market_state);
+            if let Some(interval) = self.reconcile_interval {
+                if last_reconciliation.map_or(true, |t| t.elapsed() >= interval) {
+                    last_reconciliation = Some(Instant::now());
+                    let endpoint = pool.reserve(reconciliation::RECONCILIATION_REQUESTS);
+                    match self.reconciliation_iteration(pool.client(endpoint)) {
+                        Ok(r) => {
+                            reconciliation = Some(r);
+                            self.write_metrics(reconciliation.as_ref(), &counters);
+                        }
+                        Err(e) => {
+                            pool.report_error(endpoint, &e);
+                            println!("Vault reconciliation failed: {:#?}", e);
+                        }
+                    }
+                }
+            }
+            let endpoint = pool.reserve(EVENT_QUEUE_REQUESTS);
+            let user_accounts =
+                match self.fetch_event_user_accounts(pool.client(endpoint), orderbook) {
+                    Ok(user_accounts) => user_accounts,
+                    Err(e) => {
+                        pool.report_error(endpoint, &e);
+                        println!("{:#?}", e);
+                        continue;
+                    }
+                };
+            // An empty queue is polled less and less often, which saves the request budget for draining the queue
+            if user_accounts.is_empty() {
+                thread::sleep(idle_delay);
+                idle_delay = (idle_delay * 2).min(MAX_IDLE_DELAY);
+                continue;
+            }
+            idle_delay = MIN_IDLE_DELAY;
+
+            let requests = if self.simulate_before_send {
+                CONSUME_EVENTS_REQUESTS + simulation::SIMULATION_REQUESTS
+            } else {
+                CONSUME_EVENTS_REQUESTS
+            };
+            let endpoint = pool.reserve(requests);
+            let res = self.send_consume_events(
+                pool.client(endpoint),
+                orderbook,
+                market_state,
+                &user_accounts,
+                &mut counters,
+            );
+            if let Err(e) = &res {
+                pool.report_error(endpoint, e);
+            }
             println!("{:#?}", res);
+            if self.simulate_before_send {
+                self.write_metrics(reconciliation.as_ref(), &counters);
+            }
+            if matches!(res, Ok(Some(_))) && !self.settle_owners.is_empty() {
+                let endpoint =
+                    pool.reserve(SETTLE_REQUESTS_PER_ACCOUNT * self.settle_owners.len() as u32);
+                let res =
+                    self.settle_iteration(pool.client(endpoint), market_state, &user_accounts);
+                if let Err(e) = &res {
+                    pool.report_error(endpoint, e);
+                }
+                println!("{:#?}", res);
+            }
+        }
+    }
+
+    /// Compares the vault balances with the user balances, and prints the drift
+    pub fn reconciliation_iteration(
+        &self,
+        connection: &RpcClient,
+    ) -> Result<VaultReconciliation, ClientError> {
+        let reconciliation =
+            reconciliation::reconcile_vaults(connection, &self.program_id, &self.market)?;
+        println!(
+            "Vault drift over {} user accounts: base {}, quote {}",
+            reconciliation.user_accounts,
+            reconciliation.base_drift(),
+            reconciliation.quote_drift()
+        );
+        Ok(reconciliation)
+    }
+
+    /// Writes the latest reconciliation and the simulation counters to the metrics file, if any
+    pub fn write_metrics(
+        &self,
+        reconciliation: Option<&VaultReconciliation>,
+        counters: &SimulationCounters,
+    ) {
+        let path = match &self.metrics_file {
+            Some(path) => path,
+            None => return,
+        };
+        let mut metrics = String::new();
+        if let Some(reconciliation) = reconciliation {
+            metrics.push_str(&reconciliation.to_prometheus(&self.market));
+        }
+        if self.simulate_before_send {
+            metrics.push_str(&counters.to_prometheus(&self.market));
+        }
+        // The metrics are renamed into place so that a scraper never reads a partial file
+        let tmp = format!("{}.tmp", path);
+        if let Err(e) = std::fs::write(&tmp, metrics).and_then(|_| std::fs::rename(&tmp, path)) {
+            println!("Failed to write the metrics: {:#?}", e);
         }
     }
 
@@ -64,7 +221,29 @@ impl Context {
         connection: &RpcClient,
         orderbook: &MarketState,
         market_state: &DexState,
-    ) -> Result<Signature, ClientError> {
+        counters: &mut SimulationCounters,
+    ) -> Result<(Option<Signature>, Vec<Pubkey>), ClientError> {
+        let user_accounts = self.fetch_event_user_accounts(connection, orderbook)?;
+        let signature = self.send_consume_events(
+            connection,
+            orderbook,
+            market_state,
+            &user_accounts,
+            counters,
+        )?;
+        Ok((signature, user_accounts))
+    }
+
+    /// Returns the user accounts referenced by the first events of the event queue.
+    ///
+    /// In self-crank mode, only the leading events of the self-cranked user accounts are taken into account: the
+    /// program consumes the queue in order and stops at the first event of another user account, which cannot be
+    /// skipped.
+    pub fn fetch_event_user_accounts(
+        &self,
+        connection: &RpcClient,
+        orderbook: &MarketState,
+    ) -> Result<Vec<Pubkey>, ClientError> {
         let mut event_queue_data =
             connection.get_account_data(&Pubkey::new(&orderbook.event_queue))?;
         let event_queue_header =
@@ -104,13 +283,33 @@ impl Context {
             }
         }
 
+        if !self.self_crank_accounts.is_empty() {
+            let own_events = user_accounts
+                .iter()
+                .take_while(|a| self.self_crank_accounts.contains(a))
+                .count();
+            user_accounts.truncate(own_events);
+        }
         user_accounts.truncate(MAX_NUMBER_OF_USER_ACCOUNTS);
 
         // We don't use the default sort since the initial ordering of the pubkeys is completely random
         user_accounts.sort_unstable();
         // Since the array is sorted, this removes all duplicate accounts, which shrinks the array.
         user_accounts.dedup();
+        Ok(user_accounts)
+    }
 
+    /// Sends a consume_events transaction for the given user accounts.
+    ///
+    /// Returns `None` when the transaction was simulated and dropped because the program rejects it.
+    pub fn send_consume_events(
+        &self,
+        connection: &RpcClient,
+        orderbook: &MarketState,
+        market_state: &DexState,
+        user_accounts: &[Pubkey],
+        counters: &mut SimulationCounters,
+    ) -> Result<Option<Signature>, ClientError> {
         let consume_events_instruction = consume_events(
             self.program_id,
             Accounts {
@@ -118,20 +317,167 @@ impl Context {
                 market: &self.market,
                 event_queue: &Pubkey::new(&orderbook.event_queue),
                 reward_target: &self.reward_target,
-                user_accounts: &user_accounts,
-            },
-            consume_events::Params {
-                max_iterations: MAX_ITERATIONS,
-                no_op_err: 1,
+                user_accounts,
+                bids: Some(&Pubkey::new(&orderbook.bids)),
+                asks: Some(&Pubkey::new(&orderbook.asks)),
+                maker_rewards: None,
+                cranker_identity: None,
+                cranker: None,
             },
+            consume_events::Params::new(MAX_ITERATIONS, true, false, false, true)
+                .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?,
         );
 
+        utils::validate_transaction_size(
+            &[consume_events_instruction.clone()],
+            &self.fee_payer.pubkey(),
+            1,
+        )
+        .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?;
+
         let mut transaction = Transaction::new_with_payer(
             &[consume_events_instruction],
             Some(&self.fee_payer.pubkey()),
         );
         let (recent_blockhash, _) = connection.get_recent_blockhash()?;
         transaction.partial_sign(&[&self.fee_payer], recent_blockhash);
+        // A successful simulation stands for the preflight check
+        let mut skip_preflight = false;
+        if self.simulate_before_send {
+            let simulation = connection.simulate_transaction(&transaction)?;
+            let outcome = SimulationOutcome::classify(simulation.value.err.as_ref());
+            counters.record(outcome);
+            if !outcome.should_send() {
+                println!("Dropped a consume_events transaction: {:?}", outcome);
+                return Ok(None);
+            }
+            skip_preflight = outcome == SimulationOutcome::Success;
+        }
+        connection
+            .send_transaction_with_config(
+                &transaction,
+                RpcSendTransactionConfig {
+                    skip_preflight,
+                    preflight_commitment: Some(CommitmentLevel::Processed),
+                    ..RpcSendTransactionConfig::default()
+                },
+            )
+            .map(Some)
+    }
+
+    /// Settles the managed user accounts among the given accounts which hold free tokens, into the associated
+    /// token accounts of their owners. The associated token accounts which have been closed are created again, at
+    /// the expense of the owners.
+    pub fn settle_iteration(
+        &self,
+        connection: &RpcClient,
+        market_state: &DexState,
+        user_accounts: &[Pubkey],
+    ) -> Result<Vec<Signature>, ClientError> {
+        let market_signer = Pubkey::create_program_address(
+            &[&self.market.to_bytes(), &[market_state.signer_nonce]],
+            &self.program_id,
+        )
+        .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?;
+
+        let mut instructions = vec![];
+        let mut signers = vec![&self.fee_payer];
+        for owner in self.settle_owners.iter() {
+            let (user_account, _) = Pubkey::find_program_address(
+                &[&self.market.to_bytes(), &owner.pubkey().to_bytes()],
+                &self.program_id,
+            );
+            if !user_accounts.contains(&user_account) {
+                continue;
+            }
+            let mut user_account_data = connection.get_account_data(&user_account)?;
+            let header = UserAccount::from_buffer(&mut user_account_data)
+                .map_err(|_| ClientError::from(ClientErrorKind::Custom(user_account.to_string())))?
+                .header;
+            if header.base_token_free == 0 && header.quote_token_free == 0 {
+                continue;
+            }
+            let settle_allowlist = (header.has_settle_allowlist != 0).then(|| {
+                Pubkey::find_program_address(
+                    &[&user_account.to_bytes(), SETTLE_ALLOWLIST_SEED],
+                    &self.program_id,
+                )
+                .0
+            });
+            instructions.push(settle_to_associated_accounts(
+                self.program_id,
+                settle_to_associated_accounts::Accounts {
+                    spl_token_program: &spl_token::ID,
+                    system_program: &system_program::ID,
+                    associated_token_program: &spl_associated_token_account::ID,
+                    rent_sysvar: &sysvar::rent::ID,
+                    market: &self.market,
+                    base_mint: &market_state.base_mint,
+                    quote_mint: &market_state.quote_mint,
+                    base_vault: &market_state.base_vault,
+                    quote_vault: &market_state.quote_vault,
+                    market_signer: &market_signer,
+                    user: &user_account,
+                    user_owner: &owner.pubkey(),
+                    destination_base_account: &get_associated_token_address(
+                        &owner.pubkey(),
+                        &market_state.base_mint,
+                    ),
+                    destination_quote_account: &get_associated_token_address(
+                        &owner.pubkey(),
+                        &market_state.quote_mint,
+                    ),
+                    settle_allowlist: settle_allowlist.as_ref(),
+                },
+                settle_to_associated_accounts::Params {
+                    unwrap_sol: 0,
+                    _padding: [0; 7],
+                },
+            ));
+            signers.push(owner);
+        }
+
+        // The settle instructions are batched into as few transactions as the size limits allow
+        let mut signatures = vec![];
+        let mut start = 0;
+        while start < instructions.len() {
+            let end = match utils::validate_transaction_size(
+                &instructions[start..],
+                &self.fee_payer.pubkey(),
+                1,
+            ) {
+                Ok(()) => instructions.len(),
+                Err(CrankError::TransactionTooLarge {
+                    instruction_index, ..
+                })
+                | Err(CrankError::TooManyAccountLocks {
+                    instruction_index, ..
+                }) => start + instruction_index.max(1),
+                Err(e) => return Err(ClientError::from(ClientErrorKind::Custom(e.to_string()))),
+            };
+            signatures.push(self.send_settle_transaction(
+                connection,
+                &instructions[start..end],
+                &signers[..1],
+                &signers[start + 1..end + 1],
+            )?);
+            start = end;
+        }
+        Ok(signatures)
+    }
+
+    fn send_settle_transaction(
+        &self,
+        connection: &RpcClient,
+        instructions: &[Instruction],
+        fee_payer: &[&Keypair],
+        owners: &[&Keypair],
+    ) -> Result<Signature, ClientError> {
+        let mut transaction =
+            Transaction::new_with_payer(instructions, Some(&self.fee_payer.pubkey()));
+        let (recent_blockhash, _) = connection.get_recent_blockhash()?;
+        transaction.partial_sign(fee_payer, recent_blockhash);
+        transaction.partial_sign(owners, recent_blockhash);
         connection.send_transaction_with_config(
             &transaction,
             RpcSendTransactionConfig {
@@ -1,10 +1,13 @@
+use std::time::Duration;
+
 use clap::{App, Arg};
 use dex_cranker::Context;
 use solana_clap_utils::{
     fee_payer::{fee_payer_arg, FEE_PAYER_ARG},
-    input_parsers::{keypair_of, pubkey_of},
-    input_validators::is_pubkey,
+    input_parsers::{keypair_of, pubkey_of, pubkeys_of},
+    input_validators::{is_keypair, is_pubkey},
 };
+use solana_sdk::signature::read_keypair_file;
 
 fn main() {
     let matches = App::new("dex-crank")
@@ -46,6 +49,55 @@ fn main() {
                 .validator(is_pubkey)
                 .required(true),
         )
+        .arg(
+            Arg::with_name("fallback-url")
+                .long("fallback-url")
+                .help("A Solana RPC endpoint url used when the main endpoint is out of budget or rate limiting, can be repeated")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("requests-per-second")
+                .long("requests-per-second")
+                .help("The maximum rate of RPC requests sent to each endpoint, unlimited by default")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("settle-owner")
+                .long("settle-owner")
+                .help("The keypair of the owner of a user account to settle right after its events are consumed, can be repeated")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .validator(is_keypair),
+        )
+        .arg(
+            Arg::with_name("reconcile-interval")
+                .long("reconcile-interval")
+                .help("The interval in seconds between two reconciliations of the vault balances with the user balances")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-file")
+                .long("metrics-file")
+                .help("The file to which the vault drift and simulation metrics are written in the Prometheus text format")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("simulate-before-send")
+                .long("simulate-before-send")
+                .help("Simulate the consume_events transactions and drop the ones which the program rejects, such as the ones racing another cranker to an empty queue"),
+        )
+        .arg(
+            Arg::with_name("self-crank")
+                .long("self-crank")
+                .help("Only crank the events of this user account at the head of the event queue, can be repeated")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .validator(is_pubkey),
+        )
         .get_matches();
     let endpoint = matches
         .value_of("url")
@@ -54,12 +106,42 @@ fn main() {
     let market = pubkey_of(&matches, "market").expect("Invalid market Pubkey");
     let reward_target = pubkey_of(&matches, "reward-target").expect("Invalid reward target pubkey");
     let fee_payer = keypair_of(&matches, FEE_PAYER_ARG.name).unwrap();
+    let fallback_endpoints = matches
+        .values_of("fallback-url")
+        .map(|urls| urls.map(String::from).collect())
+        .unwrap_or_default();
+    let requests_per_second = matches.value_of("requests-per-second").map(|r| {
+        let r = r.parse::<f64>().expect("Invalid request rate");
+        assert!(r > 0.0, "The request rate should be positive");
+        r
+    });
+    let settle_owners = matches
+        .values_of("settle-owner")
+        .map(|paths| {
+            paths
+                .map(|p| read_keypair_file(p).expect("Invalid settle owner keypair"))
+                .collect()
+        })
+        .unwrap_or_default();
+    let reconcile_interval = matches
+        .value_of("reconcile-interval")
+        .map(|s| Duration::from_secs(s.parse::<u64>().expect("Invalid reconciliation interval")));
+    let metrics_file = matches.value_of("metrics-file").map(String::from);
+    let simulate_before_send = matches.is_present("simulate-before-send");
+    let self_crank_accounts = pubkeys_of(&matches, "self-crank").unwrap_or_default();
     let context = Context {
         market,
         fee_payer,
         endpoint: String::from(endpoint),
+        fallback_endpoints,
+        requests_per_second,
         program_id,
         reward_target,
+        settle_owners,
+        reconcile_interval,
+        metrics_file,
+        simulate_before_send,
+        self_crank_accounts,
     };
     context.crank();
 }
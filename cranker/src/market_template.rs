@@ -0,0 +1,192 @@
+//! Named market presets loaded from TOML files, and the market parameters derived from them
+use agnostic_orderbook::{critbit::Slab, state::EventQueue};
+use dex_v4::{instruction_auto::set_volatility_fee::MAX_VOLATILITY_MULTIPLIER, CALLBACK_INFO_LEN};
+use serde::Deserialize;
+use solana_program::system_instruction::MAX_PERMITTED_DATA_LENGTH;
+
+use crate::{error::TemplateError, inspect::MarketDecimals};
+
+/// The largest relative error tolerated when rounding the tick size to a FP32 amount
+const MAX_TICK_ROUNDING_ERROR: f64 = 1e-6;
+
+/// A market preset, such as :
+///
+/// ```toml
+/// name = "bluechip"
+/// tick_size = 0.01
+/// base_lot_size = 1000
+/// quote_lot_size = 1
+/// min_base_order_size = 100000
+///
+/// [fees]
+/// crank_fee_share_bps = 2000
+///
+/// [capacities]
+/// orders = 1000
+/// events = 500
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MarketTemplate {
+    /// The name of the preset
+    pub name: String,
+    /// The tick size in UI quote units per UI base unit
+    pub tick_size: f64,
+    /// The raw base amount of a lot, used as the base currency multiplier
+    pub base_lot_size: u64,
+    /// The raw quote amount of a lot, used as the quote currency multiplier
+    pub quote_lot_size: u64,
+    /// The minimum order size as a raw base amount
+    pub min_base_order_size: u64,
+    /// The minimum distance in ticks between two orders of a same user on the same side
+    #[serde(default)]
+    pub min_order_tick_distance: u64,
+    #[serde(default)]
+    pub fees: FeeTemplate,
+    pub capacities: CapacityTemplate,
+}
+
+/// The fee settings applied by the market admin once the market is created
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FeeTemplate {
+    /// The share of the protocol fees credited to crankers, in basis points
+    #[serde(default)]
+    pub crank_fee_share_bps: u64,
+    /// The volatility window in slots, or zero to disable the volatility surcharge
+    #[serde(default)]
+    pub volatility_window_slots: u64,
+    #[serde(default)]
+    pub volatility_threshold_bps: u64,
+    #[serde(default)]
+    pub max_volatility_multiplier: u64,
+    /// The minimum number of slots an order should rest on the book before it can be cancelled
+    #[serde(default)]
+    pub min_resting_slots: u64,
+}
+
+/// The capacities of the orderbook accounts
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CapacityTemplate {
+    /// The maximum number of orders on each side of the book
+    pub orders: u64,
+    /// The maximum number of events in the event queue
+    pub events: u64,
+}
+
+/// The create_market parameters and account sizes derived from a template and the decimals of a market's mints
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketParameters {
+    /// The tick size as a FP32 amount of quote lots per base lot
+    pub tick_size: u64,
+    pub base_currency_multiplier: u64,
+    pub quote_currency_multiplier: u64,
+    pub min_base_order_size: u64,
+    pub min_order_tick_distance: u64,
+    /// The size in bytes of the event queue account
+    pub event_queue_space: usize,
+    /// The size in bytes of each of the bids and asks accounts
+    pub slab_space: usize,
+}
+
+impl MarketTemplate {
+    pub fn from_toml(s: &str) -> Result<Self, TemplateError> {
+        toml::from_str(s).map_err(|e| TemplateError::Parse(e.to_string()))
+    }
+
+    /// Checks that the template is self-consistent for the given mint decimals and derives the market parameters
+    pub fn validate(&self, decimals: MarketDecimals) -> Result<MarketParameters, TemplateError> {
+        if self.base_lot_size == 0 {
+            return Err(TemplateError::Zero("base lot size"));
+        }
+        if self.quote_lot_size == 0 {
+            return Err(TemplateError::Zero("quote lot size"));
+        }
+        if self.min_base_order_size == 0 {
+            return Err(TemplateError::Zero("minimum base order size"));
+        }
+        if self.capacities.orders == 0 {
+            return Err(TemplateError::Zero("order capacity"));
+        }
+        if self.capacities.events == 0 {
+            return Err(TemplateError::Zero("event capacity"));
+        }
+        if !(self.tick_size.is_finite() && self.tick_size > 0.0) {
+            return Err(TemplateError::Zero("tick size"));
+        }
+        if self.min_base_order_size % self.base_lot_size != 0 {
+            return Err(TemplateError::MinOrderSizeNotLotMultiple {
+                min_base_order_size: self.min_base_order_size,
+                base_lot_size: self.base_lot_size,
+            });
+        }
+        if self.min_order_tick_distance > u16::MAX as u64 {
+            return Err(TemplateError::TickDistanceTooLarge(
+                self.min_order_tick_distance,
+            ));
+        }
+
+        let exact_tick_size = self.tick_size
+            * 10f64.powi(decimals.quote as i32 - decimals.base as i32)
+            * self.base_lot_size as f64
+            / self.quote_lot_size as f64
+            * (1u64 << 32) as f64;
+        let tick_size = exact_tick_size.round();
+        if tick_size < 1.0
+            || tick_size >= u64::MAX as f64
+            || (tick_size - exact_tick_size).abs() / exact_tick_size > MAX_TICK_ROUNDING_ERROR
+        {
+            return Err(TemplateError::TickSizeTooSmall(self.tick_size));
+        }
+        let tick_size = tick_size as u64;
+        let min_order_lots = self.min_base_order_size / self.base_lot_size;
+        if ((min_order_lots as u128 * tick_size as u128) >> 32) == 0 {
+            return Err(TemplateError::TickBelowQuoteLot);
+        }
+
+        let fees = &self.fees;
+        if fees.crank_fee_share_bps > 10_000 {
+            return Err(TemplateError::CrankFeeShareTooLarge(
+                fees.crank_fee_share_bps,
+            ));
+        }
+        if fees.volatility_window_slots != 0
+            && (fees.volatility_threshold_bps == 0
+                || fees.max_volatility_multiplier == 0
+                || fees.max_volatility_multiplier > MAX_VOLATILITY_MULTIPLIER)
+        {
+            return Err(TemplateError::InvalidVolatilityFee {
+                max: MAX_VOLATILITY_MULTIPLIER,
+            });
+        }
+
+        let event_queue_space = EventQueue::compute_allocation_size(
+            self.capacities.events as usize,
+            CALLBACK_INFO_LEN as usize,
+        );
+        let slab_space = Slab::compute_allocation_size(
+            self.capacities.orders as usize,
+            CALLBACK_INFO_LEN as usize,
+        );
+        for &(account, size) in &[("event queue", event_queue_space), ("slab", slab_space)] {
+            if size as u64 > MAX_PERMITTED_DATA_LENGTH {
+                return Err(TemplateError::AccountTooLarge {
+                    account,
+                    size,
+                    max: MAX_PERMITTED_DATA_LENGTH as usize,
+                });
+            }
+        }
+
+        Ok(MarketParameters {
+            tick_size,
+            base_currency_multiplier: self.base_lot_size,
+            quote_currency_multiplier: self.quote_lot_size,
+            min_base_order_size: self.min_base_order_size,
+            min_order_tick_distance: self.min_order_tick_distance,
+            event_queue_space,
+            slab_space,
+        })
+    }
+}
@@ -0,0 +1,135 @@
+//! Receipts proving the execution of a fill, for OTC desks reporting execution quality to their clients
+//!
+//! A receipt packages a fill logged by a confirmed transaction with the slot and block time of that transaction, and
+//! is signed by its issuer. Anyone can check it against the chain with [`verify_receipt`], which fetches the
+//! transaction again and compares the logged fill with the receipt.
+use std::convert::TryInto;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use dex_v4::state::FillLog;
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::{candles::parse_fill_logs, error::ReceiptError};
+
+/// A signed record of a fill and of the confirmed transaction which logged it
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct OrderReceipt {
+    /// The signature of the transaction which logged the fill
+    pub transaction_signature: [u8; 64],
+    /// The slot of the transaction
+    pub slot: u64,
+    /// The block time of the transaction, as a unix timestamp
+    pub block_time: i64,
+    /// The index of the fill among the fills of its market logged by the transaction
+    pub fill_index: u32,
+    /// The logged fill
+    pub fill: FillLog,
+    /// The issuer of the receipt
+    pub issuer: Pubkey,
+    /// The signature by the issuer of all the other fields of the receipt
+    pub issuer_signature: [u8; 64],
+}
+
+impl OrderReceipt {
+    /// Fetches a confirmed transaction and packages its fill of the given market into a receipt signed by the issuer
+    pub fn fetch(
+        connection: &RpcClient,
+        transaction_signature: &Signature,
+        market: &Pubkey,
+        fill_index: u32,
+        issuer: &Keypair,
+    ) -> Result<Self, ReceiptError> {
+        let (slot, block_time, fill) =
+            fetch_fill(connection, transaction_signature, market, fill_index)?;
+        let mut receipt = Self {
+            transaction_signature: transaction_signature.as_ref().try_into().unwrap(),
+            slot,
+            block_time,
+            fill_index,
+            fill,
+            issuer: issuer.pubkey(),
+            issuer_signature: [0; 64],
+        };
+        receipt.issuer_signature = issuer
+            .sign_message(&receipt.message())
+            .as_ref()
+            .try_into()
+            .unwrap();
+        Ok(receipt)
+    }
+
+    /// The bytes signed by the issuer
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = self.try_to_vec().unwrap();
+        message.truncate(message.len() - self.issuer_signature.len());
+        message
+    }
+
+    /// Encodes the receipt for transport
+    pub fn to_base64(&self) -> String {
+        base64::encode(self.try_to_vec().unwrap())
+    }
+
+    /// Decodes a receipt encoded with [`OrderReceipt::to_base64`]
+    pub fn from_base64(data: &str) -> Result<Self, ReceiptError> {
+        base64::decode(data)
+            .ok()
+            .and_then(|bytes| Self::try_from_slice(&bytes).ok())
+            .ok_or(ReceiptError::InvalidEncoding)
+    }
+}
+
+/// Checks that a receipt is signed by its issuer and that its fill was logged by a successful transaction at the
+/// recorded slot and block time
+pub fn verify_receipt(connection: &RpcClient, receipt: &OrderReceipt) -> Result<(), ReceiptError> {
+    let issuer_signature = Signature::new(&receipt.issuer_signature);
+    if !issuer_signature.verify(receipt.issuer.as_ref(), &receipt.message()) {
+        return Err(ReceiptError::InvalidIssuerSignature);
+    }
+    let (slot, block_time, fill) = fetch_fill(
+        connection,
+        &Signature::new(&receipt.transaction_signature),
+        &receipt.fill.market,
+        receipt.fill_index,
+    )?;
+    if slot != receipt.slot {
+        return Err(ReceiptError::Mismatch("slot"));
+    }
+    if block_time != receipt.block_time {
+        return Err(ReceiptError::Mismatch("block time"));
+    }
+    if fill != receipt.fill {
+        return Err(ReceiptError::Mismatch("fill"));
+    }
+    Ok(())
+}
+
+/// Returns the slot, the block time and the fill of a market at the given index of a successful transaction
+fn fetch_fill(
+    connection: &RpcClient,
+    signature: &Signature,
+    market: &Pubkey,
+    fill_index: u32,
+) -> Result<(u64, i64, FillLog), ReceiptError> {
+    let transaction = connection
+        .get_transaction(signature, UiTransactionEncoding::Json)
+        .map_err(|_| ReceiptError::TransactionNotFound(*signature))?;
+    let meta = transaction
+        .transaction
+        .meta
+        .ok_or(ReceiptError::TransactionNotFound(*signature))?;
+    if meta.err.is_some() {
+        return Err(ReceiptError::TransactionFailed(*signature));
+    }
+    let block_time = transaction
+        .block_time
+        .ok_or(ReceiptError::MissingBlockTime(transaction.slot))?;
+    let fill = parse_fill_logs(market, &meta.log_messages.unwrap_or_default())
+        .into_iter()
+        .nth(fill_index as usize)
+        .ok_or(ReceiptError::FillNotFound(fill_index))?;
+    Ok((transaction.slot, block_time, fill))
+}
@@ -0,0 +1,154 @@
+//! Reconciliation of a market's vault balances with the balances owed to its users, to detect accounting drift
+use bytemuck::try_from_bytes;
+use dex_v4::state::{
+    AccountTag, DexState, UserAccountHeader, DEX_STATE_LEN, USER_ACCOUNT_HEADER_LEN,
+};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+/// The RPC requests made by a reconciliation
+pub const RECONCILIATION_REQUESTS: u32 = 4;
+
+/// The vault balances of a market and the amounts they should cover
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VaultReconciliation {
+    pub base_vault_balance: u64,
+    pub quote_vault_balance: u64,
+    /// The sum of the free and locked base amounts of the user accounts
+    pub base_owed: u128,
+    /// The sum of the free and locked quote amounts of the user accounts
+    pub quote_owed: u128,
+    /// The fees and royalties held in the quote vault until they are swept
+    pub quote_fees: u128,
+    pub user_accounts: usize,
+}
+
+impl VaultReconciliation {
+    pub fn push(&mut self, header: &UserAccountHeader) {
+        self.base_owed += header.base_token_free as u128 + header.base_token_locked as u128;
+        self.quote_owed += header.quote_token_free as u128 + header.quote_token_locked as u128;
+        self.user_accounts += 1;
+    }
+
+    /// The base vault balance in excess of what the users are owed. A negative drift means that the vault cannot
+    /// cover all of the user balances.
+    pub fn base_drift(&self) -> i128 {
+        self.base_vault_balance as i128 - self.base_owed as i128
+    }
+
+    /// The quote vault balance in excess of what the users are owed and of the fees to sweep
+    pub fn quote_drift(&self) -> i128 {
+        self.quote_vault_balance as i128 - self.quote_owed as i128 - self.quote_fees as i128
+    }
+
+    /// Formats the reconciliation as Prometheus metrics
+    pub fn to_prometheus(&self, market: &Pubkey) -> String {
+        let mut metrics = vec![];
+        let mut gauge = |name: &str, help: &str, value: String| {
+            metrics.push(format!(
+                "# HELP {name} {help}\n# TYPE {name} gauge\n{name}{{market=\"{market}\"}} {value}",
+                name = name,
+                help = help,
+                market = market,
+                value = value
+            ));
+        };
+        gauge(
+            "dex_vault_base_drift",
+            "The base vault balance minus the base amounts owed to users",
+            self.base_drift().to_string(),
+        );
+        gauge(
+            "dex_vault_quote_drift",
+            "The quote vault balance minus the quote amounts owed to users and the unswept fees",
+            self.quote_drift().to_string(),
+        );
+        gauge(
+            "dex_vault_base_balance",
+            "The base vault balance",
+            self.base_vault_balance.to_string(),
+        );
+        gauge(
+            "dex_vault_quote_balance",
+            "The quote vault balance",
+            self.quote_vault_balance.to_string(),
+        );
+        gauge(
+            "dex_user_accounts",
+            "The number of user accounts of the market",
+            self.user_accounts.to_string(),
+        );
+        metrics.join("\n") + "\n"
+    }
+}
+
+/// Fetches the vault balances of a market and the headers of all of its user accounts.
+///
+/// The fill events which are still in the queue cause a transient drift, as the taker side of a fill is settled when
+/// the order is matched while the maker side is only settled when the event is consumed.
+pub fn reconcile_vaults(
+    connection: &RpcClient,
+    program_id: &Pubkey,
+    market: &Pubkey,
+) -> Result<VaultReconciliation, ClientError> {
+    let market_data = connection.get_account_data(market)?;
+    let market_state = try_from_bytes::<DexState>(&market_data[..DEX_STATE_LEN])
+        .map_err(|_| ClientErrorKind::Custom("Invalid market account".to_owned()))?;
+    let token_balance = |vault: &Pubkey| -> Result<u64, ClientError> {
+        let data = connection.get_account_data(vault)?;
+        let account = spl_token::state::Account::unpack(&data)
+            .map_err(|_| ClientErrorKind::Custom("Invalid vault account".to_owned()))?;
+        Ok(account.amount)
+    };
+    let mut reconciliation = VaultReconciliation {
+        base_vault_balance: token_balance(&market_state.base_vault)?,
+        quote_vault_balance: token_balance(&market_state.quote_vault)?,
+        quote_fees: market_state.accumulated_fees as u128
+            + market_state.accumulated_royalties as u128,
+        ..VaultReconciliation::default()
+    };
+
+    // Only the user account headers are fetched
+    let user_accounts = connection.get_program_accounts_with_config(
+        program_id,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp {
+                    offset: 0,
+                    bytes: MemcmpEncodedBytes::Base64(base64::encode(
+                        (AccountTag::UserAccount as u64).to_le_bytes(),
+                    )),
+                    encoding: None,
+                }),
+                RpcFilterType::Memcmp(Memcmp {
+                    offset: 8,
+                    bytes: MemcmpEncodedBytes::Base64(base64::encode(market.to_bytes())),
+                    encoding: None,
+                }),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: USER_ACCOUNT_HEADER_LEN,
+                }),
+                commitment: None,
+            },
+            with_context: None,
+        },
+    )?;
+    for (_, account) in user_accounts {
+        if let Ok(header) = try_from_bytes::<UserAccountHeader>(
+            &account.data[..USER_ACCOUNT_HEADER_LEN.min(account.data.len())],
+        ) {
+            reconciliation.push(header);
+        }
+    }
+    Ok(reconciliation)
+}
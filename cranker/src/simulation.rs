@@ -0,0 +1,85 @@
+//! Simulation of the consume_events transactions before they are sent, to drop the ones which are bound to fail
+use dex_v4::error::DexError;
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_sdk::transaction::TransactionError;
+
+/// The RPC requests made to simulate a transaction
+pub const SIMULATION_REQUESTS: u32 = 1;
+
+/// The outcome of the simulation of a consume_events transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationOutcome {
+    Success,
+    /// The event queue was drained by another cranker since it was read
+    NoOp,
+    /// The program rejected the transaction, which fails the same way when it lands
+    InstructionFailure,
+    /// The transaction failed before reaching the program, for instance on an expired blockhash
+    TransactionFailure,
+}
+
+impl SimulationOutcome {
+    pub fn classify(err: Option<&TransactionError>) -> Self {
+        match err {
+            None => Self::Success,
+            Some(TransactionError::InstructionError(_, InstructionError::Custom(code)))
+                if *code == DexError::NoOp as u32 =>
+            {
+                Self::NoOp
+            }
+            Some(TransactionError::InstructionError(_, _)) => Self::InstructionFailure,
+            Some(_) => Self::TransactionFailure,
+        }
+    }
+
+    /// The transaction is dropped when the program rejects it. Transaction level failures depend on the RPC node
+    /// rather than on the market, so these transactions are still sent with a preflight check.
+    pub fn should_send(self) -> bool {
+        matches!(self, Self::Success | Self::TransactionFailure)
+    }
+}
+
+/// The number of simulated consume_events transactions, by outcome
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimulationCounters {
+    pub success: u64,
+    pub no_op: u64,
+    pub instruction_failure: u64,
+    pub transaction_failure: u64,
+}
+
+impl SimulationCounters {
+    pub fn record(&mut self, outcome: SimulationOutcome) {
+        let counter = match outcome {
+            SimulationOutcome::Success => &mut self.success,
+            SimulationOutcome::NoOp => &mut self.no_op,
+            SimulationOutcome::InstructionFailure => &mut self.instruction_failure,
+            SimulationOutcome::TransactionFailure => &mut self.transaction_failure,
+        };
+        *counter += 1;
+    }
+
+    /// Formats the counters as Prometheus metrics
+    pub fn to_prometheus(&self, market: &Pubkey) -> String {
+        let name = "dex_crank_simulations_total";
+        let mut metrics = vec![format!(
+            "# HELP {name} The simulated consume_events transactions, by outcome\n# TYPE {name} counter",
+            name = name
+        )];
+        for (outcome, value) in [
+            ("success", self.success),
+            ("no_op", self.no_op),
+            ("instruction_failure", self.instruction_failure),
+            ("transaction_failure", self.transaction_failure),
+        ] {
+            metrics.push(format!(
+                "{name}{{market=\"{market}\",outcome=\"{outcome}\"}} {value}",
+                name = name,
+                market = market,
+                outcome = outcome,
+                value = value
+            ));
+        }
+        metrics.join("\n") + "\n"
+    }
+}
@@ -1,9 +1,20 @@
 use solana_client::client_error::ClientError;
-use solana_program::instruction::InstructionError;
-use solana_sdk::signature::Signature;
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    message::Message,
+    pubkey::Pubkey,
+};
+use solana_sdk::{packet::PACKET_DATA_SIZE, signature::Signature};
 use std::fmt::Debug;
 use tokio::task;
 
+use crate::error::CrankError;
+
+/// The maximum number of accounts which can be locked by a single transaction
+pub const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+const SIGNATURE_LEN: usize = 64;
+
 pub async fn retry<F, T, K, E, R>(arg: T, f: F, e: R) -> K
 where
     F: Fn(&T) -> Result<K, E>,
@@ -22,6 +33,53 @@ where
     }
 }
 
+fn short_vec_len(n: usize) -> usize {
+    match n {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Returns the serialized size in bytes of a transaction containing the given instructions
+pub fn transaction_size(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signer_count: usize,
+) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    let signer_count = signer_count.max(message.header.num_required_signatures as usize);
+    short_vec_len(signer_count) + signer_count * SIGNATURE_LEN + message.serialize().len()
+}
+
+/// Checks that a transaction containing the given instructions fits within the packet size and account lock limits.
+///
+/// On failure, the returned error holds the index of the first instruction which should be moved to another transaction.
+pub fn validate_transaction_size(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signer_count: usize,
+) -> Result<(), CrankError> {
+    for instruction_index in 0..instructions.len() {
+        let ixs = &instructions[..=instruction_index];
+        let size = transaction_size(ixs, payer, signer_count);
+        if size > PACKET_DATA_SIZE {
+            return Err(CrankError::TransactionTooLarge {
+                instruction_index,
+                size,
+            });
+        }
+        let accounts = Message::new(ixs, Some(payer)).account_keys.len();
+        if accounts > MAX_TX_ACCOUNT_LOCKS {
+            return Err(CrankError::TooManyAccountLocks {
+                instruction_index,
+                accounts,
+            });
+        }
+    }
+    Ok(())
+}
+
 pub fn no_op_filter(r: Result<Signature, ClientError>) -> Result<Signature, ClientError> {
     if let Err(e) = &r {
         match &e.kind {
@@ -50,3 +108,73 @@ pub fn no_op_filter(r: Result<Signature, ClientError>) -> Result<Signature, Clie
         r
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction_with_data_len(program_id: &Pubkey, len: usize) -> Instruction {
+        Instruction::new_with_bytes(*program_id, &vec![0; len], vec![])
+    }
+
+    #[test]
+    fn test_transaction_size() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        // One signature, then a header, two keys, a blockhash and an instruction without accounts nor data
+        let ix = instruction_with_data_len(&program_id, 0);
+        assert_eq!(
+            transaction_size(&[ix], &payer, 1),
+            1 + 64 + 3 + 1 + 2 * 32 + 32 + 1 + 3
+        );
+        // The data length prefix takes two bytes from 128 bytes on
+        let ix = instruction_with_data_len(&program_id, 128);
+        assert_eq!(transaction_size(&[ix], &payer, 1), 169 + 1 + 128);
+        // Additional signers are accounted for even when the instructions don't require them
+        let ix = instruction_with_data_len(&program_id, 0);
+        assert_eq!(transaction_size(&[ix], &payer, 2), 169 + 64);
+    }
+
+    #[test]
+    fn test_validate_transaction_size_boundary() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let overhead =
+            transaction_size(&[instruction_with_data_len(&program_id, 128)], &payer, 1) - 128;
+        let max_data_len = PACKET_DATA_SIZE - overhead;
+
+        let ix = instruction_with_data_len(&program_id, max_data_len);
+        assert_eq!(transaction_size(&[ix.clone()], &payer, 1), PACKET_DATA_SIZE);
+        assert!(validate_transaction_size(&[ix], &payer, 1).is_ok());
+
+        let ix = instruction_with_data_len(&program_id, max_data_len + 1);
+        match validate_transaction_size(&[ix], &payer, 1) {
+            Err(CrankError::TransactionTooLarge {
+                instruction_index,
+                size,
+            }) => {
+                assert_eq!(instruction_index, 0);
+                assert_eq!(size, PACKET_DATA_SIZE + 1);
+            }
+            r => panic!("Unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_validate_transaction_size_first_oversized_instruction() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let ixs = [
+            instruction_with_data_len(&program_id, 600),
+            instruction_with_data_len(&program_id, 400),
+            instruction_with_data_len(&program_id, 400),
+        ];
+        assert!(validate_transaction_size(&ixs[..2], &payer, 1).is_ok());
+        match validate_transaction_size(&ixs, &payer, 1) {
+            Err(CrankError::TransactionTooLarge {
+                instruction_index, ..
+            }) => assert_eq!(instruction_index, 2),
+            r => panic!("Unexpected result {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use solana_client::client_error::ClientError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("The RPC request failed: {0}")]
+    Rpc(#[from] ClientError),
+    #[error("The log subscription failed: {0}")]
+    Subscription(String),
+    #[error("Received an invalid transaction signature {0}")]
+    InvalidSignature(String),
+    #[error("The store failed: {0}")]
+    Store(String),
+}
@@ -0,0 +1,216 @@
+//! The normalized records decoded from the logs of the dex program
+use asset_agnostic_orderbook::state::{get_side_from_order_id, Side};
+use borsh::BorshDeserialize;
+use dex_v4::state::{FillLog, MakerFillLog, OutLog};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+const INSTRUCTION_PREFIX: &str = "Program log: Instruction: ";
+const CONSUME_EVENTS_INSTRUCTIONS: [&str; 2] = ["Consume Events", "Force consume events"];
+
+/// A taker fill, logged by the new_order and swap instructions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub signature: Signature,
+    /// The index of the log among the program data logs of the transaction
+    pub log_index: u32,
+    pub slot: u64,
+    /// The block time, if known when the transaction was indexed
+    pub block_time: Option<i64>,
+    pub market: Pubkey,
+    /// The taker's side
+    pub side: Side,
+    /// The matched base quantity
+    pub base_qty: u64,
+    /// The matched quote quantity, fees excluded
+    pub quote_qty: u64,
+    /// The taker's user account, or wallet for swaps. Omitted if the market hides fill accounts.
+    pub taker: Option<Pubkey>,
+    pub client_order_id: Option<u128>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderEventKind {
+    /// The resting order was partially or fully filled
+    Fill,
+    /// The order left the book, either cancelled or once fully filled
+    Out,
+}
+
+impl OrderEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderEventKind::Fill => "fill",
+            OrderEventKind::Out => "out",
+        }
+    }
+}
+
+/// An event in the lifecycle of a resting order, logged by the consume_events instruction
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderEvent {
+    pub signature: Signature,
+    /// The index of the log among the program data logs of the transaction
+    pub log_index: u32,
+    pub slot: u64,
+    /// The block time, if known when the transaction was indexed
+    pub block_time: Option<i64>,
+    pub market: Pubkey,
+    pub kind: OrderEventKind,
+    pub order_id: u128,
+    pub side: Side,
+    /// The limit price of the order, as a FP32
+    pub price: u64,
+    /// The user account of the order. Omitted for the fills of markets which hide fill accounts.
+    pub user_account: Option<Pubkey>,
+    pub client_order_id: Option<u128>,
+    /// The matched base quantity of a fill, or the unfilled base quantity of an out
+    pub base_qty: u64,
+    /// The matched quote quantity of a fill, fees excluded
+    pub quote_qty: u64,
+    /// The rebate paid to the maker of a fill
+    pub maker_rebate: u64,
+}
+
+/// The balances of a user account at a slot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Balance {
+    pub user_account: Pubkey,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub base_token_free: u64,
+    pub base_token_locked: u64,
+    pub quote_token_free: u64,
+    pub quote_token_locked: u64,
+    pub slot: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexedEvent {
+    Trade(Trade),
+    Order(OrderEvent),
+}
+
+impl IndexedEvent {
+    pub fn market(&self) -> &Pubkey {
+        match self {
+            IndexedEvent::Trade(t) => &t.market,
+            IndexedEvent::Order(o) => &o.market,
+        }
+    }
+}
+
+/// The position of a log in the indexed transactions
+struct LogPosition<'a> {
+    signature: &'a Signature,
+    log_index: u32,
+    slot: u64,
+    block_time: Option<i64>,
+}
+
+/// Decodes the records of the given markets from the log messages of a transaction
+pub fn parse_logs(
+    markets: &[Pubkey],
+    signature: &Signature,
+    slot: u64,
+    block_time: Option<i64>,
+    logs: &[String],
+) -> Vec<IndexedEvent> {
+    let mut events = vec![];
+    let mut log_index = 0;
+    // The maker logs are told apart from the taker logs by the instruction which emitted them, as their encodings
+    // can collide with those of the other logs
+    let mut consuming_events = false;
+    for log in logs {
+        if let Some(instruction) = log.strip_prefix(INSTRUCTION_PREFIX) {
+            consuming_events = CONSUME_EVENTS_INSTRUCTIONS.contains(&instruction);
+            continue;
+        }
+        let bytes = match log
+            .strip_prefix(PROGRAM_DATA_PREFIX)
+            .and_then(|data| base64::decode(data).ok())
+        {
+            Some(b) => b,
+            None => continue,
+        };
+        let position = LogPosition {
+            signature,
+            log_index,
+            slot,
+            block_time,
+        };
+        log_index += 1;
+        let event = if consuming_events {
+            parse_order_event(&bytes, &position).map(IndexedEvent::Order)
+        } else {
+            parse_trade(&bytes, &position).map(IndexedEvent::Trade)
+        };
+        events.extend(event.filter(|e| markets.contains(e.market())));
+    }
+    events
+}
+
+fn parse_trade(bytes: &[u8], position: &LogPosition) -> Option<Trade> {
+    let fill = FillLog::try_from_slice(bytes).ok()?;
+    Some(Trade {
+        signature: *position.signature,
+        log_index: position.log_index,
+        slot: position.slot,
+        block_time: position.block_time,
+        market: fill.market,
+        side: if fill.side == Side::Bid as u8 {
+            Side::Bid
+        } else {
+            Side::Ask
+        },
+        base_qty: fill.base_qty,
+        quote_qty: fill.quote_qty,
+        taker: fill.taker,
+        client_order_id: fill.client_order_id,
+    })
+}
+
+fn parse_order_event(bytes: &[u8], position: &LogPosition) -> Option<OrderEvent> {
+    let (kind, market, order_id, user_account, client_order_id, base_qty, quote_qty, maker_rebate) =
+        if let Ok(fill) = MakerFillLog::try_from_slice(bytes) {
+            (
+                OrderEventKind::Fill,
+                fill.market,
+                fill.order_id,
+                fill.maker,
+                fill.client_order_id,
+                fill.base_qty,
+                fill.quote_qty,
+                fill.maker_rebate,
+            )
+        } else {
+            let out = OutLog::try_from_slice(bytes).ok()?;
+            (
+                OrderEventKind::Out,
+                out.market,
+                out.order_id,
+                Some(out.user_account),
+                out.client_order_id,
+                out.base_qty,
+                0,
+                0,
+            )
+        };
+    Some(OrderEvent {
+        signature: *position.signature,
+        log_index: position.log_index,
+        slot: position.slot,
+        block_time: position.block_time,
+        market,
+        kind,
+        order_id,
+        side: get_side_from_order_id(order_id),
+        price: (order_id >> 64) as u64,
+        user_account,
+        client_order_id,
+        base_qty,
+        quote_qty,
+        maker_rebate,
+    })
+}
@@ -0,0 +1,196 @@
+//! A side-car indexer for serum dex v4 markets.
+//!
+//! The indexer decodes the fills and order exits logged by the dex program, from a live log subscription or from a
+//! backfill of past transactions, into normalized [`Trade`](event::Trade) and [`OrderEvent`](event::OrderEvent)
+//! records, and refreshes the [`Balance`](event::Balance) of every user account involved. The records are written
+//! to a [`Store`](store::Store), with SQLite and Postgres implementations behind the `sqlite` and `postgres`
+//! features.
+//!
+//! Records are keyed by transaction signature and log index, so that a transaction seen by both the live
+//! subscription and a backfill is only indexed once.
+use std::str::FromStr;
+
+use dex_v4::state::UserAccount;
+use error::IndexerError;
+use event::{parse_logs, Balance, IndexedEvent};
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use store::Store;
+
+pub mod error;
+pub mod event;
+pub mod store;
+
+const SIGNATURES_PAGE_SIZE: usize = 1_000;
+
+pub struct Indexer<S> {
+    pub connection: RpcClient,
+    pub program_id: Pubkey,
+    /// The indexed markets
+    pub markets: Vec<Pubkey>,
+    /// The commitment of the indexed transactions. Records of confirmed transactions are not rolled back if their
+    /// fork is abandoned, which `finalized` rules out at the cost of latency.
+    pub commitment: CommitmentConfig,
+    pub store: S,
+}
+
+impl<S: Store> Indexer<S> {
+    pub fn new(
+        connection: RpcClient,
+        program_id: Pubkey,
+        markets: Vec<Pubkey>,
+        commitment: CommitmentConfig,
+        store: S,
+    ) -> Self {
+        Self {
+            connection,
+            program_id,
+            markets,
+            commitment,
+            store,
+        }
+    }
+
+    /// Indexes the transactions of a market which were not indexed by a previous backfill, in chronological order,
+    /// and returns the number of indexed transactions
+    pub fn backfill(&mut self, market: &Pubkey) -> Result<usize, IndexerError> {
+        let until = self
+            .store
+            .cursor(market)
+            .map_err(|e| IndexerError::Store(e.to_string()))?;
+        let mut signatures = vec![];
+        let mut before = None;
+        loop {
+            let page = self.connection.get_signatures_for_address_with_config(
+                market,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(SIGNATURES_PAGE_SIZE),
+                    commitment: Some(self.commitment),
+                },
+            )?;
+            let page_len = page.len();
+            for s in page {
+                let signature = Signature::from_str(&s.signature)
+                    .map_err(|_| IndexerError::InvalidSignature(s.signature.clone()))?;
+                before = Some(signature);
+                if s.err.is_none() {
+                    signatures.push(signature);
+                }
+            }
+            if page_len < SIGNATURES_PAGE_SIZE {
+                break;
+            }
+        }
+
+        for signature in signatures.iter().rev() {
+            let transaction = self
+                .connection
+                .get_transaction(signature, UiTransactionEncoding::Json)?;
+            let logs = transaction
+                .transaction
+                .meta
+                .and_then(|m| m.log_messages)
+                .unwrap_or_default();
+            self.index_transaction(signature, transaction.slot, transaction.block_time, &logs)?;
+            self.store
+                .set_cursor(market, signature)
+                .map_err(|e| IndexerError::Store(e.to_string()))?;
+        }
+        Ok(signatures.len())
+    }
+
+    /// Subscribes to the logs of the dex program over a websocket and indexes the successful transactions until the
+    /// subscription is closed
+    pub fn run_live(&mut self, websocket_url: &str) -> Result<(), IndexerError> {
+        let (_subscription, receiver) = PubsubClient::logs_subscribe(
+            websocket_url,
+            RpcTransactionLogsFilter::Mentions(vec![self.program_id.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(self.commitment),
+            },
+        )
+        .map_err(|e| IndexerError::Subscription(e.to_string()))?;
+        while let Ok(response) = receiver.recv() {
+            let logs = response.value;
+            if logs.err.is_some() {
+                continue;
+            }
+            let signature = Signature::from_str(&logs.signature)
+                .map_err(|_| IndexerError::InvalidSignature(logs.signature.clone()))?;
+            let slot = response.context.slot;
+            // The block time is not known yet when the block is only confirmed
+            let block_time = self.connection.get_block_time(slot).ok();
+            self.index_transaction(&signature, slot, block_time, &logs.logs)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the records of a transaction to the store and refreshes the balances of the user accounts involved
+    pub fn index_transaction(
+        &mut self,
+        signature: &Signature,
+        slot: u64,
+        block_time: Option<i64>,
+        logs: &[String],
+    ) -> Result<(), IndexerError> {
+        let events = parse_logs(&self.markets, signature, slot, block_time, logs);
+        let mut user_accounts = vec![];
+        for event in events.iter() {
+            match event {
+                IndexedEvent::Trade(trade) => {
+                    user_accounts.extend(trade.taker);
+                    self.store.insert_trade(trade)
+                }
+                IndexedEvent::Order(order) => {
+                    user_accounts.extend(order.user_account);
+                    self.store.insert_order_event(order)
+                }
+            }
+            .map_err(|e| IndexerError::Store(e.to_string()))?;
+        }
+        user_accounts.sort_unstable();
+        user_accounts.dedup();
+        for user_account in user_accounts.iter() {
+            if let Some(balance) = self.fetch_balance(user_account)? {
+                self.store
+                    .upsert_balance(&balance)
+                    .map_err(|e| IndexerError::Store(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the balances of a user account, or `None` for the wallets of swap takers
+    fn fetch_balance(&self, address: &Pubkey) -> Result<Option<Balance>, IndexerError> {
+        let response = self
+            .connection
+            .get_account_with_commitment(address, self.commitment)?;
+        let account = match response.value {
+            Some(a) if a.owner == self.program_id => a,
+            _ => return Ok(None),
+        };
+        let mut data = account.data;
+        let header = match UserAccount::from_buffer(&mut data) {
+            Ok(u) => *u.header,
+            Err(_) => return Ok(None),
+        };
+        Ok(Some(Balance {
+            user_account: *address,
+            market: header.market,
+            owner: header.owner,
+            base_token_free: header.base_token_free,
+            base_token_locked: header.base_token_locked,
+            quote_token_free: header.quote_token_free,
+            quote_token_locked: header.quote_token_locked,
+            slot: response.context.slot,
+        }))
+    }
+}
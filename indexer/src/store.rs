@@ -0,0 +1,81 @@
+//! The storage backends of the indexer
+use std::{collections::HashMap, convert::Infallible};
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::event::{Balance, OrderEvent, Trade};
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+mod schema;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// A destination for the indexed records.
+///
+/// Inserting a trade or order event which was already stored should be a no-op, since a transaction can be seen by
+/// both the live subscription and a backfill.
+pub trait Store {
+    type Error: std::error::Error;
+
+    fn insert_trade(&mut self, trade: &Trade) -> Result<(), Self::Error>;
+
+    fn insert_order_event(&mut self, event: &OrderEvent) -> Result<(), Self::Error>;
+
+    /// Stores the balances of a user account, unless more recent balances are already stored
+    fn upsert_balance(&mut self, balance: &Balance) -> Result<(), Self::Error>;
+
+    /// Returns the most recent transaction of a market indexed by a backfill
+    fn cursor(&mut self, market: &Pubkey) -> Result<Option<Signature>, Self::Error>;
+
+    fn set_cursor(&mut self, market: &Pubkey, signature: &Signature) -> Result<(), Self::Error>;
+}
+
+/// A store keeping the records in memory, for tests and short-lived analyses
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    pub trades: HashMap<(Signature, u32), Trade>,
+    pub order_events: HashMap<(Signature, u32), OrderEvent>,
+    pub balances: HashMap<Pubkey, Balance>,
+    pub cursors: HashMap<Pubkey, Signature>,
+}
+
+impl Store for MemoryStore {
+    type Error = Infallible;
+
+    fn insert_trade(&mut self, trade: &Trade) -> Result<(), Self::Error> {
+        self.trades
+            .entry((trade.signature, trade.log_index))
+            .or_insert_with(|| trade.clone());
+        Ok(())
+    }
+
+    fn insert_order_event(&mut self, event: &OrderEvent) -> Result<(), Self::Error> {
+        self.order_events
+            .entry((event.signature, event.log_index))
+            .or_insert_with(|| event.clone());
+        Ok(())
+    }
+
+    fn upsert_balance(&mut self, balance: &Balance) -> Result<(), Self::Error> {
+        let stored = self
+            .balances
+            .entry(balance.user_account)
+            .or_insert(*balance);
+        if stored.slot < balance.slot {
+            *stored = *balance;
+        }
+        Ok(())
+    }
+
+    fn cursor(&mut self, market: &Pubkey) -> Result<Option<Signature>, Self::Error> {
+        Ok(self.cursors.get(market).copied())
+    }
+
+    fn set_cursor(&mut self, market: &Pubkey, signature: &Signature) -> Result<(), Self::Error> {
+        self.cursors.insert(*market, *signature);
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! A store writing the records to a Postgres database
+use std::str::FromStr;
+
+use postgres_client::{Client, NoTls};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use super::{schema, Store};
+use crate::event::{Balance, OrderEvent, Trade};
+
+pub struct PostgresStore {
+    client: Client,
+}
+
+impl PostgresStore {
+    /// Connects to the database, creating its tables if needed
+    pub fn connect(params: &str) -> Result<Self, postgres_client::Error> {
+        let mut client = Client::connect(params, NoTls)?;
+        client.batch_execute(schema::CREATE_TABLES)?;
+        Ok(Self { client })
+    }
+}
+
+impl Store for PostgresStore {
+    type Error = postgres_client::Error;
+
+    fn insert_trade(&mut self, trade: &Trade) -> Result<(), Self::Error> {
+        self.client.execute(
+            schema::INSERT_TRADE,
+            &[
+                &trade.signature.to_string(),
+                &(trade.log_index as i32),
+                &(trade.slot as i64),
+                &trade.block_time,
+                &trade.market.to_string(),
+                &(trade.side as i16),
+                &(trade.base_qty as i64),
+                &(trade.quote_qty as i64),
+                &trade.taker.map(|k| k.to_string()),
+                &trade.client_order_id.map(|id| id.to_string()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_order_event(&mut self, event: &OrderEvent) -> Result<(), Self::Error> {
+        self.client.execute(
+            schema::INSERT_ORDER_EVENT,
+            &[
+                &event.signature.to_string(),
+                &(event.log_index as i32),
+                &(event.slot as i64),
+                &event.block_time,
+                &event.market.to_string(),
+                &event.kind.as_str(),
+                &event.order_id.to_string(),
+                &(event.side as i16),
+                &(event.price as i64),
+                &event.user_account.map(|k| k.to_string()),
+                &event.client_order_id.map(|id| id.to_string()),
+                &(event.base_qty as i64),
+                &(event.quote_qty as i64),
+                &(event.maker_rebate as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn upsert_balance(&mut self, balance: &Balance) -> Result<(), Self::Error> {
+        self.client.execute(
+            schema::UPSERT_BALANCE,
+            &[
+                &balance.user_account.to_string(),
+                &balance.market.to_string(),
+                &balance.owner.to_string(),
+                &(balance.base_token_free as i64),
+                &(balance.base_token_locked as i64),
+                &(balance.quote_token_free as i64),
+                &(balance.quote_token_locked as i64),
+                &(balance.slot as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn cursor(&mut self, market: &Pubkey) -> Result<Option<Signature>, Self::Error> {
+        let row = self
+            .client
+            .query_opt(schema::SELECT_CURSOR, &[&market.to_string()])?;
+        Ok(row.and_then(|r| Signature::from_str(r.get(0)).ok()))
+    }
+
+    fn set_cursor(&mut self, market: &Pubkey, signature: &Signature) -> Result<(), Self::Error> {
+        self.client.execute(
+            schema::UPSERT_CURSOR,
+            &[&market.to_string(), &signature.to_string()],
+        )?;
+        Ok(())
+    }
+}
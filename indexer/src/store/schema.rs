@@ -0,0 +1,78 @@
+//! The SQL schema shared by the SQL stores. Amounts are stored as 64 bit integers, which hold any amount below
+//! 2^63, and order ids as decimal strings.
+
+pub const CREATE_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS trades (
+    signature TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time BIGINT,
+    market TEXT NOT NULL,
+    side SMALLINT NOT NULL,
+    base_qty BIGINT NOT NULL,
+    quote_qty BIGINT NOT NULL,
+    taker TEXT,
+    client_order_id TEXT,
+    PRIMARY KEY (signature, log_index)
+);
+CREATE TABLE IF NOT EXISTS order_events (
+    signature TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time BIGINT,
+    market TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    order_id TEXT NOT NULL,
+    side SMALLINT NOT NULL,
+    price BIGINT NOT NULL,
+    user_account TEXT,
+    client_order_id TEXT,
+    base_qty BIGINT NOT NULL,
+    quote_qty BIGINT NOT NULL,
+    maker_rebate BIGINT NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+CREATE TABLE IF NOT EXISTS balances (
+    user_account TEXT PRIMARY KEY,
+    market TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    base_token_free BIGINT NOT NULL,
+    base_token_locked BIGINT NOT NULL,
+    quote_token_free BIGINT NOT NULL,
+    quote_token_locked BIGINT NOT NULL,
+    slot BIGINT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS cursors (
+    market TEXT PRIMARY KEY,
+    signature TEXT NOT NULL
+);
+";
+
+pub const INSERT_TRADE: &str = "
+INSERT INTO trades (signature, log_index, slot, block_time, market, side, base_qty, quote_qty, taker, client_order_id)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+ON CONFLICT DO NOTHING";
+
+pub const INSERT_ORDER_EVENT: &str = "
+INSERT INTO order_events (signature, log_index, slot, block_time, market, kind, order_id, side, price, user_account,
+    client_order_id, base_qty, quote_qty, maker_rebate)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+ON CONFLICT DO NOTHING";
+
+pub const UPSERT_BALANCE: &str = "
+INSERT INTO balances (user_account, market, owner, base_token_free, base_token_locked, quote_token_free,
+    quote_token_locked, slot)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+ON CONFLICT (user_account) DO UPDATE SET
+    base_token_free = excluded.base_token_free,
+    base_token_locked = excluded.base_token_locked,
+    quote_token_free = excluded.quote_token_free,
+    quote_token_locked = excluded.quote_token_locked,
+    slot = excluded.slot
+WHERE balances.slot < excluded.slot";
+
+pub const SELECT_CURSOR: &str = "SELECT signature FROM cursors WHERE market = $1";
+
+pub const UPSERT_CURSOR: &str = "
+INSERT INTO cursors (market, signature) VALUES ($1, $2)
+ON CONFLICT (market) DO UPDATE SET signature = excluded.signature";
@@ -0,0 +1,110 @@
+//! A store writing the records to a SQLite database
+use std::str::FromStr;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use super::{schema, Store};
+use crate::event::{Balance, OrderEvent, Trade};
+
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at the given path, creating its tables if needed
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(schema::CREATE_TABLES)?;
+        Ok(Self { connection })
+    }
+}
+
+/// SQLite numbers its parameters `?1`, `?2`, ... where Postgres uses `$1`, `$2`, ...
+fn statement(sql: &str) -> String {
+    sql.replace('$', "?")
+}
+
+impl Store for SqliteStore {
+    type Error = rusqlite::Error;
+
+    fn insert_trade(&mut self, trade: &Trade) -> Result<(), Self::Error> {
+        self.connection.execute(
+            &statement(schema::INSERT_TRADE),
+            params![
+                trade.signature.to_string(),
+                trade.log_index,
+                trade.slot as i64,
+                trade.block_time,
+                trade.market.to_string(),
+                trade.side as u8,
+                trade.base_qty as i64,
+                trade.quote_qty as i64,
+                trade.taker.map(|k| k.to_string()),
+                trade.client_order_id.map(|id| id.to_string()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_order_event(&mut self, event: &OrderEvent) -> Result<(), Self::Error> {
+        self.connection.execute(
+            &statement(schema::INSERT_ORDER_EVENT),
+            params![
+                event.signature.to_string(),
+                event.log_index,
+                event.slot as i64,
+                event.block_time,
+                event.market.to_string(),
+                event.kind.as_str(),
+                event.order_id.to_string(),
+                event.side as u8,
+                event.price as i64,
+                event.user_account.map(|k| k.to_string()),
+                event.client_order_id.map(|id| id.to_string()),
+                event.base_qty as i64,
+                event.quote_qty as i64,
+                event.maker_rebate as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn upsert_balance(&mut self, balance: &Balance) -> Result<(), Self::Error> {
+        self.connection.execute(
+            &statement(schema::UPSERT_BALANCE),
+            params![
+                balance.user_account.to_string(),
+                balance.market.to_string(),
+                balance.owner.to_string(),
+                balance.base_token_free as i64,
+                balance.base_token_locked as i64,
+                balance.quote_token_free as i64,
+                balance.quote_token_locked as i64,
+                balance.slot as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn cursor(&mut self, market: &Pubkey) -> Result<Option<Signature>, Self::Error> {
+        let signature: Option<String> = self
+            .connection
+            .query_row(
+                &statement(schema::SELECT_CURSOR),
+                params![market.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(signature.and_then(|s| Signature::from_str(&s).ok()))
+    }
+
+    fn set_cursor(&mut self, market: &Pubkey, signature: &Signature) -> Result<(), Self::Error> {
+        self.connection.execute(
+            &statement(schema::UPSERT_CURSOR),
+            params![market.to_string(), signature.to_string()],
+        )?;
+        Ok(())
+    }
+}
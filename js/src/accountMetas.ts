@@ -0,0 +1,2945 @@
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import BN from "bn.js";
+
+/**
+ * Describes an account of an instruction
+ */
+export interface AccountMetaInfo {
+  /** The name of the account in the instruction builders */
+  name: string;
+  /** A human readable description of the account */
+  description: string;
+  /** Whether the account is written to */
+  writable: boolean;
+  /** Whether the account signs the transaction */
+  signer: boolean;
+  /** Whether the account can be left out */
+  optional: boolean;
+  /** Whether the entry stands for a list of accounts, possibly empty */
+  variable: boolean;
+}
+
+/**
+ * The accounts of each instruction indexed by instruction tag, mirroring `account_metas.rs` in the program
+ */
+export const ACCOUNTS_METAS: AccountMetaInfo[][] = [
+  // CreateMarket
+  [
+    {
+      name: "market",
+      description: "The market account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base vault account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote vault account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "tokenMetadata",
+      description: "The metaplex token metadata",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "createMarketConfig",
+      description: "The create market config account of the deployment, which may not exist",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "feePayer",
+      description: "The optional listing fee payer, required when the deployment charges a listing fee",
+      writable: true,
+      signer: true,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "feeSource",
+      description: "The optional token account paying a listing fee in tokens, the fee payer itself for a fee in lamports",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "treasury",
+      description: "The optional treasury of the create market config",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "feeProgram",
+      description: "The optional program transferring the listing fee, the system program or the SPL token program",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // NewOrder
+  [
+    {
+      name: "splTokenProgram",
+      description: "The SPL token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userTokenAccount",
+      description: "The user source token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user wallet, or the session key for session orders",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "discountTokenAccount",
+      description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "oracle",
+      description: "The optional oracle price account, required if the market has an oracle",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "session",
+      description: "The optional session account, required for session orders",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "dmmRegistry",
+      description: "The optional designated market maker registry, which waives the taker fees of registered user accounts",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "feeReferralAccount",
+      description: "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // Swap
+  [
+    {
+      name: "splTokenProgram",
+      description: "The SPL token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketSigner",
+      description: "The DEX market signer",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userBaseAccount",
+      description: "The user base token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userQuoteAccount",
+      description: "The user quote token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user wallet",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "discountTokenAccount",
+      description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "oracle",
+      description: "The optional oracle price account, required if the market has an oracle",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "feeReferralAccount",
+      description: "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // CancelOrder
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user wallet",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // ConsumeEvents
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "rewardTarget",
+      description: "The reward target, credited with the cranker's share of the fees when it is a user account of the market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userAccounts",
+      description: "The relevant user accounts, along with the session accounts to prune when pruning expired sessions",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: true,
+    },
+    {
+      name: "bids",
+      description: "The optional AOB bids shared memory, required when sampling maker rewards, pruning expired sessions or reposting iceberg orders",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The optional AOB asks shared memory, required when sampling maker rewards, pruning expired sessions or reposting iceberg orders",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "makerRewards",
+      description: "The optional maker rewards account, required when sampling maker rewards",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "crankerIdentity",
+      description: "The optional cranker identity account, required when recording the crank",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "cranker",
+      description: "The optional owner of the cranker identity account, required when recording the crank",
+      writable: false,
+      signer: true,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // Settle
+  [
+    {
+      name: "splTokenProgram",
+      description: "The spl token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketSigner",
+      description: "The DEX market signer account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The DEX user account owner wallet, which receives the unwrapped SOL",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinationBaseAccount",
+      description: "The destination base token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinationQuoteAccount",
+      description: "The destination quote token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "settleAllowlist",
+      description: "The settle allowlist of the user account, required when one has been registered",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // InitializeAccount
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The user account to initialize",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The owner of the user account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "feePayer",
+      description: "The fee payer",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SweepFees
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketSigner",
+      description: "The DEX market signer",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The market quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinationTokenAccount",
+      description: "The destination token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "splTokenProgram",
+      description: "The spl token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "tokenMetadata",
+      description: "The metadata account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "creatorsTokenAccounts",
+      description: "The creator token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: true,
+    },
+  ],
+  // CloseAccount
+  [
+    {
+      name: "user",
+      description: "The user account to close",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The owner of the user account to close",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "targetLamportsAccount",
+      description: "The target lamports account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // CloseMarket
+  [
+    {
+      name: "market",
+      description: "The market account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The market base vault account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The market quote vault account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The AOB orderbook account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The makret admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "targetLamportsAccount",
+      description: "The target lamports account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketSigner",
+      description: "The market signer",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "splTokenProgram",
+      description: "The SPL token program ID",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // UpdateRoyalties
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The event queue account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The AOB market account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "tokenMetadata",
+      description: "The token metadata",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // MigrateAccount
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "account",
+      description: "The DEX market or user account to migrate",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "payer",
+      description: "The account paying for the rent of the added space",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // CreateMakerRewards
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "makerRewards",
+      description: "The maker rewards account to initialize",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "feePayer",
+      description: "The fee payer",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetAccountMetadata
+  [
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The owner of the user account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // HashOrderbook
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetMarketStatus
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // ForceConsumeEvents
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account, which pays for the escrow account creation",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "escrow",
+      description: "The market escrow account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userAccounts",
+      description: "The available user accounts",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: true,
+    },
+  ],
+  // ClaimEscrow
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "escrow",
+      description: "The market escrow account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user account owner wallet",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // UpdateMarketMetadata
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetOracle
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "oracle",
+      description: "The optional Pyth price account",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // SetFillPrivacy
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetBatchAuction
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "batchAuction",
+      description: "The market batch auction account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account, which pays for the batch auction account creation",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // NewBatchOrder
+  [
+    {
+      name: "splTokenProgram",
+      description: "The SPL token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "batchAuction",
+      description: "The market batch auction account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userTokenAccount",
+      description: "The user source token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user wallet",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "discountTokenAccount",
+      description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // MatchBatch
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "batchAuction",
+      description: "The market batch auction account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userAccounts",
+      description: "The user accounts of the pending orders, sorted by key",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: true,
+    },
+  ],
+  // CreateSession
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user account owner wallet, which pays for the session account creation",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "session",
+      description: "The session account of the user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // CancelSessionOrders
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "session",
+      description: "The expired session account of the user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetDesignatedMarketMaker
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "dmmRegistry",
+      description: "The market designated market maker registry",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account, which pays for the registry account creation",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // GetMarketInfo
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetCrankFeeShare
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetVolatilityFee
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // CreateCrankerIdentity
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "crankerIdentity",
+      description: "The cranker identity account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "cranker",
+      description: "The operator's wallet, which pays for the identity account creation",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // FreezeAndExport
+  [
+    {
+      name: "splTokenProgram",
+      description: "The SPL token program ID",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The market base vault account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The market quote vault account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketSigner",
+      description: "The market signer",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "migrationMarketSigner",
+      description: "The signer of the migration market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // ImportMarket
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "sourceMarket",
+      description: "The exported market of the previous deployment",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "sourceBaseVault",
+      description: "The base vault of the exported market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "sourceQuoteVault",
+      description: "The quote vault of the exported market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetMinRestingSlots
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // Heartbeat
+  [
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The owner of the user account, or the session key when a session account is given",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "session",
+      description: "The optional session account authorizing the session key",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // RelinkOrderbook
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The current orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The current AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The current AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The current AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "newOrderbook",
+      description: "The new orderbook account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "newEventQueue",
+      description: "The new AOB event queue account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "newBids",
+      description: "The new AOB bids account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "newAsks",
+      description: "The new AOB asks account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "targetLamportsAccount",
+      description: "The account receiving the lamports of the closed AOB accounts",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // CreateSettleAllowlist
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The DEX user account owner wallet, which pays for the allowlist account creation",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "settleAllowlist",
+      description: "The settle allowlist account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinations",
+      description: "The allowed destination token accounts",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: true,
+    },
+  ],
+  // PreviewNewOrder
+  [
+    {
+      name: "splTokenProgram",
+      description: "The SPL token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userTokenAccount",
+      description: "The user source token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user wallet, or the session key for session orders",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "discountTokenAccount",
+      description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "oracle",
+      description: "The optional oracle price account, required if the market has an oracle",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "session",
+      description: "The optional session account, required for session orders",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "dmmRegistry",
+      description: "The optional designated market maker registry, which waives the taker fees of registered user accounts",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "feeReferralAccount",
+      description: "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // CancelAllOrders
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user wallet",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // ReplaceOrder
+  [
+    {
+      name: "splTokenProgram",
+      description: "The SPL token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userTokenAccount",
+      description: "The user source token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The user wallet, or the session key for session orders",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "discountTokenAccount",
+      description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "oracle",
+      description: "The optional oracle price account, required if the market has an oracle",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "session",
+      description: "The optional session account, required for session orders",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "dmmRegistry",
+      description: "The optional designated market maker registry, which waives the taker fees of registered user accounts",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+    {
+      name: "feeReferralAccount",
+      description: "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+      writable: true,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // ReclaimOrderbook
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "targetLamportsAccount",
+      description: "The account receiving the lamports of the closed AOB accounts",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetCreateMarketConfig
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "createMarketConfig",
+      description: "The create market config account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "sweepAuthority",
+      description: "The sweep authority, which pays for the config account creation",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetReferralShare
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetMakerRebate
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetEmergencyAuthority
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // EmergencyHalt
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "emergencyAuthority",
+      description: "The emergency authority of the market",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // UpdateMarketAdmin
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetCancelFee
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // NominateAdmin
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // AcceptAdmin
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "pendingAdmin",
+      description: "The nominated market admin",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // ArchiveUserAccount
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account to archive",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The DEX user account owner wallet, which pays for the archive account and receives the user account's rent",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "archive",
+      description: "The user account archive",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // RestoreUserAccount
+  [
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account to restore",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The DEX user account owner wallet, which pays for the user account and receives the archive's rent",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "archive",
+      description: "The user account archive",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // SetHaltFlags
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // UpdateMarketParams
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // PruneExpiredOrders
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "orderbook",
+      description: "The orderbook",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "eventQueue",
+      description: "The AOB event queue",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "bids",
+      description: "The AOB bids shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "asks",
+      description: "The AOB asks shared memory",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "rewardTarget",
+      description: "The account receiving the prune rewards",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userAccounts",
+      description: "The user accounts whose expired orders should be pruned",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: true,
+    },
+  ],
+  // SetCrankRewardScaling
+  [
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketAdmin",
+      description: "The market admin account",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // Deposit
+  [
+    {
+      name: "splTokenProgram",
+      description: "The spl token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The DEX user account owner wallet",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "sourceBaseAccount",
+      description: "The source base token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "sourceQuoteAccount",
+      description: "The source quote token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+  ],
+  // Withdraw
+  [
+    {
+      name: "splTokenProgram",
+      description: "The spl token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketSigner",
+      description: "The DEX market signer account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The DEX user account owner wallet",
+      writable: false,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinationBaseAccount",
+      description: "The destination base token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinationQuoteAccount",
+      description: "The destination quote token account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "settleAllowlist",
+      description: "The settle allowlist of the user account, required when one has been registered",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+  // SettleToAssociatedAccounts
+  [
+    {
+      name: "splTokenProgram",
+      description: "The spl token program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "systemProgram",
+      description: "The system program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "associatedTokenProgram",
+      description: "The spl associated token account program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "rentSysvar",
+      description: "The rent sysvar, required by the associated token account program",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "market",
+      description: "The DEX market",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseMint",
+      description: "The base token mint",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteMint",
+      description: "The quote token mint",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "baseVault",
+      description: "The base token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "quoteVault",
+      description: "The quote token vault",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "marketSigner",
+      description: "The DEX market signer account",
+      writable: false,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "user",
+      description: "The DEX user account",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "userOwner",
+      description: "The DEX user account owner wallet, which pays for the creation of the destination accounts",
+      writable: true,
+      signer: true,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinationBaseAccount",
+      description: "The associated base token account of the owner",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "destinationQuoteAccount",
+      description: "The associated quote token account of the owner",
+      writable: true,
+      signer: false,
+      optional: false,
+      variable: false,
+    },
+    {
+      name: "settleAllowlist",
+      description: "The settle allowlist of the user account, required when one has been registered",
+      writable: false,
+      signer: false,
+      optional: true,
+      variable: false,
+    },
+  ],
+];
+
+/**
+ * Returns the accounts of an instruction, in order
+ * @param tag The instruction tag
+ */
+export const getAccountsMetas = (tag: number): AccountMetaInfo[] => {
+  const metas = ACCOUNTS_METAS[tag];
+  if (!metas) {
+    throw new Error(`Unknown instruction tag ${tag}`);
+  }
+  return metas;
+};
+
+/**
+ * Labels the accounts of a dex instruction, for transaction previews.
+ *
+ * The accounts which follow a variable list of accounts are left unlabeled, as the length of the list isn't known
+ * from the instruction alone.
+ * @param ix The dex instruction
+ */
+export const labelAccounts = (
+  ix: TransactionInstruction
+): { pubkey: PublicKey; meta?: AccountMetaInfo }[] => {
+  const metas = getAccountsMetas(
+    new BN(ix.data.slice(0, 8), "le").toNumber()
+  );
+  const variable = metas.findIndex((m) => m.variable);
+  const known = variable === -1 ? metas : metas.slice(0, variable);
+  return ix.keys.map((k, i) => ({ pubkey: k.pubkey, meta: known[i] }));
+};
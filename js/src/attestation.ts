@@ -0,0 +1,294 @@
+import { Connection, Keypair, PublicKey } from "@solana/web3.js";
+import { deserializeUnchecked } from "borsh";
+import bs58 from "bs58";
+import crypto from "crypto";
+import nacl from "tweetnacl";
+import { decodeOrderId } from "./orderId";
+import { UserAccount } from "./state";
+import { Side } from "./types";
+
+/**
+ * The version of the attestation format
+ */
+export const ATTESTATION_VERSION = 1;
+
+/**
+ * An open order of an attested user account. Amounts are decimal strings.
+ */
+export interface AttestedOrder {
+  id: string;
+  clientId: string;
+  side: "bid" | "ask";
+  /** The FP32 limit price of the order */
+  price: string;
+  placedSlot: string;
+}
+
+/**
+ * The state of a user account at a given slot. Amounts are raw token amounts as decimal strings.
+ */
+export interface UserAccountAttestationPayload {
+  version: number;
+  userAccount: string;
+  market: string;
+  owner: string;
+  /** The slot at which the user account was read */
+  slot: number;
+  /** The unix timestamp in seconds at which the attestation was made */
+  timestamp: number;
+  baseTokenFree: string;
+  baseTokenLocked: string;
+  quoteTokenFree: string;
+  quoteTokenLocked: string;
+  accumulatedRebates: string;
+  orders: AttestedOrder[];
+  /** The hex encoded SHA-256 hash of the raw account data */
+  dataHash: string;
+}
+
+/**
+ * An attestation payload signed by an auditor or custodian key
+ */
+export interface SignedUserAccountAttestation {
+  payload: UserAccountAttestationPayload;
+  /** The base58 public key of the signer */
+  signer: string;
+  /** The base58 ed25519 signature of the serialized payload */
+  signature: string;
+}
+
+/**
+ * The outcome of the verification of an attestation
+ */
+export interface AttestationVerification {
+  /** True if the signature matches the payload and the expected signer */
+  signatureValid: boolean;
+  /** The names of the attested fields which differ from the current chain state */
+  mismatches: string[];
+  /** The slot at which the chain state was read */
+  slot: number;
+}
+
+/**
+ * Serializes a payload with a fixed key order, which is the message being signed
+ * @param payload The attestation payload
+ * @returns
+ */
+export const serializeAttestationPayload = (
+  payload: UserAccountAttestationPayload
+): Uint8Array => {
+  const orders = payload.orders.map((o) => ({
+    id: o.id,
+    clientId: o.clientId,
+    side: o.side,
+    price: o.price,
+    placedSlot: o.placedSlot
+  }));
+  const ordered = {
+    version: payload.version,
+    userAccount: payload.userAccount,
+    market: payload.market,
+    owner: payload.owner,
+    slot: payload.slot,
+    timestamp: payload.timestamp,
+    baseTokenFree: payload.baseTokenFree,
+    baseTokenLocked: payload.baseTokenLocked,
+    quoteTokenFree: payload.quoteTokenFree,
+    quoteTokenLocked: payload.quoteTokenLocked,
+    accumulatedRebates: payload.accumulatedRebates,
+    orders,
+    dataHash: payload.dataHash
+  };
+  return Buffer.from(JSON.stringify(ordered), "utf-8");
+};
+
+/**
+ * Builds the attestation payload of a decoded user account
+ * @param userAccount The address of the user account
+ * @param decoded The decoded user account
+ * @param data The raw account data
+ * @param slot The slot at which the account was read
+ * @param timestamp The unix timestamp of the attestation, defaults to now
+ * @returns
+ */
+export const buildAttestationPayload = (
+  userAccount: PublicKey,
+  decoded: UserAccount,
+  data: Buffer,
+  slot: number,
+  timestamp = Math.floor(Date.now() / 1_000)
+): UserAccountAttestationPayload => {
+  return {
+    version: ATTESTATION_VERSION,
+    userAccount: userAccount.toBase58(),
+    market: decoded.market.toBase58(),
+    owner: decoded.owner.toBase58(),
+    slot,
+    timestamp,
+    baseTokenFree: decoded.baseTokenFree.toString(),
+    baseTokenLocked: decoded.baseTokenLocked.toString(),
+    quoteTokenFree: decoded.quoteTokenFree.toString(),
+    quoteTokenLocked: decoded.quoteTokenLocked.toString(),
+    accumulatedRebates: decoded.accumulatedRebates.toString(),
+    orders: decoded.orders.map((o) => {
+      const { price, side } = decodeOrderId(o.id);
+      return {
+        id: o.id.toString(),
+        clientId: o.clientId.toString(),
+        side: side === Side.Bid ? "bid" : "ask",
+        price: price.toString(),
+        placedSlot: o.placedSlot.toString()
+      };
+    }),
+    dataHash: crypto.createHash("sha256").update(data).digest("hex")
+  };
+};
+
+/**
+ * Signs an attestation payload
+ * @param payload The attestation payload
+ * @param signer The keypair of the auditor or custodian
+ * @returns
+ */
+export const signAttestation = (
+  payload: UserAccountAttestationPayload,
+  signer: Keypair
+): SignedUserAccountAttestation => {
+  const signature = nacl.sign.detached(
+    serializeAttestationPayload(payload),
+    signer.secretKey
+  );
+  return {
+    payload,
+    signer: signer.publicKey.toBase58(),
+    signature: bs58.encode(signature)
+  };
+};
+
+/**
+ * Checks the signature of an attestation
+ * @param attestation The signed attestation
+ * @param expectedSigner The key which should have signed the attestation, defaults to the attested signer
+ * @returns
+ */
+export const verifyAttestationSignature = (
+  attestation: SignedUserAccountAttestation,
+  expectedSigner?: PublicKey
+): boolean => {
+  if (expectedSigner && expectedSigner.toBase58() !== attestation.signer) {
+    return false;
+  }
+  try {
+    return nacl.sign.detached.verify(
+      serializeAttestationPayload(attestation.payload),
+      bs58.decode(attestation.signature),
+      new PublicKey(attestation.signer).toBytes()
+    );
+  } catch {
+    return false;
+  }
+};
+
+/**
+ * Lists the fields of an attestation payload which differ from another payload, ignoring the slot and timestamp
+ * @param attested The attested payload
+ * @param current The payload built from the current chain state
+ * @returns
+ */
+export const compareAttestationPayloads = (
+  attested: UserAccountAttestationPayload,
+  current: UserAccountAttestationPayload
+): string[] => {
+  const fields: (keyof UserAccountAttestationPayload)[] = [
+    "userAccount",
+    "market",
+    "owner",
+    "baseTokenFree",
+    "baseTokenLocked",
+    "quoteTokenFree",
+    "quoteTokenLocked",
+    "accumulatedRebates",
+    "dataHash"
+  ];
+  const mismatches = fields.filter((f) => attested[f] !== current[f]);
+  const orderKey = (o: AttestedOrder) =>
+    [o.id, o.clientId, o.side, o.price, o.placedSlot].join(":");
+  const attestedOrders = attested.orders.map(orderKey).sort();
+  const currentOrders = current.orders.map(orderKey).sort();
+  if (
+    attestedOrders.length !== currentOrders.length ||
+    attestedOrders.some((o, i) => o !== currentOrders[i])
+  ) {
+    mismatches.push("orders");
+  }
+  return mismatches;
+};
+
+/**
+ * Exports the balances and open orders of a user account into a signed attestation
+ * @param connection The Solana RPC connection
+ * @param userAccount The address of the user account
+ * @param signer The keypair of the auditor or custodian signing the attestation
+ * @returns
+ */
+export const exportUserAccountAttestation = async (
+  connection: Connection,
+  userAccount: PublicKey,
+  signer: Keypair
+): Promise<SignedUserAccountAttestation> => {
+  const { payload } = await fetchAttestationPayload(connection, userAccount);
+  return signAttestation(payload, signer);
+};
+
+/**
+ * Verifies the signature of an attestation and compares it against the current state of the user account
+ * @param connection The Solana RPC connection
+ * @param attestation The signed attestation
+ * @param expectedSigner The key which should have signed the attestation, defaults to the attested signer
+ * @returns
+ */
+export const verifyUserAccountAttestation = async (
+  connection: Connection,
+  attestation: SignedUserAccountAttestation,
+  expectedSigner?: PublicKey
+): Promise<AttestationVerification> => {
+  const signatureValid = verifyAttestationSignature(
+    attestation,
+    expectedSigner
+  );
+  const { payload, slot } = await fetchAttestationPayload(
+    connection,
+    new PublicKey(attestation.payload.userAccount)
+  );
+  return {
+    signatureValid,
+    mismatches: compareAttestationPayloads(attestation.payload, payload),
+    slot
+  };
+};
+
+const fetchAttestationPayload = async (
+  connection: Connection,
+  userAccount: PublicKey
+) => {
+  const { context, value } = await connection.getAccountInfoAndContext(
+    userAccount
+  );
+  if (!value?.data) {
+    throw new Error("Invalid account provided");
+  }
+  const decoded = deserializeUnchecked(
+    UserAccount.schema,
+    UserAccount,
+    value.data
+  ) as UserAccount;
+  return {
+    payload: buildAttestationPayload(
+      userAccount,
+      decoded,
+      value.data,
+      context.slot
+    ),
+    slot: context.slot
+  };
+};
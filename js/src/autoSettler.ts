@@ -0,0 +1,190 @@
+import {
+  Commitment,
+  Connection,
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionSignature,
+} from "@solana/web3.js";
+import BN from "bn.js";
+import { deserializeUnchecked } from "borsh";
+import { settle } from "./bindings";
+import { Market } from "./market";
+import { AccountTag, UserAccount } from "./state";
+import { getUserAccountAddress } from "./utils";
+
+/**
+ * A user account watched by the auto settler
+ */
+export interface AutoSettleTarget {
+  /** The owner of the user account, which signs the settle transactions */
+  owner: Keypair;
+  /** The token account receiving the settled base tokens */
+  destinationBaseAccount: PublicKey;
+  /** The token account receiving the settled quote tokens */
+  destinationQuoteAccount: PublicKey;
+  /** The index of the user account (0 for the main user account) */
+  accountIndex?: number;
+}
+
+export interface AutoSettlerOptions {
+  /** Settle as soon as the free base balance reaches this raw amount */
+  baseThreshold?: BN;
+  /** Settle as soon as the free quote balance reaches this raw amount */
+  quoteThreshold?: BN;
+  /** The commitment used for the subscriptions and confirmations */
+  commitment?: Commitment;
+  /** Called after each successful settlement */
+  onSettle?: (userAccount: PublicKey, signature: TransactionSignature) => void;
+  /** Called when a settlement fails */
+  onError?: (userAccount: PublicKey, err: Error) => void;
+}
+
+/**
+ * Watches a set of user accounts and automatically settles their free balances
+ * once they exceed the configured thresholds
+ */
+export class AutoSettler {
+  private _connection: Connection;
+  private _market: Market;
+  private _targets: AutoSettleTarget[];
+  private _options: AutoSettlerOptions;
+  private _subscriptions: number[] = [];
+  private _pending: Set<string> = new Set();
+
+  constructor(
+    connection: Connection,
+    market: Market,
+    targets: AutoSettleTarget[],
+    options: AutoSettlerOptions = {}
+  ) {
+    if (!options.baseThreshold && !options.quoteThreshold) {
+      throw new Error("At least one settle threshold is required");
+    }
+    this._connection = connection;
+    this._market = market;
+    this._targets = targets;
+    this._options = options;
+  }
+
+  /**
+   * Returns true if the free balances of the user account exceed one of the thresholds
+   * @param userAccount The decoded user account
+   */
+  shouldSettle(userAccount: UserAccount): boolean {
+    const { baseThreshold, quoteThreshold } = this._options;
+    return (
+      (!!baseThreshold &&
+        !userAccount.baseTokenFree.isZero() &&
+        userAccount.baseTokenFree.gte(baseThreshold)) ||
+      (!!quoteThreshold &&
+        !userAccount.quoteTokenFree.isZero() &&
+        userAccount.quoteTokenFree.gte(quoteThreshold))
+    );
+  }
+
+  private async _settle(
+    target: AutoSettleTarget,
+    userAccount: PublicKey,
+    withSettleAllowlist: boolean
+  ) {
+    const key = userAccount.toBase58();
+    if (this._pending.has(key)) {
+      return;
+    }
+    this._pending.add(key);
+    try {
+      const ix = await settle(
+        this._market,
+        target.owner.publicKey,
+        target.destinationBaseAccount,
+        target.destinationQuoteAccount,
+        target.accountIndex || 0,
+        withSettleAllowlist
+      );
+      const tx = new Transaction().add(ix);
+      const signature = await this._connection.sendTransaction(tx, [
+        target.owner,
+      ]);
+      const { value } = await this._connection.confirmTransaction(
+        signature,
+        this._options.commitment
+      );
+      if (value?.err) {
+        throw new Error(JSON.stringify(value.err));
+      }
+      this._options.onSettle?.(userAccount, signature);
+    } catch (err) {
+      this._options.onError?.(userAccount, err as Error);
+    } finally {
+      this._pending.delete(key);
+    }
+  }
+
+  private async _check(
+    target: AutoSettleTarget,
+    userAccount: PublicKey,
+    data: Buffer
+  ) {
+    // Closed, archived and outdated user accounts don't have the current user account layout
+    const tag =
+      data.length >= 8 ? new BN(data.slice(0, 8), "le").toNumber() : undefined;
+    if (tag !== AccountTag.UserAccount) {
+      this._options.onError?.(
+        userAccount,
+        new Error(`Unexpected user account tag ${tag}`)
+      );
+      return;
+    }
+    const decoded = deserializeUnchecked(
+      UserAccount.schema,
+      UserAccount,
+      data
+    ) as UserAccount;
+    if (this.shouldSettle(decoded)) {
+      await this._settle(target, userAccount, decoded.hasSettleAllowlist);
+    }
+  }
+
+  /**
+   * Checks the current balances of the user accounts and subscribes to their updates
+   */
+  async start() {
+    for (let target of this._targets) {
+      const userAccount = await getUserAccountAddress(
+        this._market.address,
+        target.owner.publicKey,
+        target.accountIndex || 0,
+        this._market.programId
+      );
+      const info = await this._connection.getAccountInfo(
+        userAccount,
+        this._options.commitment
+      );
+      if (info?.data) {
+        await this._check(target, userAccount, info.data);
+      }
+      this._subscriptions.push(
+        this._connection.onAccountChange(
+          userAccount,
+          (info) => {
+            this._check(target, userAccount, info.data).catch((err) =>
+              this._options.onError?.(userAccount, err as Error)
+            );
+          },
+          this._options.commitment
+        )
+      );
+    }
+  }
+
+  /** Removes all subscriptions */
+  async stop() {
+    await Promise.all(
+      this._subscriptions.map((id) =>
+        this._connection.removeAccountChangeListener(id)
+      )
+    );
+    this._subscriptions = [];
+  }
+}
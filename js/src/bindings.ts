@@ -3,6 +3,7 @@ import {
   PublicKey,
   Connection,
   SystemProgram,
+  SYSVAR_RENT_PUBKEY,
   TransactionInstruction
 } from "@solana/web3.js";
 import { DEX_ID, SRM_MINT } from "./ids";
@@ -16,14 +17,72 @@ import {
   closeAccountInstruction,
   swapInstruction,
   closeMarketInstruction,
-  sweepFeesInstruction
+  sweepFeesInstruction,
+  createMakerRewardsInstruction,
+  setAccountMetadataInstruction,
+  hashOrderbookInstruction,
+  setMarketStatusInstruction,
+  forceConsumeEventsInstruction,
+  claimEscrowInstruction,
+  updateMarketMetadataInstruction,
+  setOracleInstruction,
+  setFillPrivacyInstruction,
+  setBatchAuctionInstruction,
+  newBatchOrderInstruction,
+  matchBatchInstruction,
+  createSessionInstruction,
+  cancelSessionOrdersInstruction,
+  setDesignatedMarketMakerInstruction,
+  getMarketInfoInstruction,
+  setCrankFeeShareInstruction,
+  setReferralShareInstruction,
+  setMakerRebateInstruction,
+  setEmergencyAuthorityInstruction,
+  emergencyHaltInstruction,
+  updateMarketAdminInstruction,
+  setCancelFeeInstruction,
+  nominateAdminInstruction,
+  acceptAdminInstruction,
+  archiveUserAccountInstruction,
+  restoreUserAccountInstruction,
+  setHaltFlagsInstruction,
+  updateMarketParamsInstruction,
+  pruneExpiredOrdersInstruction,
+  setVolatilityFeeInstruction,
+  setCrankRewardScalingInstruction,
+  depositInstruction,
+  withdrawInstruction,
+  createCrankerIdentityInstruction,
+  freezeAndExportInstruction,
+  importMarketInstruction,
+  setMinRestingSlotsInstruction,
+  heartbeatInstruction,
+  reclaimOrderbookInstruction,
+  relinkOrderbookInstruction,
+  setCreateMarketConfigInstruction,
+  createSettleAllowlistInstruction,
+  previewNewOrderInstruction,
+  cancelAllOrdersInstruction,
+  replaceOrderInstruction,
+  settleToAssociatedAccountsInstruction,
+  migrateAccountInstruction
 } from "./raw_instructions";
-import { OrderType, PrimedTransaction, Side } from "./types";
+import { OrderType, PrimedTransaction, QuantityMode, Side } from "./types";
 import * as aaob from "@bonfida/aaob";
 import BN from "bn.js";
-import { SelfTradeBehavior } from "./state";
+import { deserializeUnchecked } from "borsh";
+import {
+  CreateMarketConfig,
+  HALT_ASKS_MASK,
+  HALT_BIDS_MASK,
+  MarketStatus,
+  ORDER_LEN,
+  SelfTradeBehavior,
+  USER_ACCOUNT_HEADER_LEN
+} from "./state";
 import { Market } from "./market";
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
   createAssociatedTokenAccountInstruction,
   getAssociatedTokenAddress
@@ -31,12 +90,16 @@ import {
 import crypto from "crypto";
 import { getMetadataKeyFromMint } from "./metadata";
 import { Metadata } from "@metaplex-foundation/mpl-token-metadata";
-import { computeFp32Price } from "./utils";
+import {
+  computeFp32Price,
+  encodeFixedString,
+  getUserAccountAddress
+} from "./utils";
 
 /**
  * Constants
  */
-const MARKET_STATE_SPACE = 280;
+const MARKET_STATE_SPACE = 736;
 const ORDER_CAPACITY = 100;
 const EVENT_CAPACITY = 100;
 const U64_MAX = new BN(0).notn(64);
@@ -51,6 +114,11 @@ const U64_MAX = new BN(0).notn(64);
  * @param marketAdmin The market admin
  * @param tickSize The tick size of the market (FP32)
  * @param crankerReward The cranker rewards (raw amount SOL)
+ * @param minOrderTickDistance The minimum distance in ticks between two orders of a same user on the same side (0 to disable)
+ * @param baseSymbol The symbol of the base token (at most 16 bytes)
+ * @param quoteSymbol The symbol of the quote token (at most 16 bytes)
+ * @param marketName The display name of the market (at most 32 bytes)
+ * @param feeSource The token account of the fee payer paying the listing fee, required if the deployment charges it in tokens
  * @returns
  */
 export const createMarket = async (
@@ -63,8 +131,25 @@ export const createMarket = async (
   tickSize: BN,
   baseCurrencyMultiplier?: BN,
   quoteCurrencyMultiplier?: BN,
-  programId = DEX_ID
+  programId = DEX_ID,
+  minOrderTickDistance = 0,
+  baseSymbol = "",
+  quoteSymbol = "",
+  marketName = "",
+  feeSource?: PublicKey
 ): Promise<PrimedTransaction[]> => {
+  // Listing fee
+  const createMarketConfig = await getCreateMarketConfigAddress(programId);
+  const config = await CreateMarketConfig.retrieve(
+    connection,
+    createMarketConfig
+  );
+  const listingFee =
+    config && !config.listingFee.isZero() ? config : undefined;
+  if (listingFee?.feeMint && !feeSource) {
+    throw new Error("A token account is required to pay the listing fee");
+  }
+
   // Metadata account
   const metadataAccount = await getMetadataKeyFromMint(baseMint);
 
@@ -136,7 +221,11 @@ export const createMarket = async (
     minBaseOrderSize: new BN(minBaseOrderSize),
     tickSize: tickSize,
     baseCurrencyMultiplier,
-    quoteCurrencyMultiplier
+    quoteCurrencyMultiplier,
+    minOrderTickDistance: new BN(minOrderTickDistance),
+    baseSymbol: encodeFixedString(baseSymbol, 16),
+    quoteSymbol: encodeFixedString(quoteSymbol, 16),
+    marketName: encodeFixedString(marketName, 32)
   }).getInstruction(
     programId,
     marketAccount.publicKey,
@@ -147,7 +236,13 @@ export const createMarket = async (
     aaobSigners[0].publicKey,
     aaobSigners[1].publicKey,
     aaobSigners[2].publicKey,
-    metadataAccount
+    metadataAccount,
+    createMarketConfig,
+    listingFee && feePayer,
+    listingFee && (feeSource || feePayer),
+    listingFee?.treasury,
+    listingFee &&
+      (listingFee.feeMint ? TOKEN_PROGRAM_ID : SystemProgram.programId)
   );
 
   return [
@@ -169,6 +264,15 @@ export const createMarket = async (
  * @param owner The user placing the address
  * @param clientOrderId Optional client order ID
  * @param discountTokenAccount Optional SRM token account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @param isDesignatedMarketMaker Whether the user account is a designated market maker of the market, which waives its taker fees
+ * @param matchLimit The maximum number of orders to match against, which bounds the compute used when sweeping a deep book. The remainder is cancelled once the limit is reached.
+ * @param notifyOnCross Whether a log should be emitted when the resting order is skipped by a taker at its price level, as the queue ahead of it is filled
+ * @param expiryTimestamp The unix timestamp after which the posted order can be pruned, 0 for an order which never expires. Posting a good-til-date order deposits the pruning reward in the user account.
+ * @param displayBaseQty The base quantity displayed on the book for a post only iceberg order, 0 to display the whole order. The hidden remainder is kept in the free balance of the user account and posted in tranches of this size by the crank.
+ * @param minBaseQty The minimum base quantity an immediate or cancel, fill or kill or market order should match, below which the transaction fails
+ * @param quantityMode Whether the order is sized by its base quantity or by `maxQuoteQty`. In quote mode, bids spend `maxQuoteQty` fees included and asks sell the base worth it, and a zero `maxBaseQty` is derived from the limit price.
+ * @param useFreeBalances Whether the order should only be funded by the free balances of the user account, as topped up by `deposit`, in which case nothing is debited from `ownerTokenAccount`
  * @returns
  */
 export const placeOrder = async (
@@ -183,10 +287,21 @@ export const placeOrder = async (
   clientOrderId?: BN,
   discountTokenAccount?: PublicKey,
   maxBaseQty?: BN,
-  maxQuoteQty?: BN
+  maxQuoteQty?: BN,
+  accountIndex = 0,
+  isDesignatedMarketMaker = false,
+  matchLimit = new BN(Number.MAX_SAFE_INTEGER),
+  notifyOnCross = false,
+  expiryTimestamp = new BN(0),
+  displayBaseQty = new BN(0),
+  minBaseQty = new BN(0),
+  quantityMode = QuantityMode.Base,
+  useFreeBalances = false
 ) => {
-  const [userAccount] = await PublicKey.findProgramAddress(
-    [market.address.toBuffer(), owner.toBuffer()],
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
     market.programId
   );
 
@@ -208,9 +323,18 @@ export const placeOrder = async (
     maxQuoteQty: maxQuoteQty || U64_MAX,
     orderType: type,
     selfTradeBehavior: selfTradeBehaviour,
-    matchLimit: new BN(Number.MAX_SAFE_INTEGER),
+    matchLimit,
+    expiryTimestamp,
+    displayBaseQty,
+    minBaseQty,
     clientOrderId,
-    hasDiscountTokenAccount: discountTokenAccount === undefined ? 0 : 1 // TODO Change
+    hasDiscountTokenAccount: discountTokenAccount === undefined ? 0 : 1, // TODO Change
+    hasOracleAccount: Number(market.oracle !== undefined),
+    hasSession: 0,
+    hasDmmRegistry: Number(isDesignatedMarketMaker),
+    notifyOnCross: Number(notifyOnCross),
+    quantityMode,
+    useFreeBalances: Number(useFreeBalances)
   }).getInstruction(
     market.programId,
     TOKEN_PROGRAM_ID,
@@ -225,12 +349,176 @@ export const placeOrder = async (
     userAccount,
     ownerTokenAccount,
     owner,
-    discountTokenAccount
+    discountTokenAccount,
+    market.oracle,
+    undefined,
+    isDesignatedMarketMaker
+      ? await getDmmRegistryAddress(market.address, market.programId)
+      : undefined
   );
 
   return instruction;
 };
 
+/**
+ * Builds an instruction previewing an order, to be simulated with the same arguments as `placeOrder`.
+ * The outcome of the order is then decoded with `NewOrderSummary.fromSimulationLogs`.
+ * @returns
+ */
+export const previewOrder = async (
+  ...args: Parameters<typeof placeOrder>
+) => {
+  const instruction = await placeOrder(...args);
+  // The preview takes the same accounts and parameters, only the instruction tag differs
+  const params = deserializeUnchecked(
+    previewNewOrderInstruction.schema,
+    previewNewOrderInstruction,
+    instruction.data
+  ) as previewNewOrderInstruction;
+  instruction.data = Buffer.from(params.serialize());
+  return instruction;
+};
+
+/**
+ * Places a buy order for an amount of quote token to spend, the base quantity is derived by the program from the limit price
+ * @param market The market on which the order is placed
+ * @param limitPrice The limit price of the order
+ * @param quoteAmount The raw quote amount to spend, taker fees included
+ * @param type The order type
+ * @param selfTradeBehaviour The self trade behavior of the order
+ * @param ownerTokenAccount The quote token account funding the order
+ * @param owner The owner of the user account
+ * @param clientOrderId Optional client order ID
+ * @param discountTokenAccount Optional SRM token account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const placeBuyOrderByQuote = async (
+  market: Market,
+  limitPrice: number,
+  quoteAmount: BN,
+  type: OrderType,
+  selfTradeBehaviour: SelfTradeBehavior,
+  ownerTokenAccount: PublicKey,
+  owner: PublicKey,
+  clientOrderId?: BN,
+  discountTokenAccount?: PublicKey,
+  accountIndex = 0
+) => {
+  return await placeOrder(
+    market,
+    Side.Bid,
+    limitPrice,
+    0,
+    type,
+    selfTradeBehaviour,
+    ownerTokenAccount,
+    owner,
+    clientOrderId,
+    discountTokenAccount,
+    new BN(0),
+    quoteAmount,
+    accountIndex
+  );
+};
+
+/**
+ * Places a sell order for an amount of quote token to receive, the base quantity is derived by the program from the limit price
+ * @param market The market on which the order is placed
+ * @param limitPrice The limit price of the order
+ * @param quoteAmount The raw quote amount to sell for, taker fees excluded
+ * @param type The order type
+ * @param selfTradeBehaviour The self trade behavior of the order
+ * @param ownerTokenAccount The base token account funding the order
+ * @param owner The owner of the user account
+ * @param clientOrderId Optional client order ID
+ * @param discountTokenAccount Optional SRM token account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const placeSellOrderByQuote = async (
+  market: Market,
+  limitPrice: number,
+  quoteAmount: BN,
+  type: OrderType,
+  selfTradeBehaviour: SelfTradeBehavior,
+  ownerTokenAccount: PublicKey,
+  owner: PublicKey,
+  clientOrderId?: BN,
+  discountTokenAccount?: PublicKey,
+  accountIndex = 0
+) => {
+  return await placeOrder(
+    market,
+    Side.Ask,
+    limitPrice,
+    0,
+    type,
+    selfTradeBehaviour,
+    ownerTokenAccount,
+    owner,
+    clientOrderId,
+    discountTokenAccount,
+    new BN(0),
+    quoteAmount,
+    accountIndex,
+    false,
+    undefined,
+    false,
+    undefined,
+    undefined,
+    undefined,
+    QuantityMode.Quote
+  );
+};
+
+/**
+ * Places a market order, which matches against the book without posting and never trades beyond the worst acceptable price
+ * @param market The market on which the order is placed
+ * @param side The side of the order
+ * @param worstPrice The worst acceptable price of the order, which doesn't need to be a multiple of the tick size
+ * @param size The raw base quantity to trade, 0 for a buy order only bounded by `maxQuoteQty`
+ * @param ownerTokenAccount The token account funding the order
+ * @param owner The owner of the user account
+ * @param maxQuoteQty Optional raw quote amount to spend, taker fees included
+ * @param minBaseQty The minimum base quantity to match, below which the transaction fails
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const placeMarketOrder = async (
+  market: Market,
+  side: Side,
+  worstPrice: number,
+  size: number,
+  ownerTokenAccount: PublicKey,
+  owner: PublicKey,
+  maxQuoteQty?: BN,
+  minBaseQty = new BN(0),
+  accountIndex = 0
+) => {
+  return await placeOrder(
+    market,
+    side,
+    worstPrice,
+    size,
+    OrderType.Market,
+    SelfTradeBehavior.DecrementTake,
+    ownerTokenAccount,
+    owner,
+    undefined,
+    undefined,
+    new BN(size),
+    maxQuoteQty,
+    accountIndex,
+    false,
+    undefined,
+    false,
+    undefined,
+    undefined,
+    minBaseQty
+  );
+};
+
 /**
  *
  * @param market  Market object on which the order is canceled
@@ -238,6 +526,7 @@ export const placeOrder = async (
  * @param orderId The order ID
  * @param orderIndex The index of the order in the user account orders list
  * @param clientOrderId Optional client order ID
+ * @param accountIndex The index of the user account (0 for the main user account)
  * @returns
  */
 export const cancelOrder = async (
@@ -245,10 +534,13 @@ export const cancelOrder = async (
   owner: PublicKey,
   orderId: BN,
   orderIndex?: BN,
-  clientOrderId?: BN
+  clientOrderId?: BN,
+  accountIndex = 0
 ) => {
-  const [userAccount] = await PublicKey.findProgramAddress(
-    [market.address.toBuffer(), owner.toBuffer()],
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
     market.programId
   );
 
@@ -270,12 +562,133 @@ export const cancelOrder = async (
   return instruction;
 };
 
+/**
+ * Cancels an order and places a new one in a single instruction, so that the cancelled order cannot be filled in
+ * between. The instruction fails if the order to replace no longer rests on the book.
+ * @param market Market object on which the order is replaced
+ * @param owner The owner of the orders
+ * @param ownerTokenAccount The token account from which the new order is funded
+ * @param orderId The ID of the order to replace
+ * @param orderIndex The index of the order to replace in the user account orders list
+ * @param side The side of the new order
+ * @param limitPrice The limit price of the new order (UI limit price not FP32)
+ * @param size The size of the new order (raw amount i.e with decimals)
+ * @param type The type of the new order
+ * @param selfTradeBehaviour The self trade behavior of the new order
+ * @param clientOrderId Optional client order ID of the order to replace, which is then found from it
+ * @param newClientOrderId Optional client order ID of the new order
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const replaceOrder = async (
+  market: Market,
+  owner: PublicKey,
+  ownerTokenAccount: PublicKey,
+  orderId: BN,
+  orderIndex: BN | undefined,
+  side: Side,
+  limitPrice: number,
+  size: number,
+  type: OrderType,
+  selfTradeBehaviour: SelfTradeBehavior,
+  clientOrderId?: BN,
+  newClientOrderId?: BN,
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+
+  const instruction = new replaceOrderInstruction({
+    orderId: clientOrderId ? clientOrderId : orderId,
+    orderIndex: orderIndex ? orderIndex : new BN(0),
+    isClientId: clientOrderId ? 1 : 0,
+    side: side as number,
+    limitPrice: computeFp32Price(market, limitPrice),
+    maxBaseQty: new BN(size),
+    maxQuoteQty: U64_MAX,
+    orderType: type,
+    selfTradeBehavior: selfTradeBehaviour,
+    matchLimit: new BN(Number.MAX_SAFE_INTEGER),
+    expiryTimestamp: new BN(0),
+    displayBaseQty: new BN(0),
+    minBaseQty: new BN(0),
+    clientOrderId: newClientOrderId || new BN(crypto.randomBytes(16)),
+    hasDiscountTokenAccount: 0,
+    hasOracleAccount: Number(market.oracle !== undefined),
+    hasSession: 0,
+    hasDmmRegistry: 0,
+    notifyOnCross: 0,
+    quantityMode: QuantityMode.Base,
+    useFreeBalances: 0
+  }).getInstruction(
+    market.programId,
+    TOKEN_PROGRAM_ID,
+    SystemProgram.programId,
+    market.address,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    market.baseVault,
+    market.quoteVault,
+    userAccount,
+    ownerTokenAccount,
+    owner,
+    undefined,
+    market.oracle
+  );
+
+  return instruction;
+};
+
+/**
+ * Cancels the open orders of a user account which rest on the book, from the most recent one
+ * @param market Market object on which the orders are cancelled
+ * @param owner The owner of the user account
+ * @param maxOrders The maximum number of orders to cancel, which bounds the compute used by the instruction
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const cancelAllOrders = async (
+  market: Market,
+  owner: PublicKey,
+  maxOrders: BN,
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+
+  const instruction = new cancelAllOrdersInstruction({
+    maxOrders
+  }).getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    userAccount,
+    owner
+  );
+
+  return instruction;
+};
+
 /**
  *
  * @param market Market object on which the user account is created
  * @param owner The owner of the user account
  * @param maxOrders The max capacity of orders
  * @param feePayer The fee payer of the transaction
+ * @param accountIndex The index of the user account (0 for the main user account)
  * @returns
  */
 export const initializeAccount = async (
@@ -283,16 +696,20 @@ export const initializeAccount = async (
   owner: PublicKey,
   maxOrders = 20,
   feePayer?: PublicKey,
-  programId = DEX_ID
+  programId = DEX_ID,
+  accountIndex = 0
 ) => {
-  const [userAccount] = await PublicKey.findProgramAddress(
-    [market.toBuffer(), owner.toBuffer()],
+  const userAccount = await getUserAccountAddress(
+    market,
+    owner,
+    accountIndex,
     programId
   );
 
   const instruction = new initializeAccountInstruction({
     market: market.toBuffer(),
-    maxOrders: new BN(maxOrders)
+    maxOrders: new BN(maxOrders),
+    accountIndex: new BN(accountIndex)
   }).getInstruction(
     programId,
     SystemProgram.programId,
@@ -304,31 +721,88 @@ export const initializeAccount = async (
   return instruction;
 };
 
+/**
+ * Computes the size of a user account, which is allocated by the program at initialization
+ * @param maxOrders The max capacity of orders
+ * @returns The size of the user account in bytes
+ */
+export const getUserAccountSpace = (maxOrders: number) => {
+  if (!Number.isInteger(maxOrders) || maxOrders < 1) {
+    throw new Error("A user account should be able to hold at least one order");
+  }
+  return USER_ACCOUNT_HEADER_LEN + maxOrders * ORDER_LEN;
+};
+
+/**
+ * Computes the rent exemption of a user account, which the fee payer is debited when the account is initialized
+ * @param connection The solana connection object to the RPC node
+ * @param maxOrders The max capacity of orders
+ * @returns The rent exemption in lamports
+ */
+export const getUserAccountRent = async (
+  connection: Connection,
+  maxOrders: number
+) => {
+  return await connection.getMinimumBalanceForRentExemption(
+    getUserAccountSpace(maxOrders)
+  );
+};
+
+/**
+ * Builds the instructions creating the main user account of an owner, sized to hold maxOrders orders
+ * @param owner The owner of the user account, which should sign
+ * @param market The market on which the user account is created
+ * @param maxOrders The max capacity of orders
+ * @param payer The fee payer debited the rent exemption, which should sign
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const createUserAccountIxs = async (
+  owner: PublicKey,
+  market: PublicKey,
+  maxOrders: number,
+  payer: PublicKey,
+  programId = DEX_ID
+) => {
+  getUserAccountSpace(maxOrders);
+  return [await initializeAccount(market, owner, maxOrders, payer, programId)];
+};
+
 /**
  *
  * @param market Market object on which funds are settled
  * @param owner The user settling their funds
  * @param destinationBaseAccount The user base token account
  * @param destinationQuoteAccount The user quote token account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @param withSettleAllowlist Whether the user account has registered a settle allowlist, which is then passed
+ * @param unwrapSol Whether the destination account holding wrapped SOL should be closed once settled, so that the owner receives native SOL
  * @returns
  */
 export const settle = async (
   market: Market,
   owner: PublicKey,
   destinationBaseAccount: PublicKey,
-  destinationQuoteAccount: PublicKey
+  destinationQuoteAccount: PublicKey,
+  accountIndex = 0,
+  withSettleAllowlist = false,
+  unwrapSol = false
 ) => {
   const [marketSigner] = await PublicKey.findProgramAddress(
     [market.address.toBuffer()],
     market.programId
   );
 
-  const [userAccount] = await PublicKey.findProgramAddress(
-    [market.address.toBuffer(), owner.toBuffer()],
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
     market.programId
   );
 
-  const instruction = new settleInstruction().getInstruction(
+  const instruction = new settleInstruction({
+    unwrapSol: Number(unwrapSol)
+  }).getInstruction(
     market.programId,
     TOKEN_PROGRAM_ID,
     market.address,
@@ -338,77 +812,297 @@ export const settle = async (
     userAccount,
     owner,
     destinationBaseAccount,
-    destinationQuoteAccount
+    destinationQuoteAccount,
+    withSettleAllowlist
+      ? await getSettleAllowlistAddress(userAccount, market.programId)
+      : undefined
   );
 
   return instruction;
 };
 
 /**
- *
- * @param market Market object on which events are consumed
- * @param rewardTarget The cranker rewards target
- * @param userAccounts The user accounts
- * @param maxIterations
- * @param noOpErr
+ * Deposits tokens into the free balances of a user account, which fund the orders placed with `useFreeBalances`
+ * @param market The market of the user account
+ * @param owner The owner of the user account and of the source token accounts
+ * @param sourceBaseAccount The token account from which the base tokens are debited
+ * @param sourceQuoteAccount The token account from which the quote tokens are debited
+ * @param baseQty The raw amount of base tokens to deposit
+ * @param quoteQty The raw amount of quote tokens to deposit
+ * @param accountIndex The index of the user account (0 for the main user account)
  * @returns
  */
-export const consumeEvents = async (
+export const deposit = async (
   market: Market,
-  rewardTarget: PublicKey,
-  userAccounts: PublicKey[],
-  maxIterations: BN,
-  noOpErr: BN
+  owner: PublicKey,
+  sourceBaseAccount: PublicKey,
+  sourceQuoteAccount: PublicKey,
+  baseQty: BN,
+  quoteQty: BN,
+  accountIndex = 0
 ) => {
-  const instruction = new consumeEventsInstruction({
-    maxIterations,
-    noOpErr
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+
+  const instruction = new depositInstruction({
+    baseQty,
+    quoteQty
   }).getInstruction(
     market.programId,
+    TOKEN_PROGRAM_ID,
     market.address,
-    market.orderbookAddress,
-    market.eventQueueAddress,
-    rewardTarget,
-    userAccounts
-      .map((e) => e.toBuffer())
-      .sort(Buffer.compare)
-      .map((e) => new PublicKey(e))
+    market.baseVault,
+    market.quoteVault,
+    userAccount,
+    owner,
+    sourceBaseAccount,
+    sourceQuoteAccount
   );
 
   return instruction;
 };
 
-export const closeAccount = async (
-  market: PublicKey,
+/**
+ * Withdraws part of the free balances of a user account, unlike `settle` which withdraws all of them
+ * @param market The market of the user account
+ * @param owner The owner of the user account
+ * @param destinationBaseAccount The token account receiving the base tokens
+ * @param destinationQuoteAccount The token account receiving the quote tokens
+ * @param baseQty The raw amount of base tokens to withdraw
+ * @param quoteQty The raw amount of quote tokens to withdraw
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @param withSettleAllowlist Whether the user account has registered a settle allowlist
+ * @returns
+ */
+export const withdraw = async (
+  market: Market,
   owner: PublicKey,
-  programId = DEX_ID
+  destinationBaseAccount: PublicKey,
+  destinationQuoteAccount: PublicKey,
+  baseQty: BN,
+  quoteQty: BN,
+  accountIndex = 0,
+  withSettleAllowlist = false
 ) => {
-  const [userAccount] = await PublicKey.findProgramAddress(
-    [market.toBuffer(), owner.toBuffer()],
-    programId
+  const [marketSigner] = await PublicKey.findProgramAddress(
+    [market.address.toBuffer()],
+    market.programId
   );
 
-  const instruction = new closeAccountInstruction().getInstruction(
-    programId,
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+
+  const instruction = new withdrawInstruction({
+    baseQty,
+    quoteQty
+  }).getInstruction(
+    market.programId,
+    TOKEN_PROGRAM_ID,
+    market.address,
+    market.baseVault,
+    market.quoteVault,
+    marketSigner,
     userAccount,
     owner,
-    owner
+    destinationBaseAccount,
+    destinationQuoteAccount,
+    withSettleAllowlist
+      ? await getSettleAllowlistAddress(userAccount, market.programId)
+      : undefined
   );
 
   return instruction;
 };
 
-export const swap = async (
+/**
+ * Settles the free balances of a user account into the associated token accounts of its owner, creating those which
+ * don't exist at the expense of the owner
+ * @param market Market object on which funds are settled
+ * @param owner The user settling their funds
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @param withSettleAllowlist Whether the user account has registered a settle allowlist, which is then passed
+ * @param unwrapSol Whether the associated wrapped SOL account should be closed once settled, so that the owner receives native SOL
+ * @returns
+ */
+export const settleToAssociatedAccounts = async (
   market: Market,
-  side: Side,
-  minOutputQuantity: number,
-  inputQuantity: number,
-  selfTradeBehaviour: SelfTradeBehavior,
-  ownerBaseTokenAccount: PublicKey,
-  ownerQuoteTokenAccount: PublicKey,
   owner: PublicKey,
-  discountTokenAccount?: PublicKey,
-  referralFeeAccount?: PublicKey
+  accountIndex = 0,
+  withSettleAllowlist = false,
+  unwrapSol = false
+) => {
+  const [marketSigner] = await PublicKey.findProgramAddress(
+    [market.address.toBuffer()],
+    market.programId
+  );
+
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+
+  const instruction = new settleToAssociatedAccountsInstruction({
+    unwrapSol: Number(unwrapSol)
+  }).getInstruction(
+    market.programId,
+    TOKEN_PROGRAM_ID,
+    SystemProgram.programId,
+    ASSOCIATED_TOKEN_PROGRAM_ID,
+    SYSVAR_RENT_PUBKEY,
+    market.address,
+    market.baseMintAddress,
+    market.quoteMintAddress,
+    market.baseVault,
+    market.quoteVault,
+    marketSigner,
+    userAccount,
+    owner,
+    await getAssociatedTokenAddress(market.baseMintAddress, owner),
+    await getAssociatedTokenAddress(market.quoteMintAddress, owner),
+    withSettleAllowlist
+      ? await getSettleAllowlistAddress(userAccount, market.programId)
+      : undefined
+  );
+
+  return instruction;
+};
+
+/**
+ *
+ * @param market Market object on which events are consumed
+ * @param rewardTarget The cranker rewards target
+ * @param userAccounts The user accounts
+ * @param maxIterations
+ * @param noOpErr
+ * @param makerRewards The optional maker rewards account of the market, the top of book makers are sampled whenever it is provided
+ * @param cranker The optional operator wallet, when provided the crank is recorded in its cranker identity and it should sign
+ * @param expiredSessions The optional session accounts of the user accounts, the orders of those which are expired are cancelled
+ * @param repostIcebergOrders Whether the next tranches of the fully filled iceberg orders should be posted
+ * @returns
+ */
+export const consumeEvents = async (
+  market: Market,
+  rewardTarget: PublicKey,
+  userAccounts: PublicKey[],
+  maxIterations: BN,
+  noOpErr: BN,
+  makerRewards?: PublicKey,
+  cranker?: PublicKey,
+  expiredSessions: PublicKey[] = [],
+  repostIcebergOrders = true
+) => {
+  const crankerIdentity = cranker
+    ? await getCrankerIdentityAddress(market.address, cranker, market.programId)
+    : undefined;
+  const pruneExpiredSessions = expiredSessions.length > 0;
+  const instruction = new consumeEventsInstruction({
+    maxIterations,
+    noOpErr,
+    recordCrankerIdentity: new BN(cranker ? 1 : 0),
+    pruneExpiredSessions: new BN(pruneExpiredSessions ? 1 : 0),
+    repostIcebergOrders: new BN(repostIcebergOrders ? 1 : 0)
+  }).getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    rewardTarget,
+    // The session accounts are passed along the user accounts, which should be sorted
+    userAccounts
+      .concat(expiredSessions)
+      .map((e) => e.toBuffer())
+      .sort(Buffer.compare)
+      .map((e) => new PublicKey(e)),
+    makerRewards || pruneExpiredSessions || repostIcebergOrders
+      ? market.bidsAddress
+      : undefined,
+    makerRewards || pruneExpiredSessions || repostIcebergOrders
+      ? market.asksAddress
+      : undefined,
+    makerRewards,
+    crankerIdentity,
+    cranker
+  );
+
+  return instruction;
+};
+
+export const closeAccount = async (
+  market: PublicKey,
+  owner: PublicKey,
+  programId = DEX_ID,
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market,
+    owner,
+    accountIndex,
+    programId
+  );
+
+  const instruction = new closeAccountInstruction().getInstruction(
+    programId,
+    userAccount,
+    owner,
+    owner
+  );
+
+  return instruction;
+};
+
+/**
+ *
+ * @param market The market of the user account
+ * @param owner The owner of the user account
+ * @param metadata The 32 bytes of opaque metadata to store in the user account
+ * @returns
+ */
+export const setAccountMetadata = async (
+  market: PublicKey,
+  owner: PublicKey,
+  metadata: Uint8Array,
+  programId = DEX_ID,
+  accountIndex = 0
+) => {
+  if (metadata.length !== 32) {
+    throw new Error("The user account metadata must be 32 bytes long");
+  }
+  const userAccount = await getUserAccountAddress(
+    market,
+    owner,
+    accountIndex,
+    programId
+  );
+
+  const instruction = new setAccountMetadataInstruction({
+    metadata
+  }).getInstruction(programId, userAccount, owner);
+
+  return instruction;
+};
+
+export const swap = async (
+  market: Market,
+  side: Side,
+  minOutputQuantity: number,
+  inputQuantity: number,
+  selfTradeBehaviour: SelfTradeBehavior,
+  ownerBaseTokenAccount: PublicKey,
+  ownerQuoteTokenAccount: PublicKey,
+  owner: PublicKey,
+  discountTokenAccount?: PublicKey,
+  referralFeeAccount?: PublicKey,
+  matchLimit = new BN(Number.MAX_SAFE_INTEGER)
 ) => {
   // Market signer
   const [marketSigner] = await PublicKey.findProgramAddress(
@@ -427,8 +1121,9 @@ export const swap = async (
       side === Side.Bid ? new BN(minOutputQuantity) : new BN(inputQuantity),
     quoteQty:
       side === Side.Bid ? new BN(inputQuantity) : new BN(minOutputQuantity),
-    matchLimit: new BN(Number.MAX_SAFE_INTEGER), // TODO Change
-    hasDiscountTokenAccount: Number(discountTokenAccount !== undefined)
+    matchLimit,
+    hasDiscountTokenAccount: Number(discountTokenAccount !== undefined),
+    hasOracleAccount: Number(market.oracle !== undefined)
   }).getInstruction(
     market.programId,
     TOKEN_PROGRAM_ID,
@@ -445,6 +1140,7 @@ export const swap = async (
     ownerQuoteTokenAccount,
     owner,
     discountTokenAccount,
+    market.oracle,
     referralFeeAccount
   );
 
@@ -532,3 +1228,1314 @@ export const sweepFees = async (
 
   return [...tokenIxs, ix];
 };
+
+/**
+ * Migrates a market or user account created by the first release of the program to the current layout
+ * @param account The market or user account to migrate
+ * @param payer The account paying for the rent of the added space
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const migrateAccount = (
+  account: PublicKey,
+  payer: PublicKey,
+  programId = DEX_ID
+) => {
+  const ix = new migrateAccountInstruction().getInstruction(
+    programId,
+    SystemProgram.programId,
+    account,
+    payer
+  );
+
+  return ix;
+};
+
+/**
+ * Derives the maker rewards account of a market
+ * @param market The market address
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getMakerRewardsAddress = async (
+  market: PublicKey,
+  programId = DEX_ID
+) => {
+  const [makerRewards] = await PublicKey.findProgramAddress(
+    [market.toBuffer(), Buffer.from("maker_rewards")],
+    programId
+  );
+  return makerRewards;
+};
+
+/**
+ *
+ * @param market Market object for which the maker rewards account is created
+ * @param epochLength The length in slots of a sampling epoch
+ * @param maxEntries The maximum number of makers tracked during an epoch
+ * @param feePayer The fee payer of the transaction
+ * @returns
+ */
+export const createMakerRewards = async (
+  market: Market,
+  epochLength: BN,
+  maxEntries: BN,
+  feePayer: PublicKey
+) => {
+  const makerRewards = await getMakerRewardsAddress(
+    market.address,
+    market.programId
+  );
+
+  const ix = new createMakerRewardsInstruction({
+    epochLength,
+    maxEntries
+  }).getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    market.address,
+    makerRewards,
+    market.marketAdmin,
+    feePayer
+  );
+
+  return ix;
+};
+
+/**
+ * Logs a hash of the market's bids and asks at the current slot
+ * @param market The market whose orderbook is hashed
+ * @returns
+ */
+export const hashOrderbook = async (market: Market) => {
+  const ix = new hashOrderbookInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress,
+    market.bidsAddress,
+    market.asksAddress
+  );
+
+  return ix;
+};
+
+/**
+ * Updates the trading status of a market (admin only)
+ * @param market The market to update
+ * @param status The new market status
+ * @returns
+ */
+export const setMarketStatus = async (market: Market, status: MarketStatus) => {
+  const ix = new setMarketStatusInstruction({
+    status: new BN(status)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Halts new orders on one or both sides of a market (admin only), cancels and settles are still allowed
+ * @param market The market to update
+ * @param haltBids Whether new bids are rejected
+ * @param haltAsks Whether new asks are rejected
+ * @returns
+ */
+export const setHaltFlags = async (
+  market: Market,
+  haltBids: boolean,
+  haltAsks: boolean
+) => {
+  const haltFlags =
+    (haltBids ? HALT_BIDS_MASK : 0) | (haltAsks ? HALT_ASKS_MASK : 0);
+  const ix = new setHaltFlagsInstruction({
+    haltFlags: new BN(haltFlags)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Updates the tick size and the minimum base order size of a market (admin only)
+ * @param market The market to update
+ * @param tickSize The new tick size (FP32), which should divide the current one while orders rest on the book
+ * @param minBaseOrderSize The new minimum base order size, only enforced on new orders
+ * @returns
+ */
+export const updateMarketParams = async (
+  market: Market,
+  tickSize: BN,
+  minBaseOrderSize: BN
+) => {
+  const ix = new updateMarketParamsInstruction({
+    tickSize,
+    minBaseOrderSize
+  }).getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    market.marketAdmin
+  );
+
+  return ix;
+};
+
+/**
+ * Prunes the expired good-til-date orders of user accounts from the book
+ * @param market The market of the orders
+ * @param userAccounts The user accounts whose expired orders should be pruned
+ * @param rewardTarget The account receiving the pruning reward of each pruned order
+ * @param maxOrders The maximum number of orders to prune, which bounds the compute used by the instruction
+ * @param noOpErr Whether the instruction should fail when no order was pruned
+ * @returns
+ */
+export const pruneExpiredOrders = async (
+  market: Market,
+  userAccounts: PublicKey[],
+  rewardTarget: PublicKey,
+  maxOrders = new BN(10),
+  noOpErr = false
+) => {
+  const ix = new pruneExpiredOrdersInstruction({
+    maxOrders,
+    noOpErr: new BN(Number(noOpErr))
+  }).getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    rewardTarget,
+    userAccounts
+  );
+
+  return ix;
+};
+
+/**
+ * Updates the symbols and display name of a market (admin only)
+ * @param market The market to update
+ * @param baseSymbol The symbol of the base token (at most 16 bytes)
+ * @param quoteSymbol The symbol of the quote token (at most 16 bytes)
+ * @param marketName The display name of the market (at most 32 bytes)
+ * @returns
+ */
+export const updateMarketMetadata = async (
+  market: Market,
+  baseSymbol: string,
+  quoteSymbol: string,
+  marketName: string
+) => {
+  const ix = new updateMarketMetadataInstruction({
+    baseSymbol: encodeFixedString(baseSymbol, 16),
+    quoteSymbol: encodeFixedString(quoteSymbol, 16),
+    marketName: encodeFixedString(marketName, 32)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Configures the Pyth price account of a market (admin only)
+ * @param market The market to update
+ * @param oracle The Pyth price account, or undefined to remove the market's oracle
+ * @returns
+ */
+export const setOracle = async (market: Market, oracle?: PublicKey) => {
+  const ix = new setOracleInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.marketAdmin,
+    oracle
+  );
+
+  return ix;
+};
+
+/**
+ * Chooses whether the fill logs of a market disclose the taker's account (admin only)
+ * @param market The market to update
+ * @param hideFillAccounts True to omit the taker's account from fill logs
+ * @returns
+ */
+export const setFillPrivacy = async (
+  market: Market,
+  hideFillAccounts: boolean
+) => {
+  const ix = new setFillPrivacyInstruction({
+    hideFillAccounts: new BN(Number(hideFillAccounts))
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Derives the escrow account of a market
+ * @param market The market address
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getEscrowAddress = async (
+  market: PublicKey,
+  programId = DEX_ID
+) => {
+  const [escrow] = await PublicKey.findProgramAddress(
+    [market.toBuffer(), Buffer.from("escrow")],
+    programId
+  );
+  return escrow;
+};
+
+/**
+ * Consumes the events of a halted market, crediting missing user accounts to the market escrow (admin only)
+ * @param market The halted market
+ * @param userAccounts The available user accounts
+ * @param maxIterations The maximum number of events to consume
+ * @param escrowCapacity The number of user accounts the escrow can hold if it needs to be created
+ * @returns
+ */
+export const forceConsumeEvents = async (
+  market: Market,
+  userAccounts: PublicKey[],
+  maxIterations: BN,
+  escrowCapacity = new BN(100)
+) => {
+  const escrow = await getEscrowAddress(market.address, market.programId);
+
+  const ix = new forceConsumeEventsInstruction({
+    maxIterations,
+    escrowCapacity
+  }).getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    market.address,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.marketAdmin,
+    escrow,
+    userAccounts
+      .map((e) => e.toBuffer())
+      .sort(Buffer.compare)
+      .map((e) => new PublicKey(e))
+  );
+
+  return ix;
+};
+
+/**
+ * Claims the balances held in the market escrow for a user account
+ * @param market The market of the user account
+ * @param owner The owner of the user account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const claimEscrow = async (
+  market: Market,
+  owner: PublicKey,
+  accountIndex = 0
+) => {
+  const escrow = await getEscrowAddress(market.address, market.programId);
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+
+  const ix = new claimEscrowInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    escrow,
+    userAccount,
+    owner
+  );
+
+  return ix;
+};
+
+/**
+ * Derives the batch auction account of a market
+ * @param market The market address
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getBatchAuctionAddress = async (
+  market: PublicKey,
+  programId = DEX_ID
+) => {
+  const [batchAuction] = await PublicKey.findProgramAddress(
+    [market.toBuffer(), Buffer.from("batch_auction")],
+    programId
+  );
+  return batchAuction;
+};
+
+/**
+ * Switches a market between continuous matching and frequent batch auctions (admin only)
+ * @param market The market to update
+ * @param batchSlots The length of a batch auction in slots, or 0 to go back to continuous matching
+ * @param capacity The maximum number of orders in a batch if the batch auction account needs to be created
+ * @returns
+ */
+export const setBatchAuction = async (
+  market: Market,
+  batchSlots: BN,
+  capacity = new BN(100)
+) => {
+  const batchAuction = await getBatchAuctionAddress(
+    market.address,
+    market.programId
+  );
+
+  const ix = new setBatchAuctionInstruction({
+    batchSlots,
+    capacity
+  }).getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    market.address,
+    batchAuction,
+    market.marketAdmin
+  );
+
+  return ix;
+};
+
+/**
+ * Adds an order to the pending batch of a market in batch auction mode
+ * @param market The market on which the order is placed
+ * @param side The side of the order
+ * @param limitPrice The limit price of the order
+ * @param size The base size of the order
+ * @param ownerTokenAccount The token account funding the order
+ * @param owner The owner of the user account
+ * @param discountTokenAccount Optional SRM token account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const placeBatchOrder = async (
+  market: Market,
+  side: Side,
+  limitPrice: number,
+  size: number,
+  ownerTokenAccount: PublicKey,
+  owner: PublicKey,
+  discountTokenAccount?: PublicKey,
+  accountIndex = 0
+) => {
+  const batchAuction = await getBatchAuctionAddress(
+    market.address,
+    market.programId
+  );
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+
+  const ix = new newBatchOrderInstruction({
+    limitPrice: computeFp32Price(market, limitPrice),
+    baseQty: new BN(size),
+    side: side as number,
+    hasDiscountTokenAccount: Number(discountTokenAccount !== undefined)
+  }).getInstruction(
+    market.programId,
+    TOKEN_PROGRAM_ID,
+    market.address,
+    batchAuction,
+    market.baseVault,
+    market.quoteVault,
+    userAccount,
+    ownerTokenAccount,
+    owner,
+    discountTokenAccount
+  );
+
+  return ix;
+};
+
+/**
+ * Crosses the pending batch of a market once its batch period has elapsed
+ * @param market The market in batch auction mode
+ * @param userAccounts The user accounts of the pending orders
+ * @returns
+ */
+export const matchBatch = async (market: Market, userAccounts: PublicKey[]) => {
+  const batchAuction = await getBatchAuctionAddress(
+    market.address,
+    market.programId
+  );
+
+  const ix = new matchBatchInstruction().getInstruction(
+    market.programId,
+    market.address,
+    batchAuction,
+    userAccounts
+      .map((e) => e.toBuffer())
+      .sort(Buffer.compare)
+      .map((e) => new PublicKey(e))
+  );
+
+  return ix;
+};
+
+/**
+ * Derives the session account of a user account
+ * @param userAccount The user account address
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getSessionAddress = async (
+  userAccount: PublicKey,
+  programId = DEX_ID
+) => {
+  const [session] = await PublicKey.findProgramAddress(
+    [userAccount.toBuffer(), Buffer.from("session")],
+    programId
+  );
+  return session;
+};
+
+/**
+ * Authorizes an ephemeral key to place orders on behalf of a user account, or renews the current session
+ * @param market The market of the user account
+ * @param owner The owner of the user account
+ * @param sessionKey The ephemeral key to authorize
+ * @param expiry The unix timestamp after which the session orders can be cancelled by anyone
+ * @param capacity The maximum number of open session orders if the session account needs to be created
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const createSession = async (
+  market: Market,
+  owner: PublicKey,
+  sessionKey: PublicKey,
+  expiry: number,
+  capacity = new BN(20),
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+  const session = await getSessionAddress(userAccount, market.programId);
+
+  const ix = new createSessionInstruction({
+    sessionKey: sessionKey.toBytes(),
+    expiry: new BN(expiry),
+    capacity
+  }).getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    userAccount,
+    owner,
+    session
+  );
+
+  return ix;
+};
+
+/**
+ * Places an order signed by a session key. Session orders are funded by the free balances of the user account.
+ * @param market The market on which the order is placed
+ * @param side The side of the order
+ * @param limitPrice The limit price of the order
+ * @param size The base size of the order
+ * @param type The order type
+ * @param selfTradeBehaviour The behavior when the order matches an order of the same user account
+ * @param owner The owner of the user account
+ * @param sessionKey The session key signing the order
+ * @param ownerTokenAccount A token account of the owner, which is not debited
+ * @param clientOrderId Optional client order ID
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const placeSessionOrder = async (
+  market: Market,
+  side: Side,
+  limitPrice: number,
+  size: number,
+  type: OrderType,
+  selfTradeBehaviour: SelfTradeBehavior,
+  owner: PublicKey,
+  sessionKey: PublicKey,
+  ownerTokenAccount: PublicKey,
+  clientOrderId?: BN,
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+  const session = await getSessionAddress(userAccount, market.programId);
+
+  const ix = new newOrderInstruction({
+    side: side as number,
+    limitPrice: computeFp32Price(market, limitPrice),
+    maxBaseQty: new BN(size),
+    maxQuoteQty: U64_MAX,
+    orderType: type,
+    selfTradeBehavior: selfTradeBehaviour,
+    matchLimit: new BN(Number.MAX_SAFE_INTEGER),
+    expiryTimestamp: new BN(0),
+    displayBaseQty: new BN(0),
+    minBaseQty: new BN(0),
+    clientOrderId: clientOrderId || new BN(crypto.randomBytes(16)),
+    hasDiscountTokenAccount: 0,
+    hasOracleAccount: Number(market.oracle !== undefined),
+    hasSession: 1,
+    hasDmmRegistry: 0,
+    notifyOnCross: 0,
+    quantityMode: QuantityMode.Base,
+    useFreeBalances: 0
+  }).getInstruction(
+    market.programId,
+    TOKEN_PROGRAM_ID,
+    SystemProgram.programId,
+    market.address,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    market.baseVault,
+    market.quoteVault,
+    userAccount,
+    ownerTokenAccount,
+    sessionKey,
+    undefined,
+    market.oracle,
+    session
+  );
+
+  return ix;
+};
+
+/**
+ * Cancels the open orders placed under an expired session
+ * @param market The market of the user account
+ * @param userAccount The user account of the session
+ * @returns
+ */
+export const cancelSessionOrders = async (
+  market: Market,
+  userAccount: PublicKey
+) => {
+  const session = await getSessionAddress(userAccount, market.programId);
+
+  const ix = new cancelSessionOrdersInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    userAccount,
+    session
+  );
+
+  return ix;
+};
+
+/**
+ * Derives the designated market maker registry of a market
+ * @param market The market address
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getDmmRegistryAddress = async (
+  market: PublicKey,
+  programId = DEX_ID
+) => {
+  const [dmmRegistry] = await PublicKey.findProgramAddress(
+    [market.toBuffer(), Buffer.from("dmm_registry")],
+    programId
+  );
+  return dmmRegistry;
+};
+
+/**
+ * Adds or removes a user account from the designated market makers of a market (admin only)
+ * @param market The market to update
+ * @param userAccount The user account of the market maker
+ * @param isDesignated True to add the user account to the registry, false to remove it
+ * @param capacity The maximum number of designated market makers if the registry needs to be created
+ * @returns
+ */
+export const setDesignatedMarketMaker = async (
+  market: Market,
+  userAccount: PublicKey,
+  isDesignated: boolean,
+  capacity = new BN(20)
+) => {
+  const dmmRegistry = await getDmmRegistryAddress(
+    market.address,
+    market.programId
+  );
+
+  const ix = new setDesignatedMarketMakerInstruction({
+    userAccount: userAccount.toBytes(),
+    capacity,
+    isDesignated: Number(isDesignated)
+  }).getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    market.address,
+    dmmRegistry,
+    market.marketAdmin
+  );
+
+  return ix;
+};
+
+/**
+ * Returns an instruction which writes the key parameters of a market into its return data, mostly useful for simulations and CPI consumers
+ * @param market The market to inspect
+ * @returns
+ */
+export const getMarketInfo = async (market: Market) => {
+  const ix = new getMarketInfoInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.orderbookAddress
+  );
+
+  return ix;
+};
+
+/**
+ * Configures the share of the protocol fees credited to crankers (admin only)
+ * @param market The market to update
+ * @param crankFeeShareBps The share of the fees of consumed fills credited to the cranker's reward target, in basis points
+ * @returns
+ */
+export const setCrankFeeShare = async (
+  market: Market,
+  crankFeeShareBps: number
+) => {
+  const ix = new setCrankFeeShareInstruction({
+    crankFeeShareBps: new BN(crankFeeShareBps)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Configures the share of the taker fees paid to referrers (admin only)
+ * @param market The market to update
+ * @param referralShareBps The share of the taker fee of referred orders paid to the referrer, in basis points
+ * @returns
+ */
+export const setReferralShare = async (
+  market: Market,
+  referralShareBps: number
+) => {
+  const ix = new setReferralShareInstruction({
+    referralShareBps: new BN(referralShareBps)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Configures the rebate paid to makers out of the taker fees (admin only), for the orders placed afterwards
+ * @param market The market to update
+ * @param makerRebateBps The rebate in basis points of the filled quote amount, at most the base taker fee
+ * @returns
+ */
+export const setMakerRebate = async (
+  market: Market,
+  makerRebateBps: number
+) => {
+  const ix = new setMakerRebateInstruction({
+    makerRebateBps: new BN(makerRebateBps)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Sets the emergency authority of a market, which can halt it without delay (admin only)
+ * @param market The market to update
+ * @param emergencyAuthority The new emergency authority, the default public key to remove it
+ * @returns
+ */
+export const setEmergencyAuthority = async (
+  market: Market,
+  emergencyAuthority: PublicKey
+) => {
+  const ix = new setEmergencyAuthorityInstruction({
+    emergencyAuthority: emergencyAuthority.toBuffer()
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Halts a market without delay. Trading can only be resumed by the admin once the emergency halt timelock has elapsed
+ * @param market The market to halt
+ * @param emergencyAuthority The emergency authority of the market
+ * @returns
+ */
+export const emergencyHalt = async (
+  market: Market,
+  emergencyAuthority: PublicKey
+) => {
+  const ix = new emergencyHaltInstruction().getInstruction(
+    market.programId,
+    market.address,
+    emergencyAuthority
+  );
+
+  return ix;
+};
+
+/**
+ * Transfers the admin rights of a market to a new key (admin only). Prefer nominateAdmin when the new admin can sign
+ * @param market The market to update
+ * @param newAdmin The new market admin, which doesn't need to sign
+ * @returns
+ */
+export const updateMarketAdmin = async (
+  market: Market,
+  newAdmin: PublicKey
+) => {
+  const ix = new updateMarketAdminInstruction({
+    newAdmin: newAdmin.toBuffer()
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Nominates a new admin, which takes over the admin rights once it accepts the nomination (admin only)
+ * @param market The market to update
+ * @param pendingAdmin The nominated admin, the default public key to withdraw the nomination
+ * @returns
+ */
+export const nominateAdmin = async (
+  market: Market,
+  pendingAdmin: PublicKey
+) => {
+  const ix = new nominateAdminInstruction({
+    pendingAdmin: pendingAdmin.toBuffer()
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Accepts the nomination as admin of a market
+ * @param market The market to update
+ * @param pendingAdmin The nominated admin, which signs the instruction
+ * @returns
+ */
+export const acceptAdmin = async (market: Market, pendingAdmin: PublicKey) => {
+  const ix = new acceptAdminInstruction().getInstruction(
+    market.programId,
+    market.address,
+    pendingAdmin
+  );
+
+  return ix;
+};
+
+/**
+ * Configures the fee charged when an unfilled order is cancelled shortly after its placement (admin only)
+ * @param market The market to update
+ * @param cancelFee The raw quote amount charged from the free quote balance, 0 to disable the fee
+ * @param cancelFeeSlots The number of slots after placement during which a cancellation is charged
+ * @returns
+ */
+export const setCancelFee = async (
+  market: Market,
+  cancelFee: BN,
+  cancelFeeSlots: number
+) => {
+  const ix = new setCancelFeeInstruction({
+    cancelFee,
+    cancelFeeSlots: new BN(cancelFeeSlots)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Configures the volatility-scaled taker fee of a market (admin only)
+ * @param market The market to update
+ * @param windowSlots The length in slots of the window over which price movement is measured, or 0 to disable the surcharge
+ * @param thresholdBps The price movement in basis points which raises the taker fee multiplier by one
+ * @param maxMultiplier The maximum taker fee multiplier
+ * @returns
+ */
+export const setVolatilityFee = async (
+  market: Market,
+  windowSlots: number,
+  thresholdBps: number,
+  maxMultiplier: number
+) => {
+  const ix = new setVolatilityFeeInstruction({
+    windowSlots: new BN(windowSlots),
+    thresholdBps: new BN(thresholdBps),
+    maxMultiplier: new BN(maxMultiplier)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Configures the scaling of the crank reward with the age of the pending events (admin only)
+ * @param market The market to update
+ * @param ageSlots The age in slots of the pending events which raises the crank reward multiplier by one, or 0 to disable the scaling
+ * @param maxMultiplier The maximum crank reward multiplier
+ * @returns
+ */
+export const setCrankRewardScaling = async (
+  market: Market,
+  ageSlots: number,
+  maxMultiplier: number
+) => {
+  const ix = new setCrankRewardScalingInstruction({
+    ageSlots: new BN(ageSlots),
+    maxMultiplier: new BN(maxMultiplier)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Derives the cranker identity account of an operator on a market
+ * @param market The market address
+ * @param cranker The operator's wallet
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getCrankerIdentityAddress = async (
+  market: PublicKey,
+  cranker: PublicKey,
+  programId = DEX_ID
+) => {
+  const [crankerIdentity] = await PublicKey.findProgramAddress(
+    [market.toBuffer(), cranker.toBuffer(), Buffer.from("cranker_identity")],
+    programId
+  );
+  return crankerIdentity;
+};
+
+/**
+ * Registers a crank operator identity which records the operator's consume_events statistics
+ * @param market The market to crank
+ * @param cranker The operator's wallet, which pays for the identity account
+ * @returns
+ */
+export const createCrankerIdentity = async (
+  market: Market,
+  cranker: PublicKey
+) => {
+  const crankerIdentity = await getCrankerIdentityAddress(
+    market.address,
+    cranker,
+    market.programId
+  );
+
+  const ix = new createCrankerIdentityInstruction().getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    market.address,
+    crankerIdentity,
+    cranker
+  );
+
+  return ix;
+};
+
+/**
+ * Freezes a market for migration to another program (admin only).
+ *
+ * The first call halts the market and starts the migration timelock. Once the timelock has elapsed,
+ * a second call with the same arguments hands the market vaults over to the migration market.
+ * @param market The market to migrate
+ * @param migrationProgram The program the market is migrated to
+ * @param migrationMarket The market of the migration program which takes over the vaults
+ * @returns
+ */
+export const freezeAndExport = async (
+  market: Market,
+  migrationProgram: PublicKey,
+  migrationMarket: PublicKey
+) => {
+  const [marketSigner] = await PublicKey.findProgramAddress(
+    [market.address.toBuffer()],
+    market.programId
+  );
+  const [migrationMarketSigner, migrationSignerNonce] =
+    await PublicKey.findProgramAddress(
+      [migrationMarket.toBuffer()],
+      migrationProgram
+    );
+
+  const ix = new freezeAndExportInstruction({
+    migrationProgram: migrationProgram.toBytes(),
+    migrationMarket: migrationMarket.toBytes(),
+    migrationSignerNonce
+  }).getInstruction(
+    market.programId,
+    TOKEN_PROGRAM_ID,
+    market.address,
+    market.marketAdmin,
+    market.baseVault,
+    market.quoteVault,
+    marketSigner,
+    migrationMarketSigner
+  );
+
+  return ix;
+};
+
+/**
+ * Takes over the vaults of a market exported by a previous deployment (admin only)
+ * @param market The market which imports the vaults
+ * @param sourceMarket The exported market
+ * @returns
+ */
+export const importMarket = async (market: Market, sourceMarket: Market) => {
+  const ix = new importMarketInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.marketAdmin,
+    sourceMarket.address,
+    sourceMarket.baseVault,
+    sourceMarket.quoteVault
+  );
+
+  return ix;
+};
+
+/**
+ * Sets the minimum number of slots an order must rest on the book before it can be cancelled (admin only)
+ * @param market The market to update
+ * @param minRestingSlots The minimum resting time in slots, 0 to disable the rule
+ * @returns
+ */
+export const setMinRestingSlots = async (
+  market: Market,
+  minRestingSlots: number
+) => {
+  const ix = new setMinRestingSlotsInstruction({
+    minRestingSlots: new BN(minRestingSlots)
+  }).getInstruction(market.programId, market.address, market.marketAdmin);
+
+  return ix;
+};
+
+/**
+ * Records that the owner of a user account, or one of its session keys, is still active
+ * @param market The market of the user account
+ * @param owner The owner of the user account
+ * @param sessionKey The session key signing the heartbeat, if the owner doesn't sign it
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const heartbeat = async (
+  market: Market,
+  owner: PublicKey,
+  sessionKey?: PublicKey,
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+  const session = sessionKey
+    ? await getSessionAddress(userAccount, market.programId)
+    : undefined;
+
+  const ix = new heartbeatInstruction().getInstruction(
+    market.programId,
+    userAccount,
+    sessionKey || owner,
+    session
+  );
+
+  return ix;
+};
+
+/**
+ * Replaces the AAOB accounts of a halted market with new ones (admin only). The current orderbook must be empty.
+ * @param connection The solana connection object to the RPC node
+ * @param market The market to relink
+ * @param feePayer The fee payer of the new AAOB accounts
+ * @param targetLamportsAccount The account receiving the lamports of the closed AAOB accounts
+ * @returns
+ */
+export const relinkOrderbook = async (
+  connection: Connection,
+  market: Market,
+  feePayer: PublicKey,
+  targetLamportsAccount: PublicKey
+): Promise<PrimedTransaction[]> => {
+  const [marketSigner] = await PublicKey.findProgramAddress(
+    [market.address.toBuffer()],
+    market.programId
+  );
+
+  const [aaobSigners, aaobInstructions] = await aaob.createMarket(
+    connection,
+    marketSigner,
+    33,
+    new BN(32),
+    EVENT_CAPACITY,
+    ORDER_CAPACITY,
+    market.minOrderSizeBN,
+    feePayer,
+    market.tickSizeBN,
+    market.programId
+  );
+  // The new orderbook is initialized by the relink instruction
+  aaobInstructions.pop();
+
+  const relink = new relinkOrderbookInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.marketAdmin,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    aaobSigners[3].publicKey,
+    aaobSigners[0].publicKey,
+    aaobSigners[1].publicKey,
+    aaobSigners[2].publicKey,
+    targetLamportsAccount
+  );
+
+  return [
+    [aaobSigners, aaobInstructions],
+    [[], [relink]]
+  ];
+};
+
+/**
+ * Closes the AAOB accounts of a migrated market once its vaults can be exported (admin only).
+ * The orderbook must be empty, the market is left without an orderbook.
+ * @param market The migrated market
+ * @param targetLamportsAccount The account receiving the lamports of the closed AAOB accounts
+ * @returns
+ */
+export const reclaimOrderbook = async (
+  market: Market,
+  targetLamportsAccount: PublicKey
+) => {
+  const ix = new reclaimOrderbookInstruction().getInstruction(
+    market.programId,
+    market.address,
+    market.marketAdmin,
+    market.orderbookAddress,
+    market.eventQueueAddress,
+    market.bidsAddress,
+    market.asksAddress,
+    targetLamportsAccount
+  );
+
+  return ix;
+};
+
+/**
+ * Derives the create market config account of a deployment
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getCreateMarketConfigAddress = async (programId = DEX_ID) => {
+  const [createMarketConfig] = await PublicKey.findProgramAddress(
+    [Buffer.from("create_market_config")],
+    programId
+  );
+  return createMarketConfig;
+};
+
+/**
+ * Sets the listing fee charged to create a market (sweep authority only)
+ * @param sweepAuthority The sweep authority of the deployment
+ * @param listingFee The listing fee, 0 to allow free market creation
+ * @param treasury The account receiving the fees, a token account of the fee mint for token fees
+ * @param feeMint The mint of the fee, undefined for a fee in lamports
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const setCreateMarketConfig = async (
+  sweepAuthority: PublicKey,
+  listingFee: BN,
+  treasury: PublicKey,
+  feeMint?: PublicKey,
+  programId = DEX_ID
+) => {
+  const ix = new setCreateMarketConfigInstruction({
+    listingFee,
+    feeMint: (feeMint || PublicKey.default).toBytes(),
+    treasury: treasury.toBytes()
+  }).getInstruction(
+    programId,
+    SystemProgram.programId,
+    await getCreateMarketConfigAddress(programId),
+    sweepAuthority
+  );
+
+  return ix;
+};
+
+/**
+ * Derives the settle allowlist account of a user account
+ * @param userAccount The user account address
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getSettleAllowlistAddress = async (
+  userAccount: PublicKey,
+  programId = DEX_ID
+) => {
+  const [settleAllowlist] = await PublicKey.findProgramAddress(
+    [userAccount.toBuffer(), Buffer.from("settle_allowlist")],
+    programId
+  );
+  return settleAllowlist;
+};
+
+/**
+ * Registers the immutable list of token accounts a user account can settle to
+ * @param market The market of the user account
+ * @param owner The owner of the user account, which pays for the allowlist account
+ * @param destinations The allowed base and quote token accounts
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const createSettleAllowlist = async (
+  market: Market,
+  owner: PublicKey,
+  destinations: PublicKey[],
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+  const settleAllowlist = await getSettleAllowlistAddress(
+    userAccount,
+    market.programId
+  );
+
+  const ix = new createSettleAllowlistInstruction().getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    market.address,
+    userAccount,
+    owner,
+    settleAllowlist,
+    destinations
+  );
+
+  return ix;
+};
+
+/**
+ * Returns the address of the archive of a user account
+ * @param userAccount The archived user account
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getUserAccountArchiveAddress = async (
+  userAccount: PublicKey,
+  programId = DEX_ID
+) => {
+  const [archive] = await PublicKey.findProgramAddress(
+    [userAccount.toBuffer(), Buffer.from("user_account_archive")],
+    programId
+  );
+  return archive;
+};
+
+/**
+ * Archives a user account without open orders nor funds, to reclaim most of its rent
+ * @param market The market of the user account
+ * @param owner The owner of the user account, which pays for the archive and receives the user account's rent
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const archiveUserAccount = async (
+  market: Market,
+  owner: PublicKey,
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+  const archive = await getUserAccountArchiveAddress(
+    userAccount,
+    market.programId
+  );
+
+  const ix = new archiveUserAccountInstruction({
+    accountIndex: new BN(accountIndex)
+  }).getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    userAccount,
+    owner,
+    archive
+  );
+
+  return ix;
+};
+
+/**
+ * Restores an archived user account at its address
+ * @param market The market of the user account
+ * @param owner The owner of the user account, which pays for the user account and receives the archive's rent
+ * @param maxOrders The max capacity of orders of the restored user account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @returns
+ */
+export const restoreUserAccount = async (
+  market: Market,
+  owner: PublicKey,
+  maxOrders = 20,
+  accountIndex = 0
+) => {
+  const userAccount = await getUserAccountAddress(
+    market.address,
+    owner,
+    accountIndex,
+    market.programId
+  );
+  const archive = await getUserAccountArchiveAddress(
+    userAccount,
+    market.programId
+  );
+
+  const ix = new restoreUserAccountInstruction({
+    maxOrders: new BN(maxOrders)
+  }).getInstruction(
+    market.programId,
+    SystemProgram.programId,
+    userAccount,
+    owner,
+    archive
+  );
+
+  return ix;
+};
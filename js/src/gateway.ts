@@ -0,0 +1,490 @@
+import { EventFill, EventQueue } from "@bonfida/aaob";
+import { Connection, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
+import { createServer, IncomingMessage, Server, ServerResponse } from "http";
+import WebSocket from "ws";
+import { Market } from "./market";
+import { Orderbook } from "./orderbook";
+import { CALLBACK_INFO_LEN } from "./state";
+import { throwIfNull } from "./utils";
+
+/**
+ * Market data gateway exposing dex-v4 markets through a CCXT-compatible schema
+ *
+ * REST endpoints:
+ * - `GET /markets`
+ * - `GET /orderbook?symbol=BASE/QUOTE&limit=20`
+ * - `GET /ticker?symbol=BASE/QUOTE`
+ * - `GET /trades?symbol=BASE/QUOTE&limit=100`
+ *
+ * WebSocket channels, on the same port:
+ * - `{ "op": "subscribe", "channel": "orderbook", "symbol": "BASE/QUOTE", "limit": 20 }`
+ *   pushes the orderbook on subscription and on every update of either side
+ * - `{ "op": "subscribe", "channel": "trades", "symbol": "BASE/QUOTE" }`
+ *   pushes the new fills of the event queue as they are appended
+ * - `{ "op": "unsubscribe", "channel": ..., "symbol": ... }`
+ *
+ * Updates are sent as `{ "channel": ..., "symbol": ..., "data": ... }`
+ */
+
+/** Maps a unified symbol (e.g `SOL/USDC`) to a dex-v4 market address */
+export type GatewayMarkets = { [symbol: string]: PublicKey };
+
+export interface GatewayOptions {
+  /** The port the gateway listens on */
+  port?: number;
+  /** The default depth of the returned orderbooks */
+  defaultDepth?: number;
+}
+
+/** CCXT unified market structure */
+export interface CcxtMarket {
+  id: string;
+  symbol: string;
+  base: string;
+  quote: string;
+  baseId: string;
+  quoteId: string;
+  active: boolean;
+  type: "spot";
+  spot: true;
+  precision: { price: number; amount: number };
+  limits: {
+    amount: { min: number; max?: number };
+    price: { min: number; max?: number };
+  };
+  info: any;
+}
+
+/** CCXT unified orderbook structure */
+export interface CcxtOrderbook {
+  symbol: string;
+  bids: [number, number][];
+  asks: [number, number][];
+  timestamp: number;
+  datetime: string;
+  nonce?: number;
+}
+
+/** CCXT unified ticker structure */
+export interface CcxtTicker {
+  symbol: string;
+  timestamp: number;
+  datetime: string;
+  bid?: number;
+  bidVolume?: number;
+  ask?: number;
+  askVolume?: number;
+  last?: number;
+  baseVolume: number;
+  quoteVolume: number;
+  info: any;
+}
+
+/** CCXT unified trade structure */
+export interface CcxtTrade {
+  id: string;
+  symbol: string;
+  timestamp: number;
+  datetime: string;
+  side: "buy" | "sell";
+  price: number;
+  amount: number;
+  cost: number;
+  takerOrMaker: "taker";
+  info: any;
+}
+
+/** A fill event along with its sequence number in the event queue */
+export interface SequencedFill {
+  seqNum: BN;
+  fill: EventFill;
+}
+
+/** The WebSocket channels served by the gateway */
+export type GatewayChannel = "orderbook" | "trades";
+
+/**
+ * Returns the fill events which are still present in an event queue
+ * @param eventQueue The deserialized event queue
+ * @param limit The maximum number of fills to return
+ */
+export const sequencedFills = (
+  eventQueue: EventQueue,
+  limit?: number
+): SequencedFill[] => {
+  const count = eventQueue.header.count.toNumber();
+  // The header sequence number is the one of the next pushed event
+  const first = eventQueue.header.seqNum.subn(count);
+  const fills: SequencedFill[] = [];
+  for (let i = 0; i < count; i++) {
+    if (limit !== undefined && fills.length >= limit) {
+      break;
+    }
+    const event = eventQueue.parseEvent(i);
+    if (event instanceof EventFill) {
+      fills.push({ seqNum: first.addn(i), fill: event });
+    }
+  }
+  return fills;
+};
+
+/**
+ * Converts a fill event into a CCXT trade structure
+ *
+ * The trade id is unique across the market: the sequence number of an event never repeats
+ * and the maker and taker user accounts disambiguate it across event queue resets.
+ * @param symbol The unified symbol of the market
+ * @param market The loaded market
+ * @param sequencedFill The fill and its sequence number
+ * @param timestamp The block time of the fill in milliseconds
+ */
+export const toCcxtTrade = (
+  symbol: string,
+  market: Market,
+  { seqNum, fill }: SequencedFill,
+  timestamp: number
+): CcxtTrade => {
+  const maker = new PublicKey(fill.makerCallbackInfo.slice(0, 32));
+  const taker = new PublicKey(fill.takerCallbackInfo.slice(0, 32));
+  const amount = market.baseSplSizeToNumber(
+    fill.baseSize.mul(market.baseCurrencyMultiplier)
+  );
+  const cost = market.quoteSplSizeToNumber(
+    fill.quoteSize.mul(market.quoteCurrencyMultiplier)
+  );
+  return {
+    id: `${seqNum.toString()}-${maker.toBase58()}-${taker.toBase58()}`,
+    symbol,
+    timestamp,
+    datetime: new Date(timestamp).toISOString(),
+    side: fill.takerSide === 0 ? "buy" : "sell",
+    price: amount === 0 ? 0 : cost / amount,
+    amount,
+    cost,
+    takerOrMaker: "taker",
+    info: fill,
+  };
+};
+
+/**
+ * Converts a dex-v4 market into a CCXT market structure
+ * @param symbol The unified symbol of the market
+ * @param market The loaded market
+ */
+export const toCcxtMarket = (symbol: string, market: Market): CcxtMarket => {
+  const [base, quote] = symbol.split("/");
+  return {
+    id: market.address.toBase58(),
+    symbol,
+    base,
+    quote,
+    baseId: market.baseMintAddress.toBase58(),
+    quoteId: market.quoteMintAddress.toBase58(),
+    active: true,
+    type: "spot",
+    spot: true,
+    precision: { price: market.tickSize, amount: market.minOrderSize },
+    limits: {
+      amount: { min: market.minOrderSize },
+      price: { min: market.tickSize },
+    },
+    info: {
+      baseVault: market.baseVault.toBase58(),
+      quoteVault: market.quoteVault.toBase58(),
+      orderbook: market.orderbookAddress.toBase58(),
+      eventQueue: market.eventQueueAddress.toBase58(),
+    },
+  };
+};
+
+/**
+ * Converts a dex-v4 orderbook into a CCXT orderbook structure
+ * @param symbol The unified symbol of the market
+ * @param orderbook The loaded orderbook
+ * @param depth The number of price levels to return on each side
+ */
+export const toCcxtOrderbook = (
+  symbol: string,
+  orderbook: Orderbook,
+  depth: number
+): CcxtOrderbook => {
+  const timestamp = Date.now();
+  return {
+    symbol,
+    bids: orderbook
+      .getL2(depth, false, true)
+      .map((p) => [p.price, p.size] as [number, number]),
+    asks: orderbook
+      .getL2(depth, true, true)
+      .map((p) => [p.price, p.size] as [number, number]),
+    timestamp,
+    datetime: new Date(timestamp).toISOString(),
+  };
+};
+
+/**
+ * CCXT-compatible market data gateway for a set of dex-v4 markets
+ */
+export class Gateway {
+  private _connection: Connection;
+  private _markets: GatewayMarkets;
+  private _options: GatewayOptions;
+  private _server?: Server;
+  private _wss?: WebSocket.Server;
+
+  constructor(
+    connection: Connection,
+    markets: GatewayMarkets,
+    options: GatewayOptions = {}
+  ) {
+    this._connection = connection;
+    this._markets = markets;
+    this._options = options;
+  }
+
+  private _address(symbol: string | null): PublicKey {
+    const address = symbol ? this._markets[symbol] : undefined;
+    if (!address) {
+      throw new Error(`Unknown symbol ${symbol}`);
+    }
+    return address;
+  }
+
+  /** Returns all configured markets */
+  async fetchMarkets(): Promise<CcxtMarket[]> {
+    return await Promise.all(
+      Object.entries(this._markets).map(async ([symbol, address]) =>
+        toCcxtMarket(symbol, await Market.load(this._connection, address))
+      )
+    );
+  }
+
+  /**
+   * @param symbol The unified symbol of the market
+   * @param limit The number of price levels to return on each side
+   */
+  async fetchOrderBook(symbol: string, limit?: number) {
+    const orderbook = await Orderbook.load(
+      this._connection,
+      this._address(symbol)
+    );
+    return toCcxtOrderbook(
+      symbol,
+      orderbook,
+      limit || this._options.defaultDepth || 20
+    );
+  }
+
+  /**
+   * @param symbol The unified symbol of the market
+   */
+  async fetchTicker(symbol: string): Promise<CcxtTicker> {
+    const orderbook = await Orderbook.load(
+      this._connection,
+      this._address(symbol)
+    );
+    const market = orderbook.market;
+    const [bid] = orderbook.getL2(1, false, true);
+    const [ask] = orderbook.getL2(1, true, true);
+    const [last] = await this.fetchTrades(symbol, 1);
+    const timestamp = Date.now();
+    return {
+      symbol,
+      timestamp,
+      datetime: new Date(timestamp).toISOString(),
+      bid: bid?.price,
+      bidVolume: bid?.size,
+      ask: ask?.price,
+      askVolume: ask?.size,
+      last: last?.price,
+      baseVolume: market.baseSplSizeToNumber(market.marketState.baseVolume),
+      quoteVolume: market.quoteSplSizeToNumber(market.marketState.quoteVolume),
+      info: {},
+    };
+  }
+
+  /**
+   * Returns the block time of a slot in milliseconds
+   * @param slot The slot at which the event queue was read
+   */
+  private async _blockTimestamp(slot: number): Promise<number> {
+    const blockTime = throwIfNull(
+      await this._connection.getBlockTime(slot),
+      `No block time for slot ${slot}`
+    );
+    return blockTime * 1_000;
+  }
+
+  /**
+   * Returns the fills which are still present in the event queue
+   *
+   * The trades are timestamped with the block time of the slot the event queue was read at.
+   * @param symbol The unified symbol of the market
+   * @param limit The maximum number of trades to return
+   */
+  async fetchTrades(symbol: string, limit = 100): Promise<CcxtTrade[]> {
+    const market = await Market.load(this._connection, this._address(symbol));
+    const { context, value } = await this._connection.getAccountInfoAndContext(
+      market.eventQueueAddress
+    );
+    const eventQueue = EventQueue.parse(
+      CALLBACK_INFO_LEN,
+      throwIfNull(value, "Event queue not found").data
+    );
+    const timestamp = await this._blockTimestamp(context.slot);
+    return sequencedFills(eventQueue, limit).map((f) =>
+      toCcxtTrade(symbol, market, f, timestamp)
+    );
+  }
+
+  /**
+   * Subscribes a WebSocket client to a channel
+   * @returns A function removing the subscription
+   */
+  private async _subscribe(
+    socket: WebSocket,
+    channel: GatewayChannel,
+    symbol: string,
+    limit?: number
+  ): Promise<() => void> {
+    const market = await Market.load(this._connection, this._address(symbol));
+    const send = (data: any) => {
+      socket.send(JSON.stringify({ channel, symbol, data }));
+    };
+
+    switch (channel) {
+      case "orderbook": {
+        const push = async () => send(await this.fetchOrderBook(symbol, limit));
+        await push();
+        const ids = [market.bidsAddress, market.asksAddress].map((k) =>
+          this._connection.onAccountChange(k, () => {
+            push().catch(console.error);
+          })
+        );
+        return () =>
+          ids.forEach((id) => this._connection.removeAccountChangeListener(id));
+      }
+      case "trades": {
+        // Only the fills appended after the subscription are pushed
+        const current = await market.loadEventQueue(this._connection);
+        let next = current.header.seqNum;
+        const id = this._connection.onAccountChange(
+          market.eventQueueAddress,
+          (info, context) => {
+            const eventQueue = EventQueue.parse(CALLBACK_INFO_LEN, info.data);
+            const fills = sequencedFills(eventQueue).filter((f) =>
+              f.seqNum.gte(next)
+            );
+            next = BN.max(next, eventQueue.header.seqNum);
+            if (fills.length === 0) {
+              return;
+            }
+            this._blockTimestamp(context.slot)
+              .then((timestamp) =>
+                send(
+                  fills.map((f) => toCcxtTrade(symbol, market, f, timestamp))
+                )
+              )
+              .catch(console.error);
+          }
+        );
+        return () => this._connection.removeAccountChangeListener(id);
+      }
+      default:
+        throw new Error(`Unknown channel ${channel}`);
+    }
+  }
+
+  private _handleSocket(socket: WebSocket) {
+    const subscriptions = new Map<string, Promise<() => void>>();
+    const unsubscribe = (key: string) => {
+      subscriptions
+        .get(key)
+        ?.then((remove) => remove())
+        .catch(() => {});
+      subscriptions.delete(key);
+    };
+
+    socket.on("message", (raw) => {
+      try {
+        const { op, channel, symbol, limit } = JSON.parse(raw.toString());
+        const key = `${channel}:${symbol}`;
+        if (op === "unsubscribe") {
+          unsubscribe(key);
+          return;
+        }
+        if (op !== "subscribe") {
+          throw new Error(`Unknown op ${op}`);
+        }
+        if (subscriptions.has(key)) {
+          return;
+        }
+        const subscription = this._subscribe(socket, channel, symbol, limit);
+        subscriptions.set(key, subscription);
+        subscription.catch((err) => {
+          subscriptions.delete(key);
+          socket.send(JSON.stringify({ error: (err as Error).message }));
+        });
+      } catch (err) {
+        socket.send(JSON.stringify({ error: (err as Error).message }));
+      }
+    });
+    socket.on("close", () => {
+      [...subscriptions.keys()].forEach(unsubscribe);
+    });
+  }
+
+  private async _handle(req: IncomingMessage, res: ServerResponse) {
+    const url = new URL(req.url || "/", "http://localhost");
+    const symbol = url.searchParams.get("symbol");
+    const limit = url.searchParams.get("limit");
+    const parsedLimit = limit ? parseInt(limit) : undefined;
+
+    let body: any;
+    switch (url.pathname) {
+      case "/markets":
+        body = await this.fetchMarkets();
+        break;
+      case "/orderbook":
+        body = await this.fetchOrderBook(symbol as string, parsedLimit);
+        break;
+      case "/ticker":
+        body = await this.fetchTicker(symbol as string);
+        break;
+      case "/trades":
+        body = await this.fetchTrades(symbol as string, parsedLimit);
+        break;
+      default:
+        res.writeHead(404, { "Content-Type": "application/json" });
+        res.end(JSON.stringify({ error: "Not found" }));
+        return;
+    }
+    res.writeHead(200, { "Content-Type": "application/json" });
+    res.end(JSON.stringify(body));
+  }
+
+  /**
+   * Starts listening for HTTP requests and WebSocket connections
+   * @returns The underlying HTTP server
+   */
+  listen(): Server {
+    this._server = createServer((req, res) => {
+      this._handle(req, res).catch((err) => {
+        res.writeHead(400, { "Content-Type": "application/json" });
+        res.end(JSON.stringify({ error: (err as Error).message }));
+      });
+    });
+    this._wss = new WebSocket.Server({ server: this._server });
+    this._wss.on("connection", (socket) => this._handleSocket(socket));
+    this._server.listen(this._options.port ?? 8080);
+    return this._server;
+  }
+
+  /** Stops the gateway */
+  close() {
+    this._wss?.clients.forEach((socket) => socket.terminate());
+    this._wss?.close();
+    this._server?.close();
+  }
+}
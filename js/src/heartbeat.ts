@@ -0,0 +1,121 @@
+import {
+  Commitment,
+  Connection,
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionSignature,
+} from "@solana/web3.js";
+import { heartbeat } from "./bindings";
+import { Market } from "./market";
+
+export interface HeartbeatSenderOptions {
+  /** The interval in milliseconds between two heartbeats */
+  intervalMs?: number;
+  /** The index of the user account (0 for the main user account) */
+  accountIndex?: number;
+  /** True if the signer is a session key of the user account rather than its owner */
+  isSessionKey?: boolean;
+  /** The commitment used for the confirmations */
+  commitment?: Commitment;
+  /** Called after each confirmed heartbeat */
+  onHeartbeat?: (signature: TransactionSignature) => void;
+  /** Called when a heartbeat fails */
+  onError?: (err: Error) => void;
+}
+
+const DEFAULT_INTERVAL_MS = 10_000;
+
+/**
+ * Periodically sends heartbeat instructions to keep the last active slot of a user account up to date
+ */
+export class HeartbeatSender {
+  private _connection: Connection;
+  private _market: Market;
+  private _owner: PublicKey;
+  private _signer: Keypair;
+  private _options: HeartbeatSenderOptions;
+  private _timer?: ReturnType<typeof setInterval>;
+  private _pending = false;
+
+  /**
+   * @param connection The Solana RPC connection
+   * @param market The market of the user account
+   * @param owner The owner of the user account
+   * @param signer The owner's keypair, or a session key if `isSessionKey` is set
+   * @param options The heartbeat options
+   */
+  constructor(
+    connection: Connection,
+    market: Market,
+    owner: PublicKey,
+    signer: Keypair,
+    options: HeartbeatSenderOptions = {}
+  ) {
+    this._connection = connection;
+    this._market = market;
+    this._owner = owner;
+    this._signer = signer;
+    this._options = options;
+  }
+
+  /**
+   * Sends a single heartbeat and waits for its confirmation
+   */
+  async send(): Promise<TransactionSignature> {
+    const ix = await heartbeat(
+      this._market,
+      this._owner,
+      this._options.isSessionKey ? this._signer.publicKey : undefined,
+      this._options.accountIndex || 0
+    );
+    const tx = new Transaction().add(ix);
+    const signature = await this._connection.sendTransaction(tx, [
+      this._signer,
+    ]);
+    const { value } = await this._connection.confirmTransaction(
+      signature,
+      this._options.commitment
+    );
+    if (value?.err) {
+      throw new Error(JSON.stringify(value.err));
+    }
+    return signature;
+  }
+
+  private async _tick() {
+    // Skip the tick while the previous heartbeat is still being confirmed
+    if (this._pending) {
+      return;
+    }
+    this._pending = true;
+    try {
+      const signature = await this.send();
+      this._options.onHeartbeat?.(signature);
+    } catch (err) {
+      this._options.onError?.(err as Error);
+    } finally {
+      this._pending = false;
+    }
+  }
+
+  /** Sends a heartbeat right away and then at every interval */
+  start() {
+    if (this._timer) {
+      return;
+    }
+    this._tick();
+    this._timer = setInterval(
+      () => this._tick(),
+      this._options.intervalMs || DEFAULT_INTERVAL_MS
+    );
+  }
+
+  /** Stops sending heartbeats */
+  stop() {
+    if (this._timer) {
+      clearInterval(this._timer);
+      this._timer = undefined;
+    }
+  }
+}
@@ -4,3 +4,14 @@ export * from "./orderbook";
 export * from "./ids";
 export * from "./types";
 export * from "./bindings";
+export * from "./autoSettler";
+export * from "./marketCreation";
+export * from "./orderId";
+export * from "./orderManager";
+export * from "./heartbeat";
+export * from "./txSender";
+export * from "./attestation";
+export * from "./twap";
+export * from "./quoter";
+export * from "./accountMetas";
+export * from "./marketStateWatcher";
@@ -13,7 +13,14 @@ import {
   divideBnToNumber,
   computeUiPrice
 } from "./utils";
-import { CALLBACK_INFO_LEN, MarketState, SelfTradeBehavior } from "./state";
+import {
+  CALLBACK_INFO_LEN,
+  HALT_ASKS_MASK,
+  HALT_BIDS_MASK,
+  MarketState,
+  MarketStatus,
+  SelfTradeBehavior
+} from "./state";
 import { DEX_ID, SRM_MINT, MSRM_MINT } from "./ids";
 import {
   EventQueue,
@@ -23,9 +30,16 @@ import {
 } from "@bonfida/aaob";
 import { getFeeTier } from "./fees";
 import { OpenOrders } from "./openOrders";
-import { cancelOrder, placeOrder, settle } from "./bindings";
+import { cancelAllOrders, cancelOrder, placeOrder, settle } from "./bindings";
 import BN from "bn.js";
-import { OrderType, Side, OrderInfo, MarketOptions } from "./types";
+import {
+  OrderType,
+  Side,
+  OrderInfo,
+  MarketOptions,
+  OrderFlow,
+  TopOfBook
+} from "./types";
 import { Orderbook } from "./orderbook";
 import { getAssociatedTokenAddress } from "@solana/spl-token";
 
@@ -285,6 +299,29 @@ export class Market {
     return this._admin;
   }
 
+  /**
+   * Returns the trading status of the market. New orders are rejected unless the market is active, while cancels and
+   * settles are always allowed
+   */
+  get status(): MarketStatus {
+    return this._marketState.status;
+  }
+
+  /** Returns whether new bids are rejected while the market is otherwise active */
+  get bidsHalted(): boolean {
+    return (this._marketState.haltFlags.toNumber() & HALT_BIDS_MASK) !== 0;
+  }
+
+  /** Returns whether new asks are rejected while the market is otherwise active */
+  get asksHalted(): boolean {
+    return (this._marketState.haltFlags.toNumber() & HALT_ASKS_MASK) !== 0;
+  }
+
+  /** Returns the admin nominated to take over the market, the default public key if there is none */
+  get pendingAdmin(): PublicKey {
+    return this._marketState.pendingAdmin;
+  }
+
   get baseCurrencyMultiplier(): BN {
     return this._baseCurrencyMultiplier;
   }
@@ -293,6 +330,110 @@ export class Market {
     return this._quoteCurrencyMultiplier;
   }
 
+  get baseSymbol(): string {
+    return this._marketState.baseSymbol;
+  }
+
+  get quoteSymbol(): string {
+    return this._marketState.quoteSymbol;
+  }
+
+  get marketName(): string {
+    return this._marketState.marketName;
+  }
+
+  /** Returns the Pyth price account of the market, if one is configured */
+  get oracle(): PublicKey | undefined {
+    return this._marketState.oracle.equals(PublicKey.default)
+      ? undefined
+      : this._marketState.oracle;
+  }
+
+  /** Returns the length of the market's batch auctions in slots, 0 if orders are matched continuously */
+  get batchSlots(): number {
+    return this._marketState.batchSlots.toNumber();
+  }
+
+  /** Returns the share of protocol fees credited to crankers, in basis points */
+  get crankFeeShareBps(): number {
+    return this._marketState.crankFeeShareBps.toNumber();
+  }
+
+  /** Returns the share of taker fees paid to the referrers of referred orders, in basis points */
+  get referralShareBps(): number {
+    return this._marketState.referralShareBps.toNumber();
+  }
+
+  /** Returns the rebate paid to makers out of the taker fees, in basis points of the filled quote amount */
+  get makerRebateBps(): number {
+    return this._marketState.makerRebateBps.toNumber();
+  }
+
+  /** Returns the raw quote amount charged when an unfilled order is cancelled within `cancelFeeSlots` of placement */
+  get cancelFee(): BN {
+    return this._marketState.cancelFee;
+  }
+
+  get cancelFeeSlots(): number {
+    return this._marketState.cancelFeeSlots.toNumber();
+  }
+
+  /** Returns the length in slots of the volatility window, 0 if the taker fee isn't scaled by volatility */
+  get volatilityWindowSlots(): number {
+    return this._marketState.volatilityWindowSlots.toNumber();
+  }
+
+  /** Returns the age in slots of the pending events which raises the crank reward multiplier by one, 0 if the crank reward isn't scaled */
+  get crankRewardAgeSlots(): number {
+    return this._marketState.crankRewardAgeSlots.toNumber();
+  }
+
+  /** Returns the maximum crank reward multiplier */
+  get maxCrankRewardMultiplier(): number {
+    return this._marketState.maxCrankRewardMultiplier.toNumber();
+  }
+
+  /** Returns the slot since which events have been waiting in the event queue, 0 if it is empty */
+  get pendingEventsSlot(): number {
+    return this._marketState.pendingEventsSlot.toNumber();
+  }
+
+  /** Returns the minimum number of slots an order must rest on the book before it can be cancelled */
+  get minRestingSlots(): number {
+    return this._marketState.minRestingSlots.toNumber();
+  }
+
+  /**
+   * Returns the order flow counters of the market over its current and previous windows.
+   * A window lasts ORDER_FLOW_WINDOW_SLOTS slots and starts at slot `window * ORDER_FLOW_WINDOW_SLOTS`.
+   */
+  get orderFlow(): OrderFlow {
+    const state = this._marketState;
+    return {
+      window: state.orderFlowWindow.toNumber(),
+      newOrders: state.newOrders.toNumber(),
+      cancels: state.cancels.toNumber(),
+      fills: state.fills.toNumber(),
+      previousNewOrders: state.previousNewOrders.toNumber(),
+      previousCancels: state.previousCancels.toNumber(),
+      previousFills: state.previousFills.toNumber()
+    };
+  }
+
+  /**
+   * Returns the best bid and ask cached in the market state, which is cheaper than loading the bids and asks
+   */
+  get topOfBook(): TopOfBook {
+    const state = this._marketState;
+    const uiPrice = (price: BN) =>
+      price.isZero() ? undefined : computeUiPrice(this, price);
+    return {
+      bestBid: uiPrice(state.bestBid),
+      bestAsk: uiPrice(state.bestAsk),
+      slot: state.topOfBookSlot.toNumber()
+    };
+  }
+
   /** Returns the inception base volume */
   baseVolume(): number {
     return this._marketState.baseVolume.toNumber();
@@ -416,6 +557,7 @@ export class Market {
    * @param ownerTokenAccount The token account of the owner of the wallet placing the trade (owner)
    * @param owner The owner of the order
    * @param discountTokenAccount Optional (M)SRM token account for fee discount
+   * @param matchLimit Optional maximum number of orders to match against, the remainder being cancelled once it is reached
    * @returns The signature of the transaction
    */
   async placeOrder(
@@ -428,7 +570,8 @@ export class Market {
     ownerTokenAccount: PublicKey,
     owner: Keypair,
     clientOrderId?: BN,
-    discountTokenAccount?: PublicKey
+    discountTokenAccount?: PublicKey,
+    matchLimit?: BN
   ) {
     const inst = await this.makePlaceOrderTransaction(
       side,
@@ -439,7 +582,8 @@ export class Market {
       ownerTokenAccount,
       owner.publicKey,
       clientOrderId,
-      discountTokenAccount
+      discountTokenAccount,
+      matchLimit
     );
     const tx = new Transaction().add(inst);
     return await this._sendTransaction(connection, tx, [owner]);
@@ -455,6 +599,7 @@ export class Market {
    * @param ownerTokenAccount The token account of the owner of the wallet placing the trade (owner)
    * @param owner The owner of the order
    * @param discountTokenAccount Optional (M)SRM token account for fee discount
+   * @param matchLimit Optional maximum number of orders to match against, the remainder being cancelled once it is reached
    * @returns Returns a TransactionInstruction object
    */
   async makePlaceOrderTransaction(
@@ -466,7 +611,8 @@ export class Market {
     ownerTokenAccount: PublicKey,
     owner: PublicKey,
     clientOrderId?: BN,
-    discountTokenAccount?: PublicKey
+    discountTokenAccount?: PublicKey,
+    matchLimit?: BN
   ) {
     return await placeOrder(
       this,
@@ -478,7 +624,12 @@ export class Market {
       ownerTokenAccount,
       owner,
       clientOrderId,
-      discountTokenAccount
+      discountTokenAccount,
+      undefined,
+      undefined,
+      0,
+      false,
+      matchLimit
     );
   }
 
@@ -620,6 +771,58 @@ export class Market {
     return signature;
   }
 
+  /**
+   *
+   * @param connection The solana connection object to the RPC node
+   * @param clientOrderId The client order id given when the order was placed
+   * @param owner The owner of the order
+   * @param accountIndex The index of the user account (0 for the main user account)
+   * @returns The signature of the cancel transaction
+   */
+  async cancelOrderByClientId(
+    connection: Connection,
+    clientOrderId: BN,
+    owner: Keypair,
+    accountIndex = 0
+  ) {
+    const instruction = await cancelOrder(
+      this,
+      owner.publicKey,
+      clientOrderId,
+      undefined,
+      clientOrderId,
+      accountIndex
+    );
+    const tx = new Transaction().add(instruction);
+    const signature = await this._sendTransaction(connection, tx, [owner]);
+    return signature;
+  }
+
+  /**
+   *
+   * @param connection The solana connection object to the RPC node
+   * @param owner The owner of the orders
+   * @param maxOrders The maximum number of orders to cancel in the transaction
+   * @param accountIndex The index of the user account (0 for the main user account)
+   * @returns The signature of the cancel transaction
+   */
+  async cancelAllOrders(
+    connection: Connection,
+    owner: Keypair,
+    maxOrders = 20,
+    accountIndex = 0
+  ) {
+    const instruction = await cancelAllOrders(
+      this,
+      owner.publicKey,
+      new BN(maxOrders),
+      accountIndex
+    );
+    const tx = new Transaction().add(instruction);
+    const signature = await this._sendTransaction(connection, tx, [owner]);
+    return signature;
+  }
+
   async cancelInBatch(
     connection: Connection,
     orders: OrderInfo[],
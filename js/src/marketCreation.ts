@@ -0,0 +1,182 @@
+import {
+  Connection,
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionInstruction
+} from "@solana/web3.js";
+import bs58 from "bs58";
+import fs from "fs";
+import { PrimedTransaction } from "./types";
+
+interface SerializedInstruction {
+  programId: string;
+  keys: { pubkey: string; isSigner: boolean; isWritable: boolean }[];
+  data: string;
+}
+
+interface PendingTransaction {
+  signature: string;
+  lastValidBlockHeight: number;
+}
+
+interface MarketCreationStep {
+  signers: string[];
+  instructions: SerializedInstruction[];
+  pending?: PendingTransaction;
+  confirmed: boolean;
+}
+
+/**
+ * The progress of a market creation, as recorded in the state file
+ */
+export interface MarketCreationState {
+  market: string;
+  steps: MarketCreationStep[];
+}
+
+const serializeInstruction = (
+  ix: TransactionInstruction
+): SerializedInstruction => ({
+  programId: ix.programId.toBase58(),
+  keys: ix.keys.map((k) => ({
+    pubkey: k.pubkey.toBase58(),
+    isSigner: k.isSigner,
+    isWritable: k.isWritable
+  })),
+  data: ix.data.toString("base64")
+});
+
+const deserializeInstruction = (ix: SerializedInstruction) =>
+  new TransactionInstruction({
+    programId: new PublicKey(ix.programId),
+    keys: ix.keys.map((k) => ({
+      pubkey: new PublicKey(k.pubkey),
+      isSigner: k.isSigner,
+      isWritable: k.isWritable
+    })),
+    data: Buffer.from(ix.data, "base64")
+  });
+
+const saveState = (statePath: string, state: MarketCreationState) => {
+  // The state file contains the secret keys of the accounts being created
+  fs.writeFileSync(statePath, JSON.stringify(state, null, 2), { mode: 0o600 });
+};
+
+/**
+ * Loads the market creation progress recorded in a state file, if it exists
+ * @param statePath The path of the state file
+ * @returns
+ */
+export const loadMarketCreationState = (
+  statePath: string
+): MarketCreationState | undefined => {
+  if (!fs.existsSync(statePath)) {
+    return undefined;
+  }
+  return JSON.parse(fs.readFileSync(statePath, "utf-8"));
+};
+
+/**
+ * Returns true if the pending transaction of a step landed successfully
+ * and false if it failed or can no longer land.
+ */
+const checkPending = async (
+  connection: Connection,
+  pending: PendingTransaction
+) => {
+  for (;;) {
+    const { value } = await connection.getSignatureStatus(pending.signature, {
+      searchTransactionHistory: true
+    });
+    if (value?.err) {
+      return false;
+    }
+    if (
+      value?.confirmationStatus === "confirmed" ||
+      value?.confirmationStatus === "finalized"
+    ) {
+      return true;
+    }
+    const blockHeight = await connection.getBlockHeight("confirmed");
+    if (!value && blockHeight > pending.lastValidBlockHeight) {
+      return false;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+  }
+};
+
+/**
+ * Sends the transactions of a market creation while recording their progress in a local state file.
+ * If the state file already exists, the recorded transactions are resumed instead and the completed steps are skipped,
+ * which ensures that the accounts of a partially created market are reused rather than stranded.
+ * The state file contains the secret keys of the created accounts and should be deleted once the market is live.
+ * @param connection The Solana RPC connection
+ * @param feePayer The fee payer of the transactions
+ * @param statePath The path of the state file
+ * @param buildTransactions Builds the market creation transactions (e.g. by calling `createMarket`), only called when no state file exists
+ * @returns The address of the market
+ */
+export const createMarketResumable = async (
+  connection: Connection,
+  feePayer: Keypair,
+  statePath: string,
+  buildTransactions: () => Promise<PrimedTransaction[]>
+) => {
+  let state = loadMarketCreationState(statePath);
+  if (!state) {
+    const transactions = await buildTransactions();
+    state = {
+      market: transactions[0][0][0].publicKey.toBase58(),
+      steps: transactions.map(([signers, instructions]) => ({
+        signers: signers.map((s) => bs58.encode(s.secretKey)),
+        instructions: instructions.map(serializeInstruction),
+        confirmed: false
+      }))
+    };
+    saveState(statePath, state);
+  }
+
+  for (const step of state.steps) {
+    if (step.confirmed) {
+      continue;
+    }
+    if (step.pending && (await checkPending(connection, step.pending))) {
+      step.confirmed = true;
+      step.pending = undefined;
+      saveState(statePath, state);
+      continue;
+    }
+
+    const signers = step.signers.map((s) =>
+      Keypair.fromSecretKey(bs58.decode(s))
+    );
+    const { blockhash, lastValidBlockHeight } =
+      await connection.getLatestBlockhash("confirmed");
+    const tx = new Transaction({
+      feePayer: feePayer.publicKey,
+      blockhash,
+      lastValidBlockHeight
+    }).add(...step.instructions.map(deserializeInstruction));
+    tx.sign(feePayer, ...signers);
+
+    // The signature is recorded before sending so that an interrupted step can be checked on retry
+    step.pending = {
+      signature: bs58.encode(tx.signature as Buffer),
+      lastValidBlockHeight
+    };
+    saveState(statePath, state);
+
+    await connection.sendRawTransaction(tx.serialize());
+    if (!(await checkPending(connection, step.pending))) {
+      throw new Error(
+        `Market creation transaction ${step.pending.signature} failed`
+      );
+    }
+    step.confirmed = true;
+    step.pending = undefined;
+    saveState(statePath, state);
+  }
+
+  return new PublicKey(state.market);
+};
@@ -0,0 +1,214 @@
+import { Commitment, Connection, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
+import { deserialize } from "borsh";
+import { MarketState, MarketStatus } from "./state";
+
+/**
+ * The kind of a governance change of a market
+ */
+export enum MarketStateChangeKind {
+  /** One of the fee parameters of the market was updated */
+  FeesUpdated = "feesUpdated",
+  /** The market status or its halt flags changed */
+  StatusChanged = "statusChanged",
+  /** The admin of the market was replaced */
+  AdminRotated = "adminRotated",
+  /** The oracle of the market was replaced */
+  OracleChanged = "oracleChanged",
+}
+
+/**
+ * The fee parameters of a market
+ */
+export interface MarketFees {
+  feeType: number;
+  royaltiesBps: BN;
+  referralShareBps: BN;
+  makerRebateBps: BN;
+  crankFeeShareBps: BN;
+  cancelFee: BN;
+  cancelFeeSlots: BN;
+}
+
+/**
+ * The trading status of a market
+ */
+export interface MarketStatusInfo {
+  status: MarketStatus;
+  haltFlags: BN;
+}
+
+export type MarketStateChange =
+  | {
+      kind: MarketStateChangeKind.FeesUpdated;
+      previous: MarketFees;
+      current: MarketFees;
+    }
+  | {
+      kind: MarketStateChangeKind.StatusChanged;
+      previous: MarketStatusInfo;
+      current: MarketStatusInfo;
+    }
+  | {
+      kind:
+        | MarketStateChangeKind.AdminRotated
+        | MarketStateChangeKind.OracleChanged;
+      previous: PublicKey;
+      current: PublicKey;
+    };
+
+const getFees = (state: MarketState): MarketFees => {
+  return {
+    feeType: state.feeType,
+    royaltiesBps: state.royaltiesBps,
+    referralShareBps: state.referralShareBps,
+    makerRebateBps: state.makerRebateBps,
+    crankFeeShareBps: state.crankFeeShareBps,
+    cancelFee: state.cancelFee,
+    cancelFeeSlots: state.cancelFeeSlots,
+  };
+};
+
+const feesEqual = (a: MarketFees, b: MarketFees) =>
+  a.feeType === b.feeType &&
+  a.royaltiesBps.eq(b.royaltiesBps) &&
+  a.referralShareBps.eq(b.referralShareBps) &&
+  a.makerRebateBps.eq(b.makerRebateBps) &&
+  a.crankFeeShareBps.eq(b.crankFeeShareBps) &&
+  a.cancelFee.eq(b.cancelFee) &&
+  a.cancelFeeSlots.eq(b.cancelFeeSlots);
+
+/**
+ * Lists the governance changes between two snapshots of a market state.
+ *
+ * Trading activity (volumes, order flow counters, best prices...) is ignored.
+ * @param previous The previous market state
+ * @param current The current market state
+ */
+export const diffMarketState = (
+  previous: MarketState,
+  current: MarketState
+): MarketStateChange[] => {
+  const changes: MarketStateChange[] = [];
+  const previousFees = getFees(previous);
+  const currentFees = getFees(current);
+  if (!feesEqual(previousFees, currentFees)) {
+    changes.push({
+      kind: MarketStateChangeKind.FeesUpdated,
+      previous: previousFees,
+      current: currentFees,
+    });
+  }
+  if (
+    previous.status !== current.status ||
+    !previous.haltFlags.eq(current.haltFlags)
+  ) {
+    changes.push({
+      kind: MarketStateChangeKind.StatusChanged,
+      previous: { status: previous.status, haltFlags: previous.haltFlags },
+      current: { status: current.status, haltFlags: current.haltFlags },
+    });
+  }
+  if (!previous.admin.equals(current.admin)) {
+    changes.push({
+      kind: MarketStateChangeKind.AdminRotated,
+      previous: previous.admin,
+      current: current.admin,
+    });
+  }
+  if (!previous.oracle.equals(current.oracle)) {
+    changes.push({
+      kind: MarketStateChangeKind.OracleChanged,
+      previous: previous.oracle,
+      current: current.oracle,
+    });
+  }
+  return changes;
+};
+
+export interface MarketStateWatcherOptions {
+  /** The commitment used for the market subscription */
+  commitment?: Commitment;
+  /** Called for each governance change of the market */
+  onChange: (change: MarketStateChange, state: MarketState) => void;
+  /** Called when an update of the market cannot be decoded */
+  onError?: (err: Error) => void;
+}
+
+/**
+ * Subscribes to the state of a market and reports its governance changes, such as fee updates,
+ * halts, admin rotations or oracle replacements
+ */
+export class MarketStateWatcher {
+  private _connection: Connection;
+  private _market: PublicKey;
+  private _options: MarketStateWatcherOptions;
+  private _state?: MarketState;
+  private _subscription?: number;
+
+  constructor(
+    connection: Connection,
+    market: PublicKey,
+    options: MarketStateWatcherOptions
+  ) {
+    this._connection = connection;
+    this._market = market;
+    this._options = options;
+  }
+
+  /** The last seen state of the market */
+  get state(): MarketState | undefined {
+    return this._state;
+  }
+
+  /**
+   * Compares a new snapshot of the market state against the last one and reports the changes
+   * @param state The new market state
+   */
+  update(state: MarketState): MarketStateChange[] {
+    const changes = this._state ? diffMarketState(this._state, state) : [];
+    this._state = state;
+    for (const change of changes) {
+      this._options.onChange(change, state);
+    }
+    return changes;
+  }
+
+  /**
+   * Fetches the current market state and subscribes to its updates
+   */
+  async start() {
+    if (this._subscription !== undefined) {
+      throw new Error("The watcher is already started");
+    }
+    const decode = (data: Buffer) =>
+      deserialize(MarketState.schema, MarketState, data) as MarketState;
+    const info = await this._connection.getAccountInfo(
+      this._market,
+      this._options.commitment
+    );
+    if (!info?.data) {
+      throw new Error("Invalid account provided");
+    }
+    this._state = decode(info.data);
+    this._subscription = this._connection.onAccountChange(
+      this._market,
+      (info) => {
+        try {
+          this.update(decode(info.data));
+        } catch (err) {
+          this._options.onError?.(err as Error);
+        }
+      },
+      this._options.commitment
+    );
+  }
+
+  /** Removes the market subscription */
+  async stop() {
+    if (this._subscription !== undefined) {
+      await this._connection.removeAccountChangeListener(this._subscription);
+      this._subscription = undefined;
+    }
+  }
+}
@@ -0,0 +1,60 @@
+import BN from "bn.js";
+import { Side } from "./types";
+
+const U64_MASK = new BN(0).notn(64);
+
+/**
+ * The components of an AAOB order id
+ */
+export interface DecodedOrderId {
+  /** The FP32 limit price of the order */
+  price: BN;
+  /** The sequence number of the order in the event queue */
+  sequenceNumber: BN;
+  /** The side of the order */
+  side: Side;
+}
+
+/**
+ * Splits a 128-bit order id into its price, sequence number and side.
+ *
+ * The upper 64 bits hold the FP32 price. The lower 64 bits hold the sequence number,
+ * bitwise negated for bids so that older bids sort higher at the same price.
+ * @param orderId The order id
+ * @returns
+ */
+export const decodeOrderId = (orderId: BN): DecodedOrderId => {
+  const price = orderId.shrn(64);
+  const lower = orderId.maskn(64);
+  const side = lower.testn(63) ? Side.Bid : Side.Ask;
+  const sequenceNumber = side === Side.Bid ? lower.notn(64) : lower;
+  return { price, sequenceNumber, side };
+};
+
+/**
+ * Builds the 128-bit order id of an order from its price, sequence number and side
+ * @param price The FP32 limit price of the order
+ * @param sequenceNumber The sequence number of the order in the event queue
+ * @param side The side of the order
+ * @returns
+ */
+export const encodeOrderId = (
+  price: BN,
+  sequenceNumber: BN,
+  side: Side
+): BN => {
+  if (price.gt(U64_MASK) || sequenceNumber.gt(U64_MASK)) {
+    throw new Error("The price and sequence number should fit in 64 bits");
+  }
+  const lower = side === Side.Bid ? sequenceNumber.notn(64) : sequenceNumber;
+  return price.shln(64).or(lower);
+};
+
+/**
+ * Returns the side of an order from its id
+ * @param orderId The order id
+ * @returns
+ */
+export const getSideFromOrderId = (orderId: BN): Side => {
+  return decodeOrderId(orderId).side;
+};
@@ -0,0 +1,348 @@
+import { Commitment, Connection, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
+import { deserializeUnchecked } from "borsh";
+import { Order, UserAccount } from "./state";
+
+/**
+ * The lifecycle state of an order tracked by the order manager
+ */
+export enum ManagedOrderState {
+  /** The placement was sent but the order hasn't been seen on chain yet */
+  Pending = "pending",
+  /** The order rests on the book */
+  Open = "open",
+  /** A cancel was sent but the order is still on chain */
+  CancelPending = "cancelPending",
+  /** The order left the book, either filled or cancelled */
+  Closed = "closed",
+  /** The placement failed */
+  Failed = "failed",
+}
+
+/**
+ * An order tracked by the order manager
+ */
+export interface ManagedOrder {
+  /** The client order id, which identifies the order before its order id is known */
+  clientId: BN;
+  /** The order id, once the order has been seen on chain or filled */
+  orderId?: BN;
+  /** The current state of the order */
+  state: ManagedOrderState;
+  /** The raw base quantity filled so far, as reported by fill events */
+  filledBaseQty: BN;
+  /** The time in milliseconds of the last state change */
+  updatedAt: number;
+  /** The signature of the last transaction sent for the order */
+  signature?: string;
+}
+
+/**
+ * The kind of mismatch between the intended and on-chain state of the orders
+ */
+export enum DivergenceKind {
+  /** An order rests on chain but was never placed through the manager */
+  UnknownOrder = "unknownOrder",
+  /** A placement was sent but the order isn't on chain after the timeout */
+  PlacementMissing = "placementMissing",
+  /** A cancel was sent but the order is still on chain after the timeout */
+  CancelStuck = "cancelStuck",
+}
+
+/**
+ * A mismatch between the intended and on-chain state of an order
+ */
+export interface OrderDivergence {
+  kind: DivergenceKind;
+  /** The client order id of the order */
+  clientId: BN;
+  /** The order id of the order, if known */
+  orderId?: BN;
+}
+
+export interface OrderManagerOptions {
+  /** The time in milliseconds after which a sent placement or cancel which isn't reflected on chain is reported */
+  timeoutMs?: number;
+  /** The commitment used for the user account subscription */
+  commitment?: Commitment;
+  /** Called when an order changes state */
+  onStateChange?: (order: ManagedOrder, previous: ManagedOrderState) => void;
+  /** Called when the intended and on-chain states diverge */
+  onDivergence?: (divergence: OrderDivergence) => void;
+}
+
+const DEFAULT_TIMEOUT_MS = 30_000;
+
+/**
+ * Maintains the intended state of the orders of a user account and reconciles it against
+ * the on-chain user account snapshots and the fill and out events of the market
+ */
+export class OrderManager {
+  private _orders: Map<string, ManagedOrder> = new Map();
+  private _options: OrderManagerOptions;
+  private _reported: Set<string> = new Set();
+  private _subscription?: number;
+
+  constructor(options: OrderManagerOptions = {}) {
+    this._options = options;
+  }
+
+  /** Returns the tracked orders */
+  get orders(): ManagedOrder[] {
+    return Array.from(this._orders.values());
+  }
+
+  /** Returns the tracked orders which are pending, open or being cancelled */
+  get activeOrders(): ManagedOrder[] {
+    return this.orders.filter(
+      (o) =>
+        o.state !== ManagedOrderState.Closed &&
+        o.state !== ManagedOrderState.Failed
+    );
+  }
+
+  /**
+   * Returns a tracked order by client order id
+   * @param clientId The client order id
+   */
+  getOrder(clientId: BN): ManagedOrder | undefined {
+    return this._orders.get(clientId.toString());
+  }
+
+  /**
+   * Records that a new order placement was sent
+   * @param clientId The client order id of the order, which should be unique
+   * @param signature The signature of the placement transaction
+   * @param now The current time in milliseconds
+   */
+  trackPlacement(clientId: BN, signature?: string, now = Date.now()) {
+    const key = clientId.toString();
+    if (this._orders.has(key)) {
+      throw new Error(`The client order id ${key} is already tracked`);
+    }
+    this._orders.set(key, {
+      clientId,
+      state: ManagedOrderState.Pending,
+      filledBaseQty: new BN(0),
+      updatedAt: now,
+      signature,
+    });
+  }
+
+  /**
+   * Records that a cancel was sent for an order
+   * @param clientId The client order id of the order
+   * @param signature The signature of the cancel transaction
+   * @param now The current time in milliseconds
+   */
+  trackCancel(clientId: BN, signature?: string, now = Date.now()) {
+    const order = this._get(clientId);
+    if (order.state === ManagedOrderState.Closed) {
+      return;
+    }
+    order.signature = signature;
+    this._transition(order, ManagedOrderState.CancelPending, now);
+  }
+
+  /**
+   * Records that the placement of an order failed
+   * @param clientId The client order id of the order
+   * @param now The current time in milliseconds
+   */
+  markFailed(clientId: BN, now = Date.now()) {
+    this._transition(this._get(clientId), ManagedOrderState.Failed, now);
+  }
+
+  /**
+   * Records the base quantity matched by an order when it was placed, as reported by the placement transaction.
+   * A pending order which was matched and isn't found on chain is considered closed.
+   * @param clientId The client order id of the order
+   * @param baseQty The raw base quantity matched
+   */
+  markFilled(clientId: BN, baseQty: BN) {
+    const order = this._get(clientId);
+    order.filledBaseQty = order.filledBaseQty.add(baseQty);
+  }
+
+  /**
+   * Applies a fill event in which the order was the maker
+   * @param orderId The order id of the maker order
+   * @param baseQty The raw base quantity filled
+   */
+  applyFill(orderId: BN, baseQty: BN) {
+    const order = this._findByOrderId(orderId);
+    if (order) {
+      order.filledBaseQty = order.filledBaseQty.add(baseQty);
+    }
+  }
+
+  /**
+   * Applies an out event, emitted when an order leaves the book
+   * @param orderId The order id of the order
+   * @param now The current time in milliseconds
+   */
+  applyOut(orderId: BN, now = Date.now()) {
+    const order = this._findByOrderId(orderId);
+    if (order) {
+      this._transition(order, ManagedOrderState.Closed, now);
+    }
+  }
+
+  /**
+   * Reconciles the tracked orders against a snapshot of the on-chain user account
+   * @param userAccount The decoded user account
+   * @param now The current time in milliseconds
+   * @returns The divergences found during this reconciliation
+   */
+  reconcile(
+    userAccount: Pick<UserAccount, "orders">,
+    now = Date.now()
+  ): OrderDivergence[] {
+    const timeoutMs = this._options.timeoutMs ?? DEFAULT_TIMEOUT_MS;
+    const divergences: OrderDivergence[] = [];
+    const onChain = new Map<string, Order>();
+    for (const o of userAccount.orders) {
+      onChain.set(o.clientId.toString(), o);
+    }
+
+    for (const [key, chainOrder] of onChain) {
+      const order = this._orders.get(key);
+      if (!order) {
+        divergences.push({
+          kind: DivergenceKind.UnknownOrder,
+          clientId: chainOrder.clientId,
+          orderId: chainOrder.id,
+        });
+        continue;
+      }
+      order.orderId = chainOrder.id;
+      if (order.state === ManagedOrderState.Pending) {
+        this._transition(order, ManagedOrderState.Open, now);
+      } else if (
+        order.state === ManagedOrderState.CancelPending &&
+        now - order.updatedAt > timeoutMs
+      ) {
+        divergences.push({
+          kind: DivergenceKind.CancelStuck,
+          clientId: order.clientId,
+          orderId: order.orderId,
+        });
+      }
+    }
+
+    for (const [key, order] of this._orders) {
+      if (onChain.has(key)) {
+        continue;
+      }
+      switch (order.state) {
+        case ManagedOrderState.Open:
+        case ManagedOrderState.CancelPending:
+          this._transition(order, ManagedOrderState.Closed, now);
+          break;
+        case ManagedOrderState.Pending:
+          // Fully matched orders never rest on the book
+          if (!order.filledBaseQty.isZero()) {
+            this._transition(order, ManagedOrderState.Closed, now);
+          } else if (now - order.updatedAt > timeoutMs) {
+            divergences.push({
+              kind: DivergenceKind.PlacementMissing,
+              clientId: order.clientId,
+            });
+          }
+          break;
+      }
+    }
+
+    for (const d of divergences) {
+      // Each divergence is only reported once
+      const id = `${d.kind}:${d.clientId.toString()}`;
+      if (!this._reported.has(id)) {
+        this._reported.add(id);
+        this._options.onDivergence?.(d);
+      }
+    }
+    return divergences;
+  }
+
+  /**
+   * Removes the closed and failed orders from the manager
+   */
+  prune() {
+    for (const [key, order] of this._orders) {
+      if (
+        order.state === ManagedOrderState.Closed ||
+        order.state === ManagedOrderState.Failed
+      ) {
+        this._orders.delete(key);
+      }
+    }
+  }
+
+  /**
+   * Reconciles the tracked orders with the current user account and on each of its updates
+   * @param connection The Solana RPC connection
+   * @param userAccount The address of the user account
+   */
+  async watch(connection: Connection, userAccount: PublicKey) {
+    if (this._subscription !== undefined) {
+      throw new Error("The order manager is already watching a user account");
+    }
+    const decode = (data: Buffer) =>
+      deserializeUnchecked(
+        UserAccount.schema,
+        UserAccount,
+        data
+      ) as UserAccount;
+    const info = await connection.getAccountInfo(
+      userAccount,
+      this._options.commitment
+    );
+    if (info?.data) {
+      this.reconcile(decode(info.data));
+    }
+    this._subscription = connection.onAccountChange(
+      userAccount,
+      (info) => {
+        this.reconcile(decode(info.data));
+      },
+      this._options.commitment
+    );
+  }
+
+  /**
+   * Removes the user account subscription
+   * @param connection The Solana RPC connection
+   */
+  async stop(connection: Connection) {
+    if (this._subscription !== undefined) {
+      await connection.removeAccountChangeListener(this._subscription);
+      this._subscription = undefined;
+    }
+  }
+
+  private _get(clientId: BN): ManagedOrder {
+    const order = this.getOrder(clientId);
+    if (!order) {
+      throw new Error(`Unknown client order id ${clientId.toString()}`);
+    }
+    return order;
+  }
+
+  private _findByOrderId(orderId: BN): ManagedOrder | undefined {
+    return this.orders.find((o) => o.orderId?.eq(orderId));
+  }
+
+  private _transition(
+    order: ManagedOrder,
+    state: ManagedOrderState,
+    now: number
+  ) {
+    if (order.state === state) {
+      return;
+    }
+    const previous = order.state;
+    order.state = state;
+    order.updatedAt = now;
+    this._options.onStateChange?.(order, previous);
+  }
+}
@@ -113,9 +113,70 @@ export class Orderbook {
       ? this._slabAsks.getL2DepthJS(depth, asks)
       : this._slabBids.getL2DepthJS(depth, asks);
   }
+
+  /**
+   * Computes depth metrics around the mid price of the orderbook
+   * @param bps The distance from the mid price in basis points within which liquidity is aggregated
+   * @param depth The number of price levels to load on each side
+   * @returns Returns the depth metrics in ui amounts, or undefined if one side of the orderbook is empty
+   */
+  getDepthMetrics(bps: number, depth = 100): DepthMetrics | undefined {
+    const bids = this.getL2(depth, false, true);
+    const asks = this.getL2(depth, true, true);
+    if (bids.length === 0 || asks.length === 0) {
+      return undefined;
+    }
+    const midPrice = (bids[0].price + asks[0].price) / 2;
+    const spreadBps = ((asks[0].price - bids[0].price) / midPrice) * 10_000;
+    const minBidPrice = midPrice * (1 - bps / 10_000);
+    const maxAskPrice = midPrice * (1 + bps / 10_000);
+
+    const aggregate = (levels: UiPrice[]) =>
+      levels.reduce(
+        (acc, l) => ({
+          size: acc.size + l.size,
+          notional: acc.notional + l.size * l.price,
+        }),
+        { size: 0, notional: 0 }
+      );
+    const bidDepth = aggregate(bids.filter((l) => l.price >= minBidPrice));
+    const askDepth = aggregate(asks.filter((l) => l.price <= maxAskPrice));
+    const totalSize = bidDepth.size + askDepth.size;
+
+    return {
+      midPrice,
+      spreadBps,
+      bidSize: bidDepth.size,
+      askSize: askDepth.size,
+      bidNotional: bidDepth.notional,
+      askNotional: askDepth.notional,
+      imbalance:
+        totalSize === 0 ? 0 : (bidDepth.size - askDepth.size) / totalSize,
+    };
+  }
 }
 
 export interface UiPrice {
   price: number;
   size: number;
 }
+
+/**
+ * Liquidity metrics computed within a distance of the mid price
+ */
+export interface DepthMetrics {
+  /** The mid price between the best bid and the best ask */
+  midPrice: number;
+  /** The spread between the best bid and the best ask in basis points */
+  spreadBps: number;
+  /** The total bid size within the distance */
+  bidSize: number;
+  /** The total ask size within the distance */
+  askSize: number;
+  /** The total bid notional (in quote) within the distance */
+  bidNotional: number;
+  /** The total ask notional (in quote) within the distance */
+  askNotional: number;
+  /** The size imbalance between -1 (only asks) and 1 (only bids) */
+  imbalance: number;
+}
@@ -0,0 +1,147 @@
+import { Market } from "./market";
+import { UserAccount } from "./state";
+
+/**
+ * The holdings of a user account, in UI amounts
+ */
+export interface Inventory {
+  base: number;
+  quote: number;
+}
+
+/**
+ * A two-sided quote, in UI prices and sizes. A side with a zero size should not be quoted.
+ */
+export interface Quote {
+  bidPrice: number;
+  bidSize: number;
+  askPrice: number;
+  askSize: number;
+}
+
+export interface QuoterOptions {
+  /** The fraction of the inventory value which should be held in base tokens, between 0 and 1 */
+  targetBaseRatio: number;
+  /** The distance of each side from the skewed mid price, in basis points */
+  halfSpreadBps: number;
+  /** The shift of the mid price when the whole inventory is on one side, in basis points */
+  maxSkewBps: number;
+  /** The size of each side when the inventory is on target (UI base amount) */
+  size: number;
+  /** The tick size to which the prices are rounded, away from the mid price */
+  tickSize?: number;
+  /** The minimum order size, smaller sides are not quoted */
+  minSize?: number;
+}
+
+/**
+ * Computes two-sided quotes which are skewed towards a target inventory.
+ *
+ * When the inventory holds more base than the target, both prices are shifted down and the bid is shrunk, so that
+ * the ask is more likely to be filled and the inventory moves back to the target. The converse holds when the
+ * inventory holds too little base.
+ */
+export class Quoter {
+  private _options: QuoterOptions;
+
+  constructor(options: QuoterOptions) {
+    if (options.targetBaseRatio < 0 || options.targetBaseRatio > 1) {
+      throw new Error("The target base ratio should be between 0 and 1");
+    }
+    if (options.halfSpreadBps < 0 || options.maxSkewBps < 0) {
+      throw new Error("The spread and skew should be nonnegative");
+    }
+    this._options = options;
+  }
+
+  /**
+   * Creates a quoter which rounds its quotes to the tick size and minimum order size of a market
+   * @param market The market on which the quotes are placed
+   * @param options The quoter options, the market parameters take precedence over `tickSize` and `minSize`
+   */
+  static forMarket(market: Market, options: QuoterOptions): Quoter {
+    return new Quoter({
+      ...options,
+      tickSize: market.tickSize,
+      minSize: market.minOrderSize,
+    });
+  }
+
+  /**
+   * The holdings of a user account, including the tokens locked in its open orders
+   * @param market The market of the user account
+   * @param userAccount The user account
+   */
+  static inventoryOf(market: Market, userAccount: UserAccount): Inventory {
+    const base = userAccount.baseTokenFree.add(userAccount.baseTokenLocked);
+    const quote = userAccount.quoteTokenFree.add(userAccount.quoteTokenLocked);
+    return {
+      base: base.toNumber() / Math.pow(10, market.baseDecimals),
+      quote: quote.toNumber() / Math.pow(10, market.quoteDecimals),
+    };
+  }
+
+  get options(): QuoterOptions {
+    return this._options;
+  }
+
+  /**
+   * The deviation of the inventory from the target, from -1 when it holds no base to 1 when it only holds base
+   * @param inventory The current inventory
+   * @param midPrice The UI price used to value the base holdings
+   */
+  imbalance(inventory: Inventory, midPrice: number): number {
+    const baseValue = inventory.base * midPrice;
+    const total = baseValue + inventory.quote;
+    if (total <= 0) {
+      return 0;
+    }
+    const deviation = baseValue / total - this._options.targetBaseRatio;
+    const range =
+      deviation > 0
+        ? 1 - this._options.targetBaseRatio
+        : this._options.targetBaseRatio;
+    return range === 0 ? 0 : Math.max(-1, Math.min(1, deviation / range));
+  }
+
+  /**
+   * Computes the quote for the current inventory. The sizes never exceed what the inventory can fund.
+   * @param midPrice The UI mid price of the market
+   * @param inventory The current inventory
+   */
+  quote(midPrice: number, inventory: Inventory): Quote {
+    const { halfSpreadBps, maxSkewBps, size, tickSize, minSize } =
+      this._options;
+    const imbalance = this.imbalance(inventory, midPrice);
+    const center = midPrice * (1 - (imbalance * maxSkewBps) / 10_000);
+    let bidPrice = center * (1 - halfSpreadBps / 10_000);
+    let askPrice = center * (1 + halfSpreadBps / 10_000);
+    if (tickSize) {
+      bidPrice = roundToTick(bidPrice, tickSize, true);
+      askPrice = roundToTick(askPrice, tickSize, false);
+    }
+
+    let bidSize = size * (1 - Math.max(imbalance, 0));
+    let askSize = size * (1 + Math.min(imbalance, 0));
+    bidSize = bidPrice > 0 ? Math.min(bidSize, inventory.quote / bidPrice) : 0;
+    askSize = Math.min(askSize, inventory.base);
+    if (minSize) {
+      bidSize = bidSize < minSize ? 0 : bidSize;
+      askSize = askSize < minSize ? 0 : askSize;
+    }
+    return {
+      bidPrice: Math.max(bidPrice, 0),
+      bidSize: Math.max(bidSize, 0),
+      askPrice,
+      askSize: Math.max(askSize, 0),
+    };
+  }
+}
+
+const roundToTick = (value: number, tick: number, down: boolean) => {
+  // The epsilon absorbs floating point errors on values which already are multiples of the tick
+  const ticks = down
+    ? Math.floor(value / tick + 1e-9)
+    : Math.ceil(value / tick - 1e-9);
+  return parseFloat((ticks * tick).toPrecision(12));
+};
@@ -62,10 +62,19 @@ export class newOrderInstruction {
   maxBaseQty: BN;
   maxQuoteQty: BN;
   matchLimit: BN;
+  expiryTimestamp: BN;
+  displayBaseQty: BN;
+  minBaseQty: BN;
   side: number;
   orderType: number;
   selfTradeBehavior: number;
   hasDiscountTokenAccount: number;
+  hasOracleAccount: number;
+  hasSession: number;
+  hasDmmRegistry: number;
+  notifyOnCross: number;
+  quantityMode: number;
+  useFreeBalances: number;
   padding: Uint8Array;
   static schema: Schema = new Map([
     [
@@ -79,11 +88,20 @@ export class newOrderInstruction {
           ["maxBaseQty", "u64"],
           ["maxQuoteQty", "u64"],
           ["matchLimit", "u64"],
+          ["expiryTimestamp", "u64"],
+          ["displayBaseQty", "u64"],
+          ["minBaseQty", "u64"],
           ["side", "u8"],
           ["orderType", "u8"],
           ["selfTradeBehavior", "u8"],
           ["hasDiscountTokenAccount", "u8"],
-          ["padding", "u32"],
+          ["hasOracleAccount", "u8"],
+          ["hasSession", "u8"],
+          ["hasDmmRegistry", "u8"],
+          ["notifyOnCross", "u8"],
+          ["quantityMode", "u8"],
+          ["useFreeBalances", "u8"],
+          ["padding", [6]],
         ],
       },
     ],
@@ -94,23 +112,40 @@ export class newOrderInstruction {
     maxBaseQty: BN;
     maxQuoteQty: BN;
     matchLimit: BN;
+    expiryTimestamp: BN;
+    displayBaseQty: BN;
+    minBaseQty: BN;
     side: number;
     orderType: number;
     selfTradeBehavior: number;
     hasDiscountTokenAccount: number;
+    hasOracleAccount: number;
+    hasSession: number;
+    hasDmmRegistry: number;
+    notifyOnCross: number;
+    quantityMode: number;
+    useFreeBalances: number;
   }) {
     this.tag = new BN(1);
     this.clientOrderId = obj.clientOrderId;
-    this.clientOrderId = obj.clientOrderId;
     this.limitPrice = obj.limitPrice;
     this.maxBaseQty = obj.maxBaseQty;
     this.maxQuoteQty = obj.maxQuoteQty;
     this.matchLimit = obj.matchLimit;
+    this.expiryTimestamp = obj.expiryTimestamp;
+    this.displayBaseQty = obj.displayBaseQty;
+    this.minBaseQty = obj.minBaseQty;
     this.side = obj.side;
     this.orderType = obj.orderType;
     this.selfTradeBehavior = obj.selfTradeBehavior;
     this.hasDiscountTokenAccount = obj.hasDiscountTokenAccount;
-    this.padding = new Uint8Array(4).fill(0);
+    this.hasOracleAccount = obj.hasOracleAccount;
+    this.hasSession = obj.hasSession;
+    this.hasDmmRegistry = obj.hasDmmRegistry;
+    this.notifyOnCross = obj.notifyOnCross;
+    this.quantityMode = obj.quantityMode;
+    this.useFreeBalances = obj.useFreeBalances;
+    this.padding = new Uint8Array(6).fill(0);
   }
   serialize(): Uint8Array {
     return serialize(newOrderInstruction.schema, this);
@@ -130,6 +165,9 @@ export class newOrderInstruction {
     userTokenAccount: PublicKey,
     userOwner: PublicKey,
     discountTokenAccount?: PublicKey,
+    oracle?: PublicKey,
+    session?: PublicKey,
+    dmmRegistry?: PublicKey,
     feeReferralAccount?: PublicKey
   ): TransactionInstruction {
     const data = Buffer.from(this.serialize());
@@ -201,6 +239,27 @@ export class newOrderInstruction {
         isWritable: false,
       });
     }
+    if (!!oracle) {
+      keys.push({
+        pubkey: oracle,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    if (!!session) {
+      keys.push({
+        pubkey: session,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!dmmRegistry) {
+      keys.push({
+        pubkey: dmmRegistry,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
     if (!!feeReferralAccount) {
       keys.push({
         pubkey: feeReferralAccount,
@@ -261,7 +320,7 @@ export class cancelOrderInstruction {
     keys.push({
       pubkey: market,
       isSigner: false,
-      isWritable: false,
+      isWritable: true,
     });
     keys.push({
       pubkey: orderbook,
@@ -302,17 +361,25 @@ export class cancelOrderInstruction {
 }
 export class settleInstruction {
   tag: BN;
+  unwrapSol: number;
+  padding: Uint8Array;
   static schema: Schema = new Map([
     [
       settleInstruction,
       {
         kind: "struct",
-        fields: [["tag", "u64"]],
+        fields: [
+          ["tag", "u64"],
+          ["unwrapSol", "u8"],
+          ["padding", [7]],
+        ],
       },
     ],
   ]);
-  constructor() {
+  constructor(obj: { unwrapSol: number }) {
     this.tag = new BN(5);
+    this.unwrapSol = obj.unwrapSol;
+    this.padding = new Uint8Array(7).fill(0);
   }
   serialize(): Uint8Array {
     return serialize(settleInstruction.schema, this);
@@ -327,7 +394,8 @@ export class settleInstruction {
     user: PublicKey,
     userOwner: PublicKey,
     destinationBaseAccount: PublicKey,
-    destinationQuoteAccount: PublicKey
+    destinationQuoteAccount: PublicKey,
+    settleAllowlist?: PublicKey
   ): TransactionInstruction {
     const data = Buffer.from(this.serialize());
     let keys: AccountKey[] = [];
@@ -364,7 +432,7 @@ export class settleInstruction {
     keys.push({
       pubkey: userOwner,
       isSigner: true,
-      isWritable: false,
+      isWritable: true,
     });
     keys.push({
       pubkey: destinationBaseAccount,
@@ -376,6 +444,13 @@ export class settleInstruction {
       isSigner: false,
       isWritable: true,
     });
+    if (!!settleAllowlist) {
+      keys.push({
+        pubkey: settleAllowlist,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
     return new TransactionInstruction({
       keys,
       programId,
@@ -387,6 +462,7 @@ export class initializeAccountInstruction {
   tag: BN;
   market: Uint8Array;
   maxOrders: BN;
+  accountIndex: BN;
   static schema: Schema = new Map([
     [
       initializeAccountInstruction,
@@ -396,14 +472,16 @@ export class initializeAccountInstruction {
           ["tag", "u64"],
           ["market", [32]],
           ["maxOrders", "u64"],
+          ["accountIndex", "u64"],
         ],
       },
     ],
   ]);
-  constructor(obj: { market: Uint8Array; maxOrders: BN }) {
+  constructor(obj: { market: Uint8Array; maxOrders: BN; accountIndex: BN }) {
     this.tag = new BN(6);
     this.market = obj.market;
     this.maxOrders = obj.maxOrders;
+    this.accountIndex = obj.accountIndex;
   }
   serialize(): Uint8Array {
     return serialize(initializeAccountInstruction.schema, this);
@@ -543,6 +621,9 @@ export class consumeEventsInstruction {
   tag: BN;
   maxIterations: BN;
   noOpErr: BN;
+  recordCrankerIdentity: BN;
+  pruneExpiredSessions: BN;
+  repostIcebergOrders: BN;
   static schema: Schema = new Map([
     [
       consumeEventsInstruction,
@@ -552,14 +633,26 @@ export class consumeEventsInstruction {
           ["tag", "u64"],
           ["maxIterations", "u64"],
           ["noOpErr", "u64"],
+          ["recordCrankerIdentity", "u64"],
+          ["pruneExpiredSessions", "u64"],
+          ["repostIcebergOrders", "u64"],
         ],
       },
     ],
   ]);
-  constructor(obj: { maxIterations: BN; noOpErr: BN }) {
+  constructor(obj: {
+    maxIterations: BN;
+    noOpErr: BN;
+    recordCrankerIdentity: BN;
+    pruneExpiredSessions: BN;
+    repostIcebergOrders: BN;
+  }) {
     this.tag = new BN(4);
     this.maxIterations = obj.maxIterations;
     this.noOpErr = obj.noOpErr;
+    this.recordCrankerIdentity = obj.recordCrankerIdentity;
+    this.pruneExpiredSessions = obj.pruneExpiredSessions;
+    this.repostIcebergOrders = obj.repostIcebergOrders;
   }
   serialize(): Uint8Array {
     return serialize(consumeEventsInstruction.schema, this);
@@ -570,7 +663,12 @@ export class consumeEventsInstruction {
     orderbook: PublicKey,
     eventQueue: PublicKey,
     rewardTarget: PublicKey,
-    userAccounts: PublicKey[]
+    userAccounts: PublicKey[],
+    bids?: PublicKey,
+    asks?: PublicKey,
+    makerRewards?: PublicKey,
+    crankerIdentity?: PublicKey,
+    cranker?: PublicKey
   ): TransactionInstruction {
     const data = Buffer.from(this.serialize());
     let keys: AccountKey[] = [];
@@ -601,6 +699,41 @@ export class consumeEventsInstruction {
         isWritable: true,
       });
     }
+    if (!!bids) {
+      keys.push({
+        pubkey: bids,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!asks) {
+      keys.push({
+        pubkey: asks,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!makerRewards) {
+      keys.push({
+        pubkey: makerRewards,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!crankerIdentity) {
+      keys.push({
+        pubkey: crankerIdentity,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!cranker) {
+      keys.push({
+        pubkey: cranker,
+        isSigner: true,
+        isWritable: false,
+      });
+    }
     return new TransactionInstruction({
       keys,
       programId,
@@ -688,6 +821,7 @@ export class swapInstruction {
   matchLimit: BN;
   side: number;
   hasDiscountTokenAccount: number;
+  hasOracleAccount: number;
   padding: Uint8Array;
   static schema: Schema = new Map([
     [
@@ -701,7 +835,8 @@ export class swapInstruction {
           ["matchLimit", "u64"],
           ["side", "u8"],
           ["hasDiscountTokenAccount", "u8"],
-          ["padding", [6]],
+          ["hasOracleAccount", "u8"],
+          ["padding", [5]],
         ],
       },
     ],
@@ -712,6 +847,7 @@ export class swapInstruction {
     matchLimit: BN;
     side: number;
     hasDiscountTokenAccount: number;
+    hasOracleAccount: number;
   }) {
     this.tag = new BN(2);
     this.baseQty = obj.baseQty;
@@ -719,7 +855,8 @@ export class swapInstruction {
     this.matchLimit = obj.matchLimit;
     this.side = obj.side;
     this.hasDiscountTokenAccount = obj.hasDiscountTokenAccount;
-    this.padding = new Uint8Array(6).fill(0);
+    this.hasOracleAccount = obj.hasOracleAccount;
+    this.padding = new Uint8Array(5).fill(0);
   }
   serialize(): Uint8Array {
     return serialize(swapInstruction.schema, this);
@@ -740,6 +877,7 @@ export class swapInstruction {
     userQuoteAccount: PublicKey,
     userOwner: PublicKey,
     discountTokenAccount?: PublicKey,
+    oracle?: PublicKey,
     feeReferralAccount?: PublicKey
   ): TransactionInstruction {
     const data = Buffer.from(this.serialize());
@@ -816,6 +954,13 @@ export class swapInstruction {
         isWritable: false,
       });
     }
+    if (!!oracle) {
+      keys.push({
+        pubkey: oracle,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
     if (!!feeReferralAccount) {
       keys.push({
         pubkey: feeReferralAccount,
@@ -883,6 +1028,53 @@ export class updateRoyaltiesInstruction {
     });
   }
 }
+export class migrateAccountInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      migrateAccountInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(11);
+  }
+  serialize(): Uint8Array {
+    return serialize(migrateAccountInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    account: PublicKey,
+    payer: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: account,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: payer,
+      isSigner: true,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
 export class createMarketInstruction {
   tag: BN;
   signerNonce: BN;
@@ -890,6 +1082,10 @@ export class createMarketInstruction {
   tickSize: BN;
   baseCurrencyMultiplier: BN;
   quoteCurrencyMultiplier: BN;
+  minOrderTickDistance: BN;
+  baseSymbol: Uint8Array;
+  quoteSymbol: Uint8Array;
+  marketName: Uint8Array;
   static schema: Schema = new Map([
     [
       createMarketInstruction,
@@ -902,6 +1098,10 @@ export class createMarketInstruction {
           ["tickSize", "u64"],
           ["baseCurrencyMultiplier", "u64"],
           ["quoteCurrencyMultiplier", "u64"],
+          ["minOrderTickDistance", "u64"],
+          ["baseSymbol", [16]],
+          ["quoteSymbol", [16]],
+          ["marketName", [32]],
         ],
       },
     ],
@@ -912,6 +1112,10 @@ export class createMarketInstruction {
     tickSize: BN;
     baseCurrencyMultiplier: BN;
     quoteCurrencyMultiplier: BN;
+    minOrderTickDistance: BN;
+    baseSymbol: Uint8Array;
+    quoteSymbol: Uint8Array;
+    marketName: Uint8Array;
   }) {
     this.tag = new BN(0);
     this.signerNonce = obj.signerNonce;
@@ -919,6 +1123,10 @@ export class createMarketInstruction {
     this.tickSize = obj.tickSize;
     this.baseCurrencyMultiplier = obj.baseCurrencyMultiplier;
     this.quoteCurrencyMultiplier = obj.quoteCurrencyMultiplier;
+    this.minOrderTickDistance = obj.minOrderTickDistance;
+    this.baseSymbol = obj.baseSymbol;
+    this.quoteSymbol = obj.quoteSymbol;
+    this.marketName = obj.marketName;
   }
   serialize(): Uint8Array {
     return serialize(createMarketInstruction.schema, this);
@@ -933,7 +1141,12 @@ export class createMarketInstruction {
     eventQueue: PublicKey,
     asks: PublicKey,
     bids: PublicKey,
-    tokenMetadata: PublicKey
+    tokenMetadata: PublicKey,
+    createMarketConfig: PublicKey,
+    feePayer?: PublicKey,
+    feeSource?: PublicKey,
+    treasury?: PublicKey,
+    feeProgram?: PublicKey
   ): TransactionInstruction {
     const data = Buffer.from(this.serialize());
     let keys: AccountKey[] = [];
@@ -982,6 +1195,3269 @@ export class createMarketInstruction {
       isSigner: false,
       isWritable: false,
     });
+    keys.push({
+      pubkey: createMarketConfig,
+      isSigner: false,
+      isWritable: false,
+    });
+    if (!!feePayer) {
+      keys.push({
+        pubkey: feePayer,
+        isSigner: true,
+        isWritable: true,
+      });
+    }
+    if (!!feeSource) {
+      keys.push({
+        pubkey: feeSource,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!treasury) {
+      keys.push({
+        pubkey: treasury,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!feeProgram) {
+      keys.push({
+        pubkey: feeProgram,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class createMakerRewardsInstruction {
+  tag: BN;
+  epochLength: BN;
+  maxEntries: BN;
+  static schema: Schema = new Map([
+    [
+      createMakerRewardsInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["epochLength", "u64"],
+          ["maxEntries", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { epochLength: BN; maxEntries: BN }) {
+    this.tag = new BN(12);
+    this.epochLength = obj.epochLength;
+    this.maxEntries = obj.maxEntries;
+  }
+  serialize(): Uint8Array {
+    return serialize(createMakerRewardsInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    makerRewards: PublicKey,
+    marketAdmin: PublicKey,
+    feePayer: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: makerRewards,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: feePayer,
+      isSigner: true,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setAccountMetadataInstruction {
+  tag: BN;
+  metadata: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      setAccountMetadataInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["metadata", [32]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { metadata: Uint8Array }) {
+    this.tag = new BN(13);
+    this.metadata = obj.metadata;
+  }
+  serialize(): Uint8Array {
+    return serialize(setAccountMetadataInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class hashOrderbookInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      hashOrderbookInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(14);
+  }
+  serialize(): Uint8Array {
+    return serialize(hashOrderbookInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setMarketStatusInstruction {
+  tag: BN;
+  status: BN;
+  static schema: Schema = new Map([
+    [
+      setMarketStatusInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["status", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { status: BN }) {
+    this.tag = new BN(15);
+    this.status = obj.status;
+  }
+  serialize(): Uint8Array {
+    return serialize(setMarketStatusInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class forceConsumeEventsInstruction {
+  tag: BN;
+  maxIterations: BN;
+  escrowCapacity: BN;
+  static schema: Schema = new Map([
+    [
+      forceConsumeEventsInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["maxIterations", "u64"],
+          ["escrowCapacity", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { maxIterations: BN; escrowCapacity: BN }) {
+    this.tag = new BN(16);
+    this.maxIterations = obj.maxIterations;
+    this.escrowCapacity = obj.escrowCapacity;
+  }
+  serialize(): Uint8Array {
+    return serialize(forceConsumeEventsInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    marketAdmin: PublicKey,
+    escrow: PublicKey,
+    userAccounts: PublicKey[]
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: escrow,
+      isSigner: false,
+      isWritable: true,
+    });
+    for (let k of userAccounts) {
+      keys.push({
+        pubkey: k,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class claimEscrowInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      claimEscrowInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(17);
+  }
+  serialize(): Uint8Array {
+    return serialize(claimEscrowInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    escrow: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: escrow,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class updateMarketMetadataInstruction {
+  tag: BN;
+  baseSymbol: Uint8Array;
+  quoteSymbol: Uint8Array;
+  marketName: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      updateMarketMetadataInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["baseSymbol", [16]],
+          ["quoteSymbol", [16]],
+          ["marketName", [32]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: {
+    baseSymbol: Uint8Array;
+    quoteSymbol: Uint8Array;
+    marketName: Uint8Array;
+  }) {
+    this.tag = new BN(18);
+    this.baseSymbol = obj.baseSymbol;
+    this.quoteSymbol = obj.quoteSymbol;
+    this.marketName = obj.marketName;
+  }
+  serialize(): Uint8Array {
+    return serialize(updateMarketMetadataInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setOracleInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      setOracleInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(19);
+  }
+  serialize(): Uint8Array {
+    return serialize(setOracleInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey,
+    oracle?: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    if (!!oracle) {
+      keys.push({
+        pubkey: oracle,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setFillPrivacyInstruction {
+  tag: BN;
+  hideFillAccounts: BN;
+  static schema: Schema = new Map([
+    [
+      setFillPrivacyInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["hideFillAccounts", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { hideFillAccounts: BN }) {
+    this.tag = new BN(20);
+    this.hideFillAccounts = obj.hideFillAccounts;
+  }
+  serialize(): Uint8Array {
+    return serialize(setFillPrivacyInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setBatchAuctionInstruction {
+  tag: BN;
+  batchSlots: BN;
+  capacity: BN;
+  static schema: Schema = new Map([
+    [
+      setBatchAuctionInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["batchSlots", "u64"],
+          ["capacity", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { batchSlots: BN; capacity: BN }) {
+    this.tag = new BN(21);
+    this.batchSlots = obj.batchSlots;
+    this.capacity = obj.capacity;
+  }
+  serialize(): Uint8Array {
+    return serialize(setBatchAuctionInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    batchAuction: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: batchAuction,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class newBatchOrderInstruction {
+  tag: BN;
+  limitPrice: BN;
+  baseQty: BN;
+  side: number;
+  hasDiscountTokenAccount: number;
+  padding: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      newBatchOrderInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["limitPrice", "u64"],
+          ["baseQty", "u64"],
+          ["side", "u8"],
+          ["hasDiscountTokenAccount", "u8"],
+          ["padding", [6]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: {
+    limitPrice: BN;
+    baseQty: BN;
+    side: number;
+    hasDiscountTokenAccount: number;
+  }) {
+    this.tag = new BN(22);
+    this.limitPrice = obj.limitPrice;
+    this.baseQty = obj.baseQty;
+    this.side = obj.side;
+    this.hasDiscountTokenAccount = obj.hasDiscountTokenAccount;
+    this.padding = new Uint8Array(6).fill(0);
+  }
+  serialize(): Uint8Array {
+    return serialize(newBatchOrderInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    splTokenProgram: PublicKey,
+    market: PublicKey,
+    batchAuction: PublicKey,
+    baseVault: PublicKey,
+    quoteVault: PublicKey,
+    user: PublicKey,
+    userTokenAccount: PublicKey,
+    userOwner: PublicKey,
+    discountTokenAccount?: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: splTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: batchAuction,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: baseVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: quoteVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userTokenAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: false,
+    });
+    if (!!discountTokenAccount) {
+      keys.push({
+        pubkey: discountTokenAccount,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class matchBatchInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      matchBatchInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(23);
+  }
+  serialize(): Uint8Array {
+    return serialize(matchBatchInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    batchAuction: PublicKey,
+    userAccounts: PublicKey[]
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: batchAuction,
+      isSigner: false,
+      isWritable: true,
+    });
+    for (let k of userAccounts) {
+      keys.push({
+        pubkey: k,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class createSessionInstruction {
+  tag: BN;
+  sessionKey: Uint8Array;
+  expiry: BN;
+  capacity: BN;
+  static schema: Schema = new Map([
+    [
+      createSessionInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["sessionKey", [32]],
+          ["expiry", "u64"],
+          ["capacity", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { sessionKey: Uint8Array; expiry: BN; capacity: BN }) {
+    this.tag = new BN(24);
+    this.sessionKey = obj.sessionKey;
+    this.expiry = obj.expiry;
+    this.capacity = obj.capacity;
+  }
+  serialize(): Uint8Array {
+    return serialize(createSessionInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    session: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: session,
+      isSigner: false,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class cancelSessionOrdersInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      cancelSessionOrdersInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(25);
+  }
+  serialize(): Uint8Array {
+    return serialize(cancelSessionOrdersInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    user: PublicKey,
+    session: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: session,
+      isSigner: false,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setDesignatedMarketMakerInstruction {
+  tag: BN;
+  userAccount: Uint8Array;
+  capacity: BN;
+  isDesignated: number;
+  padding: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      setDesignatedMarketMakerInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["userAccount", [32]],
+          ["capacity", "u64"],
+          ["isDesignated", "u8"],
+          ["padding", [7]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: {
+    userAccount: Uint8Array;
+    capacity: BN;
+    isDesignated: number;
+  }) {
+    this.tag = new BN(26);
+    this.userAccount = obj.userAccount;
+    this.capacity = obj.capacity;
+    this.isDesignated = obj.isDesignated;
+    this.padding = new Uint8Array(7).fill(0);
+  }
+  serialize(): Uint8Array {
+    return serialize(setDesignatedMarketMakerInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    dmmRegistry: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: dmmRegistry,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class getMarketInfoInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      getMarketInfoInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(27);
+  }
+  serialize(): Uint8Array {
+    return serialize(getMarketInfoInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setCrankFeeShareInstruction {
+  tag: BN;
+  crankFeeShareBps: BN;
+  static schema: Schema = new Map([
+    [
+      setCrankFeeShareInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["crankFeeShareBps", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { crankFeeShareBps: BN }) {
+    this.tag = new BN(28);
+    this.crankFeeShareBps = obj.crankFeeShareBps;
+  }
+  serialize(): Uint8Array {
+    return serialize(setCrankFeeShareInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setVolatilityFeeInstruction {
+  tag: BN;
+  windowSlots: BN;
+  thresholdBps: BN;
+  maxMultiplier: BN;
+  static schema: Schema = new Map([
+    [
+      setVolatilityFeeInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["windowSlots", "u64"],
+          ["thresholdBps", "u64"],
+          ["maxMultiplier", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { windowSlots: BN; thresholdBps: BN; maxMultiplier: BN }) {
+    this.tag = new BN(29);
+    this.windowSlots = obj.windowSlots;
+    this.thresholdBps = obj.thresholdBps;
+    this.maxMultiplier = obj.maxMultiplier;
+  }
+  serialize(): Uint8Array {
+    return serialize(setVolatilityFeeInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class createCrankerIdentityInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      createCrankerIdentityInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(30);
+  }
+  serialize(): Uint8Array {
+    return serialize(createCrankerIdentityInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    crankerIdentity: PublicKey,
+    cranker: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: crankerIdentity,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: cranker,
+      isSigner: true,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class freezeAndExportInstruction {
+  tag: BN;
+  migrationProgram: Uint8Array;
+  migrationMarket: Uint8Array;
+  migrationSignerNonce: number;
+  padding: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      freezeAndExportInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["migrationProgram", [32]],
+          ["migrationMarket", [32]],
+          ["migrationSignerNonce", "u8"],
+          ["padding", [7]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: {
+    migrationProgram: Uint8Array;
+    migrationMarket: Uint8Array;
+    migrationSignerNonce: number;
+  }) {
+    this.tag = new BN(31);
+    this.migrationProgram = obj.migrationProgram;
+    this.migrationMarket = obj.migrationMarket;
+    this.migrationSignerNonce = obj.migrationSignerNonce;
+    this.padding = new Uint8Array(7).fill(0);
+  }
+  serialize(): Uint8Array {
+    return serialize(freezeAndExportInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    splTokenProgram: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey,
+    baseVault: PublicKey,
+    quoteVault: PublicKey,
+    marketSigner: PublicKey,
+    migrationMarketSigner: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: splTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: baseVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: quoteVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketSigner,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: migrationMarketSigner,
+      isSigner: false,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class importMarketInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      importMarketInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(32);
+  }
+  serialize(): Uint8Array {
+    return serialize(importMarketInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey,
+    sourceMarket: PublicKey,
+    sourceBaseVault: PublicKey,
+    sourceQuoteVault: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: sourceMarket,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: sourceBaseVault,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: sourceQuoteVault,
+      isSigner: false,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setMinRestingSlotsInstruction {
+  tag: BN;
+  minRestingSlots: BN;
+  static schema: Schema = new Map([
+    [
+      setMinRestingSlotsInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["minRestingSlots", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { minRestingSlots: BN }) {
+    this.tag = new BN(33);
+    this.minRestingSlots = obj.minRestingSlots;
+  }
+  serialize(): Uint8Array {
+    return serialize(setMinRestingSlotsInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class heartbeatInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      heartbeatInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(34);
+  }
+  serialize(): Uint8Array {
+    return serialize(heartbeatInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    session?: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: false,
+    });
+    if (!!session) {
+      keys.push({
+        pubkey: session,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class relinkOrderbookInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      relinkOrderbookInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(35);
+  }
+  serialize(): Uint8Array {
+    return serialize(relinkOrderbookInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    newOrderbook: PublicKey,
+    newEventQueue: PublicKey,
+    newBids: PublicKey,
+    newAsks: PublicKey,
+    targetLamportsAccount: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: newOrderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: newEventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: newBids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: newAsks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: targetLamportsAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class createSettleAllowlistInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      createSettleAllowlistInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(36);
+  }
+  serialize(): Uint8Array {
+    return serialize(createSettleAllowlistInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    settleAllowlist: PublicKey,
+    destinations: PublicKey[]
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: settleAllowlist,
+      isSigner: false,
+      isWritable: true,
+    });
+    for (let k of destinations) {
+      keys.push({
+        pubkey: k,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class previewNewOrderInstruction {
+  tag: BN;
+  clientOrderId: BN;
+  limitPrice: BN;
+  maxBaseQty: BN;
+  maxQuoteQty: BN;
+  matchLimit: BN;
+  expiryTimestamp: BN;
+  displayBaseQty: BN;
+  minBaseQty: BN;
+  side: number;
+  orderType: number;
+  selfTradeBehavior: number;
+  hasDiscountTokenAccount: number;
+  hasOracleAccount: number;
+  hasSession: number;
+  hasDmmRegistry: number;
+  notifyOnCross: number;
+  quantityMode: number;
+  useFreeBalances: number;
+  padding: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      previewNewOrderInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["clientOrderId", "u128"],
+          ["limitPrice", "u64"],
+          ["maxBaseQty", "u64"],
+          ["maxQuoteQty", "u64"],
+          ["matchLimit", "u64"],
+          ["expiryTimestamp", "u64"],
+          ["displayBaseQty", "u64"],
+          ["minBaseQty", "u64"],
+          ["side", "u8"],
+          ["orderType", "u8"],
+          ["selfTradeBehavior", "u8"],
+          ["hasDiscountTokenAccount", "u8"],
+          ["hasOracleAccount", "u8"],
+          ["hasSession", "u8"],
+          ["hasDmmRegistry", "u8"],
+          ["notifyOnCross", "u8"],
+          ["quantityMode", "u8"],
+          ["useFreeBalances", "u8"],
+          ["padding", [6]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: {
+    clientOrderId: BN;
+    limitPrice: BN;
+    maxBaseQty: BN;
+    maxQuoteQty: BN;
+    matchLimit: BN;
+    expiryTimestamp: BN;
+    displayBaseQty: BN;
+    minBaseQty: BN;
+    side: number;
+    orderType: number;
+    selfTradeBehavior: number;
+    hasDiscountTokenAccount: number;
+    hasOracleAccount: number;
+    hasSession: number;
+    hasDmmRegistry: number;
+    notifyOnCross: number;
+    quantityMode: number;
+    useFreeBalances: number;
+  }) {
+    this.tag = new BN(37);
+    this.clientOrderId = obj.clientOrderId;
+    this.limitPrice = obj.limitPrice;
+    this.maxBaseQty = obj.maxBaseQty;
+    this.maxQuoteQty = obj.maxQuoteQty;
+    this.matchLimit = obj.matchLimit;
+    this.expiryTimestamp = obj.expiryTimestamp;
+    this.displayBaseQty = obj.displayBaseQty;
+    this.minBaseQty = obj.minBaseQty;
+    this.side = obj.side;
+    this.orderType = obj.orderType;
+    this.selfTradeBehavior = obj.selfTradeBehavior;
+    this.hasDiscountTokenAccount = obj.hasDiscountTokenAccount;
+    this.hasOracleAccount = obj.hasOracleAccount;
+    this.hasSession = obj.hasSession;
+    this.hasDmmRegistry = obj.hasDmmRegistry;
+    this.notifyOnCross = obj.notifyOnCross;
+    this.quantityMode = obj.quantityMode;
+    this.useFreeBalances = obj.useFreeBalances;
+    this.padding = new Uint8Array(6).fill(0);
+  }
+  serialize(): Uint8Array {
+    return serialize(previewNewOrderInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    splTokenProgram: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    baseVault: PublicKey,
+    quoteVault: PublicKey,
+    user: PublicKey,
+    userTokenAccount: PublicKey,
+    userOwner: PublicKey,
+    discountTokenAccount?: PublicKey,
+    oracle?: PublicKey,
+    session?: PublicKey,
+    dmmRegistry?: PublicKey,
+    feeReferralAccount?: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: splTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: baseVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: quoteVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userTokenAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: true,
+    });
+    if (!!discountTokenAccount) {
+      keys.push({
+        pubkey: discountTokenAccount,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    if (!!oracle) {
+      keys.push({
+        pubkey: oracle,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    if (!!session) {
+      keys.push({
+        pubkey: session,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!dmmRegistry) {
+      keys.push({
+        pubkey: dmmRegistry,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    if (!!feeReferralAccount) {
+      keys.push({
+        pubkey: feeReferralAccount,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class cancelAllOrdersInstruction {
+  tag: BN;
+  maxOrders: BN;
+  static schema: Schema = new Map([
+    [
+      cancelAllOrdersInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["maxOrders", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { maxOrders: BN }) {
+    this.tag = new BN(38);
+    this.maxOrders = obj.maxOrders;
+  }
+  serialize(): Uint8Array {
+    return serialize(cancelAllOrdersInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class replaceOrderInstruction {
+  tag: BN;
+  orderId: BN;
+  orderIndex: BN;
+  isClientId: number;
+  padding: Uint8Array;
+  clientOrderId: BN;
+  limitPrice: BN;
+  maxBaseQty: BN;
+  maxQuoteQty: BN;
+  matchLimit: BN;
+  expiryTimestamp: BN;
+  displayBaseQty: BN;
+  minBaseQty: BN;
+  side: number;
+  orderType: number;
+  selfTradeBehavior: number;
+  hasDiscountTokenAccount: number;
+  hasOracleAccount: number;
+  hasSession: number;
+  hasDmmRegistry: number;
+  notifyOnCross: number;
+  quantityMode: number;
+  useFreeBalances: number;
+  newOrderPadding: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      replaceOrderInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["orderId", "u128"],
+          ["orderIndex", "u64"],
+          ["isClientId", "u8"],
+          ["padding", [7]],
+          ["clientOrderId", "u128"],
+          ["limitPrice", "u64"],
+          ["maxBaseQty", "u64"],
+          ["maxQuoteQty", "u64"],
+          ["matchLimit", "u64"],
+          ["expiryTimestamp", "u64"],
+          ["displayBaseQty", "u64"],
+          ["minBaseQty", "u64"],
+          ["side", "u8"],
+          ["orderType", "u8"],
+          ["selfTradeBehavior", "u8"],
+          ["hasDiscountTokenAccount", "u8"],
+          ["hasOracleAccount", "u8"],
+          ["hasSession", "u8"],
+          ["hasDmmRegistry", "u8"],
+          ["notifyOnCross", "u8"],
+          ["quantityMode", "u8"],
+          ["useFreeBalances", "u8"],
+          ["newOrderPadding", [6]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: {
+    orderId: BN;
+    orderIndex: BN;
+    isClientId: number;
+    clientOrderId: BN;
+    limitPrice: BN;
+    maxBaseQty: BN;
+    maxQuoteQty: BN;
+    matchLimit: BN;
+    expiryTimestamp: BN;
+    displayBaseQty: BN;
+    minBaseQty: BN;
+    side: number;
+    orderType: number;
+    selfTradeBehavior: number;
+    hasDiscountTokenAccount: number;
+    hasOracleAccount: number;
+    hasSession: number;
+    hasDmmRegistry: number;
+    notifyOnCross: number;
+    quantityMode: number;
+    useFreeBalances: number;
+  }) {
+    this.tag = new BN(39);
+    this.orderId = obj.orderId;
+    this.orderIndex = obj.orderIndex;
+    this.isClientId = obj.isClientId;
+    this.padding = new Uint8Array(7).fill(0);
+    this.clientOrderId = obj.clientOrderId;
+    this.limitPrice = obj.limitPrice;
+    this.maxBaseQty = obj.maxBaseQty;
+    this.maxQuoteQty = obj.maxQuoteQty;
+    this.matchLimit = obj.matchLimit;
+    this.expiryTimestamp = obj.expiryTimestamp;
+    this.displayBaseQty = obj.displayBaseQty;
+    this.minBaseQty = obj.minBaseQty;
+    this.side = obj.side;
+    this.orderType = obj.orderType;
+    this.selfTradeBehavior = obj.selfTradeBehavior;
+    this.hasDiscountTokenAccount = obj.hasDiscountTokenAccount;
+    this.hasOracleAccount = obj.hasOracleAccount;
+    this.hasSession = obj.hasSession;
+    this.hasDmmRegistry = obj.hasDmmRegistry;
+    this.notifyOnCross = obj.notifyOnCross;
+    this.quantityMode = obj.quantityMode;
+    this.useFreeBalances = obj.useFreeBalances;
+    this.newOrderPadding = new Uint8Array(6).fill(0);
+  }
+  serialize(): Uint8Array {
+    return serialize(replaceOrderInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    splTokenProgram: PublicKey,
+    systemProgram: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    baseVault: PublicKey,
+    quoteVault: PublicKey,
+    user: PublicKey,
+    userTokenAccount: PublicKey,
+    userOwner: PublicKey,
+    discountTokenAccount?: PublicKey,
+    oracle?: PublicKey,
+    session?: PublicKey,
+    dmmRegistry?: PublicKey,
+    feeReferralAccount?: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: splTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: baseVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: quoteVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userTokenAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: true,
+    });
+    if (!!discountTokenAccount) {
+      keys.push({
+        pubkey: discountTokenAccount,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    if (!!oracle) {
+      keys.push({
+        pubkey: oracle,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    if (!!session) {
+      keys.push({
+        pubkey: session,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    if (!!dmmRegistry) {
+      keys.push({
+        pubkey: dmmRegistry,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    if (!!feeReferralAccount) {
+      keys.push({
+        pubkey: feeReferralAccount,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class reclaimOrderbookInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      reclaimOrderbookInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(40);
+  }
+  serialize(): Uint8Array {
+    return serialize(reclaimOrderbookInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    targetLamportsAccount: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: targetLamportsAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setCreateMarketConfigInstruction {
+  tag: BN;
+  listingFee: BN;
+  feeMint: Uint8Array;
+  treasury: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      setCreateMarketConfigInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["listingFee", "u64"],
+          ["feeMint", [32]],
+          ["treasury", [32]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: {
+    listingFee: BN;
+    feeMint: Uint8Array;
+    treasury: Uint8Array;
+  }) {
+    this.tag = new BN(41);
+    this.listingFee = obj.listingFee;
+    this.feeMint = obj.feeMint;
+    this.treasury = obj.treasury;
+  }
+  serialize(): Uint8Array {
+    return serialize(setCreateMarketConfigInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    createMarketConfig: PublicKey,
+    sweepAuthority: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: createMarketConfig,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: sweepAuthority,
+      isSigner: true,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setReferralShareInstruction {
+  tag: BN;
+  referralShareBps: BN;
+  static schema: Schema = new Map([
+    [
+      setReferralShareInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["referralShareBps", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { referralShareBps: BN }) {
+    this.tag = new BN(42);
+    this.referralShareBps = obj.referralShareBps;
+  }
+  serialize(): Uint8Array {
+    return serialize(setReferralShareInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setMakerRebateInstruction {
+  tag: BN;
+  makerRebateBps: BN;
+  static schema: Schema = new Map([
+    [
+      setMakerRebateInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["makerRebateBps", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { makerRebateBps: BN }) {
+    this.tag = new BN(43);
+    this.makerRebateBps = obj.makerRebateBps;
+  }
+  serialize(): Uint8Array {
+    return serialize(setMakerRebateInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setEmergencyAuthorityInstruction {
+  tag: BN;
+  emergencyAuthority: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      setEmergencyAuthorityInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["emergencyAuthority", [32]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { emergencyAuthority: Uint8Array }) {
+    this.tag = new BN(44);
+    this.emergencyAuthority = obj.emergencyAuthority;
+  }
+  serialize(): Uint8Array {
+    return serialize(setEmergencyAuthorityInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class emergencyHaltInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      emergencyHaltInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(45);
+  }
+  serialize(): Uint8Array {
+    return serialize(emergencyHaltInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    emergencyAuthority: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: emergencyAuthority,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class updateMarketAdminInstruction {
+  tag: BN;
+  newAdmin: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      updateMarketAdminInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["newAdmin", [32]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { newAdmin: Uint8Array }) {
+    this.tag = new BN(46);
+    this.newAdmin = obj.newAdmin;
+  }
+  serialize(): Uint8Array {
+    return serialize(updateMarketAdminInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setCancelFeeInstruction {
+  tag: BN;
+  cancelFee: BN;
+  cancelFeeSlots: BN;
+  static schema: Schema = new Map([
+    [
+      setCancelFeeInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["cancelFee", "u64"],
+          ["cancelFeeSlots", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { cancelFee: BN; cancelFeeSlots: BN }) {
+    this.tag = new BN(47);
+    this.cancelFee = obj.cancelFee;
+    this.cancelFeeSlots = obj.cancelFeeSlots;
+  }
+  serialize(): Uint8Array {
+    return serialize(setCancelFeeInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class nominateAdminInstruction {
+  tag: BN;
+  pendingAdmin: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      nominateAdminInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["pendingAdmin", [32]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { pendingAdmin: Uint8Array }) {
+    this.tag = new BN(48);
+    this.pendingAdmin = obj.pendingAdmin;
+  }
+  serialize(): Uint8Array {
+    return serialize(nominateAdminInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class acceptAdminInstruction {
+  tag: BN;
+  static schema: Schema = new Map([
+    [
+      acceptAdminInstruction,
+      {
+        kind: "struct",
+        fields: [["tag", "u64"]],
+      },
+    ],
+  ]);
+  constructor() {
+    this.tag = new BN(49);
+  }
+  serialize(): Uint8Array {
+    return serialize(acceptAdminInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    pendingAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: pendingAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class archiveUserAccountInstruction {
+  tag: BN;
+  accountIndex: BN;
+  static schema: Schema = new Map([
+    [
+      archiveUserAccountInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["accountIndex", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { accountIndex: BN }) {
+    this.tag = new BN(50);
+    this.accountIndex = obj.accountIndex;
+  }
+  serialize(): Uint8Array {
+    return serialize(archiveUserAccountInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    archive: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: archive,
+      isSigner: false,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class restoreUserAccountInstruction {
+  tag: BN;
+  maxOrders: BN;
+  static schema: Schema = new Map([
+    [
+      restoreUserAccountInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["maxOrders", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { maxOrders: BN }) {
+    this.tag = new BN(51);
+    this.maxOrders = obj.maxOrders;
+  }
+  serialize(): Uint8Array {
+    return serialize(restoreUserAccountInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    systemProgram: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    archive: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: archive,
+      isSigner: false,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setHaltFlagsInstruction {
+  tag: BN;
+  haltFlags: BN;
+  static schema: Schema = new Map([
+    [
+      setHaltFlagsInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["haltFlags", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { haltFlags: BN }) {
+    this.tag = new BN(52);
+    this.haltFlags = obj.haltFlags;
+  }
+  serialize(): Uint8Array {
+    return serialize(setHaltFlagsInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class updateMarketParamsInstruction {
+  tag: BN;
+  tickSize: BN;
+  minBaseOrderSize: BN;
+  static schema: Schema = new Map([
+    [
+      updateMarketParamsInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["tickSize", "u64"],
+          ["minBaseOrderSize", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { tickSize: BN; minBaseOrderSize: BN }) {
+    this.tag = new BN(53);
+    this.tickSize = obj.tickSize;
+    this.minBaseOrderSize = obj.minBaseOrderSize;
+  }
+  serialize(): Uint8Array {
+    return serialize(updateMarketParamsInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class pruneExpiredOrdersInstruction {
+  tag: BN;
+  maxOrders: BN;
+  noOpErr: BN;
+  static schema: Schema = new Map([
+    [
+      pruneExpiredOrdersInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["maxOrders", "u64"],
+          ["noOpErr", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { maxOrders: BN; noOpErr: BN }) {
+    this.tag = new BN(54);
+    this.maxOrders = obj.maxOrders;
+    this.noOpErr = obj.noOpErr;
+  }
+  serialize(): Uint8Array {
+    return serialize(pruneExpiredOrdersInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    orderbook: PublicKey,
+    eventQueue: PublicKey,
+    bids: PublicKey,
+    asks: PublicKey,
+    rewardTarget: PublicKey,
+    userAccounts: PublicKey[]
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: orderbook,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: eventQueue,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: bids,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: asks,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: rewardTarget,
+      isSigner: false,
+      isWritable: true,
+    });
+    for (let k of userAccounts) {
+      keys.push({
+        pubkey: k,
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class setCrankRewardScalingInstruction {
+  tag: BN;
+  ageSlots: BN;
+  maxMultiplier: BN;
+  static schema: Schema = new Map([
+    [
+      setCrankRewardScalingInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["ageSlots", "u64"],
+          ["maxMultiplier", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { ageSlots: BN; maxMultiplier: BN }) {
+    this.tag = new BN(55);
+    this.ageSlots = obj.ageSlots;
+    this.maxMultiplier = obj.maxMultiplier;
+  }
+  serialize(): Uint8Array {
+    return serialize(setCrankRewardScalingInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    market: PublicKey,
+    marketAdmin: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketAdmin,
+      isSigner: true,
+      isWritable: false,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class depositInstruction {
+  tag: BN;
+  baseQty: BN;
+  quoteQty: BN;
+  static schema: Schema = new Map([
+    [
+      depositInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["baseQty", "u64"],
+          ["quoteQty", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { baseQty: BN; quoteQty: BN }) {
+    this.tag = new BN(56);
+    this.baseQty = obj.baseQty;
+    this.quoteQty = obj.quoteQty;
+  }
+  serialize(): Uint8Array {
+    return serialize(depositInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    splTokenProgram: PublicKey,
+    market: PublicKey,
+    baseVault: PublicKey,
+    quoteVault: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    sourceBaseAccount: PublicKey,
+    sourceQuoteAccount: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: splTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: baseVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: quoteVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: sourceBaseAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: sourceQuoteAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class withdrawInstruction {
+  tag: BN;
+  baseQty: BN;
+  quoteQty: BN;
+  static schema: Schema = new Map([
+    [
+      withdrawInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["baseQty", "u64"],
+          ["quoteQty", "u64"],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { baseQty: BN; quoteQty: BN }) {
+    this.tag = new BN(57);
+    this.baseQty = obj.baseQty;
+    this.quoteQty = obj.quoteQty;
+  }
+  serialize(): Uint8Array {
+    return serialize(withdrawInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    splTokenProgram: PublicKey,
+    market: PublicKey,
+    baseVault: PublicKey,
+    quoteVault: PublicKey,
+    marketSigner: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    destinationBaseAccount: PublicKey,
+    destinationQuoteAccount: PublicKey,
+    settleAllowlist?: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: splTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: baseVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: quoteVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketSigner,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: destinationBaseAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: destinationQuoteAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    if (!!settleAllowlist) {
+      keys.push({
+        pubkey: settleAllowlist,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId,
+      data,
+    });
+  }
+}
+export class settleToAssociatedAccountsInstruction {
+  tag: BN;
+  unwrapSol: number;
+  padding: Uint8Array;
+  static schema: Schema = new Map([
+    [
+      settleToAssociatedAccountsInstruction,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["unwrapSol", "u8"],
+          ["padding", [7]],
+        ],
+      },
+    ],
+  ]);
+  constructor(obj: { unwrapSol: number }) {
+    this.tag = new BN(58);
+    this.unwrapSol = obj.unwrapSol;
+    this.padding = new Uint8Array(7).fill(0);
+  }
+  serialize(): Uint8Array {
+    return serialize(settleToAssociatedAccountsInstruction.schema, this);
+  }
+  getInstruction(
+    programId: PublicKey,
+    splTokenProgram: PublicKey,
+    systemProgram: PublicKey,
+    associatedTokenProgram: PublicKey,
+    rentSysvar: PublicKey,
+    market: PublicKey,
+    baseMint: PublicKey,
+    quoteMint: PublicKey,
+    baseVault: PublicKey,
+    quoteVault: PublicKey,
+    marketSigner: PublicKey,
+    user: PublicKey,
+    userOwner: PublicKey,
+    destinationBaseAccount: PublicKey,
+    destinationQuoteAccount: PublicKey,
+    settleAllowlist?: PublicKey
+  ): TransactionInstruction {
+    const data = Buffer.from(this.serialize());
+    let keys: AccountKey[] = [];
+    keys.push({
+      pubkey: splTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: systemProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: rentSysvar,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: market,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: baseMint,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: quoteMint,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: baseVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: quoteVault,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: marketSigner,
+      isSigner: false,
+      isWritable: false,
+    });
+    keys.push({
+      pubkey: user,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: userOwner,
+      isSigner: true,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: destinationBaseAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    keys.push({
+      pubkey: destinationQuoteAccount,
+      isSigner: false,
+      isWritable: true,
+    });
+    if (!!settleAllowlist) {
+      keys.push({
+        pubkey: settleAllowlist,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
     return new TransactionInstruction({
       keys,
       programId,
@@ -1,14 +1,30 @@
 import { Connection, PublicKey } from "@solana/web3.js";
 import BN from "bn.js";
 import { deserialize, deserializeUnchecked, Schema } from "borsh";
+import { decodeFixedString } from "./utils";
 
 export const CALLBACK_INFO_LEN = 33;
 
+/** The length in slots of a market's order flow window */
+export const ORDER_FLOW_WINDOW_SLOTS = 9_000;
+
 export enum AccountTag {
   Initialized = 0,
-  MarketState = 1,
-  UserAccount = 2,
+  // Accounts of the first release, which should be migrated with `migrateAccount`
+  MarketStateV1 = 1,
+  UserAccountV1 = 2,
   Close = 3,
+  MarketState = 4,
+  UserAccount = 5,
+  MakerRewards = 6,
+  Escrow = 7,
+  BatchAuction = 8,
+  Session = 9,
+  DmmRegistry = 10,
+  CrankerIdentity = 11,
+  SettleAllowlist = 12,
+  CreateMarketConfig = 13,
+  UserAccountArchive = 14,
 }
 
 export enum SelfTradeBehavior {
@@ -17,6 +33,17 @@ export enum SelfTradeBehavior {
   AbortTransaction = 2,
 }
 
+export enum MarketStatus {
+  Active = 0,
+  Halted = 1,
+  Migrating = 2,
+}
+
+/** The halt flag rejecting new bids */
+export const HALT_BIDS_MASK = 1;
+/** The halt flag rejecting new asks */
+export const HALT_ASKS_MASK = 1 << 1;
+
 export enum MarketFeeType {
   Default = 0,
   Stable = 1,
@@ -41,6 +68,46 @@ export class MarketState {
   quoteCurrencyMultiplier: BN;
   signerNonce: number;
   feeType: number;
+  minOrderTickDistance: number;
+  status: MarketStatus;
+  hideFillAccounts: boolean;
+  baseSymbol: string;
+  quoteSymbol: string;
+  marketName: string;
+  oracle: PublicKey;
+  batchSlots: BN;
+  crankFeeShareBps: BN;
+  volatilityWindowSlots: BN;
+  volatilityThresholdBps: BN;
+  maxVolatilityMultiplier: BN;
+  referencePrice: BN;
+  referenceSlot: BN;
+  lastPrice: BN;
+  migrationProgram: PublicKey;
+  migrationMarket: PublicKey;
+  migrationUnlockTimestamp: BN;
+  orderFlowWindow: BN;
+  newOrders: BN;
+  cancels: BN;
+  fills: BN;
+  previousNewOrders: BN;
+  previousCancels: BN;
+  previousFills: BN;
+  minRestingSlots: BN;
+  bestBid: BN;
+  bestAsk: BN;
+  topOfBookSlot: BN;
+  referralShareBps: BN;
+  makerRebateBps: BN;
+  emergencyAuthority: PublicKey;
+  emergencyHaltTimestamp: BN;
+  cancelFee: BN;
+  cancelFeeSlots: BN;
+  pendingAdmin: PublicKey;
+  haltFlags: BN;
+  crankRewardAgeSlots: BN;
+  maxCrankRewardMultiplier: BN;
+  pendingEventsSlot: BN;
 
   static schema: Schema = new Map([
     [
@@ -66,7 +133,47 @@ export class MarketState {
           ["quoteCurrencyMultiplier", "u64"],
           ["signerNonce", "u8"],
           ["feeType", "u8"],
-          ["padding", [6]],
+          ["minOrderTickDistance", "u16"],
+          ["status", "u8"],
+          ["hideFillAccounts", "u8"],
+          ["padding", [2]],
+          ["baseSymbol", [16]],
+          ["quoteSymbol", [16]],
+          ["marketName", [32]],
+          ["oracle", [32]],
+          ["batchSlots", "u64"],
+          ["crankFeeShareBps", "u64"],
+          ["volatilityWindowSlots", "u64"],
+          ["volatilityThresholdBps", "u64"],
+          ["maxVolatilityMultiplier", "u64"],
+          ["referencePrice", "u64"],
+          ["referenceSlot", "u64"],
+          ["lastPrice", "u64"],
+          ["migrationProgram", [32]],
+          ["migrationMarket", [32]],
+          ["migrationUnlockTimestamp", "u64"],
+          ["orderFlowWindow", "u64"],
+          ["newOrders", "u64"],
+          ["cancels", "u64"],
+          ["fills", "u64"],
+          ["previousNewOrders", "u64"],
+          ["previousCancels", "u64"],
+          ["previousFills", "u64"],
+          ["minRestingSlots", "u64"],
+          ["bestBid", "u64"],
+          ["bestAsk", "u64"],
+          ["topOfBookSlot", "u64"],
+          ["referralShareBps", "u64"],
+          ["makerRebateBps", "u64"],
+          ["emergencyAuthority", [32]],
+          ["emergencyHaltTimestamp", "u64"],
+          ["cancelFee", "u64"],
+          ["cancelFeeSlots", "u64"],
+          ["pendingAdmin", [32]],
+          ["haltFlags", "u64"],
+          ["crankRewardAgeSlots", "u64"],
+          ["maxCrankRewardMultiplier", "u64"],
+          ["pendingEventsSlot", "u64"],
         ],
       },
     ],
@@ -91,6 +198,46 @@ export class MarketState {
     quoteCurrencyMultiplier: BN;
     accumulatedRoyalties: BN;
     feeType: number;
+    minOrderTickDistance: number;
+    status: number;
+    hideFillAccounts: number;
+    baseSymbol: Uint8Array;
+    quoteSymbol: Uint8Array;
+    marketName: Uint8Array;
+    oracle: Uint8Array;
+    batchSlots: BN;
+    crankFeeShareBps: BN;
+    volatilityWindowSlots: BN;
+    volatilityThresholdBps: BN;
+    maxVolatilityMultiplier: BN;
+    referencePrice: BN;
+    referenceSlot: BN;
+    lastPrice: BN;
+    migrationProgram: Uint8Array;
+    migrationMarket: Uint8Array;
+    migrationUnlockTimestamp: BN;
+    orderFlowWindow: BN;
+    newOrders: BN;
+    cancels: BN;
+    fills: BN;
+    previousNewOrders: BN;
+    previousCancels: BN;
+    previousFills: BN;
+    minRestingSlots: BN;
+    bestBid: BN;
+    bestAsk: BN;
+    topOfBookSlot: BN;
+    referralShareBps: BN;
+    makerRebateBps: BN;
+    emergencyAuthority: Uint8Array;
+    emergencyHaltTimestamp: BN;
+    cancelFee: BN;
+    cancelFeeSlots: BN;
+    pendingAdmin: Uint8Array;
+    haltFlags: BN;
+    crankRewardAgeSlots: BN;
+    maxCrankRewardMultiplier: BN;
+    pendingEventsSlot: BN;
   }) {
     this.tag = obj.tag.toNumber() as AccountTag;
     this.signerNonce = obj.signerNonce;
@@ -110,6 +257,46 @@ export class MarketState {
     this.quoteCurrencyMultiplier = obj.quoteCurrencyMultiplier;
     this.baseCurrencyMultiplier = obj.baseCurrencyMultiplier;
     this.feeType = obj.feeType;
+    this.minOrderTickDistance = obj.minOrderTickDistance;
+    this.status = obj.status as MarketStatus;
+    this.hideFillAccounts = obj.hideFillAccounts !== 0;
+    this.baseSymbol = decodeFixedString(obj.baseSymbol);
+    this.quoteSymbol = decodeFixedString(obj.quoteSymbol);
+    this.marketName = decodeFixedString(obj.marketName);
+    this.oracle = new PublicKey(obj.oracle);
+    this.batchSlots = obj.batchSlots;
+    this.crankFeeShareBps = obj.crankFeeShareBps;
+    this.volatilityWindowSlots = obj.volatilityWindowSlots;
+    this.volatilityThresholdBps = obj.volatilityThresholdBps;
+    this.maxVolatilityMultiplier = obj.maxVolatilityMultiplier;
+    this.referencePrice = obj.referencePrice;
+    this.referenceSlot = obj.referenceSlot;
+    this.lastPrice = obj.lastPrice;
+    this.migrationProgram = new PublicKey(obj.migrationProgram);
+    this.migrationMarket = new PublicKey(obj.migrationMarket);
+    this.migrationUnlockTimestamp = obj.migrationUnlockTimestamp;
+    this.orderFlowWindow = obj.orderFlowWindow;
+    this.newOrders = obj.newOrders;
+    this.cancels = obj.cancels;
+    this.fills = obj.fills;
+    this.previousNewOrders = obj.previousNewOrders;
+    this.previousCancels = obj.previousCancels;
+    this.previousFills = obj.previousFills;
+    this.minRestingSlots = obj.minRestingSlots;
+    this.bestBid = obj.bestBid;
+    this.bestAsk = obj.bestAsk;
+    this.topOfBookSlot = obj.topOfBookSlot;
+    this.referralShareBps = obj.referralShareBps;
+    this.makerRebateBps = obj.makerRebateBps;
+    this.emergencyAuthority = new PublicKey(obj.emergencyAuthority);
+    this.emergencyHaltTimestamp = obj.emergencyHaltTimestamp;
+    this.cancelFee = obj.cancelFee;
+    this.cancelFeeSlots = obj.cancelFeeSlots;
+    this.pendingAdmin = new PublicKey(obj.pendingAdmin);
+    this.haltFlags = obj.haltFlags;
+    this.crankRewardAgeSlots = obj.crankRewardAgeSlots;
+    this.maxCrankRewardMultiplier = obj.maxCrankRewardMultiplier;
+    this.pendingEventsSlot = obj.pendingEventsSlot;
   }
 
   static async retrieve(connection: Connection, market: PublicKey) {
@@ -128,10 +315,41 @@ export class MarketState {
 export class Order {
   id: BN;
   clientId: BN;
+  placedSlot: BN;
+  /** The unix timestamp after which the order can be pruned, undefined for orders which never expire */
+  expiryTimestamp?: BN;
+  /** For iceberg orders, the base quantity which is not displayed on the book yet */
+  hiddenBaseQty: BN;
+  /** For iceberg orders, the base quantity of each tranche displayed on the book */
+  displayBaseQty: BN;
+  /** The maker fee rate (as a FP32) recorded when the order was placed, undefined for older orders */
+  makerRate?: number;
+  /** Whether a fill of the order was consumed, which exempts it from the cancel fee */
+  wasFilled: boolean;
 
-  constructor(obj: { id: BN; clientId: BN }) {
+  constructor(obj: {
+    id: BN;
+    clientId: BN;
+    placedSlot: BN;
+    expiryTimestamp?: BN;
+    hiddenBaseQty: BN;
+    displayBaseQty: BN;
+    makerRate?: number;
+    hasMakerRate?: number;
+    wasFilled?: number;
+  }) {
     this.clientId = obj.clientId;
     this.id = obj.id;
+    this.placedSlot = obj.placedSlot;
+    this.hiddenBaseQty = obj.hiddenBaseQty;
+    this.displayBaseQty = obj.displayBaseQty;
+    this.wasFilled = !!obj.wasFilled;
+    if (obj.expiryTimestamp && !obj.expiryTimestamp.isZero()) {
+      this.expiryTimestamp = obj.expiryTimestamp;
+    }
+    if (obj.hasMakerRate) {
+      this.makerRate = obj.makerRate;
+    }
   }
 }
 
@@ -148,6 +366,9 @@ export class UserAccount {
   accumulatedMakerBaseVolume: BN;
   accumulatedTakerQuoteVolume: BN;
   accumulatedTakerBaseVolume: BN;
+  metadata: Uint8Array;
+  lastActiveSlot: BN;
+  hasSettleAllowlist: boolean;
   orders: Order[];
 
   // @ts-ignore
@@ -169,7 +390,10 @@ export class UserAccount {
           ["accumulatedMakerBaseVolume", "u64"],
           ["accumulatedTakerQuoteVolume", "u64"],
           ["accumulatedTakerBaseVolume", "u64"],
-          ["_padding", "u32"],
+          ["metadata", [32]],
+          ["lastActiveSlot", "u64"],
+          ["hasSettleAllowlist", "u8"],
+          ["_padding", [3]],
           ["orders", [Order]],
         ],
       },
@@ -181,6 +405,14 @@ export class UserAccount {
         fields: [
           ["id", "u128"],
           ["clientId", "u128"],
+          ["placedSlot", "u64"],
+          ["expiryTimestamp", "u64"],
+          ["hiddenBaseQty", "u64"],
+          ["displayBaseQty", "u64"],
+          ["makerRate", "u32"],
+          ["hasMakerRate", "u8"],
+          ["wasFilled", "u8"],
+          ["_padding", [10]],
         ],
       },
     ],
@@ -200,6 +432,9 @@ export class UserAccount {
     accumulatedMakerBaseVolume: BN;
     accumulatedTakerQuoteVolume: BN;
     accumulatedTakerBaseVolume: BN;
+    metadata: Uint8Array;
+    lastActiveSlot: BN;
+    hasSettleAllowlist: number;
   }) {
     this.tag = obj.tag.toNumber();
     this.market = new PublicKey(obj.market);
@@ -214,6 +449,9 @@ export class UserAccount {
     this.accumulatedMakerBaseVolume = obj.accumulatedMakerBaseVolume;
     this.accumulatedTakerQuoteVolume = obj.accumulatedTakerQuoteVolume;
     this.accumulatedTakerBaseVolume = obj.accumulatedTakerBaseVolume;
+    this.metadata = obj.metadata;
+    this.lastActiveSlot = obj.lastActiveSlot;
+    this.hasSettleAllowlist = obj.hasSettleAllowlist !== 0;
   }
 
   static async retrieve(connection: Connection, userAccount: PublicKey) {
@@ -237,3 +475,390 @@ export class UserAccount {
     return this.orders.find((o) => o.id === orderId)?.clientId;
   }
 }
+
+/** The length in bytes of the borsh primitive types used by the account layouts */
+const PRIMITIVE_LEN: { [kind: string]: number } = {
+  u8: 1,
+  u16: 2,
+  u32: 4,
+  u64: 8,
+  u128: 16,
+};
+
+/**
+ * Computes the length in bytes of a struct of a borsh schema once encoded, with its vectors empty
+ * @param schema The borsh schema
+ * @param type The class of the struct
+ * @returns
+ */
+const getEncodedLen = (schema: Schema, type: any): number => {
+  const { fields } = schema.get(type);
+  let len = 0;
+  for (const [, fieldType] of fields) {
+    if (typeof fieldType === "string") {
+      len += PRIMITIVE_LEN[fieldType];
+    } else if (Array.isArray(fieldType)) {
+      // A fixed size byte array, or the u32 length prefix of a vector
+      len += typeof fieldType[0] === "number" ? fieldType[0] : 4;
+    } else {
+      len += getEncodedLen(schema, fieldType);
+    }
+  }
+  return len;
+};
+
+/**
+ * The length in bytes of a user account header, which is followed by its orders. The header ends with the number of
+ * orders as a u32, which the borsh layout reads as the length prefix of the orders.
+ */
+export const USER_ACCOUNT_HEADER_LEN = getEncodedLen(
+  UserAccount.schema,
+  UserAccount
+);
+
+/** The length in bytes of an order of a user account */
+export const ORDER_LEN = getEncodedLen(UserAccount.schema, Order);
+
+export const MAKER_REWARDS_HEADER_LEN = 80;
+
+export class MakerRewardsEntry {
+  userAccount: PublicKey;
+  epoch: BN;
+  uptimeSamples: BN;
+  depthSamples: BN;
+
+  static LEN = 56;
+
+  // @ts-ignore
+  static schema: Schema = new Map([
+    [
+      MakerRewardsEntry,
+      {
+        kind: "struct",
+        fields: [
+          ["userAccount", [32]],
+          ["epoch", "u64"],
+          ["uptimeSamples", "u64"],
+          ["depthSamples", "u64"],
+        ],
+      },
+    ],
+  ]);
+
+  constructor(obj: {
+    userAccount: Uint8Array;
+    epoch: BN;
+    uptimeSamples: BN;
+    depthSamples: BN;
+  }) {
+    this.userAccount = new PublicKey(obj.userAccount);
+    this.epoch = obj.epoch;
+    this.uptimeSamples = obj.uptimeSamples;
+    this.depthSamples = obj.depthSamples;
+  }
+}
+
+export class MakerRewards {
+  tag: AccountTag;
+  market: PublicKey;
+  epochLength: BN;
+  currentEpoch: BN;
+  lastSampleSlot: BN;
+  numberOfSamples: BN;
+  numberOfEntries: BN;
+  entries: MakerRewardsEntry[];
+
+  // @ts-ignore
+  static schema: Schema = new Map([
+    [
+      MakerRewards,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["market", [32]],
+          ["epochLength", "u64"],
+          ["currentEpoch", "u64"],
+          ["lastSampleSlot", "u64"],
+          ["numberOfSamples", "u64"],
+          ["numberOfEntries", "u64"],
+        ],
+      },
+    ],
+  ]);
+
+  constructor(obj: {
+    tag: BN;
+    market: Uint8Array;
+    epochLength: BN;
+    currentEpoch: BN;
+    lastSampleSlot: BN;
+    numberOfSamples: BN;
+    numberOfEntries: BN;
+  }) {
+    this.tag = obj.tag.toNumber() as AccountTag;
+    this.market = new PublicKey(obj.market);
+    this.epochLength = obj.epochLength;
+    this.currentEpoch = obj.currentEpoch;
+    this.lastSampleSlot = obj.lastSampleSlot;
+    this.numberOfSamples = obj.numberOfSamples;
+    this.numberOfEntries = obj.numberOfEntries;
+    this.entries = [];
+  }
+
+  static async retrieve(connection: Connection, makerRewards: PublicKey) {
+    const accountInfo = await connection.getAccountInfo(makerRewards);
+    if (!accountInfo?.data) {
+      throw new Error("Invalid account provided");
+    }
+    const rewards = deserializeUnchecked(
+      this.schema,
+      MakerRewards,
+      accountInfo.data
+    ) as MakerRewards;
+    for (let i = 0; i < rewards.numberOfEntries.toNumber(); i++) {
+      const offset = MAKER_REWARDS_HEADER_LEN + i * MakerRewardsEntry.LEN;
+      rewards.entries.push(
+        deserializeUnchecked(
+          MakerRewardsEntry.schema,
+          MakerRewardsEntry,
+          accountInfo.data.slice(offset, offset + MakerRewardsEntry.LEN)
+        ) as MakerRewardsEntry
+      );
+    }
+    return rewards;
+  }
+
+  /**
+   * Returns the entries which belong to the current sampling epoch
+   */
+  currentEntries(): MakerRewardsEntry[] {
+    return this.entries.filter((e) => e.epoch.eq(this.currentEpoch));
+  }
+}
+
+/**
+ * The listing fee charged by the create market instruction of a deployment
+ */
+export class CreateMarketConfig {
+  tag: AccountTag;
+  listingFee: BN;
+  /** The mint of the listing fee, undefined if the fee is paid in lamports */
+  feeMint?: PublicKey;
+  treasury: PublicKey;
+
+  static schema: Schema = new Map([
+    [
+      CreateMarketConfig,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["listingFee", "u64"],
+          ["feeMint", [32]],
+          ["treasury", [32]],
+        ],
+      },
+    ],
+  ]);
+
+  constructor(obj: {
+    tag: BN;
+    listingFee: BN;
+    feeMint: Uint8Array;
+    treasury: Uint8Array;
+  }) {
+    this.tag = obj.tag.toNumber() as AccountTag;
+    this.listingFee = obj.listingFee;
+    const feeMint = new PublicKey(obj.feeMint);
+    this.feeMint = feeMint.equals(PublicKey.default) ? undefined : feeMint;
+    this.treasury = new PublicKey(obj.treasury);
+  }
+
+  /**
+   * Retrieves the create market config of a deployment
+   * @param connection The Solana RPC connection
+   * @param createMarketConfig The create market config address
+   * @returns The config, or undefined if the deployment has none
+   */
+  static async retrieve(
+    connection: Connection,
+    createMarketConfig: PublicKey
+  ): Promise<CreateMarketConfig | undefined> {
+    const accountInfo = await connection.getAccountInfo(createMarketConfig);
+    if (!accountInfo?.data) {
+      return undefined;
+    }
+    return deserializeUnchecked(
+      this.schema,
+      CreateMarketConfig,
+      accountInfo.data
+    ) as CreateMarketConfig;
+  }
+}
+
+/**
+ * The compact state of an archived user account
+ */
+export class UserAccountArchive {
+  tag: AccountTag;
+  market: PublicKey;
+  owner: PublicKey;
+  accountIndex: BN;
+  accumulatedRebates: BN;
+  accumulatedMakerQuoteVolume: BN;
+  accumulatedMakerBaseVolume: BN;
+  accumulatedTakerQuoteVolume: BN;
+  accumulatedTakerBaseVolume: BN;
+  metadata: Uint8Array;
+  lastActiveSlot: BN;
+  hasSettleAllowlist: boolean;
+
+  static schema: Schema = new Map([
+    [
+      UserAccountArchive,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["market", [32]],
+          ["owner", [32]],
+          ["accountIndex", "u64"],
+          ["accumulatedRebates", "u64"],
+          ["accumulatedMakerQuoteVolume", "u64"],
+          ["accumulatedMakerBaseVolume", "u64"],
+          ["accumulatedTakerQuoteVolume", "u64"],
+          ["accumulatedTakerBaseVolume", "u64"],
+          ["metadata", [32]],
+          ["lastActiveSlot", "u64"],
+          ["hasSettleAllowlist", "u8"],
+          ["_padding", [7]],
+        ],
+      },
+    ],
+  ]);
+
+  constructor(obj: {
+    tag: BN;
+    market: Uint8Array;
+    owner: Uint8Array;
+    accountIndex: BN;
+    accumulatedRebates: BN;
+    accumulatedMakerQuoteVolume: BN;
+    accumulatedMakerBaseVolume: BN;
+    accumulatedTakerQuoteVolume: BN;
+    accumulatedTakerBaseVolume: BN;
+    metadata: Uint8Array;
+    lastActiveSlot: BN;
+    hasSettleAllowlist: number;
+  }) {
+    this.tag = obj.tag.toNumber() as AccountTag;
+    this.market = new PublicKey(obj.market);
+    this.owner = new PublicKey(obj.owner);
+    this.accountIndex = obj.accountIndex;
+    this.accumulatedRebates = obj.accumulatedRebates;
+    this.accumulatedMakerQuoteVolume = obj.accumulatedMakerQuoteVolume;
+    this.accumulatedMakerBaseVolume = obj.accumulatedMakerBaseVolume;
+    this.accumulatedTakerQuoteVolume = obj.accumulatedTakerQuoteVolume;
+    this.accumulatedTakerBaseVolume = obj.accumulatedTakerBaseVolume;
+    this.metadata = obj.metadata;
+    this.lastActiveSlot = obj.lastActiveSlot;
+    this.hasSettleAllowlist = !!obj.hasSettleAllowlist;
+  }
+
+  /**
+   * Retrieves the archive of a user account
+   * @param connection The Solana RPC connection
+   * @param archive The user account archive address
+   * @returns The archive, or undefined if the user account isn't archived
+   */
+  static async retrieve(
+    connection: Connection,
+    archive: PublicKey
+  ): Promise<UserAccountArchive | undefined> {
+    const accountInfo = await connection.getAccountInfo(archive);
+    if (!accountInfo?.data) {
+      return undefined;
+    }
+    return deserializeUnchecked(
+      this.schema,
+      UserAccountArchive,
+      accountInfo.data
+    ) as UserAccountArchive;
+  }
+}
+
+/**
+ * The outcome of an order, returned by the preview new order instruction
+ */
+export class NewOrderSummary {
+  postedOrderId: BN | null;
+  matchedBaseQty: BN;
+  matchedQuoteQty: BN;
+  postedBaseQty: BN;
+  postedQuoteQty: BN;
+  takerFee: BN;
+  royaltiesFee: BN;
+  transferredQty: BN;
+
+  static schema: Schema = new Map([
+    [
+      NewOrderSummary,
+      {
+        kind: "struct",
+        fields: [
+          ["postedOrderId", { kind: "option", type: "u128" }],
+          ["matchedBaseQty", "u64"],
+          ["matchedQuoteQty", "u64"],
+          ["postedBaseQty", "u64"],
+          ["postedQuoteQty", "u64"],
+          ["takerFee", "u64"],
+          ["royaltiesFee", "u64"],
+          ["transferredQty", "u64"],
+        ],
+      },
+    ],
+  ]);
+
+  constructor(obj: {
+    postedOrderId: BN | null;
+    matchedBaseQty: BN;
+    matchedQuoteQty: BN;
+    postedBaseQty: BN;
+    postedQuoteQty: BN;
+    takerFee: BN;
+    royaltiesFee: BN;
+    transferredQty: BN;
+  }) {
+    this.postedOrderId = obj.postedOrderId;
+    this.matchedBaseQty = obj.matchedBaseQty;
+    this.matchedQuoteQty = obj.matchedQuoteQty;
+    this.postedBaseQty = obj.postedBaseQty;
+    this.postedQuoteQty = obj.postedQuoteQty;
+    this.takerFee = obj.takerFee;
+    this.royaltiesFee = obj.royaltiesFee;
+    this.transferredQty = obj.transferredQty;
+  }
+
+  /**
+   * Extracts the summary from the logs of a simulated preview, which always fails once the order has been previewed
+   * @param logs The logs of the simulated transaction
+   * @param programId The DEX program ID
+   * @returns The summary, or undefined if the order itself failed
+   */
+  static fromSimulationLogs(
+    logs: string[],
+    programId: PublicKey
+  ): NewOrderSummary | undefined {
+    const prefix = `Program return: ${programId.toBase58()} `;
+    const returnData = logs.find((l) => l.startsWith(prefix));
+    if (!returnData) {
+      return undefined;
+    }
+    return deserialize(
+      this.schema,
+      NewOrderSummary,
+      Buffer.from(returnData.slice(prefix.length), "base64")
+    ) as NewOrderSummary;
+  }
+}
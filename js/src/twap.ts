@@ -0,0 +1,264 @@
+import {
+  Commitment,
+  Connection,
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionSignature,
+} from "@solana/web3.js";
+import BN from "bn.js";
+import { placeOrder } from "./bindings";
+import { Market } from "./market";
+import { SelfTradeBehavior, UserAccount } from "./state";
+import { OrderType, Side } from "./types";
+import { getUserAccountAddress } from "./utils";
+
+/**
+ * A child order of a TWAP execution
+ */
+export interface TwapSlice {
+  /** The time at which the child order is sent, in milliseconds from the start of the execution */
+  atMs: number;
+  /** The raw base size of the child order */
+  size: BN;
+}
+
+/**
+ * The progress of a TWAP execution
+ */
+export interface TwapProgress {
+  /** The number of child orders sent so far, including the failed ones */
+  slicesDone: number;
+  /** The total number of child orders */
+  slices: number;
+  /** The raw base quantity sent in child orders */
+  submittedBaseQty: BN;
+  /** The raw base quantity filled by the child orders */
+  filledBaseQty: BN;
+  /** The signatures of the confirmed child orders */
+  signatures: TransactionSignature[];
+}
+
+export interface TwapOptions {
+  /** The worst price at which the child orders may be filled (UI price) */
+  limitPrice: number;
+  /** The random shift of each child order, as a fraction of the interval between two child orders. Defaults to 0.2 */
+  jitter?: number;
+  /** The index of the user account (0 for the main user account) */
+  accountIndex?: number;
+  /** The commitment used for the confirmations */
+  commitment?: Commitment;
+  /** Called after each child order */
+  onProgress?: (progress: TwapProgress) => void;
+  /** Called when a child order fails, the execution then moves on to the next child order */
+  onError?: (slice: number, err: Error) => void;
+}
+
+const DEFAULT_JITTER = 0.2;
+
+/**
+ * Splits a parent order into child orders evenly spread over a duration, each shifted by a random jitter.
+ * The remainder of the division of the size is spread over the first child orders.
+ * @param totalQty The raw base size of the parent order
+ * @param durationMs The duration of the execution in milliseconds
+ * @param slices The number of child orders
+ * @param jitter The random shift of each child order, as a fraction of the interval between two child orders
+ * @param random The source of randomness, returning numbers in [0, 1)
+ * @returns The child orders in the order in which they are sent
+ */
+export const twapSchedule = (
+  totalQty: BN,
+  durationMs: number,
+  slices: number,
+  jitter = DEFAULT_JITTER,
+  random: () => number = Math.random
+): TwapSlice[] => {
+  if (slices < 1 || !Number.isInteger(slices)) {
+    throw new Error("The number of slices should be a positive integer");
+  }
+  if (jitter < 0 || jitter > 1) {
+    throw new Error("The jitter should be between 0 and 1");
+  }
+  const interval = durationMs / slices;
+  const size = totalQty.divn(slices);
+  const remainder = totalQty.modn(slices);
+  const schedule: TwapSlice[] = [];
+  for (let i = 0; i < slices; i++) {
+    // The first child order is sent right away, the others are shifted within their interval
+    const shift = i === 0 ? 0 : (random() - 0.5) * jitter * interval;
+    schedule.push({
+      atMs: Math.round(Math.min(Math.max(i * interval + shift, 0), durationMs)),
+      size: i < remainder ? size.addn(1) : size,
+    });
+  }
+  return schedule.filter((s) => !s.size.isZero());
+};
+
+/**
+ * Executes a parent order as immediate or cancel child orders spread over time, to limit its market impact
+ */
+export class TwapExecutor {
+  private _connection: Connection;
+  private _market: Market;
+  private _owner: Keypair;
+  private _ownerTokenAccount: PublicKey;
+  private _side: Side;
+  private _schedule: TwapSlice[];
+  private _options: TwapOptions;
+  private _progress: TwapProgress;
+  private _stopped = false;
+
+  /**
+   * @param connection The Solana RPC connection
+   * @param market The market on which the child orders are placed
+   * @param owner The owner of the user account
+   * @param ownerTokenAccount The token account from which the child orders are funded
+   * @param side The side of the parent order
+   * @param totalQty The raw base size of the parent order
+   * @param durationMs The duration of the execution in milliseconds
+   * @param slices The number of child orders
+   * @param options The execution options
+   */
+  constructor(
+    connection: Connection,
+    market: Market,
+    owner: Keypair,
+    ownerTokenAccount: PublicKey,
+    side: Side,
+    totalQty: BN,
+    durationMs: number,
+    slices: number,
+    options: TwapOptions
+  ) {
+    this._connection = connection;
+    this._market = market;
+    this._owner = owner;
+    this._ownerTokenAccount = ownerTokenAccount;
+    this._side = side;
+    this._options = options;
+    this._schedule = twapSchedule(
+      totalQty,
+      durationMs,
+      slices,
+      options.jitter ?? DEFAULT_JITTER
+    );
+    this._progress = {
+      slicesDone: 0,
+      slices: this._schedule.length,
+      submittedBaseQty: new BN(0),
+      filledBaseQty: new BN(0),
+      signatures: [],
+    };
+  }
+
+  /** The progress of the execution */
+  get progress(): TwapProgress {
+    return this._progress;
+  }
+
+  private async _takerBaseVolume(userAccount: PublicKey): Promise<BN> {
+    const account = await UserAccount.retrieve(this._connection, userAccount);
+    return account.accumulatedTakerBaseVolume;
+  }
+
+  private async _send(slice: TwapSlice): Promise<TransactionSignature> {
+    const ix = await placeOrder(
+      this._market,
+      this._side,
+      this._options.limitPrice,
+      0,
+      OrderType.ImmediateOrCancel,
+      SelfTradeBehavior.DecrementTake,
+      this._ownerTokenAccount,
+      this._owner.publicKey,
+      undefined,
+      undefined,
+      slice.size,
+      undefined,
+      this._options.accountIndex || 0
+    );
+    const tx = new Transaction().add(ix);
+    const signature = await this._connection.sendTransaction(tx, [
+      this._owner,
+    ]);
+    const { value } = await this._connection.confirmTransaction(
+      signature,
+      this._options.commitment
+    );
+    if (value?.err) {
+      throw new Error(JSON.stringify(value.err));
+    }
+    return signature;
+  }
+
+  /**
+   * Sends the child orders on schedule. The filled quantity is measured from the taker volume of the user account.
+   * @returns The progress of the execution once all child orders are sent or the execution is stopped
+   */
+  async run(): Promise<TwapProgress> {
+    const userAccount = await getUserAccountAddress(
+      this._market.address,
+      this._owner.publicKey,
+      this._options.accountIndex || 0,
+      this._market.programId
+    );
+    const initialVolume = await this._takerBaseVolume(userAccount);
+    const start = Date.now();
+    for (let [i, slice] of this._schedule.entries()) {
+      const wait = start + slice.atMs - Date.now();
+      if (wait > 0) {
+        await new Promise((resolve) => setTimeout(resolve, wait));
+      }
+      if (this._stopped) {
+        break;
+      }
+      try {
+        const signature = await this._send(slice);
+        this._progress.signatures.push(signature);
+        this._progress.submittedBaseQty = this._progress.submittedBaseQty.add(
+          slice.size
+        );
+        this._progress.filledBaseQty = (
+          await this._takerBaseVolume(userAccount)
+        ).sub(initialVolume);
+      } catch (err) {
+        this._options.onError?.(i, err as Error);
+      }
+      this._progress.slicesDone += 1;
+      this._options.onProgress?.(this._progress);
+    }
+    return this._progress;
+  }
+
+  /** Stops the execution before its next child order */
+  stop() {
+    this._stopped = true;
+  }
+}
+
+/**
+ * Executes a parent order as immediate or cancel child orders spread over time, see `TwapExecutor`
+ * @returns The progress of the execution once all child orders are sent
+ */
+export const executeTwap = (
+  connection: Connection,
+  market: Market,
+  owner: Keypair,
+  ownerTokenAccount: PublicKey,
+  side: Side,
+  totalQty: BN,
+  durationMs: number,
+  slices: number,
+  options: TwapOptions
+) =>
+  new TwapExecutor(
+    connection,
+    market,
+    owner,
+    ownerTokenAccount,
+    side,
+    totalQty,
+    durationMs,
+    slices,
+    options
+  ).run();
@@ -0,0 +1,326 @@
+import {
+  Commitment,
+  ComputeBudgetProgram,
+  Connection,
+  Keypair,
+  PublicKey,
+  SignatureStatus,
+  Transaction,
+  TransactionInstruction,
+  TransactionSignature,
+} from "@solana/web3.js";
+import BN from "bn.js";
+import { UserAccount } from "./state";
+
+/** The base fee charged for each signature of a transaction */
+export const DEFAULT_LAMPORTS_PER_SIGNATURE = 5_000;
+
+/** The compute unit limit applied by the runtime to a transaction which doesn't request one */
+export const DEFAULT_COMPUTE_UNIT_LIMIT = 200_000;
+
+export interface TxSenderOptions {
+  /** The compute unit limit requested for each transaction */
+  computeUnitLimit?: number;
+  /** The price of a compute unit in micro-lamports, no prioritization fee is paid when unset */
+  computeUnitPriceMicroLamports?: number;
+  /** The submission is aborted when the total fee of a transaction exceeds this amount of lamports */
+  maxFeeLamports?: number;
+  /** The base fee charged for each signature */
+  lamportsPerSignature?: number;
+  /** The commitment used for the confirmations */
+  commitment?: Commitment;
+}
+
+export interface ResubmissionOptions {
+  /** The maximum number of signed attempts, 5 by default */
+  maxAttempts?: number;
+  /** The factor applied to the compute unit price at each new attempt, 2 by default */
+  feeMultiplier?: number;
+  /** The compute unit price of the first attempt when the sender doesn't set one, 1 micro-lamport by default */
+  initialComputeUnitPriceMicroLamports?: number;
+  /** The delay in milliseconds after which a pending attempt is replaced, 2 seconds by default */
+  retryIntervalMs?: number;
+  /** The interval in milliseconds between two status checks, 500 by default */
+  pollIntervalMs?: number;
+  /**
+   * Checked before each new attempt, returning true stops the resubmission.
+   * Use `clientOrderIdGuard` to stop as soon as the order rests on the book.
+   */
+  isLanded?: () => Promise<boolean>;
+}
+
+/**
+ * Thrown when no attempt of a resubmitted transaction landed before the expiry of its last blockhash
+ */
+export class TransactionExpiredError extends Error {
+  /** The signatures of all the attempts */
+  signatures: TransactionSignature[];
+
+  constructor(signatures: TransactionSignature[]) {
+    super(
+      `None of the ${signatures.length} attempts landed before their blockhash expired`
+    );
+    this.name = "TransactionExpiredError";
+    this.signatures = signatures;
+  }
+}
+
+/**
+ * Thrown when the fee of a transaction exceeds the configured cap
+ */
+export class MaxFeeExceededError extends Error {
+  /** The total fee of the rejected transaction in lamports */
+  fee: number;
+  /** The configured cap in lamports */
+  maxFee: number;
+
+  constructor(fee: number, maxFee: number) {
+    super(`The transaction fee of ${fee} lamports exceeds the cap of ${maxFee}`);
+    this.name = "MaxFeeExceededError";
+    this.fee = fee;
+    this.maxFee = maxFee;
+  }
+}
+
+/**
+ * Computes the total fee of a transaction in lamports
+ * @param numSignatures The number of signatures of the transaction
+ * @param computeUnitLimit The compute unit limit of the transaction
+ * @param computeUnitPriceMicroLamports The price of a compute unit in micro-lamports
+ * @param lamportsPerSignature The base fee charged for each signature
+ * @returns The base fee plus the prioritization fee
+ */
+export const computeTransactionFee = (
+  numSignatures: number,
+  computeUnitLimit: number,
+  computeUnitPriceMicroLamports = 0,
+  lamportsPerSignature = DEFAULT_LAMPORTS_PER_SIGNATURE
+) => {
+  const prioritizationFee = Math.ceil(
+    (computeUnitLimit * computeUnitPriceMicroLamports) / 1_000_000
+  );
+  return numSignatures * lamportsPerSignature + prioritizationFee;
+};
+
+const COMMITMENT_LEVELS = ["processed", "confirmed", "finalized"];
+
+const isCommitted = (status: SignatureStatus, commitment: Commitment) => {
+  const target = COMMITMENT_LEVELS.indexOf(commitment);
+  const level = COMMITMENT_LEVELS.indexOf(status.confirmationStatus || "");
+  return level >= (target < 0 ? 1 : target);
+};
+
+/**
+ * Returns an `isLanded` check which looks for an open order with the given client order id in a user account.
+ * Since the program rejects a second open order with the same client order id, every attempt of a resubmitted order
+ * should carry the same nonzero client order id.
+ * @param connection The Solana RPC connection
+ * @param userAccount The address of the user account placing the order
+ * @param clientOrderId The client order id of the order
+ */
+export const clientOrderIdGuard =
+  (connection: Connection, userAccount: PublicKey, clientOrderId: BN) =>
+  async () => {
+    const account = await UserAccount.retrieve(connection, userAccount);
+    return account.orders.some((o) => o.clientId.eq(clientOrderId));
+  };
+
+/**
+ * Sends transactions with a prioritization fee and refuses to send those whose total fee exceeds a cap
+ */
+export class TxSender {
+  private _connection: Connection;
+  private _options: TxSenderOptions;
+
+  /**
+   * @param connection The Solana RPC connection
+   * @param options The compute budget and fee cap options
+   */
+  constructor(connection: Connection, options: TxSenderOptions = {}) {
+    this._connection = connection;
+    this._options = options;
+  }
+
+  /**
+   * Returns the total fee in lamports of a transaction signed by a number of signers
+   * @param numSignatures The number of signatures of the transaction
+   */
+  fee(numSignatures: number): number {
+    return computeTransactionFee(
+      numSignatures,
+      this._options.computeUnitLimit ?? DEFAULT_COMPUTE_UNIT_LIMIT,
+      this._options.computeUnitPriceMicroLamports,
+      this._options.lamportsPerSignature
+    );
+  }
+
+  /**
+   * Prepends the compute budget instructions to a list of instructions
+   * @param instructions The instructions of the transaction
+   */
+  buildTransaction(instructions: TransactionInstruction[]): Transaction {
+    const tx = new Transaction();
+    if (this._options.computeUnitLimit !== undefined) {
+      tx.add(
+        ComputeBudgetProgram.setComputeUnitLimit({
+          units: this._options.computeUnitLimit,
+        })
+      );
+    }
+    if (this._options.computeUnitPriceMicroLamports) {
+      tx.add(
+        ComputeBudgetProgram.setComputeUnitPrice({
+          microLamports: this._options.computeUnitPriceMicroLamports,
+        })
+      );
+    }
+    return tx.add(...instructions);
+  }
+
+  /**
+   * Sends a transaction and waits for its confirmation
+   * @param instructions The instructions of the transaction
+   * @param signers The signers of the transaction, the first one pays the fees
+   * @throws MaxFeeExceededError when the fee of the transaction exceeds the cap, in which case nothing is sent
+   */
+  async send(
+    instructions: TransactionInstruction[],
+    signers: Keypair[]
+  ): Promise<TransactionSignature> {
+    const fee = this.fee(signers.length);
+    const maxFee = this._options.maxFeeLamports;
+    if (maxFee !== undefined && fee > maxFee) {
+      throw new MaxFeeExceededError(fee, maxFee);
+    }
+    const tx = this.buildTransaction(instructions);
+    const signature = await this._connection.sendTransaction(tx, signers);
+    const { value } = await this._connection.confirmTransaction(
+      signature,
+      this._options.commitment
+    );
+    if (value?.err) {
+      throw new Error(JSON.stringify(value.err));
+    }
+    return signature;
+  }
+
+  /**
+   * Sends a transaction and replaces it until it lands. Each new attempt is signed with a fresh blockhash and a
+   * compute unit price raised by the fee multiplier, up to the price allowed by the fee cap.
+   *
+   * Attempts signed with different blockhashes are different transactions which can all land, the statuses of every
+   * previous attempt are therefore checked before sending a new one. Orders should carry a nonzero client order id:
+   * the program rejects a second open order with the same one, and `clientOrderIdGuard` stops the resubmission once
+   * the order rests on the book.
+   * @param instructions The instructions of the transaction
+   * @param signers The signers of the transaction, the first one pays the fees
+   * @param options The resubmission options
+   * @returns The signature of the attempt which landed
+   * @throws MaxFeeExceededError when the fee of the first attempt exceeds the cap, in which case nothing is sent
+   * @throws TransactionExpiredError when no attempt landed before the expiry of the last blockhash
+   */
+  async sendWithResubmission(
+    instructions: TransactionInstruction[],
+    signers: Keypair[],
+    options: ResubmissionOptions = {}
+  ): Promise<TransactionSignature> {
+    const maxAttempts = options.maxAttempts ?? 5;
+    const feeMultiplier = options.feeMultiplier ?? 2;
+    const retryIntervalMs = options.retryIntervalMs ?? 2_000;
+    const pollIntervalMs = options.pollIntervalMs ?? 500;
+    const commitment =
+      this._options.commitment ?? this._connection.commitment ?? "confirmed";
+    const computeUnitLimit =
+      this._options.computeUnitLimit ?? DEFAULT_COMPUTE_UNIT_LIMIT;
+
+    let price =
+      this._options.computeUnitPriceMicroLamports ||
+      options.initialComputeUnitPriceMicroLamports ||
+      1;
+    let maxPrice = Infinity;
+    const maxFee = this._options.maxFeeLamports;
+    if (maxFee !== undefined) {
+      const baseFee = computeTransactionFee(
+        signers.length,
+        computeUnitLimit,
+        0,
+        this._options.lamportsPerSignature
+      );
+      maxPrice = Math.floor(
+        ((maxFee - baseFee) * 1_000_000) / computeUnitLimit
+      );
+    }
+
+    const signatures: TransactionSignature[] = [];
+    let lastValidBlockHeight = 0;
+    for (let attempt = 0; attempt < maxAttempts; attempt++) {
+      if (attempt > 0) {
+        if (options.isLanded && (await options.isLanded())) {
+          break;
+        }
+        price = Math.min(Math.ceil(price * feeMultiplier), maxPrice);
+      }
+      const sender = new TxSender(this._connection, {
+        ...this._options,
+        computeUnitLimit,
+        computeUnitPriceMicroLamports: price,
+      });
+      const fee = sender.fee(signers.length);
+      if (maxFee !== undefined && fee > maxFee) {
+        throw new MaxFeeExceededError(fee, maxFee);
+      }
+      const latest = await this._connection.getLatestBlockhash(commitment);
+      const tx = sender.buildTransaction(instructions);
+      tx.recentBlockhash = latest.blockhash;
+      tx.feePayer = signers[0].publicKey;
+      tx.sign(...signers);
+      signatures.push(
+        await this._connection.sendRawTransaction(tx.serialize(), {
+          preflightCommitment: commitment,
+        })
+      );
+      lastValidBlockHeight = latest.lastValidBlockHeight;
+
+      const deadline = Date.now() + retryIntervalMs;
+      do {
+        const landed = await this._findLanded(signatures, commitment);
+        if (landed) {
+          return landed;
+        }
+        await new Promise((resolve) => setTimeout(resolve, pollIntervalMs));
+      } while (Date.now() < deadline);
+    }
+
+    // The pending attempts can still land until the last blockhash expires
+    while (
+      (await this._connection.getBlockHeight(commitment)) <=
+      lastValidBlockHeight
+    ) {
+      const landed = await this._findLanded(signatures, commitment);
+      if (landed) {
+        return landed;
+      }
+      await new Promise((resolve) => setTimeout(resolve, pollIntervalMs));
+    }
+    const landed = await this._findLanded(signatures, commitment);
+    if (landed) {
+      return landed;
+    }
+    throw new TransactionExpiredError(signatures);
+  }
+
+  private async _findLanded(
+    signatures: TransactionSignature[],
+    commitment: Commitment
+  ): Promise<TransactionSignature | undefined> {
+    const { value } = await this._connection.getSignatureStatuses(signatures);
+    const index = value.findIndex((s) => !!s && isCommitted(s, commitment));
+    if (index < 0) {
+      return undefined;
+    }
+    if (value[index]?.err) {
+      throw new Error(JSON.stringify(value[index]?.err));
+    }
+    return signatures[index];
+  }
+}
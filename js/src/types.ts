@@ -18,6 +18,12 @@ export enum OrderType {
   ImmediateOrCancel = 1,
   FillOrKill = 2,
   PostOnly = 3,
+  Market = 4,
+}
+
+export enum QuantityMode {
+  Base = 0,
+  Quote = 1,
 }
 
 export enum SelfTradeBehavior {
@@ -62,3 +68,26 @@ export interface MarketOptions {
   skipPreflight?: boolean;
   commitment?: Commitment;
 }
+
+/**
+ * The order flow counters of a market over its current and previous windows
+ */
+export interface OrderFlow {
+  window: number;
+  newOrders: number;
+  cancels: number;
+  fills: number;
+  previousNewOrders: number;
+  previousCancels: number;
+  previousFills: number;
+}
+
+/**
+ * The best bid and ask prices cached in the market state, undefined for an empty side
+ */
+export interface TopOfBook {
+  bestBid?: number;
+  bestAsk?: number;
+  /** The slot at which the prices were last refreshed */
+  slot: number;
+}
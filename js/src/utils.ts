@@ -1,6 +1,7 @@
 import { Connection, PublicKey } from "@solana/web3.js";
 import BN from "bn.js";
 import { Market } from "./market";
+import { DEX_ID } from "./ids";
 
 export function throwIfNull<T>(
   value: T | null,
@@ -72,3 +73,53 @@ export const computeUiPrice = (market: Market, fp32Price: BN) => {
 
   return Number(divideBnToNumber(numerator, denominator).toPrecision(5));
 };
+
+/**
+ * Derives the address of a user account
+ * @param market The market address
+ * @param owner The owner of the user account
+ * @param accountIndex The index of the user account (0 for the main user account)
+ * @param programId The DEX program ID
+ * @returns
+ */
+export const getUserAccountAddress = async (
+  market: PublicKey,
+  owner: PublicKey,
+  accountIndex = 0,
+  programId = DEX_ID
+) => {
+  const seeds = [market.toBuffer(), owner.toBuffer()];
+  if (accountIndex !== 0) {
+    seeds.push(new BN(accountIndex).toArrayLike(Buffer, "le", 8));
+  }
+  const [userAccount] = await PublicKey.findProgramAddress(seeds, programId);
+  return userAccount;
+};
+
+/**
+ * Encodes a string into a zero padded UTF-8 byte array of a fixed length
+ * @param value The string to encode
+ * @param length The length of the byte array
+ * @returns
+ */
+export const encodeFixedString = (value: string, length: number) => {
+  const encoded = Buffer.from(value, "utf-8");
+  if (encoded.length > length) {
+    throw new Error(`"${value}" is longer than ${length} bytes`);
+  }
+  const result = new Uint8Array(length).fill(0);
+  result.set(encoded);
+  return result;
+};
+
+/**
+ * Decodes a zero padded UTF-8 byte array into a string
+ * @param bytes The byte array to decode
+ * @returns
+ */
+export const decodeFixedString = (bytes: Uint8Array) => {
+  const end = bytes.indexOf(0);
+  return Buffer.from(end === -1 ? bytes : bytes.slice(0, end)).toString(
+    "utf-8"
+  );
+};
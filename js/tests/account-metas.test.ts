@@ -0,0 +1,43 @@
+import { expect, test } from "@jest/globals";
+import { Keypair, TransactionInstruction } from "@solana/web3.js";
+import BN from "bn.js";
+import { ACCOUNTS_METAS, labelAccounts } from "../src/accountMetas";
+import { setReferralShareInstruction } from "../src/raw_instructions";
+
+const programId = Keypair.generate().publicKey;
+
+test("Every instruction is described", () => {
+  expect(ACCOUNTS_METAS.length).toBe(59);
+  for (const metas of ACCOUNTS_METAS) {
+    expect(metas.length).toBeGreaterThan(0);
+  }
+});
+
+test("Accounts are labeled by index", () => {
+  const market = Keypair.generate().publicKey;
+  const marketAdmin = Keypair.generate().publicKey;
+  const ix = new setReferralShareInstruction({
+    referralShareBps: new BN(5_000),
+  }).getInstruction(programId, market, marketAdmin);
+  const labels = labelAccounts(ix);
+  expect(labels.map((l) => l.meta?.name)).toEqual(["market", "marketAdmin"]);
+  expect(labels[1].meta?.signer).toBe(true);
+  expect(labels[1].pubkey.equals(marketAdmin)).toBe(true);
+});
+
+test("Accounts following a variable list are left unlabeled", () => {
+  // consume_events takes the list of user accounts after the reward target
+  const data = Buffer.alloc(8);
+  data.writeUInt8(4, 0);
+  const keys = [...Array(6)].map(() => ({
+    pubkey: Keypair.generate().publicKey,
+    isSigner: false,
+    isWritable: true,
+  }));
+  const labels = labelAccounts(
+    new TransactionInstruction({ keys, programId, data })
+  );
+  expect(labels[3].meta?.name).toBe("rewardTarget");
+  expect(labels[4].meta).toBeUndefined();
+  expect(labels[5].meta).toBeUndefined();
+});
@@ -13,6 +13,9 @@ import { orderbookTest } from "./orderbook";
 import { selfTradeTest } from "./self-trade";
 import BN from "bn.js";
 import { metadataTest } from "./metadata";
+import { lifecycleMatrixTest } from "./lifecycle-matrix";
+import { describeCase, lifecycleMatrix } from "./utils/matrix";
+import { userAccountSpaceTest } from "./user-account-space";
 
 // Global state initialized once in test startup and cleaned up at test
 // teardown.
@@ -179,3 +182,13 @@ test("Self trade", async () => {
 test("Metadata", async () => {
   await metadataTest(connection, feePayer, 6, 6, 20_000, 30_000, 1, 6, 2);
 });
+
+test("User account space", async () => {
+  await userAccountSpaceTest(connection, feePayer);
+});
+
+for (const c of lifecycleMatrix()) {
+  test(`Lifecycle (${describeCase(c)})`, async () => {
+    await lifecycleMatrixTest(connection, feePayer, c);
+  });
+}
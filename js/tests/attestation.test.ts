@@ -0,0 +1,93 @@
+import { expect, test } from "@jest/globals";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
+import {
+  buildAttestationPayload,
+  compareAttestationPayloads,
+  signAttestation,
+  verifyAttestationSignature,
+} from "../src/attestation";
+import { encodeOrderId } from "../src/orderId";
+import { Order, UserAccount } from "../src/state";
+import { Side } from "../src/types";
+
+const userAccount = (baseTokenFree: number, orders: Order[]) =>
+  new UserAccount({
+    tag: new BN(3),
+    market: Keypair.generate().publicKey.toBytes(),
+    owner: Keypair.generate().publicKey.toBytes(),
+    baseTokenFree: new BN(baseTokenFree),
+    baseTokenLocked: new BN(0),
+    quoteTokenFree: new BN(1_000),
+    quoteTokenLocked: new BN(0),
+    orders,
+    accumulatedRebates: new BN(0),
+    accumulatedMakerQuoteVolume: new BN(0),
+    accumulatedMakerBaseVolume: new BN(0),
+    accumulatedTakerQuoteVolume: new BN(0),
+    accumulatedTakerBaseVolume: new BN(0),
+    metadata: new Uint8Array(32),
+    lastActiveSlot: new BN(0),
+    hasSettleAllowlist: 0,
+  });
+
+const order = new Order({
+  id: encodeOrderId(new BN(2).pow(new BN(32)), new BN(7), Side.Bid),
+  clientId: new BN(1),
+  placedSlot: new BN(10),
+});
+
+test("Attestation signature round trip", () => {
+  const address = Keypair.generate().publicKey;
+  const payload = buildAttestationPayload(
+    address,
+    userAccount(5, [order]),
+    Buffer.alloc(8),
+    100,
+    1_000
+  );
+  expect(payload.orders[0].side).toBe("bid");
+  expect(payload.orders[0].price).toBe("4294967296");
+
+  const auditor = Keypair.generate();
+  const attestation = signAttestation(payload, auditor);
+  expect(verifyAttestationSignature(attestation)).toBe(true);
+  expect(verifyAttestationSignature(attestation, auditor.publicKey)).toBe(
+    true
+  );
+  expect(
+    verifyAttestationSignature(attestation, Keypair.generate().publicKey)
+  ).toBe(false);
+
+  // The attestation survives a JSON round trip
+  const parsed = JSON.parse(JSON.stringify(attestation));
+  expect(verifyAttestationSignature(parsed)).toBe(true);
+
+  // Any change to the payload invalidates the signature
+  parsed.payload.baseTokenFree = "6";
+  expect(verifyAttestationSignature(parsed)).toBe(false);
+});
+
+test("Attestation comparison against chain state", () => {
+  const address = new PublicKey(new Uint8Array(32).fill(1));
+  const account = userAccount(5, [order]);
+  const data = Buffer.alloc(8);
+  const attested = buildAttestationPayload(address, account, data, 100, 1_000);
+
+  // The slot and timestamp of the current state are ignored
+  const same = buildAttestationPayload(address, account, data, 200, 2_000);
+  expect(compareAttestationPayloads(attested, same)).toEqual([]);
+
+  const changed = buildAttestationPayload(
+    address,
+    { ...account, baseTokenFree: new BN(6), orders: [] } as UserAccount,
+    Buffer.alloc(8, 1),
+    200,
+    2_000
+  );
+  expect(compareAttestationPayloads(attested, changed)).toEqual([
+    "baseTokenFree",
+    "dataHash",
+    "orders",
+  ]);
+});
@@ -0,0 +1,103 @@
+import { expect, jest, test } from "@jest/globals";
+import {
+  Connection,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  TransactionSignature,
+} from "@solana/web3.js";
+import BN from "bn.js";
+import { AutoSettler } from "../src/autoSettler";
+import * as bindings from "../src/bindings";
+import { Market } from "../src/market";
+import { AccountTag, USER_ACCOUNT_HEADER_LEN } from "../src/state";
+
+// The offset of the free base balance in the user account layout
+const BASE_TOKEN_FREE_OFFSET = 72;
+
+const userAccountData = (tag: AccountTag, baseTokenFree: number) => {
+  const data = Buffer.alloc(USER_ACCOUNT_HEADER_LEN);
+  new BN(tag).toArrayLike(Buffer, "le", 8).copy(data, 0);
+  new BN(baseTokenFree)
+    .toArrayLike(Buffer, "le", 8)
+    .copy(data, BASE_TOKEN_FREE_OFFSET);
+  return data;
+};
+
+const market = {
+  address: Keypair.generate().publicKey,
+  programId: Keypair.generate().publicKey,
+} as unknown as Market;
+
+const run = async (data: Buffer) => {
+  const owner = Keypair.generate();
+  jest.spyOn(bindings, "settle").mockResolvedValue(
+    SystemProgram.transfer({
+      fromPubkey: owner.publicKey,
+      toPubkey: owner.publicKey,
+      lamports: 1,
+    })
+  );
+  const connection = {
+    getAccountInfo: async () => ({ data }),
+    onAccountChange: () => 0,
+    removeAccountChangeListener: async () => {},
+    sendTransaction: jest.fn(async () => "signature"),
+    confirmTransaction: async () => ({ value: { err: null } }),
+  };
+  const settled: TransactionSignature[] = [];
+  const errors: Error[] = [];
+  const settler = new AutoSettler(
+    connection as unknown as Connection,
+    market,
+    [
+      {
+        owner,
+        destinationBaseAccount: PublicKey.default,
+        destinationQuoteAccount: PublicKey.default,
+      },
+    ],
+    {
+      baseThreshold: new BN(10),
+      onSettle: (_, signature) => settled.push(signature),
+      onError: (_, err) => errors.push(err),
+    }
+  );
+  await settler.start();
+  await settler.stop();
+  jest.restoreAllMocks();
+  return {
+    settled,
+    errors,
+    sent: connection.sendTransaction.mock.calls.length,
+  };
+};
+
+test("A user account above the threshold is settled", async () => {
+  const { settled, errors } = await run(
+    userAccountData(AccountTag.UserAccount, 100)
+  );
+  expect(errors).toEqual([]);
+  expect(settled).toEqual(["signature"]);
+});
+
+test("A user account below the threshold is not settled", async () => {
+  const { sent, errors } = await run(
+    userAccountData(AccountTag.UserAccount, 5)
+  );
+  expect(errors).toEqual([]);
+  expect(sent).toBe(0);
+});
+
+test("Accounts which aren't current user accounts are not decoded", async () => {
+  for (const tag of [AccountTag.Close, AccountTag.UserAccountV1]) {
+    const { sent, errors } = await run(userAccountData(tag, 100));
+    expect(sent).toBe(0);
+    expect(errors.map((e) => e.message)).toEqual([
+      `Unexpected user account tag ${tag}`,
+    ]);
+  }
+  const { sent, errors } = await run(Buffer.alloc(0));
+  expect(sent).toBe(0);
+  expect(errors.length).toBe(1);
+});
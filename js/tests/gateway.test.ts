@@ -0,0 +1,211 @@
+import { afterEach, expect, jest, test } from "@jest/globals";
+import { EventFill, EventQueue } from "@bonfida/aaob";
+import { Connection, Keypair, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
+import { AddressInfo } from "net";
+import WebSocket from "ws";
+import {
+  CcxtOrderbook,
+  Gateway,
+  sequencedFills,
+  toCcxtTrade,
+} from "../src/gateway";
+import { Market } from "../src/market";
+
+const maker = Keypair.generate().publicKey;
+const taker = Keypair.generate().publicKey;
+
+const fill = (baseSize: number, quoteSize: number): EventFill =>
+  Object.assign(Object.create(EventFill.prototype), {
+    takerSide: 1,
+    makerOrderId: new BN(1),
+    baseSize: new BN(baseSize),
+    quoteSize: new BN(quoteSize),
+    makerCallbackInfo: [...maker.toBuffer(), 0, 0, 0, 0, 0, 0, 0, 0],
+    takerCallbackInfo: [...taker.toBuffer(), 0, 0, 0, 0, 0, 0, 0, 0],
+  });
+
+// An event queue holding the given events, the next pushed event has the sequence number `seqNum`
+const eventQueue = (seqNum: number, events: any[]): EventQueue =>
+  ({
+    header: { count: new BN(events.length), seqNum: new BN(seqNum) },
+    parseEvent: (idx: number) => events[idx],
+  } as unknown as EventQueue);
+
+const market = {
+  bidsAddress: Keypair.generate().publicKey,
+  asksAddress: Keypair.generate().publicKey,
+  eventQueueAddress: Keypair.generate().publicKey,
+  baseCurrencyMultiplier: new BN(1),
+  quoteCurrencyMultiplier: new BN(1),
+  baseSplSizeToNumber: (size: BN) => size.toNumber() / 1_000,
+  quoteSplSizeToNumber: (size: BN) => size.toNumber() / 1_000_000,
+  loadEventQueue: async () => eventQueue(10, []),
+} as unknown as Market;
+
+const markets = { "SOL/USDC": Keypair.generate().publicKey };
+
+const mockConnection = () => {
+  const listeners = new Map<number, [PublicKey, (...args: any[]) => void]>();
+  let nextId = 0;
+  const connection = {
+    getAccountInfoAndContext: async () => ({
+      context: { slot: 42 },
+      value: { data: Buffer.alloc(0) },
+    }),
+    getBlockTime: jest.fn(async (slot: number) => 1_650_000_000 + slot),
+    onAccountChange: (address: PublicKey, callback: any) => {
+      listeners.set(nextId, [address, callback]);
+      return nextId++;
+    },
+    removeAccountChangeListener: jest.fn(async (id: number) => {
+      listeners.delete(id);
+    }),
+  };
+  const notify = (address: PublicKey, slot: number) =>
+    [...listeners.values()]
+      .filter(([k]) => k.equals(address))
+      .forEach(([, callback]) => callback({ data: Buffer.alloc(0) }, { slot }));
+  return {
+    connection: connection as unknown as Connection,
+    mock: connection,
+    listeners,
+    notify,
+  };
+};
+
+const listen = async (gateway: Gateway) => {
+  const server = gateway.listen();
+  await new Promise((resolve) => server.once("listening", resolve));
+  const socket = new WebSocket(
+    `ws://127.0.0.1:${(server.address() as AddressInfo).port}`
+  );
+  await new Promise((resolve) => socket.once("open", resolve));
+  return socket;
+};
+
+const nextMessage = (socket: WebSocket) =>
+  new Promise<any>((resolve) =>
+    socket.once("message", (data) => resolve(JSON.parse(data.toString())))
+  );
+
+const waitFor = async (condition: () => boolean) => {
+  for (let i = 0; i < 100 && !condition(); i++) {
+    await new Promise((resolve) => setTimeout(resolve, 10));
+  }
+  expect(condition()).toBe(true);
+};
+
+afterEach(() => {
+  jest.restoreAllMocks();
+});
+
+test("Fills are numbered from the event queue sequence number", () => {
+  const queue = eventQueue(10, [fill(1, 2), {}, fill(3, 4)]);
+  const fills = sequencedFills(queue);
+  expect(fills.map((f) => f.seqNum.toNumber())).toEqual([7, 9]);
+  expect(sequencedFills(queue, 1).length).toBe(1);
+});
+
+test("Trade ids are built from the sequence number, maker and taker", () => {
+  const [first, second] = sequencedFills(
+    eventQueue(10, [fill(2_000, 50_000_000), fill(2_000, 50_000_000)])
+  );
+  const trade = toCcxtTrade("SOL/USDC", market, first, 1_000);
+  expect(trade.id).toBe(`8-${maker.toBase58()}-${taker.toBase58()}`);
+  expect(trade.timestamp).toBe(1_000);
+  expect(trade.datetime).toBe(new Date(1_000).toISOString());
+  expect(trade.side).toBe("sell");
+  expect(trade.amount).toBe(2);
+  expect(trade.price).toBe(25);
+  // Identical fills are told apart by their position in the queue
+  expect(toCcxtTrade("SOL/USDC", market, second, 1_000).id).not.toBe(
+    trade.id
+  );
+});
+
+test("Trades are timestamped with the block time of the read slot", async () => {
+  const { connection, mock } = mockConnection();
+  jest.spyOn(Market, "load").mockResolvedValue(market);
+  jest
+    .spyOn(EventQueue, "parse")
+    .mockReturnValue(eventQueue(3, [fill(1, 1), fill(1, 1)]));
+  const gateway = new Gateway(connection, markets);
+  const trades = await gateway.fetchTrades("SOL/USDC");
+  expect(mock.getBlockTime).toHaveBeenCalledWith(42);
+  expect(trades.map((t) => t.timestamp)).toEqual([
+    1_650_000_042_000, 1_650_000_042_000,
+  ]);
+  expect(trades.map((t) => t.id.split("-")[0])).toEqual(["1", "2"]);
+});
+
+test("Orderbook updates are pushed over WebSocket", async () => {
+  const { connection, mock, notify } = mockConnection();
+  jest.spyOn(Market, "load").mockResolvedValue(market);
+  const gateway = new Gateway(connection, markets, { port: 0 });
+  let nonce = 0;
+  jest.spyOn(gateway, "fetchOrderBook").mockImplementation(
+    async (symbol) =>
+      ({
+        symbol,
+        bids: [],
+        asks: [],
+        timestamp: 0,
+        datetime: "",
+        nonce: nonce++,
+      } as CcxtOrderbook)
+  );
+  const socket = await listen(gateway);
+  try {
+    const subscribe = { channel: "orderbook", symbol: "SOL/USDC" };
+    let message = nextMessage(socket);
+    socket.send(JSON.stringify({ op: "subscribe", ...subscribe }));
+    expect(await message).toEqual({
+      ...subscribe,
+      data: expect.objectContaining({ symbol: "SOL/USDC", nonce: 0 }),
+    });
+
+    message = nextMessage(socket);
+    notify(market.asksAddress, 1);
+    expect((await message).data.nonce).toBe(1);
+
+    socket.send(JSON.stringify({ op: "unsubscribe", ...subscribe }));
+    await waitFor(
+      () => mock.removeAccountChangeListener.mock.calls.length === 2
+    );
+
+    message = nextMessage(socket);
+    socket.send(JSON.stringify({ op: "subscribe", channel: "orderbook" }));
+    expect((await message).error).toBe("Unknown symbol undefined");
+  } finally {
+    socket.close();
+    gateway.close();
+  }
+});
+
+test("Only new fills are pushed on the trades channel", async () => {
+  const { connection, listeners, notify } = mockConnection();
+  jest.spyOn(Market, "load").mockResolvedValue(market);
+  // The queue holds two fills at subscription time, one more is then appended
+  jest
+    .spyOn(EventQueue, "parse")
+    .mockReturnValue(eventQueue(11, [fill(1, 1), fill(1, 1), fill(1, 1)]));
+  const gateway = new Gateway(connection, markets, { port: 0 });
+  const socket = await listen(gateway);
+  try {
+    const message = nextMessage(socket);
+    socket.send(
+      JSON.stringify({ op: "subscribe", channel: "trades", symbol: "SOL/USDC" })
+    );
+    await waitFor(() => listeners.size === 1);
+    notify(market.eventQueueAddress, 7);
+    const { channel, data } = await message;
+    expect(channel).toBe("trades");
+    expect(data.length).toBe(1);
+    expect(data[0].id).toBe(`10-${maker.toBase58()}-${taker.toBase58()}`);
+    expect(data[0].timestamp).toBe(1_650_000_007_000);
+  } finally {
+    socket.close();
+    gateway.close();
+  }
+});
@@ -0,0 +1,209 @@
+import { signAndSendInstructions } from "@bonfida/utils";
+import { Connection, Keypair, PublicKey } from "@solana/web3.js";
+import {
+  placeOrder,
+  settle,
+  consumeEvents,
+  cancelOrder,
+} from "../src/bindings";
+import BN from "bn.js";
+import { expect } from "@jest/globals";
+import { UserAccount, MarketState } from "../src/state";
+import { AccountLayout } from "@solana/spl-token";
+import { Side } from "@bonfida/aaob";
+import { OrderType, SelfTradeBehavior } from "../src/types";
+import { Market } from "../src/market";
+import { createContext, initializeTraders } from "./utils/context";
+import { computeFp32Price, computeUiPrice } from "../src/utils";
+import { LifecycleCase } from "./utils/matrix";
+
+/** The price of the resting ask, in ticks */
+const PRICE_TICKS = 150;
+/** The sizes of the orders, in base lots */
+const ASK_LOTS = 4;
+const BID_LOTS = 6;
+
+const getAmount = async (connection: Connection, tokenAccount: PublicKey) => {
+  const raw = await connection.getAccountInfo(tokenAccount);
+  if (!raw) {
+    throw new Error(`Account ${tokenAccount.toBase58()} not found`);
+  }
+  return new BN(AccountLayout.decode(raw.data).amount.toString());
+};
+
+/**
+ * Checks that a UI price converts to a FP32 price on the tick grid and back within a tick
+ */
+const checkUiRoundTrip = (market: Market, uiPrice: number, fp32: BN) => {
+  const tickSize = market.tickSizeBN;
+  const converted = computeFp32Price(market, uiPrice);
+  expect(converted.umod(tickSize).isZero()).toBe(true);
+  // The conversion floors to the tick, it should not lose more than one tick
+  expect(converted.lte(fp32)).toBe(true);
+  expect(fp32.sub(converted).lte(tickSize)).toBe(true);
+  const back = computeUiPrice(market, converted);
+  expect(Math.abs(back - uiPrice)).toBeLessThanOrEqual(
+    market.tickSize + uiPrice * 1e-4
+  );
+};
+
+/**
+ * Runs an order lifecycle (post, match, crank, cancel and settle) on a market with the given decimals, tick size
+ * and lot sizes, and checks that no token is created or lost along the way
+ */
+export const lifecycleMatrixTest = async (
+  connection: Connection,
+  feePayer: Keypair,
+  c: LifecycleCase
+) => {
+  const baseMul = c.baseCurrencyMultiplier;
+  const quoteMul = c.quoteCurrencyMultiplier;
+  const tick = c.tickSize.toNumber() / 2 ** 32;
+
+  // The ask sits on the grid, the bid crosses it by two ticks
+  const askFp32 = c.tickSize.muln(PRICE_TICKS);
+  const bidFp32 = c.tickSize.muln(PRICE_TICKS + 2);
+  const toUi = (fp32: BN) =>
+    ((fp32.toNumber() / 2 ** 32) * quoteMul.toNumber()) /
+    baseMul.toNumber() /
+    Math.pow(10, c.quoteDecimals - c.baseDecimals);
+
+  const bidSize = baseMul.muln(BID_LOTS);
+  const askSize = baseMul.muln(ASK_LOTS);
+  const baseTokenAmount = bidSize.muln(10).toNumber();
+  const quoteTokenAmount = Math.ceil(
+    10 * BID_LOTS * (PRICE_TICKS + 2) * tick * quoteMul.toNumber()
+  );
+
+  const { marketKey, base, quote, Alice, Bob } = await createContext(
+    connection,
+    feePayer,
+    c.tickSize,
+    baseMul,
+    c.baseDecimals,
+    c.quoteDecimals,
+    baseMul,
+    quoteMul
+  );
+  const {
+    aliceBaseAta,
+    aliceQuoteAta,
+    bobBaseAta,
+    bobQuoteAta,
+    aliceUa,
+    bobUa,
+  } = await initializeTraders(
+    connection,
+    base,
+    quote,
+    Alice,
+    Bob,
+    feePayer,
+    marketKey,
+    baseTokenAmount,
+    quoteTokenAmount
+  );
+  const market = await Market.load(connection, marketKey);
+
+  /**
+   * UI round trip
+   */
+  checkUiRoundTrip(market, toUi(askFp32), askFp32);
+  checkUiRoundTrip(market, toUi(bidFp32), bidFp32);
+  expect(Math.abs(market.tickSize - toUi(c.tickSize))).toBeLessThanOrEqual(
+    toUi(c.tickSize) * 1e-4
+  );
+
+  /**
+   * Lifecycle
+   */
+  const askPrice = computeFp32Price(market, toUi(askFp32));
+  let tx = await signAndSendInstructions(connection, [Alice, Bob], feePayer, [
+    await placeOrder(
+      market,
+      Side.Ask,
+      toUi(askFp32),
+      askSize.toNumber(),
+      OrderType.Limit,
+      SelfTradeBehavior.AbortTransaction,
+      bobBaseAta,
+      Bob.publicKey
+    ),
+    await placeOrder(
+      market,
+      Side.Bid,
+      toUi(bidFp32),
+      bidSize.toNumber(),
+      OrderType.Limit,
+      SelfTradeBehavior.AbortTransaction,
+      aliceQuoteAta,
+      Alice.publicKey
+    ),
+    await consumeEvents(
+      market,
+      feePayer.publicKey,
+      [aliceUa, bobUa],
+      new BN(10),
+      new BN(1)
+    ),
+  ]);
+  console.log(`Matched ${tx}`);
+
+  let aliceUserAccount = await UserAccount.retrieve(connection, aliceUa);
+  expect(aliceUserAccount.orders.length).toBe(1);
+  tx = await signAndSendInstructions(connection, [Alice, Bob], feePayer, [
+    await cancelOrder(market, Alice.publicKey, aliceUserAccount.orders[0].id),
+    await settle(market, Alice.publicKey, aliceBaseAta, aliceQuoteAta),
+    await settle(market, Bob.publicKey, bobBaseAta, bobQuoteAta),
+  ]);
+  console.log(`Cancelled and settled ${tx}`);
+
+  /**
+   * Balances
+   */
+  aliceUserAccount = await UserAccount.retrieve(connection, aliceUa);
+  const bobUserAccount = await UserAccount.retrieve(connection, bobUa);
+  for (const ua of [aliceUserAccount, bobUserAccount]) {
+    expect(ua.orders.length).toBe(0);
+    expect(ua.baseTokenFree.isZero()).toBe(true);
+    expect(ua.baseTokenLocked.isZero()).toBe(true);
+    expect(ua.quoteTokenFree.isZero()).toBe(true);
+    expect(ua.quoteTokenLocked.isZero()).toBe(true);
+  }
+
+  const aliceBase = await getAmount(connection, aliceBaseAta);
+  const aliceQuote = await getAmount(connection, aliceQuoteAta);
+  const bobBase = await getAmount(connection, bobBaseAta);
+  const bobQuote = await getAmount(connection, bobQuoteAta);
+  const baseVault = await getAmount(connection, market.baseVault);
+  const quoteVault = await getAmount(connection, market.quoteVault);
+
+  // The ask is fully matched at its own price
+  const matchedQuote = askSize
+    .mul(askPrice)
+    .shrn(32)
+    .mul(quoteMul)
+    .div(baseMul);
+  expect(aliceBase.toString()).toBe(
+    askSize.add(new BN(baseTokenAmount)).toString()
+  );
+  expect(bobBase.toString()).toBe(
+    new BN(baseTokenAmount).sub(askSize).toString()
+  );
+  expect(bobQuote.toString()).toBe(
+    matchedQuote.add(new BN(quoteTokenAmount)).toString()
+  );
+
+  // Conservation: what the traders lost is held by the vaults, as fees only
+  expect(aliceBase.add(bobBase).add(baseVault).toString()).toBe(
+    (2 * baseTokenAmount).toString()
+  );
+  expect(aliceQuote.add(bobQuote).add(quoteVault).toString()).toBe(
+    (2 * quoteTokenAmount).toString()
+  );
+  expect(baseVault.isZero()).toBe(true);
+  const marketState = await MarketState.retrieve(connection, marketKey);
+  expect(quoteVault.toString()).toBe(
+    marketState.accumulatedFees.add(marketState.accumulatedRoyalties).toString()
+  );
+};
@@ -0,0 +1,81 @@
+import { expect, test } from "@jest/globals";
+import { Connection, Keypair } from "@solana/web3.js";
+import BN from "bn.js";
+import { deserialize } from "borsh";
+import {
+  diffMarketState,
+  MarketStateChange,
+  MarketStateChangeKind,
+  MarketStateWatcher,
+} from "../src/marketStateWatcher";
+import { MarketState, MarketStatus } from "../src/state";
+
+// A zeroed market state account
+const baseState = deserialize(
+  MarketState.schema,
+  MarketState,
+  Buffer.alloc(736)
+) as MarketState;
+
+const withFields = (fields: Partial<MarketState>): MarketState =>
+  Object.assign(Object.create(MarketState.prototype), baseState, fields);
+
+test("Trading activity is not reported", () => {
+  const next = withFields({
+    baseVolume: new BN(1_000),
+    bestBid: new BN(42),
+    fills: new BN(3),
+  });
+  expect(diffMarketState(baseState, next)).toEqual([]);
+});
+
+test("Governance changes are reported by kind", () => {
+  const admin = Keypair.generate().publicKey;
+  const oracle = Keypair.generate().publicKey;
+  const next = withFields({
+    makerRebateBps: new BN(5),
+    status: MarketStatus.Halted,
+    admin,
+    oracle,
+  });
+  const changes = diffMarketState(baseState, next);
+  expect(changes.map((c) => c.kind)).toEqual([
+    MarketStateChangeKind.FeesUpdated,
+    MarketStateChangeKind.StatusChanged,
+    MarketStateChangeKind.AdminRotated,
+    MarketStateChangeKind.OracleChanged,
+  ]);
+  const fees = changes[0];
+  if (fees.kind !== MarketStateChangeKind.FeesUpdated) {
+    throw new Error("Unexpected change");
+  }
+  expect(fees.previous.makerRebateBps.toNumber()).toBe(0);
+  expect(fees.current.makerRebateBps.toNumber()).toBe(5);
+  expect(changes[2].current).toEqual(admin);
+});
+
+test("A halt flag update is a status change", () => {
+  const changes = diffMarketState(
+    baseState,
+    withFields({ haltFlags: new BN(1) })
+  );
+  expect(changes.length).toBe(1);
+  expect(changes[0].kind).toBe(MarketStateChangeKind.StatusChanged);
+});
+
+test("The watcher diffs each update against the last seen state", () => {
+  const seen: MarketStateChange[] = [];
+  const watcher = new MarketStateWatcher(
+    new Connection("http://localhost:8899"),
+    Keypair.generate().publicKey,
+    { onChange: (change) => seen.push(change) }
+  );
+  // The first snapshot is only recorded
+  expect(watcher.update(baseState)).toEqual([]);
+  const rotated = withFields({ admin: Keypair.generate().publicKey });
+  watcher.update(rotated);
+  watcher.update(rotated);
+  expect(seen.length).toBe(1);
+  expect(seen[0].kind).toBe(MarketStateChangeKind.AdminRotated);
+  expect(watcher.state).toBe(rotated);
+});
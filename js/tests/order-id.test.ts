@@ -0,0 +1,42 @@
+import { expect, test } from "@jest/globals";
+import BN from "bn.js";
+import { decodeOrderId, encodeOrderId } from "../src/orderId";
+import { Side } from "../src/types";
+
+const U64_MAX = new BN(0).notn(64);
+
+test("Order id round trip", () => {
+  const prices = [new BN(0), new BN(1), new BN(2).pow(new BN(32)), U64_MAX];
+  const sequenceNumbers = [new BN(0), new BN(42), new BN(2).pow(new BN(62))];
+  for (const price of prices) {
+    for (const sequenceNumber of sequenceNumbers) {
+      for (const side of [Side.Bid, Side.Ask]) {
+        const orderId = encodeOrderId(price, sequenceNumber, side);
+        const decoded = decodeOrderId(orderId);
+        expect(decoded.price.eq(price)).toBe(true);
+        expect(decoded.sequenceNumber.eq(sequenceNumber)).toBe(true);
+        expect(decoded.side).toBe(side);
+      }
+    }
+  }
+});
+
+test("Order id ordering", () => {
+  const price = new BN(2).pow(new BN(32));
+  // Older bids sort higher at the same price, older asks sort lower
+  const olderBid = encodeOrderId(price, new BN(1), Side.Bid);
+  const newerBid = encodeOrderId(price, new BN(2), Side.Bid);
+  expect(olderBid.gt(newerBid)).toBe(true);
+  const olderAsk = encodeOrderId(price, new BN(1), Side.Ask);
+  const newerAsk = encodeOrderId(price, new BN(2), Side.Ask);
+  expect(olderAsk.lt(newerAsk)).toBe(true);
+  // The price always takes precedence
+  expect(
+    encodeOrderId(price.addn(1), new BN(1), Side.Bid).gt(olderBid)
+  ).toBe(true);
+});
+
+test("Order id encoding rejects oversized components", () => {
+  expect(() => encodeOrderId(U64_MAX.addn(1), new BN(0), Side.Ask)).toThrow();
+  expect(() => encodeOrderId(new BN(0), U64_MAX.addn(1), Side.Bid)).toThrow();
+});
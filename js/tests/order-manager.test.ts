@@ -0,0 +1,79 @@
+import { expect, test } from "@jest/globals";
+import BN from "bn.js";
+import {
+  DivergenceKind,
+  ManagedOrderState,
+  OrderDivergence,
+  OrderManager,
+} from "../src/orderManager";
+import { Order } from "../src/state";
+
+const order = (id: number, clientId: number) =>
+  new Order({
+    id: new BN(id),
+    clientId: new BN(clientId),
+    placedSlot: new BN(0),
+  });
+
+test("Order lifecycle", () => {
+  const manager = new OrderManager({ timeoutMs: 1_000 });
+  const clientId = new BN(1);
+
+  manager.trackPlacement(clientId, undefined, 0);
+  expect(manager.getOrder(clientId)?.state).toBe(ManagedOrderState.Pending);
+
+  manager.reconcile({ orders: [order(10, 1)] }, 100);
+  expect(manager.getOrder(clientId)?.state).toBe(ManagedOrderState.Open);
+  expect(manager.getOrder(clientId)?.orderId?.eqn(10)).toBe(true);
+
+  manager.applyFill(new BN(10), new BN(5));
+  expect(manager.getOrder(clientId)?.filledBaseQty.eqn(5)).toBe(true);
+
+  manager.trackCancel(clientId, undefined, 200);
+  expect(manager.getOrder(clientId)?.state).toBe(
+    ManagedOrderState.CancelPending
+  );
+
+  manager.reconcile({ orders: [] }, 300);
+  expect(manager.getOrder(clientId)?.state).toBe(ManagedOrderState.Closed);
+
+  manager.prune();
+  expect(manager.orders.length).toBe(0);
+});
+
+test("Fully matched placements are closed", () => {
+  const manager = new OrderManager({ timeoutMs: 1_000 });
+  const clientId = new BN(2);
+  manager.trackPlacement(clientId, undefined, 0);
+  manager.markFilled(clientId, new BN(7));
+  const divergences = manager.reconcile({ orders: [] }, 5_000);
+  expect(divergences.length).toBe(0);
+  expect(manager.getOrder(clientId)?.state).toBe(ManagedOrderState.Closed);
+});
+
+test("Divergences are reported once", () => {
+  const reported: OrderDivergence[] = [];
+  const manager = new OrderManager({
+    timeoutMs: 1_000,
+    onDivergence: (d) => reported.push(d),
+  });
+  manager.trackPlacement(new BN(3), undefined, 0);
+  manager.trackPlacement(new BN(4), undefined, 0);
+  manager.reconcile({ orders: [order(40, 4)] }, 10);
+  manager.trackCancel(new BN(4), undefined, 20);
+
+  // Nothing is reported before the timeout
+  expect(manager.reconcile({ orders: [order(40, 4)] }, 500).length).toBe(0);
+
+  const snapshot = { orders: [order(40, 4), order(50, 5)] };
+  const divergences = manager.reconcile(snapshot, 2_000);
+  expect(divergences.map((d) => d.kind).sort()).toEqual(
+    [
+      DivergenceKind.CancelStuck,
+      DivergenceKind.PlacementMissing,
+      DivergenceKind.UnknownOrder,
+    ].sort()
+  );
+  manager.reconcile(snapshot, 3_000);
+  expect(reported.length).toBe(3);
+});
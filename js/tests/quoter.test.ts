@@ -0,0 +1,57 @@
+import { expect, test } from "@jest/globals";
+import { Quoter } from "../src/quoter";
+
+const options = {
+  targetBaseRatio: 0.5,
+  halfSpreadBps: 10,
+  maxSkewBps: 50,
+  size: 10,
+};
+
+test("Quotes are symmetric when the inventory is on target", () => {
+  const quoter = new Quoter(options);
+  const quote = quoter.quote(100, { base: 50, quote: 5_000 });
+  expect(quoter.imbalance({ base: 50, quote: 5_000 }, 100)).toBe(0);
+  expect(quote.bidPrice).toBeCloseTo(99.9);
+  expect(quote.askPrice).toBeCloseTo(100.1);
+  expect(quote.bidSize).toBe(10);
+  expect(quote.askSize).toBe(10);
+});
+
+test("A long inventory skews the quotes down and shrinks the bid", () => {
+  const quoter = new Quoter(options);
+  // 75% of the value is held in base, halfway between the target and a base only inventory
+  const inventory = { base: 75, quote: 2_500 };
+  expect(quoter.imbalance(inventory, 100)).toBeCloseTo(0.5);
+  const quote = quoter.quote(100, inventory);
+  expect(quote.bidPrice).toBeCloseTo(99.75 * 0.999);
+  expect(quote.askPrice).toBeCloseTo(99.75 * 1.001);
+  expect(quote.bidSize).toBeCloseTo(5);
+  expect(quote.askSize).toBe(10);
+});
+
+test("A short inventory skews the quotes up and shrinks the ask", () => {
+  const quoter = new Quoter(options);
+  const quote = quoter.quote(100, { base: 0, quote: 10_000 });
+  expect(quote.bidPrice).toBeGreaterThan(100 * 0.999);
+  expect(quote.askSize).toBe(0);
+  expect(quote.bidSize).toBe(10);
+});
+
+test("Quotes are rounded to the tick size and funded by the inventory", () => {
+  const quoter = new Quoter({ ...options, tickSize: 0.5, minSize: 1 });
+  const quote = quoter.quote(100, { base: 50, quote: 5_000 });
+  expect(quote.bidPrice).toBe(99.5);
+  expect(quote.askPrice).toBe(100.5);
+  // Half a base token cannot fund an ask above the minimum size
+  expect(quoter.quote(100, { base: 0.5, quote: 5_000 }).askSize).toBe(0);
+  const large = new Quoter({ ...options, size: 100, tickSize: 0.5 });
+  const largeQuote = large.quote(100, { base: 50, quote: 5_000 });
+  expect(largeQuote.bidSize).toBeCloseTo(5_000 / 99.5);
+  expect(largeQuote.askSize).toBe(50);
+});
+
+test("Invalid options are rejected", () => {
+  expect(() => new Quoter({ ...options, targetBaseRatio: 1.5 })).toThrow();
+  expect(() => new Quoter({ ...options, maxSkewBps: -1 })).toThrow();
+});
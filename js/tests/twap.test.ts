@@ -0,0 +1,31 @@
+import { expect, test } from "@jest/globals";
+import BN from "bn.js";
+import { twapSchedule } from "../src/twap";
+
+test("Child orders are evenly spread without jitter", () => {
+  const schedule = twapSchedule(new BN(10), 3_000, 4, 0);
+  expect(schedule.map((s) => s.atMs)).toEqual([0, 750, 1_500, 2_250]);
+  expect(schedule.map((s) => s.size.toNumber())).toEqual([3, 3, 2, 2]);
+});
+
+test("Jitter shifts child orders within their interval", () => {
+  const schedule = twapSchedule(new BN(100), 1_000, 10, 0.5, () => 0.99);
+  expect(schedule[0].atMs).toBe(0);
+  for (let [i, slice] of schedule.entries()) {
+    expect(Math.abs(slice.atMs - i * 100)).toBeLessThanOrEqual(25);
+  }
+  const total = schedule.reduce((acc, s) => acc.add(s.size), new BN(0));
+  expect(total.toNumber()).toBe(100);
+});
+
+test("Empty child orders are dropped", () => {
+  const schedule = twapSchedule(new BN(2), 1_000, 5, 0);
+  expect(schedule.length).toBe(2);
+  expect(schedule.map((s) => s.size.toNumber())).toEqual([1, 1]);
+});
+
+test("Invalid schedules are rejected", () => {
+  expect(() => twapSchedule(new BN(10), 1_000, 0)).toThrow();
+  expect(() => twapSchedule(new BN(10), 1_000, 2.5)).toThrow();
+  expect(() => twapSchedule(new BN(10), 1_000, 2, 2)).toThrow();
+});
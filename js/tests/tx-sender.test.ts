@@ -0,0 +1,120 @@
+import { expect, test } from "@jest/globals";
+import {
+  Connection,
+  Keypair,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  computeTransactionFee,
+  MaxFeeExceededError,
+  TransactionExpiredError,
+  TxSender,
+} from "../src/txSender";
+
+test("Transaction fee", () => {
+  expect(computeTransactionFee(1, 200_000)).toBe(5_000);
+  expect(computeTransactionFee(2, 200_000, 1_000)).toBe(10_200);
+  // The prioritization fee is rounded up
+  expect(computeTransactionFee(1, 1, 1)).toBe(5_001);
+});
+
+test("Transactions above the fee cap are not sent", async () => {
+  const connection = new Connection("http://127.0.0.1:1");
+  const sender = new TxSender(connection, {
+    computeUnitLimit: 400_000,
+    computeUnitPriceMicroLamports: 50_000,
+    maxFeeLamports: 10_000,
+  });
+  expect(sender.fee(1)).toBe(25_000);
+
+  const payer = Keypair.generate();
+  const ix = SystemProgram.transfer({
+    fromPubkey: payer.publicKey,
+    toPubkey: payer.publicKey,
+    lamports: 1,
+  });
+  await expect(sender.send([ix], [payer])).rejects.toBeInstanceOf(
+    MaxFeeExceededError
+  );
+
+  // The compute budget instructions are prepended
+  expect(sender.buildTransaction([ix]).instructions.length).toBe(3);
+});
+
+// The landing attempt is only visible from the status check following the given number of checks
+const mockConnection = (landingAttempt: number, hiddenChecks = 0) => {
+  const sent: Transaction[] = [];
+  let blockHeight = 0;
+  let checks = 0;
+  const connection = {
+    commitment: "confirmed",
+    getLatestBlockhash: async () => ({
+      blockhash: Keypair.generate().publicKey.toBase58(),
+      lastValidBlockHeight: blockHeight + 2,
+    }),
+    getBlockHeight: async () => blockHeight++,
+    sendRawTransaction: async (raw: Buffer) => {
+      sent.push(Transaction.from(raw));
+      return `attempt-${sent.length - 1}`;
+    },
+    getSignatureStatuses: async (signatures: string[]) => ({
+      value: signatures.map((_, i) =>
+        i === landingAttempt && checks++ >= hiddenChecks
+          ? {
+              slot: 1,
+              confirmations: 1,
+              err: null,
+              confirmationStatus: "confirmed",
+            }
+          : null
+      ),
+    }),
+  };
+  return { connection: connection as unknown as Connection, sent };
+};
+
+// The compute unit price follows the discriminator of the second compute budget instruction
+const computeUnitPrice = (tx: Transaction) =>
+  tx.instructions[1].data.readUInt32LE(1);
+
+test("Transactions are resubmitted with a higher fee until one lands", async () => {
+  const payer = Keypair.generate();
+  const ix = SystemProgram.transfer({
+    fromPubkey: payer.publicKey,
+    toPubkey: payer.publicKey,
+    lamports: 1,
+  });
+  const options = { retryIntervalMs: 0, pollIntervalMs: 0 };
+
+  const { connection, sent } = mockConnection(2);
+  const sender = new TxSender(connection, {
+    computeUnitLimit: 100_000,
+    computeUnitPriceMicroLamports: 10_000,
+    maxFeeLamports: 8_000,
+  });
+  const signature = await sender.sendWithResubmission([ix], [payer], options);
+  expect(signature).toBe("attempt-2");
+  // The third attempt is capped by the fee cap
+  expect(sent.map(computeUnitPrice)).toEqual([10_000, 20_000, 30_000]);
+  expect(sent[0].recentBlockhash).not.toBe(sent[1].recentBlockhash);
+
+  // The resubmission stops once the order is known to have landed
+  const guarded = mockConnection(0, 1);
+  const landed = await new TxSender(guarded.connection).sendWithResubmission(
+    [ix],
+    [payer],
+    { ...options, isLanded: async () => true }
+  );
+  expect(landed).toBe("attempt-0");
+  expect(guarded.sent.length).toBe(1);
+
+  const expired = mockConnection(-1);
+  await expect(
+    new TxSender(expired.connection).sendWithResubmission([ix], [payer], {
+      ...options,
+      maxAttempts: 3,
+    })
+  ).rejects.toBeInstanceOf(TransactionExpiredError);
+  expect(expired.sent.length).toBe(3);
+});
@@ -0,0 +1,49 @@
+import { expect, test } from "@jest/globals";
+import { serialize } from "borsh";
+import BN from "bn.js";
+import { getUserAccountSpace } from "../src/bindings";
+import { ORDER_LEN, USER_ACCOUNT_HEADER_LEN, UserAccount } from "../src/state";
+
+// Pinned by the compile-time assertions of program/src/state.rs
+const RUST_USER_ACCOUNT_HEADER_LEN = 192;
+const RUST_ORDER_LEN = 80;
+
+/** The size computed by `initialize_account::required_space` in the program */
+const requiredSpace = (maxOrders: number) =>
+  RUST_USER_ACCOUNT_HEADER_LEN + maxOrders * RUST_ORDER_LEN;
+
+test("The user account layout matches the program", () => {
+  expect(USER_ACCOUNT_HEADER_LEN).toBe(RUST_USER_ACCOUNT_HEADER_LEN);
+  expect(ORDER_LEN).toBe(RUST_ORDER_LEN);
+  for (const maxOrders of [1, 3, 20, 1_000]) {
+    expect(getUserAccountSpace(maxOrders)).toBe(requiredSpace(maxOrders));
+  }
+  expect(() => getUserAccountSpace(0)).toThrow();
+});
+
+test("The user account header length matches its borsh encoding", () => {
+  const header = {
+    tag: new BN(5),
+    market: new Uint8Array(32),
+    owner: new Uint8Array(32),
+    baseTokenFree: new BN(0),
+    baseTokenLocked: new BN(0),
+    quoteTokenFree: new BN(0),
+    quoteTokenLocked: new BN(0),
+    accumulatedRebates: new BN(0),
+    accumulatedMakerQuoteVolume: new BN(0),
+    accumulatedMakerBaseVolume: new BN(0),
+    accumulatedTakerQuoteVolume: new BN(0),
+    accumulatedTakerBaseVolume: new BN(0),
+    metadata: new Uint8Array(32),
+    lastActiveSlot: new BN(0),
+    hasSettleAllowlist: 0,
+    _padding: new Uint8Array(3),
+    orders: [],
+  };
+  const encoded = serialize(
+    UserAccount.schema,
+    Object.assign(Object.create(UserAccount.prototype), header)
+  );
+  expect(encoded.length).toBe(USER_ACCOUNT_HEADER_LEN);
+});
@@ -0,0 +1,43 @@
+import { signAndSendInstructions } from "@bonfida/utils";
+import { Connection, Keypair } from "@solana/web3.js";
+import { expect } from "@jest/globals";
+import {
+  getUserAccountRent,
+  getUserAccountSpace,
+  initializeAccount,
+} from "../src/bindings";
+import { getUserAccountAddress } from "../src/utils";
+import { DEX_ID } from "../src/ids";
+
+/**
+ * Checks that user accounts are allocated by the program with the size and rent advertised by the JS helpers
+ */
+export const userAccountSpaceTest = async (
+  connection: Connection,
+  feePayer: Keypair
+) => {
+  const owner = Keypair.generate();
+  // The market of a user account is not checked at initialization
+  const market = Keypair.generate().publicKey;
+  const maxOrders = 7;
+
+  const tx = await signAndSendInstructions(connection, [owner], feePayer, [
+    await initializeAccount(
+      market,
+      owner.publicKey,
+      maxOrders,
+      feePayer.publicKey
+    ),
+  ]);
+  console.log(`Initialized user account ${tx}`);
+
+  const userAccount = await getUserAccountAddress(
+    market,
+    owner.publicKey,
+    0,
+    DEX_ID
+  );
+  const info = await connection.getAccountInfo(userAccount);
+  expect(info?.data.length).toBe(getUserAccountSpace(maxOrders));
+  expect(info?.lamports).toBe(await getUserAccountRent(connection, maxOrders));
+};
@@ -0,0 +1,54 @@
+import BN from "bn.js";
+
+/**
+ * The market configuration of a lifecycle test case
+ */
+export interface LifecycleCase {
+  baseDecimals: number;
+  quoteDecimals: number;
+  /** The tick size (as a FP32) */
+  tickSize: BN;
+  baseCurrencyMultiplier: BN;
+  quoteCurrencyMultiplier: BN;
+}
+
+/** The quote decimals crossed with every base decimals between 0 and 9 */
+const QUOTE_DECIMALS = [0, 3, 6, 9];
+
+/** A whole and a fractional tick size */
+const TICK_SIZES = [new BN(2 ** 32), new BN(2 ** 30)];
+
+/**
+ * Generates the lifecycle test cases.
+ *
+ * Every base decimals between 0 and 9 is crossed with a spread of quote decimals, while the tick sizes and the
+ * unit or scaled lot sizes are rotated through the decimal pairs so that each combination appears at both low and
+ * high decimals.
+ */
+export const lifecycleMatrix = (): LifecycleCase[] => {
+  const cases: LifecycleCase[] = [];
+  let i = 0;
+  for (let baseDecimals = 0; baseDecimals <= 9; baseDecimals++) {
+    for (const quoteDecimals of QUOTE_DECIMALS) {
+      const scaledLots = Math.floor(i / TICK_SIZES.length) % 2 === 1;
+      cases.push({
+        baseDecimals,
+        quoteDecimals,
+        tickSize: TICK_SIZES[i % TICK_SIZES.length],
+        baseCurrencyMultiplier: scaledLots
+          ? new BN(10).pow(new BN(Math.min(baseDecimals, 3)))
+          : new BN(1),
+        quoteCurrencyMultiplier: scaledLots
+          ? new BN(10).pow(new BN(Math.min(quoteDecimals, 2)))
+          : new BN(1),
+      });
+      i++;
+    }
+  }
+  return cases;
+};
+
+export const describeCase = (c: LifecycleCase) =>
+  `decimals ${c.baseDecimals}/${c.quoteDecimals}, tick ${
+    c.tickSize.toNumber() / 2 ** 32
+  }, lots ${c.baseCurrencyMultiplier.toString()}/${c.quoteCurrencyMultiplier.toString()}`;
@@ -0,0 +1,3055 @@
+//! Names and roles of the accounts of each instruction, indexed like the accounts of the instruction.
+//!
+//! Wallets and explorers can use these tables to label the accounts of a dex-v4 transaction in their previews,
+//! without depending on the instruction builders. An optional account is only present when the instruction needs it,
+//! and a variable entry stands for a list of accounts of any length, possibly empty.
+use crate::instruction_auto::DexInstruction;
+
+/// Describes an account of an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountMetaInfo {
+    /// The name of the account in the instruction builders
+    pub name: &'static str,
+    /// A human readable description of the account
+    pub description: &'static str,
+    /// Whether the account is written to
+    pub writable: bool,
+    /// Whether the account signs the transaction
+    pub signer: bool,
+    /// Whether the account can be left out
+    pub optional: bool,
+    /// Whether the entry stands for a list of accounts
+    pub variable: bool,
+}
+
+impl DexInstruction {
+    /// Returns the accounts of the instruction, in order
+    pub fn get_accounts_metas(self) -> &'static [AccountMetaInfo] {
+        use DexInstruction::*;
+        match self {
+            CreateMarket => CREATE_MARKET_ACCOUNTS,
+            NewOrder => NEW_ORDER_ACCOUNTS,
+            Swap => SWAP_ACCOUNTS,
+            CancelOrder => CANCEL_ORDER_ACCOUNTS,
+            ConsumeEvents => CONSUME_EVENTS_ACCOUNTS,
+            Settle => SETTLE_ACCOUNTS,
+            InitializeAccount => INITIALIZE_ACCOUNT_ACCOUNTS,
+            SweepFees => SWEEP_FEES_ACCOUNTS,
+            CloseAccount => CLOSE_ACCOUNT_ACCOUNTS,
+            CloseMarket => CLOSE_MARKET_ACCOUNTS,
+            UpdateRoyalties => UPDATE_ROYALTIES_ACCOUNTS,
+            MigrateAccount => MIGRATE_ACCOUNT_ACCOUNTS,
+            CreateMakerRewards => CREATE_MAKER_REWARDS_ACCOUNTS,
+            SetAccountMetadata => SET_ACCOUNT_METADATA_ACCOUNTS,
+            HashOrderbook => HASH_ORDERBOOK_ACCOUNTS,
+            SetMarketStatus => SET_MARKET_STATUS_ACCOUNTS,
+            ForceConsumeEvents => FORCE_CONSUME_EVENTS_ACCOUNTS,
+            ClaimEscrow => CLAIM_ESCROW_ACCOUNTS,
+            UpdateMarketMetadata => UPDATE_MARKET_METADATA_ACCOUNTS,
+            SetOracle => SET_ORACLE_ACCOUNTS,
+            SetFillPrivacy => SET_FILL_PRIVACY_ACCOUNTS,
+            SetBatchAuction => SET_BATCH_AUCTION_ACCOUNTS,
+            NewBatchOrder => NEW_BATCH_ORDER_ACCOUNTS,
+            MatchBatch => MATCH_BATCH_ACCOUNTS,
+            CreateSession => CREATE_SESSION_ACCOUNTS,
+            CancelSessionOrders => CANCEL_SESSION_ORDERS_ACCOUNTS,
+            SetDesignatedMarketMaker => SET_DESIGNATED_MARKET_MAKER_ACCOUNTS,
+            GetMarketInfo => GET_MARKET_INFO_ACCOUNTS,
+            SetCrankFeeShare => SET_CRANK_FEE_SHARE_ACCOUNTS,
+            SetVolatilityFee => SET_VOLATILITY_FEE_ACCOUNTS,
+            CreateCrankerIdentity => CREATE_CRANKER_IDENTITY_ACCOUNTS,
+            FreezeAndExport => FREEZE_AND_EXPORT_ACCOUNTS,
+            ImportMarket => IMPORT_MARKET_ACCOUNTS,
+            SetMinRestingSlots => SET_MIN_RESTING_SLOTS_ACCOUNTS,
+            Heartbeat => HEARTBEAT_ACCOUNTS,
+            RelinkOrderbook => RELINK_ORDERBOOK_ACCOUNTS,
+            CreateSettleAllowlist => CREATE_SETTLE_ALLOWLIST_ACCOUNTS,
+            PreviewNewOrder => PREVIEW_NEW_ORDER_ACCOUNTS,
+            CancelAllOrders => CANCEL_ALL_ORDERS_ACCOUNTS,
+            ReplaceOrder => REPLACE_ORDER_ACCOUNTS,
+            ReclaimOrderbook => RECLAIM_ORDERBOOK_ACCOUNTS,
+            SetCreateMarketConfig => SET_CREATE_MARKET_CONFIG_ACCOUNTS,
+            SetReferralShare => SET_REFERRAL_SHARE_ACCOUNTS,
+            SetMakerRebate => SET_MAKER_REBATE_ACCOUNTS,
+            SetEmergencyAuthority => SET_EMERGENCY_AUTHORITY_ACCOUNTS,
+            EmergencyHalt => EMERGENCY_HALT_ACCOUNTS,
+            UpdateMarketAdmin => UPDATE_MARKET_ADMIN_ACCOUNTS,
+            SetCancelFee => SET_CANCEL_FEE_ACCOUNTS,
+            NominateAdmin => NOMINATE_ADMIN_ACCOUNTS,
+            AcceptAdmin => ACCEPT_ADMIN_ACCOUNTS,
+            ArchiveUserAccount => ARCHIVE_USER_ACCOUNT_ACCOUNTS,
+            RestoreUserAccount => RESTORE_USER_ACCOUNT_ACCOUNTS,
+            SetHaltFlags => SET_HALT_FLAGS_ACCOUNTS,
+            UpdateMarketParams => UPDATE_MARKET_PARAMS_ACCOUNTS,
+            PruneExpiredOrders => PRUNE_EXPIRED_ORDERS_ACCOUNTS,
+            SetCrankRewardScaling => SET_CRANK_REWARD_SCALING_ACCOUNTS,
+            Deposit => DEPOSIT_ACCOUNTS,
+            Withdraw => WITHDRAW_ACCOUNTS,
+            SettleToAssociatedAccounts => SETTLE_TO_ASSOCIATED_ACCOUNTS_ACCOUNTS,
+        }
+    }
+
+    /// Returns the account at an index of the accounts of the instruction.
+    ///
+    /// The lookup gives up at the first variable list of accounts, whose length isn't known from the instruction alone.
+    pub fn get_account_meta(self, index: usize) -> Option<&'static AccountMetaInfo> {
+        let metas = self.get_accounts_metas();
+        let known = metas.iter().position(|m| m.variable).unwrap_or(metas.len());
+        metas[..known].get(index)
+    }
+}
+
+/// The accounts of a create_market instruction
+pub const CREATE_MARKET_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The market account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base vault account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote vault account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "token_metadata",
+        description: "The metaplex token metadata",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "create_market_config",
+        description: "The create market config account of the deployment, which may not exist",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_payer",
+        description: "The optional listing fee payer, required when the deployment charges a listing fee",
+        writable: true,
+        signer: true,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_source",
+        description: "The optional token account paying a listing fee in tokens, the fee payer itself for a fee in lamports",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "treasury",
+        description: "The optional treasury of the create market config",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_program",
+        description: "The optional program transferring the listing fee, the system program or the SPL token program",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a new_order instruction
+pub const NEW_ORDER_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The SPL token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_token_account",
+        description: "The user source token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user wallet, or the session key for session orders",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "discount_token_account",
+        description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "oracle",
+        description: "The optional oracle price account, required if the market has an oracle",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "session",
+        description: "The optional session account, required for session orders",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "dmm_registry",
+        description: "The optional designated market maker registry, which waives the taker fees of registered user accounts",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_referral_account",
+        description: "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a swap instruction
+pub const SWAP_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The SPL token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_signer",
+        description: "The DEX market signer",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_base_account",
+        description: "The user base token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_quote_account",
+        description: "The user quote token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user wallet",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "discount_token_account",
+        description:
+            "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "oracle",
+        description: "The optional oracle price account, required if the market has an oracle",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_referral_account",
+        description:
+            "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a cancel_order instruction
+pub const CANCEL_ORDER_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user wallet",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a consume_events instruction
+pub const CONSUME_EVENTS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "reward_target",
+        description: "The reward target, credited with the cranker's share of the fees when it is a user account of the market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_accounts",
+        description: "The relevant user accounts, along with the session accounts to prune when pruning expired sessions",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: true,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The optional AOB bids shared memory, required when sampling maker rewards, pruning expired sessions or reposting iceberg orders",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The optional AOB asks shared memory, required when sampling maker rewards, pruning expired sessions or reposting iceberg orders",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "maker_rewards",
+        description: "The optional maker rewards account, required when sampling maker rewards",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "cranker_identity",
+        description: "The optional cranker identity account, required when recording the crank",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "cranker",
+        description: "The optional owner of the cranker identity account, required when recording the crank",
+        writable: false,
+        signer: true,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a settle instruction
+pub const SETTLE_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The spl token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_signer",
+        description: "The DEX market signer account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The DEX user account owner wallet, which receives the unwrapped SOL",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destination_base_account",
+        description: "The destination base token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destination_quote_account",
+        description: "The destination quote token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "settle_allowlist",
+        description:
+            "The settle allowlist of the user account, required when one has been registered",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a initialize_account instruction
+pub const INITIALIZE_ACCOUNT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The user account to initialize",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The owner of the user account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_payer",
+        description: "The fee payer",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a sweep_fees instruction
+pub const SWEEP_FEES_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_signer",
+        description: "The DEX market signer",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The market quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destination_token_account",
+        description: "The destination token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The spl token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "token_metadata",
+        description: "The metadata account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "creators_token_accounts",
+        description: "The creator token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: true,
+    },
+];
+
+/// The accounts of a close_account instruction
+pub const CLOSE_ACCOUNT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "user",
+        description: "The user account to close",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The owner of the user account to close",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "target_lamports_account",
+        description: "The target lamports account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a close_market instruction
+pub const CLOSE_MARKET_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The market account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The market base vault account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The market quote vault account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The AOB orderbook account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The makret admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "target_lamports_account",
+        description: "The target lamports account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_signer",
+        description: "The market signer",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The SPL token program ID",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a update_royalties instruction
+pub const UPDATE_ROYALTIES_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The event queue account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The AOB market account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "token_metadata",
+        description: "The token metadata",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a migrate_account instruction
+pub const MIGRATE_ACCOUNT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "account",
+        description: "The DEX market or user account to migrate",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "payer",
+        description: "The account paying for the rent of the added space",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a create_maker_rewards instruction
+pub const CREATE_MAKER_REWARDS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "maker_rewards",
+        description: "The maker rewards account to initialize",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_payer",
+        description: "The fee payer",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_account_metadata instruction
+pub const SET_ACCOUNT_METADATA_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The owner of the user account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a hash_orderbook instruction
+pub const HASH_ORDERBOOK_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_market_status instruction
+pub const SET_MARKET_STATUS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a force_consume_events instruction
+pub const FORCE_CONSUME_EVENTS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account, which pays for the escrow account creation",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "escrow",
+        description: "The market escrow account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_accounts",
+        description: "The available user accounts",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: true,
+    },
+];
+
+/// The accounts of a claim_escrow instruction
+pub const CLAIM_ESCROW_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "escrow",
+        description: "The market escrow account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user account owner wallet",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a update_market_metadata instruction
+pub const UPDATE_MARKET_METADATA_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_oracle instruction
+pub const SET_ORACLE_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "oracle",
+        description: "The optional Pyth price account",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_fill_privacy instruction
+pub const SET_FILL_PRIVACY_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_batch_auction instruction
+pub const SET_BATCH_AUCTION_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "batch_auction",
+        description: "The market batch auction account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account, which pays for the batch auction account creation",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a new_batch_order instruction
+pub const NEW_BATCH_ORDER_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The SPL token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "batch_auction",
+        description: "The market batch auction account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_token_account",
+        description: "The user source token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user wallet",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "discount_token_account",
+        description:
+            "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a match_batch instruction
+pub const MATCH_BATCH_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "batch_auction",
+        description: "The market batch auction account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_accounts",
+        description: "The user accounts of the pending orders, sorted by key",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: true,
+    },
+];
+
+/// The accounts of a create_session instruction
+pub const CREATE_SESSION_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user account owner wallet, which pays for the session account creation",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "session",
+        description: "The session account of the user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a cancel_session_orders instruction
+pub const CANCEL_SESSION_ORDERS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "session",
+        description: "The expired session account of the user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_designated_market_maker instruction
+pub const SET_DESIGNATED_MARKET_MAKER_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "dmm_registry",
+        description: "The market designated market maker registry",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account, which pays for the registry account creation",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a get_market_info instruction
+pub const GET_MARKET_INFO_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_crank_fee_share instruction
+pub const SET_CRANK_FEE_SHARE_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_volatility_fee instruction
+pub const SET_VOLATILITY_FEE_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a create_cranker_identity instruction
+pub const CREATE_CRANKER_IDENTITY_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "cranker_identity",
+        description: "The cranker identity account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "cranker",
+        description: "The operator's wallet, which pays for the identity account creation",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a freeze_and_export instruction
+pub const FREEZE_AND_EXPORT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The SPL token program ID",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The market base vault account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The market quote vault account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_signer",
+        description: "The market signer",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "migration_market_signer",
+        description: "The signer of the migration market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a import_market instruction
+pub const IMPORT_MARKET_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "source_market",
+        description: "The exported market of the previous deployment",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "source_base_vault",
+        description: "The base vault of the exported market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "source_quote_vault",
+        description: "The quote vault of the exported market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_min_resting_slots instruction
+pub const SET_MIN_RESTING_SLOTS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a heartbeat instruction
+pub const HEARTBEAT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description:
+            "The owner of the user account, or the session key when a session account is given",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "session",
+        description: "The optional session account authorizing the session key",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a relink_orderbook instruction
+pub const RELINK_ORDERBOOK_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The current orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The current AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The current AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The current AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "new_orderbook",
+        description: "The new orderbook account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "new_event_queue",
+        description: "The new AOB event queue account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "new_bids",
+        description: "The new AOB bids account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "new_asks",
+        description: "The new AOB asks account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "target_lamports_account",
+        description: "The account receiving the lamports of the closed AOB accounts",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a create_settle_allowlist instruction
+pub const CREATE_SETTLE_ALLOWLIST_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description:
+            "The DEX user account owner wallet, which pays for the allowlist account creation",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "settle_allowlist",
+        description: "The settle allowlist account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destinations",
+        description: "The allowed destination token accounts",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: true,
+    },
+];
+
+/// The accounts of a preview_new_order instruction
+pub const PREVIEW_NEW_ORDER_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The SPL token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_token_account",
+        description: "The user source token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user wallet, or the session key for session orders",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "discount_token_account",
+        description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "oracle",
+        description: "The optional oracle price account, required if the market has an oracle",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "session",
+        description: "The optional session account, required for session orders",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "dmm_registry",
+        description: "The optional designated market maker registry, which waives the taker fees of registered user accounts",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_referral_account",
+        description: "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a cancel_all_orders instruction
+pub const CANCEL_ALL_ORDERS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user wallet",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a replace_order instruction
+pub const REPLACE_ORDER_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The SPL token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_token_account",
+        description: "The user source token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The user wallet, or the session key for session orders",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "discount_token_account",
+        description: "The optional SRM or MSRM discount token account (must be owned by the user wallet)",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "oracle",
+        description: "The optional oracle price account, required if the market has an oracle",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "session",
+        description: "The optional session account, required for session orders",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "dmm_registry",
+        description: "The optional designated market maker registry, which waives the taker fees of registered user accounts",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "fee_referral_account",
+        description: "The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees",
+        writable: true,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a reclaim_orderbook instruction
+pub const RECLAIM_ORDERBOOK_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "target_lamports_account",
+        description: "The account receiving the lamports of the closed AOB accounts",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_create_market_config instruction
+pub const SET_CREATE_MARKET_CONFIG_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "create_market_config",
+        description: "The create market config account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "sweep_authority",
+        description: "The sweep authority, which pays for the config account creation",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_referral_share instruction
+pub const SET_REFERRAL_SHARE_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_maker_rebate instruction
+pub const SET_MAKER_REBATE_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_emergency_authority instruction
+pub const SET_EMERGENCY_AUTHORITY_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a emergency_halt instruction
+pub const EMERGENCY_HALT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "emergency_authority",
+        description: "The emergency authority of the market",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a update_market_admin instruction
+pub const UPDATE_MARKET_ADMIN_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_cancel_fee instruction
+pub const SET_CANCEL_FEE_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a nominate_admin instruction
+pub const NOMINATE_ADMIN_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a accept_admin instruction
+pub const ACCEPT_ADMIN_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "pending_admin",
+        description: "The nominated market admin",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a archive_user_account instruction
+pub const ARCHIVE_USER_ACCOUNT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account to archive",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The DEX user account owner wallet, which pays for the archive account and receives the user account's rent",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "archive",
+        description: "The user account archive",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a restore_user_account instruction
+pub const RESTORE_USER_ACCOUNT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account to restore",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The DEX user account owner wallet, which pays for the user account and receives the archive's rent",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "archive",
+        description: "The user account archive",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a set_halt_flags instruction
+pub const SET_HALT_FLAGS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a update_market_params instruction
+pub const UPDATE_MARKET_PARAMS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a prune_expired_orders instruction
+pub const PRUNE_EXPIRED_ORDERS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "orderbook",
+        description: "The orderbook",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "event_queue",
+        description: "The AOB event queue",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "bids",
+        description: "The AOB bids shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "asks",
+        description: "The AOB asks shared memory",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "reward_target",
+        description: "The account receiving the prune rewards",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_accounts",
+        description: "The user accounts whose expired orders should be pruned",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: true,
+    },
+];
+
+/// The accounts of a set_crank_reward_scaling instruction
+pub const SET_CRANK_REWARD_SCALING_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_admin",
+        description: "The market admin account",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a deposit instruction
+pub const DEPOSIT_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The spl token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The DEX user account owner wallet",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "source_base_account",
+        description: "The source base token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "source_quote_account",
+        description: "The source quote token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+];
+
+/// The accounts of a withdraw instruction
+pub const WITHDRAW_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The spl token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_signer",
+        description: "The DEX market signer account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The DEX user account owner wallet",
+        writable: false,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destination_base_account",
+        description: "The destination base token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destination_quote_account",
+        description: "The destination quote token account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "settle_allowlist",
+        description:
+            "The settle allowlist of the user account, required when one has been registered",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
+
+/// The accounts of a settle_to_associated_accounts instruction
+pub const SETTLE_TO_ASSOCIATED_ACCOUNTS_ACCOUNTS: &[AccountMetaInfo] = &[
+    AccountMetaInfo {
+        name: "spl_token_program",
+        description: "The spl token program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "system_program",
+        description: "The system program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "associated_token_program",
+        description: "The spl associated token account program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "rent_sysvar",
+        description: "The rent sysvar, required by the associated token account program",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market",
+        description: "The DEX market",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_mint",
+        description: "The base token mint",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_mint",
+        description: "The quote token mint",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "base_vault",
+        description: "The base token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "quote_vault",
+        description: "The quote token vault",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "market_signer",
+        description: "The DEX market signer account",
+        writable: false,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user",
+        description: "The DEX user account",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "user_owner",
+        description: "The DEX user account owner wallet, which pays for the creation of the destination accounts",
+        writable: true,
+        signer: true,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destination_base_account",
+        description: "The associated base token account of the owner",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "destination_quote_account",
+        description: "The associated quote token account of the owner",
+        writable: true,
+        signer: false,
+        optional: false,
+        variable: false,
+    },
+    AccountMetaInfo {
+        name: "settle_allowlist",
+        description: "The settle allowlist of the user account, required when one has been registered",
+        writable: false,
+        signer: false,
+        optional: true,
+        variable: false,
+    },
+];
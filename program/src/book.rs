@@ -0,0 +1,112 @@
+//! Read-only views of a market's orderbook, for on-chain programs which are given the bids and asks accounts of a
+//! DEX market, such as liquidation routers which size their orders against the book before trading.
+//!
+//! The views walk the critbit trees in place and never allocate, which keeps them usable within the small heap of an
+//! on-chain program. Prices are the FP32 prices of the orderbook and quantities are in base lots: they are converted
+//! to token amounts with the market's `base_currency_multiplier` and `quote_currency_multiplier`.
+use std::iter::Peekable;
+
+use asset_agnostic_orderbook::state::{critbit::Slab, AccountTag, Side};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{state::CallBackInfo, utils::leaf_handles};
+
+/// A resting order of the orderbook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookOrder {
+    /// The AOB order id, which encodes the price and the queue position of the order
+    pub order_id: u128,
+    /// The FP32 price of the order
+    pub price: u64,
+    /// The remaining base quantity of the order, in base lots
+    pub base_qty: u64,
+    /// The DEX user account which owns the order
+    pub user_account: Pubkey,
+}
+
+/// A price level of the orderbook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    /// The FP32 price of the level
+    pub price: u64,
+    /// The total base quantity resting at this price, in base lots
+    pub base_qty: u64,
+    /// The number of orders resting at this price
+    pub orders: u32,
+}
+
+/// One side of the orderbook, loaded from the data of the bids or the asks account
+pub struct BookSide<'a> {
+    slab: Slab<'a, CallBackInfo>,
+    side: Side,
+}
+
+impl<'a> BookSide<'a> {
+    /// The caller should check that the account is the bids or asks account of the market's orderbook, as only the
+    /// account tag is verified here.
+    pub fn from_buffer(buf: &'a mut [u8], side: Side) -> Result<Self, ProgramError> {
+        let tag = match side {
+            Side::Bid => AccountTag::Bids,
+            Side::Ask => AccountTag::Asks,
+        };
+        let slab = Slab::from_buffer(buf, tag)?;
+        Ok(Self { slab, side })
+    }
+
+    /// The resting orders from the best price, in the order in which a taker matches them
+    pub fn orders(&self) -> impl Iterator<Item = BookOrder> + '_ {
+        leaf_handles(&self.slab, matches!(self.side, Side::Ask)).map(move |handle| {
+            let leaf = &self.slab.leaf_nodes[handle as usize];
+            BookOrder {
+                order_id: leaf.key,
+                price: (leaf.key >> 64) as u64,
+                base_qty: leaf.base_quantity,
+                user_account: self.slab.callback_infos[handle as usize].user_account,
+            }
+        })
+    }
+
+    /// The resting orders of a user account, from the best price
+    pub fn orders_of<'b>(
+        &'b self,
+        user_account: &'b Pubkey,
+    ) -> impl Iterator<Item = BookOrder> + 'b {
+        self.orders()
+            .filter(move |o| &o.user_account == user_account)
+    }
+
+    /// The price levels from the best price. The best `n` levels are given by `levels().take(n)`.
+    pub fn levels(&self) -> Levels<impl Iterator<Item = BookOrder> + '_> {
+        Levels {
+            orders: self.orders().peekable(),
+        }
+    }
+
+    /// The best order of this side, if any
+    pub fn best(&self) -> Option<BookOrder> {
+        self.orders().next()
+    }
+}
+
+/// An iterator over the price levels of a side of the orderbook, see [`BookSide::levels`]
+pub struct Levels<I: Iterator<Item = BookOrder>> {
+    orders: Peekable<I>,
+}
+
+impl<I: Iterator<Item = BookOrder>> Iterator for Levels<I> {
+    type Item = Level;
+
+    fn next(&mut self) -> Option<Level> {
+        let first = self.orders.next()?;
+        let mut level = Level {
+            price: first.price,
+            base_qty: first.base_qty,
+            orders: 1,
+        };
+        while let Some(order) = self.orders.next_if(|o| o.price == level.price) {
+            level.base_qty += order.base_qty;
+            level.orders += 1;
+        }
+        Some(level)
+    }
+}
@@ -0,0 +1,128 @@
+//! The order enums shared by the program and its clients.
+//!
+//! Instruction parameters carry these enums as `u8` fields so that they stay `Pod`. The numeric encoding of each
+//! variant is fixed by an explicit discriminant and must never change, as it is part of the instruction format.
+use asset_agnostic_orderbook::state as aaob;
+use borsh::{BorshDeserialize, BorshSerialize};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
+use solana_program::program_error::ProgramError;
+use std::convert::TryFrom;
+
+/// The side of an order
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive, BorshDeserialize, BorshSerialize,
+)]
+#[repr(u8)]
+#[allow(missing_docs)]
+pub enum Side {
+    Bid = 0,
+    Ask = 1,
+}
+
+/// This enum describes different supported behaviors for handling self trading scenarios
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive, BorshDeserialize, BorshSerialize,
+)]
+#[repr(u8)]
+pub enum SelfTradeBehavior {
+    /// Decrement take means that both the maker and taker sides of the matched orders are decremented.
+    ///
+    /// This is equivalent to a normal order match, except for the fact that no fees are applies.
+    DecrementTake = 0,
+    /// Cancels the maker side of the order.
+    CancelProvide = 1,
+    /// Cancels the whole transaction as soon as a self-matching scenario is encountered.
+    AbortTransaction = 2,
+}
+
+/// This enum describes all supported order types
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive, BorshDeserialize, BorshSerialize,
+)]
+#[repr(u8)]
+pub enum OrderType {
+    #[allow(missing_docs)]
+    Limit = 0,
+    #[allow(missing_docs)]
+    ImmediateOrCancel = 1,
+    #[allow(missing_docs)]
+    FillOrKill = 2,
+    #[allow(missing_docs)]
+    PostOnly = 3,
+    /// Matches against the book without posting, up to the worst acceptable price given as the limit price
+    Market = 4,
+}
+
+/// This enum describes the quantity an order is sized by
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive, BorshDeserialize, BorshSerialize,
+)]
+#[repr(u8)]
+pub enum QuantityMode {
+    /// The order trades up to `max_base_qty`
+    Base = 0,
+    /// The order trades up to `max_quote_qty`. Bids spend it, taker fees included, while asks sell the base quantity
+    /// worth it, taker fees excluded. A nonzero `max_base_qty` further caps the traded base quantity.
+    Quote = 1,
+}
+
+macro_rules! impl_u8_encoding {
+    ($($t:ident),*) => {
+        $(
+            impl From<$t> for u8 {
+                fn from(value: $t) -> Self {
+                    value as u8
+                }
+            }
+
+            impl TryFrom<u8> for $t {
+                type Error = ProgramError;
+
+                fn try_from(value: u8) -> Result<Self, Self::Error> {
+                    FromPrimitive::from_u8(value).ok_or(ProgramError::InvalidArgument)
+                }
+            }
+        )*
+    };
+}
+
+impl_u8_encoding!(Side, SelfTradeBehavior, OrderType, QuantityMode);
+
+impl From<Side> for aaob::Side {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Bid => aaob::Side::Bid,
+            Side::Ask => aaob::Side::Ask,
+        }
+    }
+}
+
+impl From<aaob::Side> for Side {
+    fn from(side: aaob::Side) -> Self {
+        match side {
+            aaob::Side::Bid => Side::Bid,
+            aaob::Side::Ask => Side::Ask,
+        }
+    }
+}
+
+impl From<SelfTradeBehavior> for aaob::SelfTradeBehavior {
+    fn from(behavior: SelfTradeBehavior) -> Self {
+        match behavior {
+            SelfTradeBehavior::DecrementTake => aaob::SelfTradeBehavior::DecrementTake,
+            SelfTradeBehavior::CancelProvide => aaob::SelfTradeBehavior::CancelProvide,
+            SelfTradeBehavior::AbortTransaction => aaob::SelfTradeBehavior::AbortTransaction,
+        }
+    }
+}
+
+impl From<aaob::SelfTradeBehavior> for SelfTradeBehavior {
+    fn from(behavior: aaob::SelfTradeBehavior) -> Self {
+        match behavior {
+            aaob::SelfTradeBehavior::DecrementTake => SelfTradeBehavior::DecrementTake,
+            aaob::SelfTradeBehavior::CancelProvide => SelfTradeBehavior::CancelProvide,
+            aaob::SelfTradeBehavior::AbortTransaction => SelfTradeBehavior::AbortTransaction,
+        }
+    }
+}
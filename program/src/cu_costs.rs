@@ -0,0 +1,24 @@
+//! Compute unit budgets of the instructions, for clients to set the compute budget of their transactions
+//! without simulating them.
+//!
+//! This file is generated by `tests/common/performance_tests_parse/run_cu_bench.sh`, do not edit it.
+
+/// A new_order which posts on the book without matching
+/// (not measured, defaults to the compute budget of an instruction)
+pub const NEW_ORDER_POST: u32 = 200_000;
+
+/// A new_order which fully fills a single resting order
+/// (not measured, defaults to the compute budget of an instruction)
+pub const NEW_ORDER_TAKE: u32 = 200_000;
+
+/// A consume_events which consumes a single fill event
+/// (not measured, defaults to the compute budget of an instruction)
+pub const CONSUME_EVENT: u32 = 200_000;
+
+/// A consume_events which finds the event queue empty
+/// (not measured, defaults to the compute budget of an instruction)
+pub const CONSUME_EVENTS_EMPTY: u32 = 200_000;
+
+/// A cancel_order of a resting order, found from its client order id
+/// (not measured, defaults to the compute budget of an instruction)
+pub const CANCEL_ORDER: u32 = 200_000;
@@ -1,10 +1,14 @@
 use num_derive::FromPrimitive;
+use num_traits::FromPrimitive as _;
 use thiserror::Error;
 
 use solana_program::{decode_error::DecodeError, program_error::ProgramError};
 
 pub type AoResult<T = ()> = Result<T, DexError>;
 
+/// The prefix of the log emitted with the key of an account which fails a check
+pub const ACCOUNT_CHECK_FAILED_LOG: &str = "Account check failed: ";
+
 #[derive(Clone, Debug, Error, FromPrimitive)]
 pub enum DexError {
     #[error("The given order index is invalid.")]
@@ -57,6 +61,99 @@ pub enum DexError {
     EventQueueMustBeEmpty,
     #[error("Event queue mismatch")]
     EventQueueMismatch,
+    #[error("The account has an outdated layout and should be migrated")]
+    AccountNotMigrated,
+    #[error("Invalid maker rewards account provided")]
+    InvalidMakerRewardsAccount,
+    #[error("The maker rewards account has reached its maximum capacity")]
+    MakerRewardsFull,
+    #[error("The order is too close to another order of the user account on the same side")]
+    OrderTooClose,
+    #[error("The market is halted")]
+    MarketHalted,
+    #[error("The market must be halted")]
+    MarketNotHalted,
+    #[error("Invalid escrow account provided")]
+    InvalidEscrowAccount,
+    #[error("The escrow account has reached its maximum capacity")]
+    EscrowFull,
+    #[error("Invalid oracle account provided")]
+    InvalidOracleAccount,
+    #[error("The market matches orders in batch auctions")]
+    BatchAuctionMode,
+    #[error("The market does not match orders in batch auctions")]
+    NotBatchAuctionMode,
+    #[error("Invalid batch auction account provided")]
+    InvalidBatchAuctionAccount,
+    #[error("The batch auction has reached its maximum capacity")]
+    BatchAuctionFull,
+    #[error("The batch auction cannot be matched yet")]
+    BatchAuctionNotReady,
+    #[error("Invalid session account provided")]
+    InvalidSessionAccount,
+    #[error("The session has expired")]
+    SessionExpired,
+    #[error("The session has not expired yet")]
+    SessionNotExpired,
+    #[error("The session has reached its maximum number of orders")]
+    SessionFull,
+    #[error("Invalid designated market maker registry account provided")]
+    InvalidDmmRegistryAccount,
+    #[error("The designated market maker registry has reached its maximum capacity")]
+    DmmRegistryFull,
+    #[error("A runtime invariant has been violated")]
+    InvariantViolation,
+    #[error("The user does not hold enough tokens to fund the order")]
+    InsufficientFunds,
+    #[error("Invalid cranker identity account provided")]
+    InvalidCrankerIdentityAccount,
+    #[error("The market is being migrated")]
+    MarketMigrating,
+    #[error("The market is not being migrated")]
+    MarketNotMigrating,
+    #[error("The migration timelock has not elapsed yet")]
+    MigrationTimelockActive,
+    #[error("Invalid migration account provided")]
+    InvalidMigrationAccount,
+    #[error("The order has not rested on the book long enough to be cancelled")]
+    OrderTooRecent,
+    #[error("An account is missing from the instruction")]
+    MissingAccount,
+    #[error("Too many accounts were provided to the instruction")]
+    TooManyAccounts,
+    #[error("Invalid settle allowlist account provided")]
+    InvalidSettleAllowlistAccount,
+    #[error("The settle destination is not in the user account's allowlist")]
+    SettleDestinationNotAllowed,
+    #[error("The order preview succeeded and was reverted")]
+    PreviewOnly,
+    #[error("The order size rounds down to zero lots")]
+    OrderSizeRoundsToZero,
+    #[error("Invalid create market config account provided")]
+    InvalidCreateMarketConfigAccount,
+    #[error("The listing fee accounts are missing or invalid")]
+    InvalidListingFeeAccount,
+    #[error("Invalid emergency authority account provided")]
+    InvalidEmergencyAuthority,
+    #[error("The market cannot be resumed before the end of the emergency halt timelock")]
+    EmergencyHaltTimelock,
+    #[error("Invalid pending market admin account provided")]
+    InvalidPendingAdminAccount,
+    #[error("Invalid user account archive provided")]
+    InvalidUserAccountArchive,
+    #[error("New orders on this side of the market are halted")]
+    SideHalted,
+    #[error("The user account already has an open order with this client order id")]
+    DuplicateClientOrderId,
+    #[error("Invalid associated token account program provided")]
+    InvalidAssociatedTokenProgram,
+}
+
+impl DexError {
+    /// Returns the DEX error matching a custom program error code
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::from_u32(code)
+    }
 }
 
 impl From<DexError> for ProgramError {
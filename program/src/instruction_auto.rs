@@ -1,8 +1,18 @@
 #![allow(clippy::too_many_arguments)]
 use crate::processor::close_account;
 pub use crate::processor::{
-    cancel_order, close_market, consume_events, create_market, initialize_account, new_order,
-    settle, swap, sweep_fees, update_royalties,
+    accept_admin, archive_user_account, cancel_all_orders, cancel_order, cancel_session_orders,
+    claim_escrow, close_market, consume_events, create_cranker_identity, create_maker_rewards,
+    create_market, create_session, create_settle_allowlist, deposit, emergency_halt,
+    force_consume_events, freeze_and_export, get_market_info, hash_orderbook, heartbeat,
+    import_market, initialize_account, match_batch, migrate_account, new_batch_order, new_order,
+    nominate_admin, preview_new_order, prune_expired_orders, reclaim_orderbook, relink_orderbook,
+    replace_order, restore_user_account, set_account_metadata, set_batch_auction, set_cancel_fee,
+    set_crank_fee_share, set_crank_reward_scaling, set_create_market_config,
+    set_designated_market_maker, set_emergency_authority, set_fill_privacy, set_halt_flags,
+    set_maker_rebate, set_market_status, set_min_resting_slots, set_oracle, set_referral_share,
+    set_volatility_fee, settle, settle_to_associated_accounts, swap, sweep_fees,
+    update_market_admin, update_market_metadata, update_market_params, update_royalties, withdraw,
 };
 use bonfida_utils::InstructionsAccount;
 use num_derive::{FromPrimitive, ToPrimitive};
@@ -12,61 +22,70 @@ use solana_program::{instruction::Instruction, pubkey::Pubkey};
 pub enum DexInstruction {
     /// Creates a new DEX market
     ///
-    /// | Index | Writable | Signer | Description                 |
-    /// | ------------------------------------------------------- |
-    /// | 0     | ✅        | ❌      | The market account          |
-    /// | 1     | ✅        | ❌      | The orderbook account       |
-    /// | 2     | ❌        | ❌      | The base vault account      |
-    /// | 3     | ❌        | ❌      | The quote vault account     |
-    /// | 4     | ❌        | ❌      | The market admin account    |
-    /// | 5     | ✅        | ❌      | The AOB event queue account |
-    /// | 6     | ✅        | ❌      | The AOB asks account        |
-    /// | 7     | ✅        | ❌      | The AOB bids account        |
-    /// | 8     | ❌        | ❌      | The metaplex token metadata |
+    /// | Index | Writable | Signer | Description                                                                                           |
+    /// | --------------------------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The market account                                                                                    |
+    /// | 1     | ✅        | ❌      | The orderbook account                                                                                 |
+    /// | 2     | ❌        | ❌      | The base vault account                                                                                |
+    /// | 3     | ❌        | ❌      | The quote vault account                                                                               |
+    /// | 4     | ❌        | ❌      | The market admin account                                                                              |
+    /// | 5     | ✅        | ❌      | The AOB event queue account                                                                           |
+    /// | 6     | ✅        | ❌      | The AOB asks account                                                                                  |
+    /// | 7     | ✅        | ❌      | The AOB bids account                                                                                  |
+    /// | 8     | ❌        | ❌      | The metaplex token metadata                                                                           |
+    /// | 9     | ❌        | ❌      | The create market config account of the deployment, which may not exist                               |
+    /// | 10    | ✅        | ✅      | The optional listing fee payer, required when the deployment charges a listing fee                    |
+    /// | 11    | ✅        | ❌      | The optional token account paying a listing fee in tokens, the fee payer itself for a fee in lamports |
+    /// | 12    | ✅        | ❌      | The optional treasury of the create market config                                                     |
+    /// | 13    | ❌        | ❌      | The optional program transferring the listing fee, the system program or the SPL token program        |
     CreateMarket,
     /// Execute a new order instruction. Supported types include Limit, IOC, FOK, or Post only.
     ///
-    /// | Index | Writable | Signer | Description                                                                        |
-    /// | -------------------------------------------------------------------------------------------------------------- |
-    /// | 0     | ❌        | ❌      | The SPL token program                                                              |
-    /// | 1     | ❌        | ❌      | The system program                                                                 |
-    /// | 2     | ✅        | ❌      | The DEX market                                                                     |
-    /// | 3     | ✅        | ❌      | The orderbook                                                                      |
-    /// | 4     | ✅        | ❌      | The AOB event queue                                                                |
-    /// | 5     | ✅        | ❌      | The AOB bids shared memory                                                         |
-    /// | 6     | ✅        | ❌      | The AOB asks shared memory                                                         |
-    /// | 7     | ✅        | ❌      | The base token vault                                                               |
-    /// | 8     | ✅        | ❌      | The quote token vault                                                              |
-    /// | 9     | ✅        | ❌      | The DEX user account                                                               |
-    /// | 10    | ✅        | ❌      | The user source token account                                                      |
-    /// | 11    | ✅        | ✅      | The user wallet                                                                    |
-    /// | 12    | ❌        | ❌      | The optional SRM or MSRM discount token account (must be owned by the user wallet) |
-    /// | 13    | ✅        | ❌      | The optional referrer's token account which will receive a 20% cut of the fees     |
+    /// | Index | Writable | Signer | Description                                                                                                  |
+    /// | ---------------------------------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program                                                                                        |
+    /// | 1     | ❌        | ❌      | The system program                                                                                           |
+    /// | 2     | ✅        | ❌      | The DEX market                                                                                               |
+    /// | 3     | ✅        | ❌      | The orderbook                                                                                                |
+    /// | 4     | ✅        | ❌      | The AOB event queue                                                                                          |
+    /// | 5     | ✅        | ❌      | The AOB bids shared memory                                                                                   |
+    /// | 6     | ✅        | ❌      | The AOB asks shared memory                                                                                   |
+    /// | 7     | ✅        | ❌      | The base token vault                                                                                         |
+    /// | 8     | ✅        | ❌      | The quote token vault                                                                                        |
+    /// | 9     | ✅        | ❌      | The DEX user account                                                                                         |
+    /// | 10    | ✅        | ❌      | The user source token account                                                                                |
+    /// | 11    | ✅        | ✅      | The user wallet, or the session key for session orders                                                       |
+    /// | 12    | ❌        | ❌      | The optional SRM or MSRM discount token account (must be owned by the user wallet)                           |
+    /// | 13    | ❌        | ❌      | The optional oracle price account, required if the market has an oracle                                      |
+    /// | 14    | ✅        | ❌      | The optional session account, required for session orders                                                    |
+    /// | 15    | ❌        | ❌      | The optional designated market maker registry, which waives the taker fees of registered user accounts       |
+    /// | 16    | ✅        | ❌      | The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees |
     NewOrder,
     ///
-    /// | Index | Writable | Signer | Description                                                                        |
-    /// | -------------------------------------------------------------------------------------------------------------- |
-    /// | 0     | ❌        | ❌      | The SPL token program                                                              |
-    /// | 1     | ❌        | ❌      | The system program                                                                 |
-    /// | 2     | ✅        | ❌      | The DEX market                                                                     |
-    /// | 3     | ✅        | ❌      | The orderbook                                                                      |
-    /// | 4     | ✅        | ❌      | The AOB event queue                                                                |
-    /// | 5     | ✅        | ❌      | The AOB bids shared memory                                                         |
-    /// | 6     | ✅        | ❌      | The AOB asks shared memory                                                         |
-    /// | 7     | ✅        | ❌      | The base token vault                                                               |
-    /// | 8     | ✅        | ❌      | The quote token vault                                                              |
-    /// | 9     | ❌        | ❌      | The DEX market signer                                                              |
-    /// | 10    | ✅        | ❌      | The user base token account                                                        |
-    /// | 11    | ✅        | ❌      | The user quote token account                                                       |
-    /// | 12    | ✅        | ✅      | The user wallet                                                                    |
-    /// | 13    | ❌        | ❌      | The optional SRM or MSRM discount token account (must be owned by the user wallet) |
-    /// | 14    | ✅        | ❌      | The optional referrer's token account which will receive a 20% cut of the fees     |
+    /// | Index | Writable | Signer | Description                                                                                                  |
+    /// | ---------------------------------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program                                                                                        |
+    /// | 1     | ❌        | ❌      | The system program                                                                                           |
+    /// | 2     | ✅        | ❌      | The DEX market                                                                                               |
+    /// | 3     | ✅        | ❌      | The orderbook                                                                                                |
+    /// | 4     | ✅        | ❌      | The AOB event queue                                                                                          |
+    /// | 5     | ✅        | ❌      | The AOB bids shared memory                                                                                   |
+    /// | 6     | ✅        | ❌      | The AOB asks shared memory                                                                                   |
+    /// | 7     | ✅        | ❌      | The base token vault                                                                                         |
+    /// | 8     | ✅        | ❌      | The quote token vault                                                                                        |
+    /// | 9     | ❌        | ❌      | The DEX market signer                                                                                        |
+    /// | 10    | ✅        | ❌      | The user base token account                                                                                  |
+    /// | 11    | ✅        | ❌      | The user quote token account                                                                                 |
+    /// | 12    | ✅        | ✅      | The user wallet                                                                                              |
+    /// | 13    | ❌        | ❌      | The optional SRM or MSRM discount token account (must be owned by the user wallet)                           |
+    /// | 14    | ❌        | ❌      | The optional oracle price account, required if the market has an oracle                                      |
+    /// | 15    | ✅        | ❌      | The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees |
     Swap,
     /// Cancel an existing order and remove it from the orderbook.
     ///
     /// | Index | Writable | Signer | Description                |
     /// | ------------------------------------------------------ |
-    /// | 0     | ❌        | ❌      | The DEX market             |
+    /// | 0     | ✅        | ❌      | The DEX market             |
     /// | 1     | ✅        | ❌      | The orderbook              |
     /// | 2     | ✅        | ❌      | The AOB event queue        |
     /// | 3     | ✅        | ❌      | The AOB bids shared memory |
@@ -76,27 +95,33 @@ pub enum DexInstruction {
     CancelOrder,
     /// Crank the processing of DEX events.
     ///
-    /// | Index    | Writable | Signer | Description                |
-    /// | --------------------------------------------------------- |
-    /// | 0        | ✅        | ❌      | The DEX market             |
-    /// | 1        | ✅        | ❌      | The orderbook              |
-    /// | 2        | ✅        | ❌      | The AOB event queue        |
-    /// | 3        | ✅        | ❌      | The reward target          |
-    /// | 4..4 + N | ✅        | ❌      | The relevant user accounts |
+    /// | Index    | Writable | Signer | Description                                                                                              |
+    /// | --------------------------------------------------------------------------------------------------------------------------------------- |
+    /// | 0        | ✅        | ❌      | The DEX market                                                                                           |
+    /// | 1        | ✅        | ❌      | The orderbook                                                                                            |
+    /// | 2        | ✅        | ❌      | The AOB event queue                                                                                      |
+    /// | 3        | ✅        | ❌      | The reward target, credited with the cranker's share of the fees when it is a user account of the market |
+    /// | 4..4 + N | ✅        | ❌      | The relevant user accounts, along with the session accounts to prune when pruning expired sessions       |
+    /// | 4 + N    | ✅        | ❌      | The optional AOB bids shared memory, required when sampling maker rewards or pruning expired sessions    |
+    /// | 5 + N    | ✅        | ❌      | The optional AOB asks shared memory, required when sampling maker rewards or pruning expired sessions    |
+    /// | 6 + N    | ✅        | ❌      | The optional maker rewards account, required when sampling maker rewards                                 |
+    /// | 7 + N    | ✅        | ❌      | The optional cranker identity account, required when recording the crank                                 |
+    /// | 8 + N    | ❌        | ✅      | The optional owner of the cranker identity account, required when recording the crank                    |
     ConsumeEvents,
     /// Extract available base and quote token assets from a user account
     ///
-    /// | Index | Writable | Signer | Description                         |
-    /// | --------------------------------------------------------------- |
-    /// | 0     | ❌        | ❌      | The spl token program               |
-    /// | 1     | ❌        | ❌      | The DEX market                      |
-    /// | 2     | ✅        | ❌      | The base token vault                |
-    /// | 3     | ✅        | ❌      | The quote token vault               |
-    /// | 4     | ❌        | ❌      | The DEX market signer account       |
-    /// | 5     | ✅        | ❌      | The DEX user account                |
-    /// | 6     | ❌        | ✅      | The DEX user account owner wallet   |
-    /// | 7     | ✅        | ❌      | The destination base token account  |
-    /// | 8     | ✅        | ❌      | The destination quote token account |
+    /// | Index | Writable | Signer | Description                                                                     |
+    /// | ----------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The spl token program                                                           |
+    /// | 1     | ❌        | ❌      | The DEX market                                                                  |
+    /// | 2     | ✅        | ❌      | The base token vault                                                            |
+    /// | 3     | ✅        | ❌      | The quote token vault                                                           |
+    /// | 4     | ❌        | ❌      | The DEX market signer account                                                   |
+    /// | 5     | ✅        | ❌      | The DEX user account                                                            |
+    /// | 6     | ✅        | ✅      | The DEX user account owner wallet, which receives the unwrapped SOL             |
+    /// | 7     | ✅        | ❌      | The destination base token account                                              |
+    /// | 8     | ✅        | ❌      | The destination quote token account                                             |
+    /// | 9     | ❌        | ❌      | The settle allowlist of the user account, required when one has been registered |
     Settle,
     /// Initialize a new user account
     ///
@@ -152,6 +177,482 @@ pub enum DexInstruction {
     /// | 2     | ❌        | ❌      | The AOB market account  |
     /// | 3     | ❌        | ❌      | The token metadata      |
     UpdateRoyalties,
+    /// Migrate a market or user account of the first release to the current layout
+    ///
+    /// | Index | Writable | Signer | Description                                        |
+    /// | ------------------------------------------------------------------------------ |
+    /// | 0     | ❌        | ❌      | The system program                                 |
+    /// | 1     | ✅        | ❌      | The DEX market or user account to migrate          |
+    /// | 2     | ✅        | ✅      | The account paying for the rent of the added space |
+    MigrateAccount,
+    /// Create the maker rewards sampling account of a market. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description                             |
+    /// | ------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The system program                      |
+    /// | 1     | ❌        | ❌      | The DEX market                          |
+    /// | 2     | ✅        | ❌      | The maker rewards account to initialize |
+    /// | 3     | ❌        | ✅      | The market admin account                |
+    /// | 4     | ✅        | ✅      | The fee payer                           |
+    CreateMakerRewards,
+    /// Set the opaque metadata of a user account
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX user account          |
+    /// | 1     | ❌        | ✅      | The owner of the user account |
+    SetAccountMetadata,
+    /// Compute and log a hash of the orderbook's bids and asks at the current slot. This instruction is permissionless.
+    ///
+    /// | Index | Writable | Signer | Description                |
+    /// | ------------------------------------------------------ |
+    /// | 0     | ❌        | ❌      | The DEX market             |
+    /// | 1     | ❌        | ❌      | The orderbook              |
+    /// | 2     | ❌        | ❌      | The AOB bids shared memory |
+    /// | 3     | ❌        | ❌      | The AOB asks shared memory |
+    HashOrderbook,
+    /// Update the trading status of a market. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetMarketStatus,
+    /// Consume the events of a halted market even when user accounts are missing. This is an admin instruction
+    ///
+    /// | Index    | Writable | Signer | Description                                                          |
+    /// | --------------------------------------------------------------------------------------------------- |
+    /// | 0        | ❌        | ❌      | The system program                                                   |
+    /// | 1        | ✅        | ❌      | The DEX market                                                       |
+    /// | 2        | ✅        | ❌      | The orderbook                                                        |
+    /// | 3        | ✅        | ❌      | The AOB event queue                                                  |
+    /// | 4        | ✅        | ✅      | The market admin account, which pays for the escrow account creation |
+    /// | 5        | ✅        | ❌      | The market escrow account                                            |
+    /// | 6..6 + N | ✅        | ❌      | The available user accounts                                          |
+    ForceConsumeEvents,
+    /// Transfer the balances held in a market's escrow back to their user account
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The DEX market                |
+    /// | 1     | ❌        | ❌      | The orderbook                 |
+    /// | 2     | ❌        | ❌      | The AOB bids shared memory    |
+    /// | 3     | ❌        | ❌      | The AOB asks shared memory    |
+    /// | 4     | ✅        | ❌      | The market escrow account     |
+    /// | 5     | ✅        | ❌      | The DEX user account          |
+    /// | 6     | ❌        | ✅      | The user account owner wallet |
+    ClaimEscrow,
+    /// Update the symbols and display name of a market. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    UpdateMarketMetadata,
+    /// Configure the oracle price account of a market, or remove it when no oracle account is given. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description                     |
+    /// | ----------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market                  |
+    /// | 1     | ❌        | ✅      | The market admin account        |
+    /// | 2     | ❌        | ❌      | The optional Pyth price account |
+    SetOracle,
+    /// Choose whether the fill logs of a market disclose the taker's account. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetFillPrivacy,
+    /// Switch a market between continuous matching and frequent batch auctions
+    ///
+    /// | Index | Writable | Signer | Description                                                                 |
+    /// | ------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The system program                                                          |
+    /// | 1     | ✅        | ❌      | The DEX market                                                              |
+    /// | 2     | ✅        | ❌      | The market batch auction account                                            |
+    /// | 3     | ✅        | ✅      | The market admin account, which pays for the batch auction account creation |
+    SetBatchAuction,
+    /// Add an order to the pending batch of a market in batch auction mode
+    ///
+    /// | Index | Writable | Signer | Description                                                                        |
+    /// | -------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program                                                              |
+    /// | 1     | ❌        | ❌      | The DEX market                                                                     |
+    /// | 2     | ✅        | ❌      | The market batch auction account                                                   |
+    /// | 3     | ✅        | ❌      | The base token vault                                                               |
+    /// | 4     | ✅        | ❌      | The quote token vault                                                              |
+    /// | 5     | ✅        | ❌      | The DEX user account                                                               |
+    /// | 6     | ✅        | ❌      | The user source token account                                                      |
+    /// | 7     | ❌        | ✅      | The user wallet                                                                    |
+    /// | 8     | ❌        | ❌      | The optional SRM or MSRM discount token account (must be owned by the user wallet) |
+    NewBatchOrder,
+    /// Cross the pending batch of a market at a uniform clearing price
+    ///
+    /// | Index    | Writable | Signer | Description                                            |
+    /// | ------------------------------------------------------------------------------------- |
+    /// | 0        | ✅        | ❌      | The DEX market                                         |
+    /// | 1        | ✅        | ❌      | The market batch auction account                       |
+    /// | 2..2 + N | ✅        | ❌      | The user accounts of the pending orders, sorted by key |
+    MatchBatch,
+    /// Authorize an ephemeral session key to place orders on behalf of a user account
+    ///
+    /// | Index | Writable | Signer | Description                                                                |
+    /// | ------------------------------------------------------------------------------------------------------ |
+    /// | 0     | ❌        | ❌      | The system program                                                         |
+    /// | 1     | ❌        | ❌      | The DEX user account                                                       |
+    /// | 2     | ✅        | ✅      | The user account owner wallet, which pays for the session account creation |
+    /// | 3     | ✅        | ❌      | The session account of the user account                                    |
+    CreateSession,
+    /// Cancel the open orders placed under an expired session
+    ///
+    /// | Index | Writable | Signer | Description                                     |
+    /// | --------------------------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market                                  |
+    /// | 1     | ✅        | ❌      | The orderbook                                   |
+    /// | 2     | ✅        | ❌      | The AOB event queue                             |
+    /// | 3     | ✅        | ❌      | The AOB bids shared memory                      |
+    /// | 4     | ✅        | ❌      | The AOB asks shared memory                      |
+    /// | 5     | ✅        | ❌      | The DEX user account                            |
+    /// | 6     | ✅        | ❌      | The expired session account of the user account |
+    CancelSessionOrders,
+    /// Add or remove a user account from the designated market maker registry of a market
+    ///
+    /// | Index | Writable | Signer | Description                                                            |
+    /// | -------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The system program                                                     |
+    /// | 1     | ❌        | ❌      | The DEX market                                                         |
+    /// | 2     | ✅        | ❌      | The market designated market maker registry                            |
+    /// | 3     | ✅        | ✅      | The market admin account, which pays for the registry account creation |
+    SetDesignatedMarketMaker,
+    /// Write the key parameters of a market into the instruction's return data
+    ///
+    /// | Index | Writable | Signer | Description    |
+    /// | ------------------------------------------ |
+    /// | 0     | ❌        | ❌      | The DEX market |
+    /// | 1     | ❌        | ❌      | The orderbook  |
+    GetMarketInfo,
+    /// Configure the share of the protocol fees paid out to crankers. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetCrankFeeShare,
+    /// Configure the volatility-scaled taker fee of a market. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetVolatilityFee,
+    /// Register a crank operator identity which records the operator's consume_events statistics
+    ///
+    /// | Index | Writable | Signer | Description                                                         |
+    /// | ----------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The system program                                                  |
+    /// | 1     | ❌        | ❌      | The DEX market                                                      |
+    /// | 2     | ✅        | ❌      | The cranker identity account                                        |
+    /// | 3     | ✅        | ✅      | The operator's wallet, which pays for the identity account creation |
+    CreateCrankerIdentity,
+    /// Freeze a market and hand its vaults over to another program once the migration timelock has elapsed
+    ///
+    /// | Index | Writable | Signer | Description                        |
+    /// | -------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program ID           |
+    /// | 1     | ✅        | ❌      | The DEX market                     |
+    /// | 2     | ❌        | ✅      | The market admin account           |
+    /// | 3     | ✅        | ❌      | The market base vault account      |
+    /// | 4     | ✅        | ❌      | The market quote vault account     |
+    /// | 5     | ❌        | ❌      | The market signer                  |
+    /// | 6     | ❌        | ❌      | The signer of the migration market |
+    FreezeAndExport,
+    /// Take over the vaults of a market exported by a previous deployment
+    ///
+    /// | Index | Writable | Signer | Description                                    |
+    /// | -------------------------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market                                 |
+    /// | 1     | ❌        | ✅      | The market admin account                       |
+    /// | 2     | ❌        | ❌      | The exported market of the previous deployment |
+    /// | 3     | ❌        | ❌      | The base vault of the exported market          |
+    /// | 4     | ❌        | ❌      | The quote vault of the exported market         |
+    ImportMarket,
+    /// Configure the minimum number of slots an order must rest on the book before it can be cancelled
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetMinRestingSlots,
+    /// Record that the owner of a user account, or one of its session keys, is still active
+    ///
+    /// | Index | Writable | Signer | Description                                                                       |
+    /// | ------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX user account                                                              |
+    /// | 1     | ❌        | ✅      | The owner of the user account, or the session key when a session account is given |
+    /// | 2     | ❌        | ❌      | The optional session account authorizing the session key                          |
+    Heartbeat,
+    /// Replace the AAOB accounts of a halted market with freshly initialized ones. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description                                                   |
+    /// | ----------------------------------------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market                                                |
+    /// | 1     | ❌        | ✅      | The market admin account                                      |
+    /// | 2     | ✅        | ❌      | The current orderbook                                         |
+    /// | 3     | ✅        | ❌      | The current AOB event queue                                   |
+    /// | 4     | ✅        | ❌      | The current AOB bids shared memory                            |
+    /// | 5     | ✅        | ❌      | The current AOB asks shared memory                            |
+    /// | 6     | ✅        | ❌      | The new orderbook account                                     |
+    /// | 7     | ✅        | ❌      | The new AOB event queue account                               |
+    /// | 8     | ✅        | ❌      | The new AOB bids account                                      |
+    /// | 9     | ✅        | ❌      | The new AOB asks account                                      |
+    /// | 10    | ✅        | ❌      | The account receiving the lamports of the closed AOB accounts |
+    RelinkOrderbook,
+    /// Register an immutable allowlist of settle destination token accounts for a user account
+    ///
+    /// | Index    | Writable | Signer | Description                                                                      |
+    /// | --------------------------------------------------------------------------------------------------------------- |
+    /// | 0        | ❌        | ❌      | The system program                                                               |
+    /// | 1        | ❌        | ❌      | The DEX market                                                                   |
+    /// | 2        | ✅        | ❌      | The DEX user account                                                             |
+    /// | 3        | ✅        | ✅      | The DEX user account owner wallet, which pays for the allowlist account creation |
+    /// | 4        | ✅        | ❌      | The settle allowlist account                                                     |
+    /// | 5..5 + N | ❌        | ❌      | The allowed destination token accounts                                           |
+    CreateSettleAllowlist,
+    /// Preview a new order. This instruction always fails and is meant to be simulated
+    ///
+    /// | Index | Writable | Signer | Description                                                                                                  |
+    /// | ---------------------------------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program                                                                                        |
+    /// | 1     | ❌        | ❌      | The system program                                                                                           |
+    /// | 2     | ✅        | ❌      | The DEX market                                                                                               |
+    /// | 3     | ✅        | ❌      | The orderbook                                                                                                |
+    /// | 4     | ✅        | ❌      | The AOB event queue                                                                                          |
+    /// | 5     | ✅        | ❌      | The AOB bids shared memory                                                                                   |
+    /// | 6     | ✅        | ❌      | The AOB asks shared memory                                                                                   |
+    /// | 7     | ✅        | ❌      | The base token vault                                                                                         |
+    /// | 8     | ✅        | ❌      | The quote token vault                                                                                        |
+    /// | 9     | ✅        | ❌      | The DEX user account                                                                                         |
+    /// | 10    | ✅        | ❌      | The user source token account                                                                                |
+    /// | 11    | ✅        | ✅      | The user wallet, or the session key for session orders                                                       |
+    /// | 12    | ❌        | ❌      | The optional SRM or MSRM discount token account (must be owned by the user wallet)                           |
+    /// | 13    | ❌        | ❌      | The optional oracle price account, required if the market has an oracle                                      |
+    /// | 14    | ✅        | ❌      | The optional session account, required for session orders                                                    |
+    /// | 15    | ❌        | ❌      | The optional designated market maker registry, which waives the taker fees of registered user accounts       |
+    /// | 16    | ✅        | ❌      | The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees |
+    PreviewNewOrder,
+    /// Cancel all the open orders of a user account which rest on the book, up to a maximum number of orders.
+    ///
+    /// | Index | Writable | Signer | Description                |
+    /// | ------------------------------------------------------ |
+    /// | 0     | ✅        | ❌      | The DEX market             |
+    /// | 1     | ✅        | ❌      | The orderbook              |
+    /// | 2     | ✅        | ❌      | The AOB event queue        |
+    /// | 3     | ✅        | ❌      | The AOB bids shared memory |
+    /// | 4     | ✅        | ❌      | The AOB asks shared memory |
+    /// | 5     | ✅        | ❌      | The DEX user account       |
+    /// | 6     | ❌        | ✅      | The user wallet            |
+    CancelAllOrders,
+    /// Cancel an existing order and place a new one in a single instruction, so that the order cannot be filled in between.
+    ///
+    /// | Index | Writable | Signer | Description                                                                                                  |
+    /// | ---------------------------------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program                                                                                        |
+    /// | 1     | ❌        | ❌      | The system program                                                                                           |
+    /// | 2     | ✅        | ❌      | The DEX market                                                                                               |
+    /// | 3     | ✅        | ❌      | The orderbook                                                                                                |
+    /// | 4     | ✅        | ❌      | The AOB event queue                                                                                          |
+    /// | 5     | ✅        | ❌      | The AOB bids shared memory                                                                                   |
+    /// | 6     | ✅        | ❌      | The AOB asks shared memory                                                                                   |
+    /// | 7     | ✅        | ❌      | The base token vault                                                                                         |
+    /// | 8     | ✅        | ❌      | The quote token vault                                                                                        |
+    /// | 9     | ✅        | ❌      | The DEX user account                                                                                         |
+    /// | 10    | ✅        | ❌      | The user source token account                                                                                |
+    /// | 11    | ✅        | ✅      | The user wallet, or the session key for session orders                                                       |
+    /// | 12    | ❌        | ❌      | The optional SRM or MSRM discount token account (must be owned by the user wallet)                           |
+    /// | 13    | ❌        | ❌      | The optional oracle price account, required if the market has an oracle                                      |
+    /// | 14    | ✅        | ❌      | The optional session account, required for session orders                                                    |
+    /// | 15    | ❌        | ❌      | The optional designated market maker registry, which waives the taker fees of registered user accounts       |
+    /// | 16    | ✅        | ❌      | The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees |
+    ReplaceOrder,
+    /// Close the AAOB accounts of a migrated market once its orderbook is empty
+    ///
+    /// | Index | Writable | Signer | Description                                                   |
+    /// | ----------------------------------------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market                                                |
+    /// | 1     | ❌        | ✅      | The market admin account                                      |
+    /// | 2     | ✅        | ❌      | The orderbook                                                 |
+    /// | 3     | ✅        | ❌      | The AOB event queue                                           |
+    /// | 4     | ✅        | ❌      | The AOB bids shared memory                                    |
+    /// | 5     | ✅        | ❌      | The AOB asks shared memory                                    |
+    /// | 6     | ✅        | ❌      | The account receiving the lamports of the closed AOB accounts |
+    ReclaimOrderbook,
+    /// Set the listing fee charged by create_market
+    ///
+    /// | Index | Writable | Signer | Description                                                     |
+    /// | ------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The system program                                              |
+    /// | 1     | ✅        | ❌      | The create market config account                                |
+    /// | 2     | ✅        | ✅      | The sweep authority, which pays for the config account creation |
+    SetCreateMarketConfig,
+    /// Configure the share of the taker fees which is paid to referrers
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetReferralShare,
+    /// Configure the rebate paid to makers out of the taker fees
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetMakerRebate,
+    /// Set the emergency authority of a market, which can halt it without delay
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetEmergencyAuthority,
+    /// Halt a market without delay, signed by its emergency authority
+    ///
+    /// | Index | Writable | Signer | Description                           |
+    /// | ----------------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market                        |
+    /// | 1     | ❌        | ✅      | The emergency authority of the market |
+    EmergencyHalt,
+    /// Transfer the admin rights of a market to a new key
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    UpdateMarketAdmin,
+    /// Configure the fee charged on the early cancellation of unfilled orders
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetCancelFee,
+    /// Nominate a new market admin, which takes over once it accepts the nomination
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    NominateAdmin,
+    /// Accept the nomination as market admin
+    ///
+    /// | Index | Writable | Signer | Description                |
+    /// | ------------------------------------------------------ |
+    /// | 0     | ✅        | ❌      | The DEX market             |
+    /// | 1     | ❌        | ✅      | The nominated market admin |
+    AcceptAdmin,
+    /// Archive a dormant user account to reclaim most of its rent
+    ///
+    /// | Index | Writable | Signer | Description                                                                                                |
+    /// | -------------------------------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The system program                                                                                         |
+    /// | 1     | ✅        | ❌      | The DEX user account to archive                                                                            |
+    /// | 2     | ✅        | ✅      | The DEX user account owner wallet, which pays for the archive account and receives the user account's rent |
+    /// | 3     | ✅        | ❌      | The user account archive                                                                                   |
+    ArchiveUserAccount,
+    /// Restore an archived user account at its address
+    ///
+    /// | Index | Writable | Signer | Description                                                                                        |
+    /// | ------------------------------------------------------------------------------------------------------------------------------ |
+    /// | 0     | ❌        | ❌      | The system program                                                                                 |
+    /// | 1     | ✅        | ❌      | The DEX user account to restore                                                                    |
+    /// | 2     | ✅        | ✅      | The DEX user account owner wallet, which pays for the user account and receives the archive's rent |
+    /// | 3     | ✅        | ❌      | The user account archive                                                                           |
+    RestoreUserAccount,
+    /// Halt new orders on one or both sides of the market
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetHaltFlags,
+    /// Update the tick size and the minimum base order size of a market
+    ///
+    /// | Index | Writable | Signer | Description                |
+    /// | ------------------------------------------------------ |
+    /// | 0     | ✅        | ❌      | The DEX market             |
+    /// | 1     | ✅        | ❌      | The orderbook              |
+    /// | 2     | ❌        | ❌      | The AOB bids shared memory |
+    /// | 3     | ❌        | ❌      | The AOB asks shared memory |
+    /// | 4     | ❌        | ✅      | The market admin account   |
+    UpdateMarketParams,
+    /// Remove the expired good-til-date orders of user accounts from the book
+    ///
+    /// | Index    | Writable | Signer | Description                                             |
+    /// | -------------------------------------------------------------------------------------- |
+    /// | 0        | ✅        | ❌      | The DEX market                                          |
+    /// | 1        | ✅        | ❌      | The orderbook                                           |
+    /// | 2        | ✅        | ❌      | The AOB event queue                                     |
+    /// | 3        | ✅        | ❌      | The AOB bids shared memory                              |
+    /// | 4        | ✅        | ❌      | The AOB asks shared memory                              |
+    /// | 5        | ✅        | ❌      | The account receiving the prune rewards                 |
+    /// | 6..6 + N | ✅        | ❌      | The user accounts whose expired orders should be pruned |
+    PruneExpiredOrders,
+    /// Configure the scaling of the crank reward with the age of the pending events. This is an admin instruction
+    ///
+    /// | Index | Writable | Signer | Description              |
+    /// | ---------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The DEX market           |
+    /// | 1     | ❌        | ✅      | The market admin account |
+    SetCrankRewardScaling,
+    /// Deposit tokens into the free balances of a user account
+    ///
+    /// | Index | Writable | Signer | Description                       |
+    /// | ------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The spl token program             |
+    /// | 1     | ❌        | ❌      | The DEX market                    |
+    /// | 2     | ✅        | ❌      | The base token vault              |
+    /// | 3     | ✅        | ❌      | The quote token vault             |
+    /// | 4     | ✅        | ❌      | The DEX user account              |
+    /// | 5     | ❌        | ✅      | The DEX user account owner wallet |
+    /// | 6     | ✅        | ❌      | The source base token account     |
+    /// | 7     | ✅        | ❌      | The source quote token account    |
+    Deposit,
+    /// Withdraw part of the free balances of a user account
+    ///
+    /// | Index | Writable | Signer | Description                                                                     |
+    /// | ----------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The spl token program                                                           |
+    /// | 1     | ❌        | ❌      | The DEX market                                                                  |
+    /// | 2     | ✅        | ❌      | The base token vault                                                            |
+    /// | 3     | ✅        | ❌      | The quote token vault                                                           |
+    /// | 4     | ❌        | ❌      | The DEX market signer account                                                   |
+    /// | 5     | ✅        | ❌      | The DEX user account                                                            |
+    /// | 6     | ❌        | ✅      | The DEX user account owner wallet                                               |
+    /// | 7     | ✅        | ❌      | The destination base token account                                              |
+    /// | 8     | ✅        | ❌      | The destination quote token account                                             |
+    /// | 9     | ❌        | ❌      | The settle allowlist of the user account, required when one has been registered |
+    Withdraw,
+    /// Settle the free balances of a user account into the associated token accounts of its owner, creating them if missing
+    ///
+    /// | Index | Writable | Signer | Description                                                                                |
+    /// | ---------------------------------------------------------------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The spl token program                                                                      |
+    /// | 1     | ❌        | ❌      | The system program                                                                         |
+    /// | 2     | ❌        | ❌      | The spl associated token account program                                                   |
+    /// | 3     | ❌        | ❌      | The rent sysvar, required by the associated token account program                          |
+    /// | 4     | ❌        | ❌      | The DEX market                                                                             |
+    /// | 5     | ❌        | ❌      | The base token mint                                                                        |
+    /// | 6     | ❌        | ❌      | The quote token mint                                                                       |
+    /// | 7     | ✅        | ❌      | The base token vault                                                                       |
+    /// | 8     | ✅        | ❌      | The quote token vault                                                                      |
+    /// | 9     | ❌        | ❌      | The DEX market signer account                                                              |
+    /// | 10    | ✅        | ❌      | The DEX user account                                                                       |
+    /// | 11    | ✅        | ✅      | The DEX user account owner wallet, which pays for the creation of the destination accounts |
+    /// | 12    | ✅        | ❌      | The associated base token account of the owner                                             |
+    /// | 13    | ✅        | ❌      | The associated quote token account of the owner                                            |
+    /// | 14    | ❌        | ❌      | The settle allowlist of the user account, required when one has been registered            |
+    SettleToAssociatedAccounts,
 }
 ///          Create a new DEX market
 ///         
@@ -243,3 +744,423 @@ pub fn update_royalties(
 ) -> Instruction {
     accounts.get_instruction_cast(program_id, DexInstruction::UpdateRoyalties as u8, params)
 }
+///          Migrate a market or user account of the first release to the current layout
+pub fn migrate_account(
+    program_id: Pubkey,
+    accounts: migrate_account::Accounts<Pubkey>,
+    params: migrate_account::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::MigrateAccount as u8, params)
+}
+///          Create the maker rewards sampling account of a market. This is an admin instruction
+pub fn create_maker_rewards(
+    program_id: Pubkey,
+    accounts: create_maker_rewards::Accounts<Pubkey>,
+    params: create_maker_rewards::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::CreateMakerRewards as u8, params)
+}
+///          Set the opaque metadata of a user account
+pub fn set_account_metadata(
+    program_id: Pubkey,
+    accounts: set_account_metadata::Accounts<Pubkey>,
+    params: set_account_metadata::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetAccountMetadata as u8, params)
+}
+///          Compute and log a hash of the orderbook's bids and asks at the current slot. This instruction is permissionless.
+pub fn hash_orderbook(
+    program_id: Pubkey,
+    accounts: hash_orderbook::Accounts<Pubkey>,
+    params: hash_orderbook::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::HashOrderbook as u8, params)
+}
+///          Update the trading status of a market. This is an admin instruction
+pub fn set_market_status(
+    program_id: Pubkey,
+    accounts: set_market_status::Accounts<Pubkey>,
+    params: set_market_status::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetMarketStatus as u8, params)
+}
+///          Consume the events of a halted market even when user accounts are missing. This is an admin instruction
+pub fn force_consume_events(
+    program_id: Pubkey,
+    accounts: force_consume_events::Accounts<Pubkey>,
+    params: force_consume_events::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::ForceConsumeEvents as u8, params)
+}
+///          Transfer the balances held in a market's escrow back to their user account
+pub fn claim_escrow(
+    program_id: Pubkey,
+    accounts: claim_escrow::Accounts<Pubkey>,
+    params: claim_escrow::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::ClaimEscrow as u8, params)
+}
+///          Update the symbols and display name of a market. This is an admin instruction
+pub fn update_market_metadata(
+    program_id: Pubkey,
+    accounts: update_market_metadata::Accounts<Pubkey>,
+    params: update_market_metadata::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::UpdateMarketMetadata as u8,
+        params,
+    )
+}
+///          Configure the oracle price account of a market, or remove it when no oracle account is given. This is an admin instruction
+pub fn set_oracle(
+    program_id: Pubkey,
+    accounts: set_oracle::Accounts<Pubkey>,
+    params: set_oracle::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetOracle as u8, params)
+}
+///          Choose whether the fill logs of a market disclose the taker's account. This is an admin instruction
+pub fn set_fill_privacy(
+    program_id: Pubkey,
+    accounts: set_fill_privacy::Accounts<Pubkey>,
+    params: set_fill_privacy::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetFillPrivacy as u8, params)
+}
+///          Switch a market between continuous matching and frequent batch auctions
+pub fn set_batch_auction(
+    program_id: Pubkey,
+    accounts: set_batch_auction::Accounts<Pubkey>,
+    params: set_batch_auction::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetBatchAuction as u8, params)
+}
+///          Add an order to the pending batch of a market in batch auction mode
+pub fn new_batch_order(
+    program_id: Pubkey,
+    accounts: new_batch_order::Accounts<Pubkey>,
+    params: new_batch_order::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::NewBatchOrder as u8, params)
+}
+///          Cross the pending batch of a market at a uniform clearing price
+pub fn match_batch(
+    program_id: Pubkey,
+    accounts: match_batch::Accounts<Pubkey>,
+    params: match_batch::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::MatchBatch as u8, params)
+}
+///          Authorize an ephemeral session key to place orders on behalf of a user account
+pub fn create_session(
+    program_id: Pubkey,
+    accounts: create_session::Accounts<Pubkey>,
+    params: create_session::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::CreateSession as u8, params)
+}
+///          Cancel the open orders placed under an expired session
+pub fn cancel_session_orders(
+    program_id: Pubkey,
+    accounts: cancel_session_orders::Accounts<Pubkey>,
+    params: cancel_session_orders::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::CancelSessionOrders as u8,
+        params,
+    )
+}
+///          Add or remove a user account from the designated market maker registry of a market
+pub fn set_designated_market_maker(
+    program_id: Pubkey,
+    accounts: set_designated_market_maker::Accounts<Pubkey>,
+    params: set_designated_market_maker::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::SetDesignatedMarketMaker as u8,
+        params,
+    )
+}
+///          Write the key parameters of a market into the instruction's return data
+pub fn get_market_info(
+    program_id: Pubkey,
+    accounts: get_market_info::Accounts<Pubkey>,
+    params: get_market_info::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::GetMarketInfo as u8, params)
+}
+///          Configure the share of the protocol fees paid out to crankers. This is an admin instruction
+pub fn set_crank_fee_share(
+    program_id: Pubkey,
+    accounts: set_crank_fee_share::Accounts<Pubkey>,
+    params: set_crank_fee_share::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetCrankFeeShare as u8, params)
+}
+///          Configure the volatility-scaled taker fee of a market. This is an admin instruction
+pub fn set_volatility_fee(
+    program_id: Pubkey,
+    accounts: set_volatility_fee::Accounts<Pubkey>,
+    params: set_volatility_fee::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetVolatilityFee as u8, params)
+}
+///          Register a crank operator identity which records the operator's consume_events statistics
+pub fn create_cranker_identity(
+    program_id: Pubkey,
+    accounts: create_cranker_identity::Accounts<Pubkey>,
+    params: create_cranker_identity::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::CreateCrankerIdentity as u8,
+        params,
+    )
+}
+///          Freeze a market and hand its vaults over to another program once the migration timelock has elapsed
+pub fn freeze_and_export(
+    program_id: Pubkey,
+    accounts: freeze_and_export::Accounts<Pubkey>,
+    params: freeze_and_export::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::FreezeAndExport as u8, params)
+}
+///          Take over the vaults of a market exported by a previous deployment
+pub fn import_market(
+    program_id: Pubkey,
+    accounts: import_market::Accounts<Pubkey>,
+    params: import_market::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::ImportMarket as u8, params)
+}
+///          Configure the minimum number of slots an order must rest on the book before it can be cancelled
+pub fn set_min_resting_slots(
+    program_id: Pubkey,
+    accounts: set_min_resting_slots::Accounts<Pubkey>,
+    params: set_min_resting_slots::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetMinRestingSlots as u8, params)
+}
+///          Record that the owner of a user account, or one of its session keys, is still active
+pub fn heartbeat(
+    program_id: Pubkey,
+    accounts: heartbeat::Accounts<Pubkey>,
+    params: heartbeat::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::Heartbeat as u8, params)
+}
+///          Replace the AAOB accounts of a halted market with freshly initialized ones. This is an admin instruction
+pub fn relink_orderbook(
+    program_id: Pubkey,
+    accounts: relink_orderbook::Accounts<Pubkey>,
+    params: relink_orderbook::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::RelinkOrderbook as u8, params)
+}
+///          Register an immutable allowlist of settle destination token accounts for a user account
+pub fn create_settle_allowlist(
+    program_id: Pubkey,
+    accounts: create_settle_allowlist::Accounts<Pubkey>,
+    params: create_settle_allowlist::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::CreateSettleAllowlist as u8,
+        params,
+    )
+}
+///          Preview a new order. This instruction always fails and is meant to be simulated
+pub fn preview_new_order(
+    program_id: Pubkey,
+    accounts: preview_new_order::Accounts<Pubkey>,
+    params: preview_new_order::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::PreviewNewOrder as u8, params)
+}
+///          Cancel all the open orders of a user account which rest on the book, up to a maximum number of orders.
+pub fn cancel_all_orders(
+    program_id: Pubkey,
+    accounts: cancel_all_orders::Accounts<Pubkey>,
+    params: cancel_all_orders::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::CancelAllOrders as u8, params)
+}
+///          Cancel an existing order and place a new one in a single instruction, so that the order cannot be filled in between.
+pub fn replace_order(
+    program_id: Pubkey,
+    accounts: replace_order::Accounts<Pubkey>,
+    params: replace_order::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::ReplaceOrder as u8, params)
+}
+///          Close the AAOB accounts of a migrated market once its orderbook is empty
+pub fn reclaim_orderbook(
+    program_id: Pubkey,
+    accounts: reclaim_orderbook::Accounts<Pubkey>,
+    params: reclaim_orderbook::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::ReclaimOrderbook as u8, params)
+}
+///          Set the listing fee charged by create_market
+pub fn set_create_market_config(
+    program_id: Pubkey,
+    accounts: set_create_market_config::Accounts<Pubkey>,
+    params: set_create_market_config::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::SetCreateMarketConfig as u8,
+        params,
+    )
+}
+///          Configure the share of the taker fees which is paid to referrers
+pub fn set_referral_share(
+    program_id: Pubkey,
+    accounts: set_referral_share::Accounts<Pubkey>,
+    params: set_referral_share::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetReferralShare as u8, params)
+}
+///          Configure the rebate paid to makers out of the taker fees
+pub fn set_maker_rebate(
+    program_id: Pubkey,
+    accounts: set_maker_rebate::Accounts<Pubkey>,
+    params: set_maker_rebate::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetMakerRebate as u8, params)
+}
+///          Set the emergency authority of a market, which can halt it without delay
+pub fn set_emergency_authority(
+    program_id: Pubkey,
+    accounts: set_emergency_authority::Accounts<Pubkey>,
+    params: set_emergency_authority::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::SetEmergencyAuthority as u8,
+        params,
+    )
+}
+///          Halt a market without delay, signed by its emergency authority
+pub fn emergency_halt(
+    program_id: Pubkey,
+    accounts: emergency_halt::Accounts<Pubkey>,
+    params: emergency_halt::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::EmergencyHalt as u8, params)
+}
+///          Transfer the admin rights of a market to a new key
+pub fn update_market_admin(
+    program_id: Pubkey,
+    accounts: update_market_admin::Accounts<Pubkey>,
+    params: update_market_admin::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::UpdateMarketAdmin as u8, params)
+}
+///          Configure the fee charged on the early cancellation of unfilled orders
+pub fn set_cancel_fee(
+    program_id: Pubkey,
+    accounts: set_cancel_fee::Accounts<Pubkey>,
+    params: set_cancel_fee::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetCancelFee as u8, params)
+}
+///          Nominate a new market admin, which takes over once it accepts the nomination
+pub fn nominate_admin(
+    program_id: Pubkey,
+    accounts: nominate_admin::Accounts<Pubkey>,
+    params: nominate_admin::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::NominateAdmin as u8, params)
+}
+///          Accept the nomination as market admin
+pub fn accept_admin(
+    program_id: Pubkey,
+    accounts: accept_admin::Accounts<Pubkey>,
+    params: accept_admin::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::AcceptAdmin as u8, params)
+}
+///          Archive a dormant user account to reclaim most of its rent
+pub fn archive_user_account(
+    program_id: Pubkey,
+    accounts: archive_user_account::Accounts<Pubkey>,
+    params: archive_user_account::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::ArchiveUserAccount as u8, params)
+}
+///          Restore an archived user account at its address
+pub fn restore_user_account(
+    program_id: Pubkey,
+    accounts: restore_user_account::Accounts<Pubkey>,
+    params: restore_user_account::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::RestoreUserAccount as u8, params)
+}
+///          Halt new orders on one or both sides of the market
+pub fn set_halt_flags(
+    program_id: Pubkey,
+    accounts: set_halt_flags::Accounts<Pubkey>,
+    params: set_halt_flags::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::SetHaltFlags as u8, params)
+}
+///          Update the tick size and the minimum base order size of a market
+pub fn update_market_params(
+    program_id: Pubkey,
+    accounts: update_market_params::Accounts<Pubkey>,
+    params: update_market_params::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::UpdateMarketParams as u8, params)
+}
+///          Remove the expired good-til-date orders of user accounts from the book
+pub fn prune_expired_orders(
+    program_id: Pubkey,
+    accounts: prune_expired_orders::Accounts<Pubkey>,
+    params: prune_expired_orders::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::PruneExpiredOrders as u8, params)
+}
+///          Configure the scaling of the crank reward with the age of the pending events. This is an admin instruction
+pub fn set_crank_reward_scaling(
+    program_id: Pubkey,
+    accounts: set_crank_reward_scaling::Accounts<Pubkey>,
+    params: set_crank_reward_scaling::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::SetCrankRewardScaling as u8,
+        params,
+    )
+}
+///          Deposit tokens into the free balances of a user account
+pub fn deposit(
+    program_id: Pubkey,
+    accounts: deposit::Accounts<Pubkey>,
+    params: deposit::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::Deposit as u8, params)
+}
+///          Withdraw part of the free balances of a user account
+pub fn withdraw(
+    program_id: Pubkey,
+    accounts: withdraw::Accounts<Pubkey>,
+    params: withdraw::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(program_id, DexInstruction::Withdraw as u8, params)
+}
+///          Settle the free balances of a user account into the associated token accounts of its owner, creating them if missing
+pub fn settle_to_associated_accounts(
+    program_id: Pubkey,
+    accounts: settle_to_associated_accounts::Accounts<Pubkey>,
+    params: settle_to_associated_accounts::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        program_id,
+        DexInstruction::SettleToAssociatedAccounts as u8,
+        params,
+    )
+}
@@ -0,0 +1,58 @@
+//! Runtime invariant assertions, compiled in with the `strict-checks` feature.
+//!
+//! These checks are too expensive for production builds but catch accounting and orderbook bugs early on devnet.
+use asset_agnostic_orderbook::state::{critbit::Slab, AccountTag};
+use solana_program::{
+    account_info::AccountInfo, msg, program_error::ProgramError, program_pack::Pack,
+};
+
+use crate::{
+    error::DexError,
+    state::{CallBackInfo, DexState},
+};
+
+/// Checks that the quote vault holds at least the fees and royalties which are owed to the market
+pub(crate) fn check_vault_covers_fees(
+    market_state: &DexState,
+    quote_vault: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let vault = spl_token::state::Account::unpack(&quote_vault.data.borrow())?;
+    let owed = market_state
+        .accumulated_fees
+        .checked_add(market_state.accumulated_royalties)
+        .ok_or(DexError::NumericalOverflow)?;
+    if vault.amount < owed {
+        msg!(
+            "Invariant violation: the quote vault holds {} but {} is owed in fees and royalties",
+            vault.amount,
+            owed
+        );
+        return Err(DexError::InvariantViolation.into());
+    }
+    Ok(())
+}
+
+/// Checks that the best bid is strictly below the best ask once matching is done
+pub(crate) fn check_book_not_crossed(
+    bids: &AccountInfo,
+    asks: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let mut bids_guard = bids.data.borrow_mut();
+    let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+    let mut asks_guard = asks.data.borrow_mut();
+    let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+
+    if let (Some(best_bid), Some(best_ask)) = (bids.find_max(), asks.find_min()) {
+        let bid_price = (bids.leaf_nodes[best_bid as usize].key >> 64) as u64;
+        let ask_price = (asks.leaf_nodes[best_ask as usize].key >> 64) as u64;
+        if bid_price >= ask_price {
+            msg!(
+                "Invariant violation: the best bid {} crosses the best ask {}",
+                bid_price,
+                ask_price
+            );
+            return Err(DexError::InvariantViolation.into());
+        }
+    }
+    Ok(())
+}
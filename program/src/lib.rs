@@ -6,14 +6,30 @@ This program is intended for use to build a decentralized exchange (DEX) special
 */
 
 #[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod entrypoint;
+/// Read-only views of the orderbook for other on-chain programs
+pub mod book;
+/// Names and roles of the accounts of the instructions, for transaction previews
+#[cfg(not(target_arch = "wasm32"))]
+pub mod account_metas;
+/// Order enums with stable numeric encodings, shared by the program and its clients
+pub mod common;
+/// Measured compute unit budgets of the instructions
+pub mod cu_costs;
 #[doc(hidden)]
 pub mod error;
 /// Program instructions and their CPI-compatible bindings
+#[cfg(not(target_arch = "wasm32"))]
 pub mod instruction_auto;
 /// Describes the different data structres that the program uses to encode state
 pub mod state;
+/// Account decoders and market math exported to javascript
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
 
+#[cfg(feature = "strict-checks")]
+pub(crate) mod invariants;
 pub(crate) mod processor;
 pub(crate) mod utils;
 
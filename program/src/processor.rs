@@ -1,11 +1,13 @@
-use num_traits::FromPrimitive;
-use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
-    pubkey::Pubkey,
+use solana_program::pubkey::Pubkey;
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    crate::{error::DexError, instruction_auto::DexInstruction},
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    },
 };
 
-use crate::instruction_auto::DexInstruction;
-
 ////////////////////////////////////////////////////////////
 // Constants
 pub static SRM_MINT: Pubkey =
@@ -23,39 +25,247 @@ pub static CALLBACK_INFO_LEN: u64 = 33;
 pub static CALLBACK_ID_LEN: u64 = 32;
 /// The most significant bit of the fee tier field in CallBack Info indicates if the transaction is referred
 pub static REFERRAL_MASK: u8 = 1 << 7;
+/// This bit of the fee tier field in CallBack Info indicates that a resting order should be notified when it is
+/// traded through without being filled
+pub static NOTIFY_ON_CROSS_MASK: u8 = 1 << 6;
 
 ////////////////////////////////////////////////////////////
 
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod cancel_order;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod consume_events;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod create_market;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod initialize_account;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod new_order;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod settle;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod swap;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod sweep_fees;
 
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod close_account;
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod close_market;
 
 #[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod update_royalties;
 
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod migrate_account;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod create_maker_rewards;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_account_metadata;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod hash_orderbook;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_market_status;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod force_consume_events;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod claim_escrow;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod update_market_metadata;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_oracle;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_fill_privacy;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_batch_auction;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod new_batch_order;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod match_batch;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod create_session;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cancel_session_orders;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_designated_market_maker;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod get_market_info;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_crank_fee_share;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_volatility_fee;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod create_cranker_identity;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod freeze_and_export;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod import_market;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_min_resting_slots;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod heartbeat;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod relink_orderbook;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod create_settle_allowlist;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod preview_new_order;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cancel_all_orders;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod replace_order;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod reclaim_orderbook;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_create_market_config;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_referral_share;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_maker_rebate;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_emergency_authority;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod emergency_halt;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod update_market_admin;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_cancel_fee;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod nominate_admin;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod accept_admin;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod archive_user_account;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod restore_user_account;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_halt_flags;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod update_market_params;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod prune_expired_orders;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod set_crank_reward_scaling;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod deposit;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod withdraw;
+
+#[allow(missing_docs)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod settle_to_associated_accounts;
+
 pub struct Processor {}
 
 // We add an offset larger than 1 to keep the instruction's internal arguments aligned
+#[cfg(not(target_arch = "wasm32"))]
 pub(crate) const INSTRUCTION_TAG_OFFSET: usize = 8;
 
+#[cfg(not(target_arch = "wasm32"))]
 impl Processor {
     pub fn process_instruction(
         program_id: &Pubkey,
@@ -67,6 +277,8 @@ impl Processor {
             .ok_or(ProgramError::InvalidInstructionData)?;
         let instruction_data = &instruction_data[INSTRUCTION_TAG_OFFSET..];
 
+        Self::check_account_count(instruction_tag, accounts)?;
+
         match instruction_tag {
             DexInstruction::CreateMarket => {
                 msg!("Instruction: Create Market");
@@ -90,7 +302,7 @@ impl Processor {
             }
             DexInstruction::Settle => {
                 msg!("Instruction: Settle");
-                settle::process(program_id, accounts)?;
+                settle::process(program_id, accounts, instruction_data)?;
             }
             DexInstruction::InitializeAccount => {
                 msg!("Instruction: Initialize account");
@@ -112,6 +324,285 @@ impl Processor {
                 msg!("Instruction: Update royalties");
                 update_royalties::process(program_id, accounts)?
             }
+            DexInstruction::MigrateAccount => {
+                msg!("Instruction: Migrate account");
+                migrate_account::process(program_id, accounts)?
+            }
+            DexInstruction::CreateMakerRewards => {
+                msg!("Instruction: Create maker rewards");
+                create_maker_rewards::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetAccountMetadata => {
+                msg!("Instruction: Set account metadata");
+                set_account_metadata::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::HashOrderbook => {
+                msg!("Instruction: Hash orderbook");
+                hash_orderbook::process(program_id, accounts)?
+            }
+            DexInstruction::SetMarketStatus => {
+                msg!("Instruction: Set market status");
+                set_market_status::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::ForceConsumeEvents => {
+                msg!("Instruction: Force consume events");
+                force_consume_events::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::ClaimEscrow => {
+                msg!("Instruction: Claim escrow");
+                claim_escrow::process(program_id, accounts)?
+            }
+            DexInstruction::UpdateMarketMetadata => {
+                msg!("Instruction: Update market metadata");
+                update_market_metadata::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetOracle => {
+                msg!("Instruction: Set oracle");
+                set_oracle::process(program_id, accounts)?
+            }
+            DexInstruction::SetFillPrivacy => {
+                msg!("Instruction: Set fill privacy");
+                set_fill_privacy::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetBatchAuction => {
+                msg!("Instruction: Set batch auction");
+                set_batch_auction::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::NewBatchOrder => {
+                msg!("Instruction: New batch order");
+                new_batch_order::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::MatchBatch => {
+                msg!("Instruction: Match batch");
+                match_batch::process(program_id, accounts)?
+            }
+            DexInstruction::CreateSession => {
+                msg!("Instruction: Create session");
+                create_session::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::CancelSessionOrders => {
+                msg!("Instruction: Cancel session orders");
+                cancel_session_orders::process(program_id, accounts)?
+            }
+            DexInstruction::SetDesignatedMarketMaker => {
+                msg!("Instruction: Set designated market maker");
+                set_designated_market_maker::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::GetMarketInfo => {
+                msg!("Instruction: Get market info");
+                get_market_info::process(program_id, accounts)?
+            }
+            DexInstruction::SetCrankFeeShare => {
+                msg!("Instruction: Set crank fee share");
+                set_crank_fee_share::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetVolatilityFee => {
+                msg!("Instruction: Set volatility fee");
+                set_volatility_fee::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::CreateCrankerIdentity => {
+                msg!("Instruction: Create cranker identity");
+                create_cranker_identity::process(program_id, accounts)?
+            }
+            DexInstruction::FreezeAndExport => {
+                msg!("Instruction: Freeze and export");
+                freeze_and_export::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::ImportMarket => {
+                msg!("Instruction: Import market");
+                import_market::process(program_id, accounts)?
+            }
+            DexInstruction::SetMinRestingSlots => {
+                msg!("Instruction: Set min resting slots");
+                set_min_resting_slots::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::Heartbeat => {
+                msg!("Instruction: Heartbeat");
+                heartbeat::process(program_id, accounts)?
+            }
+            DexInstruction::RelinkOrderbook => {
+                msg!("Instruction: Relink orderbook");
+                relink_orderbook::process(program_id, accounts)?
+            }
+            DexInstruction::CreateSettleAllowlist => {
+                msg!("Instruction: Create settle allowlist");
+                create_settle_allowlist::process(program_id, accounts)?
+            }
+            DexInstruction::PreviewNewOrder => {
+                msg!("Instruction: Preview new order");
+                preview_new_order::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::CancelAllOrders => {
+                msg!("Instruction: Cancel all orders");
+                cancel_all_orders::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::ReplaceOrder => {
+                msg!("Instruction: Replace order");
+                replace_order::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::ReclaimOrderbook => {
+                msg!("Instruction: Reclaim orderbook");
+                reclaim_orderbook::process(program_id, accounts)?
+            }
+            DexInstruction::SetCreateMarketConfig => {
+                msg!("Instruction: Set create market config");
+                set_create_market_config::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetReferralShare => {
+                msg!("Instruction: Set referral share");
+                set_referral_share::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetMakerRebate => {
+                msg!("Instruction: Set maker rebate");
+                set_maker_rebate::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetEmergencyAuthority => {
+                msg!("Instruction: Set emergency authority");
+                set_emergency_authority::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::EmergencyHalt => {
+                msg!("Instruction: Emergency halt");
+                emergency_halt::process(program_id, accounts)?
+            }
+            DexInstruction::UpdateMarketAdmin => {
+                msg!("Instruction: Update market admin");
+                update_market_admin::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetCancelFee => {
+                msg!("Instruction: Set cancel fee");
+                set_cancel_fee::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::NominateAdmin => {
+                msg!("Instruction: Nominate admin");
+                nominate_admin::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::AcceptAdmin => {
+                msg!("Instruction: Accept admin");
+                accept_admin::process(program_id, accounts)?
+            }
+            DexInstruction::ArchiveUserAccount => {
+                msg!("Instruction: Archive user account");
+                archive_user_account::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::RestoreUserAccount => {
+                msg!("Instruction: Restore user account");
+                restore_user_account::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetHaltFlags => {
+                msg!("Instruction: Set halt flags");
+                set_halt_flags::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::UpdateMarketParams => {
+                msg!("Instruction: Update market params");
+                update_market_params::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::PruneExpiredOrders => {
+                msg!("Instruction: Prune expired orders");
+                prune_expired_orders::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SetCrankRewardScaling => {
+                msg!("Instruction: Set crank reward scaling");
+                set_crank_reward_scaling::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::Deposit => {
+                msg!("Instruction: Deposit");
+                deposit::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::Withdraw => {
+                msg!("Instruction: Withdraw");
+                withdraw::process(program_id, accounts, instruction_data)?
+            }
+            DexInstruction::SettleToAssociatedAccounts => {
+                msg!("Instruction: Settle to associated accounts");
+                settle_to_associated_accounts::process(program_id, accounts, instruction_data)?
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the minimum number of accounts of an instruction, along with its maximum number of accounts when it
+    /// doesn't take a variable list of accounts
+    fn account_count_bounds(instruction: DexInstruction) -> (usize, Option<usize>) {
+        use DexInstruction::*;
+        match instruction {
+            CreateMarket => (10, Some(14)),
+            NewOrder => (12, Some(17)),
+            Swap => (13, Some(16)),
+            CancelOrder => (7, Some(7)),
+            ConsumeEvents => (4, None),
+            Settle => (9, Some(10)),
+            InitializeAccount => (4, Some(4)),
+            SweepFees => (6, None),
+            CloseAccount => (3, Some(3)),
+            CloseMarket => (11, Some(11)),
+            UpdateRoyalties => (4, Some(4)),
+            MigrateAccount => (3, Some(3)),
+            CreateMakerRewards => (5, Some(5)),
+            SetAccountMetadata => (2, Some(2)),
+            HashOrderbook => (4, Some(4)),
+            SetMarketStatus => (2, Some(2)),
+            ForceConsumeEvents => (6, None),
+            ClaimEscrow => (7, Some(7)),
+            UpdateMarketMetadata => (2, Some(2)),
+            SetOracle => (2, Some(3)),
+            SetFillPrivacy => (2, Some(2)),
+            SetBatchAuction => (4, Some(4)),
+            NewBatchOrder => (8, Some(9)),
+            MatchBatch => (2, None),
+            CreateSession => (4, Some(4)),
+            CancelSessionOrders => (7, Some(7)),
+            SetDesignatedMarketMaker => (4, Some(4)),
+            GetMarketInfo => (2, Some(2)),
+            SetCrankFeeShare => (2, Some(2)),
+            SetVolatilityFee => (2, Some(2)),
+            CreateCrankerIdentity => (4, Some(4)),
+            FreezeAndExport => (7, Some(7)),
+            ImportMarket => (5, Some(5)),
+            SetMinRestingSlots => (2, Some(2)),
+            Heartbeat => (2, Some(3)),
+            RelinkOrderbook => (11, Some(11)),
+            CreateSettleAllowlist => (5, None),
+            PreviewNewOrder => (12, Some(17)),
+            CancelAllOrders => (7, Some(7)),
+            ReplaceOrder => (12, Some(16)),
+            ReclaimOrderbook => (7, Some(7)),
+            SetCreateMarketConfig => (3, Some(3)),
+            SetReferralShare => (2, Some(2)),
+            SetMakerRebate => (2, Some(2)),
+            SetEmergencyAuthority => (2, Some(2)),
+            EmergencyHalt => (2, Some(2)),
+            UpdateMarketAdmin => (2, Some(2)),
+            SetCancelFee => (2, Some(2)),
+            NominateAdmin => (2, Some(2)),
+            AcceptAdmin => (2, Some(2)),
+            ArchiveUserAccount => (4, Some(4)),
+            RestoreUserAccount => (4, Some(4)),
+            SetHaltFlags => (2, Some(2)),
+            UpdateMarketParams => (5, Some(5)),
+            PruneExpiredOrders => (6, None),
+            SetCrankRewardScaling => (2, Some(2)),
+            Deposit => (8, Some(8)),
+            Withdraw => (9, Some(10)),
+            SettleToAssociatedAccounts => (14, Some(15)),
+        }
+    }
+
+    fn check_account_count(instruction: DexInstruction, accounts: &[AccountInfo]) -> ProgramResult {
+        let (min, max) = Self::account_count_bounds(instruction);
+        if accounts.len() < min {
+            msg!(
+                "Expected at least {} accounts, got {}. The account at index {} is missing",
+                min,
+                accounts.len(),
+                accounts.len()
+            );
+            return Err(DexError::MissingAccount.into());
+        }
+        if let Some(max) = max {
+            if accounts.len() > max {
+                msg!("Expected at most {} accounts, got {}", max, accounts.len());
+                return Err(DexError::TooManyAccounts.into());
+            }
         }
         Ok(())
     }
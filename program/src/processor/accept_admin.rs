@@ -0,0 +1,74 @@
+//! Take over the admin rights of a market. This instruction is signed by the admin nominated with nominate_admin
+use crate::{
+    error::DexError,
+    state::DexState,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(Clone, Copy, BorshDeserialize, BorshSerialize, BorshSize, Pod, Zeroable)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The nominated market admin
+    #[cons(signer)]
+    pub pending_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            pending_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.pending_admin).map_err(|e| {
+            msg!("The nominated market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    if market_state.pending_admin == Pubkey::default() {
+        msg!("No market admin was nominated");
+        return Err(DexError::InvalidPendingAdminAccount.into());
+    }
+    check_account_key(
+        accounts.pending_admin,
+        &market_state.pending_admin,
+        DexError::InvalidPendingAdminAccount,
+    )?;
+
+    market_state.admin = market_state.pending_admin;
+    market_state.pending_admin = Pubkey::default();
+    msg!("The market admin is now {}", market_state.admin);
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+//! Archive a dormant user account, which has no open orders and no funds left, to reclaim most of its rent.
+//!
+//! The metrics and metadata of the user account are moved to a compact archive account, derived from the user
+//! account address, and the user account is closed. The user account can be recreated at the same address from its
+//! archive with restore_user_account.
+use crate::{
+    error::DexError,
+    state::{AccountTag, UserAccount, UserAccountArchive, USER_ACCOUNT_ARCHIVE_LEN},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the archive account of a user account
+pub const USER_ACCOUNT_ARCHIVE_SEED: &[u8] = b"user_account_archive";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a archive_user_account instruction.
+*/
+pub struct Params {
+    /// The index of the user account, which is needed to restore it at its address
+    pub account_index: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX user account to archive
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The DEX user account owner wallet, which pays for the archive account and receives the user account's rent
+    #[cons(writable, signer)]
+    pub user_owner: &'a T,
+
+    /// The user account archive
+    #[cons(writable)]
+    pub archive: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            archive: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params { account_index } =
+        try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    let header = user_account.header;
+    if &header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if header.number_of_orders != 0
+        || header.base_token_free != 0
+        || header.base_token_locked != 0
+        || header.quote_token_free != 0
+        || header.quote_token_locked != 0
+    {
+        msg!("The user account cannot be archived as it has pending orders or unsettled funds");
+        return Err(DexError::UserAccountStillActive.into());
+    }
+
+    let market_key_bytes = header.market.to_bytes();
+    let owner_key_bytes = header.owner.to_bytes();
+    let account_index_bytes = account_index.to_le_bytes();
+    let mut seeds: Vec<&[u8]> = vec![&market_key_bytes, &owner_key_bytes];
+    if *account_index != 0 {
+        seeds.push(&account_index_bytes);
+    }
+    let (user_account_key, _) = Pubkey::find_program_address(&seeds, program_id);
+    if &user_account_key != accounts.user.key {
+        msg!("The account index doesn't match the user account");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let user_key_bytes = accounts.user.key.to_bytes();
+    let (archive_key, archive_nonce) =
+        Pubkey::find_program_address(&[&user_key_bytes, USER_ACCOUNT_ARCHIVE_SEED], program_id);
+    if &archive_key != accounts.archive.key {
+        msg!("Provided an invalid archive account for the specified user account");
+        return Err(DexError::InvalidUserAccountArchive.into());
+    }
+
+    let lamports = Rent::get()?.minimum_balance(USER_ACCOUNT_ARCHIVE_LEN);
+    let allocate_account = create_account(
+        accounts.user_owner.key,
+        accounts.archive.key,
+        lamports,
+        USER_ACCOUNT_ARCHIVE_LEN as u64,
+        program_id,
+    );
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.user_owner.clone(),
+            accounts.archive.clone(),
+        ],
+        &[&[&user_key_bytes, USER_ACCOUNT_ARCHIVE_SEED, &[archive_nonce]]],
+    )?;
+
+    let mut archive_data = accounts.archive.data.borrow_mut();
+    let archive = UserAccountArchive::from_buffer_unchecked(&mut archive_data)?;
+    *archive = UserAccountArchive {
+        tag: AccountTag::UserAccountArchive as u64,
+        market: header.market,
+        owner: header.owner,
+        account_index: *account_index,
+        accumulated_rebates: header.accumulated_rebates,
+        accumulated_maker_quote_volume: header.accumulated_maker_quote_volume,
+        accumulated_maker_base_volume: header.accumulated_maker_base_volume,
+        accumulated_taker_quote_volume: header.accumulated_taker_quote_volume,
+        accumulated_taker_base_volume: header.accumulated_taker_base_volume,
+        metadata: header.metadata,
+        last_active_slot: header.last_active_slot,
+        has_settle_allowlist: header.has_settle_allowlist,
+        _padding: [0; 7],
+    };
+
+    header.tag = AccountTag::Closed as u64;
+
+    let mut lamports = accounts.user.lamports.borrow_mut();
+    let mut owner_lamports = accounts.user_owner.lamports.borrow_mut();
+
+    **owner_lamports += **lamports;
+    **lamports = 0;
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+//! Cancel all the open orders of a user account which rest on the book, up to a maximum number of orders.
+//!
+//! Orders which are not yet allowed to be cancelled by the market's minimum resting time are skipped, as are orders
+//! which were matched and only wait for their fill events to be consumed.
+use crate::{
+    error::DexError,
+    processor::cancel_session_orders::cancel_resting_order,
+    state::{CallBackInfo, DexState, UserAccount},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use asset_agnostic_orderbook::state::{
+    critbit::Slab, get_side_from_order_id, market_state::MarketState, AccountTag, Side,
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a cancel_all_orders instruction.
+*/
+pub struct Params {
+    /// The maximum number of orders to cancel, which bounds the compute used by the instruction. The orders are
+    /// cancelled from the most recent one.
+    pub max_orders: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The orderbook
+    #[cons(writable)]
+    pub orderbook: &'a T,
+
+    /// The AOB event queue
+    #[cons(writable)]
+    pub event_queue: &'a T,
+
+    /// The AOB bids shared memory
+    #[cons(writable)]
+    pub bids: &'a T,
+
+    /// The AOB asks shared memory
+    #[cons(writable)]
+    pub asks: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The user wallet
+    #[cons(signer)]
+    pub user_owner: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let Params { max_orders } = bytemuck::try_from_bytes(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    if *max_orders == 0 {
+        msg!("The maximum number of orders to cancel should be nonzero");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(
+        accounts.bids,
+        &orderbook.bids,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.asks,
+        &orderbook.asks,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    drop(orderbook_guard);
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let mut user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if &user_account.header.market != accounts.market.key {
+        msg!("The provided user account doesn't match the current market");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let slot = Clock::get()?.slot;
+    let orders = {
+        let mut bids_guard = accounts.bids.data.borrow_mut();
+        let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+        let mut asks_guard = accounts.asks.data.borrow_mut();
+        let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+        user_account
+            .orders()
+            .iter()
+            .rev()
+            .filter(|o| market_state.check_resting_time(o.placed_slot, slot).is_ok())
+            .filter(|o| {
+                let slab = match get_side_from_order_id(o.id) {
+                    Side::Bid => &bids,
+                    Side::Ask => &asks,
+                };
+                slab.find_by_key(o.id).is_some()
+            })
+            .take(*max_orders as usize)
+            .copied()
+            .collect::<Vec<_>>()
+    };
+
+    let cancelled = orders.len() as u64;
+    let mut cancel_fees = 0;
+    for order in orders {
+        cancel_resting_order(
+            program_id,
+            &market_state,
+            accounts.orderbook,
+            accounts.event_queue,
+            accounts.bids,
+            accounts.asks,
+            &mut user_account,
+            order.id,
+        )?;
+        cancel_fees += market_state.charge_cancel_fee(&mut user_account.header, &order, slot);
+    }
+    msg!("Cancelled {} orders", cancelled);
+    if cancel_fees != 0 {
+        msg!("Charged a cancel fee of {}", cancel_fees);
+    }
+
+    if cancelled != 0 {
+        market_state.order_flow.record_cancels(slot, cancelled);
+        market_state.refresh_top_of_book(accounts.bids, accounts.asks, slot)?;
+    }
+
+    Ok(())
+}
@@ -15,10 +15,12 @@ use borsh::BorshSerialize;
 use bytemuck::{CheckedBitPattern, NoUninit};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program_error::{PrintProgramError, ProgramError},
     pubkey::Pubkey,
+    sysvar::Sysvar,
 };
 
 #[derive(Clone, Copy, CheckedBitPattern, NoUninit, BorshDeserialize, BorshSerialize, BorshSize)]
@@ -42,6 +44,7 @@ pub struct Params {
 #[derive(InstructionsAccount)]
 pub struct Accounts<'a, T> {
     /// The DEX market
+    #[cons(writable)]
     pub market: &'a T,
 
     /// The orderbook
@@ -127,7 +130,7 @@ pub(crate) fn process(
         _padding,
     } = params;
 
-    let market_state = DexState::get(accounts.market)?;
+    let mut market_state = DexState::get(accounts.market)?;
     let mut user_account_data = accounts.user.data.borrow_mut();
     let mut user_account = accounts.load_user_account(&mut user_account_data)?;
 
@@ -136,7 +139,10 @@ pub(crate) fn process(
     if *is_client_id {
         (order_index, order_id) = user_account
             .find_order_id_and_index_by_client_id(order_id)
-            .unwrap();
+            .map_err(|e| {
+                msg!("No open order with the given client order id!");
+                e
+            })?;
     } else {
         let order_id_from_index = user_account.read_order(order_index as usize)?.id;
         if order_id != order_id_from_index {
@@ -145,6 +151,10 @@ pub(crate) fn process(
         }
     }
 
+    let slot = Clock::get()?.slot;
+    let order = user_account.read_order(order_index as usize)?;
+    market_state.check_resting_time(order.placed_slot, slot)?;
+
     let invoke_params = asset_agnostic_orderbook::instruction::cancel_order::Params { order_id };
     let invoke_accounts = asset_agnostic_orderbook::instruction::cancel_order::Accounts {
         market: accounts.orderbook,
@@ -196,7 +206,14 @@ pub(crate) fn process(
         }
     };
 
+    let cancel_fee = market_state.charge_cancel_fee(&mut user_account.header, &order, slot);
+    if cancel_fee != 0 {
+        msg!("Charged a cancel fee of {}", cancel_fee);
+    }
+
     user_account.remove_order(order_index as usize)?;
+    market_state.order_flow.record_cancels(slot, 1);
+    market_state.refresh_top_of_book(accounts.bids, accounts.asks, slot)?;
 
     Ok(())
 }
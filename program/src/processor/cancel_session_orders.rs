@@ -0,0 +1,267 @@
+//! Cancel the open orders placed under an expired session. This instruction is permissionless
+//!
+//! The funds locked by the cancelled orders are unlocked in the user account and the session's order list is emptied.
+use crate::{
+    error::DexError,
+    state::{CallBackInfo, DexState, Session, UserAccount},
+    utils::{check_account_key, check_account_owner},
+};
+use asset_agnostic_orderbook::{
+    error::AoError,
+    state::{critbit::Slab, get_side_from_order_id, market_state::MarketState, AccountTag, Side},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The orderbook
+    #[cons(writable)]
+    pub orderbook: &'a T,
+
+    /// The AOB event queue
+    #[cons(writable)]
+    pub event_queue: &'a T,
+
+    /// The AOB bids shared memory
+    #[cons(writable)]
+    pub bids: &'a T,
+
+    /// The AOB asks shared memory
+    #[cons(writable)]
+    pub asks: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The expired session account of the user account
+    #[cons(writable)]
+    pub session: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            session: next_account_info(accounts_iter)?,
+        };
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.session, program_id, DexError::InvalidSessionAccount)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(
+        accounts.bids,
+        &orderbook.bids,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.asks,
+        &orderbook.asks,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    drop(orderbook_guard);
+
+    let mut session_data = accounts.session.data.borrow_mut();
+    let mut session = Session::from_buffer(&mut session_data)?;
+    if &session.header.user_account != accounts.user.key {
+        msg!("The session account does not belong to this user account");
+        return Err(DexError::InvalidSessionAccount.into());
+    }
+    let clock = Clock::get()?;
+    if !session.is_expired(clock.unix_timestamp) {
+        msg!("The session expires at {}", session.header.expiry);
+        return Err(DexError::SessionNotExpired.into());
+    }
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let mut user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    if &user_account.header.market != accounts.market.key {
+        msg!("The provided user account doesn't match the current market");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    cancel_open_session_orders(
+        program_id,
+        &mut market_state,
+        accounts.orderbook,
+        accounts.event_queue,
+        accounts.bids,
+        accounts.asks,
+        &mut user_account,
+        &mut session,
+        clock.slot,
+    )?;
+
+    Ok(())
+}
+
+/// Cancels the session orders which still rest on the book, unlocks their funds in the user account and empties
+/// the session's order list. Returns the number of cancelled orders.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cancel_open_session_orders<'b>(
+    program_id: &Pubkey,
+    market_state: &mut DexState,
+    orderbook: &AccountInfo<'b>,
+    event_queue: &AccountInfo<'b>,
+    bids: &AccountInfo<'b>,
+    asks: &AccountInfo<'b>,
+    user_account: &mut UserAccount,
+    session: &mut Session,
+    slot: u64,
+) -> Result<u64, ProgramError> {
+    // Orders which were matched or cancelled since, including those with pending fill events, are skipped
+    let open_order_ids = {
+        let mut bids_guard = bids.data.borrow_mut();
+        let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+        let mut asks_guard = asks.data.borrow_mut();
+        let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+        session
+            .order_ids()
+            .iter()
+            .copied()
+            .filter(|&order_id| user_account.find_order_index(order_id).is_ok())
+            .filter(|&order_id| {
+                let slab = match get_side_from_order_id(order_id) {
+                    Side::Bid => &bids,
+                    Side::Ask => &asks,
+                };
+                slab.find_by_key(order_id).is_some()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    market_state
+        .order_flow
+        .record_cancels(slot, open_order_ids.len() as u64);
+
+    let cancelled = open_order_ids.len() as u64;
+    for order_id in open_order_ids {
+        cancel_resting_order(
+            program_id,
+            market_state,
+            orderbook,
+            event_queue,
+            bids,
+            asks,
+            user_account,
+            order_id,
+        )?;
+        msg!("Cancelled session order {}", order_id);
+    }
+
+    session.clear();
+    if cancelled != 0 {
+        market_state.refresh_top_of_book(bids, asks, slot)?;
+    }
+
+    Ok(cancelled)
+}
+
+/// Cancels an order of a user account which rests on the book, unlocks its funds and removes it from the user account
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cancel_resting_order<'b>(
+    program_id: &Pubkey,
+    market_state: &DexState,
+    orderbook: &AccountInfo<'b>,
+    event_queue: &AccountInfo<'b>,
+    bids: &AccountInfo<'b>,
+    asks: &AccountInfo<'b>,
+    user_account: &mut UserAccount,
+    order_id: u128,
+) -> Result<(), ProgramError> {
+    let invoke_params = asset_agnostic_orderbook::instruction::cancel_order::Params { order_id };
+    let invoke_accounts = asset_agnostic_orderbook::instruction::cancel_order::Accounts {
+        market: orderbook,
+        event_queue,
+        bids,
+        asks,
+    };
+
+    let mut order_summary = match asset_agnostic_orderbook::instruction::cancel_order::process::<
+        CallBackInfo,
+    >(program_id, invoke_accounts, invoke_params)
+    {
+        Err(error) => {
+            error.print::<AoError>();
+            return Err(DexError::AOBError.into());
+        }
+        Ok(s) => s,
+    };
+    market_state
+        .unscale_order_summary(&mut order_summary)
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let header = &mut user_account.header;
+    match get_side_from_order_id(order_id) {
+        Side::Bid => {
+            header.quote_token_free = header
+                .quote_token_free
+                .checked_add(order_summary.total_quote_qty)
+                .ok_or(DexError::NumericalOverflow)?;
+            header.quote_token_locked = header
+                .quote_token_locked
+                .checked_sub(order_summary.total_quote_qty)
+                .ok_or(DexError::NumericalOverflow)?;
+        }
+        Side::Ask => {
+            header.base_token_free = header
+                .base_token_free
+                .checked_add(order_summary.total_base_qty)
+                .ok_or(DexError::NumericalOverflow)?;
+            header.base_token_locked = header
+                .base_token_locked
+                .checked_sub(order_summary.total_base_qty)
+                .ok_or(DexError::NumericalOverflow)?;
+        }
+    }
+
+    let order_index = user_account.find_order_index(order_id)?;
+    user_account.remove_order(order_index)?;
+    Ok(())
+}
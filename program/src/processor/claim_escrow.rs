@@ -0,0 +1,165 @@
+//! Transfer the balances held in a market's escrow back to their user account
+//!
+//! Orders of the user account which are no longer in the orderbook are removed.
+use crate::{
+    error::DexError,
+    state::{CallBackInfo, DexState, Escrow, UserAccount},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use asset_agnostic_orderbook::state::{
+    critbit::Slab, get_side_from_order_id, market_state::MarketState, AccountTag, Side,
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The orderbook
+    pub orderbook: &'a T,
+
+    /// The AOB bids shared memory
+    pub bids: &'a T,
+
+    /// The AOB asks shared memory
+    pub asks: &'a T,
+
+    /// The market escrow account
+    #[cons(writable)]
+    pub escrow: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The user account owner wallet
+    #[cons(signer)]
+    pub user_owner: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            escrow: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.escrow, program_id, DexError::InvalidEscrowAccount)?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    drop(market_state);
+
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(
+        accounts.bids,
+        &orderbook.bids,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.asks,
+        &orderbook.asks,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    drop(orderbook_guard);
+
+    let mut escrow_guard = accounts.escrow.data.borrow_mut();
+    let mut escrow = Escrow::from_buffer(&mut escrow_guard)?;
+    if &escrow.header.market != accounts.market.key {
+        msg!("The escrow account does not belong to this market");
+        return Err(DexError::InvalidEscrowAccount.into());
+    }
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let mut user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if &user_account.header.market != accounts.market.key {
+        msg!("The user account does not belong to this market");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let entry = escrow.remove(accounts.user.key).ok_or(DexError::NoOp)?;
+
+    user_account.header.base_token_free = user_account
+        .header
+        .base_token_free
+        .checked_add(entry.base_token_free)
+        .ok_or(DexError::NumericalOverflow)?;
+    user_account.header.base_token_locked = user_account
+        .header
+        .base_token_locked
+        .saturating_sub(entry.base_token_unlocked);
+    user_account.header.quote_token_free = user_account
+        .header
+        .quote_token_free
+        .checked_add(entry.quote_token_free)
+        .ok_or(DexError::NumericalOverflow)?;
+    user_account.header.quote_token_locked = user_account
+        .header
+        .quote_token_locked
+        .saturating_sub(entry.quote_token_unlocked);
+
+    let mut bids_guard = accounts.bids.data.borrow_mut();
+    let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+    let mut asks_guard = accounts.asks.data.borrow_mut();
+    let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+
+    // Iterate backwards as removing an order moves the last order into its slot
+    for order_index in (0..user_account.orders().len()).rev() {
+        let order_id = user_account.orders()[order_index].id;
+        let slab = match get_side_from_order_id(order_id) {
+            Side::Bid => &bids,
+            Side::Ask => &asks,
+        };
+        if slab.find_by_key(order_id).is_none() {
+            user_account.remove_order(order_index)?;
+        }
+    }
+
+    Ok(())
+}
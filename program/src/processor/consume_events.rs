@@ -1,32 +1,65 @@
 //! Crank the processing of DEX events.
+//!
+//! Events are always consumed in queue order. The crank stops at the first event whose user account was not
+//! provided, and only the events before it are popped from the queue, so that an event is never applied ahead of an
+//! earlier one and the remaining events are picked up by the next crank. The user accounts can be passed in any order.
+//!
+//! An empty event queue is detected before the market state is loaded, so that idle cranks exit early at a fraction
+//! of the cost of a productive one, unless they sample maker rewards or prune expired sessions.
+//!
+//! The best bid and best ask makers are sampled into the market's maker rewards account on every crank which is
+//! passed that account, at most once per slot. The account is recognized by its tag, so that the sampling cadence is
+//! set by the program rather than by a cranker flag.
+//!
+//! When pruning expired sessions, the orders of the expired session accounts passed along the user accounts are
+//! cancelled as part of the crank, which saves a separate cancel_session_orders call for each expired session.
+//!
+//! When reposting iceberg orders, the next tranche of each iceberg order whose displayed tranche was fully filled is
+//! posted once the consumed events are popped from the queue. The tranche is funded from the free balance of the user
+//! account, and the iceberg order ends when the balance falls short, when the order has expired or when the tranche
+//! would cross the book. Without reposting, the iceberg orders end with their last displayed tranche.
+
+use std::{borrow::Cow, mem::size_of};
 
 use num_traits::FromPrimitive;
 
 use crate::{
     error::DexError,
-    state::{CallBackInfo, DexState, FeeTier, UserAccount},
-    utils::{check_account_key, check_account_owner, fp32_mul},
+    processor::cancel_session_orders::cancel_open_session_orders,
+    state::{
+        AccountTag as StateAccountTag, CallBackInfo, CrankerIdentity, DexState, Escrow, FeeTier,
+        MakerFillLog, MakerRewards, Order, OutLog, Session, UserAccount, SESSION_HEADER_LEN,
+        USER_ACCOUNT_HEADER_LEN,
+    },
+    utils::{check_account_key, check_account_owner, check_signer, fp32_mul},
 };
 use asset_agnostic_orderbook::{
     error::AoError,
     state::{
+        critbit::Slab,
         event_queue::{EventQueue, EventRef, FillEvent, FillEventRef, OutEvent, OutEventRef},
-        AccountTag, Side,
+        market_state::MarketState,
+        AccountTag, SelfTradeBehavior, Side,
     },
 };
 use bonfida_utils::BorshSize;
 use bonfida_utils::InstructionsAccount;
 use borsh::BorshDeserialize;
 use borsh::BorshSerialize;
-use bytemuck::{try_from_bytes, Pod, Zeroable};
+use bytemuck::{bytes_of_mut, Pod, Zeroable};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program_error::{PrintProgramError, ProgramError},
     pubkey::Pubkey,
+    sysvar::Sysvar,
 };
 
+/// The size of the original consume_events params, which only held `max_iterations` and `no_op_err`
+pub const LEGACY_PARAMS_LEN: usize = 16;
+
 #[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
 #[repr(C)]
 /**
@@ -40,6 +73,91 @@ pub struct Params {
     /// Value should be 0 or 1.
     /// Is u64 to allow for type casting.
     pub no_op_err: u64,
+    /// Decide if the consumed events and crank rewards should be recorded in the cranker's identity account.
+    /// Value should be 0 or 1.
+    /// Is u64 to allow for type casting.
+    pub record_cranker_identity: u64,
+    /// Decide if the orders of the expired sessions passed along the user accounts should be cancelled.
+    /// Value should be 0 or 1.
+    /// Is u64 to allow for type casting.
+    pub prune_expired_sessions: u64,
+    /// Decide if the next tranches of the fully filled iceberg orders should be posted.
+    /// Value should be 0 or 1.
+    /// Is u64 to allow for type casting.
+    pub repost_iceberg_orders: u64,
+}
+
+impl Params {
+    /// Builds validated consume_events arguments
+    pub fn new(
+        max_iterations: u64,
+        no_op_err: bool,
+        record_cranker_identity: bool,
+        prune_expired_sessions: bool,
+        repost_iceberg_orders: bool,
+    ) -> Result<Self, ProgramError> {
+        let params = Self {
+            max_iterations,
+            no_op_err: no_op_err as u64,
+            record_cranker_identity: record_cranker_identity as u64,
+            prune_expired_sessions: prune_expired_sessions as u64,
+            repost_iceberg_orders: repost_iceberg_orders as u64,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Parses and validates the consume_events arguments from the instruction data.
+    ///
+    /// The flags were appended to the params over time, so the instruction data of older clients stops after
+    /// `no_op_err` or after any later flag. The missing flags are then zero.
+    pub fn parse(instruction_data: &[u8]) -> Result<Self, ProgramError> {
+        let len = instruction_data.len();
+        if len < LEGACY_PARAMS_LEN || len > size_of::<Self>() || len % size_of::<u64>() != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let mut params = Self::zeroed();
+        bytes_of_mut(&mut params)[..len].copy_from_slice(instruction_data);
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Checks that the maximum number of events is nonzero and that the flags are either 0 or 1
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        if self.max_iterations == 0 {
+            msg!("The maximum number of events to consume should be nonzero");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.no_op_err > 1
+            || self.record_cranker_identity > 1
+            || self.prune_expired_sessions > 1
+            || self.repost_iceberg_orders > 1
+        {
+            msg!("The no_op_err, record_cranker_identity, prune_expired_sessions and repost_iceberg_orders flags should be 0 or 1");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    /// Whether the transaction should fail when there are no events to consume
+    pub fn no_op_err(&self) -> bool {
+        self.no_op_err != 0
+    }
+
+    /// Whether the crank should be recorded in the cranker's identity account
+    pub fn record_cranker_identity(&self) -> bool {
+        self.record_cranker_identity != 0
+    }
+
+    /// Whether the orders of the expired sessions should be cancelled
+    pub fn prune_expired_sessions(&self) -> bool {
+        self.prune_expired_sessions != 0
+    }
+
+    /// Whether the next tranches of the fully filled iceberg orders should be posted
+    pub fn repost_iceberg_orders(&self) -> bool {
+        self.repost_iceberg_orders != 0
+    }
 }
 
 #[derive(InstructionsAccount)]
@@ -56,30 +174,136 @@ pub struct Accounts<'a, T> {
     #[cons(writable)]
     pub event_queue: &'a T,
 
-    /// The reward target
+    /// The reward target, credited with the cranker's share of the fees when it is a user account of the market
     #[cons(writable)]
     pub reward_target: &'a T,
 
-    /// The relevant user accounts
+    /// The relevant user accounts, along with the session accounts to prune when pruning expired sessions
     #[cons(writable)]
     pub user_accounts: &'a [T],
+
+    /// The optional AOB bids shared memory, required along the maker rewards account, when pruning expired sessions or
+    /// when reposting iceberg orders
+    #[cons(writable)]
+    pub bids: Option<&'a T>,
+
+    /// The optional AOB asks shared memory, required along the maker rewards account, when pruning expired sessions or
+    /// when reposting iceberg orders
+    #[cons(writable)]
+    pub asks: Option<&'a T>,
+
+    /// The optional maker rewards account of the market, sampled whenever it is provided
+    #[cons(writable)]
+    pub maker_rewards: Option<&'a T>,
+
+    /// The optional cranker identity account, required when recording the crank
+    #[cons(writable)]
+    pub cranker_identity: Option<&'a T>,
+
+    /// The optional owner of the cranker identity account, required when recording the crank
+    #[cons(signer)]
+    pub cranker: Option<&'a T>,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
     pub fn parse(
         program_id: &Pubkey,
         accounts: &'a [AccountInfo<'b>],
+        record_cranker_identity: bool,
+        prune_expired_sessions: bool,
+        repost_iceberg_orders: bool,
     ) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
-        let a = Self {
-            market: next_account_info(accounts_iter)?,
-            orderbook: next_account_info(accounts_iter)?,
-            event_queue: next_account_info(accounts_iter)?,
-            reward_target: next_account_info(accounts_iter)?,
-            user_accounts: accounts_iter.as_slice(),
+        let market = next_account_info(accounts_iter)?;
+        let orderbook = next_account_info(accounts_iter)?;
+        let event_queue = next_account_info(accounts_iter)?;
+        let reward_target = next_account_info(accounts_iter)?;
+        let mut remaining = accounts_iter.as_slice();
+        let (cranker_identity, cranker) = if record_cranker_identity {
+            if remaining.len() < 2 {
+                msg!("The cranker identity and cranker accounts are required to record the crank");
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            let (rest, identity_accounts) = remaining.split_at(remaining.len() - 2);
+            remaining = rest;
+            (Some(&identity_accounts[0]), Some(&identity_accounts[1]))
+        } else {
+            (None, None)
+        };
+        let sample_maker_rewards = remaining
+            .last()
+            .map(|a| is_maker_rewards_account(program_id, a))
+            .unwrap_or(false);
+        let a = if sample_maker_rewards {
+            if remaining.len() < 3 {
+                msg!("The bids and asks accounts are required to sample maker rewards");
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            let (user_accounts, sampling_accounts) = remaining.split_at(remaining.len() - 3);
+            Self {
+                market,
+                orderbook,
+                event_queue,
+                reward_target,
+                user_accounts,
+                bids: Some(&sampling_accounts[0]),
+                asks: Some(&sampling_accounts[1]),
+                maker_rewards: Some(&sampling_accounts[2]),
+                cranker_identity,
+                cranker,
+            }
+        } else if prune_expired_sessions || repost_iceberg_orders {
+            if remaining.len() < 2 {
+                msg!("The bids and asks accounts are required to prune expired sessions or repost iceberg orders");
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            let (user_accounts, orderbook_accounts) = remaining.split_at(remaining.len() - 2);
+            Self {
+                market,
+                orderbook,
+                event_queue,
+                reward_target,
+                user_accounts,
+                bids: Some(&orderbook_accounts[0]),
+                asks: Some(&orderbook_accounts[1]),
+                maker_rewards: None,
+                cranker_identity,
+                cranker,
+            }
+        } else {
+            Self {
+                market,
+                orderbook,
+                event_queue,
+                reward_target,
+                user_accounts: remaining,
+                bids: None,
+                asks: None,
+                maker_rewards: None,
+                cranker_identity,
+                cranker,
+            }
         };
 
         check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        if let Some(maker_rewards) = a.maker_rewards {
+            check_account_owner(
+                maker_rewards,
+                program_id,
+                DexError::InvalidMakerRewardsAccount,
+            )?;
+        }
+        if let (Some(cranker_identity), Some(cranker)) = (a.cranker_identity, a.cranker) {
+            check_account_owner(
+                cranker_identity,
+                program_id,
+                DexError::InvalidCrankerIdentityAccount,
+            )?;
+            check_signer(cranker).map_err(|e| {
+                msg!("The cranker should be a signer to record the crank!");
+                e
+            })?;
+        }
 
         Ok(a)
     }
@@ -90,40 +314,124 @@ pub(crate) fn process(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let accounts = Accounts::parse(program_id, accounts)?;
+    let params = Params::parse(instruction_data)?;
 
-    let Params {
-        max_iterations,
-        no_op_err,
-    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let accounts = Accounts::parse(
+        program_id,
+        accounts,
+        params.record_cranker_identity(),
+        params.prune_expired_sessions(),
+        params.repost_iceberg_orders(),
+    )?;
+
+    if accounts.maker_rewards.is_none()
+        && !params.prune_expired_sessions()
+        && is_event_queue_empty(accounts.event_queue)?
+    {
+        msg!("The event queue is empty");
+        if params.no_op_err() {
+            return Err(DexError::NoOp.into());
+        }
+        return Ok(());
+    }
+
+    // The user accounts are looked up by binary search
+    let user_accounts = sorted_user_accounts(accounts.user_accounts);
+    let accounts = Accounts {
+        user_accounts: &user_accounts,
+        ..accounts
+    };
 
     let mut market_state = DexState::get(accounts.market)?;
 
+    check_accounts(&market_state, &accounts).unwrap();
+
+    // The expired sessions are pruned first as the AOB borrows the event queue when cancelling orders
+    let pruned_orders = match (accounts.bids, accounts.asks) {
+        (Some(bids), Some(asks)) if params.prune_expired_sessions() => {
+            prune_expired_sessions(program_id, &accounts, bids, asks, &mut market_state)?
+        }
+        _ => 0,
+    };
+
     let mut event_queue_guard = accounts.event_queue.data.borrow_mut();
     let event_queue =
         EventQueue::<CallBackInfo>::from_buffer(&mut event_queue_guard, AccountTag::EventQueue)?;
 
-    check_accounts(&market_state, &accounts).unwrap();
+    if let (Some(bids), Some(asks), Some(maker_rewards)) =
+        (accounts.bids, accounts.asks, accounts.maker_rewards)
+    {
+        sample_maker_rewards_account(&market_state, &accounts, bids, asks, maker_rewards)?;
+    }
 
+    let pending_events = event_queue.len();
     let mut total_iterations = 0;
+    let mut total_fills = 0;
+    let mut iceberg_tranches = Vec::new();
+    let fees_before = market_state.accumulated_fees;
 
-    for event in event_queue.iter().take(*max_iterations as usize) {
-        if consume_event(accounts.user_accounts, event, &mut market_state).is_err() {
-            break;
-        }
+    for event in event_queue.iter().take(params.max_iterations as usize) {
+        let is_fill = matches!(event, EventRef::Fill(_));
+        let user_account = event_user_account(&event);
+        let tranche = match consume_event(
+            accounts.market.key,
+            accounts.user_accounts,
+            event,
+            &mut market_state,
+            None,
+        ) {
+            Ok(tranche) => tranche,
+            Err(e) => {
+                if let DexError::MissingUserAccount = e {
+                    msg!(
+                        "Stopping at event {} as its user account {} was not provided",
+                        total_iterations,
+                        user_account
+                    );
+                }
+                break;
+            }
+        };
+        iceberg_tranches.extend(tranche);
         total_iterations += 1;
+        total_fills += is_fill as u64;
     }
 
     if total_iterations == 0 {
-        msg!("Failed to complete one iteration");
-        if *no_op_err == 1 {
-            return Err(DexError::NoOp.into());
+        if pruned_orders == 0 {
+            msg!("Failed to complete one iteration");
+            if params.no_op_err() {
+                return Err(DexError::NoOp.into());
+            }
         }
         return Ok(());
     }
 
     drop(event_queue_guard);
 
+    let slot = Clock::get()?.slot;
+    market_state.order_flow.record_fills(slot, total_fills);
+
+    let generated_fees = market_state.accumulated_fees - fees_before;
+    let crank_reward = pay_crank_reward(
+        program_id,
+        &accounts,
+        &mut market_state,
+        generated_fees,
+        slot,
+    )?;
+    market_state.record_consumed_events(pending_events - total_iterations);
+
+    if let (Some(cranker_identity), Some(cranker)) = (accounts.cranker_identity, accounts.cranker) {
+        record_crank(
+            accounts.market,
+            cranker_identity,
+            cranker,
+            total_iterations,
+            crank_reward,
+        )?;
+    }
+
     let invoke_params = asset_agnostic_orderbook::instruction::consume_events::Params {
         number_of_entries_to_consume: total_iterations,
     };
@@ -141,9 +449,221 @@ pub(crate) fn process(
         return Err(DexError::AOBError.into());
     }
 
+    // The tranches are posted once the consumed events are popped, which makes room for the events of the AOB
+    match (accounts.bids, accounts.asks) {
+        (Some(bids), Some(asks))
+            if params.repost_iceberg_orders() && !iceberg_tranches.is_empty() =>
+        {
+            repost_iceberg_tranches(
+                program_id,
+                &accounts,
+                bids,
+                asks,
+                &mut market_state,
+                &iceberg_tranches,
+            )?;
+        }
+        _ => (),
+    }
+
     Ok(())
 }
 
+/// The next tranche of an iceberg order whose displayed tranche was fully filled
+pub(crate) struct IcebergTranche {
+    callback_info: CallBackInfo,
+    side: Side,
+    limit_price: u64,
+    /// The filled order, whose client id, expiry and maker fee rate carry over to the next tranche
+    order: Order,
+}
+
+/// Posts the next tranches of iceberg orders and returns the number of orders posted
+fn repost_iceberg_tranches<'b>(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo<'b>>,
+    bids: &AccountInfo<'b>,
+    asks: &AccountInfo<'b>,
+    market_state: &mut DexState,
+    tranches: &[IcebergTranche],
+) -> Result<u64, ProgramError> {
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(bids, &orderbook.bids, DexError::InvalidOrderbookAccount)?;
+    check_account_key(asks, &orderbook.asks, DexError::InvalidOrderbookAccount)?;
+    drop(orderbook_guard);
+
+    let clock = Clock::get()?;
+    let mut reposted_orders = 0;
+    for IcebergTranche {
+        callback_info,
+        side,
+        limit_price,
+        order,
+    } in tranches
+    {
+        // The user account was provided as the event which filled the previous tranche was consumed
+        let user_account_info = accounts
+            .user_accounts
+            .binary_search_by_key(&callback_info.user_account, |k| *k.key)
+            .map(|i| &accounts.user_accounts[i])
+            .map_err(|_| DexError::MissingUserAccount)?;
+        let mut user_account_data = user_account_info.data.borrow_mut();
+        let mut user_account = UserAccount::from_buffer(&mut user_account_data)?;
+
+        let base_qty = match order.next_tranche_base_qty() {
+            Some(q) => q,
+            None => {
+                msg!("The iceberg order {} is complete", order.id);
+                continue;
+            }
+        };
+        if order.is_expired(clock.unix_timestamp) {
+            msg!("The iceberg order {} has expired", order.id);
+            continue;
+        }
+        let scaled_base_qty = market_state.scale_base_amount(base_qty);
+        if scaled_base_qty == 0 {
+            msg!(
+                "The remaining base quantity {} of the iceberg order {} is smaller than a lot",
+                base_qty,
+                order.id
+            );
+            continue;
+        }
+        let (required_qty, free_qty) = match side {
+            Side::Bid => (
+                market_state
+                    .get_quote_from_base(base_qty, *limit_price)
+                    .ok_or(DexError::NumericalOverflow)?,
+                user_account.header.quote_token_free,
+            ),
+            Side::Ask => (base_qty, user_account.header.base_token_free),
+        };
+        if required_qty > free_qty {
+            msg!(
+                "The free balance doesn't cover the next tranche of the iceberg order {}, its remaining {} base is cancelled",
+                order.id,
+                order.hidden_base_qty
+            );
+            continue;
+        }
+
+        let invoke_params = asset_agnostic_orderbook::instruction::new_order::Params {
+            max_base_qty: scaled_base_qty,
+            max_quote_qty: market_state.scale_quote_amount(u64::MAX),
+            limit_price: *limit_price,
+            side: *side,
+            match_limit: 1,
+            callback_info: *callback_info,
+            post_only: true,
+            post_allowed: true,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
+        };
+        let invoke_accounts = asset_agnostic_orderbook::instruction::new_order::Accounts {
+            market: accounts.orderbook,
+            event_queue: accounts.event_queue,
+            bids,
+            asks,
+        };
+        let mut order_summary = match asset_agnostic_orderbook::instruction::new_order::process(
+            program_id,
+            invoke_accounts,
+            invoke_params,
+        ) {
+            Err(error) => {
+                error.print::<AoError>();
+                return Err(DexError::AOBError.into());
+            }
+            Ok(s) => s,
+        };
+        market_state
+            .unscale_order_summary(&mut order_summary)
+            .unwrap();
+        let order_id = match order_summary.posted_order_id {
+            Some(id) => id,
+            None => {
+                msg!(
+                    "The next tranche of the iceberg order {} would cross the book",
+                    order.id
+                );
+                continue;
+            }
+        };
+
+        // The posted tranche is reserved from the free balance, which was checked against the full tranche
+        let posted_base_qty = order_summary.total_base_qty_posted;
+        let (posted_qty, free_balance, locked_balance) = match side {
+            Side::Bid => (
+                market_state
+                    .get_quote_from_base(posted_base_qty, *limit_price)
+                    .ok_or(DexError::NumericalOverflow)?,
+                &mut user_account.header.quote_token_free,
+                &mut user_account.header.quote_token_locked,
+            ),
+            Side::Ask => (
+                posted_base_qty,
+                &mut user_account.header.base_token_free,
+                &mut user_account.header.base_token_locked,
+            ),
+        };
+        *free_balance = free_balance
+            .checked_sub(posted_qty)
+            .ok_or(DexError::InsufficientFunds)?;
+        *locked_balance += posted_qty;
+        user_account.add_order(Order {
+            id: order_id,
+            placed_slot: clock.slot,
+            hidden_base_qty: order.hidden_base_qty - posted_base_qty,
+            was_filled: 0,
+            ..*order
+        })?;
+        msg!("Reposted the iceberg order {} as {}", order.id, order_id);
+        reposted_orders += 1;
+    }
+
+    if reposted_orders != 0 {
+        market_state
+            .order_flow
+            .record_new_orders(clock.slot, reposted_orders);
+        market_state.refresh_top_of_book(bids, asks, clock.slot)?;
+    }
+
+    Ok(reposted_orders)
+}
+
+/// Whether the event queue holds no event, which only reads its header
+fn is_event_queue_empty(event_queue: &AccountInfo) -> Result<bool, ProgramError> {
+    let mut event_queue_guard = event_queue.data.borrow_mut();
+    let event_queue =
+        EventQueue::<CallBackInfo>::from_buffer(&mut event_queue_guard, AccountTag::EventQueue)?;
+    Ok(event_queue.len() == 0)
+}
+
+/// Returns the user accounts sorted by key, which are only copied when the cranker did not sort them already
+fn sorted_user_accounts<'a, 'b>(
+    user_accounts: &'a [AccountInfo<'b>],
+) -> Cow<'a, [AccountInfo<'b>]> {
+    if user_accounts.windows(2).all(|w| w[0].key <= w[1].key) {
+        return Cow::Borrowed(user_accounts);
+    }
+    msg!("Sorting the user accounts by key");
+    let mut sorted = user_accounts.to_vec();
+    sorted.sort_unstable_by_key(|a| *a.key);
+    Cow::Owned(sorted)
+}
+
+/// The user account which is credited by an event
+fn event_user_account(event: &EventRef<CallBackInfo>) -> Pubkey {
+    match event {
+        EventRef::Fill(FillEventRef {
+            maker_callback_info,
+            ..
+        }) => maker_callback_info.user_account,
+        EventRef::Out(OutEventRef { callback_info, .. }) => callback_info.user_account,
+    }
+}
+
 fn check_accounts(market_state: &DexState, accounts: &Accounts<AccountInfo>) -> ProgramResult {
     check_account_key(
         accounts.orderbook,
@@ -153,11 +673,201 @@ fn check_accounts(market_state: &DexState, accounts: &Accounts<AccountInfo>) ->
     Ok(())
 }
 
-fn consume_event(
+/// Credits the cranker's share of the fees generated during this crank to the reward target and returns it.
+///
+/// The reward is only paid out when the reward target is a user account of this market, otherwise the fees are
+/// left in full to the fee authority. The share is scaled up with the age of the pending events when the market
+/// enables it, but never exceeds the generated fees.
+fn pay_crank_reward(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    market_state: &mut DexState,
+    generated_fees: u64,
+    slot: u64,
+) -> Result<u64, ProgramError> {
+    let reward = ((generated_fees as u128)
+        * (market_state.crank_fee_share_bps as u128)
+        * (market_state.crank_reward_multiplier(slot) as u128)
+        / 10_000)
+        .min(generated_fees as u128) as u64;
+    if reward == 0 {
+        return Ok(0);
+    }
+    if accounts.reward_target.owner != program_id
+        || accounts.reward_target.data_len() < USER_ACCOUNT_HEADER_LEN
+    {
+        return Ok(0);
+    }
+    let mut reward_target_guard = accounts.reward_target.data.borrow_mut();
+    let reward_target = match UserAccount::from_buffer(&mut reward_target_guard) {
+        Ok(u) if &u.header.market == accounts.market.key => u,
+        _ => {
+            msg!("The reward target is not a user account of this market, no crank reward is paid");
+            return Ok(0);
+        }
+    };
+    reward_target.header.quote_token_free = reward_target
+        .header
+        .quote_token_free
+        .checked_add(reward)
+        .ok_or(DexError::NumericalOverflow)?;
+    market_state.accumulated_fees -= reward;
+
+    Ok(reward)
+}
+
+fn record_crank(
+    market: &AccountInfo,
+    cranker_identity: &AccountInfo,
+    cranker: &AccountInfo,
+    events_consumed: u64,
+    crank_reward: u64,
+) -> ProgramResult {
+    let mut cranker_identity_guard = cranker_identity.data.borrow_mut();
+    let cranker_identity = CrankerIdentity::from_buffer(&mut cranker_identity_guard)?;
+    if &cranker_identity.market != market.key || &cranker_identity.owner != cranker.key {
+        msg!("The cranker identity does not belong to this market and cranker");
+        return Err(DexError::InvalidCrankerIdentityAccount.into());
+    }
+    cranker_identity.events_consumed = cranker_identity
+        .events_consumed
+        .checked_add(events_consumed)
+        .ok_or(DexError::NumericalOverflow)?;
+    cranker_identity.rewards_earned = cranker_identity
+        .rewards_earned
+        .checked_add(crank_reward)
+        .ok_or(DexError::NumericalOverflow)?;
+    cranker_identity.last_crank_slot = Clock::get()?.slot;
+    Ok(())
+}
+
+/// Cancels the open orders of the expired sessions found among the user accounts and returns the number of cancelled
+/// orders. Sessions which are still active or whose user account isn't part of the crank are skipped.
+fn prune_expired_sessions<'b>(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo<'b>>,
+    bids: &AccountInfo<'b>,
+    asks: &AccountInfo<'b>,
+    market_state: &mut DexState,
+) -> Result<u64, ProgramError> {
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(bids, &orderbook.bids, DexError::InvalidOrderbookAccount)?;
+    check_account_key(asks, &orderbook.asks, DexError::InvalidOrderbookAccount)?;
+    drop(orderbook_guard);
+
+    let clock = Clock::get()?;
+    let mut pruned_orders = 0;
+    for session_info in accounts.user_accounts {
+        if session_info.owner != program_id || session_info.data_len() < SESSION_HEADER_LEN {
+            continue;
+        }
+        let mut session_guard = session_info.data.borrow_mut();
+        let mut session = match Session::from_buffer(&mut session_guard) {
+            Ok(s) if s.header.number_of_orders != 0 && s.is_expired(clock.unix_timestamp) => s,
+            _ => continue,
+        };
+        let user_account_info = match accounts
+            .user_accounts
+            .binary_search_by_key(&session.header.user_account, |k| *k.key)
+        {
+            Ok(i) => &accounts.user_accounts[i],
+            Err(_) => continue,
+        };
+        let mut user_account_guard = user_account_info.data.borrow_mut();
+        let mut user_account = UserAccount::from_buffer(&mut user_account_guard)?;
+        if &user_account.header.market != accounts.market.key {
+            continue;
+        }
+        pruned_orders += cancel_open_session_orders(
+            program_id,
+            market_state,
+            accounts.orderbook,
+            accounts.event_queue,
+            bids,
+            asks,
+            &mut user_account,
+            &mut session,
+            clock.slot,
+        )?;
+    }
+
+    Ok(pruned_orders)
+}
+
+/// Whether an account is a maker rewards account, which is only checked against the market once sampled
+fn is_maker_rewards_account(program_id: &Pubkey, account: &AccountInfo) -> bool {
+    account.owner == program_id
+        && account.data.borrow().get(0..8)
+            == Some(&(StateAccountTag::MakerRewards as u64).to_le_bytes()[..])
+}
+
+fn sample_maker_rewards_account(
+    market_state: &DexState,
+    accounts: &Accounts<AccountInfo>,
+    bids: &AccountInfo,
+    asks: &AccountInfo,
+    maker_rewards: &AccountInfo,
+) -> ProgramResult {
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(bids, &orderbook.bids, DexError::InvalidOrderbookAccount)?;
+    check_account_key(asks, &orderbook.asks, DexError::InvalidOrderbookAccount)?;
+    drop(orderbook_guard);
+
+    let mut maker_rewards_guard = maker_rewards.data.borrow_mut();
+    let mut maker_rewards = MakerRewards::from_buffer(&mut maker_rewards_guard)?;
+    if &maker_rewards.header.market != accounts.market.key {
+        msg!("The maker rewards account does not belong to this market");
+        return Err(DexError::InvalidMakerRewardsAccount.into());
+    }
+
+    if !maker_rewards.start_sample(Clock::get()?.slot) {
+        return Ok(());
+    }
+
+    let mut bids_guard = bids.data.borrow_mut();
+    let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+    let mut asks_guard = asks.data.borrow_mut();
+    let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+
+    for (slab, best) in [(&bids, bids.find_max()), (&asks, asks.find_min())] {
+        if let Some(handle) = best {
+            let leaf = &slab.leaf_nodes[handle as usize];
+            let callback_info = &slab.callback_infos[handle as usize];
+            let base_qty = market_state
+                .unscale_base_amount(leaf.base_quantity)
+                .ok_or(DexError::NumericalOverflow)?;
+            // A full rewards account should never prevent the crank from making progress
+            if let Err(e) = maker_rewards.record(&callback_info.user_account, base_qty) {
+                msg!("Failed to record maker rewards sample: {}", e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the maker fee rate recorded in a resting order of a user account
+fn recorded_maker_rate(user_account_info: &AccountInfo, order_id: u128) -> Option<u64> {
+    let mut user_account_data = user_account_info.data.borrow_mut();
+    let user_account = UserAccount::from_buffer(&mut user_account_data).unwrap();
+    let order_index = user_account.find_order_index(order_id).ok()?;
+    user_account.orders()[order_index].maker_rate()
+}
+
+/// Processes a single event, returning the next tranche to post when the displayed tranche of an iceberg order was
+/// fully filled.
+///
+/// When an escrow is provided, the balances of missing user accounts are credited to the escrow instead of failing.
+/// The maker side of fills and the orders leaving the book are logged along with their client order ids.
+pub(crate) fn consume_event(
+    market: &Pubkey,
     accounts: &[AccountInfo],
     event: EventRef<CallBackInfo>,
     market_state: &mut DexState,
-) -> Result<(), DexError> {
+    mut escrow: Option<&mut Escrow>,
+) -> Result<Option<IcebergTranche>, DexError> {
     match event {
         EventRef::Fill(FillEventRef {
             event,
@@ -168,7 +878,7 @@ fn consume_event(
                 tag: _,
                 taker_side,
                 mut quote_size,
-                maker_order_id: _,
+                maker_order_id,
                 mut base_size,
                 ..
             } = event;
@@ -178,22 +888,34 @@ fn consume_event(
             base_size = base_size
                 .checked_mul(market_state.base_currency_multiplier)
                 .unwrap();
-            let maker_account_info = &accounts[accounts
+            let maker_account_index = accounts
                 .binary_search_by_key(&maker_callback_info.user_account, |k| *k.key)
-                .map_err(|_| DexError::MissingUserAccount)?];
+                .ok();
+            if maker_account_index.is_none() && escrow.is_none() {
+                return Err(DexError::MissingUserAccount);
+            }
             let (taker_fee_tier, is_referred) = FeeTier::from_u8(taker_callback_info.fee_tier);
-            let mut maker_account_data = maker_account_info.data.borrow_mut();
-            let mut maker_account = UserAccount::from_buffer(&mut maker_account_data).unwrap();
             let (maker_fee_tier, _) = FeeTier::from_u8(maker_callback_info.fee_tier);
             let taker_fee = taker_fee_tier.taker_fee(quote_size);
-            let maker_rebate = maker_fee_tier.maker_rebate(quote_size);
             let royalties_fee =
                 market_state.royalties_bps.checked_mul(quote_size).unwrap() / 10_000;
             let referral_fee = if is_referred {
-                taker_fee_tier.referral_fee(quote_size)
+                taker_fee_tier.referral_fee(quote_size, market_state.referral_share_bps)
             } else {
                 0
             };
+            // The rate recorded when the maker order was placed prevails, so that fee schedule changes don't apply to
+            // resting orders
+            let maker_rate = maker_account_index
+                .and_then(|i| recorded_maker_rate(&accounts[i], *maker_order_id))
+                .unwrap_or_else(|| {
+                    maker_fee_tier.market_maker_rate(market_state.maker_rebate_bps)
+                });
+            // Rebates are paid out of the taker fee net of the referral fee, so that they never exceed the fees
+            // collected on the fill. The taker fee is zero for designated market makers.
+            let maker_rebate = fp32_mul(quote_size, maker_rate)
+                .unwrap()
+                .min(taker_fee - referral_fee);
             let total_fees = taker_fee
                 .checked_sub(maker_rebate)
                 .and_then(|n| n.checked_sub(referral_fee))
@@ -209,6 +931,46 @@ fn consume_event(
                 .checked_add(royalties_fee)
                 .unwrap();
 
+            let maker_account_info = match maker_account_index {
+                Some(i) => &accounts[i],
+                None => {
+                    let entry = escrow
+                        .as_mut()
+                        .unwrap()
+                        .get_or_insert(&maker_callback_info.user_account)?;
+                    match Side::from_u8(*taker_side).unwrap() {
+                        Side::Bid => {
+                            entry.quote_token_free += quote_size + maker_rebate;
+                            entry.base_token_unlocked += base_size;
+                        }
+                        Side::Ask => {
+                            entry.base_token_free += base_size;
+                            entry.quote_token_unlocked += quote_size;
+                            entry.quote_token_free += maker_rebate;
+                        }
+                    }
+                    market_state.quote_volume =
+                        market_state.quote_volume.checked_add(quote_size).unwrap();
+                    market_state.base_volume =
+                        market_state.base_volume.checked_add(base_size).unwrap();
+                    MakerFillLog {
+                        market: *market,
+                        maker: (!market_state.hides_fill_accounts())
+                            .then(|| maker_callback_info.user_account),
+                        order_id: *maker_order_id,
+                        client_order_id: None,
+                        base_qty: base_size,
+                        quote_qty: quote_size,
+                        maker_rebate,
+                    }
+                    .emit()
+                    .unwrap();
+                    return Ok(None);
+                }
+            };
+            let mut maker_account_data = maker_account_info.data.borrow_mut();
+            let mut maker_account = UserAccount::from_buffer(&mut maker_account_data).unwrap();
+
             match Side::from_u8(*taker_side).unwrap() {
                 Side::Bid => {
                     maker_account.header.quote_token_free = maker_account
@@ -234,7 +996,7 @@ fn consume_event(
                         .quote_token_locked
                         .checked_sub(quote_size)
                         .unwrap();
-                    maker_account
+                    maker_account.header.quote_token_free = maker_account
                         .header
                         .quote_token_free
                         .checked_add(maker_rebate)
@@ -255,8 +1017,27 @@ fn consume_event(
                 .checked_add(base_size)
                 .unwrap();
 
+            // Filled orders are exempt from the cancel fee
+            maker_account.record_fill(*maker_order_id);
+
             market_state.quote_volume = market_state.quote_volume.checked_add(quote_size).unwrap();
             market_state.base_volume = market_state.base_volume.checked_add(base_size).unwrap();
+
+            MakerFillLog {
+                market: *market,
+                maker: (!market_state.hides_fill_accounts())
+                    .then(|| maker_callback_info.user_account),
+                order_id: *maker_order_id,
+                client_order_id: maker_account
+                    .find_order_index(*maker_order_id)
+                    .ok()
+                    .map(|i| maker_account.orders()[i].client_id),
+                base_qty: base_size,
+                quote_qty: quote_size,
+                maker_rebate,
+            }
+            .emit()
+            .unwrap();
         }
         EventRef::Out(OutEventRef {
             event,
@@ -268,16 +1049,46 @@ fn consume_event(
                 mut base_size,
                 ..
             } = event;
-            let user_account_info = &accounts[accounts
+            let user_account_index = accounts
                 .binary_search_by_key(&callback_info.user_account, |k| *k.key)
-                .map_err(|_| DexError::MissingUserAccount)?];
-            let mut user_account_data = user_account_info.data.borrow_mut();
-            let mut user_account = UserAccount::from_buffer(&mut user_account_data).unwrap();
+                .ok();
 
             base_size = base_size
                 .checked_mul(market_state.base_currency_multiplier)
                 .unwrap();
 
+            let user_account_info = match (user_account_index, escrow) {
+                (Some(i), _) => &accounts[i],
+                (None, Some(escrow)) => {
+                    let entry = escrow.get_or_insert(&callback_info.user_account)?;
+                    match Side::from_u8(*side).unwrap() {
+                        Side::Ask => {
+                            entry.base_token_free += base_size;
+                            entry.base_token_unlocked += base_size;
+                        }
+                        Side::Bid => {
+                            let price = (order_id >> 64) as u64;
+                            let qty_to_transfer = fp32_mul(base_size, price).unwrap();
+                            entry.quote_token_free += qty_to_transfer;
+                            entry.quote_token_unlocked += qty_to_transfer;
+                        }
+                    }
+                    OutLog {
+                        market: *market,
+                        user_account: callback_info.user_account,
+                        order_id: *order_id,
+                        client_order_id: None,
+                        base_qty: base_size,
+                    }
+                    .emit()
+                    .unwrap();
+                    return Ok(None);
+                }
+                (None, None) => return Err(DexError::MissingUserAccount),
+            };
+            let mut user_account_data = user_account_info.data.borrow_mut();
+            let mut user_account = UserAccount::from_buffer(&mut user_account_data).unwrap();
+
             if base_size != 0 {
                 match Side::from_u8(*side).unwrap() {
                     Side::Ask => {
@@ -309,8 +1120,26 @@ fn consume_event(
                 }
             }
             let order_index = user_account.find_order_index(*order_id).unwrap();
+            OutLog {
+                market: *market,
+                user_account: callback_info.user_account,
+                order_id: *order_id,
+                client_order_id: Some(user_account.orders()[order_index].client_id),
+                base_qty: base_size,
+            }
+            .emit()
+            .unwrap();
+            let order = user_account.orders()[order_index];
             user_account.remove_order(order_index).unwrap();
+            if base_size == 0 && order.hidden_base_qty != 0 {
+                return Ok(Some(IcebergTranche {
+                    callback_info: *callback_info,
+                    side: Side::from_u8(*side).unwrap(),
+                    limit_price: (order_id >> 64) as u64,
+                    order,
+                }));
+            }
         }
     };
-    Ok(())
+    Ok(None)
 }
@@ -0,0 +1,140 @@
+//! Register a crank operator identity on a market.
+//!
+//! The consume_events instructions signed by the operator and given the identity account record the number of events
+//! consumed and the crank rewards earned, which lets operators prove their service level.
+use crate::{
+    error::DexError,
+    state::{AccountTag, CrankerIdentity, DexState, CRANKER_IDENTITY_LEN},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the cranker identity account of an operator on a market
+pub const CRANKER_IDENTITY_SEED: &[u8] = b"cranker_identity";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The cranker identity account
+    #[cons(writable)]
+    pub cranker_identity: &'a T,
+
+    /// The operator's wallet, which pays for the identity account creation
+    #[cons(writable, signer)]
+    pub cranker: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            cranker_identity: next_account_info(accounts_iter)?,
+            cranker: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.cranker).map_err(|e| {
+            msg!("The cranker should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    // Make sure that the market is valid
+    DexState::get(accounts.market)?;
+
+    let market_key_bytes = accounts.market.key.to_bytes();
+    let cranker_key_bytes = accounts.cranker.key.to_bytes();
+    let (cranker_identity_key, cranker_identity_nonce) = Pubkey::find_program_address(
+        &[&market_key_bytes, &cranker_key_bytes, CRANKER_IDENTITY_SEED],
+        program_id,
+    );
+
+    if &cranker_identity_key != accounts.cranker_identity.key {
+        msg!("Provided an invalid cranker identity account for the specified market and cranker");
+        return Err(DexError::InvalidCrankerIdentityAccount.into());
+    }
+
+    if !accounts.cranker_identity.data_is_empty() {
+        msg!("The cranker identity account already exists");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let lamports = Rent::get()?.minimum_balance(CRANKER_IDENTITY_LEN);
+
+    let allocate_account = create_account(
+        accounts.cranker.key,
+        accounts.cranker_identity.key,
+        lamports,
+        CRANKER_IDENTITY_LEN as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.cranker.clone(),
+            accounts.cranker_identity.clone(),
+        ],
+        &[&[
+            &market_key_bytes,
+            &cranker_key_bytes,
+            CRANKER_IDENTITY_SEED,
+            &[cranker_identity_nonce],
+        ]],
+    )?;
+
+    let mut cranker_identity_data = accounts.cranker_identity.data.borrow_mut();
+    let cranker_identity = CrankerIdentity::from_buffer_unchecked(&mut cranker_identity_data)?;
+
+    *cranker_identity = CrankerIdentity {
+        tag: AccountTag::CrankerIdentity as u64,
+        market: *accounts.market.key,
+        owner: *accounts.cranker.key,
+        events_consumed: 0,
+        rewards_earned: 0,
+        last_crank_slot: 0,
+    };
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Create the maker rewards sampling account of a market. This is an admin instruction
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::DexError,
+    state::{
+        AccountTag, DexState, MakerRewards, MakerRewardsEntry, MakerRewardsHeader,
+        MAKER_REWARDS_HEADER_LEN,
+    },
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+
+/// The seed used to derive the maker rewards account of a market
+pub const MAKER_REWARDS_SEED: &[u8] = b"maker_rewards";
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a create_maker_rewards instruction.
+*/
+pub struct Params {
+    /// The length in slots of a sampling epoch
+    pub epoch_length: u64,
+    /// The maximum number of user accounts which can be tracked during an epoch
+    pub max_entries: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The maker rewards account to initialize
+    #[cons(writable)]
+    pub maker_rewards: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+
+    /// The fee payer
+    #[cons(writable, signer)]
+    pub fee_payer: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            maker_rewards: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+            fee_payer: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(
+            a.maker_rewards,
+            &system_program::ID,
+            DexError::InvalidStateAccountOwner,
+        )?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        epoch_length,
+        max_entries,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+    drop(market_state);
+
+    if *epoch_length == 0 || *max_entries == 0 {
+        msg!("The epoch length and maximum number of entries should be nonzero!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let market_key_bytes = accounts.market.key.to_bytes();
+    let (maker_rewards_key, maker_rewards_nonce) =
+        Pubkey::find_program_address(&[&market_key_bytes, MAKER_REWARDS_SEED], program_id);
+
+    if &maker_rewards_key != accounts.maker_rewards.key {
+        msg!("Provided an invalid maker rewards account for the specified market");
+        return Err(DexError::InvalidMakerRewardsAccount.into());
+    }
+
+    let space = max_entries
+        .checked_mul(MakerRewardsEntry::LEN as u64)
+        .and_then(|n| n.checked_add(MAKER_REWARDS_HEADER_LEN as u64))
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let lamports = Rent::get()?.minimum_balance(space as usize);
+
+    let allocate_account = create_account(
+        accounts.fee_payer.key,
+        accounts.maker_rewards.key,
+        lamports,
+        space,
+        program_id,
+    );
+
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.fee_payer.clone(),
+            accounts.maker_rewards.clone(),
+        ],
+        &[&[
+            &market_key_bytes,
+            MAKER_REWARDS_SEED,
+            &[maker_rewards_nonce],
+        ]],
+    )?;
+
+    let mut maker_rewards_data = accounts.maker_rewards.data.borrow_mut();
+    let maker_rewards = MakerRewards::from_buffer_unchecked(&mut maker_rewards_data)?;
+
+    *(maker_rewards.header) = MakerRewardsHeader {
+        tag: AccountTag::MakerRewards as u64,
+        market: *accounts.market.key,
+        epoch_length: *epoch_length,
+        current_epoch: 0,
+        last_sample_slot: 0,
+        number_of_samples: 0,
+        number_of_entries: 0,
+    };
+
+    Ok(())
+}
@@ -1,8 +1,15 @@
 //! Creates a new DEX market
+//!
+//! When the deployment has a create market config with a nonzero listing fee, the fee is transferred to the treasury
+//! of the config before the market is initialized.
 use crate::{
     error::DexError,
-    state::{AccountTag, CallBackInfo, DexState, MarketFeeType},
-    utils::{check_account_owner, check_metadata_account, verify_metadata},
+    processor::set_create_market_config::CREATE_MARKET_CONFIG_SEED,
+    state::{
+        AccountTag, CallBackInfo, CreateMarketConfig, DexState, MarketFeeType, MarketStatus,
+        OrderFlowStats, DEFAULT_REFERRAL_SHARE_BPS,
+    },
+    utils::{check_account_key, check_account_owner, check_metadata_account, verify_metadata},
 };
 use asset_agnostic_orderbook::error::AoError;
 use bonfida_utils::checks::check_rent_exempt;
@@ -17,9 +24,11 @@ use solana_program::{
     clock::Clock,
     entrypoint::ProgramResult,
     msg,
+    program::invoke,
     program_error::{PrintProgramError, ProgramError},
     program_pack::Pack,
     pubkey::Pubkey,
+    system_instruction, system_program,
     sysvar::Sysvar,
 };
 
@@ -36,6 +45,14 @@ pub struct Params {
     pub tick_size: u64,
     pub base_currency_multiplier: u64,
     pub quote_currency_multiplier: u64,
+    /// The minimum distance in ticks between two orders of a same user on the same side (0 to disable)
+    pub min_order_tick_distance: u64,
+    /// The UTF-8 symbol of the base token, padded with zeroes
+    pub base_symbol: [u8; 16],
+    /// The UTF-8 symbol of the quote token, padded with zeroes
+    pub quote_symbol: [u8; 16],
+    /// The UTF-8 display name of the market, padded with zeroes
+    pub market_name: [u8; 32],
 }
 
 #[derive(InstructionsAccount)]
@@ -71,6 +88,24 @@ pub struct Accounts<'a, T> {
 
     /// The metaplex token metadata
     pub token_metadata: &'a T,
+
+    /// The create market config account of the deployment, which may not exist
+    pub create_market_config: &'a T,
+
+    /// The optional listing fee payer, required when the deployment charges a listing fee
+    #[cons(writable, signer)]
+    pub fee_payer: Option<&'a T>,
+
+    /// The optional token account paying a listing fee in tokens, the fee payer itself for a fee in lamports
+    #[cons(writable)]
+    pub fee_source: Option<&'a T>,
+
+    /// The optional treasury of the create market config
+    #[cons(writable)]
+    pub treasury: Option<&'a T>,
+
+    /// The optional program transferring the listing fee, the system program or the SPL token program
+    pub fee_program: Option<&'a T>,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
@@ -90,6 +125,11 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             asks: next_account_info(accounts_iter)?,
             bids: next_account_info(accounts_iter)?,
             token_metadata: next_account_info(accounts_iter)?,
+            create_market_config: next_account_info(accounts_iter)?,
+            fee_payer: next_account_info(accounts_iter).ok(),
+            fee_source: next_account_info(accounts_iter).ok(),
+            treasury: next_account_info(accounts_iter).ok(),
+            fee_program: next_account_info(accounts_iter).ok(),
         };
 
         check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
@@ -124,6 +164,10 @@ pub(crate) fn process(
         tick_size,
         base_currency_multiplier,
         quote_currency_multiplier,
+        min_order_tick_distance,
+        base_symbol,
+        quote_symbol,
+        market_name,
     } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
 
     if base_currency_multiplier == &0 || quote_currency_multiplier == &0 || tick_size == &0 {
@@ -131,6 +175,11 @@ pub(crate) fn process(
         return Err(ProgramError::InvalidArgument);
     }
 
+    if *min_order_tick_distance > u16::MAX as u64 {
+        msg!("The minimum order tick distance is too large!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
     let market_signer = Pubkey::create_program_address(
         &[&accounts.market.key.to_bytes(), &[*signer_nonce as u8]],
         program_id,
@@ -141,6 +190,8 @@ pub(crate) fn process(
     #[cfg(not(feature = "disable-mpl-checks"))]
     check_metadata_account(accounts.token_metadata, &base_mint)?;
 
+    collect_listing_fee(program_id, &accounts)?;
+
     let current_timestamp = Clock::get()?.unix_timestamp;
     if accounts.market.data.borrow()[0] != AccountTag::Uninitialized as u8 {
         // Checking the first byte is sufficient as there is a small number of AccountTags
@@ -178,11 +229,45 @@ pub(crate) fn process(
         accumulated_fees: 0,
         min_base_order_size: *min_base_order_size,
         fee_type: MarketFeeType::Default as u8,
-        _padding: [0; 6],
+        min_order_tick_distance: *min_order_tick_distance as u16,
+        status: MarketStatus::Active as u8,
+        hide_fill_accounts: 0,
+        _padding: [0; 2],
+        base_symbol: *base_symbol,
+        quote_symbol: *quote_symbol,
+        market_name: *market_name,
+        oracle: Pubkey::default(),
+        batch_slots: 0,
+        crank_fee_share_bps: 0,
+        volatility_window_slots: 0,
+        volatility_threshold_bps: 0,
+        max_volatility_multiplier: 0,
+        reference_price: 0,
+        reference_slot: 0,
+        last_price: 0,
+        migration_program: Pubkey::default(),
+        migration_market: Pubkey::default(),
+        migration_unlock_timestamp: 0,
+        order_flow: OrderFlowStats::zeroed(),
+        min_resting_slots: 0,
+        best_bid: 0,
+        best_ask: 0,
+        top_of_book_slot: 0,
         royalties_bps: royalties_bps as u64,
         accumulated_royalties: 0,
         base_currency_multiplier: *base_currency_multiplier,
         quote_currency_multiplier: *quote_currency_multiplier,
+        referral_share_bps: DEFAULT_REFERRAL_SHARE_BPS,
+        maker_rebate_bps: 0,
+        emergency_authority: Pubkey::default(),
+        emergency_halt_timestamp: 0,
+        cancel_fee: 0,
+        cancel_fee_slots: 0,
+        pending_admin: Pubkey::default(),
+        halt_flags: 0,
+        crank_reward_age_slots: 0,
+        max_crank_reward_multiplier: 0,
+        pending_events_slot: 0,
     };
 
     let invoke_params = asset_agnostic_orderbook::instruction::create_market::Params {
@@ -208,6 +293,96 @@ pub(crate) fn process(
     Ok(())
 }
 
+/// Transfers the listing fee of the deployment, if any, to its treasury
+fn collect_listing_fee(program_id: &Pubkey, accounts: &Accounts<AccountInfo>) -> ProgramResult {
+    let (config_key, _) = Pubkey::find_program_address(&[CREATE_MARKET_CONFIG_SEED], program_id);
+    check_account_key(
+        accounts.create_market_config,
+        &config_key,
+        DexError::InvalidCreateMarketConfigAccount,
+    )?;
+    if accounts.create_market_config.data_is_empty() {
+        return Ok(());
+    }
+    check_account_owner(
+        accounts.create_market_config,
+        program_id,
+        DexError::InvalidStateAccountOwner,
+    )?;
+    let config = {
+        let mut config_data = accounts.create_market_config.data.borrow_mut();
+        *CreateMarketConfig::from_buffer(&mut config_data)?
+    };
+    if config.listing_fee == 0 {
+        return Ok(());
+    }
+
+    let (fee_payer, fee_source, treasury, fee_program) = match (
+        accounts.fee_payer,
+        accounts.fee_source,
+        accounts.treasury,
+        accounts.fee_program,
+    ) {
+        (Some(a), Some(b), Some(c), Some(d)) => (a, b, c, d),
+        _ => {
+            msg!(
+                "The deployment charges a listing fee of {}",
+                config.listing_fee
+            );
+            return Err(DexError::InvalidListingFeeAccount.into());
+        }
+    };
+    if !fee_payer.is_signer {
+        msg!("The listing fee payer should be a signer for this transaction!");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_account_key(
+        treasury,
+        &config.treasury,
+        DexError::InvalidListingFeeAccount,
+    )?;
+
+    if config.is_lamports_fee() {
+        check_account_key(
+            fee_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        let instruction =
+            system_instruction::transfer(fee_payer.key, treasury.key, config.listing_fee);
+        invoke(
+            &instruction,
+            &[fee_program.clone(), fee_payer.clone(), treasury.clone()],
+        )?;
+    } else {
+        check_account_key(
+            fee_program,
+            &spl_token::ID,
+            DexError::InvalidSplTokenProgram,
+        )?;
+        let instruction = spl_token::instruction::transfer(
+            &spl_token::ID,
+            fee_source.key,
+            treasury.key,
+            fee_payer.key,
+            &[],
+            config.listing_fee,
+        )?;
+        invoke(
+            &instruction,
+            &[
+                fee_program.clone(),
+                fee_source.clone(),
+                treasury.clone(),
+                fee_payer.clone(),
+            ],
+        )?;
+    }
+    msg!("Paid a listing fee of {}", config.listing_fee);
+
+    Ok(())
+}
+
 fn check_vault_account_and_get_mint(
     account: &AccountInfo,
     market_signer: &Pubkey,
@@ -0,0 +1,195 @@
+//! Authorize an ephemeral session key to place orders on behalf of a user account until an expiry.
+//!
+//! Session orders are funded from the user account's free balances. Once the session expires, they can be cancelled by anyone
+//! with the cancel_session_orders instruction. Calling this instruction on an existing session replaces its key and expiry.
+use crate::{
+    error::DexError,
+    state::{AccountTag, Session, SessionHeader, UserAccount, SESSION_HEADER_LEN},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the session account of a user account
+pub const SESSION_SEED: &[u8] = b"session";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a create_session instruction.
+*/
+pub struct Params {
+    /// The ephemeral key authorized to place orders
+    pub session_key: Pubkey,
+    /// The unix timestamp at which the session expires
+    pub expiry: i64,
+    /// The maximum number of open session orders, used when the session account is created
+    pub capacity: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX user account
+    pub user: &'a T,
+
+    /// The user account owner wallet, which pays for the session account creation
+    #[cons(writable, signer)]
+    pub user_owner: &'a T,
+
+    /// The session account of the user account
+    #[cons(writable)]
+    pub session: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            session: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        session_key,
+        expiry,
+        capacity,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+    drop(user_account_data);
+
+    if *expiry <= Clock::get()?.unix_timestamp {
+        msg!("The session expiry should be in the future!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if accounts.session.data_is_empty() {
+        create_session(program_id, &accounts, *capacity)?;
+    }
+    check_account_owner(
+        accounts.session,
+        program_id,
+        DexError::InvalidSessionAccount,
+    )?;
+
+    let mut session_data = accounts.session.data.borrow_mut();
+    let session = Session::from_buffer(&mut session_data)?;
+    if &session.header.user_account != accounts.user.key {
+        msg!("The session account does not belong to this user account");
+        return Err(DexError::InvalidSessionAccount.into());
+    }
+
+    // The orders of a previous session are carried over and become cancellable after the new expiry
+    session.header.session_key = *session_key;
+    session.header.expiry = *expiry;
+
+    Ok(())
+}
+
+fn create_session(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    capacity: u64,
+) -> ProgramResult {
+    if capacity == 0 {
+        msg!("The session capacity should be nonzero!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let user_key_bytes = accounts.user.key.to_bytes();
+    let (session_key, session_nonce) =
+        Pubkey::find_program_address(&[&user_key_bytes, SESSION_SEED], program_id);
+
+    if &session_key != accounts.session.key {
+        msg!("Provided an invalid session account for the specified user account");
+        return Err(DexError::InvalidSessionAccount.into());
+    }
+
+    let space = capacity
+        .checked_mul(std::mem::size_of::<u128>() as u64)
+        .and_then(|n| n.checked_add(SESSION_HEADER_LEN as u64))
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let lamports = Rent::get()?.minimum_balance(space as usize);
+
+    let allocate_account = create_account(
+        accounts.user_owner.key,
+        accounts.session.key,
+        lamports,
+        space,
+        program_id,
+    );
+
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.user_owner.clone(),
+            accounts.session.clone(),
+        ],
+        &[&[&user_key_bytes, SESSION_SEED, &[session_nonce]]],
+    )?;
+
+    let mut session_data = accounts.session.data.borrow_mut();
+    let session = Session::from_buffer_unchecked(&mut session_data)?;
+
+    *(session.header) = SessionHeader {
+        tag: AccountTag::Session as u64,
+        user_account: *accounts.user.key,
+        session_key: Pubkey::default(),
+        expiry: 0,
+        number_of_orders: 0,
+    };
+
+    Ok(())
+}
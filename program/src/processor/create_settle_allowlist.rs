@@ -0,0 +1,186 @@
+//! Register the settle destination allowlist of a user account.
+//!
+//! Once registered, settle only transfers the user account's funds to the listed token accounts. The allowlist is
+//! immutable: it can neither be modified nor closed, so that a compromised owner key cannot redirect the funds.
+use crate::{
+    error::DexError,
+    state::{
+        AccountTag, DexState, SettleAllowlist, UserAccount, MAX_SETTLE_DESTINATIONS,
+        SETTLE_ALLOWLIST_LEN,
+    },
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the settle allowlist account of a user account
+pub const SETTLE_ALLOWLIST_SEED: &[u8] = b"settle_allowlist";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The DEX user account owner wallet, which pays for the allowlist account creation
+    #[cons(writable, signer)]
+    pub user_owner: &'a T,
+
+    /// The settle allowlist account
+    #[cons(writable)]
+    pub settle_allowlist: &'a T,
+
+    /// The allowed destination token accounts
+    pub destinations: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            settle_allowlist: next_account_info(accounts_iter)?,
+            destinations: accounts_iter.as_slice(),
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+        if a.destinations.is_empty() || a.destinations.len() > MAX_SETTLE_DESTINATIONS {
+            msg!(
+                "Between 1 and {} destination accounts must be provided",
+                MAX_SETTLE_DESTINATIONS
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let market_state = DexState::get(accounts.market)?;
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if &user_account.header.market != accounts.market.key {
+        msg!("The provided user account doesn't match the current market");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if user_account.header.has_settle_allowlist != 0 {
+        msg!("The user account already has a settle allowlist");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let mut destinations = [Pubkey::default(); MAX_SETTLE_DESTINATIONS];
+    for (destination, account) in destinations.iter_mut().zip(accounts.destinations) {
+        let token_account = spl_token::state::Account::unpack(&account.data.borrow())?;
+        if token_account.mint != market_state.base_mint
+            && token_account.mint != market_state.quote_mint
+        {
+            msg!(
+                "The destination {} is not a base or quote token account",
+                account.key
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        *destination = *account.key;
+    }
+
+    let user_key_bytes = accounts.user.key.to_bytes();
+    let (settle_allowlist_key, settle_allowlist_nonce) =
+        Pubkey::find_program_address(&[&user_key_bytes, SETTLE_ALLOWLIST_SEED], program_id);
+
+    if &settle_allowlist_key != accounts.settle_allowlist.key {
+        msg!("Provided an invalid settle allowlist account for the specified user account");
+        return Err(DexError::InvalidSettleAllowlistAccount.into());
+    }
+
+    if !accounts.settle_allowlist.data_is_empty() {
+        msg!("The settle allowlist account already exists");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let lamports = Rent::get()?.minimum_balance(SETTLE_ALLOWLIST_LEN);
+
+    let allocate_account = create_account(
+        accounts.user_owner.key,
+        accounts.settle_allowlist.key,
+        lamports,
+        SETTLE_ALLOWLIST_LEN as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.user_owner.clone(),
+            accounts.settle_allowlist.clone(),
+        ],
+        &[&[
+            &user_key_bytes,
+            SETTLE_ALLOWLIST_SEED,
+            &[settle_allowlist_nonce],
+        ]],
+    )?;
+
+    let mut settle_allowlist_data = accounts.settle_allowlist.data.borrow_mut();
+    let settle_allowlist = SettleAllowlist::from_buffer_unchecked(&mut settle_allowlist_data)?;
+
+    *settle_allowlist = SettleAllowlist {
+        tag: AccountTag::SettleAllowlist as u64,
+        user_account: *accounts.user.key,
+        number_of_destinations: accounts.destinations.len() as u64,
+        destinations,
+    };
+
+    user_account.header.has_settle_allowlist = 1;
+
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! Deposit base and quote tokens from the user wallet into the free balances of a user account
+//!
+//! Orders placed with `use_free_balances` are then funded by these balances without any token transfer, which saves
+//! a transfer per order for frequent traders. The deposited tokens can be taken back with a withdraw or settle
+//! instruction.
+use crate::{
+    error::DexError,
+    state::{DexState, UserAccount},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a deposit instruction.
+*/
+pub struct Params {
+    /// The amount of base tokens to deposit
+    pub base_qty: u64,
+    /// The amount of quote tokens to deposit
+    pub quote_qty: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The spl token program
+    pub spl_token_program: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The base token vault
+    #[cons(writable)]
+    pub base_vault: &'a T,
+
+    /// The quote token vault
+    #[cons(writable)]
+    pub quote_vault: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The DEX user account owner wallet
+    #[cons(signer)]
+    pub user_owner: &'a T,
+
+    /// The source base token account
+    #[cons(writable)]
+    pub source_base_account: &'a T,
+
+    /// The source quote token account
+    #[cons(writable)]
+    pub source_quote_account: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            source_base_account: next_account_info(accounts_iter)?,
+            source_quote_account: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.spl_token_program,
+            &spl_token::ID,
+            DexError::InvalidSplTokenProgram,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+
+    pub fn load_user_account(
+        &self,
+        user_account_data: &'a mut [u8],
+    ) -> Result<UserAccount<'a>, ProgramError> {
+        let user_account = UserAccount::from_buffer(user_account_data)?;
+        if &user_account.header.owner != self.user_owner.key {
+            msg!("Invalid user account owner provided!");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if &user_account.header.market != self.market.key {
+            msg!("The provided user account doesn't match the current market");
+            return Err(ProgramError::InvalidArgument);
+        };
+        Ok(user_account)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        base_qty,
+        quote_qty,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if *base_qty == 0 && *quote_qty == 0 {
+        msg!("The deposit should not be empty");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.base_vault,
+        &market_state.base_vault,
+        DexError::InvalidBaseVaultAccount,
+    )?;
+    check_account_key(
+        accounts.quote_vault,
+        &market_state.quote_vault,
+        DexError::InvalidQuoteVaultAccount,
+    )?;
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = accounts.load_user_account(&mut user_account_data)?;
+
+    user_account.header.base_token_free = user_account
+        .header
+        .base_token_free
+        .checked_add(*base_qty)
+        .ok_or(DexError::NumericalOverflow)?;
+    user_account.header.quote_token_free = user_account
+        .header
+        .quote_token_free
+        .checked_add(*quote_qty)
+        .ok_or(DexError::NumericalOverflow)?;
+
+    transfer_to_vault(
+        &accounts,
+        accounts.source_base_account,
+        accounts.base_vault,
+        *base_qty,
+    )?;
+    transfer_to_vault(
+        &accounts,
+        accounts.source_quote_account,
+        accounts.quote_vault,
+        *quote_qty,
+    )?;
+
+    Ok(())
+}
+
+fn transfer_to_vault<'a>(
+    accounts: &Accounts<'_, AccountInfo<'a>>,
+    source: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let transfer_instruction = spl_token::instruction::transfer(
+        &spl_token::ID,
+        source.key,
+        vault.key,
+        accounts.user_owner.key,
+        &[],
+        amount,
+    )?;
+
+    invoke(
+        &transfer_instruction,
+        &[
+            accounts.spl_token_program.clone(),
+            source.clone(),
+            vault.clone(),
+            accounts.user_owner.clone(),
+        ],
+    )
+}
@@ -0,0 +1,94 @@
+//! Halt a market without delay. This instruction is signed by the emergency authority of the market
+//!
+//! The emergency authority cannot resume trading. The admin can resume it with set_market_status once
+//! [`EMERGENCY_RESUME_DELAY`] seconds have elapsed since the market was halted. Halting a market which is already
+//! halted does not restart the delay.
+use crate::{
+    error::DexError,
+    state::{DexState, MarketStatus},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+/// The delay in seconds between an emergency halt and the earliest resumption of trading by the admin
+pub const EMERGENCY_RESUME_DELAY: i64 = 24 * 3600;
+
+#[derive(Clone, Copy, BorshDeserialize, BorshSerialize, BorshSize, Pod, Zeroable)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The emergency authority of the market
+    #[cons(signer)]
+    pub emergency_authority: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            emergency_authority: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.emergency_authority).map_err(|e| {
+            msg!("The emergency authority should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    if market_state.emergency_authority == Pubkey::default() {
+        msg!("The market has no emergency authority");
+        return Err(DexError::InvalidEmergencyAuthority.into());
+    }
+    check_account_key(
+        accounts.emergency_authority,
+        &market_state.emergency_authority,
+        DexError::InvalidEmergencyAuthority,
+    )?;
+    if market_state.is_migrating() {
+        msg!("The market is already halted for its migration");
+        return Err(DexError::MarketMigrating.into());
+    }
+
+    // A repeated halt keeps the timelock of the first one, so that the emergency authority cannot lock the admin
+    // out by halting the market again and again
+    if market_state.status != MarketStatus::Halted as u8 {
+        market_state.status = MarketStatus::Halted as u8;
+        market_state.emergency_halt_timestamp = Clock::get()?.unix_timestamp;
+    }
+    msg!(
+        "Market halted, it can be resumed by the admin after {}",
+        market_state.emergency_halt_timestamp + EMERGENCY_RESUME_DELAY
+    );
+
+    Ok(())
+}
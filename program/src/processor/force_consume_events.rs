@@ -0,0 +1,257 @@
+//! Consume the events of a halted market even when user accounts are missing. This is an admin instruction
+//!
+//! The balances of missing user accounts are credited to the market's escrow account, from which they can later be claimed.
+use crate::{
+    error::DexError,
+    processor::consume_events::consume_event,
+    state::{
+        AccountTag, CallBackInfo, DexState, Escrow, EscrowEntry, EscrowHeader, ESCROW_HEADER_LEN,
+    },
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use asset_agnostic_orderbook::{
+    error::AoError,
+    state::{event_queue::EventQueue, AccountTag as AobAccountTag},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::{PrintProgramError, ProgramError},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the escrow account of a market
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a force_consume_events instruction.
+*/
+pub struct Params {
+    /// The maximum number of events to consume
+    pub max_iterations: u64,
+    /// The maximum number of user accounts the escrow can hold, used when the escrow account is created
+    pub escrow_capacity: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The orderbook
+    #[cons(writable)]
+    pub orderbook: &'a T,
+
+    /// The AOB event queue
+    #[cons(writable)]
+    pub event_queue: &'a T,
+
+    /// The market admin account, which pays for the escrow account creation
+    #[cons(writable, signer)]
+    pub market_admin: &'a T,
+
+    /// The market escrow account
+    #[cons(writable)]
+    pub escrow: &'a T,
+
+    /// The available user accounts
+    #[cons(writable)]
+    pub user_accounts: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+            escrow: next_account_info(accounts_iter)?,
+            user_accounts: accounts_iter.as_slice(),
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        max_iterations,
+        escrow_capacity,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+
+    check_accounts(&market_state, &accounts)?;
+
+    if !market_state.is_halted() {
+        msg!("Events can only be force consumed on a halted market");
+        return Err(DexError::MarketNotHalted.into());
+    }
+
+    if accounts.escrow.data_is_empty() {
+        create_escrow(program_id, &accounts, *escrow_capacity)?;
+    }
+    check_account_owner(accounts.escrow, program_id, DexError::InvalidEscrowAccount)?;
+
+    let mut escrow_guard = accounts.escrow.data.borrow_mut();
+    let mut escrow = Escrow::from_buffer(&mut escrow_guard)?;
+    if &escrow.header.market != accounts.market.key {
+        msg!("The escrow account does not belong to this market");
+        return Err(DexError::InvalidEscrowAccount.into());
+    }
+
+    let mut event_queue_guard = accounts.event_queue.data.borrow_mut();
+    let event_queue =
+        EventQueue::<CallBackInfo>::from_buffer(&mut event_queue_guard, AobAccountTag::EventQueue)?;
+
+    let pending_events = event_queue.len();
+    let mut total_iterations = 0;
+
+    // The iceberg orders of a halted market end with their displayed tranche, their hidden remainder is left free
+    for event in event_queue.iter().take(*max_iterations as usize) {
+        consume_event(
+            accounts.market.key,
+            accounts.user_accounts,
+            event,
+            &mut market_state,
+            Some(&mut escrow),
+        )?;
+        total_iterations += 1;
+    }
+
+    if total_iterations == 0 {
+        msg!("The event queue is empty");
+        return Ok(());
+    }
+
+    drop(event_queue_guard);
+    market_state.record_consumed_events(pending_events - total_iterations);
+
+    let invoke_params = asset_agnostic_orderbook::instruction::consume_events::Params {
+        number_of_entries_to_consume: total_iterations,
+    };
+    let invoke_accounts = asset_agnostic_orderbook::instruction::consume_events::Accounts {
+        market: accounts.orderbook,
+        event_queue: accounts.event_queue,
+    };
+
+    if let Err(error) = asset_agnostic_orderbook::instruction::consume_events::process::<CallBackInfo>(
+        program_id,
+        invoke_accounts,
+        invoke_params,
+    ) {
+        error.print::<AoError>();
+        return Err(DexError::AOBError.into());
+    }
+
+    Ok(())
+}
+
+fn check_accounts(market_state: &DexState, accounts: &Accounts<AccountInfo>) -> ProgramResult {
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+    Ok(())
+}
+
+fn create_escrow(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    escrow_capacity: u64,
+) -> ProgramResult {
+    if escrow_capacity == 0 {
+        msg!("The escrow capacity should be nonzero!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let market_key_bytes = accounts.market.key.to_bytes();
+    let (escrow_key, escrow_nonce) =
+        Pubkey::find_program_address(&[&market_key_bytes, ESCROW_SEED], program_id);
+
+    if &escrow_key != accounts.escrow.key {
+        msg!("Provided an invalid escrow account for the specified market");
+        return Err(DexError::InvalidEscrowAccount.into());
+    }
+
+    let space = escrow_capacity
+        .checked_mul(EscrowEntry::LEN as u64)
+        .and_then(|n| n.checked_add(ESCROW_HEADER_LEN as u64))
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let lamports = Rent::get()?.minimum_balance(space as usize);
+
+    let allocate_account = create_account(
+        accounts.market_admin.key,
+        accounts.escrow.key,
+        lamports,
+        space,
+        program_id,
+    );
+
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.market_admin.clone(),
+            accounts.escrow.clone(),
+        ],
+        &[&[&market_key_bytes, ESCROW_SEED, &[escrow_nonce]]],
+    )?;
+
+    let mut escrow_data = accounts.escrow.data.borrow_mut();
+    let escrow = Escrow::from_buffer_unchecked(&mut escrow_data)?;
+
+    *(escrow.header) = EscrowHeader {
+        tag: AccountTag::Escrow as u64,
+        market: *accounts.market.key,
+        number_of_entries: 0,
+    };
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+//! Migrate a market to another program. This is an admin instruction
+//!
+//! The migration happens in two phases. The first call halts the market for good and records the migration target,
+//! which starts a timelock during which users can still cancel their orders and settle their balances. Once the
+//! timelock has elapsed, a second call with the same target hands the authority over the market vaults to the
+//! signer of the migration market. The user accounts and orderbook of the frozen market stay readable so that the
+//! migration program can import the remaining balances and resting orders.
+use crate::{
+    error::DexError,
+    state::{DexState, MarketStatus},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::instruction::{set_authority, AuthorityType};
+
+/// The delay in seconds between the freeze of a market and the handover of its vaults
+pub const MIGRATION_TIMELOCK: i64 = 7 * 24 * 3600;
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a freeze_and_export instruction.
+*/
+pub struct Params {
+    /// The program the market is migrated to
+    pub migration_program: Pubkey,
+    /// The market of the migration program which takes over the vaults
+    pub migration_market: Pubkey,
+    /// The signer nonce of the migration market, only used once the timelock has elapsed
+    pub migration_signer_nonce: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 7],
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The SPL token program ID
+    pub spl_token_program: &'a T,
+
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+
+    /// The market base vault account
+    #[cons(writable)]
+    pub base_vault: &'a T,
+
+    /// The market quote vault account
+    #[cons(writable)]
+    pub quote_vault: &'a T,
+
+    /// The market signer
+    pub market_signer: &'a T,
+
+    /// The signer of the migration market
+    pub migration_market_signer: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            market_signer: next_account_info(accounts_iter)?,
+            migration_market_signer: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.spl_token_program,
+            &spl_token::ID,
+            DexError::InvalidSplTokenProgram,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        migration_program,
+        migration_market,
+        migration_signer_nonce,
+        _padding,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_accounts(program_id, &market_state, &accounts)?;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    if !market_state.is_migrating() {
+        if migration_program == program_id || *migration_market == Pubkey::default() {
+            msg!("The market should be migrated to a market of another program");
+            return Err(DexError::InvalidMigrationAccount.into());
+        }
+        market_state.status = MarketStatus::Migrating as u8;
+        market_state.migration_program = *migration_program;
+        market_state.migration_market = *migration_market;
+        market_state.migration_unlock_timestamp = now + MIGRATION_TIMELOCK;
+        msg!(
+            "Market frozen, the vaults can be exported after {}",
+            market_state.migration_unlock_timestamp
+        );
+        return Ok(());
+    }
+
+    if &market_state.migration_program != migration_program
+        || &market_state.migration_market != migration_market
+    {
+        msg!("The migration target does not match the one recorded when the market was frozen");
+        return Err(DexError::InvalidMigrationAccount.into());
+    }
+    if now < market_state.migration_unlock_timestamp {
+        msg!(
+            "The vaults can only be exported after {}",
+            market_state.migration_unlock_timestamp
+        );
+        return Err(DexError::MigrationTimelockActive.into());
+    }
+
+    let migration_market_signer = Pubkey::create_program_address(
+        &[&migration_market.to_bytes(), &[*migration_signer_nonce]],
+        migration_program,
+    )?;
+    check_account_key(
+        accounts.migration_market_signer,
+        &migration_market_signer,
+        DexError::InvalidMigrationAccount,
+    )?;
+
+    for vault in [accounts.base_vault, accounts.quote_vault] {
+        let instruction = set_authority(
+            &spl_token::ID,
+            vault.key,
+            Some(&migration_market_signer),
+            AuthorityType::AccountOwner,
+            accounts.market_signer.key,
+            &[],
+        )?;
+        invoke_signed(
+            &instruction,
+            &[
+                accounts.spl_token_program.clone(),
+                vault.clone(),
+                accounts.market_signer.clone(),
+            ],
+            &[&[
+                &accounts.market.key.to_bytes(),
+                &[market_state.signer_nonce],
+            ]],
+        )?;
+    }
+    msg!("Market vaults exported to {}", migration_market);
+
+    Ok(())
+}
+
+fn check_accounts(
+    program_id: &Pubkey,
+    market_state: &DexState,
+    accounts: &Accounts<AccountInfo>,
+) -> ProgramResult {
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+    let market_signer = Pubkey::create_program_address(
+        &[
+            &accounts.market.key.to_bytes(),
+            &[market_state.signer_nonce],
+        ],
+        program_id,
+    )?;
+    check_account_key(
+        accounts.market_signer,
+        &market_signer,
+        DexError::InvalidMarketSignerAccount,
+    )?;
+    check_account_key(
+        accounts.base_vault,
+        &market_state.base_vault,
+        DexError::InvalidBaseVaultAccount,
+    )?;
+    check_account_key(
+        accounts.quote_vault,
+        &market_state.quote_vault,
+        DexError::InvalidQuoteVaultAccount,
+    )?;
+
+    Ok(())
+}
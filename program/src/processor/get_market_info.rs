@@ -0,0 +1,122 @@
+//! Write the key parameters of a market into the instruction's return data. This instruction is permissionless.
+//!
+//! The return data is a borsh encoded [`MarketInfo`], which lets programs read a market through CPI without depending on the
+//! layout of the market account.
+use crate::{
+    error::DexError,
+    state::{DexState, FeeTier},
+    utils::{check_account_key, check_account_owner},
+};
+use asset_agnostic_orderbook::state::{market_state::MarketState, AccountTag};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+/// The market parameters returned by a get_market_info instruction
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct MarketInfo {
+    /// The mint key of the base token
+    pub base_mint: Pubkey,
+    /// The mint key of the quote token
+    pub quote_mint: Pubkey,
+    /// The base token vault
+    pub base_vault: Pubkey,
+    /// The quote token vault
+    pub quote_vault: Pubkey,
+    /// The orderbook
+    pub orderbook: Pubkey,
+    /// The base lot size, in raw base amount
+    pub base_currency_multiplier: u64,
+    /// The quote lot size, in raw quote amount
+    pub quote_currency_multiplier: u64,
+    /// The orderbook's tick size (as a FP32)
+    pub tick_size: u64,
+    /// The minimum base order size
+    pub min_base_order_size: u64,
+    /// The market's fee type
+    pub fee_type: u8,
+    /// The taker fee rate of the base fee tier (as a FP32)
+    pub base_taker_rate: u64,
+    /// The royalties rate, in basis points
+    pub royalties_bps: u64,
+    /// The market's trading status
+    pub status: u8,
+    /// The length in slots of the market's batch auctions, zero for continuous matching
+    pub batch_slots: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The orderbook
+    pub orderbook: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+        };
+
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+
+    let tick_size = {
+        let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+        MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?.tick_size
+    };
+
+    let market_info = MarketInfo {
+        base_mint: market_state.base_mint,
+        quote_mint: market_state.quote_mint,
+        base_vault: market_state.base_vault,
+        quote_vault: market_state.quote_vault,
+        orderbook: market_state.orderbook,
+        base_currency_multiplier: market_state.base_currency_multiplier,
+        quote_currency_multiplier: market_state.quote_currency_multiplier,
+        tick_size,
+        min_base_order_size: market_state.min_base_order_size,
+        fee_type: market_state.fee_type,
+        base_taker_rate: FeeTier::from_srm_and_msrm_balances(&market_state, 0, 0).taker_rate(),
+        royalties_bps: market_state.royalties_bps,
+        status: market_state.status,
+        batch_slots: market_state.batch_slots,
+    };
+
+    set_return_data(&market_info.try_to_vec()?);
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Compute and log a hash of the orderbook's bids and asks at the current slot. This instruction is permissionless.
+use crate::{
+    error::DexError,
+    state::DexState,
+    utils::{check_account_key, check_account_owner},
+};
+use asset_agnostic_orderbook::state::{market_state::MarketState, AccountTag};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    msg,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The orderbook
+    pub orderbook: &'a T,
+
+    /// The AOB bids shared memory
+    pub bids: &'a T,
+
+    /// The AOB asks shared memory
+    pub asks: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+        };
+
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(
+        accounts.bids,
+        &orderbook.bids,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.asks,
+        &orderbook.asks,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    drop(orderbook_guard);
+
+    let slot = Clock::get()?.slot;
+    let bids_data = accounts.bids.data.borrow();
+    let asks_data = accounts.asks.data.borrow();
+    let hash = hashv(&[&slot.to_le_bytes(), &bids_data[..], &asks_data[..]]);
+
+    msg!("Orderbook hash at slot {}: {}", slot, hash);
+    set_return_data(&hash.to_bytes());
+
+    Ok(())
+}
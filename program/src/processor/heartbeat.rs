@@ -0,0 +1,94 @@
+//! Record that the owner of a user account, or one of its session keys, is still active
+//!
+//! This instruction only updates the `last_active_slot` of the user account, which lets integrators tell live
+//! trading bots apart from abandoned accounts without having to place orders.
+use crate::{
+    error::DexError,
+    state::{Session, UserAccount},
+    utils::{check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The owner of the user account, or the session key when a session account is given
+    #[cons(signer)]
+    pub user_owner: &'a T,
+
+    /// The optional session account authorizing the session key
+    pub session: Option<&'a T>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            session: next_account_info(accounts_iter).ok(),
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+        if let Some(session) = a.session {
+            check_account_owner(session, program_id, DexError::InvalidSessionAccount)?;
+        }
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let clock = Clock::get()?;
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    match accounts.session {
+        Some(session) => {
+            let mut session_data = session.data.borrow_mut();
+            Session::from_buffer(&mut session_data)?.check(
+                accounts.user.key,
+                accounts.user_owner.key,
+                clock.unix_timestamp,
+            )?;
+        }
+        None => {
+            if &user_account.header.owner != accounts.user_owner.key {
+                msg!("Invalid user account owner provided!");
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+    }
+
+    user_account.header.last_active_slot = clock.slot;
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! Take over the vaults of a market exported by a previous deployment of the program. This is an admin instruction
+//!
+//! The source market must have been frozen with this program and market as its migration target and its vaults
+//! must have been handed over to the signer of this market with the freeze_and_export instruction. The market adopts
+//! the source vaults along with the fees, royalties and volumes of the source market.
+use crate::{
+    error::DexError,
+    state::{DexState, DEX_STATE_LEN},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+
+    /// The exported market of the previous deployment
+    pub source_market: &'a T,
+
+    /// The base vault of the exported market
+    pub source_base_vault: &'a T,
+
+    /// The quote vault of the exported market
+    pub source_quote_vault: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+            source_market: next_account_info(accounts_iter)?,
+            source_base_vault: next_account_info(accounts_iter)?,
+            source_quote_vault: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        if a.source_market.key == a.market.key {
+            msg!("A market cannot import itself");
+            return Err(DexError::InvalidMigrationAccount.into());
+        }
+        check_account_owner(
+            a.source_base_vault,
+            &spl_token::ID,
+            DexError::InvalidBaseVaultAccount,
+        )?;
+        check_account_owner(
+            a.source_quote_vault,
+            &spl_token::ID,
+            DexError::InvalidQuoteVaultAccount,
+        )?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+
+    let source_data = accounts.source_market.data.borrow();
+    let source_state = source_data
+        .get(..DEX_STATE_LEN)
+        .and_then(|d| try_from_bytes::<DexState>(d).ok())
+        .filter(|s| s.is_migrating())
+        .ok_or_else(|| {
+            msg!("The source market is not being migrated");
+            ProgramError::from(DexError::MarketNotMigrating)
+        })?;
+
+    if &source_state.migration_program != program_id
+        || &source_state.migration_market != accounts.market.key
+    {
+        msg!("The source market is being migrated to another market");
+        return Err(DexError::InvalidMigrationAccount.into());
+    }
+    if Clock::get()?.unix_timestamp < source_state.migration_unlock_timestamp {
+        msg!(
+            "The source market can only be imported after {}",
+            source_state.migration_unlock_timestamp
+        );
+        return Err(DexError::MigrationTimelockActive.into());
+    }
+    if source_state.base_mint != market_state.base_mint
+        || source_state.quote_mint != market_state.quote_mint
+    {
+        msg!("The source market trades other mints");
+        return Err(DexError::InvalidMigrationAccount.into());
+    }
+    if market_state.base_vault == source_state.base_vault {
+        msg!("The source market has already been imported");
+        return Err(DexError::InvalidMigrationAccount.into());
+    }
+
+    check_account_key(
+        accounts.source_base_vault,
+        &source_state.base_vault,
+        DexError::InvalidBaseVaultAccount,
+    )?;
+    check_account_key(
+        accounts.source_quote_vault,
+        &source_state.quote_vault,
+        DexError::InvalidQuoteVaultAccount,
+    )?;
+
+    let market_signer = Pubkey::create_program_address(
+        &[
+            &accounts.market.key.to_bytes(),
+            &[market_state.signer_nonce],
+        ],
+        program_id,
+    )?;
+    for vault in [accounts.source_base_vault, accounts.source_quote_vault] {
+        let vault_data = spl_token::state::Account::unpack(&vault.data.borrow())?;
+        if vault_data.owner != market_signer {
+            msg!("The source vaults have not been handed over to this market yet");
+            return Err(DexError::InvalidMigrationAccount.into());
+        }
+    }
+
+    market_state.base_vault = source_state.base_vault;
+    market_state.quote_vault = source_state.quote_vault;
+    market_state.accumulated_fees = market_state
+        .accumulated_fees
+        .checked_add(source_state.accumulated_fees)
+        .ok_or(DexError::NumericalOverflow)?;
+    market_state.accumulated_royalties = market_state
+        .accumulated_royalties
+        .checked_add(source_state.accumulated_royalties)
+        .ok_or(DexError::NumericalOverflow)?;
+    market_state.base_volume = market_state
+        .base_volume
+        .checked_add(source_state.base_volume)
+        .ok_or(DexError::NumericalOverflow)?;
+    market_state.quote_volume = market_state
+        .quote_volume
+        .checked_add(source_state.quote_volume)
+        .ok_or(DexError::NumericalOverflow)?;
+
+    msg!(
+        "Imported the vaults of market {}",
+        accounts.source_market.key
+    );
+
+    Ok(())
+}
@@ -1,4 +1,8 @@
 //! Initialize a new user account
+//!
+//! The user account is allocated by the program at its program derived address, the rent exemption being paid by the
+//! fee payer. Its size, and therefore its rent, is fixed by the maximum number of orders it can hold, see
+//! [`required_space`] and [`required_lamports`].
 use bonfida_utils::BorshSize;
 use bonfida_utils::InstructionsAccount;
 use borsh::BorshDeserialize;
@@ -19,6 +23,7 @@ use solana_program::{
 
 use crate::{
     error::DexError,
+    instruction_auto::initialize_account,
     state::{Order, UserAccount, UserAccountHeader, USER_ACCOUNT_HEADER_LEN},
     utils::{check_account_key, check_account_owner, check_signer},
 };
@@ -33,6 +38,10 @@ pub struct Params {
     pub market: Pubkey,
     /// The maximum number of orders the user account may hold
     pub max_orders: u64,
+    /// The index of the user account, which allows an owner to hold several user accounts on the same market.
+    ///
+    /// The user account of index 0 is derived from the market and owner keys only.
+    pub account_index: u64,
 }
 
 #[derive(InstructionsAccount)]
@@ -84,6 +93,61 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
     }
 }
 
+/// Returns the size in bytes of a user account which can hold `max_orders` orders
+pub fn required_space(max_orders: u64) -> Result<u64, ProgramError> {
+    if max_orders == 0 {
+        msg!("The minimum number of orders an account should be able to hold is 1");
+        return Err(ProgramError::InvalidArgument);
+    }
+    max_orders
+        .checked_mul(Order::LEN as u64)
+        .and_then(|n| n.checked_add(USER_ACCOUNT_HEADER_LEN as u64))
+        .ok_or_else(|| DexError::NumericalOverflow.into())
+}
+
+/// Returns the lamports debited from the fee payer to make a user account which can hold `max_orders` orders rent
+/// exempt
+pub fn required_lamports(rent: &Rent, max_orders: u64) -> Result<u64, ProgramError> {
+    Ok(rent.minimum_balance(required_space(max_orders)? as usize))
+}
+
+/// Builds the instructions creating the user account of index `account_index` of an owner on a market, which can hold
+/// `max_orders` orders.
+///
+/// The payer is debited [`required_lamports`] and should sign along with the owner.
+pub fn create_user_account_ixs(
+    program_id: Pubkey,
+    owner: &Pubkey,
+    market: &Pubkey,
+    max_orders: u64,
+    account_index: u64,
+    payer: &Pubkey,
+) -> Result<Vec<solana_program::instruction::Instruction>, ProgramError> {
+    required_space(max_orders)?;
+    let market_key_bytes = market.to_bytes();
+    let owner_key_bytes = owner.to_bytes();
+    let account_index_bytes = account_index.to_le_bytes();
+    let mut seeds: Vec<&[u8]> = vec![&market_key_bytes, &owner_key_bytes];
+    if account_index != 0 {
+        seeds.push(&account_index_bytes);
+    }
+    let (user_account, _) = Pubkey::find_program_address(&seeds, &program_id);
+    Ok(vec![initialize_account(
+        program_id,
+        Accounts {
+            system_program: &system_program::ID,
+            user: &user_account,
+            user_owner: owner,
+            fee_payer: payer,
+        },
+        Params {
+            market: *market,
+            max_orders,
+            account_index,
+        },
+    )])
+}
+
 pub(crate) fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -91,59 +155,71 @@ pub(crate) fn process(
 ) -> ProgramResult {
     let accounts = Accounts::parse(program_id, accounts)?;
 
-    let Params { market, max_orders } =
-        try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let Params {
+        market,
+        max_orders,
+        account_index,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
 
-    let market_key_bytes = market.to_bytes();
-    let (user_account_key, user_account_nonce) = Pubkey::find_program_address(
-        &[&market_key_bytes, &accounts.user_owner.key.to_bytes()],
+    create_user_account(
         program_id,
-    );
+        accounts.system_program,
+        accounts.user,
+        accounts.user_owner.key,
+        accounts.fee_payer,
+        market,
+        *max_orders,
+        *account_index,
+    )?;
 
-    if &user_account_key != accounts.user.key {
-        msg!("Provided an invalid user account for the specified market and owner");
-        return Err(ProgramError::InvalidArgument);
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let u = UserAccount::from_buffer_unchecked(&mut user_account_data)?;
+
+    *(u.header) = UserAccountHeader::new(market, accounts.user_owner.key);
+
+    Ok(())
+}
+
+/// Allocates the user account of an owner on a market at its program derived address
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_user_account<'a>(
+    program_id: &Pubkey,
+    system_program: &AccountInfo<'a>,
+    user: &AccountInfo<'a>,
+    user_owner: &Pubkey,
+    fee_payer: &AccountInfo<'a>,
+    market: &Pubkey,
+    max_orders: u64,
+    account_index: u64,
+) -> ProgramResult {
+    let market_key_bytes = market.to_bytes();
+    let owner_key_bytes = user_owner.to_bytes();
+    let account_index_bytes = account_index.to_le_bytes();
+    let mut seeds: Vec<&[u8]> = vec![&market_key_bytes, &owner_key_bytes];
+    if account_index != 0 {
+        seeds.push(&account_index_bytes);
     }
+    let (user_account_key, user_account_nonce) = Pubkey::find_program_address(&seeds, program_id);
 
-    if max_orders == &0 {
-        msg!("The minimum number of orders an account should be able to hold is 1");
+    if &user_account_key != user.key {
+        msg!("Provided an invalid user account for the specified market and owner");
         return Err(ProgramError::InvalidArgument);
     }
 
-    // (USER_ACCOUNT_HEADER_LEN as u64) + max_orders * (Order::LEN as u64);
-    let space = max_orders
-        .checked_mul(Order::LEN as u64)
-        .and_then(|n| n.checked_add(USER_ACCOUNT_HEADER_LEN as u64))
-        .ok_or(DexError::NumericalOverflow)?;
+    let space = required_space(max_orders)?;
+    let lamports = required_lamports(&Rent::get()?, max_orders)?;
 
-    let lamports = Rent::get()?.minimum_balance(space as usize);
+    let nonce = [user_account_nonce];
+    let mut signer_seeds = seeds.clone();
+    signer_seeds.push(&nonce);
 
-    let allocate_account = create_account(
-        accounts.fee_payer.key,
-        accounts.user.key,
-        lamports,
-        space,
-        program_id,
-    );
+    let allocate_account = create_account(fee_payer.key, user.key, lamports, space, program_id);
 
     invoke_signed(
         &allocate_account,
-        &[
-            accounts.system_program.clone(),
-            accounts.fee_payer.clone(),
-            accounts.user.clone(),
-        ],
-        &[&[
-            &market_key_bytes,
-            &accounts.user_owner.key.to_bytes(),
-            &[user_account_nonce],
-        ]],
+        &[system_program.clone(), fee_payer.clone(), user.clone()],
+        &[&signer_seeds],
     )?;
 
-    let mut user_account_data = accounts.user.data.borrow_mut();
-    let u = UserAccount::from_buffer_unchecked(&mut user_account_data)?;
-
-    *(u.header) = UserAccountHeader::new(market, accounts.user_owner.key);
-
     Ok(())
 }
@@ -0,0 +1,285 @@
+//! Cross the pending batch of a market in batch auction mode at a uniform clearing price. This instruction is permissionless
+//!
+//! The clearing price maximizes the matched volume, ties being broken by the smallest imbalance between demand and supply
+//! and then by the midpoint of the remaining candidate prices. Orders are filled in price then time priority and
+//! both sides pay taker fees. The unfilled remainders are unlocked in their user accounts and the batch is emptied.
+use std::cmp::Reverse;
+
+use crate::{
+    error::DexError,
+    state::{BatchAuction, BatchOrder, DexState, FeeTier, FillLog, UserAccount},
+    utils::check_account_owner,
+};
+use asset_agnostic_orderbook::state::Side;
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market batch auction account
+    #[cons(writable)]
+    pub batch_auction: &'a T,
+
+    /// The user accounts of the pending orders, sorted by key
+    #[cons(writable)]
+    pub user_accounts: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            batch_auction: next_account_info(accounts_iter)?,
+            user_accounts: accounts_iter.as_slice(),
+        };
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(
+            a.batch_auction,
+            program_id,
+            DexError::InvalidBatchAuctionAccount,
+        )?;
+        for user_account in a.user_accounts {
+            check_account_owner(user_account, program_id, DexError::InvalidStateAccountOwner)?;
+        }
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    market_state.check_active()?;
+    if !market_state.is_batch_auction() {
+        msg!("The market does not match orders in batch auctions");
+        return Err(DexError::NotBatchAuctionMode.into());
+    }
+
+    let mut batch_guard = accounts.batch_auction.data.borrow_mut();
+    let mut batch = BatchAuction::from_buffer(&mut batch_guard)?;
+    if &batch.header.market != accounts.market.key {
+        msg!("The batch auction account does not belong to this market");
+        return Err(DexError::InvalidBatchAuctionAccount.into());
+    }
+    if batch.header.number_of_orders == 0 {
+        msg!("The batch auction has no pending orders");
+        return Err(DexError::NoOp.into());
+    }
+
+    let current_slot = Clock::get()?.slot;
+    if current_slot
+        < batch
+            .header
+            .start_slot
+            .saturating_add(market_state.batch_slots)
+    {
+        msg!(
+            "The batch auction can be matched from slot {}",
+            batch
+                .header
+                .start_slot
+                .saturating_add(market_state.batch_slots)
+        );
+        return Err(DexError::BatchAuctionNotReady.into());
+    }
+
+    let orders = batch.orders();
+    let (clearing_price, volume) = find_clearing_price(orders);
+    let mut fills = vec![0; orders.len()];
+    allocate_fills(&mut fills, orders, true, clearing_price, volume);
+    allocate_fills(&mut fills, orders, false, clearing_price, volume);
+
+    let mut total_quote_qty = 0u64;
+    for (order, &filled_base_qty) in orders.iter().zip(fills.iter()) {
+        let user_account_info = accounts
+            .user_accounts
+            .binary_search_by_key(&order.user_account, |a| *a.key)
+            .map(|i| &accounts.user_accounts[i])
+            .map_err(|_| {
+                msg!("Missing user account {}", order.user_account);
+                DexError::MissingUserAccount
+            })?;
+        let mut user_account_data = user_account_info.data.borrow_mut();
+        let mut user_account = UserAccount::from_buffer(&mut user_account_data)?;
+        let header = &mut user_account.header;
+
+        let (fee_tier, _) = FeeTier::from_u8(order.fee_tier);
+        let is_bid = order.side == Side::Bid as u8;
+        // Bids pay a rounded up quote amount while asks receive a rounded down one, which keeps the vault solvent
+        let quote_qty = if is_bid {
+            market_state.get_quote_from_base_ceil(filled_base_qty, clearing_price)
+        } else {
+            market_state.get_quote_from_base(filled_base_qty, clearing_price)
+        }
+        .ok_or(DexError::NumericalOverflow)?;
+        let taker_fee = fee_tier.taker_fee(quote_qty);
+        let royalties_fees = quote_qty
+            .checked_mul(market_state.royalties_bps)
+            .ok_or(DexError::NumericalOverflow)?
+            / 10_000;
+
+        if is_bid {
+            let paid_quote_qty = quote_qty
+                .checked_add(taker_fee)
+                .and_then(|n| n.checked_add(royalties_fees))
+                .ok_or(DexError::NumericalOverflow)?;
+            header.quote_token_locked = header
+                .quote_token_locked
+                .checked_sub(order.locked_quote_qty)
+                .ok_or(DexError::NumericalOverflow)?;
+            header.quote_token_free = order
+                .locked_quote_qty
+                .checked_sub(paid_quote_qty)
+                .and_then(|n| n.checked_add(header.quote_token_free))
+                .ok_or(DexError::NumericalOverflow)?;
+            header.base_token_free = header
+                .base_token_free
+                .checked_add(filled_base_qty)
+                .ok_or(DexError::NumericalOverflow)?;
+            total_quote_qty += quote_qty;
+        } else {
+            header.base_token_locked = header
+                .base_token_locked
+                .checked_sub(order.base_qty)
+                .ok_or(DexError::NumericalOverflow)?;
+            header.base_token_free = order
+                .base_qty
+                .checked_sub(filled_base_qty)
+                .and_then(|n| n.checked_add(header.base_token_free))
+                .ok_or(DexError::NumericalOverflow)?;
+            header.quote_token_free = quote_qty
+                .checked_sub(taker_fee + royalties_fees)
+                .and_then(|n| n.checked_add(header.quote_token_free))
+                .ok_or(DexError::NumericalOverflow)?;
+        }
+        header.accumulated_taker_base_volume += filled_base_qty;
+        header.accumulated_taker_quote_volume += quote_qty;
+
+        market_state.accumulated_fees = market_state
+            .accumulated_fees
+            .checked_add(taker_fee)
+            .ok_or(DexError::NumericalOverflow)?;
+        market_state.accumulated_royalties = market_state
+            .accumulated_royalties
+            .checked_add(royalties_fees)
+            .ok_or(DexError::NumericalOverflow)?;
+    }
+
+    market_state
+        .order_flow
+        .record_new_orders(current_slot, orders.len() as u64);
+    market_state.order_flow.record_fills(
+        current_slot,
+        fills.iter().filter(|&&q| q != 0).count() as u64,
+    );
+
+    if volume != 0 {
+        msg!(
+            "Matched {} base at the clearing price {}",
+            volume,
+            clearing_price
+        );
+        FillLog {
+            market: *accounts.market.key,
+            side: Side::Bid as u8,
+            base_qty: volume,
+            quote_qty: total_quote_qty,
+            slot: current_slot,
+            taker: None,
+            oracle_price: None,
+            client_order_id: None,
+        }
+        .emit()?;
+    }
+
+    batch.clear();
+
+    Ok(())
+}
+
+/// Returns the uniform clearing price of a batch along with the base volume it matches
+fn find_clearing_price(orders: &[BatchOrder]) -> (u64, u64) {
+    let mut best = (0, Reverse(u64::MAX));
+    let (mut lowest_price, mut highest_price) = (0, 0);
+    for candidate in orders.iter().map(|o| o.limit_price) {
+        let (demand, supply) = orders.iter().fold((0u64, 0u64), |(demand, supply), o| {
+            if o.side == Side::Bid as u8 && o.limit_price >= candidate {
+                (demand.saturating_add(o.base_qty), supply)
+            } else if o.side == Side::Ask as u8 && o.limit_price <= candidate {
+                (demand, supply.saturating_add(o.base_qty))
+            } else {
+                (demand, supply)
+            }
+        });
+        let volume = demand.min(supply);
+        let score = (volume, Reverse(demand.max(supply) - volume));
+        if score > best {
+            best = score;
+            lowest_price = candidate;
+            highest_price = candidate;
+        } else if score == best {
+            lowest_price = lowest_price.min(candidate);
+            highest_price = highest_price.max(candidate);
+        }
+    }
+    (lowest_price + (highest_price - lowest_price) / 2, best.0)
+}
+
+/// Distributes the matched volume among the orders of one side which cross the clearing price
+fn allocate_fills(
+    fills: &mut [u64],
+    orders: &[BatchOrder],
+    is_bid: bool,
+    clearing_price: u64,
+    mut volume: u64,
+) {
+    let side = (if is_bid { Side::Bid } else { Side::Ask }) as u8;
+    let mut indices = (0..orders.len())
+        .filter(|&i| orders[i].side == side)
+        .filter(|&i| {
+            if is_bid {
+                orders[i].limit_price >= clearing_price
+            } else {
+                orders[i].limit_price <= clearing_price
+            }
+        })
+        .collect::<Vec<_>>();
+    // The sort is stable, which preserves the time priority between orders at the same price
+    if is_bid {
+        indices.sort_by_key(|&i| Reverse(orders[i].limit_price));
+    } else {
+        indices.sort_by_key(|&i| orders[i].limit_price);
+    }
+    for i in indices {
+        if volume == 0 {
+            break;
+        }
+        fills[i] = volume.min(orders[i].base_qty);
+        volume -= fills[i];
+    }
+}
@@ -0,0 +1,155 @@
+//! Migrate a market or user account created by the first release of the program to the current layout
+//!
+//! The account is reallocated to the current size, the payer topping up its rent, and the fields added since the
+//! first release are initialized as for a new account. The migration is permissionless as it doesn't change any
+//! balance or setting of the account. The data of an account can grow by at most 10 KiB in an instruction, which
+//! bounds the number of order slots of the user accounts which can be migrated.
+use crate::{
+    error::DexError,
+    state::{
+        AccountTag, DexStateV1, Order, OrderV1, UserAccountHeaderV1, DEX_STATE_V1_LEN,
+        ORDER_V1_LEN, USER_ACCOUNT_HEADER_LEN, USER_ACCOUNT_HEADER_V1_LEN,
+    },
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{bytes_of, pod_read_unaligned, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use std::convert::TryInto;
+
+#[derive(Clone, Copy, BorshDeserialize, BorshSerialize, BorshSize, Pod, Zeroable)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX market or user account to migrate
+    #[cons(writable)]
+    pub account: &'a T,
+
+    /// The account paying for the rent of the added space
+    #[cons(writable, signer)]
+    pub payer: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            account: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.payer).map_err(|e| {
+            msg!("The payer should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.account, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+/// Returns the current market state data of a market of the first release
+fn migrate_market(data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+    let state: DexStateV1 = pod_read_unaligned(
+        data.get(0..DEX_STATE_V1_LEN)
+            .ok_or(ProgramError::InvalidAccountData)?,
+    );
+    Ok(bytes_of(&state.migrate()).to_vec())
+}
+
+/// Returns the current user account data of a user account of the first release, which keeps its capacity
+fn migrate_user_account(data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+    let header: UserAccountHeaderV1 = pod_read_unaligned(
+        data.get(0..USER_ACCOUNT_HEADER_V1_LEN)
+            .ok_or(ProgramError::InvalidAccountData)?,
+    );
+    let max_orders = (data.len() - USER_ACCOUNT_HEADER_V1_LEN) / ORDER_V1_LEN;
+    if header.number_of_orders as usize > max_orders {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut migrated = vec![0; USER_ACCOUNT_HEADER_LEN + max_orders * Order::LEN];
+    let (migrated_header, migrated_orders) = migrated.split_at_mut(USER_ACCOUNT_HEADER_LEN);
+    migrated_header.copy_from_slice(bytes_of(&header.migrate()));
+    let orders = data[USER_ACCOUNT_HEADER_V1_LEN..]
+        .chunks_exact(ORDER_V1_LEN)
+        .take(header.number_of_orders as usize)
+        .map(pod_read_unaligned::<OrderV1>);
+    for (order, slot) in orders.zip(migrated_orders.chunks_exact_mut(Order::LEN)) {
+        slot.copy_from_slice(bytes_of(&order.migrate()));
+    }
+    Ok(migrated)
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let migrated = {
+        let data = accounts.account.data.borrow();
+        let tag = data
+            .get(0..8)
+            .map(|t| u64::from_le_bytes(t.try_into().unwrap()));
+        if tag == Some(AccountTag::DexStateV1 as u64) {
+            migrate_market(&data)?
+        } else if tag == Some(AccountTag::UserAccountV1 as u64) {
+            migrate_user_account(&data)?
+        } else {
+            msg!("The account doesn't need to be migrated");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    };
+
+    if migrated.len().saturating_sub(accounts.account.data_len()) > MAX_PERMITTED_DATA_INCREASE {
+        msg!("The account is too large to be migrated");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let lamports = Rent::get()?
+        .minimum_balance(migrated.len())
+        .saturating_sub(accounts.account.lamports());
+    if lamports != 0 {
+        invoke(
+            &system_instruction::transfer(accounts.payer.key, accounts.account.key, lamports),
+            &[
+                accounts.system_program.clone(),
+                accounts.payer.clone(),
+                accounts.account.clone(),
+            ],
+        )?;
+    }
+
+    accounts.account.realloc(migrated.len(), false)?;
+    accounts
+        .account
+        .data
+        .borrow_mut()
+        .copy_from_slice(&migrated);
+
+    Ok(())
+}
@@ -0,0 +1,265 @@
+//! Add an order to the pending batch of a market in batch auction mode.
+//!
+//! The funds required to fill the order at its limit price, taker fees included, are locked until the batch is matched.
+use crate::{
+    error::DexError,
+    state::{BatchAuction, BatchOrder, DexState, FeeTier, UserAccount},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use asset_agnostic_orderbook::state::Side;
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use num_traits::FromPrimitive;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a new_batch_order instruction.
+*/
+pub struct Params {
+    /// The order's limit price (as a FP32)
+    pub limit_price: u64,
+    /// The quantity of base token to buy or sell
+    pub base_qty: u64,
+    /// The order's side (Bid or Ask)
+    pub side: u8,
+    /// Whether or not the optional discount token account was given
+    pub has_discount_token_account: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 6],
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The SPL token program
+    pub spl_token_program: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The market batch auction account
+    #[cons(writable)]
+    pub batch_auction: &'a T,
+
+    /// The base token vault
+    #[cons(writable)]
+    pub base_vault: &'a T,
+
+    /// The quote token vault
+    #[cons(writable)]
+    pub quote_vault: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The user source token account
+    #[cons(writable)]
+    pub user_token_account: &'a T,
+
+    /// The user wallet
+    #[cons(signer)]
+    pub user_owner: &'a T,
+
+    /// The optional SRM or MSRM discount token account (must be owned by the user wallet)
+    pub discount_token_account: Option<&'a T>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+        has_discount_token_account: bool,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            batch_auction: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_token_account: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            discount_token_account: if has_discount_token_account {
+                next_account_info(accounts_iter).ok()
+            } else {
+                None
+            },
+        };
+
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.spl_token_program,
+            &spl_token::ID,
+            DexError::InvalidSplTokenProgram,
+        )?;
+        if let Some(discount_account) = a.discount_token_account {
+            check_account_owner(
+                discount_account,
+                &spl_token::ID,
+                DexError::InvalidSplTokenProgram,
+            )?
+        }
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(
+            a.batch_auction,
+            program_id,
+            DexError::InvalidBatchAuctionAccount,
+        )?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let Params {
+        limit_price,
+        base_qty,
+        side,
+        has_discount_token_account,
+        ..
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let accounts = Accounts::parse(program_id, accounts, *has_discount_token_account != 0)?;
+
+    let market_state = DexState::get(accounts.market)?;
+    market_state.check_active()?;
+    market_state.check_side_active(*side)?;
+    if !market_state.is_batch_auction() {
+        msg!("The market does not match orders in batch auctions");
+        return Err(DexError::NotBatchAuctionMode.into());
+    }
+    check_account_key(
+        accounts.base_vault,
+        &market_state.base_vault,
+        DexError::InvalidBaseVaultAccount,
+    )?;
+    check_account_key(
+        accounts.quote_vault,
+        &market_state.quote_vault,
+        DexError::InvalidQuoteVaultAccount,
+    )?;
+
+    if *base_qty == 0 || base_qty < &market_state.min_base_order_size {
+        msg!("The base order size is too small.");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *limit_price == 0 {
+        msg!("The limit price should be nonzero.");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut batch_guard = accounts.batch_auction.data.borrow_mut();
+    let mut batch = BatchAuction::from_buffer(&mut batch_guard)?;
+    if &batch.header.market != accounts.market.key {
+        msg!("The batch auction account does not belong to this market");
+        return Err(DexError::InvalidBatchAuctionAccount.into());
+    }
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if &user_account.header.market != accounts.market.key {
+        msg!("The provided user account doesn't match the current market");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let fee_tier = accounts
+        .discount_token_account
+        .map(|a| FeeTier::get(&market_state, a, accounts.user_owner.key))
+        .unwrap_or(Ok(FeeTier::Base))?;
+
+    let (qty_to_transfer, transfer_destination, locked_quote_qty) =
+        match FromPrimitive::from_u8(*side).ok_or(ProgramError::InvalidArgument)? {
+            Side::Bid => {
+                // The bid is filled at or below its limit price, which bounds the fees it can be charged
+                let quote_qty = market_state
+                    .get_quote_from_base_ceil(*base_qty, *limit_price)
+                    .ok_or(DexError::NumericalOverflow)?;
+                let royalties_fees = quote_qty
+                    .checked_mul(market_state.royalties_bps)
+                    .ok_or(DexError::NumericalOverflow)?
+                    / 10_000;
+                let locked_quote_qty = quote_qty
+                    .checked_add(fee_tier.taker_fee(quote_qty))
+                    .and_then(|n| n.checked_add(royalties_fees))
+                    .ok_or(DexError::NumericalOverflow)?;
+                let q = locked_quote_qty.saturating_sub(user_account.header.quote_token_free);
+                user_account.header.quote_token_free = user_account
+                    .header
+                    .quote_token_free
+                    .saturating_sub(locked_quote_qty);
+                user_account.header.quote_token_locked += locked_quote_qty;
+                (q, accounts.quote_vault, locked_quote_qty)
+            }
+            Side::Ask => {
+                let q = base_qty.saturating_sub(user_account.header.base_token_free);
+                user_account.header.base_token_free = user_account
+                    .header
+                    .base_token_free
+                    .saturating_sub(*base_qty);
+                user_account.header.base_token_locked += base_qty;
+                (q, accounts.base_vault, 0)
+            }
+        };
+
+    batch.push(
+        BatchOrder {
+            user_account: *accounts.user.key,
+            limit_price: *limit_price,
+            base_qty: *base_qty,
+            locked_quote_qty,
+            side: *side,
+            fee_tier: fee_tier as u8,
+            _padding: [0; 6],
+        },
+        Clock::get()?.slot,
+    )?;
+
+    if qty_to_transfer != 0 {
+        let token_transfer_instruction = spl_token::instruction::transfer(
+            accounts.spl_token_program.key,
+            accounts.user_token_account.key,
+            transfer_destination.key,
+            accounts.user_owner.key,
+            &[],
+            qty_to_transfer,
+        )?;
+
+        invoke(
+            &token_transfer_instruction,
+            &[
+                accounts.spl_token_program.clone(),
+                accounts.user_token_account.clone(),
+                transfer_destination.clone(),
+                accounts.user_owner.clone(),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,30 +1,48 @@
-//! Execute a new order instruction. Supported types include Limit, IOC, FOK, or Post only.
+//! Execute a new order instruction. Supported types include Limit, IOC, FOK, Post only or Market.
+//!
+//! A Market order matches against the book without posting, like an ImmediateOrCancel order, and its limit price is
+//! the worst price it accepts. That price doesn't need to be a multiple of the tick size, as it is rounded to the
+//! tick towards a better price. A market bid without a base quantity is only bounded by its quote budget, so that it
+//! spends the budget at whatever prices the book offers within the band.
 use crate::{
     error::DexError,
-    state::{CallBackInfo, DexState, FeeTier, Order, UserAccount},
+    state::{
+        is_designated_market_maker, CallBackInfo, CrossNotificationLog, DexState, FeeLog, FeeTier,
+        FillLog, Order, RoundingLog, Session, UserAccount, EXPIRED_ORDER_PRUNE_REWARD,
+    },
     utils::check_account_owner,
-    utils::{check_account_key, check_signer},
+    utils::{check_account_key, check_signer, get_oracle_price, leaf_handles},
 };
 use asset_agnostic_orderbook::error::AoError;
-use asset_agnostic_orderbook::state::Side;
+use asset_agnostic_orderbook::state::{
+    critbit::Slab,
+    event_queue::{EventQueue, EventRef, FillEventRef},
+    get_side_from_order_id,
+    market_state::MarketState,
+    AccountTag, SelfTradeBehavior, Side,
+};
 use bonfida_utils::BorshSize;
 use bonfida_utils::InstructionsAccount;
 use borsh::BorshDeserialize;
 use borsh::BorshSerialize;
 use bytemuck::{try_from_bytes, Pod, Zeroable};
-use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program::{invoke, invoke_signed},
     program_error::{PrintProgramError, ProgramError},
+    program_pack::Pack,
     pubkey::Pubkey,
-    system_program,
+    system_instruction, system_program,
+    sysvar::Sysvar,
 };
 
-use super::REFERRAL_MASK;
+use super::{NOTIFY_ON_CROSS_MASK, REFERRAL_MASK};
+
+pub use crate::common::{OrderType, QuantityMode};
 
 #[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
 #[repr(C)]
@@ -33,43 +51,95 @@ The required arguments for a new_order instruction.
 */
 pub struct Params {
     #[cfg(all(not(target_arch = "aarch64"), not(feature = "aarch64-test")))]
-    /// The client order id number that will be stored in the user account
+    /// The client order id number that will be stored in the user account.
+    ///
+    /// The order is rejected with [`DexError::DuplicateClientOrderId`] when a nonzero client order id is already used
+    /// by an open order of the user account, so that a resubmitted order is placed at most once.
     pub client_order_id: u128,
     #[cfg(any(target_arch = "aarch64", feature = "aarch64-test"))]
     pub client_order_id: [u64; 2],
-    /// The order's limit price (as a FP32)
+    /// The order's limit price (as a FP32), which is the worst acceptable price of a Market order
     pub limit_price: u64,
-    /// The max quantity of base token to match and post
+    /// The max quantity of base token to match and post.
+    ///
+    /// For bids, or for asks in the [`QuantityMode::Quote`] mode, zero derives the base quantity from `max_quote_qty`
+    /// and `limit_price`, so that orders can be expressed as an amount of quote token to trade. Market bids are then
+    /// only bounded by `max_quote_qty`.
     pub max_base_qty: u64,
-    /// The max quantity of quote token to match and post
+    /// The max quantity of quote token to match and post.
+    ///
+    /// Matching stops once this budget is spent, whichever of the base and quote bounds is reached first. For bids,
+    /// the budget also covers the taker fees, which makes it the most the order can debit for a market buy.
     pub max_quote_qty: u64,
-    /// The maximum number of orders to be matched against.
+    /// The maximum number of orders to be matched against, which should be at least 1.
     ///
     /// Setting this number too high can sometimes lead to excessive resource consumption which can cause a failure.
+    /// Bounding it guarantees that sweeping a deep book fits in the compute budget. Once the limit is reached, the
+    /// remainder of the order is cancelled rather than posted, as it could still cross the book, which makes
+    /// FillOrKill orders abort unless they were fully filled.
     pub match_limit: u64,
+    /// The unix timestamp after which the posted order can be pruned from the book, 0 for an order which never
+    /// expires.
+    ///
+    /// Posting a good-til-date order deposits [`EXPIRED_ORDER_PRUNE_REWARD`] lamports from the user wallet into the
+    /// user account, which are paid to the pruner once the order expires. An expired order can still be matched until
+    /// it is pruned.
+    pub expiry_timestamp: i64,
+    /// The base quantity displayed on the book for an iceberg order, 0 to display the whole order.
+    ///
+    /// Iceberg orders should be post only. Only a tranche of this size rests on the book, and the hidden remainder of
+    /// `max_base_qty` is kept in the free balance of the user account. Once a tranche is fully filled, the crank
+    /// posts the next one at the same price, behind the orders already resting there. Cancelling the displayed
+    /// tranche ends the iceberg order, leaving the hidden remainder free to be settled.
+    pub display_base_qty: u64,
+    /// The minimum base quantity an ImmediateOrCancel, FillOrKill or Market order should match, below which the
+    /// instruction aborts. Zero for orders of other types.
+    pub min_base_qty: u64,
     /// The order's side (Bid or Ask)
     pub side: u8,
-    /// The order type (supported types include Limit, FOK, IOC and PostOnly)
+    /// The order type (supported types include Limit, FOK, IOC, PostOnly and Market)
     pub order_type: u8,
     /// Configures what happens when this order is at least partially matched against an order belonging to the same user account
     pub self_trade_behavior: u8,
     /// Whether or not the optional discount token account was given
     pub has_discount_token_account: u8,
+    /// Whether or not the optional oracle account was given
+    pub has_oracle_account: u8,
+    /// Whether or not the order is placed by a session key, in which case the session account is given
+    pub has_session: u8,
+    /// Whether or not the optional designated market maker registry was given
+    pub has_dmm_registry: u8,
+    /// Whether the order, once resting on the book, should be notified by a [`CrossNotificationLog`] when a taker
+    /// trades at its price level without filling it because of its queue position
+    pub notify_on_cross: u8,
+    /// The [`QuantityMode`] of the order, which tells whether it is sized by `max_base_qty` or by `max_quote_qty`
+    pub quantity_mode: u8,
+    /// Whether the order should only be funded by the free balances of the user account, in which case no token is
+    /// transferred from the user token account. Those balances can be topped up beforehand with a deposit instruction.
+    pub use_free_balances: u8,
     /// To eliminate implicit padding
-    pub _padding: u32,
+    pub _padding: [u8; 6],
 }
 
-/// This enum describes all supported order types
-#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, FromPrimitive)]
-pub enum OrderType {
-    #[allow(missing_docs)]
-    Limit,
-    #[allow(missing_docs)]
-    ImmediateOrCancel,
-    #[allow(missing_docs)]
-    FillOrKill,
-    #[allow(missing_docs)]
-    PostOnly,
+/// The outcome of a new order, returned by the preview_new_order instruction
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct NewOrderSummary {
+    /// The id of the order posted on the book, if any
+    pub posted_order_id: Option<u128>,
+    /// The matched base quantity
+    pub matched_base_qty: u64,
+    /// The matched quote quantity, fees excluded
+    pub matched_quote_qty: u64,
+    /// The base quantity posted on the book
+    pub posted_base_qty: u64,
+    /// The quote quantity posted on the book
+    pub posted_quote_qty: u64,
+    /// The taker fee, including the volatility surcharge
+    pub taker_fee: u64,
+    /// The royalties fee
+    pub royalties_fee: u64,
+    /// The quantity debited from the user token account, the rest being taken from the free balances of the user account
+    pub transferred_qty: u64,
 }
 
 #[derive(InstructionsAccount)]
@@ -116,14 +186,24 @@ pub struct Accounts<'a, T> {
     #[cons(writable)]
     pub user_token_account: &'a T,
 
-    /// The user wallet
+    /// The user wallet, or the session key for session orders
     #[cons(writable, signer)]
     pub user_owner: &'a T,
 
     /// The optional SRM or MSRM discount token account (must be owned by the user wallet)
     pub discount_token_account: Option<&'a T>,
 
-    /// The optional referrer's token account which will receive a 20% cut of the fees
+    /// The optional oracle price account, required if the market has an oracle
+    pub oracle: Option<&'a T>,
+
+    /// The optional session account, required for session orders
+    #[cons(writable)]
+    pub session: Option<&'a T>,
+
+    /// The optional designated market maker registry, which waives the taker fees of registered user accounts
+    pub dmm_registry: Option<&'a T>,
+
+    /// The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees
     #[cons(writable)]
     pub fee_referral_account: Option<&'a T>,
 }
@@ -133,6 +213,9 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
         program_id: &Pubkey,
         accounts: &'a [AccountInfo<'b>],
         has_discount_token_account: bool,
+        has_oracle_account: bool,
+        has_session: bool,
+        has_dmm_registry: bool,
     ) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
         let a = Self {
@@ -153,6 +236,21 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             } else {
                 None
             },
+            oracle: if has_oracle_account {
+                next_account_info(accounts_iter).ok()
+            } else {
+                None
+            },
+            session: if has_session {
+                next_account_info(accounts_iter).ok()
+            } else {
+                None
+            },
+            dmm_registry: if has_dmm_registry {
+                next_account_info(accounts_iter).ok()
+            } else {
+                None
+            },
             fee_referral_account: next_account_info(accounts_iter).ok(),
         };
 
@@ -181,6 +279,16 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
         }
         check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
         check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        if let Some(session) = a.session {
+            check_account_owner(session, program_id, DexError::InvalidSessionAccount)?;
+        }
+        if let Some(dmm_registry) = a.dmm_registry {
+            check_account_owner(
+                dmm_registry,
+                program_id,
+                DexError::InvalidDmmRegistryAccount,
+            )?;
+        }
 
         Ok(a)
     }
@@ -190,7 +298,8 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
         user_account_data: &'a mut [u8],
     ) -> Result<UserAccount<'a>, ProgramError> {
         let user_account = UserAccount::from_buffer(user_account_data)?;
-        if &user_account.header.owner != self.user_owner.key {
+        // Session orders are authorized by the session account instead
+        if self.session.is_none() && &user_account.header.owner != self.user_owner.key {
             msg!("Invalid user account owner provided!");
             return Err(ProgramError::InvalidArgument);
         }
@@ -198,7 +307,7 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             msg!("The provided user account doesn't match the current market");
             return Err(ProgramError::InvalidArgument);
         };
-        if &user_account.header.owner != self.user_owner.key {
+        if self.session.is_none() && &user_account.header.owner != self.user_owner.key {
             msg!("Invalid user account owner provided!");
             return Err(ProgramError::InvalidArgument);
         }
@@ -215,6 +324,16 @@ pub(crate) fn process(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    execute(program_id, accounts, instruction_data)?;
+    Ok(())
+}
+
+/// Places the order and returns its outcome
+pub(crate) fn execute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> Result<NewOrderSummary, ProgramError> {
     let Params {
         side,
         limit_price,
@@ -223,54 +342,231 @@ pub(crate) fn process(
         order_type,
         self_trade_behavior,
         match_limit,
+        expiry_timestamp,
+        display_base_qty,
+        min_base_qty,
         has_discount_token_account,
+        has_oracle_account,
+        has_session,
+        has_dmm_registry,
+        notify_on_cross,
+        quantity_mode,
+        use_free_balances,
         client_order_id,
         ..
     } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
     #[cfg(any(target_arch = "aarch64", feature = "aarch64-test"))]
     let client_order_id: &u128 = bytemuck::cast_ref(client_order_id);
-    let accounts = Accounts::parse(program_id, accounts, *has_discount_token_account != 0)?;
+    let accounts = Accounts::parse(
+        program_id,
+        accounts,
+        *has_discount_token_account != 0,
+        *has_oracle_account != 0,
+        *has_session != 0,
+        *has_dmm_registry != 0,
+    )?;
+    if *match_limit == 0 {
+        msg!("The match limit should be at least 1");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let order_type_kind: OrderType = FromPrimitive::from_u8(*order_type).ok_or_else(|| {
+        msg!(
+            "Invalid order type, expected Limit, ImmediateOrCancel, FillOrKill, PostOnly or Market"
+        );
+        ProgramError::InvalidArgument
+    })?;
+    if *min_base_qty != 0
+        && *order_type != OrderType::ImmediateOrCancel as u8
+        && *order_type != OrderType::FillOrKill as u8
+        && *order_type != OrderType::Market as u8
+    {
+        msg!("The minimum base quantity only applies to ImmediateOrCancel, FillOrKill and Market orders");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let quantity_mode: QuantityMode = FromPrimitive::from_u8(*quantity_mode).ok_or_else(|| {
+        msg!("Invalid quantity mode, expected Base or Quote");
+        ProgramError::InvalidArgument
+    })?;
+    if order_type_kind == OrderType::Market && *limit_price == 0 {
+        msg!("A market order should specify its worst acceptable price as the limit price");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let self_trade_behavior: SelfTradeBehavior = FromPrimitive::from_u8(*self_trade_behavior)
+        .ok_or_else(|| {
+            msg!("Invalid self trade behavior, expected DecrementTake, CancelProvide or AbortTransaction");
+            ProgramError::InvalidArgument
+        })?;
+    if *has_session != 0 && accounts.session.is_none() {
+        msg!("The session account is required for session orders");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
 
-    let market_state = DexState::get(accounts.market)?;
+    let mut market_state = DexState::get(accounts.market)?;
+    market_state.check_active()?;
+    market_state.check_side_active(*side)?;
+    market_state.check_continuous()?;
     let mut user_account_data = accounts.user.data.borrow_mut();
     let mut user_account = accounts.load_user_account(&mut user_account_data)?;
-
-    // Check the order size
-    if max_base_qty < &market_state.min_base_order_size {
-        msg!("The base order size is too small.");
+    // A client order id identifies a single open order, which makes resubmitted orders idempotent
+    if *client_order_id != 0
+        && user_account
+            .find_order_id_and_index_by_client_id(*client_order_id)
+            .is_ok()
+    {
+        msg!("An order with this client order id is already open");
+        return Err(DexError::DuplicateClientOrderId.into());
+    }
+    let mut session_data = accounts.session.map(|s| s.data.borrow_mut());
+    let mut session = session_data
+        .as_mut()
+        .map(|d| Session::from_buffer(d))
+        .transpose()?;
+    let clock = Clock::get()?;
+    if *expiry_timestamp != 0 && *expiry_timestamp <= clock.unix_timestamp {
+        msg!("The order expiry {} has already passed", expiry_timestamp);
         return Err(ProgramError::InvalidArgument);
     }
+    if let Some(session) = &session {
+        session.check(
+            accounts.user.key,
+            accounts.user_owner.key,
+            clock.unix_timestamp,
+        )?;
+    }
 
-    check_accounts(&market_state, &accounts).unwrap();
-    let (post_only, post_allowed) = match FromPrimitive::from_u8(*order_type).unwrap() {
+    check_accounts(&market_state, &accounts)?;
+    let (post_only, post_allowed) = match order_type_kind {
         OrderType::Limit => (false, true),
-        OrderType::ImmediateOrCancel | OrderType::FillOrKill => (false, false),
+        OrderType::ImmediateOrCancel | OrderType::FillOrKill | OrderType::Market => (false, false),
         OrderType::PostOnly => (true, true),
     };
     let fee_tier = accounts
         .discount_token_account
         .map(|a| FeeTier::get(&market_state, a, accounts.user_owner.key))
         .unwrap_or(Ok(FeeTier::Base))?;
+    let fee_tier = match accounts.dmm_registry {
+        Some(registry)
+            if is_designated_market_maker(registry, accounts.market.key, accounts.user.key)? =>
+        {
+            FeeTier::Dmm
+        }
+        _ => fee_tier,
+    };
     let callback_info = CallBackInfo {
         user_account: *accounts.user.key,
         fee_tier: fee_tier as u8
-            | ((accounts.fee_referral_account.is_some() as u8) * REFERRAL_MASK),
+            | ((accounts.fee_referral_account.is_some() as u8) * REFERRAL_MASK)
+            | ((*notify_on_cross != 0) as u8 * NOTIFY_ON_CROSS_MASK),
+    };
+    let tick_size = {
+        let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+        MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?.tick_size
+    };
+    let limit_price = &if order_type_kind == OrderType::Market {
+        worst_price_on_tick(*limit_price, *side, tick_size)?
+    } else {
+        *limit_price
     };
+    let fee_multiplier = market_state.volatility_multiplier(clock.slot);
     if *side == Side::Bid as u8 && *order_type != OrderType::PostOnly as u8 {
         // We make sure to leave enough quote quantity to pay for taker fees in the worst case
-        max_quote_qty = fee_tier.remove_taker_fee(max_quote_qty);
+        max_quote_qty = fee_tier.remove_scaled_taker_fee(max_quote_qty, fee_multiplier);
+    }
+    let max_base_qty = &if *side == Side::Bid as u8
+        && *max_base_qty == 0
+        && order_type_kind == OrderType::Market
+    {
+        u64::MAX - u64::MAX % market_state.base_currency_multiplier
+    } else if (*side == Side::Bid as u8 || quantity_mode == QuantityMode::Quote)
+        && *max_base_qty == 0
+    {
+        // An ask sells the most base at its limit price, which bounds the base quantity worth the quote amount
+        market_state
+            .get_base_from_quote(max_quote_qty, *limit_price)
+            .ok_or(DexError::NumericalOverflow)?
+    } else {
+        *max_base_qty
+    };
+
+    // Check the order size
+    if max_base_qty < &market_state.min_base_order_size {
+        msg!("The base order size is too small.");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Only the displayed tranche of an iceberg order is submitted to the orderbook
+    let hidden_base_qty = if *display_base_qty != 0 && display_base_qty < max_base_qty {
+        if *order_type != OrderType::PostOnly as u8 {
+            msg!("Iceberg orders should be post only");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if *has_session != 0 {
+            msg!("Iceberg orders cannot be placed by a session key");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if display_base_qty < &market_state.min_base_order_size {
+            msg!("The displayed base quantity is too small.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        *max_base_qty - *display_base_qty
+    } else {
+        0
+    };
+    let max_base_qty = &(*max_base_qty - hidden_base_qty);
+
+    // The orderbook works in lots, so an order smaller than a lot would silently do nothing
+    let scaled_base_qty = market_state.scale_base_amount(*max_base_qty);
+    if scaled_base_qty == 0 {
+        msg!(
+            "The base order size {} is smaller than the base lot size {}",
+            max_base_qty,
+            market_state.base_currency_multiplier
+        );
+        return Err(DexError::OrderSizeRoundsToZero.into());
+    }
+    let scaled_quote_qty = market_state.scale_quote_amount(max_quote_qty);
+    if scaled_quote_qty == 0 {
+        msg!(
+            "The quote order size {} is smaller than the quote lot size {}",
+            max_quote_qty,
+            market_state.quote_currency_multiplier
+        );
+        return Err(DexError::OrderSizeRoundsToZero.into());
+    }
+    let rounding_log =
+        (*max_base_qty % market_state.base_currency_multiplier != 0).then(|| RoundingLog {
+            market: *accounts.market.key,
+            side: *side,
+            requested_base_qty: *max_base_qty,
+            base_qty: *max_base_qty - *max_base_qty % market_state.base_currency_multiplier,
+        });
+
+    // An order which can rest is checked against the user's orders before it is matched, as it rests at its limit price
+    if post_allowed {
+        check_order_tick_distance(
+            &market_state,
+            &user_account,
+            FromPrimitive::from_u8(*side).unwrap(),
+            *limit_price,
+            tick_size,
+        )?;
     }
 
     let invoke_params = asset_agnostic_orderbook::instruction::new_order::Params {
-        max_base_qty: market_state.scale_base_amount(*max_base_qty),
-        max_quote_qty: market_state.scale_quote_amount(max_quote_qty),
+        max_base_qty: scaled_base_qty,
+        max_quote_qty: scaled_quote_qty,
         limit_price: *limit_price,
         side: FromPrimitive::from_u8(*side).unwrap(),
         match_limit: *match_limit,
         callback_info,
         post_only,
         post_allowed,
-        self_trade_behavior: FromPrimitive::from_u8(*self_trade_behavior).unwrap(),
+        self_trade_behavior,
+    };
+    let events_before = {
+        let mut event_queue_guard = accounts.event_queue.data.borrow_mut();
+        EventQueue::<CallBackInfo>::from_buffer(&mut event_queue_guard, AccountTag::EventQueue)?
+            .len()
     };
     let invoke_accounts = asset_agnostic_orderbook::instruction::new_order::Accounts {
         market: accounts.orderbook,
@@ -299,97 +595,148 @@ pub(crate) fn process(
         .get_quote_from_base(order_summary.total_base_qty_posted, *limit_price)
         .unwrap();
 
-    let (qty_to_transfer, transfer_destination, referral_fee) =
-        match FromPrimitive::from_u8(*side).unwrap() {
-            Side::Bid => {
-                // We update the order summary to properly handle the FOK order type
-                let matched_quote_qty = order_summary.total_quote_qty - posted_quote_qty;
-                let taker_fee = fee_tier.taker_fee(matched_quote_qty);
-                let royalties_fees = matched_quote_qty
-                    .checked_mul(market_state.royalties_bps)
-                    .unwrap()
-                    / 10_000;
-                order_summary.total_quote_qty += taker_fee + royalties_fees;
-                let referral_fee = fee_tier.referral_fee(matched_quote_qty);
-                let q = order_summary
-                    .total_quote_qty
-                    .saturating_sub(user_account.header.quote_token_free);
-                user_account.header.quote_token_free = user_account
-                    .header
-                    .quote_token_free
-                    .saturating_sub(order_summary.total_quote_qty);
-                user_account.header.quote_token_locked += posted_quote_qty;
-                user_account.header.base_token_free = order_summary
-                    .total_base_qty
-                    .checked_sub(order_summary.total_base_qty_posted)
-                    .and_then(|n| n.checked_add(user_account.header.base_token_free))
-                    .unwrap();
-
-                (q, accounts.quote_vault, referral_fee)
-            }
-            Side::Ask => {
-                let q = order_summary
-                    .total_base_qty
-                    .saturating_sub(user_account.header.base_token_free);
-                user_account.header.base_token_free = user_account
-                    .header
-                    .base_token_free
-                    .saturating_sub(order_summary.total_base_qty);
-                user_account.header.base_token_locked += order_summary.total_base_qty_posted;
-                let taken_quote_qty = order_summary.total_quote_qty - posted_quote_qty;
-                let taker_fee = fee_tier.taker_fee(taken_quote_qty);
-                let royalties_fees = taken_quote_qty
-                    .checked_mul(market_state.royalties_bps)
-                    .unwrap()
-                    / 10_000;
-                let referral_fee = fee_tier.referral_fee(taken_quote_qty);
-                user_account.header.quote_token_free = taken_quote_qty
-                    .checked_sub(taker_fee + royalties_fees)
-                    .and_then(|n| n.checked_add(user_account.header.quote_token_free))
-                    .unwrap();
-                (q, accounts.base_vault, referral_fee)
-            }
+    let matched_base_qty = order_summary.total_base_qty - order_summary.total_base_qty_posted;
+    let matched_quote_qty = order_summary.total_quote_qty - posted_quote_qty;
+    // The volatility surcharge is collected upfront as it isn't known to the event queue
+    let volatility_fee = fee_tier
+        .taker_fee(matched_quote_qty)
+        .checked_mul(fee_multiplier - 1)
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let taker_fee = fee_tier.taker_fee(matched_quote_qty);
+    let royalties_fees = matched_quote_qty
+        .checked_mul(market_state.royalties_bps)
+        .unwrap()
+        / 10_000;
+    let referral_fee = fee_tier.referral_fee(matched_quote_qty, market_state.referral_share_bps);
+
+    let (mut qty_to_transfer, transfer_destination) = match FromPrimitive::from_u8(*side).unwrap() {
+        Side::Bid => {
+            // We update the order summary to properly handle the FOK order type
+            order_summary.total_quote_qty += taker_fee + royalties_fees + volatility_fee;
+            let q = order_summary
+                .total_quote_qty
+                .saturating_sub(user_account.header.quote_token_free);
+            user_account.header.quote_token_free = user_account
+                .header
+                .quote_token_free
+                .saturating_sub(order_summary.total_quote_qty);
+            user_account.header.quote_token_locked += posted_quote_qty;
+            user_account.header.base_token_free = order_summary
+                .total_base_qty
+                .checked_sub(order_summary.total_base_qty_posted)
+                .and_then(|n| n.checked_add(user_account.header.base_token_free))
+                .unwrap();
+
+            (q, accounts.quote_vault)
+        }
+        Side::Ask => {
+            let q = order_summary
+                .total_base_qty
+                .saturating_sub(user_account.header.base_token_free);
+            user_account.header.base_token_free = user_account
+                .header
+                .base_token_free
+                .saturating_sub(order_summary.total_base_qty);
+            user_account.header.base_token_locked += order_summary.total_base_qty_posted;
+            user_account.header.quote_token_free = matched_quote_qty
+                .checked_sub(taker_fee + royalties_fees + volatility_fee)
+                .and_then(|n| n.checked_add(user_account.header.quote_token_free))
+                .unwrap();
+            (q, accounts.base_vault)
+        }
+    };
+
+    if hidden_base_qty != 0 {
+        // The free balance covering the hidden remainder is topped up from the user wallet when needed
+        let (reserve, free_balance) = match FromPrimitive::from_u8(*side).unwrap() {
+            Side::Bid => (
+                market_state
+                    .get_quote_from_base(hidden_base_qty, *limit_price)
+                    .ok_or(DexError::NumericalOverflow)?,
+                &mut user_account.header.quote_token_free,
+            ),
+            Side::Ask => (hidden_base_qty, &mut user_account.header.base_token_free),
         };
+        let q = reserve.saturating_sub(*free_balance);
+        *free_balance += q;
+        qty_to_transfer += q;
+    }
 
-    let abort = match FromPrimitive::from_u8(*order_type).unwrap() {
-        OrderType::ImmediateOrCancel => order_summary.total_base_qty == 0,
+    let abort = match order_type_kind {
+        OrderType::ImmediateOrCancel | OrderType::Market => {
+            order_summary.total_base_qty == 0 || matched_base_qty < *min_base_qty
+        }
         OrderType::FillOrKill => {
-            if *side == Side::Bid as u8 {
-                order_summary.total_quote_qty < max_quote_qty
+            let filled = if *side == Side::Bid as u8 || quantity_mode == QuantityMode::Quote {
+                order_summary.total_quote_qty >= max_quote_qty
             } else {
-                &order_summary.total_base_qty < max_base_qty
-            }
+                &order_summary.total_base_qty >= max_base_qty
+            };
+            !filled || matched_base_qty < *min_base_qty
         }
         OrderType::PostOnly => order_summary.posted_order_id.is_none(),
         _ => false,
     };
 
     if abort {
+        if matched_base_qty < *min_base_qty {
+            msg!(
+                "The matched base quantity {} is below the minimum of {}",
+                matched_base_qty,
+                min_base_qty
+            );
+        }
         msg!(
             "The specified order type {:?} has caused an abort",
             order_type
         );
         return Err(DexError::TransactionAborted.into());
     }
+    let summary = NewOrderSummary {
+        posted_order_id: order_summary.posted_order_id,
+        matched_base_qty,
+        matched_quote_qty,
+        posted_base_qty: order_summary.total_base_qty_posted,
+        posted_quote_qty,
+        taker_fee: taker_fee + volatility_fee,
+        royalties_fee: royalties_fees,
+        transferred_qty: qty_to_transfer,
+    };
 
-    let token_transfer_instruction = spl_token::instruction::transfer(
-        accounts.spl_token_program.key,
-        accounts.user_token_account.key,
-        transfer_destination.key,
-        accounts.user_owner.key,
-        &[],
-        qty_to_transfer,
-    )?;
+    market_state.accumulated_fees = market_state
+        .accumulated_fees
+        .checked_add(volatility_fee)
+        .ok_or(DexError::NumericalOverflow)?;
+    market_state.record_fill_price(matched_base_qty, matched_quote_qty, clock.slot);
+    if matched_base_qty != 0 {
+        market_state.record_enqueued_events(clock.slot);
+    }
 
-    invoke(
-        &token_transfer_instruction,
-        &[
-            accounts.spl_token_program.clone(),
-            accounts.user_token_account.clone(),
-            transfer_destination.clone(),
-            accounts.user_owner.clone(),
-        ],
-    )?;
+    // The session key cannot move the owner's tokens
+    let from_free_balances = session.is_some() || *use_free_balances != 0;
+    check_funds(&accounts, qty_to_transfer, from_free_balances)?;
+
+    if !from_free_balances {
+        let token_transfer_instruction = spl_token::instruction::transfer(
+            accounts.spl_token_program.key,
+            accounts.user_token_account.key,
+            transfer_destination.key,
+            accounts.user_owner.key,
+            &[],
+            qty_to_transfer,
+        )?;
+
+        invoke(
+            &token_transfer_instruction,
+            &[
+                accounts.spl_token_program.clone(),
+                accounts.user_token_account.clone(),
+                transfer_destination.clone(),
+                accounts.user_owner.clone(),
+            ],
+        )?;
+    }
 
     if let Some(a) = accounts.fee_referral_account {
         let referral_fee_transfer_instruction = spl_token::instruction::transfer(
@@ -420,17 +767,231 @@ pub(crate) fn process(
         user_account.add_order(Order {
             id: order_id,
             client_id: *client_order_id,
+            placed_slot: clock.slot,
+            expiry_timestamp: *expiry_timestamp,
+            hidden_base_qty,
+            display_base_qty: if hidden_base_qty != 0 {
+                *display_base_qty
+            } else {
+                0
+            },
+            maker_rate: fee_tier.market_maker_rate(market_state.maker_rebate_bps) as u32,
+            has_maker_rate: 1,
+            was_filled: 0,
+            _padding: [0; 10],
         })?;
+        if let Some(session) = session.as_mut() {
+            session.push(order_id, |id| user_account.find_order_index(id).is_ok())?;
+        }
         msg!("Added new order with order_id {:?}", order_id);
     }
 
-    user_account.header.accumulated_taker_base_volume += order_summary
-        .total_base_qty
-        .saturating_sub(order_summary.total_base_qty_posted);
-    user_account.header.accumulated_taker_quote_volume += order_summary
-        .total_quote_qty
-        .saturating_sub(posted_quote_qty);
+    user_account.header.accumulated_taker_base_volume += matched_base_qty;
+    user_account.header.accumulated_taker_quote_volume += matched_quote_qty;
+    user_account.header.last_active_slot = clock.slot;
+    market_state.order_flow.record_new_orders(clock.slot, 1);
+    market_state.refresh_top_of_book(accounts.bids, accounts.asks, clock.slot)?;
+
+    if matched_base_qty != 0 {
+        FillLog {
+            market: *accounts.market.key,
+            side: *side,
+            base_qty: matched_base_qty,
+            quote_qty: matched_quote_qty,
+            slot: clock.slot,
+            taker: (!market_state.hides_fill_accounts()).then(|| *accounts.user.key),
+            oracle_price: accounts.oracle.map(get_oracle_price).transpose()?.flatten(),
+            client_order_id: Some(*client_order_id),
+        }
+        .emit()?;
+        FeeLog {
+            market: *accounts.market.key,
+            taker_fee: taker_fee + volatility_fee,
+            royalties_fee: royalties_fees,
+            referral_fee: accounts.fee_referral_account.map_or(0, |_| referral_fee),
+            referrer: accounts.fee_referral_account.map(|a| *a.key),
+        }
+        .emit()?;
+        notify_crossed_orders(
+            &accounts,
+            FromPrimitive::from_u8(*side).unwrap(),
+            events_before,
+            clock.slot,
+        )?;
+    }
+
+    if let Some(rounding_log) = rounding_log {
+        rounding_log.emit()?;
+    }
+
+    if *expiry_timestamp != 0 && order_summary.posted_order_id.is_some() {
+        // The user account is borrowed by the system program to receive the prune reward
+        drop(user_account_data);
+        invoke(
+            &system_instruction::transfer(
+                accounts.user_owner.key,
+                accounts.user.key,
+                EXPIRED_ORDER_PRUNE_REWARD,
+            ),
+            &[
+                accounts.system_program.clone(),
+                accounts.user_owner.clone(),
+                accounts.user.clone(),
+            ],
+        )?;
+    }
+
+    #[cfg(feature = "strict-checks")]
+    {
+        crate::invariants::check_book_not_crossed(accounts.bids, accounts.asks)?;
+        crate::invariants::check_vault_covers_fees(&market_state, accounts.quote_vault)?;
+    }
 
+    Ok(summary)
+}
+
+/// Rounds the worst acceptable price of a market order to the tick size, towards a better price for the order
+fn worst_price_on_tick(worst_price: u64, side: u8, tick_size: u64) -> Result<u64, ProgramError> {
+    let remainder = worst_price % tick_size;
+    let price = if side == Side::Bid as u8 || remainder == 0 {
+        worst_price - remainder
+    } else {
+        worst_price
+            .checked_add(tick_size - remainder)
+            .ok_or(DexError::NumericalOverflow)?
+    };
+    if price == 0 {
+        msg!(
+            "The worst acceptable price is below the tick size {}",
+            tick_size
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(price)
+}
+
+/// The most resting orders inspected for cross notifications, which bounds the compute spent on them
+const MAX_CROSS_NOTIFICATION_ORDERS: usize = 16;
+
+/// Notifies the resting orders placed with `notify_on_cross` which sit at the last price level traded by the order
+/// but were not filled because of their queue position
+fn notify_crossed_orders(
+    accounts: &Accounts<AccountInfo>,
+    side: Side,
+    events_before: u64,
+    slot: u64,
+) -> ProgramResult {
+    // The makers are matched from the best price, so the last fill is at the last traded price level
+    let filled_order_ids = {
+        let mut event_queue_guard = accounts.event_queue.data.borrow_mut();
+        let event_queue = EventQueue::<CallBackInfo>::from_buffer(
+            &mut event_queue_guard,
+            AccountTag::EventQueue,
+        )?;
+        event_queue
+            .iter()
+            .skip(events_before as usize)
+            .filter_map(|e| match e {
+                EventRef::Fill(FillEventRef { event, .. }) => Some(event.maker_order_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let last_price = match filled_order_ids.last() {
+        Some(order_id) => (order_id >> 64) as u64,
+        None => return Ok(()),
+    };
+
+    let (slab_account, slab_tag, ascending) = match side {
+        Side::Bid => (accounts.asks, AccountTag::Asks, true),
+        Side::Ask => (accounts.bids, AccountTag::Bids, false),
+    };
+    let mut slab_guard = slab_account.data.borrow_mut();
+    let slab = Slab::<CallBackInfo>::from_buffer(&mut slab_guard, slab_tag)?;
+    for handle in leaf_handles(&slab, ascending).take(MAX_CROSS_NOTIFICATION_ORDERS) {
+        let leaf = &slab.leaf_nodes[handle as usize];
+        if (leaf.key >> 64) as u64 != last_price {
+            break;
+        }
+        if filled_order_ids.contains(&leaf.key) {
+            continue;
+        }
+        let callback_info = &slab.callback_infos[handle as usize];
+        if callback_info.fee_tier & NOTIFY_ON_CROSS_MASK != 0 {
+            CrossNotificationLog {
+                market: *accounts.market.key,
+                user_account: callback_info.user_account,
+                order_id: leaf.key,
+                slot,
+            }
+            .emit()?;
+        }
+    }
+    Ok(())
+}
+
+/// Makes sure that the order can be funded before transferring tokens, logging the exact shortfall otherwise
+fn check_funds(
+    accounts: &Accounts<AccountInfo>,
+    qty_to_transfer: u64,
+    from_free_balances: bool,
+) -> ProgramResult {
+    if qty_to_transfer == 0 {
+        return Ok(());
+    }
+    if from_free_balances {
+        msg!(
+            "The order should be funded by the free balances of the user account, which are short by {}",
+            qty_to_transfer
+        );
+        return Err(DexError::InsufficientFunds.into());
+    }
+    let available =
+        spl_token::state::Account::unpack(&accounts.user_token_account.data.borrow())?.amount;
+    if available < qty_to_transfer {
+        msg!(
+            "The order debits {} from the user token account which only holds {}, a shortfall of {}",
+            qty_to_transfer,
+            available,
+            qty_to_transfer - available
+        );
+        return Err(DexError::InsufficientFunds.into());
+    }
+    Ok(())
+}
+
+fn check_order_tick_distance(
+    market_state: &DexState,
+    user_account: &UserAccount,
+    side: Side,
+    price: u64,
+    tick_size: u64,
+) -> ProgramResult {
+    if market_state.min_order_tick_distance == 0 {
+        return Ok(());
+    }
+    let min_distance = (market_state.min_order_tick_distance as u64)
+        .checked_mul(tick_size)
+        .ok_or(DexError::NumericalOverflow)?;
+    for o in user_account.orders() {
+        if get_side_from_order_id(o.id) != side {
+            continue;
+        }
+        let other_price = (o.id >> 64) as u64;
+        let distance = if price > other_price {
+            price - other_price
+        } else {
+            other_price - price
+        };
+        if distance < min_distance {
+            msg!(
+                "The order is less than {} ticks away from order {}",
+                market_state.min_order_tick_distance,
+                o.id
+            );
+            return Err(DexError::OrderTooClose.into());
+        }
+    }
     Ok(())
 }
 
@@ -450,6 +1011,7 @@ fn check_accounts(market_state: &DexState, accounts: &Accounts<AccountInfo>) ->
         &market_state.quote_vault,
         DexError::InvalidQuoteVaultAccount,
     )?;
+    market_state.check_oracle(accounts.oracle)?;
 
     Ok(())
 }
@@ -0,0 +1,27 @@
+//! Preview a new order. This instruction is meant to be simulated and always fails.
+//!
+//! It takes the same accounts and parameters as new_order and runs the same checks and matching, then writes the
+//! outcome of the order into the return data, encoded as a borsh [`NewOrderSummary`], before failing with
+//! [`DexError::PreviewOnly`] so that no state change is ever committed. A simulation failing with any other error
+//! means that the order itself would fail.
+use super::new_order;
+pub use super::new_order::{Accounts, NewOrderSummary, Params};
+use crate::error::DexError;
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::set_return_data,
+    pubkey::Pubkey,
+};
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let summary = new_order::execute(program_id, accounts, instruction_data)?;
+
+    set_return_data(&summary.try_to_vec()?);
+
+    msg!("The order preview is reverted");
+    Err(DexError::PreviewOnly.into())
+}
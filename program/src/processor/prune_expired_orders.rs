@@ -0,0 +1,209 @@
+//! Remove the expired good-til-date orders of user accounts from the book. This instruction is permissionless
+//!
+//! The funds locked by the pruned orders are unlocked in their user accounts. For each pruned order, the
+//! [`EXPIRED_ORDER_PRUNE_REWARD`] lamports deposited in the user account when the order was posted are paid to the
+//! reward target, which incentivizes pruning the way fee shares incentivize consuming events.
+use crate::{
+    error::DexError,
+    processor::cancel_session_orders::cancel_resting_order,
+    state::{CallBackInfo, DexState, UserAccount, EXPIRED_ORDER_PRUNE_REWARD},
+    utils::{check_account_key, check_account_owner},
+};
+use asset_agnostic_orderbook::state::{
+    critbit::Slab, get_side_from_order_id, market_state::MarketState, AccountTag, Side,
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a prune_expired_orders instruction.
+*/
+pub struct Params {
+    /// The maximum number of orders to prune, which bounds the compute used by the instruction
+    pub max_orders: u64,
+    /// Whether the instruction should fail when no order was pruned
+    pub no_op_err: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The orderbook
+    #[cons(writable)]
+    pub orderbook: &'a T,
+
+    /// The AOB event queue
+    #[cons(writable)]
+    pub event_queue: &'a T,
+
+    /// The AOB bids shared memory
+    #[cons(writable)]
+    pub bids: &'a T,
+
+    /// The AOB asks shared memory
+    #[cons(writable)]
+    pub asks: &'a T,
+
+    /// The account receiving the prune rewards
+    #[cons(writable)]
+    pub reward_target: &'a T,
+
+    /// The user accounts whose expired orders should be pruned
+    #[cons(writable)]
+    pub user_accounts: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            reward_target: next_account_info(accounts_iter)?,
+            user_accounts: accounts_iter.as_slice(),
+        };
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        for user_account in a.user_accounts {
+            check_account_owner(user_account, program_id, DexError::InvalidStateAccountOwner)?;
+        }
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        max_orders,
+        no_op_err,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(
+        accounts.bids,
+        &orderbook.bids,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.asks,
+        &orderbook.asks,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    drop(orderbook_guard);
+
+    let clock = Clock::get()?;
+    let rent = Rent::get()?;
+    let mut pruned_orders = 0;
+    for user_account_info in accounts.user_accounts {
+        if pruned_orders >= *max_orders {
+            break;
+        }
+        let mut user_account_guard = user_account_info.data.borrow_mut();
+        let mut user_account = UserAccount::from_buffer(&mut user_account_guard)?;
+        if &user_account.header.market != accounts.market.key {
+            msg!("The provided user account doesn't match the current market");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Orders which were matched since, including those with pending fill events, are skipped
+        let expired_order_ids = {
+            let mut bids_guard = accounts.bids.data.borrow_mut();
+            let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+            let mut asks_guard = accounts.asks.data.borrow_mut();
+            let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+            user_account
+                .orders()
+                .iter()
+                .filter(|o| o.is_expired(clock.unix_timestamp))
+                .map(|o| o.id)
+                .filter(|&order_id| {
+                    let slab = match get_side_from_order_id(order_id) {
+                        Side::Bid => &bids,
+                        Side::Ask => &asks,
+                    };
+                    slab.find_by_key(order_id).is_some()
+                })
+                .take((*max_orders - pruned_orders) as usize)
+                .collect::<Vec<_>>()
+        };
+
+        for &order_id in &expired_order_ids {
+            cancel_resting_order(
+                program_id,
+                &market_state,
+                accounts.orderbook,
+                accounts.event_queue,
+                accounts.bids,
+                accounts.asks,
+                &mut user_account,
+                order_id,
+            )?;
+            msg!("Pruned expired order {}", order_id);
+        }
+        pruned_orders += expired_order_ids.len() as u64;
+        drop(user_account_guard);
+
+        // The rent exemption of the user account is never paid out
+        let reward = EXPIRED_ORDER_PRUNE_REWARD
+            .saturating_mul(expired_order_ids.len() as u64)
+            .min(
+                user_account_info
+                    .lamports()
+                    .saturating_sub(rent.minimum_balance(user_account_info.data_len())),
+            );
+        **user_account_info.lamports.borrow_mut() -= reward;
+        **accounts.reward_target.lamports.borrow_mut() += reward;
+    }
+
+    if pruned_orders == 0 {
+        msg!("No expired order to prune");
+        if *no_op_err != 0 {
+            return Err(DexError::NoOp.into());
+        }
+        return Ok(());
+    }
+
+    market_state
+        .order_flow
+        .record_cancels(clock.slot, pruned_orders);
+    market_state.refresh_top_of_book(accounts.bids, accounts.asks, clock.slot)?;
+
+    Ok(())
+}
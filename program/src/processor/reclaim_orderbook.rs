@@ -0,0 +1,161 @@
+//! Close the AAOB accounts of a market which was migrated to another program. This is an admin instruction
+//!
+//! The orderbook of a frozen market stays readable while its resting orders may still be imported by the migration
+//! program. Once the vaults can be exported and the orderbook has been emptied, its accounts only hold rent: they are
+//! closed and the market is left without an orderbook. The market account itself is kept, as the user accounts still
+//! refer to it.
+use crate::{
+    error::DexError,
+    processor::relink_orderbook::check_orderbook_empty,
+    state::{CallBackInfo, DexState},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use asset_agnostic_orderbook::{
+    error::AoError,
+    state::{market_state::MarketState, AccountTag},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+
+    /// The orderbook
+    #[cons(writable)]
+    pub orderbook: &'a T,
+
+    /// The AOB event queue
+    #[cons(writable)]
+    pub event_queue: &'a T,
+
+    /// The AOB bids shared memory
+    #[cons(writable)]
+    pub bids: &'a T,
+
+    /// The AOB asks shared memory
+    #[cons(writable)]
+    pub asks: &'a T,
+
+    /// The account receiving the lamports of the closed AOB accounts
+    #[cons(writable)]
+    pub target_lamports_account: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            target_lamports_account: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    if !market_state.is_migrating() {
+        msg!("Only the orderbook of a migrated market can be reclaimed");
+        return Err(DexError::MarketStillActive.into());
+    }
+    if Clock::get()?.unix_timestamp < market_state.migration_unlock_timestamp {
+        msg!(
+            "The orderbook can only be reclaimed after {}",
+            market_state.migration_unlock_timestamp
+        );
+        return Err(DexError::MigrationTimelockActive.into());
+    }
+
+    {
+        let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+        let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+        check_account_key(
+            accounts.event_queue,
+            &orderbook.event_queue,
+            DexError::InvalidOrderbookAccount,
+        )?;
+        check_account_key(
+            accounts.bids,
+            &orderbook.bids,
+            DexError::InvalidOrderbookAccount,
+        )?;
+        check_account_key(
+            accounts.asks,
+            &orderbook.asks,
+            DexError::InvalidOrderbookAccount,
+        )?;
+    }
+    check_orderbook_empty(accounts.event_queue, accounts.bids, accounts.asks)?;
+
+    let invoke_accounts = asset_agnostic_orderbook::instruction::close_market::Accounts {
+        market: accounts.orderbook,
+        event_queue: accounts.event_queue,
+        bids: accounts.bids,
+        asks: accounts.asks,
+        lamports_target_account: accounts.target_lamports_account,
+    };
+    let invoke_params = asset_agnostic_orderbook::instruction::close_market::Params {};
+    if let Err(error) = asset_agnostic_orderbook::instruction::close_market::process::<CallBackInfo>(
+        program_id,
+        invoke_accounts,
+        invoke_params,
+    ) {
+        error.print::<AoError>();
+        return Err(DexError::AOBError.into());
+    }
+
+    market_state.orderbook = Pubkey::default();
+
+    msg!("Reclaimed orderbook {}", accounts.orderbook.key);
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+//! Replace the AAOB accounts of a halted market with freshly initialized ones. This is an admin instruction
+//!
+//! This is used when an AAOB upgrade changes the layout of the orderbook accounts. The current orderbook must be empty
+//! so that no resting order or pending event is lost. Its accounts are closed and the new ones are initialized with
+//! the same tick size and minimum order size.
+use crate::{
+    error::DexError,
+    state::{CallBackInfo, DexState},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use asset_agnostic_orderbook::{
+    error::AoError,
+    state::{critbit::Slab, event_queue::EventQueue, market_state::MarketState, AccountTag},
+};
+use bonfida_utils::checks::check_rent_exempt;
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+    pubkey::Pubkey,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+
+    /// The current orderbook
+    #[cons(writable)]
+    pub orderbook: &'a T,
+
+    /// The current AOB event queue
+    #[cons(writable)]
+    pub event_queue: &'a T,
+
+    /// The current AOB bids shared memory
+    #[cons(writable)]
+    pub bids: &'a T,
+
+    /// The current AOB asks shared memory
+    #[cons(writable)]
+    pub asks: &'a T,
+
+    /// The new orderbook account
+    #[cons(writable)]
+    pub new_orderbook: &'a T,
+
+    /// The new AOB event queue account
+    #[cons(writable)]
+    pub new_event_queue: &'a T,
+
+    /// The new AOB bids account
+    #[cons(writable)]
+    pub new_bids: &'a T,
+
+    /// The new AOB asks account
+    #[cons(writable)]
+    pub new_asks: &'a T,
+
+    /// The account receiving the lamports of the closed AOB accounts
+    #[cons(writable)]
+    pub target_lamports_account: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            new_orderbook: next_account_info(accounts_iter)?,
+            new_event_queue: next_account_info(accounts_iter)?,
+            new_bids: next_account_info(accounts_iter)?,
+            new_asks: next_account_info(accounts_iter)?,
+            target_lamports_account: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        for new_account in [a.new_orderbook, a.new_event_queue, a.new_bids, a.new_asks] {
+            check_account_owner(new_account, program_id, DexError::InvalidStateAccountOwner)?;
+            check_rent_exempt(new_account)?;
+        }
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    if market_state.is_migrating() {
+        msg!("The orderbook of a market being migrated cannot be relinked");
+        return Err(DexError::MarketMigrating.into());
+    }
+    if !market_state.is_halted() {
+        msg!("The orderbook can only be relinked on a halted market");
+        return Err(DexError::MarketNotHalted.into());
+    }
+
+    let tick_size = {
+        let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+        let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+        check_account_key(
+            accounts.event_queue,
+            &orderbook.event_queue,
+            DexError::InvalidOrderbookAccount,
+        )?;
+        check_account_key(
+            accounts.bids,
+            &orderbook.bids,
+            DexError::InvalidOrderbookAccount,
+        )?;
+        check_account_key(
+            accounts.asks,
+            &orderbook.asks,
+            DexError::InvalidOrderbookAccount,
+        )?;
+        orderbook.tick_size
+    };
+    check_orderbook_empty(accounts.event_queue, accounts.bids, accounts.asks)?;
+
+    let invoke_accounts = asset_agnostic_orderbook::instruction::close_market::Accounts {
+        market: accounts.orderbook,
+        event_queue: accounts.event_queue,
+        bids: accounts.bids,
+        asks: accounts.asks,
+        lamports_target_account: accounts.target_lamports_account,
+    };
+    let invoke_params = asset_agnostic_orderbook::instruction::close_market::Params {};
+    if let Err(error) = asset_agnostic_orderbook::instruction::close_market::process::<CallBackInfo>(
+        program_id,
+        invoke_accounts,
+        invoke_params,
+    ) {
+        error.print::<AoError>();
+        return Err(DexError::AOBError.into());
+    }
+
+    let invoke_params = asset_agnostic_orderbook::instruction::create_market::Params {
+        min_base_order_size: market_state.min_base_order_size
+            / market_state.base_currency_multiplier,
+        tick_size,
+    };
+    let invoke_accounts = asset_agnostic_orderbook::instruction::create_market::Accounts {
+        market: accounts.new_orderbook,
+        event_queue: accounts.new_event_queue,
+        bids: accounts.new_bids,
+        asks: accounts.new_asks,
+    };
+    if let Err(error) = asset_agnostic_orderbook::instruction::create_market::process::<CallBackInfo>(
+        program_id,
+        invoke_accounts,
+        invoke_params,
+    ) {
+        error.print::<AoError>();
+        return Err(DexError::AOBError.into());
+    }
+
+    market_state.orderbook = *accounts.new_orderbook.key;
+
+    msg!(
+        "Relinked the market to orderbook {}",
+        accounts.new_orderbook.key
+    );
+
+    Ok(())
+}
+
+/// Checks that an orderbook has no resting order nor pending event, so that closing it loses nothing
+pub(crate) fn check_orderbook_empty(
+    event_queue: &AccountInfo,
+    bids: &AccountInfo,
+    asks: &AccountInfo,
+) -> ProgramResult {
+    let mut event_queue_guard = event_queue.data.borrow_mut();
+    let event_queue =
+        EventQueue::<CallBackInfo>::from_buffer(&mut event_queue_guard, AccountTag::EventQueue)?;
+    if !event_queue.is_empty() {
+        msg!("The event queue of the current orderbook must be consumed first");
+        return Err(DexError::EventQueueMustBeEmpty.into());
+    }
+
+    let mut bids_guard = bids.data.borrow_mut();
+    let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+    let mut asks_guard = asks.data.borrow_mut();
+    let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+    if bids.find_max().is_some() || asks.find_min().is_some() {
+        msg!("The orders of the current orderbook must be cancelled first");
+        return Err(DexError::MarketStillActive.into());
+    }
+
+    Ok(())
+}
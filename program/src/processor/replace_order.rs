@@ -0,0 +1,169 @@
+//! Cancel an existing order and place a new one in a single instruction, so that the order cannot be filled in between.
+//!
+//! It takes the same accounts as new_order. The order to replace should still rest on the book, otherwise the
+//! instruction fails and no new order is placed. The cancelled order frees its slot in the user account for the new one.
+pub use super::new_order::Accounts;
+use super::{cancel_session_orders::cancel_resting_order, new_order};
+use crate::{
+    error::DexError,
+    state::{CallBackInfo, DexState, UserAccount},
+    utils::check_account_key,
+};
+use asset_agnostic_orderbook::state::{
+    critbit::Slab, get_side_from_order_id, market_state::MarketState, AccountTag, Side,
+};
+use bonfida_utils::BorshSize;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{bytes_of, try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg,
+    program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a replace_order instruction.
+*/
+pub struct Params {
+    #[cfg(all(not(target_arch = "aarch64"), not(feature = "aarch64-test")))]
+    /// The order id of the order to replace, or its client order id if `is_client_id` is set
+    pub order_id: u128,
+    #[cfg(any(target_arch = "aarch64", feature = "aarch64-test"))]
+    pub order_id: [u64; 2],
+    /// The index in the user account of the order to replace, ignored if `is_client_id` is set
+    pub order_index: u64,
+    /// Whether `order_id` is a client order id
+    pub is_client_id: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 7],
+    /// The new order
+    pub new_order: new_order::Params,
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let params: &Params =
+        try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    #[cfg(all(not(target_arch = "aarch64"), not(feature = "aarch64-test")))]
+    let order_id = params.order_id;
+    #[cfg(any(target_arch = "aarch64", feature = "aarch64-test"))]
+    let order_id: u128 = bytemuck::cast(params.order_id);
+    if params.new_order.has_session != 0 {
+        msg!("Session orders cannot be replaced");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    {
+        let accounts = Accounts::parse(
+            program_id,
+            accounts,
+            params.new_order.has_discount_token_account != 0,
+            params.new_order.has_oracle_account != 0,
+            false,
+            params.new_order.has_dmm_registry != 0,
+        )?;
+        cancel(
+            program_id,
+            &accounts,
+            order_id,
+            params.order_index,
+            params.is_client_id != 0,
+        )?;
+    }
+
+    new_order::execute(program_id, accounts, bytes_of(&params.new_order))?;
+    Ok(())
+}
+
+/// Cancels the order to replace, which should still rest on the book
+fn cancel(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    order_id: u128,
+    order_index: u64,
+    is_client_id: bool,
+) -> ProgramResult {
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(
+        accounts.bids,
+        &orderbook.bids,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.asks,
+        &orderbook.asks,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    drop(orderbook_guard);
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let mut user_account = UserAccount::from_buffer(&mut user_account_data)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if &user_account.header.market != accounts.market.key {
+        msg!("The provided user account doesn't match the current market");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let order_index = if is_client_id {
+        user_account
+            .find_order_id_and_index_by_client_id(order_id)
+            .map_err(|e| {
+                msg!("No open order with the given client order id!");
+                e
+            })?
+            .0
+    } else {
+        order_index
+    };
+    let order = user_account.read_order(order_index as usize)?;
+    if !is_client_id && order.id != order_id {
+        msg!("Order id does not match with the order at the given index!");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let slot = Clock::get()?.slot;
+    market_state.check_resting_time(order.placed_slot, slot)?;
+
+    // An order which was matched since should not be replaced, as its fill is only pending in the event queue
+    let is_resting = {
+        let (slab_account, slab_tag) = match get_side_from_order_id(order.id) {
+            Side::Bid => (accounts.bids, AccountTag::Bids),
+            Side::Ask => (accounts.asks, AccountTag::Asks),
+        };
+        let mut slab_guard = slab_account.data.borrow_mut();
+        let slab = Slab::<CallBackInfo>::from_buffer(&mut slab_guard, slab_tag)?;
+        slab.find_by_key(order.id).is_some()
+    };
+    if !is_resting {
+        msg!("The order to replace no longer rests on the book");
+        return Err(DexError::OrderNotFound.into());
+    }
+
+    cancel_resting_order(
+        program_id,
+        &market_state,
+        accounts.orderbook,
+        accounts.event_queue,
+        accounts.bids,
+        accounts.asks,
+        &mut user_account,
+        order.id,
+    )?;
+    market_state.order_flow.record_cancels(slot, 1);
+    // The top of book is refreshed by the new order
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Restore an archived user account at its address, with the metrics and metadata it had when it was archived.
+//!
+//! The archive account is closed and its rent returned to the owner, who pays for the new user account.
+use crate::{
+    error::DexError,
+    processor::{
+        archive_user_account::USER_ACCOUNT_ARCHIVE_SEED, initialize_account::create_user_account,
+    },
+    state::{AccountTag, UserAccount, UserAccountArchive, UserAccountHeader},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a restore_user_account instruction.
+*/
+pub struct Params {
+    /// The maximum number of orders the restored user account may hold
+    pub max_orders: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX user account to restore
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The DEX user account owner wallet, which pays for the user account and receives the archive's rent
+    #[cons(writable, signer)]
+    pub user_owner: &'a T,
+
+    /// The user account archive
+    #[cons(writable)]
+    pub archive: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            archive: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(
+            a.user,
+            &system_program::ID,
+            DexError::InvalidStateAccountOwner,
+        )?;
+        check_account_owner(a.archive, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params { max_orders } =
+        try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut archive_data = accounts.archive.data.borrow_mut();
+    let archive = UserAccountArchive::from_buffer(&mut archive_data)?;
+    if &archive.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let user_key_bytes = accounts.user.key.to_bytes();
+    let (archive_key, _) =
+        Pubkey::find_program_address(&[&user_key_bytes, USER_ACCOUNT_ARCHIVE_SEED], program_id);
+    if &archive_key != accounts.archive.key {
+        msg!("The archive doesn't match the user account");
+        return Err(DexError::InvalidUserAccountArchive.into());
+    }
+
+    create_user_account(
+        program_id,
+        accounts.system_program,
+        accounts.user,
+        accounts.user_owner.key,
+        accounts.user_owner,
+        &archive.market,
+        *max_orders,
+        archive.account_index,
+    )?;
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = UserAccount::from_buffer_unchecked(&mut user_account_data)?;
+    let mut header = UserAccountHeader::new(&archive.market, &archive.owner);
+    header.accumulated_rebates = archive.accumulated_rebates;
+    header.accumulated_maker_quote_volume = archive.accumulated_maker_quote_volume;
+    header.accumulated_maker_base_volume = archive.accumulated_maker_base_volume;
+    header.accumulated_taker_quote_volume = archive.accumulated_taker_quote_volume;
+    header.accumulated_taker_base_volume = archive.accumulated_taker_base_volume;
+    header.metadata = archive.metadata;
+    header.last_active_slot = archive.last_active_slot;
+    header.has_settle_allowlist = archive.has_settle_allowlist;
+    *user_account.header = header;
+
+    archive.tag = AccountTag::Closed as u64;
+
+    let mut lamports = accounts.archive.lamports.borrow_mut();
+    let mut owner_lamports = accounts.user_owner.lamports.borrow_mut();
+
+    **owner_lamports += **lamports;
+    **lamports = 0;
+
+    Ok(())
+}
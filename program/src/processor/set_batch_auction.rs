@@ -0,0 +1,196 @@
+//! Switch a market between continuous matching and frequent batch auctions. This is an admin instruction
+//!
+//! In batch auction mode, orders are collected in the market's batch auction account for a fixed number of slots
+//! before being crossed at a uniform clearing price by the permissionless match_batch instruction.
+use crate::{
+    error::DexError,
+    state::{
+        AccountTag, BatchAuction, BatchAuctionHeader, BatchOrder, DexState,
+        BATCH_AUCTION_HEADER_LEN,
+    },
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the batch auction account of a market
+pub const BATCH_AUCTION_SEED: &[u8] = b"batch_auction";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a set_batch_auction instruction.
+*/
+pub struct Params {
+    /// The length in slots of a batch auction, or zero to go back to continuous matching
+    pub batch_slots: u64,
+    /// The maximum number of orders in a batch, used when the batch auction account is created
+    pub capacity: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market batch auction account
+    #[cons(writable)]
+    pub batch_auction: &'a T,
+
+    /// The market admin account, which pays for the batch auction account creation
+    #[cons(writable, signer)]
+    pub market_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            batch_auction: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        batch_slots,
+        capacity,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+
+    if accounts.batch_auction.data_is_empty() {
+        create_batch_auction(program_id, &accounts, *capacity)?;
+    }
+    check_account_owner(
+        accounts.batch_auction,
+        program_id,
+        DexError::InvalidBatchAuctionAccount,
+    )?;
+
+    let mut batch_guard = accounts.batch_auction.data.borrow_mut();
+    let batch = BatchAuction::from_buffer(&mut batch_guard)?;
+    if &batch.header.market != accounts.market.key {
+        msg!("The batch auction account does not belong to this market");
+        return Err(DexError::InvalidBatchAuctionAccount.into());
+    }
+
+    // Pending orders can only be matched or refunded by match_batch
+    if *batch_slots == 0 && batch.header.number_of_orders != 0 {
+        msg!("The pending batch should be matched before switching back to continuous matching");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    market_state.batch_slots = *batch_slots;
+
+    Ok(())
+}
+
+fn create_batch_auction(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    capacity: u64,
+) -> ProgramResult {
+    if capacity == 0 {
+        msg!("The batch auction capacity should be nonzero!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let market_key_bytes = accounts.market.key.to_bytes();
+    let (batch_auction_key, batch_auction_nonce) =
+        Pubkey::find_program_address(&[&market_key_bytes, BATCH_AUCTION_SEED], program_id);
+
+    if &batch_auction_key != accounts.batch_auction.key {
+        msg!("Provided an invalid batch auction account for the specified market");
+        return Err(DexError::InvalidBatchAuctionAccount.into());
+    }
+
+    let space = capacity
+        .checked_mul(BatchOrder::LEN as u64)
+        .and_then(|n| n.checked_add(BATCH_AUCTION_HEADER_LEN as u64))
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let lamports = Rent::get()?.minimum_balance(space as usize);
+
+    let allocate_account = create_account(
+        accounts.market_admin.key,
+        accounts.batch_auction.key,
+        lamports,
+        space,
+        program_id,
+    );
+
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.market_admin.clone(),
+            accounts.batch_auction.clone(),
+        ],
+        &[&[
+            &market_key_bytes,
+            BATCH_AUCTION_SEED,
+            &[batch_auction_nonce],
+        ]],
+    )?;
+
+    let mut batch_auction_data = accounts.batch_auction.data.borrow_mut();
+    let batch = BatchAuction::from_buffer_unchecked(&mut batch_auction_data)?;
+
+    *(batch.header) = BatchAuctionHeader {
+        tag: AccountTag::BatchAuction as u64,
+        market: *accounts.market.key,
+        start_slot: 0,
+        number_of_orders: 0,
+    };
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Configure the fee charged when an order is cancelled shortly after its placement. This is an admin instruction
+//!
+//! The fee is taken from the free quote balance of the user account when an order of which no fill was consumed is
+//! cancelled within the given number of slots, which discourages quote stuffing. New markets start without a fee.
+use crate::{
+    error::DexError,
+    state::DexState,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a set_cancel_fee instruction.
+*/
+pub struct Params {
+    /// The raw quote amount charged per cancelled order, zero to disable the fee
+    pub cancel_fee: u64,
+    /// The number of slots after the placement of an order during which its cancellation is charged
+    pub cancel_fee_slots: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        cancel_fee,
+        cancel_fee_slots,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if *cancel_fee != 0 && *cancel_fee_slots == 0 {
+        msg!("A cancel fee requires a nonzero number of slots");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+
+    market_state.cancel_fee = *cancel_fee;
+    market_state.cancel_fee_slots = *cancel_fee_slots;
+
+    Ok(())
+}
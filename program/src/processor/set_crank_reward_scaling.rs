@@ -0,0 +1,102 @@
+//! Configure the scaling of the crank reward with the age of the pending events. This is an admin instruction
+//!
+//! While enabled, the crank fee share is multiplied by one plus the number of `age_slots` periods elapsed since the
+//! event queue stopped being empty, up to `max_multiplier`, so that stale queues become increasingly attractive to
+//! crank. The scaled reward never exceeds the fees generated by the crank.
+use crate::{
+    error::DexError,
+    state::DexState,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// The largest configurable crank reward multiplier
+pub const MAX_CRANK_REWARD_MULTIPLIER: u64 = 20;
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a set_crank_reward_scaling instruction.
+*/
+pub struct Params {
+    /// The age in slots of the pending events which raises the crank reward multiplier by one, or zero to disable
+    /// the scaling
+    pub age_slots: u64,
+    /// The maximum crank reward multiplier
+    pub max_multiplier: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        age_slots,
+        max_multiplier,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if *age_slots != 0 && (*max_multiplier == 0 || *max_multiplier > MAX_CRANK_REWARD_MULTIPLIER) {
+        msg!(
+            "The maximum multiplier should be between 1 and {}",
+            MAX_CRANK_REWARD_MULTIPLIER
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+
+    market_state.crank_reward_age_slots = *age_slots;
+    market_state.max_crank_reward_multiplier = *max_multiplier;
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+//! Set the listing fee charged by create_market. This is an admin instruction of the deployment
+//!
+//! The settings are held by a single program derived account, created on the first call and signed for by the sweep
+//! authority. A zero listing fee allows free market creation again.
+use crate::{
+    error::DexError,
+    processor::SWEEP_AUTHORITY,
+    state::{AccountTag, CreateMarketConfig, CREATE_MARKET_CONFIG_LEN},
+    utils::{check_account_key, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the create market config account of the deployment
+pub const CREATE_MARKET_CONFIG_SEED: &[u8] = b"create_market_config";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a set_create_market_config instruction.
+*/
+pub struct Params {
+    /// The fee charged to create a market, 0 to allow free market creation
+    pub listing_fee: u64,
+    /// The mint of the listing fee, the default pubkey for a fee in lamports
+    pub fee_mint: Pubkey,
+    /// The account receiving the listing fees, a token account of the fee mint for token fees
+    pub treasury: Pubkey,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The create market config account
+    #[cons(writable)]
+    pub create_market_config: &'a T,
+
+    /// The sweep authority, which pays for the config account creation
+    #[cons(writable, signer)]
+    pub sweep_authority: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            create_market_config: next_account_info(accounts_iter)?,
+            sweep_authority: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.sweep_authority).map_err(|e| {
+            msg!("The sweep authority should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_key(
+            a.sweep_authority,
+            &SWEEP_AUTHORITY,
+            DexError::InvalidSweepAuthority,
+        )?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(accounts)?;
+    let Params {
+        listing_fee,
+        fee_mint,
+        treasury,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let (config_key, config_nonce) =
+        Pubkey::find_program_address(&[CREATE_MARKET_CONFIG_SEED], program_id);
+    if &config_key != accounts.create_market_config.key {
+        msg!("Provided an invalid create market config account");
+        return Err(DexError::InvalidCreateMarketConfigAccount.into());
+    }
+
+    if accounts.create_market_config.data_is_empty() {
+        let lamports = Rent::get()?.minimum_balance(CREATE_MARKET_CONFIG_LEN);
+        let allocate_account = create_account(
+            accounts.sweep_authority.key,
+            accounts.create_market_config.key,
+            lamports,
+            CREATE_MARKET_CONFIG_LEN as u64,
+            program_id,
+        );
+        invoke_signed(
+            &allocate_account,
+            &[
+                accounts.system_program.clone(),
+                accounts.sweep_authority.clone(),
+                accounts.create_market_config.clone(),
+            ],
+            &[&[CREATE_MARKET_CONFIG_SEED, &[config_nonce]]],
+        )?;
+    }
+
+    let mut config_data = accounts.create_market_config.data.borrow_mut();
+    let config = CreateMarketConfig::from_buffer_unchecked(&mut config_data)?;
+    *config = CreateMarketConfig {
+        tag: AccountTag::CreateMarketConfig as u64,
+        listing_fee: *listing_fee,
+        fee_mint: *fee_mint,
+        treasury: *treasury,
+    };
+    msg!("The listing fee is now {}", listing_fee);
+
+    Ok(())
+}
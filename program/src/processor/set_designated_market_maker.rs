@@ -0,0 +1,198 @@
+//! Add or remove a user account from the designated market maker registry of a market. This is an admin instruction
+//!
+//! Designated market makers pay no taker fees and earn an enhanced maker rebate on the orders they place
+//! with the registry account.
+use crate::{
+    error::DexError,
+    state::{AccountTag, DexState, DmmRegistry, DmmRegistryHeader, DMM_REGISTRY_HEADER_LEN},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+
+/// The seed used to derive the designated market maker registry of a market
+pub const DMM_REGISTRY_SEED: &[u8] = b"dmm_registry";
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a set_designated_market_maker instruction.
+*/
+pub struct Params {
+    /// The user account to add or remove
+    pub user_account: Pubkey,
+    /// The maximum number of designated market makers, used when the registry account is created
+    pub capacity: u64,
+    /// 1 to add the user account to the registry, 0 to remove it
+    pub is_designated: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 7],
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The market designated market maker registry
+    #[cons(writable)]
+    pub dmm_registry: &'a T,
+
+    /// The market admin account, which pays for the registry account creation
+    #[cons(writable, signer)]
+    pub market_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            system_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            dmm_registry: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        user_account,
+        capacity,
+        is_designated,
+        ..
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if *is_designated > 1 {
+        msg!("is_designated should be 0 or 1");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+    drop(market_state);
+
+    if accounts.dmm_registry.data_is_empty() {
+        create_dmm_registry(program_id, &accounts, *capacity)?;
+    }
+    check_account_owner(
+        accounts.dmm_registry,
+        program_id,
+        DexError::InvalidDmmRegistryAccount,
+    )?;
+
+    let mut registry_guard = accounts.dmm_registry.data.borrow_mut();
+    let mut registry = DmmRegistry::from_buffer(&mut registry_guard)?;
+    if &registry.header.market != accounts.market.key {
+        msg!("The designated market maker registry does not belong to this market");
+        return Err(DexError::InvalidDmmRegistryAccount.into());
+    }
+
+    if *is_designated == 1 {
+        registry.insert(user_account)?;
+    } else if !registry.remove(user_account) {
+        msg!("The user account is not a designated market maker");
+        return Err(DexError::NoOp.into());
+    }
+
+    Ok(())
+}
+
+fn create_dmm_registry(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    capacity: u64,
+) -> ProgramResult {
+    if capacity == 0 {
+        msg!("The registry capacity should be nonzero!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let market_key_bytes = accounts.market.key.to_bytes();
+    let (dmm_registry_key, dmm_registry_nonce) =
+        Pubkey::find_program_address(&[&market_key_bytes, DMM_REGISTRY_SEED], program_id);
+
+    if &dmm_registry_key != accounts.dmm_registry.key {
+        msg!("Provided an invalid designated market maker registry for the specified market");
+        return Err(DexError::InvalidDmmRegistryAccount.into());
+    }
+
+    let space = capacity
+        .checked_mul(32)
+        .and_then(|n| n.checked_add(DMM_REGISTRY_HEADER_LEN as u64))
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let lamports = Rent::get()?.minimum_balance(space as usize);
+
+    let allocate_account = create_account(
+        accounts.market_admin.key,
+        accounts.dmm_registry.key,
+        lamports,
+        space,
+        program_id,
+    );
+
+    invoke_signed(
+        &allocate_account,
+        &[
+            accounts.system_program.clone(),
+            accounts.market_admin.clone(),
+            accounts.dmm_registry.clone(),
+        ],
+        &[&[&market_key_bytes, DMM_REGISTRY_SEED, &[dmm_registry_nonce]]],
+    )?;
+
+    let mut registry_data = accounts.dmm_registry.data.borrow_mut();
+    let registry = DmmRegistry::from_buffer_unchecked(&mut registry_data)?;
+
+    *(registry.header) = DmmRegistryHeader {
+        tag: AccountTag::DmmRegistry as u64,
+        market: *accounts.market.key,
+        number_of_entries: 0,
+    };
+
+    Ok(())
+}
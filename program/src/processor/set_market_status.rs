@@ -0,0 +1,113 @@
+//! Update the trading status of a market. This is an admin instruction
+//!
+//! A market halted by its emergency authority can only be resumed once the emergency halt timelock has elapsed.
+use super::emergency_halt::EMERGENCY_RESUME_DELAY;
+use crate::{
+    error::DexError,
+    state::{DexState, MarketStatus},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use num_traits::FromPrimitive;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use std::convert::TryFrom;
+
+#[derive(Clone, Copy, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a set_market_status instruction.
+*/
+pub struct Params {
+    /// The new market status (u64 for padding)
+    pub status: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params { status } =
+        try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let status: MarketStatus = u8::try_from(*status)
+        .ok()
+        .and_then(FromPrimitive::from_u8)
+        .ok_or_else(|| {
+            msg!("Invalid market status");
+            ProgramError::InvalidArgument
+        })?;
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+
+    if market_state.is_migrating() || status == MarketStatus::Migrating {
+        msg!("Migrations can only be started with the freeze_and_export instruction and cannot be undone");
+        return Err(DexError::MarketMigrating.into());
+    }
+
+    if status == MarketStatus::Active && market_state.emergency_halt_timestamp != 0 {
+        let resume_timestamp = market_state.emergency_halt_timestamp + EMERGENCY_RESUME_DELAY;
+        if Clock::get()?.unix_timestamp < resume_timestamp {
+            msg!(
+                "The market was halted by its emergency authority and cannot be resumed before {}",
+                resume_timestamp
+            );
+            return Err(DexError::EmergencyHaltTimelock.into());
+        }
+    }
+
+    market_state.status = status as u8;
+    msg!("Market status set to {:?}", status);
+
+    Ok(())
+}
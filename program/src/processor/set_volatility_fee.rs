@@ -0,0 +1,113 @@
+//! Configure the volatility-scaled taker fee of a market. This is an admin instruction
+//!
+//! While enabled, the taker fee is multiplied by one plus the number of `threshold_bps` steps the price has moved
+//! since the start of the current volatility window, up to `max_multiplier`. The surcharge is credited to the fee
+//! authority.
+use crate::{
+    error::DexError,
+    state::DexState,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// The largest configurable taker fee multiplier
+pub const MAX_VOLATILITY_MULTIPLIER: u64 = 10;
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a set_volatility_fee instruction.
+*/
+pub struct Params {
+    /// The length in slots of the window over which price movement is measured, or zero to disable the surcharge
+    pub window_slots: u64,
+    /// The price movement in basis points which raises the taker fee multiplier by one
+    pub threshold_bps: u64,
+    /// The maximum taker fee multiplier
+    pub max_multiplier: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        window_slots,
+        threshold_bps,
+        max_multiplier,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if *window_slots != 0
+        && (*threshold_bps == 0
+            || *max_multiplier == 0
+            || *max_multiplier > MAX_VOLATILITY_MULTIPLIER)
+    {
+        msg!(
+            "The threshold should be nonzero and the maximum multiplier should be between 1 and {}",
+            MAX_VOLATILITY_MULTIPLIER
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+
+    market_state.volatility_window_slots = *window_slots;
+    market_state.volatility_threshold_bps = *threshold_bps;
+    market_state.max_volatility_multiplier = *max_multiplier;
+    // Start measuring from the next taker fill
+    market_state.reference_price = 0;
+    market_state.reference_slot = 0;
+    market_state.last_price = 0;
+
+    Ok(())
+}
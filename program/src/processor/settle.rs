@@ -1,26 +1,52 @@
 //! Extract available base and quote token assets from a user account
+//!
+//! When the user account has registered a settle allowlist, the allowlist account must be provided and both
+//! destinations must be part of it.
+//!
+//! The transferred amounts are set as the instruction's return data, encoded as a borsh [`SettleSummary`].
+//!
+//! With `unwrap_sol`, the destination account holding wrapped SOL is closed once settled, so that the owner wallet
+//! receives native SOL. Empty instruction data, as sent by older clients, settles without unwrapping.
 use crate::{
     error::DexError,
-    state::{DexState, UserAccount},
+    state::{DexState, SettleAllowlist, UserAccount},
     utils::{check_account_key, check_account_owner, check_signer},
 };
 use bonfida_utils::BorshSize;
 use bonfida_utils::InstructionsAccount;
 use borsh::BorshDeserialize;
 use borsh::BorshSerialize;
-use bytemuck::{Pod, Zeroable};
+use bytemuck::{try_from_bytes, Pod, Zeroable};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program::invoke_signed,
+    program::{invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
+    program_pack::Pack,
     pubkey::Pubkey,
 };
 
 #[derive(Clone, Copy, BorshDeserialize, BorshSerialize, BorshSize, Pod, Zeroable)]
 #[repr(C)]
-pub struct Params {}
+/**
+The required arguments for a settle instruction.
+*/
+pub struct Params {
+    /// Whether the destination account holding wrapped SOL should be closed, delivering native SOL to the owner wallet
+    pub unwrap_sol: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 7],
+}
+
+/// The amounts transferred out of the market vaults by a settle instruction
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct SettleSummary {
+    /// The transferred base amount
+    pub base_qty: u64,
+    /// The transferred quote amount
+    pub quote_qty: u64,
+}
 
 #[derive(InstructionsAccount)]
 pub struct Accounts<'a, T> {
@@ -45,8 +71,8 @@ pub struct Accounts<'a, T> {
     #[cons(writable)]
     pub user: &'a T,
 
-    /// The DEX user account owner wallet
-    #[cons(signer)]
+    /// The DEX user account owner wallet, which receives the unwrapped SOL
+    #[cons(writable, signer)]
     pub user_owner: &'a T,
 
     /// The destination base token account
@@ -56,6 +82,9 @@ pub struct Accounts<'a, T> {
     /// The destination quote token account
     #[cons(writable)]
     pub destination_quote_account: &'a T,
+
+    /// The settle allowlist of the user account, required when one has been registered
+    pub settle_allowlist: Option<&'a T>,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
@@ -74,6 +103,7 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             user_owner: next_account_info(accounts_iter)?,
             destination_base_account: next_account_info(accounts_iter)?,
             destination_quote_account: next_account_info(accounts_iter)?,
+            settle_allowlist: next_account_info(accounts_iter).ok(),
         };
         check_signer(a.user_owner).map_err(|e| {
             msg!("The user account owner should be a signer for this transaction!");
@@ -107,69 +137,153 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
     }
 }
 
-pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
     let accounts = Accounts::parse(program_id, accounts)?;
+    let params = if instruction_data.is_empty() {
+        Params::zeroed()
+    } else {
+        *try_from_bytes::<Params>(instruction_data)
+            .map_err(|_| ProgramError::InvalidInstructionData)?
+    };
+
+    settle_user_account(program_id, &accounts, &params)
+}
 
+/// Transfers the free balances of the user account to the destination accounts
+pub(crate) fn settle_user_account(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    params: &Params,
+) -> ProgramResult {
     let market_state = DexState::get(accounts.market)?;
 
     let mut user_account_data = accounts.user.data.borrow_mut();
-    let mut user_account = accounts.load_user_account(&mut user_account_data)?;
+    let user_account = accounts.load_user_account(&mut user_account_data)?;
+
+    check_accounts(program_id, &market_state, accounts).unwrap();
+    if user_account.header.has_settle_allowlist != 0 {
+        check_settle_allowlist(program_id, accounts)?;
+    }
 
-    check_accounts(program_id, &market_state, &accounts).unwrap();
+    let quote_qty = user_account.header.quote_token_free;
+    let base_qty = user_account.header.base_token_free;
+    transfer_from_vault(
+        accounts,
+        &market_state,
+        accounts.quote_vault,
+        accounts.destination_quote_account,
+        quote_qty,
+    )?;
+    transfer_from_vault(
+        accounts,
+        &market_state,
+        accounts.base_vault,
+        accounts.destination_base_account,
+        base_qty,
+    )?;
 
-    let transfer_quote_instruction = spl_token::instruction::transfer(
+    user_account.header.quote_token_free = 0;
+    user_account.header.base_token_free = 0;
+
+    if params.unwrap_sol != 0 {
+        unwrap_sol(accounts)?;
+    }
+
+    set_return_data(
+        &SettleSummary {
+            base_qty,
+            quote_qty,
+        }
+        .try_to_vec()?,
+    );
+
+    #[cfg(feature = "strict-checks")]
+    crate::invariants::check_vault_covers_fees(&market_state, accounts.quote_vault)?;
+
+    Ok(())
+}
+
+/// Transfers tokens out of a market vault, skipping empty amounts to save compute
+pub(crate) fn transfer_from_vault<'a>(
+    accounts: &Accounts<'_, AccountInfo<'a>>,
+    market_state: &DexState,
+    vault: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let transfer_instruction = spl_token::instruction::transfer(
         &spl_token::ID,
-        &market_state.quote_vault,
-        accounts.destination_quote_account.key,
+        vault.key,
+        destination.key,
         accounts.market_signer.key,
         &[],
-        user_account.header.quote_token_free,
+        amount,
     )?;
 
     invoke_signed(
-        &transfer_quote_instruction,
+        &transfer_instruction,
         &[
             accounts.spl_token_program.clone(),
-            accounts.quote_vault.clone(),
-            accounts.destination_quote_account.clone(),
+            vault.clone(),
+            destination.clone(),
             accounts.market_signer.clone(),
         ],
         &[&[
             &accounts.market.key.to_bytes(),
             &[market_state.signer_nonce as u8],
         ]],
-    )?;
+    )
+}
+
+/// Closes the destination account holding wrapped SOL, which sends its lamports to the owner wallet
+fn unwrap_sol(accounts: &Accounts<AccountInfo>) -> ProgramResult {
+    let mut wrapped_sol_account = None;
+    for destination in [
+        accounts.destination_quote_account,
+        accounts.destination_base_account,
+    ] {
+        let token_account = spl_token::state::Account::unpack(&destination.data.borrow())?;
+        if token_account.mint == spl_token::native_mint::ID {
+            if &token_account.owner != accounts.user_owner.key {
+                msg!("The wrapped SOL account should be owned by the user account owner");
+                return Err(ProgramError::InvalidArgument);
+            }
+            wrapped_sol_account = Some(destination);
+            break;
+        }
+    }
+    let wrapped_sol_account = wrapped_sol_account.ok_or_else(|| {
+        msg!("None of the destination accounts holds wrapped SOL");
+        ProgramError::InvalidArgument
+    })?;
 
-    let transfer_base_instruction = spl_token::instruction::transfer(
+    let close_instruction = spl_token::instruction::close_account(
         &spl_token::ID,
-        &market_state.base_vault,
-        accounts.destination_base_account.key,
-        accounts.market_signer.key,
+        wrapped_sol_account.key,
+        accounts.user_owner.key,
+        accounts.user_owner.key,
         &[],
-        user_account.header.base_token_free,
     )?;
-
-    invoke_signed(
-        &transfer_base_instruction,
+    invoke(
+        &close_instruction,
         &[
             accounts.spl_token_program.clone(),
-            accounts.base_vault.clone(),
-            accounts.destination_base_account.clone(),
-            accounts.market_signer.clone(),
+            wrapped_sol_account.clone(),
+            accounts.user_owner.clone(),
+            accounts.user_owner.clone(),
         ],
-        &[&[
-            &accounts.market.key.to_bytes(),
-            &[market_state.signer_nonce as u8],
-        ]],
-    )?;
-
-    user_account.header.quote_token_free = 0;
-    user_account.header.base_token_free = 0;
-
-    Ok(())
+    )
 }
 
-fn check_accounts(
+pub(crate) fn check_accounts(
     program_id: &Pubkey,
     market_state: &DexState,
     accounts: &Accounts<AccountInfo>,
@@ -199,3 +313,35 @@ fn check_accounts(
 
     Ok(())
 }
+
+pub(crate) fn check_settle_allowlist(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+) -> ProgramResult {
+    let settle_allowlist_account = accounts.settle_allowlist.ok_or_else(|| {
+        msg!("The user account has a settle allowlist which must be provided");
+        DexError::InvalidSettleAllowlistAccount
+    })?;
+    check_account_owner(
+        settle_allowlist_account,
+        program_id,
+        DexError::InvalidSettleAllowlistAccount,
+    )?;
+    let mut settle_allowlist_data = settle_allowlist_account.data.borrow_mut();
+    let settle_allowlist = SettleAllowlist::from_buffer(&mut settle_allowlist_data)?;
+    if &settle_allowlist.user_account != accounts.user.key {
+        msg!("The settle allowlist does not belong to this user account");
+        return Err(DexError::InvalidSettleAllowlistAccount.into());
+    }
+    for destination in [
+        accounts.destination_base_account,
+        accounts.destination_quote_account,
+    ] {
+        if !settle_allowlist.allows(destination.key) {
+            msg!("The destination {} is not allowed", destination.key);
+            return Err(DexError::SettleDestinationNotAllowed.into());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Extract available base and quote token assets from a user account into the associated token accounts of its
+//! owner, creating them when they don't exist
+//!
+//! This lets the free balances of a user account be settled after its owner has closed their token accounts, as
+//! happens with crank-driven settlement. The owner wallet pays the rent of the created accounts. Destinations which
+//! already exist are settled into as is, and the settle allowlist of the user account applies in the same way as
+//! for settle.
+use crate::{
+    error::DexError,
+    state::DexState,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account::instruction::create_associated_token_account;
+
+use super::settle;
+
+#[derive(Clone, Copy, BorshDeserialize, BorshSerialize, BorshSize, Pod, Zeroable)]
+#[repr(C)]
+/**
+The required arguments for a settle_to_associated_accounts instruction.
+*/
+pub struct Params {
+    /// Whether the destination account holding wrapped SOL should be closed, delivering native SOL to the owner wallet
+    pub unwrap_sol: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 7],
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The spl token program
+    pub spl_token_program: &'a T,
+
+    /// The system program
+    pub system_program: &'a T,
+
+    /// The spl associated token account program
+    pub associated_token_program: &'a T,
+
+    /// The rent sysvar, required by the associated token account program
+    pub rent_sysvar: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The base token mint
+    pub base_mint: &'a T,
+
+    /// The quote token mint
+    pub quote_mint: &'a T,
+
+    /// The base token vault
+    #[cons(writable)]
+    pub base_vault: &'a T,
+
+    /// The quote token vault
+    #[cons(writable)]
+    pub quote_vault: &'a T,
+
+    /// The DEX market signer account
+    pub market_signer: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The DEX user account owner wallet, which pays for the creation of the destination accounts
+    #[cons(writable, signer)]
+    pub user_owner: &'a T,
+
+    /// The associated base token account of the owner
+    #[cons(writable)]
+    pub destination_base_account: &'a T,
+
+    /// The associated quote token account of the owner
+    #[cons(writable)]
+    pub destination_quote_account: &'a T,
+
+    /// The settle allowlist of the user account, required when one has been registered
+    pub settle_allowlist: Option<&'a T>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+            associated_token_program: next_account_info(accounts_iter)?,
+            rent_sysvar: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            base_mint: next_account_info(accounts_iter)?,
+            quote_mint: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            market_signer: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            destination_base_account: next_account_info(accounts_iter)?,
+            destination_quote_account: next_account_info(accounts_iter)?,
+            settle_allowlist: next_account_info(accounts_iter).ok(),
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.spl_token_program,
+            &spl_token::ID,
+            DexError::InvalidSplTokenProgram,
+        )?;
+        check_account_key(
+            a.system_program,
+            &system_program::ID,
+            DexError::InvalidSystemProgramAccount,
+        )?;
+        check_account_key(
+            a.associated_token_program,
+            &spl_associated_token_account::ID,
+            DexError::InvalidAssociatedTokenProgram,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+
+    /// The same accounts, laid out as those of a settle instruction
+    fn as_settle_accounts(&self) -> settle::Accounts<'a, AccountInfo<'b>> {
+        settle::Accounts {
+            spl_token_program: self.spl_token_program,
+            market: self.market,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            market_signer: self.market_signer,
+            user: self.user,
+            user_owner: self.user_owner,
+            destination_base_account: self.destination_base_account,
+            destination_quote_account: self.destination_quote_account,
+            settle_allowlist: self.settle_allowlist,
+        }
+    }
+
+    /// Creates the associated token account of the owner for the given mint, unless it already exists
+    fn create_destination_if_missing(
+        &self,
+        destination: &AccountInfo<'b>,
+        mint: &AccountInfo<'b>,
+    ) -> ProgramResult {
+        if destination.owner != &system_program::ID {
+            return Ok(());
+        }
+        msg!("Creating the associated token account {}", destination.key);
+        invoke(
+            &create_associated_token_account(self.user_owner.key, self.user_owner.key, mint.key),
+            &[
+                self.associated_token_program.clone(),
+                self.user_owner.clone(),
+                destination.clone(),
+                self.user_owner.clone(),
+                mint.clone(),
+                self.system_program.clone(),
+                self.spl_token_program.clone(),
+                self.rent_sysvar.clone(),
+            ],
+        )
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let params = try_from_bytes::<Params>(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let settle_accounts = accounts.as_settle_accounts();
+    {
+        let market_state = DexState::get(accounts.market)?;
+        if accounts.base_mint.key != &market_state.base_mint
+            || accounts.quote_mint.key != &market_state.quote_mint
+        {
+            msg!("The provided mints don't match the market");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // The allowlist is checked before paying for accounts which could not be settled into
+        let mut user_account_data = accounts.user.data.borrow_mut();
+        let user_account = settle_accounts.load_user_account(&mut user_account_data)?;
+        if user_account.header.has_settle_allowlist != 0 {
+            settle::check_settle_allowlist(program_id, &settle_accounts)?;
+        }
+    }
+
+    accounts
+        .create_destination_if_missing(accounts.destination_base_account, accounts.base_mint)?;
+    accounts
+        .create_destination_if_missing(accounts.destination_quote_account, accounts.quote_mint)?;
+
+    settle::settle_user_account(
+        program_id,
+        &settle_accounts,
+        &settle::Params {
+            unwrap_sol: params.unwrap_sol,
+            _padding: [0; 7],
+        },
+    )
+}
@@ -1,7 +1,12 @@
+//! Execute a swap against the orderbook without a user account.
+//!
+//! The order is matched like an ImmediateOrCancel order and is never posted. The proceeds are settled to the caller's
+//! token accounts within the instruction, so that routers can trade on a market without initializing an account,
+//! waiting for the crank and settling.
 use crate::{
     error::DexError,
-    state::{CallBackInfo, DexState, FeeTier},
-    utils::{check_account_key, check_account_owner, check_signer},
+    state::{CallBackInfo, DexState, FeeLog, FeeTier, FillLog},
+    utils::{check_account_key, check_account_owner, check_signer, get_oracle_price},
 };
 use asset_agnostic_orderbook::state::{SelfTradeBehavior, Side};
 use asset_agnostic_orderbook::{error::AoError, state::AccountTag};
@@ -13,6 +18,7 @@ use bytemuck::{try_from_bytes, Pod, Zeroable};
 use num_traits::FromPrimitive;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program::invoke,
@@ -20,6 +26,7 @@ use solana_program::{
     program_error::{PrintProgramError, ProgramError},
     pubkey::Pubkey,
     system_program,
+    sysvar::Sysvar,
 };
 
 use super::REFERRAL_MASK;
@@ -27,7 +34,7 @@ use super::REFERRAL_MASK;
 #[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
 #[repr(C)]
 /**
-The required arguments for a new_order instruction.
+The required arguments for a swap instruction.
 */
 pub struct Params {
     /// For bids, the min output quantity. For asks, the exact input quantity.
@@ -42,8 +49,10 @@ pub struct Params {
     pub side: u8,
     /// Whether or not the optional discount token account was given
     pub has_discount_token_account: u8,
+    /// Whether or not the optional oracle account was given
+    pub has_oracle_account: u8,
     /// To eliminate implicit padding
-    pub _padding: [u8; 6],
+    pub _padding: [u8; 5],
 }
 
 #[derive(InstructionsAccount)]
@@ -100,7 +109,10 @@ pub struct Accounts<'a, T> {
     /// The optional SRM or MSRM discount token account (must be owned by the user wallet)
     pub discount_token_account: Option<&'a T>,
 
-    /// The optional referrer's token account which will receive a 20% cut of the fees
+    /// The optional oracle price account, required if the market has an oracle
+    pub oracle: Option<&'a T>,
+
+    /// The optional referrer's token account which will receive the market's `referral_share_bps` share of the fees
     #[cons(writable)]
     pub fee_referral_account: Option<&'a T>,
 }
@@ -110,6 +122,7 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
         program_id: &Pubkey,
         accounts: &'a [AccountInfo<'b>],
         has_discount_token_account: bool,
+        has_oracle_account: bool,
     ) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
         let a = Self {
@@ -131,6 +144,11 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             } else {
                 None
             },
+            oracle: if has_oracle_account {
+                next_account_info(accounts_iter).ok()
+            } else {
+                None
+            },
             fee_referral_account: next_account_info(accounts_iter).ok(),
         };
         check_signer(a.user_owner).map_err(|e| {
@@ -171,11 +189,20 @@ pub(crate) fn process(
         mut quote_qty,
         match_limit,
         has_discount_token_account,
+        has_oracle_account,
         _padding: _,
     } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
-    let accounts = Accounts::parse(program_id, accounts, *has_discount_token_account != 0)?;
+    let accounts = Accounts::parse(
+        program_id,
+        accounts,
+        *has_discount_token_account != 0,
+        *has_oracle_account != 0,
+    )?;
 
-    let market_state = DexState::get(accounts.market)?;
+    let mut market_state = DexState::get(accounts.market)?;
+    market_state.check_active()?;
+    market_state.check_side_active(*side)?;
+    market_state.check_continuous()?;
 
     // Check the order size
     if base_qty < &market_state.min_base_order_size {
@@ -183,7 +210,7 @@ pub(crate) fn process(
         return Err(ProgramError::InvalidArgument);
     }
 
-    check_accounts(program_id, &market_state, &accounts).unwrap();
+    check_accounts(program_id, &market_state, &accounts)?;
     let fee_tier = accounts
         .discount_token_account
         .map(|a| FeeTier::get(&market_state, a, accounts.user_owner.key))
@@ -193,9 +220,11 @@ pub(crate) fn process(
         fee_tier: fee_tier as u8
             | ((accounts.fee_referral_account.is_some() as u8) * REFERRAL_MASK),
     };
+    let slot = Clock::get()?.slot;
+    let fee_multiplier = market_state.volatility_multiplier(slot);
     if *side == Side::Bid as u8 {
         // We make sure to leave enough quote quantity to pay for taker fees in the worst case
-        quote_qty = fee_tier.remove_taker_fee(quote_qty);
+        quote_qty = fee_tier.remove_scaled_taker_fee(quote_qty, fee_multiplier);
     }
 
     let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
@@ -250,8 +279,18 @@ pub(crate) fn process(
     market_state
         .unscale_order_summary(&mut order_summary)
         .unwrap();
-
-    let referral_fee = fee_tier.referral_fee(order_summary.total_quote_qty);
+    let matched_quote_qty = order_summary.total_quote_qty;
+    // The volatility surcharge is collected upfront as it isn't known to the event queue
+    let volatility_fee = fee_tier
+        .taker_fee(matched_quote_qty)
+        .checked_mul(fee_multiplier - 1)
+        .ok_or(DexError::NumericalOverflow)?;
+
+    let taker_fee = fee_tier.taker_fee(order_summary.total_quote_qty);
+    let referral_fee = fee_tier.referral_fee(
+        order_summary.total_quote_qty,
+        market_state.referral_share_bps,
+    );
     let royalties_fees = order_summary
         .total_quote_qty
         .checked_mul(market_state.royalties_bps)
@@ -262,8 +301,8 @@ pub(crate) fn process(
             Side::Bid => {
                 // We update the order summary to properly handle the FOK order type
 
-                order_summary.total_quote_qty +=
-                    fee_tier.taker_fee(order_summary.total_quote_qty) + royalties_fees;
+                order_summary.total_quote_qty += taker_fee + royalties_fees;
+                order_summary.total_quote_qty += volatility_fee;
 
                 let is_valid = &order_summary.total_base_qty >= base_qty;
 
@@ -274,8 +313,6 @@ pub(crate) fn process(
                 )
             }
             Side::Ask => {
-                let taker_fee = fee_tier.taker_fee(order_summary.total_quote_qty);
-
                 let is_valid = order_summary.total_quote_qty >= quote_qty;
 
                 (
@@ -283,7 +320,7 @@ pub(crate) fn process(
                     order_summary.total_base_qty,
                     order_summary
                         .total_quote_qty
-                        .checked_sub(taker_fee + royalties_fees)
+                        .checked_sub(taker_fee + royalties_fees + volatility_fee)
                         .unwrap(),
                 )
             }
@@ -294,6 +331,15 @@ pub(crate) fn process(
         return Err(DexError::TransactionAborted.into());
     };
 
+    market_state.accumulated_fees = market_state
+        .accumulated_fees
+        .checked_add(volatility_fee)
+        .ok_or(DexError::NumericalOverflow)?;
+    market_state.record_fill_price(order_summary.total_base_qty, matched_quote_qty, slot);
+    if order_summary.total_base_qty != 0 {
+        market_state.record_enqueued_events(slot);
+    }
+
     let base_transfer_params = (
         base_transfer_qty,
         accounts.user_base_account,
@@ -384,6 +430,37 @@ pub(crate) fn process(
         )?;
     }
 
+    market_state.order_flow.record_new_orders(slot, 1);
+    market_state.refresh_top_of_book(accounts.bids, accounts.asks, slot)?;
+
+    if order_summary.total_base_qty != 0 {
+        FillLog {
+            market: *accounts.market.key,
+            side: *side,
+            base_qty: order_summary.total_base_qty,
+            quote_qty: matched_quote_qty,
+            slot,
+            taker: (!market_state.hides_fill_accounts()).then(|| *accounts.user_owner.key),
+            oracle_price: accounts.oracle.map(get_oracle_price).transpose()?.flatten(),
+            client_order_id: None,
+        }
+        .emit()?;
+        FeeLog {
+            market: *accounts.market.key,
+            taker_fee: taker_fee + volatility_fee,
+            royalties_fee: royalties_fees,
+            referral_fee: accounts.fee_referral_account.map_or(0, |_| referral_fee),
+            referrer: accounts.fee_referral_account.map(|a| *a.key),
+        }
+        .emit()?;
+    }
+
+    #[cfg(feature = "strict-checks")]
+    {
+        crate::invariants::check_book_not_crossed(accounts.bids, accounts.asks)?;
+        crate::invariants::check_vault_covers_fees(&market_state, accounts.quote_vault)?;
+    }
+
     Ok(())
 }
 
@@ -419,6 +496,7 @@ fn check_accounts(
         &market_state.quote_vault,
         DexError::InvalidQuoteVaultAccount,
     )?;
+    market_state.check_oracle(accounts.oracle)?;
 
     Ok(())
 }
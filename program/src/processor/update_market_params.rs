@@ -0,0 +1,148 @@
+//! Update the tick size and the minimum base order size of a market. This is an admin instruction
+//!
+//! The tick size can only be changed to a divisor of the current one while orders rest on the book, so that every
+//! resting price stays a multiple of the new tick size. Any tick size can be set on an empty book.
+//!
+//! A new minimum base order size only applies to new orders: resting orders below it are left on the book, where they
+//! can still be matched, cancelled or replaced.
+use crate::{
+    error::DexError,
+    state::{CallBackInfo, DexState},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use asset_agnostic_orderbook::state::{critbit::Slab, market_state::MarketState, AccountTag};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a update_market_params instruction.
+*/
+pub struct Params {
+    /// The new tick size of the orderbook, as a FP32 number of quote lots per base lot
+    pub tick_size: u64,
+    /// The new minimum base order size, in base token units
+    pub min_base_order_size: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The DEX market
+    #[cons(writable)]
+    pub market: &'a T,
+
+    /// The orderbook
+    #[cons(writable)]
+    pub orderbook: &'a T,
+
+    /// The AOB bids shared memory
+    pub bids: &'a T,
+
+    /// The AOB asks shared memory
+    pub asks: &'a T,
+
+    /// The market admin account
+    #[cons(signer)]
+    pub market_admin: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            market_admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(a.market_admin).map_err(|e| {
+            msg!("The market admin should be a signer for this transaction!");
+            e
+        })?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.orderbook, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        tick_size,
+        min_base_order_size,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if *tick_size == 0 {
+        msg!("The tick size should be nonzero");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut market_state = DexState::get(accounts.market)?;
+    check_account_key(
+        accounts.market_admin,
+        &market_state.admin,
+        DexError::InvalidMarketAdminAccount,
+    )?;
+    check_account_key(
+        accounts.orderbook,
+        &market_state.orderbook,
+        DexError::InvalidOrderbookAccount,
+    )?;
+
+    let mut orderbook_guard = accounts.orderbook.data.borrow_mut();
+    let orderbook = MarketState::from_buffer(&mut orderbook_guard, AccountTag::Market)?;
+    check_account_key(
+        accounts.bids,
+        &orderbook.bids,
+        DexError::InvalidOrderbookAccount,
+    )?;
+    check_account_key(
+        accounts.asks,
+        &orderbook.asks,
+        DexError::InvalidOrderbookAccount,
+    )?;
+
+    if orderbook.tick_size % *tick_size != 0 {
+        let mut bids_guard = accounts.bids.data.borrow_mut();
+        let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AccountTag::Bids)?;
+        let mut asks_guard = accounts.asks.data.borrow_mut();
+        let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AccountTag::Asks)?;
+        if bids.find_max().is_some() || asks.find_min().is_some() {
+            msg!("The tick size should divide the current one while orders rest on the book");
+            return Err(DexError::MarketStillActive.into());
+        }
+    }
+
+    orderbook.tick_size = *tick_size;
+    orderbook.min_base_order_size = *min_base_order_size / market_state.base_currency_multiplier;
+    market_state.min_base_order_size = *min_base_order_size;
+
+    msg!(
+        "Updated the tick size to {} and the minimum base order size to {}",
+        tick_size,
+        min_base_order_size
+    );
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Withdraw part of the free base and quote balances of a user account
+//!
+//! Unlike settle, which empties both free balances, only the requested amounts are transferred so that the rest
+//! keeps funding the orders placed with `use_free_balances`. The settle allowlist of the user account applies in the
+//! same way.
+use crate::{
+    error::DexError,
+    state::DexState,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use bonfida_utils::BorshSize;
+use bonfida_utils::InstructionsAccount;
+use borsh::BorshDeserialize;
+use borsh::BorshSerialize;
+use bytemuck::{try_from_bytes, Pod, Zeroable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::settle;
+
+#[derive(Copy, Clone, Zeroable, Pod, BorshDeserialize, BorshSerialize, BorshSize)]
+#[repr(C)]
+/**
+The required arguments for a withdraw instruction.
+*/
+pub struct Params {
+    /// The amount of base tokens to withdraw
+    pub base_qty: u64,
+    /// The amount of quote tokens to withdraw
+    pub quote_qty: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The spl token program
+    pub spl_token_program: &'a T,
+
+    /// The DEX market
+    pub market: &'a T,
+
+    /// The base token vault
+    #[cons(writable)]
+    pub base_vault: &'a T,
+
+    /// The quote token vault
+    #[cons(writable)]
+    pub quote_vault: &'a T,
+
+    /// The DEX market signer account
+    pub market_signer: &'a T,
+
+    /// The DEX user account
+    #[cons(writable)]
+    pub user: &'a T,
+
+    /// The DEX user account owner wallet
+    #[cons(signer)]
+    pub user_owner: &'a T,
+
+    /// The destination base token account
+    #[cons(writable)]
+    pub destination_base_account: &'a T,
+
+    /// The destination quote token account
+    #[cons(writable)]
+    pub destination_quote_account: &'a T,
+
+    /// The settle allowlist of the user account, required when one has been registered
+    pub settle_allowlist: Option<&'a T>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            market_signer: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            destination_base_account: next_account_info(accounts_iter)?,
+            destination_quote_account: next_account_info(accounts_iter)?,
+            settle_allowlist: next_account_info(accounts_iter).ok(),
+        };
+        check_signer(a.user_owner).map_err(|e| {
+            msg!("The user account owner should be a signer for this transaction!");
+            e
+        })?;
+        check_account_key(
+            a.spl_token_program,
+            &spl_token::ID,
+            DexError::InvalidSplTokenProgram,
+        )?;
+        check_account_owner(a.market, program_id, DexError::InvalidStateAccountOwner)?;
+        check_account_owner(a.user, program_id, DexError::InvalidStateAccountOwner)?;
+
+        Ok(a)
+    }
+
+    /// The same accounts, laid out as those of a settle instruction
+    fn as_settle_accounts(&self) -> settle::Accounts<'a, AccountInfo<'b>> {
+        settle::Accounts {
+            spl_token_program: self.spl_token_program,
+            market: self.market,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            market_signer: self.market_signer,
+            user: self.user,
+            user_owner: self.user_owner,
+            destination_base_account: self.destination_base_account,
+            destination_quote_account: self.destination_quote_account,
+            settle_allowlist: self.settle_allowlist,
+        }
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?.as_settle_accounts();
+
+    let Params {
+        base_qty,
+        quote_qty,
+    } = try_from_bytes(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let market_state = DexState::get(accounts.market)?;
+
+    let mut user_account_data = accounts.user.data.borrow_mut();
+    let user_account = accounts.load_user_account(&mut user_account_data)?;
+
+    settle::check_accounts(program_id, &market_state, &accounts)?;
+    if user_account.header.has_settle_allowlist != 0 {
+        settle::check_settle_allowlist(program_id, &accounts)?;
+    }
+
+    if *base_qty > user_account.header.base_token_free
+        || *quote_qty > user_account.header.quote_token_free
+    {
+        msg!(
+            "The free balances of the user account are {} base and {} quote tokens",
+            user_account.header.base_token_free,
+            user_account.header.quote_token_free
+        );
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    settle::transfer_from_vault(
+        &accounts,
+        &market_state,
+        accounts.quote_vault,
+        accounts.destination_quote_account,
+        *quote_qty,
+    )?;
+    settle::transfer_from_vault(
+        &accounts,
+        &market_state,
+        accounts.base_vault,
+        accounts.destination_base_account,
+        *base_qty,
+    )?;
+
+    user_account.header.quote_token_free -= *quote_qty;
+    user_account.header.base_token_free -= *base_qty;
+
+    #[cfg(feature = "strict-checks")]
+    crate::invariants::check_vault_covers_fees(&market_state, accounts.quote_vault)?;
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use asset_agnostic_orderbook::state::{orderbook::CallbackInfo, OrderSummary};
+use asset_agnostic_orderbook::state::{
+    critbit::Slab, orderbook::CallbackInfo, AccountTag as AobAccountTag, OrderSummary,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use bytemuck::{try_cast_slice_mut, try_from_bytes_mut, Pod, Zeroable};
 use num_derive::{FromPrimitive, ToPrimitive};
@@ -9,44 +11,46 @@ use std::{cell::RefMut, convert::TryInto, mem::size_of};
 
 use crate::{
     error::DexError,
-    processor::{MSRM_MINT, REFERRAL_MASK, SRM_MINT},
-    utils::{fp32_div, fp32_mul, FP_32_ONE},
+    processor::{MSRM_MINT, NOTIFY_ON_CROSS_MASK, REFERRAL_MASK, SRM_MINT},
+    utils::{bps_to_fp32, check_account_key, fp32_div, fp32_mul, FP_32_ONE},
 };
 
+pub use crate::common::{SelfTradeBehavior, Side};
+
 #[derive(Clone, Debug, PartialEq, Copy)]
 #[allow(missing_docs)]
 #[repr(u64)]
 pub enum AccountTag {
     Uninitialized,
+    /// A market with the layout of [`DexStateV1`], which should be migrated with `migrate_account`
+    DexStateV1,
+    /// A user account with the layout of [`UserAccountHeaderV1`], which should be migrated with `migrate_account`
+    UserAccountV1,
+    Closed,
     DexState,
     UserAccount,
-    Closed,
-}
-
-#[derive(Clone, Copy, PartialEq, FromPrimitive, ToPrimitive)]
-#[repr(u8)]
-#[allow(missing_docs)]
-pub enum Side {
-    Bid,
-    Ask,
+    MakerRewards,
+    Escrow,
+    BatchAuction,
+    Session,
+    DmmRegistry,
+    CrankerIdentity,
+    SettleAllowlist,
+    CreateMarketConfig,
+    UserAccountArchive,
 }
 
-/// This enum describes different supported behaviors for handling self trading scenarios
-#[derive(PartialEq, Clone, Copy)]
-#[repr(u64)]
-pub enum SelfTradeBehavior {
-    /// Decrement take means that both the maker and taker sides of the matched orders are decremented.
-    ///
-    /// This is equivalent to a normal order match, except for the fact that no fees are applies.
-    DecrementTake,
-    /// Cancels the maker side of the order.
-    CancelProvide,
-    /// Cancels the whole transaction as soon as a self-matching scenario is encountered.
-    AbortTransaction,
+/// Rejects accounts which still have the given legacy tag, whose layout can't be read as the current one
+fn check_migrated(buf: &[u8], legacy_tag: AccountTag) -> Result<(), ProgramError> {
+    if buf.get(0..8) == Some(&(legacy_tag as u64).to_le_bytes()[..]) {
+        msg!("The account has an outdated layout and should be migrated first");
+        return Err(DexError::AccountNotMigrated.into());
+    }
+    Ok(())
 }
 
 /// The primary market state object
-#[derive(Copy, Clone, Pod, Zeroable)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[repr(C)]
 pub struct DexState {
     /// This u64 is used to verify and version the dex state
@@ -85,17 +89,246 @@ pub struct DexState {
     pub signer_nonce: u8,
     /// Fee type (e.g. default or stable)
     pub fee_type: u8,
+    /// The minimum distance in ticks between two orders of a same user account on the same side.
+    /// A value of zero disables the rule.
+    pub min_order_tick_distance: u16,
+    /// The market's trading status
+    pub status: u8,
+    /// When nonzero, the fill logs of the market omit the taker's account
+    pub hide_fill_accounts: u8,
     /// Padding
-    pub _padding: [u8; 6],
+    pub _padding: [u8; 2],
+    /// The UTF-8 symbol of the base token, padded with zeroes
+    pub base_symbol: [u8; 16],
+    /// The UTF-8 symbol of the quote token, padded with zeroes
+    pub quote_symbol: [u8; 16],
+    /// The UTF-8 display name of the market, padded with zeroes
+    pub market_name: [u8; 32],
+    /// The optional Pyth price account of the market, the default pubkey if none is configured
+    pub oracle: Pubkey,
+    /// The length in slots of the market's batch auctions. A value of zero enables continuous matching.
+    pub batch_slots: u64,
+    /// The share of the protocol fees of consumed fills credited to the cranker's reward target, in basis points.
+    /// The remainder is left for the fee authority to sweep.
+    pub crank_fee_share_bps: u64,
+    /// The length in slots of the window over which price movement is measured. A value of zero disables the
+    /// volatility-scaled taker fee.
+    pub volatility_window_slots: u64,
+    /// The price movement in basis points which raises the taker fee multiplier by one
+    pub volatility_threshold_bps: u64,
+    /// The maximum taker fee multiplier
+    pub max_volatility_multiplier: u64,
+    /// The FP32 average price of the first taker fill of the current volatility window
+    pub reference_price: u64,
+    /// The slot at which the current volatility window started
+    pub reference_slot: u64,
+    /// The FP32 average price of the latest taker fill
+    pub last_price: u64,
+    /// The program the market is being migrated to, the default pubkey if no migration is under way
+    pub migration_program: Pubkey,
+    /// The market of the migration program which takes over the vaults of this market
+    pub migration_market: Pubkey,
+    /// The timestamp after which the vaults can be handed over to the migration market
+    pub migration_unlock_timestamp: i64,
+    /// The order flow counters of the current and previous windows
+    pub order_flow: OrderFlowStats,
+    /// The minimum number of slots an order must rest on the book before it can be cancelled.
+    /// A value of zero disables the rule.
+    pub min_resting_slots: u64,
+    /// The FP32 limit price of the best bid, zero if there is no bid. Prices are in quote lots per base lot, as in
+    /// order ids.
+    pub best_bid: u64,
+    /// The FP32 limit price of the best ask, zero if there is no ask
+    pub best_ask: u64,
+    /// The slot at which the best bid and ask were last refreshed
+    pub top_of_book_slot: u64,
+    /// The share of the taker fee paid to the referrer of a referred order, in basis points
+    pub referral_share_bps: u64,
+    /// The rebate paid to every maker out of the taker fee of its fills, in basis points of the filled quote amount.
+    /// Fee tiers with a higher maker rate keep their own rate.
+    pub maker_rebate_bps: u64,
+    /// The authority which can halt the market without delay, the default pubkey if none is configured
+    pub emergency_authority: Pubkey,
+    /// The timestamp at which the emergency authority last halted the market while it was not halted already, zero if
+    /// the market was never halted by its emergency authority
+    pub emergency_halt_timestamp: i64,
+    /// The raw quote amount charged from the free quote balance of a user account when it cancels an unfilled order
+    /// shortly after placing it. A value of zero disables the fee.
+    pub cancel_fee: u64,
+    /// The number of slots after the placement of an order during which its cancellation is charged the cancel fee
+    pub cancel_fee_slots: u64,
+    /// The key nominated by the admin to take over the admin rights, the default pubkey if there is no nomination
+    pub pending_admin: Pubkey,
+    /// The sides of the market on which new orders are rejected, see [`HALT_BIDS_MASK`] and [`HALT_ASKS_MASK`]
+    pub halt_flags: u64,
+    /// The age in slots of the pending events which raises the crank reward multiplier by one. A value of zero
+    /// disables the scaling.
+    pub crank_reward_age_slots: u64,
+    /// The maximum crank reward multiplier
+    pub max_crank_reward_multiplier: u64,
+    /// The slot at which events were pushed to the empty event queue, zero while the queue is empty
+    pub pending_events_slot: u64,
+}
+
+/// The length in slots of an order flow window, about an hour
+pub const ORDER_FLOW_WINDOW_SLOTS: u64 = 9_000;
+
+/// Counts of the orders, cancels and fills of a market over fixed windows of [`ORDER_FLOW_WINDOW_SLOTS`] slots
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct OrderFlowStats {
+    /// The index of the current window, which starts at slot `window * ORDER_FLOW_WINDOW_SLOTS`
+    pub window: u64,
+    /// The number of orders placed during the current window, including swaps. Batch orders are counted when
+    /// their batch is matched.
+    pub new_orders: u64,
+    /// The number of orders cancelled during the current window
+    pub cancels: u64,
+    /// The number of fill events consumed and batch orders filled during the current window
+    pub fills: u64,
+    /// The number of orders placed during the previous window
+    pub previous_new_orders: u64,
+    /// The number of orders cancelled during the previous window
+    pub previous_cancels: u64,
+    /// The number of fill events consumed and batch orders filled during the previous window
+    pub previous_fills: u64,
+}
+
+impl OrderFlowStats {
+    /// Rolls over to the window of the given slot, moving the current counts to the previous window when needed
+    fn roll(&mut self, slot: u64) {
+        let window = slot / ORDER_FLOW_WINDOW_SLOTS;
+        if window == self.window {
+            return;
+        }
+        if window == self.window + 1 {
+            self.previous_new_orders = self.new_orders;
+            self.previous_cancels = self.cancels;
+            self.previous_fills = self.fills;
+        } else {
+            self.previous_new_orders = 0;
+            self.previous_cancels = 0;
+            self.previous_fills = 0;
+        }
+        self.window = window;
+        self.new_orders = 0;
+        self.cancels = 0;
+        self.fills = 0;
+    }
+
+    pub(crate) fn record_new_orders(&mut self, slot: u64, count: u64) {
+        self.roll(slot);
+        self.new_orders = self.new_orders.saturating_add(count);
+    }
+
+    pub(crate) fn record_cancels(&mut self, slot: u64, count: u64) {
+        self.roll(slot);
+        self.cancels = self.cancels.saturating_add(count);
+    }
+
+    pub(crate) fn record_fills(&mut self, slot: u64, count: u64) {
+        self.roll(slot);
+        self.fills = self.fills.saturating_add(count);
+    }
 }
 
+/// The referral share of new markets, in basis points of the taker fee
+pub const DEFAULT_REFERRAL_SHARE_BPS: u64 = 2_000;
+
 /// Size in bytes of the dex state object
 pub const DEX_STATE_LEN: usize = size_of::<DexState>();
 
+/// The market state layout of the first release, tagged with [`AccountTag::DexStateV1`]
+#[derive(Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+pub struct DexStateV1 {
+    #[allow(missing_docs)]
+    pub tag: u64,
+    #[allow(missing_docs)]
+    pub base_mint: Pubkey,
+    #[allow(missing_docs)]
+    pub quote_mint: Pubkey,
+    #[allow(missing_docs)]
+    pub base_vault: Pubkey,
+    #[allow(missing_docs)]
+    pub quote_vault: Pubkey,
+    #[allow(missing_docs)]
+    pub orderbook: Pubkey,
+    #[allow(missing_docs)]
+    pub admin: Pubkey,
+    #[allow(missing_docs)]
+    pub creation_timestamp: i64,
+    #[allow(missing_docs)]
+    pub base_volume: u64,
+    #[allow(missing_docs)]
+    pub quote_volume: u64,
+    #[allow(missing_docs)]
+    pub accumulated_fees: u64,
+    #[allow(missing_docs)]
+    pub min_base_order_size: u64,
+    #[allow(missing_docs)]
+    pub royalties_bps: u64,
+    #[allow(missing_docs)]
+    pub accumulated_royalties: u64,
+    #[allow(missing_docs)]
+    pub base_currency_multiplier: u64,
+    #[allow(missing_docs)]
+    pub quote_currency_multiplier: u64,
+    #[allow(missing_docs)]
+    pub signer_nonce: u8,
+    #[allow(missing_docs)]
+    pub fee_type: u8,
+    #[allow(missing_docs)]
+    pub _padding: [u8; 6],
+}
+
+/// Size in bytes of the market state object of the first release
+pub const DEX_STATE_V1_LEN: usize = size_of::<DexStateV1>();
+
+const _: () = assert!(DEX_STATE_V1_LEN == 280);
+
+impl DexStateV1 {
+    /// Returns the current market state, with the fields added since the first release set as for a new market
+    pub fn migrate(&self) -> DexState {
+        DexState {
+            tag: AccountTag::DexState as u64,
+            base_mint: self.base_mint,
+            quote_mint: self.quote_mint,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            orderbook: self.orderbook,
+            admin: self.admin,
+            creation_timestamp: self.creation_timestamp,
+            base_volume: self.base_volume,
+            quote_volume: self.quote_volume,
+            accumulated_fees: self.accumulated_fees,
+            min_base_order_size: self.min_base_order_size,
+            royalties_bps: self.royalties_bps,
+            accumulated_royalties: self.accumulated_royalties,
+            base_currency_multiplier: self.base_currency_multiplier,
+            quote_currency_multiplier: self.quote_currency_multiplier,
+            signer_nonce: self.signer_nonce,
+            fee_type: self.fee_type,
+            referral_share_bps: DEFAULT_REFERRAL_SHARE_BPS,
+            ..DexState::zeroed()
+        }
+    }
+}
+
+/// The lamports deposited in the user account for each good-til-date order posted on the book, paid out to whoever
+/// prunes the order once it expires
+pub const EXPIRED_ORDER_PRUNE_REWARD: u64 = 5_000;
+
+/// The halt flag rejecting new bids
+pub const HALT_BIDS_MASK: u64 = 1;
+/// The halt flag rejecting new asks
+pub const HALT_ASKS_MASK: u64 = 1 << 1;
+
 impl DexState {
     pub(crate) fn get<'a, 'b: 'a>(
         account_info: &'a AccountInfo<'b>,
     ) -> Result<RefMut<'a, Self>, ProgramError> {
+        check_migrated(&account_info.data.borrow(), AccountTag::DexStateV1)?;
         let a = Self::get_unchecked(account_info);
         if a.tag != AccountTag::DexState as u64 {
             return Err(ProgramError::InvalidAccountData);
@@ -110,6 +343,111 @@ impl DexState {
         a
     }
 
+    pub(crate) fn is_halted(&self) -> bool {
+        self.status == MarketStatus::Halted as u8 || self.is_migrating()
+    }
+
+    pub(crate) fn is_migrating(&self) -> bool {
+        self.status == MarketStatus::Migrating as u8
+    }
+
+    pub(crate) fn check_active(&self) -> Result<(), DexError> {
+        if self.is_halted() {
+            msg!("The market is halted");
+            return Err(DexError::MarketHalted);
+        }
+        Ok(())
+    }
+
+    /// Checks that new orders are accepted on a side of the market, given as the raw side of the order
+    pub(crate) fn check_side_active(&self, side: u8) -> Result<(), DexError> {
+        let mask = if side == Side::Bid as u8 {
+            HALT_BIDS_MASK
+        } else {
+            HALT_ASKS_MASK
+        };
+        if self.halt_flags & mask != 0 {
+            msg!("New orders on this side of the market are halted");
+            return Err(DexError::SideHalted);
+        }
+        Ok(())
+    }
+
+    /// Checks that an order placed at `placed_slot` has rested long enough to be cancelled at `slot`.
+    ///
+    /// Orders can always be cancelled on a halted market so that users can exit.
+    pub(crate) fn check_resting_time(&self, placed_slot: u64, slot: u64) -> Result<(), DexError> {
+        if self.is_halted() {
+            return Ok(());
+        }
+        let cancellable_slot = placed_slot.saturating_add(self.min_resting_slots);
+        if slot < cancellable_slot {
+            msg!("The order can be cancelled from slot {}", cancellable_slot);
+            return Err(DexError::OrderTooRecent);
+        }
+        Ok(())
+    }
+
+    /// Charges the cancel fee of an order cancelled at `slot` to the free quote balance of its user account, and
+    /// returns the charged amount.
+    ///
+    /// Only orders which are cancelled within `cancel_fee_slots` of their placement and of which no fill has been
+    /// consumed are charged, up to the free quote balance. Orders are never charged on a halted market. Fills which
+    /// are still in the event queue aren't known, and only cancel_order and cancel_all_orders charge the fee.
+    pub(crate) fn charge_cancel_fee(
+        &mut self,
+        user_account: &mut UserAccountHeader,
+        order: &Order,
+        slot: u64,
+    ) -> u64 {
+        if self.cancel_fee == 0
+            || self.is_halted()
+            || order.was_filled != 0
+            || slot >= order.placed_slot.saturating_add(self.cancel_fee_slots)
+        {
+            return 0;
+        }
+        let fee = self.cancel_fee.min(user_account.quote_token_free);
+        user_account.quote_token_free -= fee;
+        self.accumulated_fees = self.accumulated_fees.saturating_add(fee);
+        fee
+    }
+
+    pub(crate) fn is_batch_auction(&self) -> bool {
+        self.batch_slots != 0
+    }
+
+    pub(crate) fn check_continuous(&self) -> Result<(), DexError> {
+        if self.is_batch_auction() {
+            msg!("The market matches orders in batch auctions");
+            return Err(DexError::BatchAuctionMode);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn hides_fill_accounts(&self) -> bool {
+        self.hide_fill_accounts != 0
+    }
+
+    pub(crate) fn has_oracle(&self) -> bool {
+        self.oracle != Pubkey::default()
+    }
+
+    pub(crate) fn check_oracle(&self, oracle: Option<&AccountInfo>) -> Result<(), ProgramError> {
+        match oracle {
+            Some(o) => Ok(check_account_key(
+                o,
+                &self.oracle,
+                DexError::InvalidOracleAccount,
+            )?),
+            None if self.has_oracle() => {
+                msg!("The market's oracle account should be provided");
+                Err(DexError::InvalidOracleAccount.into())
+            }
+            None => Ok(()),
+        }
+    }
+
     pub(crate) fn scale_quote_amount(&self, raw_quote_amount: u64) -> u64 {
         raw_quote_amount / self.quote_currency_multiplier
     }
@@ -134,7 +472,8 @@ impl DexState {
         Some(())
     }
 
-    pub(crate) fn get_quote_from_base(
+    /// Returns the raw quote amount matching a raw base amount at a FP32 price, rounded down
+    pub fn get_quote_from_base(
         &self,
         raw_base_amount: u64,
         scaled_price_fp32: u64,
@@ -144,10 +483,125 @@ impl DexState {
             .and_then(|n| n.checked_div(self.base_currency_multiplier as u128))
             .and_then(|n| n.try_into().ok())
     }
+
+    /// Returns the taker fee multiplier implied by the price movement of the current volatility window
+    pub(crate) fn volatility_multiplier(&self, slot: u64) -> u64 {
+        if self.volatility_window_slots == 0
+            || self.reference_price == 0
+            || slot.saturating_sub(self.reference_slot) >= self.volatility_window_slots
+        {
+            return 1;
+        }
+        let movement = if self.last_price > self.reference_price {
+            self.last_price - self.reference_price
+        } else {
+            self.reference_price - self.last_price
+        };
+        let movement_bps = (movement as u128) * 10_000 / (self.reference_price as u128);
+        let steps = movement_bps / (self.volatility_threshold_bps.max(1) as u128);
+        (1 + steps).min(self.max_volatility_multiplier.max(1) as u128) as u64
+    }
+
+    /// Returns the crank reward multiplier implied by the age of the pending events at the given slot
+    pub(crate) fn crank_reward_multiplier(&self, slot: u64) -> u64 {
+        if self.crank_reward_age_slots == 0 || self.pending_events_slot == 0 {
+            return 1;
+        }
+        let steps = slot.saturating_sub(self.pending_events_slot) / self.crank_reward_age_slots;
+        steps
+            .saturating_add(1)
+            .min(self.max_crank_reward_multiplier.max(1))
+    }
+
+    /// Records that events were pushed to the event queue, which starts aging the pending events if it was empty
+    pub(crate) fn record_enqueued_events(&mut self, slot: u64) {
+        if self.pending_events_slot == 0 {
+            self.pending_events_slot = slot;
+        }
+    }
+
+    /// Records that events were popped from the event queue, which resets the age of the pending events once the
+    /// queue is empty. Events still pending keep aging from the slot the queue stopped being empty.
+    pub(crate) fn record_consumed_events(&mut self, remaining_events: u64) {
+        if remaining_events == 0 {
+            self.pending_events_slot = 0;
+        }
+    }
+
+    /// Records the average price of a taker fill, starting a new volatility window when the current one has elapsed
+    pub(crate) fn record_fill_price(&mut self, base_qty: u64, quote_qty: u64, slot: u64) {
+        if self.volatility_window_slots == 0 || base_qty == 0 {
+            return;
+        }
+        let price = match fp32_div(quote_qty, base_qty) {
+            Some(p) if p != 0 => p,
+            _ => return,
+        };
+        if self.reference_price == 0
+            || slot.saturating_sub(self.reference_slot) >= self.volatility_window_slots
+        {
+            self.reference_price = price;
+            self.reference_slot = slot;
+        }
+        self.last_price = price;
+    }
+
+    /// Caches the best bid and ask of the orderbook, so that they can be read without deserializing the slabs.
+    /// This is called by every instruction which changes the orderbook.
+    pub(crate) fn refresh_top_of_book(
+        &mut self,
+        bids: &AccountInfo,
+        asks: &AccountInfo,
+        slot: u64,
+    ) -> Result<(), ProgramError> {
+        let mut bids_guard = bids.data.borrow_mut();
+        let bids = Slab::<CallBackInfo>::from_buffer(&mut bids_guard, AobAccountTag::Bids)?;
+        let mut asks_guard = asks.data.borrow_mut();
+        let asks = Slab::<CallBackInfo>::from_buffer(&mut asks_guard, AobAccountTag::Asks)?;
+        self.best_bid = bids
+            .find_max()
+            .map_or(0, |h| (bids.leaf_nodes[h as usize].key >> 64) as u64);
+        self.best_ask = asks
+            .find_min()
+            .map_or(0, |h| (asks.leaf_nodes[h as usize].key >> 64) as u64);
+        self.top_of_book_slot = slot;
+        Ok(())
+    }
+
+    /// Returns the largest raw base amount, in whole base lots, which can be bought with a raw quote amount at a FP32 price
+    pub fn get_base_from_quote(
+        &self,
+        raw_quote_amount: u64,
+        scaled_price_fp32: u64,
+    ) -> Option<u64> {
+        let base_amount: u64 = ((raw_quote_amount as u128) << 32)
+            .checked_mul(self.base_currency_multiplier as u128)?
+            .checked_div(
+                (scaled_price_fp32 as u128).checked_mul(self.quote_currency_multiplier as u128)?,
+            )?
+            .try_into()
+            .ok()?;
+        Some(base_amount - base_amount % self.base_currency_multiplier)
+    }
+
+    /// Same as `get_quote_from_base`, rounded up
+    pub fn get_quote_from_base_ceil(
+        &self,
+        raw_base_amount: u64,
+        scaled_price_fp32: u64,
+    ) -> Option<u64> {
+        let numerator = (raw_base_amount as u128)
+            .checked_mul(scaled_price_fp32 as u128)?
+            .checked_mul(self.quote_currency_multiplier as u128)?;
+        let denominator = (self.base_currency_multiplier as u128) << 32;
+        let quotient = numerator / denominator;
+        let rounding = (numerator % denominator != 0) as u128;
+        (quotient + rounding).try_into().ok()
+    }
 }
 
 /// This header describes a user account's state
-#[derive(Copy, Clone, Pod, Zeroable)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[repr(C)]
 pub struct UserAccountHeader {
     /// This byte is used to verify and version the dex state
@@ -176,25 +630,62 @@ pub struct UserAccountHeader {
     pub accumulated_taker_quote_volume: u64,
     /// The accumulated taker quote volume of the user. This field is just a metric.
     pub accumulated_taker_base_volume: u64,
+    /// Opaque data set by the owner, which integrators can use to tag their user accounts
+    pub metadata: [u8; 32],
+    /// The slot of the last heartbeat or order placed by the owner or one of its session keys
+    pub last_active_slot: u64,
+    /// Set to 1 once a settle allowlist has been registered, in which case settle only transfers to its destinations
+    pub has_settle_allowlist: u8,
     /// We are forced to add padding here to keep the subsequent field as a u32 which maintains Borsh compatibility while respecting alignment constraints
-    _padding: u32,
+    _padding: [u8; 3],
     /// The user account's number of active orders.
     pub number_of_orders: u32,
 }
 
 /// Represents and order in the user account. The client id offers an alias which can be used off-chain to map custom ids to an actual order id.
-#[derive(Copy, Clone, Pod, Zeroable)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[repr(C)]
 pub struct Order {
     /// The raw order id
     pub id: u128,
-    /// The client-defined order id. Care should be taken off-chain to only create new orders with new client_ids.
+    /// The client-defined order id. Nonzero client ids are unique among the open orders of a user account.
     pub client_id: u128,
+    /// The slot at which the order was placed
+    pub placed_slot: u64,
+    /// The unix timestamp after which the order can be pruned from the book, 0 for orders which never expire
+    pub expiry_timestamp: i64,
+    /// For iceberg orders, the base quantity which is not displayed on the book yet, 0 for regular orders
+    pub hidden_base_qty: u64,
+    /// For iceberg orders, the base quantity of each tranche displayed on the book
+    pub display_base_qty: u64,
+    /// The maker fee rate of the order's fee tier when it was placed (as a FP32), used when the order is filled
+    pub maker_rate: u32,
+    /// Whether the maker fee rate was recorded, which isn't the case for orders placed before rates were recorded
+    pub has_maker_rate: u8,
+    /// Set to 1 once a fill of the order has been consumed
+    pub was_filled: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 10],
 }
 
 impl Order {
     /// The length in bytes of the order's binary representation
     pub const LEN: usize = std::mem::size_of::<Self>();
+
+    /// Returns the maker fee rate recorded when the order was placed, if any
+    pub fn maker_rate(&self) -> Option<u64> {
+        (self.has_maker_rate != 0).then(|| self.maker_rate as u64)
+    }
+
+    /// Whether the order is a good-til-date order whose expiry has passed
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiry_timestamp != 0 && now > self.expiry_timestamp
+    }
+
+    /// Returns the base quantity of the next tranche of an iceberg order, if any is left
+    pub fn next_tranche_base_qty(&self) -> Option<u64> {
+        (self.hidden_base_qty != 0).then(|| self.display_base_qty.min(self.hidden_base_qty))
+    }
 }
 
 #[allow(missing_docs)]
@@ -204,7 +695,93 @@ pub struct UserAccount<'a> {
 }
 
 /// Size in bytes of the user account header object
-pub const USER_ACCOUNT_HEADER_LEN: usize = 152;
+pub const USER_ACCOUNT_HEADER_LEN: usize = size_of::<UserAccountHeader>();
+
+// The header layout is part of the user account format, and the orders which follow it must stay aligned
+const _: () = assert!(USER_ACCOUNT_HEADER_LEN == 192);
+const _: () = assert!(USER_ACCOUNT_HEADER_LEN % std::mem::align_of::<Order>() == 0);
+
+/// The user account header layout of the first release, tagged with [`AccountTag::UserAccountV1`]
+#[derive(Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+pub struct UserAccountHeaderV1 {
+    #[allow(missing_docs)]
+    pub tag: u64,
+    #[allow(missing_docs)]
+    pub market: Pubkey,
+    #[allow(missing_docs)]
+    pub owner: Pubkey,
+    #[allow(missing_docs)]
+    pub base_token_free: u64,
+    #[allow(missing_docs)]
+    pub base_token_locked: u64,
+    #[allow(missing_docs)]
+    pub quote_token_free: u64,
+    #[allow(missing_docs)]
+    pub quote_token_locked: u64,
+    #[allow(missing_docs)]
+    pub accumulated_rebates: u64,
+    #[allow(missing_docs)]
+    pub accumulated_maker_quote_volume: u64,
+    #[allow(missing_docs)]
+    pub accumulated_maker_base_volume: u64,
+    #[allow(missing_docs)]
+    pub accumulated_taker_quote_volume: u64,
+    #[allow(missing_docs)]
+    pub accumulated_taker_base_volume: u64,
+    #[allow(missing_docs)]
+    pub _padding: u32,
+    #[allow(missing_docs)]
+    pub number_of_orders: u32,
+}
+
+/// The order layout of the first release
+#[derive(Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+pub struct OrderV1 {
+    #[allow(missing_docs)]
+    pub id: u128,
+    #[allow(missing_docs)]
+    pub client_id: u128,
+}
+
+/// Size in bytes of the user account header object of the first release
+pub const USER_ACCOUNT_HEADER_V1_LEN: usize = size_of::<UserAccountHeaderV1>();
+
+/// Size in bytes of an order of the first release
+pub const ORDER_V1_LEN: usize = size_of::<OrderV1>();
+
+const _: () = assert!(USER_ACCOUNT_HEADER_V1_LEN == 152);
+const _: () = assert!(ORDER_V1_LEN == 32);
+
+impl UserAccountHeaderV1 {
+    /// Returns the current user account header, with the fields added since the first release left empty
+    pub fn migrate(&self) -> UserAccountHeader {
+        let mut header = UserAccountHeader::new(&self.market, &self.owner);
+        header.base_token_free = self.base_token_free;
+        header.base_token_locked = self.base_token_locked;
+        header.quote_token_free = self.quote_token_free;
+        header.quote_token_locked = self.quote_token_locked;
+        header.accumulated_rebates = self.accumulated_rebates;
+        header.accumulated_maker_quote_volume = self.accumulated_maker_quote_volume;
+        header.accumulated_maker_base_volume = self.accumulated_maker_base_volume;
+        header.accumulated_taker_quote_volume = self.accumulated_taker_quote_volume;
+        header.accumulated_taker_base_volume = self.accumulated_taker_base_volume;
+        header.number_of_orders = self.number_of_orders;
+        header
+    }
+}
+
+impl OrderV1 {
+    /// Returns the current order, with the fields added since the first release left empty
+    pub fn migrate(&self) -> Order {
+        Order {
+            id: self.id,
+            client_id: self.client_id,
+            ..Order::zeroed()
+        }
+    }
+}
 
 impl UserAccountHeader {
     pub(crate) fn new(market: &Pubkey, owner: &Pubkey) -> Self {
@@ -218,11 +795,14 @@ impl UserAccountHeader {
             quote_token_locked: 0,
             number_of_orders: 0,
             accumulated_rebates: 0,
-            _padding: 0,
+            has_settle_allowlist: 0,
+            _padding: [0; 3],
             accumulated_maker_quote_volume: 0,
             accumulated_maker_base_volume: 0,
             accumulated_taker_quote_volume: 0,
             accumulated_taker_base_volume: 0,
+            metadata: [0; 32],
+            last_active_slot: 0,
         }
     }
 }
@@ -230,6 +810,7 @@ impl UserAccountHeader {
 impl<'a> UserAccount<'a> {
     #[allow(missing_docs)]
     pub fn from_buffer(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        check_migrated(buf, AccountTag::UserAccountV1)?;
         let user_acc = UserAccount::from_buffer_unchecked(buf).unwrap();
         if user_acc.header.tag != AccountTag::UserAccount as u64 {
             return Err(ProgramError::InvalidAccountData);
@@ -248,6 +829,11 @@ impl<'a> UserAccount<'a> {
 }
 
 impl<'a> UserAccount<'a> {
+    /// The user account's open orders
+    pub fn orders(&self) -> &[Order] {
+        &self.orders[..self.header.number_of_orders as usize]
+    }
+
     #[allow(missing_docs)]
     pub fn read_order(&self, order_index: usize) -> Result<Order, DexError> {
         if order_index >= self.header.number_of_orders as usize {
@@ -279,6 +865,13 @@ impl<'a> UserAccount<'a> {
         Ok(())
     }
 
+    /// Flags an open order as filled, when it is still in the user account
+    pub(crate) fn record_fill(&mut self, order_id: u128) {
+        if let Ok(order_index) = self.find_order_index(order_id) {
+            self.orders[order_index].was_filled = 1;
+        }
+    }
+
     #[allow(missing_docs)]
     pub fn find_order_index(&self, order_id: u128) -> Result<usize, DexError> {
         let res = self
@@ -307,59 +900,789 @@ impl<'a> UserAccount<'a> {
     }
 }
 
-#[doc(hidden)]
-#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
-pub enum MarketFeeType {
-    Default,
-    Stable,
+/// This header describes a market's maker rewards sampling state
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct MakerRewardsHeader {
+    /// This u64 is used to verify and version the maker rewards state
+    pub tag: u64,
+    /// The maker rewards account's associated DEX market
+    pub market: Pubkey,
+    /// The length in slots of a sampling epoch
+    pub epoch_length: u64,
+    /// The current sampling epoch
+    pub current_epoch: u64,
+    /// The slot at which the last sample was taken. At most one sample is taken per slot.
+    pub last_sample_slot: u64,
+    /// The number of samples taken during the current epoch
+    pub number_of_samples: u64,
+    /// The number of user accounts currently tracked
+    pub number_of_entries: u64,
 }
 
-#[doc(hidden)]
-#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
-pub enum FeeTier {
-    Base,
-    Srm2,
-    Srm3,
-    Srm4,
-    Srm5,
-    Srm6,
-    MSrm,
-    Stable,
+/// Size in bytes of the maker rewards header object
+pub const MAKER_REWARDS_HEADER_LEN: usize = size_of::<MakerRewardsHeader>();
+
+/// The maker sampling metrics of a user account for a given epoch
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct MakerRewardsEntry {
+    /// The sampled user account
+    pub user_account: Pubkey,
+    /// The epoch to which the metrics refer
+    pub epoch: u64,
+    /// The number of samples during which the user account held the best bid or best ask (counted once per side)
+    pub uptime_samples: u64,
+    /// The sum over all samples of the base quantity held by the user account at the top of the book
+    pub depth_samples: u64,
 }
 
-#[doc(hidden)]
-impl FeeTier {
-    pub fn from_srm_and_msrm_balances(
-        dex_state: &DexState,
-        srm_held: u64,
-        msrm_held: u64,
-    ) -> FeeTier {
-        let one_srm = 1_000_000;
+impl MakerRewardsEntry {
+    /// The length in bytes of the entry's binary representation
+    pub const LEN: usize = std::mem::size_of::<Self>();
+}
 
-        if dex_state.fee_type == MarketFeeType::Stable as u8 {
-            return FeeTier::Stable;
-        }
+#[allow(missing_docs)]
+pub struct MakerRewards<'a> {
+    pub header: &'a mut MakerRewardsHeader,
+    entries: &'a mut [MakerRewardsEntry],
+}
 
-        match () {
-            () if msrm_held >= 1 => FeeTier::MSrm,
-            () if srm_held >= one_srm * 1_000_000 => FeeTier::Srm6,
-            () if srm_held >= one_srm * 100_000 => FeeTier::Srm5,
-            () if srm_held >= one_srm * 10_000 => FeeTier::Srm4,
-            () if srm_held >= one_srm * 1_000 => FeeTier::Srm3,
-            () if srm_held >= one_srm * 100 => FeeTier::Srm2,
-            () => FeeTier::Base,
+impl<'a> MakerRewards<'a> {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let rewards = MakerRewards::from_buffer_unchecked(buf)?;
+        if rewards.header.tag != AccountTag::MakerRewards as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(rewards)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let (hd, tl) = buf.split_at_mut(MAKER_REWARDS_HEADER_LEN);
+        let header: &mut MakerRewardsHeader =
+            try_from_bytes_mut(hd).map_err(|_| ProgramError::InvalidAccountData)?;
+        let entries = try_cast_slice_mut(tl).map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { header, entries })
+    }
+
+    /// The currently tracked entries
+    pub fn entries(&self) -> &[MakerRewardsEntry] {
+        &self.entries[..self.header.number_of_entries as usize]
+    }
+
+    /// Registers a new sample taken at the given slot, rolling over to a new epoch when needed.
+    ///
+    /// Returns false when a sample has already been taken during this slot.
+    pub(crate) fn start_sample(&mut self, slot: u64) -> bool {
+        if self.header.number_of_samples != 0 && self.header.last_sample_slot == slot {
+            return false;
+        }
+        let epoch = slot / self.header.epoch_length;
+        if epoch != self.header.current_epoch {
+            self.header.current_epoch = epoch;
+            self.header.number_of_samples = 0;
+        }
+        self.header.last_sample_slot = slot;
+        self.header.number_of_samples += 1;
+        true
+    }
+
+    /// Credits a user account with one uptime sample and the base quantity it holds at the top of the book.
+    pub(crate) fn record(&mut self, user_account: &Pubkey, base_qty: u64) -> Result<(), DexError> {
+        let epoch = self.header.current_epoch;
+        let number_of_entries = self.header.number_of_entries as usize;
+        let index = match self.entries[..number_of_entries]
+            .iter()
+            .position(|e| &e.user_account == user_account)
+        {
+            Some(i) => i,
+            None => {
+                // Entries which are older than the previous epoch are no longer relevant and can be recycled
+                let stale = self.entries[..number_of_entries]
+                    .iter()
+                    .position(|e| e.epoch.saturating_add(1) < epoch);
+                let i = match stale {
+                    Some(i) => i,
+                    None => {
+                        if number_of_entries >= self.entries.len() {
+                            return Err(DexError::MakerRewardsFull);
+                        }
+                        self.header.number_of_entries += 1;
+                        number_of_entries
+                    }
+                };
+                self.entries[i] = MakerRewardsEntry {
+                    user_account: *user_account,
+                    epoch,
+                    uptime_samples: 0,
+                    depth_samples: 0,
+                };
+                i
+            }
+        };
+        let entry = &mut self.entries[index];
+        if entry.epoch != epoch {
+            entry.epoch = epoch;
+            entry.uptime_samples = 0;
+            entry.depth_samples = 0;
+        }
+        entry.uptime_samples += 1;
+        entry.depth_samples = entry.depth_samples.saturating_add(base_qty);
+        Ok(())
+    }
+}
+
+/// This header describes a market's escrow, which holds the balances of user accounts which were missing when their events were force consumed
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct EscrowHeader {
+    /// This u64 is used to verify and version the escrow state
+    pub tag: u64,
+    /// The escrow's associated DEX market
+    pub market: Pubkey,
+    /// The number of user accounts currently holding balances in the escrow
+    pub number_of_entries: u64,
+}
+
+/// Size in bytes of the escrow header object
+pub const ESCROW_HEADER_LEN: usize = size_of::<EscrowHeader>();
+
+/// The balances held in escrow for a given user account
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct EscrowEntry {
+    /// The user account to which the balances belong
+    pub user_account: Pubkey,
+    /// The amount of base token to add to the user account's free balance
+    pub base_token_free: u64,
+    /// The amount of base token to remove from the user account's locked balance
+    pub base_token_unlocked: u64,
+    /// The amount of quote token to add to the user account's free balance
+    pub quote_token_free: u64,
+    /// The amount of quote token to remove from the user account's locked balance
+    pub quote_token_unlocked: u64,
+}
+
+impl EscrowEntry {
+    /// The length in bytes of the entry's binary representation
+    pub const LEN: usize = std::mem::size_of::<Self>();
+}
+
+#[allow(missing_docs)]
+pub struct Escrow<'a> {
+    pub header: &'a mut EscrowHeader,
+    entries: &'a mut [EscrowEntry],
+}
+
+impl<'a> Escrow<'a> {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let escrow = Escrow::from_buffer_unchecked(buf)?;
+        if escrow.header.tag != AccountTag::Escrow as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(escrow)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let (hd, tl) = buf.split_at_mut(ESCROW_HEADER_LEN);
+        let header: &mut EscrowHeader =
+            try_from_bytes_mut(hd).map_err(|_| ProgramError::InvalidAccountData)?;
+        let entries = try_cast_slice_mut(tl).map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { header, entries })
+    }
+
+    /// The user accounts currently holding balances in the escrow
+    pub fn entries(&self) -> &[EscrowEntry] {
+        &self.entries[..self.header.number_of_entries as usize]
+    }
+
+    /// Returns the escrow entry of a user account, creating it if needed
+    pub(crate) fn get_or_insert(
+        &mut self,
+        user_account: &Pubkey,
+    ) -> Result<&mut EscrowEntry, DexError> {
+        let number_of_entries = self.header.number_of_entries as usize;
+        let index = match self.entries[..number_of_entries]
+            .iter()
+            .position(|e| &e.user_account == user_account)
+        {
+            Some(i) => i,
+            None => {
+                let slot = self
+                    .entries
+                    .get_mut(number_of_entries)
+                    .ok_or(DexError::EscrowFull)?;
+                *slot = EscrowEntry {
+                    user_account: *user_account,
+                    base_token_free: 0,
+                    base_token_unlocked: 0,
+                    quote_token_free: 0,
+                    quote_token_unlocked: 0,
+                };
+                self.header.number_of_entries += 1;
+                number_of_entries
+            }
+        };
+        Ok(&mut self.entries[index])
+    }
+
+    /// Removes and returns the escrow entry of a user account
+    pub(crate) fn remove(&mut self, user_account: &Pubkey) -> Option<EscrowEntry> {
+        let number_of_entries = self.header.number_of_entries as usize;
+        let index = self.entries[..number_of_entries]
+            .iter()
+            .position(|e| &e.user_account == user_account)?;
+        let entry = self.entries[index];
+        self.entries[index] = self.entries[number_of_entries - 1];
+        self.header.number_of_entries -= 1;
+        Some(entry)
+    }
+}
+
+/// This header describes a market's pending batch auction
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct BatchAuctionHeader {
+    /// This u64 is used to verify and version the batch auction state
+    pub tag: u64,
+    /// The batch auction's associated DEX market
+    pub market: Pubkey,
+    /// The slot at which the first order of the pending batch was received
+    pub start_slot: u64,
+    /// The number of orders in the pending batch
+    pub number_of_orders: u64,
+}
+
+/// Size in bytes of the batch auction header object
+pub const BATCH_AUCTION_HEADER_LEN: usize = size_of::<BatchAuctionHeader>();
+
+/// An order waiting for the next batch auction
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct BatchOrder {
+    /// The user account which placed the order
+    pub user_account: Pubkey,
+    /// The order's limit price (as a FP32)
+    pub limit_price: u64,
+    /// The order's base quantity
+    pub base_qty: u64,
+    /// The quote quantity locked in the user account, which is only relevant for bids
+    pub locked_quote_qty: u64,
+    /// The order's side
+    pub side: u8,
+    /// The fee tier of the user account
+    pub fee_tier: u8,
+    #[allow(missing_docs)]
+    pub _padding: [u8; 6],
+}
+
+impl BatchOrder {
+    /// The length in bytes of the order's binary representation
+    pub const LEN: usize = std::mem::size_of::<Self>();
+}
+
+#[allow(missing_docs)]
+pub struct BatchAuction<'a> {
+    pub header: &'a mut BatchAuctionHeader,
+    orders: &'a mut [BatchOrder],
+}
+
+impl<'a> BatchAuction<'a> {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let batch = BatchAuction::from_buffer_unchecked(buf)?;
+        if batch.header.tag != AccountTag::BatchAuction as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(batch)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let (hd, tl) = buf.split_at_mut(BATCH_AUCTION_HEADER_LEN);
+        let header: &mut BatchAuctionHeader =
+            try_from_bytes_mut(hd).map_err(|_| ProgramError::InvalidAccountData)?;
+        let orders = try_cast_slice_mut(tl).map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { header, orders })
+    }
+
+    /// The orders of the pending batch, in order of arrival
+    pub fn orders(&self) -> &[BatchOrder] {
+        &self.orders[..self.header.number_of_orders as usize]
+    }
+
+    /// Adds an order to the pending batch, which starts the batch if it is the first one
+    pub(crate) fn push(&mut self, order: BatchOrder, current_slot: u64) -> Result<(), DexError> {
+        let number_of_orders = self.header.number_of_orders as usize;
+        let slot = self
+            .orders
+            .get_mut(number_of_orders)
+            .ok_or(DexError::BatchAuctionFull)?;
+        *slot = order;
+        if number_of_orders == 0 {
+            self.header.start_slot = current_slot;
+        }
+        self.header.number_of_orders += 1;
+        Ok(())
+    }
+
+    /// Empties the pending batch
+    pub(crate) fn clear(&mut self) {
+        self.header.number_of_orders = 0;
+        self.header.start_slot = 0;
+    }
+}
+
+/// This header describes a session key authorized to place orders on behalf of a user account
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct SessionHeader {
+    /// This u64 is used to verify and version the session state
+    pub tag: u64,
+    /// The user account on behalf of which the session key places orders
+    pub user_account: Pubkey,
+    /// The ephemeral key authorized by the user account owner
+    pub session_key: Pubkey,
+    /// The unix timestamp after which the session orders can be cancelled by anyone
+    pub expiry: i64,
+    /// The number of order ids recorded in the session
+    pub number_of_orders: u64,
+}
+
+/// Size in bytes of the session header object
+pub const SESSION_HEADER_LEN: usize = size_of::<SessionHeader>();
+
+#[allow(missing_docs)]
+pub struct Session<'a> {
+    pub header: &'a mut SessionHeader,
+    order_ids: &'a mut [u128],
+}
+
+impl<'a> Session<'a> {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let session = Session::from_buffer_unchecked(buf)?;
+        if session.header.tag != AccountTag::Session as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(session)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let (hd, tl) = buf.split_at_mut(SESSION_HEADER_LEN);
+        let header: &mut SessionHeader =
+            try_from_bytes_mut(hd).map_err(|_| ProgramError::InvalidAccountData)?;
+        let order_ids = try_cast_slice_mut(tl).map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { header, order_ids })
+    }
+
+    /// The ids of the orders placed under the session
+    pub fn order_ids(&self) -> &[u128] {
+        &self.order_ids[..self.header.number_of_orders as usize]
+    }
+
+    pub(crate) fn is_expired(&self, now: i64) -> bool {
+        now > self.header.expiry
+    }
+
+    /// Checks that the session authorizes a key to trade for a user account
+    pub(crate) fn check(
+        &self,
+        user_account: &Pubkey,
+        session_key: &Pubkey,
+        now: i64,
+    ) -> Result<(), DexError> {
+        if &self.header.user_account != user_account || &self.header.session_key != session_key {
+            msg!("The session does not authorize this key for this user account");
+            return Err(DexError::InvalidSessionAccount);
+        }
+        if self.is_expired(now) {
+            msg!("The session expired at {}", self.header.expiry);
+            return Err(DexError::SessionExpired);
+        }
+        Ok(())
+    }
+
+    /// Records the id of an order placed under the session.
+    ///
+    /// When the session is full, the ids of the orders which are no longer open are dropped first.
+    pub(crate) fn push(
+        &mut self,
+        order_id: u128,
+        is_open: impl Fn(u128) -> bool,
+    ) -> Result<(), DexError> {
+        if self.header.number_of_orders as usize == self.order_ids.len() {
+            let mut kept = 0;
+            for i in 0..self.order_ids.len() {
+                if is_open(self.order_ids[i]) {
+                    self.order_ids[kept] = self.order_ids[i];
+                    kept += 1;
+                }
+            }
+            self.header.number_of_orders = kept as u64;
+        }
+        let slot = self
+            .order_ids
+            .get_mut(self.header.number_of_orders as usize)
+            .ok_or(DexError::SessionFull)?;
+        *slot = order_id;
+        self.header.number_of_orders += 1;
+        Ok(())
+    }
+
+    /// Forgets the recorded order ids
+    pub(crate) fn clear(&mut self) {
+        self.header.number_of_orders = 0;
+    }
+}
+
+/// This header describes the designated market makers of a market
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct DmmRegistryHeader {
+    /// This u64 is used to verify and version the registry state
+    pub tag: u64,
+    /// The registry's associated DEX market
+    pub market: Pubkey,
+    /// The number of registered user accounts
+    pub number_of_entries: u64,
+}
+
+/// Size in bytes of the designated market maker registry header object
+pub const DMM_REGISTRY_HEADER_LEN: usize = size_of::<DmmRegistryHeader>();
+
+#[allow(missing_docs)]
+pub struct DmmRegistry<'a> {
+    pub header: &'a mut DmmRegistryHeader,
+    entries: &'a mut [Pubkey],
+}
+
+impl<'a> DmmRegistry<'a> {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let registry = DmmRegistry::from_buffer_unchecked(buf)?;
+        if registry.header.tag != AccountTag::DmmRegistry as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(registry)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let (hd, tl) = buf.split_at_mut(DMM_REGISTRY_HEADER_LEN);
+        let header: &mut DmmRegistryHeader =
+            try_from_bytes_mut(hd).map_err(|_| ProgramError::InvalidAccountData)?;
+        let entries = try_cast_slice_mut(tl).map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { header, entries })
+    }
+
+    /// The registered user accounts
+    pub fn entries(&self) -> &[Pubkey] {
+        &self.entries[..self.header.number_of_entries as usize]
+    }
+
+    #[allow(missing_docs)]
+    pub fn contains(&self, user_account: &Pubkey) -> bool {
+        self.entries().contains(user_account)
+    }
+
+    pub(crate) fn insert(&mut self, user_account: &Pubkey) -> Result<(), DexError> {
+        if self.contains(user_account) {
+            return Ok(());
+        }
+        let slot = self
+            .entries
+            .get_mut(self.header.number_of_entries as usize)
+            .ok_or(DexError::DmmRegistryFull)?;
+        *slot = *user_account;
+        self.header.number_of_entries += 1;
+        Ok(())
+    }
+
+    /// Returns false if the user account was not registered
+    pub(crate) fn remove(&mut self, user_account: &Pubkey) -> bool {
+        let number_of_entries = self.header.number_of_entries as usize;
+        match self.entries().iter().position(|k| k == user_account) {
+            Some(i) => {
+                self.entries[i] = self.entries[number_of_entries - 1];
+                self.header.number_of_entries -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Returns true if a user account is in the designated market maker registry of its market
+pub(crate) fn is_designated_market_maker(
+    dmm_registry: &AccountInfo,
+    market: &Pubkey,
+    user_account: &Pubkey,
+) -> Result<bool, ProgramError> {
+    let mut registry_guard = dmm_registry.data.borrow_mut();
+    let registry = DmmRegistry::from_buffer(&mut registry_guard)?;
+    if &registry.header.market != market {
+        msg!("The designated market maker registry does not belong to this market");
+        return Err(DexError::InvalidDmmRegistryAccount.into());
+    }
+    Ok(registry.contains(user_account))
+}
+
+/// The crank service record of an operator on a market
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct CrankerIdentity {
+    /// This u64 is used to verify and version the cranker identity state
+    pub tag: u64,
+    /// The identity's associated DEX market
+    pub market: Pubkey,
+    /// The operator's wallet, which signs the consume_events instructions recorded by this identity
+    pub owner: Pubkey,
+    /// The total number of events consumed
+    pub events_consumed: u64,
+    /// The total amount of quote token credited as crank rewards
+    pub rewards_earned: u64,
+    /// The slot of the latest recorded crank
+    pub last_crank_slot: u64,
+}
+
+/// Size in bytes of the cranker identity object
+pub const CRANKER_IDENTITY_LEN: usize = size_of::<CrankerIdentity>();
+
+impl CrankerIdentity {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let identity = Self::from_buffer_unchecked(buf)?;
+        if identity.tag != AccountTag::CrankerIdentity as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(identity)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        buf.get_mut(..CRANKER_IDENTITY_LEN)
+            .and_then(|b| try_from_bytes_mut(b).ok())
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+}
+
+/// The maximum number of destination token accounts of a settle allowlist
+pub const MAX_SETTLE_DESTINATIONS: usize = 4;
+
+/// The immutable list of token accounts a user account can settle to
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct SettleAllowlist {
+    /// This u64 is used to verify and version the settle allowlist state
+    pub tag: u64,
+    /// The user account restricted by the allowlist
+    pub user_account: Pubkey,
+    /// The number of allowed destinations
+    pub number_of_destinations: u64,
+    /// The allowed destination token accounts, only the first `number_of_destinations` are used
+    pub destinations: [Pubkey; MAX_SETTLE_DESTINATIONS],
+}
+
+/// Size in bytes of the settle allowlist object
+pub const SETTLE_ALLOWLIST_LEN: usize = size_of::<SettleAllowlist>();
+
+impl SettleAllowlist {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let allowlist = Self::from_buffer_unchecked(buf)?;
+        if allowlist.tag != AccountTag::SettleAllowlist as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(allowlist)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        buf.get_mut(..SETTLE_ALLOWLIST_LEN)
+            .and_then(|b| try_from_bytes_mut(b).ok())
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Checks whether a token account is one of the allowed destinations
+    pub fn allows(&self, destination: &Pubkey) -> bool {
+        self.destinations[..self.number_of_destinations as usize].contains(destination)
+    }
+}
+
+/// The deployment-wide settings of market creation, stored in a single program derived account
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct CreateMarketConfig {
+    /// This u64 is used to verify and version the create market config state
+    pub tag: u64,
+    /// The fee charged to create a market, 0 to allow free market creation
+    pub listing_fee: u64,
+    /// The mint of the listing fee, the default pubkey if the fee is paid in lamports
+    pub fee_mint: Pubkey,
+    /// The account receiving the listing fees, a token account of the fee mint for token fees
+    pub treasury: Pubkey,
+}
+
+/// Size in bytes of the create market config object
+pub const CREATE_MARKET_CONFIG_LEN: usize = size_of::<CreateMarketConfig>();
+
+impl CreateMarketConfig {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let config = Self::from_buffer_unchecked(buf)?;
+        if config.tag != AccountTag::CreateMarketConfig as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(config)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        buf.get_mut(..CREATE_MARKET_CONFIG_LEN)
+            .and_then(|b| try_from_bytes_mut(b).ok())
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Whether the listing fee is paid in lamports rather than in tokens
+    pub fn is_lamports_fee(&self) -> bool {
+        self.fee_mint == Pubkey::default()
+    }
+}
+
+/// The compact state of an archived user account, from which the user account can be restored at its address
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct UserAccountArchive {
+    /// This u64 is used to verify and version the archive state
+    pub tag: u64,
+    /// The archived user account's associated DEX market
+    pub market: Pubkey,
+    /// The archived user account owner's wallet
+    pub owner: Pubkey,
+    /// The index of the archived user account
+    pub account_index: u64,
+    /// The all time quantity of rebates accumulated by the user account
+    pub accumulated_rebates: u64,
+    /// The accumulated maker quote volume of the user account
+    pub accumulated_maker_quote_volume: u64,
+    /// The accumulated maker base volume of the user account
+    pub accumulated_maker_base_volume: u64,
+    /// The accumulated taker quote volume of the user account
+    pub accumulated_taker_quote_volume: u64,
+    /// The accumulated taker base volume of the user account
+    pub accumulated_taker_base_volume: u64,
+    /// The opaque data set by the owner
+    pub metadata: [u8; 32],
+    /// The slot of the last activity of the user account
+    pub last_active_slot: u64,
+    /// Set to 1 when the user account has a settle allowlist, which still applies once the account is restored
+    pub has_settle_allowlist: u8,
+    /// To eliminate implicit padding
+    pub _padding: [u8; 7],
+}
+
+/// Size in bytes of the user account archive object
+pub const USER_ACCOUNT_ARCHIVE_LEN: usize = size_of::<UserAccountArchive>();
+
+impl UserAccountArchive {
+    #[allow(missing_docs)]
+    pub fn from_buffer(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let archive = Self::from_buffer_unchecked(buf)?;
+        if archive.tag != AccountTag::UserAccountArchive as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        };
+        Ok(archive)
+    }
+
+    #[allow(missing_docs)]
+    pub fn from_buffer_unchecked(buf: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        buf.get_mut(..USER_ACCOUNT_ARCHIVE_LEN)
+            .and_then(|b| try_from_bytes_mut(b).ok())
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+}
+
+/// This enum describes the trading status of a market
+#[derive(Clone, Copy, PartialEq, Debug, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum MarketStatus {
+    /// Orders can be placed
+    Active,
+    /// New orders and swaps are rejected, existing orders can still be cancelled and settled
+    Halted,
+    /// The market is halted for good and its vaults are handed over to another program once the migration
+    /// timelock has elapsed
+    Migrating,
+}
+
+#[doc(hidden)]
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum MarketFeeType {
+    Default,
+    Stable,
+}
+
+#[doc(hidden)]
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum FeeTier {
+    Base,
+    Srm2,
+    Srm3,
+    Srm4,
+    Srm5,
+    Srm6,
+    MSrm,
+    Stable,
+    Dmm,
+}
+
+#[doc(hidden)]
+impl FeeTier {
+    pub fn from_srm_and_msrm_balances(
+        dex_state: &DexState,
+        srm_held: u64,
+        msrm_held: u64,
+    ) -> FeeTier {
+        let one_srm = 1_000_000;
+
+        if dex_state.fee_type == MarketFeeType::Stable as u8 {
+            return FeeTier::Stable;
+        }
+
+        match () {
+            () if msrm_held >= 1 => FeeTier::MSrm,
+            () if srm_held >= one_srm * 1_000_000 => FeeTier::Srm6,
+            () if srm_held >= one_srm * 100_000 => FeeTier::Srm5,
+            () if srm_held >= one_srm * 10_000 => FeeTier::Srm4,
+            () if srm_held >= one_srm * 1_000 => FeeTier::Srm3,
+            () if srm_held >= one_srm * 100 => FeeTier::Srm2,
+            () => FeeTier::Base,
         }
     }
 
     pub fn from_u8(tag: u8) -> (Self, bool) {
         let is_referred = (tag & REFERRAL_MASK) != 0;
-        let fee_tier = match tag & (!REFERRAL_MASK) {
+        let fee_tier = match tag & !(REFERRAL_MASK | NOTIFY_ON_CROSS_MASK) {
             0 => FeeTier::Base,
             1 => FeeTier::Srm2,
             2 => FeeTier::Srm3,
             3 => FeeTier::Srm4,
             4 => FeeTier::Srm5,
             5 => FeeTier::Srm6,
+            6 => FeeTier::MSrm,
+            7 => FeeTier::Stable,
+            8 => FeeTier::Dmm,
             _ => unreachable!(),
         };
         (fee_tier, is_referred)
@@ -398,19 +1721,34 @@ impl FeeTier {
             FeeTier::Srm6 => (32 << 32) / 100_000,
             FeeTier::MSrm => (30 << 32) / 100_000,
             FeeTier::Stable => (10 << 32) / 100_000,
+            FeeTier::Dmm => 0,
         }
     }
 
     pub fn maker_rate(self) -> u64 {
-        0
+        match self {
+            FeeTier::Dmm => (2 << 32) / 100_000,
+            _ => 0,
+        }
+    }
+
+    /// The FP32 maker rate of the fee tier on a market, which is at least the market's maker rebate
+    pub fn market_maker_rate(self, maker_rebate_bps: u64) -> u64 {
+        self.maker_rate().max(bps_to_fp32(maker_rebate_bps))
     }
 
-    pub fn maker_rebate(self, _quote_qty: u64) -> u64 {
-        0
+    /// The rebate can exceed the taker fee it is paid from, in which case it should be capped by the caller
+    pub fn maker_rebate(self, quote_qty: u64) -> u64 {
+        fp32_mul(quote_qty, self.maker_rate()).unwrap()
     }
 
     pub fn remove_taker_fee(self, quote_qty: u64) -> u64 {
-        let rate = self.taker_rate();
+        self.remove_scaled_taker_fee(quote_qty, 1)
+    }
+
+    /// Same as `remove_taker_fee` with the taker rate scaled by a volatility multiplier
+    pub fn remove_scaled_taker_fee(self, quote_qty: u64, multiplier: u64) -> u64 {
+        let rate = self.taker_rate() * multiplier;
         fp32_div(quote_qty, FP_32_ONE + rate).unwrap()
     }
 
@@ -419,14 +1757,15 @@ impl FeeTier {
         fp32_mul(quote_qty, rate).unwrap()
     }
 
-    pub fn referral_rate(self) -> u64 {
+    /// The referral rate for a market's referral share, in basis points of the taker fee net of the base rebate
+    pub fn referral_rate(self, referral_share_bps: u64) -> u64 {
         let taker_rate = self.taker_rate();
         let min_maker_rebate = Self::Base.maker_rate();
-        taker_rate.saturating_sub(min_maker_rebate) / 5
+        taker_rate.saturating_sub(min_maker_rebate) * referral_share_bps / 10_000
     }
 
-    pub fn referral_fee(self, quote_qty: u64) -> u64 {
-        let rate = self.referral_rate();
+    pub fn referral_fee(self, quote_qty: u64, referral_share_bps: u64) -> u64 {
+        let rate = self.referral_rate(referral_share_bps);
         fp32_mul(quote_qty, rate).unwrap()
     }
 }
@@ -447,3 +1786,159 @@ impl CallbackInfo for CallBackInfo {
         &self.user_account
     }
 }
+
+/// A snapshot of an oracle price
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct OraclePrice {
+    /// The price, in units of `10^expo`
+    pub price: i64,
+    /// The confidence interval of the price, in units of `10^expo`
+    pub conf: u64,
+    /// The price exponent
+    pub expo: i32,
+}
+
+/// The record of the taker side of a fill, logged as program data by the new_order and swap instructions
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct FillLog {
+    /// The DEX market
+    pub market: Pubkey,
+    /// The taker's side
+    pub side: u8,
+    /// The matched base quantity
+    pub base_qty: u64,
+    /// The matched quote quantity, fees excluded
+    pub quote_qty: u64,
+    /// The slot of the fill
+    pub slot: u64,
+    /// The taker's user account, or wallet for swaps. Omitted if the market hides fill accounts.
+    pub taker: Option<Pubkey>,
+    /// The oracle price at the time of the fill, if the market has an oracle with a current price
+    pub oracle_price: Option<OraclePrice>,
+    /// The client order id of the taker order, for new_order fills
+    pub client_order_id: Option<u128>,
+}
+
+impl FillLog {
+    pub(crate) fn emit(&self) -> Result<(), ProgramError> {
+        solana_program::log::sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// The fees paid by the taker of a fill, logged as program data by the new_order and swap instructions right after
+/// the corresponding [`FillLog`]
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct FeeLog {
+    /// The DEX market
+    pub market: Pubkey,
+    /// The taker fee, including the volatility surcharge
+    pub taker_fee: u64,
+    /// The royalties fee
+    pub royalties_fee: u64,
+    /// The part of the taker fee transferred to the referrer
+    pub referral_fee: u64,
+    /// The referrer's token account, if the order was referred
+    pub referrer: Option<Pubkey>,
+}
+
+impl FeeLog {
+    pub(crate) fn emit(&self) -> Result<(), ProgramError> {
+        solana_program::log::sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// The receipt of the lot rounding of an order, logged as program data by the new_order instruction when its base
+/// quantity is not a multiple of the base lot size. The dust is left out of the order and never debited.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct RoundingLog {
+    /// The DEX market
+    pub market: Pubkey,
+    /// The order's side
+    pub side: u8,
+    /// The base quantity requested by the order
+    pub requested_base_qty: u64,
+    /// The base quantity rounded down to a multiple of the base lot size
+    pub base_qty: u64,
+}
+
+impl RoundingLog {
+    pub(crate) fn emit(&self) -> Result<(), ProgramError> {
+        solana_program::log::sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// The notification of a resting order placed with `notify_on_cross`, logged as program data by the new_order
+/// instruction when a taker trades at the order's price level without filling the order because of its queue position
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct CrossNotificationLog {
+    /// The DEX market
+    pub market: Pubkey,
+    /// The user account of the resting order
+    pub user_account: Pubkey,
+    /// The id of the resting order
+    pub order_id: u128,
+    /// The slot of the trade
+    pub slot: u64,
+}
+
+impl CrossNotificationLog {
+    pub(crate) fn emit(&self) -> Result<(), ProgramError> {
+        solana_program::log::sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// The record of the maker side of a fill, logged as program data by the consume_events instruction when the fill
+/// event is consumed
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct MakerFillLog {
+    /// The DEX market
+    pub market: Pubkey,
+    /// The maker's user account. Omitted if the market hides fill accounts.
+    pub maker: Option<Pubkey>,
+    /// The id of the maker order
+    pub order_id: u128,
+    /// The client order id given when the maker order was placed, unless the maker account was not provided and the
+    /// fill was credited to the escrow
+    pub client_order_id: Option<u128>,
+    /// The matched base quantity
+    pub base_qty: u64,
+    /// The matched quote quantity, fees excluded
+    pub quote_qty: u64,
+    /// The rebate paid to the maker
+    pub maker_rebate: u64,
+}
+
+impl MakerFillLog {
+    pub(crate) fn emit(&self) -> Result<(), ProgramError> {
+        solana_program::log::sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// The record of an order leaving the book, logged as program data by the consume_events instruction when the out
+/// event is consumed
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub struct OutLog {
+    /// The DEX market
+    pub market: Pubkey,
+    /// The user account of the order
+    pub user_account: Pubkey,
+    /// The id of the order
+    pub order_id: u128,
+    /// The client order id given when the order was placed, unless the user account was not provided and the
+    /// remaining balance was credited to the escrow
+    pub client_order_id: Option<u128>,
+    /// The base quantity which was not filled
+    pub base_qty: u64,
+}
+
+impl OutLog {
+    pub(crate) fn emit(&self) -> Result<(), ProgramError> {
+        solana_program::log::sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
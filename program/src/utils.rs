@@ -1,8 +1,16 @@
-use crate::error::DexError;
+// The checks are only used by the instruction processors, which are not compiled to wasm
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+use crate::error::{DexError, ACCOUNT_CHECK_FAILED_LOG};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::state::OraclePrice;
+use asset_agnostic_orderbook::state::critbit::{InnerNode, Node, NodeHandle, Slab};
+#[cfg(not(target_arch = "wasm32"))]
 use mpl_token_metadata::{
     pda::find_metadata_account,
     state::{Creator, Metadata, TokenMetadataAccount},
 };
+#[cfg(not(target_arch = "wasm32"))]
+use pyth_sdk_solana::load_price_feed_from_account_info;
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
     pubkey::Pubkey,
@@ -15,6 +23,7 @@ pub fn check_account_key(
     error: DexError,
 ) -> Result<(), DexError> {
     if account.key != key {
+        msg!("{}{}", ACCOUNT_CHECK_FAILED_LOG, account.key);
         return Err(error);
     }
     Ok(())
@@ -26,6 +35,7 @@ pub fn check_account_owner(
     error: DexError,
 ) -> Result<(), DexError> {
     if account.owner != owner {
+        msg!("{}{}", ACCOUNT_CHECK_FAILED_LOG, account.key);
         return Err(error);
     }
     Ok(())
@@ -33,11 +43,26 @@ pub fn check_account_owner(
 
 pub fn check_signer(account: &AccountInfo) -> ProgramResult {
     if !(account.is_signer) {
+        msg!("{}{}", ACCOUNT_CHECK_FAILED_LOG, account.key);
         return Err(ProgramError::MissingRequiredSignature);
     }
     Ok(())
 }
 
+/// Returns the current price of a Pyth price account, or `None` if the price is not currently trading
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn get_oracle_price(oracle: &AccountInfo) -> Result<Option<OraclePrice>, ProgramError> {
+    let price_feed = load_price_feed_from_account_info(oracle).map_err(|e| {
+        msg!("Failed to load the oracle price feed: {:?}", e);
+        DexError::InvalidOracleAccount
+    })?;
+    Ok(price_feed.get_current_price().map(|p| OraclePrice {
+        price: p.price,
+        conf: p.conf,
+        expo: p.expo,
+    }))
+}
+
 pub(crate) const FP_32_ONE: u64 = 1 << 32;
 
 /// a is fp0, b is fp32 and result is a/b fp0
@@ -54,6 +79,11 @@ pub(crate) fn fp32_mul(a: u64, b_fp32: u64) -> Option<u64> {
         .and_then(|e| safe_downcast(e >> 32))
 }
 
+/// Converts a rate of at most 10,000 basis points to a fp32 rate
+pub(crate) fn bps_to_fp32(bps: u64) -> u64 {
+    (bps << 32) / 10_000
+}
+
 fn safe_downcast(n: u128) -> Option<u64> {
     static BOUND: u128 = u64::MAX as u128;
     if n > BOUND {
@@ -63,6 +93,51 @@ fn safe_downcast(n: u128) -> Option<u64> {
     }
 }
 
+/// Walks the leaves of a critbit tree in key order without consuming the slab or allocating, see [`leaf_handles`]
+pub(crate) struct LeafHandles<'a> {
+    inner_nodes: &'a [InnerNode],
+    // The critbit prefixes of a path strictly increase, so that a path holds at most 128 inner nodes, each of which
+    // leaves at most one sibling to visit
+    stack: [NodeHandle; 128],
+    len: usize,
+    ascending: bool,
+}
+
+impl<'a> Iterator for LeafHandles<'a> {
+    type Item = NodeHandle;
+
+    fn next(&mut self) -> Option<NodeHandle> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let mut handle = self.stack[self.len];
+        while let Node::Inner = Node::from_handle(handle) {
+            let children = self.inner_nodes[(!handle) as usize].children;
+            self.stack[self.len] = children[self.ascending as usize];
+            self.len += 1;
+            handle = children[!self.ascending as usize];
+        }
+        Some(handle)
+    }
+}
+
+/// The leaf handles of a slab by ascending or descending key, which index both its leaves and its callback infos
+pub(crate) fn leaf_handles<'a, C>(slab: &'a Slab<'_, C>, ascending: bool) -> LeafHandles<'a> {
+    let mut walk = LeafHandles {
+        inner_nodes: &slab.inner_nodes[..],
+        stack: [0; 128],
+        len: 0,
+        ascending,
+    };
+    if let Some(root) = slab.root() {
+        walk.stack[0] = root;
+        walk.len = 1;
+    }
+    walk
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 pub fn check_metadata_account(account: &AccountInfo, mint: &Pubkey) -> ProgramResult {
     let expected = find_metadata_account(mint).0;
     check_account_key(account, &expected, DexError::InvalidMetadataKey)?;
@@ -78,6 +153,7 @@ pub fn check_metadata_account(account: &AccountInfo, mint: &Pubkey) -> ProgramRe
 }
 
 #[allow(dead_code)]
+#[cfg(not(target_arch = "wasm32"))]
 pub fn get_verified_creators(account: &AccountInfo) -> Option<Vec<Creator>> {
     let metadata: Metadata = Metadata::from_account_info(account).unwrap();
     let creators = metadata.data.creators;
@@ -94,6 +170,7 @@ pub fn get_verified_creators(account: &AccountInfo) -> Option<Vec<Creator>> {
     None
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 pub fn verify_metadata(creators: &[Creator]) -> ProgramResult {
     let sum: u8 = creators.iter().map(|x| x.share).sum();
     if sum != 100 {
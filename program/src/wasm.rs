@@ -0,0 +1,253 @@
+//! This module is only compiled for the `wasm32-unknown-unknown` target, which lets browser clients decode
+//! the program's accounts and reproduce the market's math without a backend.
+//!
+//! The orderbook itself is decoded with the asset agnostic orderbook's own bindings.
+#![allow(missing_docs)]
+use bytemuck::{pod_read_unaligned, Pod};
+use wasm_bindgen::prelude::*;
+
+use crate::state::{
+    AccountTag, DexState, FeeTier, MarketFeeType, Order, UserAccountHeader, DEX_STATE_LEN,
+    USER_ACCOUNT_HEADER_LEN,
+};
+
+fn read<T: Pod>(data: &[u8], len: usize) -> Result<T, JsValue> {
+    if data.len() < len {
+        return Err(JsValue::from_str("The account data is too short"));
+    }
+    Ok(pod_read_unaligned(&data[..len]))
+}
+
+/// A decoded DEX market
+#[wasm_bindgen]
+pub struct Market {
+    state: DexState,
+}
+
+#[wasm_bindgen]
+impl Market {
+    /// Decodes the data of a DEX market account
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> Result<Market, JsValue> {
+        let state: DexState = read(data, DEX_STATE_LEN)?;
+        if state.tag != AccountTag::DexState as u64 {
+            return Err(JsValue::from_str("The account is not a DEX market"));
+        }
+        Ok(Self { state })
+    }
+
+    pub fn base_mint(&self) -> Vec<u8> {
+        self.state.base_mint.to_bytes().to_vec()
+    }
+
+    pub fn quote_mint(&self) -> Vec<u8> {
+        self.state.quote_mint.to_bytes().to_vec()
+    }
+
+    pub fn orderbook(&self) -> Vec<u8> {
+        self.state.orderbook.to_bytes().to_vec()
+    }
+
+    pub fn admin(&self) -> Vec<u8> {
+        self.state.admin.to_bytes().to_vec()
+    }
+
+    pub fn pending_admin(&self) -> Vec<u8> {
+        self.state.pending_admin.to_bytes().to_vec()
+    }
+
+    /// Returns the halted sides of the market, see `HALT_BIDS_MASK` and `HALT_ASKS_MASK`
+    pub fn halt_flags(&self) -> u64 {
+        self.state.halt_flags
+    }
+
+    pub fn min_base_order_size(&self) -> u64 {
+        self.state.min_base_order_size
+    }
+
+    pub fn base_currency_multiplier(&self) -> u64 {
+        self.state.base_currency_multiplier
+    }
+
+    pub fn quote_currency_multiplier(&self) -> u64 {
+        self.state.quote_currency_multiplier
+    }
+
+    pub fn status(&self) -> u8 {
+        self.state.status
+    }
+
+    pub fn batch_slots(&self) -> u64 {
+        self.state.batch_slots
+    }
+
+    pub fn crank_fee_share_bps(&self) -> u64 {
+        self.state.crank_fee_share_bps
+    }
+
+    pub fn referral_share_bps(&self) -> u64 {
+        self.state.referral_share_bps
+    }
+
+    pub fn maker_rebate_bps(&self) -> u64 {
+        self.state.maker_rebate_bps
+    }
+
+    /// Returns the timestamp of the latest emergency halt of the market, zero if there was none
+    pub fn emergency_halt_timestamp(&self) -> i64 {
+        self.state.emergency_halt_timestamp
+    }
+
+    /// Returns the raw quote amount charged on the early cancellation of an unfilled order
+    pub fn cancel_fee(&self) -> u64 {
+        self.state.cancel_fee
+    }
+
+    pub fn cancel_fee_slots(&self) -> u64 {
+        self.state.cancel_fee_slots
+    }
+
+    pub fn min_resting_slots(&self) -> u64 {
+        self.state.min_resting_slots
+    }
+
+    /// Returns the cached FP32 price of the best bid, zero if there is no bid
+    pub fn best_bid(&self) -> u64 {
+        self.state.best_bid
+    }
+
+    /// Returns the cached FP32 price of the best ask, zero if there is no ask
+    pub fn best_ask(&self) -> u64 {
+        self.state.best_ask
+    }
+
+    pub fn top_of_book_slot(&self) -> u64 {
+        self.state.top_of_book_slot
+    }
+
+    /// Returns the timestamp after which the vaults of a migrating market can be exported
+    pub fn migration_unlock_timestamp(&self) -> i64 {
+        self.state.migration_unlock_timestamp
+    }
+
+    /// Returns the taker fee multiplier which applies to orders placed at the given slot
+    pub fn volatility_multiplier(&self, slot: u64) -> u64 {
+        self.state.volatility_multiplier(slot)
+    }
+
+    /// Returns the crank reward multiplier earned by consuming the pending events at the given slot
+    pub fn crank_reward_multiplier(&self, slot: u64) -> u64 {
+        self.state.crank_reward_multiplier(slot)
+    }
+
+    /// Returns the raw quote amount matching a raw base amount at a FP32 price, if it doesn't overflow
+    pub fn get_quote_from_base(&self, raw_base_amount: u64, price_fp32: u64) -> Option<u64> {
+        self.state.get_quote_from_base(raw_base_amount, price_fp32)
+    }
+
+    /// Returns the raw base amount, in whole base lots, which can be bought with a raw quote amount at a FP32 price
+    pub fn get_base_from_quote(&self, raw_quote_amount: u64, price_fp32: u64) -> Option<u64> {
+        self.state.get_base_from_quote(raw_quote_amount, price_fp32)
+    }
+
+    /// Returns the taker fee paid on a quote amount for a given fee tier
+    pub fn taker_fee(&self, fee_tier: u8, quote_qty: u64) -> Result<u64, JsValue> {
+        Ok(self.fee_tier(fee_tier)?.taker_fee(quote_qty))
+    }
+
+    /// Returns the largest quote amount which can be matched by a bid of `quote_qty` once taker fees are paid
+    pub fn remove_taker_fee(&self, fee_tier: u8, quote_qty: u64) -> Result<u64, JsValue> {
+        Ok(self.fee_tier(fee_tier)?.remove_taker_fee(quote_qty))
+    }
+
+    fn fee_tier(&self, fee_tier: u8) -> Result<FeeTier, JsValue> {
+        if self.state.fee_type == MarketFeeType::Stable as u8 {
+            return Ok(FeeTier::Stable);
+        }
+        match fee_tier {
+            0..=5 => Ok(FeeTier::from_u8(fee_tier).0),
+            6 => Ok(FeeTier::MSrm),
+            8 => Ok(FeeTier::Dmm),
+            _ => Err(JsValue::from_str("Invalid fee tier")),
+        }
+    }
+}
+
+/// A decoded DEX user account
+#[wasm_bindgen]
+pub struct UserAccount {
+    header: UserAccountHeader,
+    orders: Vec<Order>,
+}
+
+#[wasm_bindgen]
+impl UserAccount {
+    /// Decodes the data of a DEX user account
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> Result<UserAccount, JsValue> {
+        let header: UserAccountHeader = read(data, USER_ACCOUNT_HEADER_LEN)?;
+        if header.tag != AccountTag::UserAccount as u64 {
+            return Err(JsValue::from_str("The account is not a DEX user account"));
+        }
+        let orders = data[USER_ACCOUNT_HEADER_LEN..]
+            .chunks_exact(Order::LEN)
+            .take(header.number_of_orders as usize)
+            .map(pod_read_unaligned)
+            .collect();
+        Ok(Self { header, orders })
+    }
+
+    pub fn market(&self) -> Vec<u8> {
+        self.header.market.to_bytes().to_vec()
+    }
+
+    pub fn owner(&self) -> Vec<u8> {
+        self.header.owner.to_bytes().to_vec()
+    }
+
+    pub fn base_token_free(&self) -> u64 {
+        self.header.base_token_free
+    }
+
+    pub fn base_token_locked(&self) -> u64 {
+        self.header.base_token_locked
+    }
+
+    pub fn quote_token_free(&self) -> u64 {
+        self.header.quote_token_free
+    }
+
+    pub fn quote_token_locked(&self) -> u64 {
+        self.header.quote_token_locked
+    }
+
+    pub fn accumulated_rebates(&self) -> u64 {
+        self.header.accumulated_rebates
+    }
+
+    pub fn metadata(&self) -> Vec<u8> {
+        self.header.metadata.to_vec()
+    }
+
+    pub fn last_active_slot(&self) -> u64 {
+        self.header.last_active_slot
+    }
+
+    /// Returns the ids of the active orders as consecutive 16 bytes little endian integers
+    pub fn order_ids(&self) -> Vec<u8> {
+        self.orders
+            .iter()
+            .flat_map(|o| o.id.to_le_bytes())
+            .collect()
+    }
+
+    /// Returns the FP32 limit prices of the active orders
+    pub fn order_prices(&self) -> Vec<u64> {
+        self.orders.iter().map(|o| (o.id >> 64) as u64).collect()
+    }
+
+    /// Returns the slots at which the active orders were placed
+    pub fn order_placed_slots(&self) -> Vec<u64> {
+        self.orders.iter().map(|o| o.placed_slot).collect()
+    }
+}
@@ -0,0 +1,7 @@
+#!/usr/bin/env bash
+# Runs the test suite against both the production build and the strict-checks build
+set -euo pipefail
+cd "$(dirname "$0")"
+
+cargo test "$@"
+cargo test --features strict-checks "$@"
@@ -0,0 +1,82 @@
+use dex_v4::instruction_auto::{cancel_order, set_referral_share, DexInstruction};
+use num_traits::FromPrimitive;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn test_account_metas_match_builders() {
+    let keys = (0..7).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let instruction = cancel_order(
+        dex_v4::ID,
+        cancel_order::Accounts {
+            market: &keys[0],
+            orderbook: &keys[1],
+            event_queue: &keys[2],
+            bids: &keys[3],
+            asks: &keys[4],
+            user: &keys[5],
+            user_owner: &keys[6],
+        },
+        cancel_order::Params {
+            order_id: 0,
+            order_index: 0,
+            is_client_id: false,
+            _padding: [0; 7],
+        },
+    );
+    let metas = DexInstruction::CancelOrder.get_accounts_metas();
+    assert_eq!(metas.len(), instruction.accounts.len());
+    for (meta, account) in metas.iter().zip(instruction.accounts.iter()) {
+        assert_eq!(meta.writable, account.is_writable, "{}", meta.name);
+        assert_eq!(meta.signer, account.is_signer, "{}", meta.name);
+    }
+    assert_eq!(
+        DexInstruction::CancelOrder
+            .get_account_meta(6)
+            .unwrap()
+            .name,
+        "user_owner"
+    );
+    assert!(DexInstruction::CancelOrder.get_account_meta(7).is_none());
+
+    let instruction = set_referral_share(
+        dex_v4::ID,
+        set_referral_share::Accounts {
+            market: &keys[0],
+            market_admin: &keys[1],
+        },
+        set_referral_share::Params {
+            referral_share_bps: 0,
+        },
+    );
+    let metas = DexInstruction::SetReferralShare.get_accounts_metas();
+    assert_eq!(metas.len(), instruction.accounts.len());
+    assert!(metas[1].signer && !metas[1].writable);
+}
+
+#[test]
+fn test_account_metas_variable_lists() {
+    // The accounts following the user accounts of consume_events depend on the length of the list
+    let metas = DexInstruction::ConsumeEvents.get_accounts_metas();
+    assert!(metas[4].variable);
+    assert_eq!(
+        DexInstruction::ConsumeEvents
+            .get_account_meta(3)
+            .unwrap()
+            .name,
+        "reward_target"
+    );
+    assert!(DexInstruction::ConsumeEvents.get_account_meta(4).is_none());
+
+    // Every instruction is described, and only the optional accounts can be left out
+    let mut tag = 0;
+    while let Some(instruction) = DexInstruction::from_u8(tag) {
+        let metas = instruction.get_accounts_metas();
+        assert!(!metas.is_empty());
+        let first_optional = metas.iter().position(|m| m.optional);
+        if let Some(i) = first_optional {
+            assert!(metas[i..].iter().all(|m| m.optional || m.variable));
+        }
+        tag += 1;
+    }
+    assert_eq!(tag, 59);
+}
@@ -0,0 +1,91 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::book::{BookSide, Level};
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+pub mod common;
+use crate::common::utils::{create_test_market, sign_send_instructions};
+
+#[tokio::test]
+async fn test_book_views() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let first_user = market.create_user(&mut prg_test_ctx).await;
+    let second_user = market.create_user(&mut prg_test_ctx).await;
+
+    for (user, price, size) in [
+        (&first_user, 1_000, 100),
+        (&second_user, 1_000, 50),
+        (&second_user, 998, 20),
+        (&first_user, 999, 10),
+    ] {
+        let ix = market.new_order(user, Side::Bid, price, size, OrderType::Limit, 10);
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .unwrap();
+    }
+
+    let mut bids_data = prg_test_ctx
+        .banks_client
+        .get_account(market.bids)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    // The account tag of the other side is rejected
+    assert!(BookSide::from_buffer(&mut bids_data.clone(), Side::Ask).is_err());
+    let bids = BookSide::from_buffer(&mut bids_data, Side::Bid).unwrap();
+
+    // The levels are aggregated from the best price
+    let levels = bids.levels().take(2).collect::<Vec<_>>();
+    assert_eq!(
+        levels,
+        vec![
+            Level {
+                price: 1_000 << 32,
+                base_qty: 150,
+                orders: 2,
+            },
+            Level {
+                price: 999 << 32,
+                base_qty: 10,
+                orders: 1,
+            },
+        ]
+    );
+    assert_eq!(bids.levels().count(), 3);
+
+    // The orders at the same price are in queue order
+    let best = bids.best().unwrap();
+    assert_eq!(best.user_account, first_user.user_account);
+    assert_eq!(best.base_qty, 100);
+
+    let sizes = bids
+        .orders_of(&second_user.user_account)
+        .map(|o| o.base_qty)
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, vec![50, 20]);
+
+    // The asks are walked from the lowest price
+    for price in [1_003, 1_001, 1_002] {
+        let ix = market.new_order(&first_user, Side::Ask, price, 10, OrderType::Limit, 10);
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&first_user.owner])
+            .await
+            .unwrap();
+    }
+    let mut asks_data = prg_test_ctx
+        .banks_client
+        .get_account(market.asks)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let asks = BookSide::from_buffer(&mut asks_data, Side::Ask).unwrap();
+    let prices = asks.orders().map(|o| o.price >> 32).collect::<Vec<_>>();
+    assert_eq!(prices, vec![1_001, 1_002, 1_003]);
+    assert_eq!(asks.best().unwrap().user_account, first_user.user_account);
+}
@@ -0,0 +1,85 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::cancel_all_orders;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program::instruction::Instruction;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_dex_state, get_user_account_header, sign_send_instructions, TestMarket,
+    TestUser,
+};
+
+fn cancel_all(market: &TestMarket, user: &TestUser, max_orders: u64) -> Instruction {
+    cancel_all_orders(
+        dex_v4::ID,
+        cancel_all_orders::Accounts {
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            bids: &market.bids,
+            asks: &market.asks,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+        },
+        cancel_all_orders::Params { max_orders },
+    )
+}
+
+#[tokio::test]
+async fn test_cancel_all_orders() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+
+    // A quote of two bids and two asks
+    for (side, price) in [
+        (Side::Bid, 998),
+        (Side::Bid, 999),
+        (Side::Ask, 1_001),
+        (Side::Ask, 1_002),
+    ] {
+        let ix = market.new_order(&user, side, price, 100, OrderType::Limit, 10);
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .unwrap();
+    }
+
+    // The maximum number of orders should be nonzero
+    let ix = cancel_all(&market, &user, 0);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    // The most recent orders are cancelled first
+    let ix = cancel_all(&market, &user, 3);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.number_of_orders, 1);
+    assert_eq!(header.base_token_locked, 0);
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_bid, 998 << 32);
+    assert_eq!(dex_state.best_ask, 0);
+
+    let ix = cancel_all(&market, &user, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.number_of_orders, 0);
+    assert_eq!(header.quote_token_locked, 0);
+    // The locked funds are released to the user account
+    assert_eq!(header.base_token_free, 200);
+    assert!(header.quote_token_free >= (998 + 999) * 100);
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_bid, 0);
+}
@@ -0,0 +1,97 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::cancel_order;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions, TestMarket,
+    TestUser,
+};
+
+fn cancel_by_client_id(
+    market: &TestMarket,
+    user: &TestUser,
+    client_order_id: u128,
+) -> solana_program::instruction::Instruction {
+    cancel_order(
+        dex_v4::ID,
+        cancel_order::Accounts {
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            bids: &market.bids,
+            asks: &market.asks,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+        },
+        cancel_order::Params {
+            order_id: client_order_id,
+            order_index: 0,
+            is_client_id: true,
+            _padding: [0; 7],
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_cancel_order_by_client_id() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+
+    // Two resting bids tracked by their client order ids
+    for (client_order_id, price) in [(7u128, 1_000), (8u128, 999)] {
+        let mut params = order_params(Side::Bid, price, 100, OrderType::Limit, 10);
+        params.client_order_id = bytemuck::cast(client_order_id);
+        let ix = market.new_order_with_params(&user, params);
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .unwrap();
+    }
+
+    // A client order id cannot be used by two open orders
+    let mut params = order_params(Side::Bid, 998, 100, OrderType::Limit, 10);
+    params.client_order_id = bytemuck::cast(7u128);
+    let ix = market.new_order_with_params(&user, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    // An unknown client order id is rejected
+    let ix = cancel_by_client_id(&market, &user, 9);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    // The order index is ignored, the order is found from its client order id
+    let before = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    let ix = cancel_by_client_id(&market, &user, 8);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.number_of_orders, 1);
+    assert!(header.quote_token_locked < before.quote_token_locked);
+    assert_eq!(
+        header.quote_token_free + header.quote_token_locked,
+        before.quote_token_free + before.quote_token_locked
+    );
+
+    // A cancelled client order id cannot be cancelled twice
+    let ix = cancel_by_client_id(&market, &user, 8);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+}
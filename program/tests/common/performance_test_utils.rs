@@ -106,6 +106,10 @@ pub async fn create_aob_dex(
 
     // Create the dex market
     let market_admin = Keypair::new();
+    let (create_market_config, _) = Pubkey::find_program_address(
+        &[dex_v4::instruction_auto::set_create_market_config::CREATE_MARKET_CONFIG_SEED],
+        &dex_v4::ID,
+    );
     let create_market_instruction = dex_v4::instruction_auto::create_market(
         dex_v4::ID,
         dex_v4::instruction_auto::create_market::Accounts {
@@ -118,6 +122,11 @@ pub async fn create_aob_dex(
             asks: &aaob_accounts.asks,
             bids: &aaob_accounts.bids,
             token_metadata: &find_metadata_account(&base_mint_key).0,
+            create_market_config: &create_market_config,
+            fee_payer: None,
+            fee_source: None,
+            treasury: None,
+            fee_program: None,
         },
         dex_v4::instruction_auto::create_market::Params {
             signer_nonce: signer_nonce as u64,
@@ -125,6 +134,10 @@ pub async fn create_aob_dex(
             tick_size: 1,
             base_currency_multiplier: 1,
             quote_currency_multiplier: 1,
+            min_order_tick_distance: 0,
+            base_symbol: [0; 16],
+            quote_symbol: [0; 16],
+            market_name: [0; 32],
         },
     );
     sign_send_instructions(&mut pgr_test_ctx, vec![create_market_instruction], vec![])
@@ -171,6 +184,7 @@ pub async fn create_aob_dex(
             initialize_account::Params {
                 market: market_account.pubkey(),
                 max_orders: 100,
+                account_index: 0,
             },
         );
         sign_send_instructions(
@@ -429,6 +443,9 @@ pub async fn aob_dex_new_order(
             },
             user_owner: &dex_test_ctx.user_owners[user_account_index].pubkey(),
             discount_token_account: None,
+            oracle: None,
+            session: None,
+            dmm_registry: None,
             fee_referral_account: None,
         },
         new_order::Params {
@@ -440,12 +457,21 @@ pub async fn aob_dex_new_order(
             self_trade_behavior: asset_agnostic_orderbook::state::SelfTradeBehavior::DecrementTake
                 as u8,
             match_limit: 10,
+            expiry_timestamp: 0,
+            display_base_qty: 0,
+            min_base_qty: 0,
             #[cfg(not(any(feature = "aarch64-test", target_arch = "aarch64")))]
             client_order_id: 0,
             #[cfg(any(feature = "aarch64-test", target_arch = "aarch64"))]
             client_order_id: bytemuck::cast(0u128),
             has_discount_token_account: false as u8,
-            _padding: 0,
+            has_oracle_account: false as u8,
+            has_session: false as u8,
+            has_dmm_registry: false as u8,
+            notify_on_cross: 0,
+            quantity_mode: 0,
+            use_free_balances: 0,
+            _padding: [0; 6],
         },
     );
     sign_send_instructions(
@@ -0,0 +1,51 @@
+# Generates src/cu_costs.rs from the output of the compute_units test, see run_cu_bench.sh
+import math
+
+# The constants, in the order of the generated module, with their descriptions
+BENCHES = [
+    ("NEW_ORDER_POST", "A new_order which posts on the book without matching"),
+    ("NEW_ORDER_TAKE", "A new_order which fully fills a single resting order"),
+    ("CONSUME_EVENT", "A consume_events which consumes a single fill event"),
+    ("CONSUME_EVENTS_EMPTY", "A consume_events which finds the event queue empty"),
+    ("CANCEL_ORDER", "A cancel_order of a resting order, found from its client order id"),
+]
+# The default compute budget of an instruction, used for the benches missing from the log
+DEFAULT_UNITS = 200_000
+# The measurements depend on the state of the book, a margin keeps the budgets from being exceeded
+MARGIN = 1.1
+
+dex_program_id = ""
+current = None
+measured = {}
+try:
+    log_file = open("cu.log", "r")
+except FileNotFoundError:
+    log_file = []
+for line in log_file:
+    if "Dex_key" in line:
+        dex_program_id = line.split(" ")[1].strip("\n")
+        continue
+    if line.startswith("CU bench "):
+        current = line.split(" ")[2].strip("\n")
+        continue
+    if current is not None and (dex_program_id + " consumed") in line:
+        tokens = line.split(" ")
+        measured[current] = int(tokens[tokens.index("consumed") + 1])
+        current = None
+
+out = open("../../../src/cu_costs.rs", "w")
+out.write("//! Compute unit budgets of the instructions, for clients to set the compute budget of their transactions\n")
+out.write("//! without simulating them.\n")
+out.write("//!\n")
+out.write("//! This file is generated by `tests/common/performance_tests_parse/run_cu_bench.sh`, do not edit it.\n")
+for name, description in BENCHES:
+    out.write("\n")
+    out.write("/// {}\n".format(description))
+    if name in measured:
+        units = int(math.ceil(measured[name] * MARGIN / 1000) * 1000)
+        out.write("/// (measured at {} compute units)\n".format(measured[name]))
+    else:
+        units = DEFAULT_UNITS
+        out.write("/// (not measured, defaults to the compute budget of an instruction)\n")
+    out.write("pub const {}: u32 = {:_};\n".format(name, units))
+print(measured)
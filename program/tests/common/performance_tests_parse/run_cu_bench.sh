@@ -0,0 +1,5 @@
+#!/bin/bash
+
+RUST_BACKTRACE=1 cargo test-bpf --test compute_units -- --nocapture &> tests/common/performance_tests_parse/cu.log
+cd  tests/common/performance_tests_parse/
+python3 gen_cu_costs.py
@@ -1,16 +1,24 @@
 use asset_agnostic_orderbook::state::event_queue::EventQueue;
 use asset_agnostic_orderbook::state::market_state::MarketState;
-use dex_v4::state::CallBackInfo;
+use asset_agnostic_orderbook::state::{SelfTradeBehavior, Side};
+use bytemuck::{try_from_bytes, try_from_bytes_mut};
+use dex_v4::instruction_auto::{create_market, initialize_account, new_order};
+use dex_v4::state::{
+    CallBackInfo, DexState, UserAccountHeader, DEX_STATE_LEN, USER_ACCOUNT_HEADER_LEN,
+};
+use mpl_token_metadata::pda::find_metadata_account;
 use solana_program::instruction::Instruction;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
-use solana_program::system_instruction::create_account;
+use solana_program::system_instruction::{create_account, transfer};
+use solana_program::system_program;
 use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
 use solana_sdk::account::Account;
 use solana_sdk::signature::Signer;
 use solana_sdk::transport::TransportError;
 use solana_sdk::{signature::Keypair, transaction::Transaction};
 use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_token::instruction::{mint_to, sync_native};
 use spl_token::state::Mint;
 use std::str::FromStr;
 
@@ -172,3 +180,352 @@ pub async fn create_aob_market_and_accounts(
         bids: bids_account.pubkey(),
     }
 }
+
+/// A market created without token metadata, with its mints
+pub struct TestMarket {
+    pub market: Pubkey,
+    pub market_admin: Keypair,
+    pub orderbook: Pubkey,
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub base_mint_auth: Keypair,
+    pub quote_mint: Pubkey,
+    pub quote_mint_auth: Keypair,
+}
+
+/// A user account of a test market, with funded token accounts
+pub struct TestUser {
+    pub owner: Keypair,
+    pub user_account: Pubkey,
+    pub base_token_account: Pubkey,
+    pub quote_token_account: Pubkey,
+}
+
+/// Starts the program test and creates a market with a tick size of 1 and unit lots
+pub async fn create_test_market(program_test: ProgramTest) -> (ProgramTestContext, TestMarket) {
+    create_test_market_with_lots(program_test, 1, 1).await
+}
+
+/// Starts the program test and creates a market with a tick size of 1 and the given lot sizes
+pub async fn create_test_market_with_lots(
+    program_test: ProgramTest,
+    base_currency_multiplier: u64,
+    quote_currency_multiplier: u64,
+) -> (ProgramTestContext, TestMarket) {
+    create_test_market_with_quote_mint(
+        program_test,
+        base_currency_multiplier,
+        quote_currency_multiplier,
+        None,
+    )
+    .await
+}
+
+/// Starts the program test and creates a market with a tick size of 1, the given lot sizes and the given quote mint
+/// address, such as the wrapped SOL mint, or a new quote mint
+pub async fn create_test_market_with_quote_mint(
+    program_test: ProgramTest,
+    base_currency_multiplier: u64,
+    quote_currency_multiplier: u64,
+    quote_mint_address: Option<&str>,
+) -> (ProgramTestContext, TestMarket) {
+    create_test_market_with_params(
+        program_test,
+        base_currency_multiplier,
+        quote_currency_multiplier,
+        quote_mint_address,
+        0,
+    )
+    .await
+}
+
+/// Starts the program test and creates a market with a tick size of 1, unit lots and the given minimum distance in
+/// ticks between the same-side orders of a user
+pub async fn create_test_market_with_tick_distance(
+    program_test: ProgramTest,
+    min_order_tick_distance: u64,
+) -> (ProgramTestContext, TestMarket) {
+    create_test_market_with_params(program_test, 1, 1, None, min_order_tick_distance).await
+}
+
+async fn create_test_market_with_params(
+    mut program_test: ProgramTest,
+    base_currency_multiplier: u64,
+    quote_currency_multiplier: u64,
+    quote_mint_address: Option<&str>,
+    min_order_tick_distance: u64,
+) -> (ProgramTestContext, TestMarket) {
+    let base_mint_auth = Keypair::new();
+    let (base_mint, _) = mint_bootstrap(None, 0, &mut program_test, &base_mint_auth.pubkey());
+    let quote_mint_auth = Keypair::new();
+    let (quote_mint, _) = mint_bootstrap(
+        quote_mint_address,
+        6,
+        &mut program_test,
+        &quote_mint_auth.pubkey(),
+    );
+    let mut prg_test_ctx = program_test.start_with_context().await;
+    let rent = prg_test_ctx.banks_client.get_rent().await.unwrap();
+
+    let market = Keypair::new();
+    let market_admin = Keypair::new();
+    let ix = create_account(
+        &prg_test_ctx.payer.pubkey(),
+        &market.pubkey(),
+        rent.minimum_balance(DEX_STATE_LEN),
+        DEX_STATE_LEN as u64,
+        &dex_v4::ID,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market])
+        .await
+        .unwrap();
+    let (market_signer, signer_nonce) =
+        Pubkey::find_program_address(&[&market.pubkey().to_bytes()], &dex_v4::ID);
+    let aaob_accounts = create_aob_market_and_accounts(&mut prg_test_ctx, dex_v4::ID).await;
+    let base_vault = create_associated_token(&mut prg_test_ctx, &base_mint, &market_signer)
+        .await
+        .unwrap();
+    let quote_vault = create_associated_token(&mut prg_test_ctx, &quote_mint, &market_signer)
+        .await
+        .unwrap();
+    let (create_market_config, _) = Pubkey::find_program_address(
+        &[dex_v4::instruction_auto::set_create_market_config::CREATE_MARKET_CONFIG_SEED],
+        &dex_v4::ID,
+    );
+    let ix = create_market(
+        dex_v4::ID,
+        create_market::Accounts {
+            base_vault: &base_vault,
+            quote_vault: &quote_vault,
+            market: &market.pubkey(),
+            orderbook: &aaob_accounts.market,
+            market_admin: &market_admin.pubkey(),
+            event_queue: &aaob_accounts.event_queue,
+            asks: &aaob_accounts.asks,
+            bids: &aaob_accounts.bids,
+            token_metadata: &find_metadata_account(&base_mint).0,
+            create_market_config: &create_market_config,
+            fee_payer: None,
+            fee_source: None,
+            treasury: None,
+            fee_program: None,
+        },
+        create_market::Params {
+            signer_nonce: signer_nonce as u64,
+            min_base_order_size: 1,
+            tick_size: 1 << 32,
+            base_currency_multiplier,
+            quote_currency_multiplier,
+            min_order_tick_distance,
+            base_symbol: [0; 16],
+            quote_symbol: [0; 16],
+            market_name: [0; 32],
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+
+    let test_market = TestMarket {
+        market: market.pubkey(),
+        market_admin,
+        orderbook: aaob_accounts.market,
+        event_queue: aaob_accounts.event_queue,
+        bids: aaob_accounts.bids,
+        asks: aaob_accounts.asks,
+        base_vault,
+        quote_vault,
+        base_mint,
+        base_mint_auth,
+        quote_mint,
+        quote_mint_auth,
+    };
+    (prg_test_ctx, test_market)
+}
+
+impl TestMarket {
+    pub async fn create_user(&self, prg_test_ctx: &mut ProgramTestContext) -> TestUser {
+        let owner = Keypair::new();
+        let (user_account, _) = Pubkey::find_program_address(
+            &[&self.market.to_bytes(), &owner.pubkey().to_bytes()],
+            &dex_v4::ID,
+        );
+        let ix = initialize_account(
+            dex_v4::ID,
+            initialize_account::Accounts {
+                system_program: &system_program::ID,
+                user: &user_account,
+                user_owner: &owner.pubkey(),
+                fee_payer: &prg_test_ctx.payer.pubkey(),
+            },
+            initialize_account::Params {
+                market: self.market,
+                max_orders: 10,
+                account_index: 0,
+            },
+        );
+        sign_send_instructions(prg_test_ctx, vec![ix], vec![&owner])
+            .await
+            .unwrap();
+
+        let base_token_account =
+            create_associated_token(prg_test_ctx, &self.base_mint, &owner.pubkey())
+                .await
+                .unwrap();
+        let quote_token_account =
+            create_associated_token(prg_test_ctx, &self.quote_mint, &owner.pubkey())
+                .await
+                .unwrap();
+        let ix = mint_to(
+            &spl_token::ID,
+            &self.base_mint,
+            &base_token_account,
+            &self.base_mint_auth.pubkey(),
+            &[],
+            1 << 25,
+        )
+        .unwrap();
+        sign_send_instructions(prg_test_ctx, vec![ix], vec![&self.base_mint_auth])
+            .await
+            .unwrap();
+        if self.quote_mint == spl_token::native_mint::ID {
+            // Wrapped SOL cannot be minted, it is wrapped from the payer's lamports instead
+            let ixs = vec![
+                transfer(&prg_test_ctx.payer.pubkey(), &quote_token_account, 1 << 30),
+                sync_native(&spl_token::ID, &quote_token_account).unwrap(),
+            ];
+            sign_send_instructions(prg_test_ctx, ixs, vec![])
+                .await
+                .unwrap();
+        } else {
+            let ix = mint_to(
+                &spl_token::ID,
+                &self.quote_mint,
+                &quote_token_account,
+                &self.quote_mint_auth.pubkey(),
+                &[],
+                1 << 30,
+            )
+            .unwrap();
+            sign_send_instructions(prg_test_ctx, vec![ix], vec![&self.quote_mint_auth])
+                .await
+                .unwrap();
+        }
+
+        TestUser {
+            owner,
+            user_account,
+            base_token_account,
+            quote_token_account,
+        }
+    }
+
+    /// Builds a new order at an integer price
+    pub fn new_order(
+        &self,
+        user: &TestUser,
+        side: Side,
+        price: u64,
+        max_base_qty: u64,
+        order_type: new_order::OrderType,
+        match_limit: u64,
+    ) -> Instruction {
+        self.new_order_with_params(
+            user,
+            order_params(side, price, max_base_qty, order_type, match_limit),
+        )
+    }
+
+    pub fn new_order_with_params(&self, user: &TestUser, params: new_order::Params) -> Instruction {
+        new_order(
+            dex_v4::ID,
+            new_order::Accounts {
+                spl_token_program: &spl_token::ID,
+                system_program: &system_program::ID,
+                market: &self.market,
+                orderbook: &self.orderbook,
+                event_queue: &self.event_queue,
+                bids: &self.bids,
+                asks: &self.asks,
+                base_vault: &self.base_vault,
+                quote_vault: &self.quote_vault,
+                user: &user.user_account,
+                user_token_account: if params.side == Side::Bid as u8 {
+                    &user.quote_token_account
+                } else {
+                    &user.base_token_account
+                },
+                user_owner: &user.owner.pubkey(),
+                discount_token_account: None,
+                oracle: None,
+                session: None,
+                dmm_registry: None,
+                fee_referral_account: None,
+            },
+            params,
+        )
+    }
+}
+
+pub async fn get_user_account_header(
+    prg_test_ctx: &mut ProgramTestContext,
+    user_account: &Pubkey,
+) -> UserAccountHeader {
+    let mut data = prg_test_ctx
+        .banks_client
+        .get_account(*user_account)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    *try_from_bytes_mut::<UserAccountHeader>(&mut data[..USER_ACCOUNT_HEADER_LEN]).unwrap()
+}
+
+pub async fn get_dex_state(prg_test_ctx: &mut ProgramTestContext, market: &Pubkey) -> DexState {
+    let data = prg_test_ctx
+        .banks_client
+        .get_account(*market)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    *try_from_bytes::<DexState>(&data[..DEX_STATE_LEN]).unwrap()
+}
+
+/// The parameters of a new order at an integer price
+pub fn order_params(
+    side: Side,
+    price: u64,
+    max_base_qty: u64,
+    order_type: new_order::OrderType,
+    match_limit: u64,
+) -> new_order::Params {
+    new_order::Params {
+        #[cfg(not(any(feature = "aarch64-test", target_arch = "aarch64")))]
+        client_order_id: 0,
+        #[cfg(any(feature = "aarch64-test", target_arch = "aarch64"))]
+        client_order_id: bytemuck::cast(0u128),
+        side: side as u8,
+        limit_price: price << 32,
+        max_base_qty,
+        max_quote_qty: u64::MAX,
+        order_type: order_type as u8,
+        self_trade_behavior: SelfTradeBehavior::DecrementTake as u8,
+        match_limit,
+        expiry_timestamp: 0,
+        display_base_qty: 0,
+        min_base_qty: 0,
+        has_discount_token_account: false as u8,
+        has_oracle_account: false as u8,
+        has_session: false as u8,
+        has_dmm_registry: false as u8,
+        notify_on_cross: 0,
+        quantity_mode: 0,
+        use_free_balances: 0,
+        _padding: [0; 6],
+    }
+}
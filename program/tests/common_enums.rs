@@ -0,0 +1,76 @@
+use asset_agnostic_orderbook::state as aaob;
+use borsh::{BorshDeserialize, BorshSerialize};
+use dex_v4::common::{OrderType, QuantityMode, SelfTradeBehavior, Side};
+use solana_program::program_error::ProgramError;
+use std::convert::TryFrom;
+
+fn check_u8_encoding<T>(variants: &[(T, u8)])
+where
+    T: Copy + PartialEq + std::fmt::Debug + Into<u8> + TryFrom<u8, Error = ProgramError>,
+    T: BorshSerialize + BorshDeserialize,
+{
+    for (variant, encoding) in variants {
+        assert_eq!(Into::<u8>::into(*variant), *encoding);
+        assert_eq!(T::try_from(*encoding).unwrap(), *variant);
+        let serialized = variant.try_to_vec().unwrap();
+        assert_eq!(serialized, vec![*encoding]);
+        assert_eq!(T::try_from_slice(&serialized).unwrap(), *variant);
+    }
+    let invalid = variants.len() as u8;
+    assert_eq!(T::try_from(invalid), Err(ProgramError::InvalidArgument));
+    assert_eq!(T::try_from(u8::MAX), Err(ProgramError::InvalidArgument));
+    assert!(T::try_from_slice(&[invalid]).is_err());
+}
+
+#[test]
+fn test_side_encoding() {
+    check_u8_encoding(&[(Side::Bid, 0), (Side::Ask, 1)]);
+    // The program encoding matches the one of the orderbook
+    assert_eq!(aaob::Side::Bid as u8, Side::Bid as u8);
+    assert_eq!(aaob::Side::Ask as u8, Side::Ask as u8);
+    assert_eq!(Side::from(aaob::Side::from(Side::Bid)), Side::Bid);
+    assert_eq!(Side::from(aaob::Side::from(Side::Ask)), Side::Ask);
+}
+
+#[test]
+fn test_self_trade_behavior_encoding() {
+    check_u8_encoding(&[
+        (SelfTradeBehavior::DecrementTake, 0),
+        (SelfTradeBehavior::CancelProvide, 1),
+        (SelfTradeBehavior::AbortTransaction, 2),
+    ]);
+    for (behavior, aaob_encoding) in [
+        (
+            SelfTradeBehavior::DecrementTake,
+            aaob::SelfTradeBehavior::DecrementTake as u8,
+        ),
+        (
+            SelfTradeBehavior::CancelProvide,
+            aaob::SelfTradeBehavior::CancelProvide as u8,
+        ),
+        (
+            SelfTradeBehavior::AbortTransaction,
+            aaob::SelfTradeBehavior::AbortTransaction as u8,
+        ),
+    ] {
+        assert_eq!(behavior as u8, aaob_encoding);
+        let aaob_behavior: aaob::SelfTradeBehavior = behavior.into();
+        assert_eq!(SelfTradeBehavior::from(aaob_behavior), behavior);
+    }
+}
+
+#[test]
+fn test_order_type_encoding() {
+    check_u8_encoding(&[
+        (OrderType::Limit, 0),
+        (OrderType::ImmediateOrCancel, 1),
+        (OrderType::FillOrKill, 2),
+        (OrderType::PostOnly, 3),
+        (OrderType::Market, 4),
+    ]);
+}
+
+#[test]
+fn test_quantity_mode_encoding() {
+    check_u8_encoding(&[(QuantityMode::Base, 0), (QuantityMode::Quote, 1)]);
+}
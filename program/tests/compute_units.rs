@@ -0,0 +1,128 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::cancel_order;
+use dex_v4::instruction_auto::consume_events;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program::instruction::Instruction;
+use solana_program_test::processor;
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{create_test_market, order_params, sign_send_instructions};
+
+/// Sends a single instruction, preceded by the marker which `gen_cu_costs.py` uses to attribute the compute units
+/// logged by the runtime to a `cu_costs` constant
+async fn bench(
+    prg_test_ctx: &mut ProgramTestContext,
+    name: &str,
+    ix: Instruction,
+    signers: Vec<&Keypair>,
+) {
+    eprintln!("CU bench {}", name);
+    sign_send_instructions(prg_test_ctx, vec![ix], signers)
+        .await
+        .unwrap();
+}
+
+// Run with `tests/common/performance_tests_parse/run_cu_bench.sh` to regenerate `src/cu_costs.rs`
+#[tokio::test]
+async fn test_compute_units() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    eprintln!("Dex_key {}", dex_v4::ID);
+    let maker = market.create_user(&mut prg_test_ctx).await;
+    let taker = market.create_user(&mut prg_test_ctx).await;
+
+    let ix = market.new_order(&maker, Side::Ask, 1_000, 100, OrderType::Limit, 10);
+    bench(&mut prg_test_ctx, "NEW_ORDER_POST", ix, vec![&maker.owner]).await;
+    let mut params = order_params(Side::Ask, 1_001, 100, OrderType::Limit, 10);
+    params.client_order_id = bytemuck::cast(1u128);
+    let ix = market.new_order_with_params(&maker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+        .await
+        .unwrap();
+
+    let ix = market.new_order(
+        &taker,
+        Side::Bid,
+        1_000,
+        100,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    bench(&mut prg_test_ctx, "NEW_ORDER_TAKE", ix, vec![&taker.owner]).await;
+
+    let mut user_accounts = vec![maker.user_account, taker.user_account];
+    user_accounts.sort_unstable();
+    let ix = consume_events(
+        dex_v4::ID,
+        consume_events::Accounts {
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            reward_target: &Keypair::new().pubkey(),
+            user_accounts: &user_accounts,
+            bids: None,
+            asks: None,
+            maker_rewards: None,
+            cranker_identity: None,
+            cranker: None,
+        },
+        consume_events::Params::new(1, true, false, false, false).unwrap(),
+    );
+    bench(&mut prg_test_ctx, "CONSUME_EVENT", ix, vec![]).await;
+    let consume_events_ix = |max_iterations, no_op_err| {
+        consume_events(
+            dex_v4::ID,
+            consume_events::Accounts {
+                market: &market.market,
+                orderbook: &market.orderbook,
+                event_queue: &market.event_queue,
+                reward_target: &Keypair::new().pubkey(),
+                user_accounts: &user_accounts,
+                bids: None,
+                asks: None,
+                maker_rewards: None,
+                cranker_identity: None,
+                cranker: None,
+            },
+            consume_events::Params::new(max_iterations, no_op_err, false, false, false).unwrap(),
+        )
+    };
+    sign_send_instructions(&mut prg_test_ctx, vec![consume_events_ix(10, true)], vec![])
+        .await
+        .unwrap();
+    // An empty queue is a no-op, which fails the transaction when requested
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![consume_events_ix(2, true)], vec![])
+            .await
+            .is_err()
+    );
+    let ix = consume_events_ix(1, false);
+    bench(&mut prg_test_ctx, "CONSUME_EVENTS_EMPTY", ix, vec![]).await;
+
+    // The second ask is still resting on the book
+    let ix = cancel_order(
+        dex_v4::ID,
+        cancel_order::Accounts {
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            bids: &market.bids,
+            asks: &market.asks,
+            user: &maker.user_account,
+            user_owner: &maker.owner.pubkey(),
+        },
+        cancel_order::Params {
+            order_id: 1,
+            order_index: 0,
+            is_client_id: true,
+            _padding: [0; 7],
+        },
+    );
+    bench(&mut prg_test_ctx, "CANCEL_ORDER", ix, vec![&maker.owner]).await;
+}
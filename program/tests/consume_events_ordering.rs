@@ -0,0 +1,109 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::consume_events;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{create_test_market, get_user_account_header, sign_send_instructions};
+
+#[tokio::test]
+async fn test_consume_events_ordering() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+
+    let mut users = vec![];
+    for _ in 0..3 {
+        users.push(market.create_user(&mut prg_test_ctx).await);
+    }
+    let (first_maker, second_maker, taker) = (&users[0], &users[1], &users[2]);
+
+    // Two resting asks, filled in this order by a single bid, which queues a fill event for each maker
+    let ix = market.new_order(first_maker, Side::Ask, 1_000, 100, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&first_maker.owner])
+        .await
+        .unwrap();
+    let ix = market.new_order(second_maker, Side::Ask, 1_001, 100, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&second_maker.owner])
+        .await
+        .unwrap();
+    let ix = market.new_order(
+        taker,
+        Side::Bid,
+        1_001,
+        200,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+
+    let crank = |user_accounts: &[Pubkey], max_iterations: u64| {
+        consume_events(
+            dex_v4::ID,
+            consume_events::Accounts {
+                market: &market.market,
+                orderbook: &market.orderbook,
+                event_queue: &market.event_queue,
+                reward_target: &Keypair::new().pubkey(),
+                user_accounts,
+                bids: None,
+                asks: None,
+                maker_rewards: None,
+                cranker_identity: None,
+                cranker: None,
+            },
+            consume_events::Params::new(max_iterations, true, false, false, false).unwrap(),
+        )
+    };
+
+    // The second fill cannot be applied ahead of the first one
+    let ix = crank(&[second_maker.user_account], 2);
+    assert!(sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .is_err());
+    let header = get_user_account_header(&mut prg_test_ctx, &second_maker.user_account).await;
+    assert_eq!(header.base_token_locked, 100);
+    assert_eq!(header.quote_token_free, 0);
+
+    // Unsorted user accounts are accepted, and the crank stops at the event whose user account is missing
+    let mut user_accounts = [first_maker.user_account, taker.user_account];
+    user_accounts.sort_unstable_by(|a, b| b.cmp(a));
+    let ix = crank(&user_accounts, 2);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &first_maker.user_account).await;
+    assert_eq!(header.base_token_locked, 0);
+    assert!(header.quote_token_free >= 100 * 1_000);
+    let header = get_user_account_header(&mut prg_test_ctx, &second_maker.user_account).await;
+    assert_eq!(header.base_token_locked, 100);
+    assert_eq!(header.quote_token_free, 0);
+
+    // The first fill was popped from the queue, so it is not applied twice by the next crank
+    let mut user_accounts = [first_maker.user_account, second_maker.user_account];
+    user_accounts.sort_unstable_by(|a, b| b.cmp(a));
+    let ix = crank(&user_accounts, 2);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let first_header = get_user_account_header(&mut prg_test_ctx, &first_maker.user_account).await;
+    assert_eq!(first_header.base_token_locked, 0);
+    assert!(first_header.quote_token_free < 2 * 100 * 1_000);
+    let header = get_user_account_header(&mut prg_test_ctx, &second_maker.user_account).await;
+    assert_eq!(header.base_token_locked, 0);
+    assert!(header.quote_token_free >= 100 * 1_001);
+
+    // The queue is now empty
+    let ix = crank(&user_accounts, 2);
+    assert!(sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .is_err());
+}
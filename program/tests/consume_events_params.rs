@@ -0,0 +1,81 @@
+use bytemuck::bytes_of;
+use dex_v4::instruction_auto::consume_events::{Params, LEGACY_PARAMS_LEN};
+use solana_program::program_error::ProgramError;
+
+#[test]
+fn test_consume_events_params() {
+    let params = Params::new(10, true, true, false, true).unwrap();
+    assert_eq!(params.max_iterations, 10);
+    assert!(params.no_op_err());
+    assert!(params.record_cranker_identity());
+    assert!(!params.prune_expired_sessions());
+    assert!(params.repost_iceberg_orders());
+
+    let parsed = Params::parse(bytes_of(&params)).unwrap();
+    assert_eq!(parsed.max_iterations, 10);
+    assert!(parsed.no_op_err());
+    assert!(parsed.record_cranker_identity());
+    assert!(!parsed.prune_expired_sessions());
+    assert!(parsed.repost_iceberg_orders());
+
+    // The maximum number of iterations should be nonzero
+    assert_eq!(
+        Params::new(0, false, false, false, false).err(),
+        Some(ProgramError::InvalidArgument)
+    );
+
+    // The flags should be 0 or 1
+    let invalid = Params {
+        max_iterations: 10,
+        no_op_err: 2,
+        record_cranker_identity: 0,
+        prune_expired_sessions: 0,
+        repost_iceberg_orders: 0,
+    };
+    assert_eq!(
+        Params::parse(bytes_of(&invalid)).err(),
+        Some(ProgramError::InvalidArgument)
+    );
+    let invalid = Params {
+        prune_expired_sessions: 2,
+        ..Params::new(10, false, false, false, false).unwrap()
+    };
+    assert_eq!(
+        Params::parse(bytes_of(&invalid)).err(),
+        Some(ProgramError::InvalidArgument)
+    );
+    let invalid = Params {
+        repost_iceberg_orders: 2,
+        ..Params::new(10, false, false, false, false).unwrap()
+    };
+    assert_eq!(
+        Params::parse(bytes_of(&invalid)).err(),
+        Some(ProgramError::InvalidArgument)
+    );
+
+    // The legacy layout only holds the maximum number of iterations and the no_op_err flag
+    let legacy = Params::parse(&bytes_of(&params)[..LEGACY_PARAMS_LEN]).unwrap();
+    assert_eq!(legacy.max_iterations, 10);
+    assert!(legacy.no_op_err());
+    assert!(!legacy.record_cranker_identity());
+    assert!(!legacy.prune_expired_sessions());
+    assert!(!legacy.repost_iceberg_orders());
+
+    // The layouts in between stop after one of the flags
+    let parsed = Params::parse(&bytes_of(&params)[..LEGACY_PARAMS_LEN + 8]).unwrap();
+    assert!(parsed.record_cranker_identity());
+    assert!(!parsed.repost_iceberg_orders());
+
+    // The instruction data should end on a field of the params
+    for data in [
+        &bytes_of(&params)[1..],
+        &bytes_of(&params)[..LEGACY_PARAMS_LEN - 8],
+        &bytes_of(&params)[..LEGACY_PARAMS_LEN + 1],
+        &[bytes_of(&params), &[0; 8]].concat(),
+    ] {
+        assert_eq!(
+            Params::parse(data).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use bytemuck::bytes_of;
+use dex_v4::instruction_auto::create_market;
+use dex_v4::instruction_auto::set_create_market_config::CREATE_MARKET_CONFIG_SEED;
+use dex_v4::state::{AccountTag, CreateMarketConfig, DEX_STATE_LEN};
+use mpl_token_metadata::pda::find_metadata_account;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction::create_account;
+use solana_program::system_program;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signer};
+pub mod common;
+use crate::common::utils::{
+    create_aob_market_and_accounts, create_associated_token, mint_bootstrap, sign_send_instructions,
+};
+
+const LISTING_FEE: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn test_create_market_listing_fee() {
+    let mut program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let treasury = Pubkey::new_unique();
+    let (create_market_config, _) =
+        Pubkey::find_program_address(&[CREATE_MARKET_CONFIG_SEED], &dex_v4::ID);
+    let config = CreateMarketConfig {
+        tag: AccountTag::CreateMarketConfig as u64,
+        listing_fee: LISTING_FEE,
+        fee_mint: Pubkey::default(),
+        treasury,
+    };
+    program_test.add_account(
+        create_market_config,
+        Account {
+            lamports: 1_000_000,
+            data: bytes_of(&config).to_vec(),
+            owner: dex_v4::ID,
+            ..Account::default()
+        },
+    );
+    let (base_mint, _) = mint_bootstrap(None, 0, &mut program_test, &Pubkey::new_unique());
+    let (quote_mint, _) = mint_bootstrap(None, 6, &mut program_test, &Pubkey::new_unique());
+    let mut prg_test_ctx = program_test.start_with_context().await;
+    let rent = prg_test_ctx.banks_client.get_rent().await.unwrap();
+
+    let market = Keypair::new();
+    let ix = create_account(
+        &prg_test_ctx.payer.pubkey(),
+        &market.pubkey(),
+        rent.minimum_balance(DEX_STATE_LEN),
+        DEX_STATE_LEN as u64,
+        &dex_v4::ID,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market])
+        .await
+        .unwrap();
+    let (market_signer, signer_nonce) =
+        Pubkey::find_program_address(&[&market.pubkey().to_bytes()], &dex_v4::ID);
+    let aaob_accounts = create_aob_market_and_accounts(&mut prg_test_ctx, dex_v4::ID).await;
+    let base_vault = create_associated_token(&mut prg_test_ctx, &base_mint, &market_signer)
+        .await
+        .unwrap();
+    let quote_vault = create_associated_token(&mut prg_test_ctx, &quote_mint, &market_signer)
+        .await
+        .unwrap();
+
+    let fee_payer = prg_test_ctx.payer.pubkey();
+    let create_market_ix = |pay_fee: bool| -> Instruction {
+        create_market(
+            dex_v4::ID,
+            create_market::Accounts {
+                base_vault: &base_vault,
+                quote_vault: &quote_vault,
+                market: &market.pubkey(),
+                orderbook: &aaob_accounts.market,
+                market_admin: &Keypair::new().pubkey(),
+                event_queue: &aaob_accounts.event_queue,
+                asks: &aaob_accounts.asks,
+                bids: &aaob_accounts.bids,
+                token_metadata: &find_metadata_account(&base_mint).0,
+                create_market_config: &create_market_config,
+                fee_payer: pay_fee.then(|| &fee_payer),
+                fee_source: pay_fee.then(|| &fee_payer),
+                treasury: pay_fee.then(|| &treasury),
+                fee_program: pay_fee.then(|| &system_program::ID),
+            },
+            create_market::Params {
+                signer_nonce: signer_nonce as u64,
+                min_base_order_size: 1,
+                tick_size: 1 << 32,
+                base_currency_multiplier: 1,
+                quote_currency_multiplier: 1,
+                min_order_tick_distance: 0,
+                base_symbol: [0; 16],
+                quote_symbol: [0; 16],
+                market_name: [0; 32],
+            },
+        )
+    };
+
+    // The market cannot be created without paying the listing fee
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![create_market_ix(false)], vec![])
+            .await
+            .is_err()
+    );
+
+    sign_send_instructions(&mut prg_test_ctx, vec![create_market_ix(true)], vec![])
+        .await
+        .unwrap();
+    let treasury_account = prg_test_ctx
+        .banks_client
+        .get_account(treasury)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(treasury_account.lamports, LISTING_FEE);
+}
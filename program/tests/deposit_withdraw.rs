@@ -0,0 +1,146 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::OrderType;
+use dex_v4::instruction_auto::{deposit, withdraw};
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions, TestMarket,
+    TestUser,
+};
+
+async fn get_token_balance(prg_test_ctx: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = prg_test_ctx
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+fn deposit_ix(market: &TestMarket, user: &TestUser, base_qty: u64, quote_qty: u64) -> Instruction {
+    deposit(
+        dex_v4::ID,
+        deposit::Accounts {
+            spl_token_program: &spl_token::ID,
+            market: &market.market,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            source_base_account: &user.base_token_account,
+            source_quote_account: &user.quote_token_account,
+        },
+        deposit::Params {
+            base_qty,
+            quote_qty,
+        },
+    )
+}
+
+fn withdraw_ix(market: &TestMarket, user: &TestUser, base_qty: u64, quote_qty: u64) -> Instruction {
+    let (market_signer, _) =
+        Pubkey::find_program_address(&[&market.market.to_bytes()], &dex_v4::ID);
+    withdraw(
+        dex_v4::ID,
+        withdraw::Accounts {
+            spl_token_program: &spl_token::ID,
+            market: &market.market,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            market_signer: &market_signer,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            destination_base_account: &user.base_token_account,
+            destination_quote_account: &user.quote_token_account,
+            settle_allowlist: None,
+        },
+        withdraw::Params {
+            base_qty,
+            quote_qty,
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_deposit_withdraw() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+    let initial_base = get_token_balance(&mut prg_test_ctx, &user.base_token_account).await;
+
+    let ix = deposit_ix(&market, &user, 0, 0);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    let ix = deposit_ix(&market, &user, 1_000, 500_000);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.base_token_free, 1_000);
+    assert_eq!(header.quote_token_free, 500_000);
+    assert_eq!(
+        get_token_balance(&mut prg_test_ctx, &user.base_token_account).await,
+        initial_base - 1_000
+    );
+
+    // The order is funded by the free balance without touching the wallet
+    let mut params = order_params(Side::Ask, 1_000, 100, OrderType::Limit, 10);
+    params.use_free_balances = 1;
+    let ix = market.new_order_with_params(&user, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.base_token_free, 900);
+    assert_eq!(header.base_token_locked, 100);
+    assert_eq!(
+        get_token_balance(&mut prg_test_ctx, &user.base_token_account).await,
+        initial_base - 1_000
+    );
+
+    // A shortfall is not pulled from the wallet
+    let mut params = order_params(Side::Ask, 1_001, 1_000, OrderType::Limit, 10);
+    params.use_free_balances = 1;
+    let ix = market.new_order_with_params(&user, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    let ix = withdraw_ix(&market, &user, 901, 0);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    let ix = withdraw_ix(&market, &user, 400, 0);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.base_token_free, 500);
+    assert_eq!(header.quote_token_free, 500_000);
+    assert_eq!(
+        get_token_balance(&mut prg_test_ctx, &user.base_token_account).await,
+        initial_base - 600
+    );
+}
@@ -0,0 +1,134 @@
+use dex_v4::error::DexError;
+use dex_v4::instruction_auto::emergency_halt::EMERGENCY_RESUME_DELAY;
+use dex_v4::instruction_auto::{emergency_halt, set_emergency_authority, set_market_status};
+use dex_v4::state::MarketStatus;
+use solana_program::clock::Clock;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+pub mod common;
+use crate::common::utils::{create_test_market, get_dex_state, sign_send_instructions, TestMarket};
+
+fn resume_ix(market: &TestMarket) -> Instruction {
+    set_market_status(
+        dex_v4::ID,
+        set_market_status::Accounts {
+            market: &market.market,
+            market_admin: &market.market_admin.pubkey(),
+        },
+        set_market_status::Params {
+            status: MarketStatus::Active as u64,
+        },
+    )
+}
+
+/// Moves to the next slot with the given unix timestamp, which also gives a new blockhash to repeat transactions
+async fn set_timestamp(prg_test_ctx: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = prg_test_ctx.banks_client.get_sysvar().await.unwrap();
+    prg_test_ctx.warp_to_slot(clock.slot + 1).unwrap();
+    prg_test_ctx.last_blockhash = prg_test_ctx
+        .banks_client
+        .get_latest_blockhash()
+        .await
+        .unwrap();
+    clock.slot += 1;
+    clock.unix_timestamp = unix_timestamp;
+    prg_test_ctx.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_emergency_halt() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+
+    let emergency_authority = Keypair::new();
+    let ix = set_emergency_authority(
+        dex_v4::ID,
+        set_emergency_authority::Accounts {
+            market: &market.market,
+            market_admin: &market.market_admin.pubkey(),
+        },
+        set_emergency_authority::Params {
+            emergency_authority: emergency_authority.pubkey(),
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market.market_admin])
+        .await
+        .unwrap();
+    let halt_ix = emergency_halt(
+        dex_v4::ID,
+        emergency_halt::Accounts {
+            market: &market.market,
+            emergency_authority: &emergency_authority.pubkey(),
+        },
+        emergency_halt::Params {},
+    );
+
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![halt_ix.clone()],
+        vec![&emergency_authority],
+    )
+    .await
+    .unwrap();
+    let market_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(market_state.status, MarketStatus::Halted as u8);
+    let halt_timestamp = market_state.emergency_halt_timestamp;
+
+    // Halting the market again right before the end of the timelock does not restart it
+    set_timestamp(
+        &mut prg_test_ctx,
+        halt_timestamp + EMERGENCY_RESUME_DELAY - 1,
+    )
+    .await;
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![halt_ix.clone()],
+        vec![&emergency_authority],
+    )
+    .await
+    .unwrap();
+    let market_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(market_state.emergency_halt_timestamp, halt_timestamp);
+    let result = sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![resume_ix(&market)],
+        vec![&market.market_admin],
+    )
+    .await;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, DexError::EmergencyHaltTimelock as u32),
+        r => panic!("Unexpected result {:?}", r),
+    }
+
+    // The admin can resume the market once the delay has elapsed since the first halt
+    set_timestamp(&mut prg_test_ctx, halt_timestamp + EMERGENCY_RESUME_DELAY).await;
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![resume_ix(&market)],
+        vec![&market.market_admin],
+    )
+    .await
+    .unwrap();
+    let market_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(market_state.status, MarketStatus::Active as u8);
+
+    // Halting the resumed market starts a new timelock
+    sign_send_instructions(&mut prg_test_ctx, vec![halt_ix], vec![&emergency_authority])
+        .await
+        .unwrap();
+    let market_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(
+        market_state.emergency_halt_timestamp,
+        halt_timestamp + EMERGENCY_RESUME_DELAY
+    );
+}
@@ -1,23 +1,71 @@
 use asset_agnostic_orderbook::state::market_state::MarketState;
 use asset_agnostic_orderbook::state::AccountTag;
 use bytemuck::try_from_bytes_mut;
+use dex_v4::instruction_auto::accept_admin;
 use dex_v4::instruction_auto::cancel_order;
 use dex_v4::instruction_auto::consume_events;
+use dex_v4::instruction_auto::create_cranker_identity;
+use dex_v4::instruction_auto::create_maker_rewards;
 use dex_v4::instruction_auto::create_market;
+use dex_v4::instruction_auto::create_session;
+use dex_v4::instruction_auto::create_settle_allowlist;
+use dex_v4::instruction_auto::emergency_halt;
+use dex_v4::instruction_auto::force_consume_events;
+use dex_v4::instruction_auto::freeze_and_export;
+use dex_v4::instruction_auto::hash_orderbook;
+use dex_v4::instruction_auto::heartbeat;
 use dex_v4::instruction_auto::initialize_account;
 use dex_v4::instruction_auto::new_order;
+use dex_v4::instruction_auto::nominate_admin;
+use dex_v4::instruction_auto::preview_new_order;
+use dex_v4::instruction_auto::reclaim_orderbook;
+use dex_v4::instruction_auto::relink_orderbook;
+use dex_v4::instruction_auto::set_account_metadata;
+use dex_v4::instruction_auto::set_batch_auction;
+use dex_v4::instruction_auto::set_cancel_fee;
+use dex_v4::instruction_auto::set_crank_fee_share;
+use dex_v4::instruction_auto::set_crank_reward_scaling;
+use dex_v4::instruction_auto::set_designated_market_maker;
+use dex_v4::instruction_auto::set_emergency_authority;
+use dex_v4::instruction_auto::set_fill_privacy;
+use dex_v4::instruction_auto::set_halt_flags;
+use dex_v4::instruction_auto::set_maker_rebate;
+use dex_v4::instruction_auto::set_market_status;
+use dex_v4::instruction_auto::set_min_resting_slots;
+use dex_v4::instruction_auto::set_oracle;
+use dex_v4::instruction_auto::set_referral_share;
+use dex_v4::instruction_auto::set_volatility_fee;
 use dex_v4::instruction_auto::settle;
 use dex_v4::instruction_auto::swap;
 use dex_v4::instruction_auto::sweep_fees;
+use dex_v4::instruction_auto::update_market_admin;
+use dex_v4::instruction_auto::update_market_metadata;
+use dex_v4::instruction_auto::update_market_params;
+use dex_v4::state::BatchAuctionHeader;
+use dex_v4::state::CrankerIdentity;
+use dex_v4::state::DexState;
+use dex_v4::state::DmmRegistryHeader;
+use dex_v4::state::EscrowHeader;
+use dex_v4::state::MakerRewardsHeader;
+use dex_v4::state::MarketStatus;
+use dex_v4::state::SessionHeader;
 use dex_v4::state::UserAccountHeader;
+use dex_v4::state::BATCH_AUCTION_HEADER_LEN;
 use dex_v4::state::DEX_STATE_LEN;
+use dex_v4::state::DMM_REGISTRY_HEADER_LEN;
+use dex_v4::state::ESCROW_HEADER_LEN;
+use dex_v4::state::HALT_ASKS_MASK;
+use dex_v4::state::MAKER_REWARDS_HEADER_LEN;
+use dex_v4::state::SESSION_HEADER_LEN;
 use dex_v4::state::USER_ACCOUNT_HEADER_LEN;
 use mpl_token_metadata::pda::find_metadata_account;
 use solana_program::account_info::AccountInfo;
 use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::AccountMeta;
 use solana_program::msg;
 use solana_program::program_error::PrintProgramError;
 use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
 use solana_program::system_instruction::create_account;
 use solana_program::system_program;
 use solana_program_test::processor;
@@ -137,6 +185,10 @@ async fn test_dex() {
 
     // Create the dex market
     let market_admin = Keypair::new();
+    let (create_market_config, _) = Pubkey::find_program_address(
+        &[dex_v4::instruction_auto::set_create_market_config::CREATE_MARKET_CONFIG_SEED],
+        &dex_program_id,
+    );
     let create_market_instruction = create_market(
         dex_program_id,
         dex_v4::instruction_auto::create_market::Accounts {
@@ -149,6 +201,11 @@ async fn test_dex() {
             asks: &aaob_accounts.asks,
             bids: &aaob_accounts.bids,
             token_metadata: &find_metadata_account(&base_mint_key).0,
+            create_market_config: &create_market_config,
+            fee_payer: None,
+            fee_source: None,
+            treasury: None,
+            fee_program: None,
         },
         create_market::Params {
             signer_nonce: signer_nonce as u64,
@@ -156,6 +213,10 @@ async fn test_dex() {
             tick_size: 42949672,
             base_currency_multiplier: 1,
             quote_currency_multiplier: 10000,
+            min_order_tick_distance: 0,
+            base_symbol: *b"BASE\0\0\0\0\0\0\0\0\0\0\0\0",
+            quote_symbol: *b"QUOTE\0\0\0\0\0\0\0\0\0\0\0",
+            market_name: [0; 32],
         },
     );
     sign_send_instructions(&mut prg_test_ctx, vec![create_market_instruction], vec![])
@@ -221,6 +282,7 @@ async fn test_dex() {
         initialize_account::Params {
             market: market_account.pubkey(),
             max_orders: 10,
+            account_index: 0,
         },
     );
     sign_send_instructions(
@@ -313,6 +375,9 @@ async fn test_dex() {
             user_token_account: &user_base_token_account,
             user_owner: &user_account_owner.pubkey(),
             discount_token_account: None,
+            oracle: None,
+            session: None,
+            dmm_registry: None,
             fee_referral_account: None,
         },
         new_order::Params {
@@ -328,8 +393,17 @@ async fn test_dex() {
             self_trade_behavior: asset_agnostic_orderbook::state::SelfTradeBehavior::DecrementTake
                 as u8,
             match_limit: 10,
+            expiry_timestamp: 0,
+            display_base_qty: 0,
+            min_base_qty: 0,
             has_discount_token_account: false as u8,
-            _padding: 0,
+            has_oracle_account: false as u8,
+            has_session: false as u8,
+            has_dmm_registry: false as u8,
+            notify_on_cross: 0,
+            quantity_mode: 0,
+            use_free_balances: 0,
+            _padding: [0; 6],
         },
     );
     sign_send_instructions(
@@ -340,6 +414,158 @@ async fn test_dex() {
     .await
     .unwrap();
 
+    // New asks are rejected while the ask side is halted, and resume once the flags are cleared
+    let halt_flags_ix = |halt_flags| {
+        set_halt_flags(
+            dex_program_id,
+            set_halt_flags::Accounts {
+                market: &market_account.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_halt_flags::Params { halt_flags },
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![halt_flags_ix(1 << 2)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![halt_flags_ix(HALT_ASKS_MASK)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let halted_ask_instruction = new_order(
+        dex_program_id,
+        new_order::Accounts {
+            spl_token_program: &spl_token::ID,
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            orderbook: &aaob_accounts.market,
+            event_queue: &aaob_market_state.event_queue,
+            bids: &aaob_market_state.bids,
+            asks: &aaob_market_state.asks,
+            base_vault: &base_vault,
+            quote_vault: &quote_vault,
+            user: &user_account,
+            user_token_account: &user_base_token_account,
+            user_owner: &user_account_owner.pubkey(),
+            discount_token_account: None,
+            oracle: None,
+            session: None,
+            dmm_registry: None,
+            fee_referral_account: None,
+        },
+        new_order::Params {
+            #[cfg(not(any(feature = "aarch64-test", target_arch = "aarch64")))]
+            client_order_id: 0,
+            #[cfg(any(feature = "aarch64-test", target_arch = "aarch64"))]
+            client_order_id: bytemuck::cast(0u128),
+            side: asset_agnostic_orderbook::state::Side::Ask as u8,
+            limit_price: 10 * aaob_market_state.tick_size,
+            max_base_qty: 1,
+            max_quote_qty: u64::MAX,
+            order_type: new_order::OrderType::Limit as u8,
+            self_trade_behavior: asset_agnostic_orderbook::state::SelfTradeBehavior::DecrementTake
+                as u8,
+            match_limit: 10,
+            expiry_timestamp: 0,
+            display_base_qty: 0,
+            min_base_qty: 0,
+            has_discount_token_account: false as u8,
+            has_oracle_account: false as u8,
+            has_session: false as u8,
+            has_dmm_registry: false as u8,
+            notify_on_cross: 0,
+            quantity_mode: 0,
+            use_free_balances: 0,
+            _padding: [0; 6],
+        },
+    );
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![halted_ask_instruction],
+        vec![&user_account_owner]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![halt_flags_ix(0)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+
+    // While the ask rests on the book, the tick size can only be changed to a divisor of the current one
+    let market_params_ix = |tick_size, min_base_order_size| {
+        update_market_params(
+            dex_program_id,
+            update_market_params::Accounts {
+                market: &market_account.pubkey(),
+                orderbook: &aaob_accounts.market,
+                bids: &aaob_market_state.bids,
+                asks: &aaob_market_state.asks,
+                market_admin: &market_admin.pubkey(),
+            },
+            update_market_params::Params {
+                tick_size,
+                min_base_order_size,
+            },
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![market_params_ix(3 * aaob_market_state.tick_size, 1)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![market_params_ix(0, 1)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![market_params_ix(aaob_market_state.tick_size / 2, 2)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.min_base_order_size, 2);
+    let mut orderbook_data = prg_test_ctx
+        .banks_client
+        .get_account(aaob_accounts.market)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let orderbook = MarketState::from_buffer(&mut orderbook_data, AccountTag::Market).unwrap();
+    assert_eq!(orderbook.tick_size, aaob_market_state.tick_size / 2);
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![market_params_ix(aaob_market_state.tick_size / 2, 1)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+
     let mut user_acc_data = prg_test_ctx
         .banks_client
         .get_account(user_account)
@@ -350,6 +576,137 @@ async fn test_dex() {
     let user_acc: &mut UserAccountHeader =
         try_from_bytes_mut(&mut user_acc_data[..USER_ACCOUNT_HEADER_LEN]).unwrap();
     println!("Number of orders {:?}", user_acc.number_of_orders);
+    let number_of_orders = user_acc.number_of_orders;
+
+    // Previewing an order always fails and leaves the user account untouched
+    let ix = preview_new_order(
+        dex_program_id,
+        preview_new_order::Accounts {
+            spl_token_program: &spl_token::ID,
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            orderbook: &aaob_accounts.market,
+            event_queue: &aaob_market_state.event_queue,
+            bids: &aaob_market_state.bids,
+            asks: &aaob_market_state.asks,
+            base_vault: &base_vault,
+            quote_vault: &quote_vault,
+            user: &user_account,
+            user_token_account: &user_base_token_account,
+            user_owner: &user_account_owner.pubkey(),
+            discount_token_account: None,
+            oracle: None,
+            session: None,
+            dmm_registry: None,
+            fee_referral_account: None,
+        },
+        preview_new_order::Params {
+            #[cfg(not(any(feature = "aarch64-test", target_arch = "aarch64")))]
+            client_order_id: 0,
+            #[cfg(any(feature = "aarch64-test", target_arch = "aarch64"))]
+            client_order_id: bytemuck::cast(0u128),
+            side: asset_agnostic_orderbook::state::Side::Ask as u8,
+            limit_price: 9 * aaob_market_state.tick_size,
+            max_base_qty: 1,
+            max_quote_qty: u64::MAX,
+            order_type: new_order::OrderType::Limit as u8,
+            self_trade_behavior: asset_agnostic_orderbook::state::SelfTradeBehavior::DecrementTake
+                as u8,
+            match_limit: 10,
+            expiry_timestamp: 0,
+            display_base_qty: 0,
+            min_base_qty: 0,
+            has_discount_token_account: false as u8,
+            has_oracle_account: false as u8,
+            has_session: false as u8,
+            has_dmm_registry: false as u8,
+            notify_on_cross: 0,
+            quantity_mode: 0,
+            use_free_balances: 0,
+            _padding: [0; 6],
+        },
+    );
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+            .await
+            .is_err()
+    );
+    let mut user_acc_data = prg_test_ctx
+        .banks_client
+        .get_account(user_account)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let user_acc: &mut UserAccountHeader =
+        try_from_bytes_mut(&mut user_acc_data[..USER_ACCOUNT_HEADER_LEN]).unwrap();
+    assert_eq!(user_acc.number_of_orders, number_of_orders);
+
+    // Tag the user account
+    let ix = set_account_metadata(
+        dex_program_id,
+        set_account_metadata::Accounts {
+            user: &user_account,
+            user_owner: &user_account_owner.pubkey(),
+        },
+        set_account_metadata::Params { metadata: [42; 32] },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+        .await
+        .unwrap();
+    let mut user_acc_data = prg_test_ctx
+        .banks_client
+        .get_account(user_account)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let user_acc: &mut UserAccountHeader =
+        try_from_bytes_mut(&mut user_acc_data[..USER_ACCOUNT_HEADER_LEN]).unwrap();
+    assert_eq!(user_acc.metadata, [42; 32]);
+
+    // Authorize a session key for the user account
+    let session_key = Keypair::new();
+    let ix = system_instruction::transfer(
+        &prg_test_ctx.payer.pubkey(),
+        &user_account_owner.pubkey(),
+        100_000_000,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let (session, _) = Pubkey::find_program_address(
+        &[&user_account.to_bytes(), create_session::SESSION_SEED],
+        &dex_program_id,
+    );
+    let ix = create_session(
+        dex_program_id,
+        create_session::Accounts {
+            system_program: &system_program::ID,
+            user: &user_account,
+            user_owner: &user_account_owner.pubkey(),
+            session: &session,
+        },
+        create_session::Params {
+            session_key: session_key.pubkey(),
+            expiry: i64::MAX,
+            capacity: 10,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+        .await
+        .unwrap();
+    let mut session_data = prg_test_ctx
+        .banks_client
+        .get_account(session)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let session_header: &mut SessionHeader =
+        try_from_bytes_mut(&mut session_data[..SESSION_HEADER_LEN]).unwrap();
+    assert_eq!(session_header.user_account, user_account);
+    assert_eq!(session_header.session_key, session_key.pubkey());
 
     // Cancel Order
     // let new_order_instruction = cancel_order(
@@ -398,6 +755,9 @@ async fn test_dex() {
             user_token_account: &user_base_token_account,
             user_owner: &user_account_owner.pubkey(),
             discount_token_account: None,
+            oracle: None,
+            session: None,
+            dmm_registry: None,
             fee_referral_account: None,
         },
         new_order::Params {
@@ -413,8 +773,17 @@ async fn test_dex() {
             self_trade_behavior: asset_agnostic_orderbook::state::SelfTradeBehavior::DecrementTake
                 as u8,
             match_limit: 10,
+            expiry_timestamp: 0,
+            display_base_qty: 0,
+            min_base_qty: 0,
             has_discount_token_account: false as u8,
-            _padding: 0,
+            has_oracle_account: false as u8,
+            has_session: false as u8,
+            has_dmm_registry: false as u8,
+            notify_on_cross: 0,
+            quantity_mode: 0,
+            use_free_balances: 0,
+            _padding: [0; 6],
         },
     );
     sign_send_instructions(
@@ -442,6 +811,9 @@ async fn test_dex() {
     //         user_token_account: &user_quote_token_account,
     //         user_owner: &user_account_owner.pubkey(),
     //         discount_token_account: None,
+    //         oracle: None,
+    //         session: None,
+    //         dmm_registry: None,
     //         fee_referral_account: None,
     //     },
     //     new_order::Params {
@@ -458,7 +830,10 @@ async fn test_dex() {
     //             as u8,
     //         match_limit: 10,
     //         has_discount_token_account: false as u8,
-    //         _padding: 0,
+    //         has_oracle_account: false as u8,
+    //         has_session: false as u8,
+    //         has_dmm_registry: false as u8,
+    //         notify_on_cross: 0,
     //     },
     // );
     // sign_send_instructions(
@@ -503,8 +878,12 @@ async fn test_dex() {
             user_owner: &user_account_owner.pubkey(),
             destination_base_account: &user_base_token_account,
             destination_quote_account: &user_quote_token_account,
+            settle_allowlist: None,
+        },
+        settle::Params {
+            unwrap_sol: 0,
+            _padding: [0; 7],
         },
-        settle::Params {},
     );
     sign_send_instructions(
         &mut prg_test_ctx,
@@ -532,6 +911,7 @@ async fn test_dex() {
     //         user_quote_account: &user_quote_token_account,
     //         user_owner: &user_account_owner.pubkey(),
     //         discount_token_account: None,
+    //         oracle: None,
     //         fee_referral_account: None,
     //     },
     //     swap::Params {
@@ -540,7 +920,8 @@ async fn test_dex() {
     //         quote_qty: 100000,
     //         match_limit: 10,
     //         has_discount_token_account: 0,
-    //         _padding: [0; 6],
+    //         has_oracle_account: 0,
+    //         _padding: [0; 5],
     //     },
     // );
     // sign_send_instructions(
@@ -578,16 +959,39 @@ async fn test_dex() {
             event_queue: &aaob_market_state.event_queue,
             reward_target: &reward_target.pubkey(),
             user_accounts: &[user_account],
+            bids: None,
+            asks: None,
+            maker_rewards: None,
+            cranker_identity: None,
+            cranker: None,
         },
         consume_events::Params {
             max_iterations: 11,
             no_op_err: 1,
+                record_cranker_identity: 0,
+            prune_expired_sessions: 0,
+            repost_iceberg_orders: 0,
         },
     );
     sign_send_instructions(&mut prg_test_ctx, vec![consume_events_instruction], vec![])
         .await
         .unwrap();
 
+    // Hash the orderbook
+    let ix = hash_orderbook(
+        dex_program_id,
+        hash_orderbook::Accounts {
+            market: &market_account.pubkey(),
+            orderbook: &aaob_accounts.market,
+            bids: &aaob_market_state.bids,
+            asks: &aaob_market_state.asks,
+        },
+        hash_orderbook::Params {},
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+
     // Change royalties_bps
     let ix = update_royalties(
         dex_program_id,
@@ -603,6 +1007,32 @@ async fn test_dex() {
         .await
         .unwrap();
 
+    // Create the maker rewards account
+    let (maker_rewards, _) = Pubkey::find_program_address(
+        &[
+            &market_account.pubkey().to_bytes(),
+            create_maker_rewards::MAKER_REWARDS_SEED,
+        ],
+        &dex_program_id,
+    );
+    let ix = create_maker_rewards(
+        dex_program_id,
+        create_maker_rewards::Accounts {
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            maker_rewards: &maker_rewards,
+            market_admin: &market_admin.pubkey(),
+            fee_payer: &prg_test_ctx.payer.pubkey(),
+        },
+        create_maker_rewards::Params {
+            epoch_length: 1_000,
+            max_entries: 10,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+
     // consume_events should not error when no_op_err == 0
     let consume_events_instruction = consume_events(
         dex_program_id,
@@ -612,13 +1042,963 @@ async fn test_dex() {
             event_queue: &aaob_market_state.event_queue,
             reward_target: &reward_target.pubkey(),
             user_accounts: &[user_account],
+            bids: Some(&aaob_market_state.bids),
+            asks: Some(&aaob_market_state.asks),
+            maker_rewards: Some(&maker_rewards),
+            cranker_identity: None,
+            cranker: None,
         },
         consume_events::Params {
             max_iterations: 10,
             no_op_err: 0,
+            record_cranker_identity: 0,
+            prune_expired_sessions: 0,
+            repost_iceberg_orders: 0,
         },
     );
     sign_send_instructions(&mut prg_test_ctx, vec![consume_events_instruction], vec![])
         .await
         .unwrap();
+
+    let mut maker_rewards_data = prg_test_ctx
+        .banks_client
+        .get_account(maker_rewards)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let maker_rewards_header: &mut MakerRewardsHeader =
+        try_from_bytes_mut(&mut maker_rewards_data[..MAKER_REWARDS_HEADER_LEN]).unwrap();
+    assert_eq!(maker_rewards_header.number_of_samples, 1);
+
+    // Rename the market
+    let mut market_name = [0; 32];
+    market_name[..9].copy_from_slice(b"BASE/USDC");
+    let ix = update_market_metadata(
+        dex_program_id,
+        update_market_metadata::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        update_market_metadata::Params {
+            base_symbol: *b"BASE\0\0\0\0\0\0\0\0\0\0\0\0",
+            quote_symbol: *b"USDC\0\0\0\0\0\0\0\0\0\0\0\0",
+            market_name,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(&market_state.quote_symbol[..4], b"USDC");
+    assert_eq!(market_state.market_name, market_name);
+
+    // Remove the market oracle
+    let ix = set_oracle(
+        dex_program_id,
+        set_oracle::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+            oracle: None,
+        },
+        set_oracle::Params {},
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+
+    // Hide the taker accounts from the fill logs
+    let ix = set_fill_privacy(
+        dex_program_id,
+        set_fill_privacy::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_fill_privacy::Params {
+            hide_fill_accounts: 1,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.hide_fill_accounts, 1);
+
+    // Halt the market
+    let ix = set_market_status(
+        dex_program_id,
+        set_market_status::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_market_status::Params {
+            status: MarketStatus::Halted as u64,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+
+    // The emergency authority can halt the market, after which the admin cannot resume it before the timelock
+    let emergency_authority = Keypair::new();
+    let ix = set_emergency_authority(
+        dex_program_id,
+        set_emergency_authority::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_emergency_authority::Params {
+            emergency_authority: emergency_authority.pubkey(),
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+    let emergency_halt_ix = |authority: &Keypair| {
+        emergency_halt(
+            dex_program_id,
+            emergency_halt::Accounts {
+                market: &market_account.pubkey(),
+                emergency_authority: &authority.pubkey(),
+            },
+            emergency_halt::Params {},
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![emergency_halt_ix(&market_admin)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![emergency_halt_ix(&emergency_authority)],
+        vec![&emergency_authority],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.status, MarketStatus::Halted as u8);
+    assert!(market_state.emergency_halt_timestamp > 0);
+    let ix = set_market_status(
+        dex_program_id,
+        set_market_status::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_market_status::Params {
+            status: MarketStatus::Active as u64,
+        },
+    );
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+            .await
+            .is_err()
+    );
+
+    // Drain the event queue of the halted market, which creates its escrow account
+    let (escrow, _) = Pubkey::find_program_address(
+        &[
+            &market_account.pubkey().to_bytes(),
+            force_consume_events::ESCROW_SEED,
+        ],
+        &dex_program_id,
+    );
+    let ix = system_instruction::transfer(
+        &prg_test_ctx.payer.pubkey(),
+        &market_admin.pubkey(),
+        1_000_000_000,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let ix = force_consume_events(
+        dex_program_id,
+        force_consume_events::Accounts {
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            orderbook: &aaob_accounts.market,
+            event_queue: &aaob_market_state.event_queue,
+            market_admin: &market_admin.pubkey(),
+            escrow: &escrow,
+            user_accounts: &[],
+        },
+        force_consume_events::Params {
+            max_iterations: 10,
+            escrow_capacity: 10,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+    let mut escrow_data = prg_test_ctx
+        .banks_client
+        .get_account(escrow)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let escrow_header: &mut EscrowHeader =
+        try_from_bytes_mut(&mut escrow_data[..ESCROW_HEADER_LEN]).unwrap();
+    assert_eq!(escrow_header.market, market_account.pubkey());
+
+    // Switch the market to batch auctions, which creates its batch auction account
+    let (batch_auction, _) = Pubkey::find_program_address(
+        &[
+            &market_account.pubkey().to_bytes(),
+            set_batch_auction::BATCH_AUCTION_SEED,
+        ],
+        &dex_program_id,
+    );
+    let ix = set_batch_auction(
+        dex_program_id,
+        set_batch_auction::Accounts {
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            batch_auction: &batch_auction,
+            market_admin: &market_admin.pubkey(),
+        },
+        set_batch_auction::Params {
+            batch_slots: 2,
+            capacity: 10,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+    let mut batch_auction_data = prg_test_ctx
+        .banks_client
+        .get_account(batch_auction)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let batch_auction_header: &mut BatchAuctionHeader =
+        try_from_bytes_mut(&mut batch_auction_data[..BATCH_AUCTION_HEADER_LEN]).unwrap();
+    assert_eq!(batch_auction_header.market, market_account.pubkey());
+    assert_eq!(batch_auction_header.number_of_orders, 0);
+
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.batch_slots, 2);
+
+    // Register the user account as a designated market maker
+    let (dmm_registry, _) = Pubkey::find_program_address(
+        &[
+            &market_account.pubkey().to_bytes(),
+            set_designated_market_maker::DMM_REGISTRY_SEED,
+        ],
+        &dex_program_id,
+    );
+    let ix = set_designated_market_maker(
+        dex_program_id,
+        set_designated_market_maker::Accounts {
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            dmm_registry: &dmm_registry,
+            market_admin: &market_admin.pubkey(),
+        },
+        set_designated_market_maker::Params {
+            user_account,
+            capacity: 10,
+            is_designated: 1,
+            _padding: [0; 7],
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+    let mut dmm_registry_data = prg_test_ctx
+        .banks_client
+        .get_account(dmm_registry)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let dmm_registry_header: &mut DmmRegistryHeader =
+        try_from_bytes_mut(&mut dmm_registry_data[..DMM_REGISTRY_HEADER_LEN]).unwrap();
+    assert_eq!(dmm_registry_header.number_of_entries, 1);
+    assert_eq!(
+        &dmm_registry_data[DMM_REGISTRY_HEADER_LEN..DMM_REGISTRY_HEADER_LEN + 32],
+        user_account.as_ref()
+    );
+
+    // Share a quarter of the protocol fees with crankers
+    let ix = set_crank_fee_share(
+        dex_program_id,
+        set_crank_fee_share::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_crank_fee_share::Params {
+            crank_fee_share_bps: 10_001,
+        },
+    );
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+            .await
+            .is_err()
+    );
+    let ix = set_crank_fee_share(
+        dex_program_id,
+        set_crank_fee_share::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_crank_fee_share::Params {
+            crank_fee_share_bps: 2_500,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.crank_fee_share_bps, 2_500);
+
+    // New markets pay a fifth of the taker fee to referrers, which the admin can change
+    assert_eq!(
+        market_state.referral_share_bps,
+        dex_v4::state::DEFAULT_REFERRAL_SHARE_BPS
+    );
+    let referral_share_ix = |referral_share_bps| {
+        set_referral_share(
+            dex_program_id,
+            set_referral_share::Accounts {
+                market: &market_account.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_referral_share::Params { referral_share_bps },
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![referral_share_ix(10_001)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![referral_share_ix(5_000)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.referral_share_bps, 5_000);
+
+    // Makers earn no rebate until the admin sets one, which cannot exceed the base taker fee of 4 bps
+    assert_eq!(market_state.maker_rebate_bps, 0);
+    let maker_rebate_ix = |maker_rebate_bps| {
+        set_maker_rebate(
+            dex_program_id,
+            set_maker_rebate::Accounts {
+                market: &market_account.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_maker_rebate::Params { maker_rebate_bps },
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![maker_rebate_ix(5)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![maker_rebate_ix(2)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.maker_rebate_bps, 2);
+
+    // The admin rights can be moved to another key, which can then move them back
+    let new_admin = Keypair::new();
+    let update_admin_ix = |admin: &Keypair, new_admin: &Keypair| {
+        update_market_admin(
+            dex_program_id,
+            update_market_admin::Accounts {
+                market: &market_account.pubkey(),
+                market_admin: &admin.pubkey(),
+            },
+            update_market_admin::Params {
+                new_admin: new_admin.pubkey(),
+            },
+        )
+    };
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![update_admin_ix(&market_admin, &new_admin)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![maker_rebate_ix(1)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![update_admin_ix(&new_admin, &market_admin)],
+        vec![&new_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.admin, market_admin.pubkey());
+
+    // A nominated admin only takes over once it accepts the nomination, which no other key can do
+    let nominate_admin_ix = nominate_admin(
+        dex_program_id,
+        nominate_admin::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        nominate_admin::Params {
+            pending_admin: new_admin.pubkey(),
+        },
+    );
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![nominate_admin_ix],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let accept_admin_ix = |pending_admin: &Keypair| {
+        accept_admin(
+            dex_program_id,
+            accept_admin::Accounts {
+                market: &market_account.pubkey(),
+                pending_admin: &pending_admin.pubkey(),
+            },
+            accept_admin::Params {},
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![accept_admin_ix(&market_admin)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.admin, market_admin.pubkey());
+    assert_eq!(market_state.pending_admin, new_admin.pubkey());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![accept_admin_ix(&new_admin)],
+        vec![&new_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.admin, new_admin.pubkey());
+    assert_eq!(market_state.pending_admin, Pubkey::default());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![update_admin_ix(&new_admin, &market_admin)],
+        vec![&new_admin],
+    )
+    .await
+    .unwrap();
+
+    // A cancel fee needs a window of slots, and is disabled again so that the cancellations below are free
+    let cancel_fee_ix = |cancel_fee, cancel_fee_slots| {
+        set_cancel_fee(
+            dex_program_id,
+            set_cancel_fee::Accounts {
+                market: &market_account.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_cancel_fee::Params {
+                cancel_fee,
+                cancel_fee_slots,
+            },
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![cancel_fee_ix(100, 0)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![cancel_fee_ix(100, 10)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.cancel_fee, 100);
+    assert_eq!(market_state.cancel_fee_slots, 10);
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![cancel_fee_ix(0, 0)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+
+    // Add one taker fee multiple for every 5% price move within 100 slots, up to three times the base fee
+    let ix = set_volatility_fee(
+        dex_program_id,
+        set_volatility_fee::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_volatility_fee::Params {
+            window_slots: 100,
+            threshold_bps: 500,
+            max_multiplier: 3,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.volatility_window_slots, 100);
+    assert_eq!(market_state.volatility_threshold_bps, 500);
+    assert_eq!(market_state.max_volatility_multiplier, 3);
+    assert_eq!(market_state.reference_price, 0);
+
+    // Raise the crank reward by one multiple for every 50 slots the pending events wait, up to four times the base reward
+    let crank_reward_scaling_ix = |age_slots, max_multiplier| {
+        set_crank_reward_scaling(
+            dex_program_id,
+            set_crank_reward_scaling::Accounts {
+                market: &market_account.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_crank_reward_scaling::Params {
+                age_slots,
+                max_multiplier,
+            },
+        )
+    };
+    assert!(sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![crank_reward_scaling_ix(50, 0)],
+        vec![&market_admin]
+    )
+    .await
+    .is_err());
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![crank_reward_scaling_ix(50, 4)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.crank_reward_age_slots, 50);
+    assert_eq!(market_state.max_crank_reward_multiplier, 4);
+
+    // Register a cranker identity
+    let cranker = prg_test_ctx.payer.pubkey();
+    let (cranker_identity, _) = Pubkey::find_program_address(
+        &[
+            &market_account.pubkey().to_bytes(),
+            &cranker.to_bytes(),
+            create_cranker_identity::CRANKER_IDENTITY_SEED,
+        ],
+        &dex_program_id,
+    );
+    let ix = create_cranker_identity(
+        dex_program_id,
+        create_cranker_identity::Accounts {
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            cranker_identity: &cranker_identity,
+            cranker: &cranker,
+        },
+        create_cranker_identity::Params {},
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let mut cranker_identity_data = prg_test_ctx
+        .banks_client
+        .get_account(cranker_identity)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let cranker_identity: &mut CrankerIdentity =
+        try_from_bytes_mut(&mut cranker_identity_data).unwrap();
+    assert_eq!(cranker_identity.market, market_account.pubkey());
+    assert_eq!(cranker_identity.owner, cranker);
+    assert_eq!(cranker_identity.events_consumed, 0);
+
+    // Configure the minimum resting time of orders
+    let set_min_resting_slots_ix = |min_resting_slots| {
+        set_min_resting_slots(
+            dex_program_id,
+            set_min_resting_slots::Accounts {
+                market: &market_account.pubkey(),
+                market_admin: &market_admin.pubkey(),
+            },
+            set_min_resting_slots::Params { min_resting_slots },
+        )
+    };
+    let ix = set_min_resting_slots_ix(set_min_resting_slots::MAX_MIN_RESTING_SLOTS + 1);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+            .await
+            .is_err()
+    );
+    let ix = set_min_resting_slots_ix(10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+        .await
+        .unwrap();
+
+    // Send heartbeats for the user account
+    let heartbeat_ix = |user_owner: &Pubkey, session: Option<&Pubkey>| {
+        heartbeat(
+            dex_program_id,
+            heartbeat::Accounts {
+                user: &user_account,
+                user_owner,
+                session,
+            },
+            heartbeat::Params {},
+        )
+    };
+    let ix = heartbeat_ix(&user_account_owner.pubkey(), None);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+        .await
+        .unwrap();
+    let ix = heartbeat_ix(&session_key.pubkey(), Some(&session));
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&session_key])
+        .await
+        .unwrap();
+    // The session key cannot send heartbeats without its session account
+    let ix = heartbeat_ix(&session_key.pubkey(), None);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&session_key])
+            .await
+            .is_err()
+    );
+
+    // Instructions with a missing or an extra account are rejected
+    let mut ix = heartbeat_ix(&user_account_owner.pubkey(), None);
+    ix.accounts.pop();
+    assert!(sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .is_err());
+    let mut ix = heartbeat_ix(&session_key.pubkey(), Some(&session));
+    ix.accounts
+        .push(AccountMeta::new_readonly(market_account.pubkey(), false));
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&session_key])
+            .await
+            .is_err()
+    );
+
+    // Once a settle allowlist is registered, settle only transfers to its destinations
+    let (settle_allowlist, _) = Pubkey::find_program_address(
+        &[
+            &user_account.to_bytes(),
+            create_settle_allowlist::SETTLE_ALLOWLIST_SEED,
+        ],
+        &dex_program_id,
+    );
+    let ix = create_settle_allowlist(
+        dex_program_id,
+        create_settle_allowlist::Accounts {
+            system_program: &system_program::ID,
+            market: &market_account.pubkey(),
+            user: &user_account,
+            user_owner: &user_account_owner.pubkey(),
+            settle_allowlist: &settle_allowlist,
+            destinations: &[user_base_token_account, user_quote_token_account],
+        },
+        create_settle_allowlist::Params {},
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+        .await
+        .unwrap();
+    let settle_ix = |destination_quote_account: &Pubkey, settle_allowlist: Option<&Pubkey>| {
+        settle(
+            dex_program_id,
+            settle::Accounts {
+                spl_token_program: &spl_token::ID,
+                market: &market_account.pubkey(),
+                base_vault: &base_vault,
+                quote_vault: &quote_vault,
+                market_signer: &market_signer,
+                user: &user_account,
+                user_owner: &user_account_owner.pubkey(),
+                destination_base_account: &user_base_token_account,
+                destination_quote_account,
+                settle_allowlist,
+            },
+            settle::Params {
+                unwrap_sol: 0,
+                _padding: [0; 7],
+            },
+        )
+    };
+    let ix = settle_ix(&base_mint_auth_token_account, Some(&settle_allowlist));
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+            .await
+            .is_err()
+    );
+    let ix = settle_ix(&user_quote_token_account, None);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+            .await
+            .is_err()
+    );
+    let ix = settle_ix(&user_quote_token_account, Some(&settle_allowlist));
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+        .await
+        .unwrap();
+
+    // Only the market admin can relink the orderbook
+    let new_aaob_accounts = create_aob_market_and_accounts(&mut prg_test_ctx, dex_program_id).await;
+    let ix = relink_orderbook(
+        dex_program_id,
+        relink_orderbook::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &user_account_owner.pubkey(),
+            orderbook: &aaob_accounts.market,
+            event_queue: &aaob_market_state.event_queue,
+            bids: &aaob_market_state.bids,
+            asks: &aaob_market_state.asks,
+            new_orderbook: &new_aaob_accounts.market,
+            new_event_queue: &new_aaob_accounts.event_queue,
+            new_bids: &new_aaob_accounts.bids,
+            new_asks: &new_aaob_accounts.asks,
+            target_lamports_account: &market_admin.pubkey(),
+        },
+        relink_orderbook::Params {},
+    );
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user_account_owner])
+            .await
+            .is_err()
+    );
+
+    // The orders and cancels placed so far are counted in the order flow stats
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.order_flow.window, 0);
+    assert!(market_state.order_flow.new_orders > 0);
+    assert!(market_state.order_flow.cancels > 0);
+    assert_eq!(market_state.min_resting_slots, 10);
+
+    // Freeze the market for migration. This halts the market for good, so it should stay the last step.
+    let migration_program = Pubkey::new_unique();
+    let migration_market = Pubkey::new_unique();
+    let (migration_market_signer, migration_signer_nonce) =
+        Pubkey::find_program_address(&[&migration_market.to_bytes()], &migration_program);
+    let freeze_ix = |migration_signer_nonce| {
+        freeze_and_export(
+            dex_program_id,
+            freeze_and_export::Accounts {
+                spl_token_program: &spl_token::ID,
+                market: &market_account.pubkey(),
+                market_admin: &market_admin.pubkey(),
+                base_vault: &base_vault,
+                quote_vault: &quote_vault,
+                market_signer: &market_signer,
+                migration_market_signer: &migration_market_signer,
+            },
+            freeze_and_export::Params {
+                migration_program,
+                migration_market,
+                migration_signer_nonce,
+                _padding: [0; 7],
+            },
+        )
+    };
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![freeze_ix(migration_signer_nonce)],
+        vec![&market_admin],
+    )
+    .await
+    .unwrap();
+    let mut market_data = prg_test_ctx
+        .banks_client
+        .get_account(market_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let market_state: &mut DexState =
+        try_from_bytes_mut(&mut market_data[..DEX_STATE_LEN]).unwrap();
+    assert_eq!(market_state.status, MarketStatus::Migrating as u8);
+    assert_eq!(market_state.migration_program, migration_program);
+    assert_eq!(market_state.migration_market, migration_market);
+    assert!(market_state.migration_unlock_timestamp >= freeze_and_export::MIGRATION_TIMELOCK);
+
+    // The vaults cannot be exported before the timelock has elapsed
+    let ix = freeze_ix(migration_signer_nonce.wrapping_sub(1));
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+            .await
+            .is_err()
+    );
+
+    // The orderbook of the frozen market stays readable until the vaults can be exported
+    let ix = reclaim_orderbook(
+        dex_program_id,
+        reclaim_orderbook::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+            orderbook: &aaob_accounts.market,
+            event_queue: &aaob_market_state.event_queue,
+            bids: &aaob_market_state.bids,
+            asks: &aaob_market_state.asks,
+            target_lamports_account: &market_admin.pubkey(),
+        },
+        reclaim_orderbook::Params {},
+    );
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+            .await
+            .is_err()
+    );
+
+    // The migration cannot be undone
+    let ix = set_market_status(
+        dex_program_id,
+        set_market_status::Accounts {
+            market: &market_account.pubkey(),
+            market_admin: &market_admin.pubkey(),
+        },
+        set_market_status::Params {
+            status: MarketStatus::Active as u64,
+        },
+    );
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market_admin])
+            .await
+            .is_err()
+    );
 }
@@ -0,0 +1,189 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::consume_events;
+use dex_v4::instruction_auto::new_order::OrderType;
+use dex_v4::instruction_auto::withdraw;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions, TestMarket,
+};
+
+fn consume_events_ix(
+    market: &TestMarket,
+    user_accounts: &[Pubkey],
+    max_iterations: u64,
+) -> Instruction {
+    consume_events(
+        dex_v4::ID,
+        consume_events::Accounts {
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            reward_target: &Keypair::new().pubkey(),
+            user_accounts,
+            bids: Some(&market.bids),
+            asks: Some(&market.asks),
+            maker_rewards: None,
+            cranker_identity: None,
+            cranker: None,
+        },
+        consume_events::Params::new(max_iterations, true, false, false, true).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_iceberg_orders() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let maker = market.create_user(&mut prg_test_ctx).await;
+    let taker = market.create_user(&mut prg_test_ctx).await;
+    let mut user_accounts = vec![maker.user_account, taker.user_account];
+    user_accounts.sort_unstable();
+
+    // Iceberg orders should be post only
+    let mut params = order_params(Side::Ask, 1_000, 300, OrderType::Limit, 10);
+    params.display_base_qty = 100;
+    let ix = market.new_order_with_params(&maker, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+            .await
+            .is_err()
+    );
+
+    // Only the first tranche is displayed, the hidden remainder is kept in the free balance
+    let mut params = order_params(Side::Ask, 1_000, 300, OrderType::PostOnly, 10);
+    params.display_base_qty = 100;
+    let ix = market.new_order_with_params(&maker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &maker.user_account).await;
+    assert_eq!(header.base_token_locked, 100);
+    assert_eq!(header.base_token_free, 200);
+
+    // A taker can only match the displayed tranche
+    let ix = market.new_order(
+        &taker,
+        Side::Bid,
+        1_000,
+        150,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert_eq!(header.base_token_free, 100);
+
+    // Consuming the fill posts the next tranche
+    let ix = consume_events_ix(&market, &user_accounts, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &maker.user_account).await;
+    assert_eq!(header.number_of_orders, 1);
+    assert_eq!(header.base_token_locked, 100);
+    assert_eq!(header.base_token_free, 100);
+
+    let ix = market.new_order(
+        &taker,
+        Side::Bid,
+        1_000,
+        120,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let ix = consume_events_ix(&market, &user_accounts, 11);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &maker.user_account).await;
+    assert_eq!(header.number_of_orders, 1);
+    assert_eq!(header.base_token_locked, 100);
+    assert_eq!(header.base_token_free, 0);
+
+    // The iceberg order ends with its last tranche
+    let ix = market.new_order(
+        &taker,
+        Side::Bid,
+        1_000,
+        100,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let ix = consume_events_ix(&market, &user_accounts, 12);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &maker.user_account).await;
+    assert_eq!(header.number_of_orders, 0);
+    assert_eq!(header.base_token_locked, 0);
+    assert!(header.quote_token_free >= 300 * 1_000);
+
+    // The remainder of an iceberg order is cancelled when the free balance no longer covers the next tranche
+    let mut params = order_params(Side::Ask, 1_000, 300, OrderType::PostOnly, 10);
+    params.display_base_qty = 100;
+    let ix = market.new_order_with_params(&maker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+        .await
+        .unwrap();
+    let (market_signer, _) =
+        Pubkey::find_program_address(&[&market.market.to_bytes()], &dex_v4::ID);
+    let ix = withdraw(
+        dex_v4::ID,
+        withdraw::Accounts {
+            spl_token_program: &spl_token::ID,
+            market: &market.market,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            market_signer: &market_signer,
+            user: &maker.user_account,
+            user_owner: &maker.owner.pubkey(),
+            destination_base_account: &maker.base_token_account,
+            destination_quote_account: &maker.quote_token_account,
+            settle_allowlist: None,
+        },
+        withdraw::Params {
+            base_qty: 150,
+            quote_qty: 0,
+        },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+        .await
+        .unwrap();
+    let ix = market.new_order(
+        &taker,
+        Side::Bid,
+        1_000,
+        100,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let ix = consume_events_ix(&market, &user_accounts, 13);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &maker.user_account).await;
+    assert_eq!(header.number_of_orders, 0);
+    assert_eq!(header.base_token_locked, 0);
+    assert_eq!(header.base_token_free, 50);
+}
@@ -0,0 +1,153 @@
+use bytemuck::{bytes_of, pod_read_unaligned, Zeroable};
+use dex_v4::instruction_auto::migrate_account;
+use dex_v4::state::{
+    AccountTag, DexState, DexStateV1, Order, OrderV1, UserAccountHeader, UserAccountHeaderV1,
+    DEX_STATE_LEN, USER_ACCOUNT_HEADER_LEN,
+};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::sign_send_instructions;
+
+fn migrate_account_ix(account: &Pubkey, payer: &Pubkey) -> Instruction {
+    migrate_account(
+        dex_v4::ID,
+        migrate_account::Accounts {
+            system_program: &system_program::ID,
+            account,
+            payer,
+        },
+        migrate_account::Params {},
+    )
+}
+
+#[tokio::test]
+async fn test_migrate_account() {
+    let mut program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+
+    // A market and a user account holding one of two orders, as laid out by the first release
+    let market = Pubkey::new_unique();
+    let market_v1 = DexStateV1 {
+        tag: AccountTag::DexStateV1 as u64,
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        orderbook: Pubkey::new_unique(),
+        admin: Pubkey::new_unique(),
+        accumulated_fees: 1_000,
+        min_base_order_size: 10,
+        base_currency_multiplier: 1,
+        quote_currency_multiplier: 1,
+        signer_nonce: 254,
+        fee_type: 1,
+        ..DexStateV1::zeroed()
+    };
+    let data = bytes_of(&market_v1).to_vec();
+    assert_eq!(data.len(), 280);
+    program_test.add_account(
+        market,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: dex_v4::ID,
+            ..Account::default()
+        },
+    );
+
+    let user_account = Pubkey::new_unique();
+    let header_v1 = UserAccountHeaderV1 {
+        tag: AccountTag::UserAccountV1 as u64,
+        market,
+        owner: Pubkey::new_unique(),
+        base_token_free: 5,
+        quote_token_locked: 7,
+        accumulated_maker_quote_volume: 11,
+        number_of_orders: 1,
+        ..UserAccountHeaderV1::zeroed()
+    };
+    let order_v1 = OrderV1 {
+        id: 42 << 64,
+        client_id: 3,
+    };
+    let mut data = bytes_of(&header_v1).to_vec();
+    data.extend_from_slice(bytes_of(&order_v1));
+    data.extend_from_slice(bytes_of(&OrderV1::zeroed()));
+    assert_eq!(data.len(), 152 + 2 * 32);
+    program_test.add_account(
+        user_account,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: dex_v4::ID,
+            ..Account::default()
+        },
+    );
+
+    let mut prg_test_ctx = program_test.start_with_context().await;
+    let payer = prg_test_ctx.payer.pubkey();
+    let rent = prg_test_ctx.banks_client.get_rent().await.unwrap();
+
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![
+            migrate_account_ix(&market, &payer),
+            migrate_account_ix(&user_account, &payer),
+        ],
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let market_account = prg_test_ctx
+        .banks_client
+        .get_account(market)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(market_account.data.len(), DEX_STATE_LEN);
+    assert!(rent.is_exempt(market_account.lamports, DEX_STATE_LEN));
+    let market_state: DexState = pod_read_unaligned(&market_account.data);
+    assert_eq!(market_state.tag, AccountTag::DexState as u64);
+    assert_eq!(market_state.base_mint, market_v1.base_mint);
+    assert_eq!(market_state.orderbook, market_v1.orderbook);
+    assert_eq!(market_state.admin, market_v1.admin);
+    assert_eq!(market_state.accumulated_fees, 1_000);
+    assert_eq!(market_state.min_base_order_size, 10);
+    assert_eq!(market_state.signer_nonce, 254);
+    assert_eq!(market_state.fee_type, 1);
+
+    let account = prg_test_ctx
+        .banks_client
+        .get_account(user_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), USER_ACCOUNT_HEADER_LEN + 2 * Order::LEN);
+    assert!(rent.is_exempt(account.lamports, account.data.len()));
+    let header: UserAccountHeader = pod_read_unaligned(&account.data[..USER_ACCOUNT_HEADER_LEN]);
+    assert_eq!(header.tag, AccountTag::UserAccount as u64);
+    assert_eq!(header.market, market);
+    assert_eq!(header.owner, header_v1.owner);
+    assert_eq!(header.base_token_free, 5);
+    assert_eq!(header.quote_token_locked, 7);
+    assert_eq!(header.accumulated_maker_quote_volume, 11);
+    assert_eq!(header.number_of_orders, 1);
+    let order: Order = pod_read_unaligned(&account.data[USER_ACCOUNT_HEADER_LEN..][..Order::LEN]);
+    assert_eq!(order.id, order_v1.id);
+    assert_eq!(order.client_id, 3);
+
+    // Current accounts are left as they are
+    let ix = migrate_account_ix(&user_account, &payer);
+    assert!(sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .is_err());
+}
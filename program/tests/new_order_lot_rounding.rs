@@ -0,0 +1,70 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_program_test::ProgramTestContext;
+pub mod common;
+use crate::common::utils::{
+    create_test_market_with_lots, get_user_account_header, sign_send_instructions,
+};
+
+async fn get_token_balance(prg_test_ctx: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = prg_test_ctx
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+#[tokio::test]
+async fn test_new_order_lot_rounding() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    // A base lot is 10 raw base tokens
+    let (mut prg_test_ctx, market) = create_test_market_with_lots(program_test, 10, 1).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+    let initial_balance = get_token_balance(&mut prg_test_ctx, &user.base_token_account).await;
+
+    // An order smaller than a lot is rejected instead of being truncated to nothing
+    let ix = market.new_order(&user, Side::Ask, 1, 9, OrderType::Limit, 10);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    // The smallest representable order is a single lot
+    let ix = market.new_order(&user, Side::Ask, 1, 10, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.base_token_locked, 10);
+    assert_eq!(
+        get_token_balance(&mut prg_test_ctx, &user.base_token_account).await,
+        initial_balance - 10
+    );
+
+    // The dust of an order is left out of it and never debited
+    let ix = market.new_order(&user, Side::Ask, 2, 19, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.base_token_locked, 20);
+    assert_eq!(header.base_token_free, 0);
+    assert_eq!(header.number_of_orders, 2);
+    assert_eq!(
+        get_token_balance(&mut prg_test_ctx, &user.base_token_account).await,
+        initial_balance - 20
+    );
+}
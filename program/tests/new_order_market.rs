@@ -0,0 +1,77 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions,
+};
+
+#[tokio::test]
+async fn test_new_order_market() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let maker = market.create_user(&mut prg_test_ctx).await;
+    let taker = market.create_user(&mut prg_test_ctx).await;
+
+    let ixs = vec![
+        market.new_order(&maker, Side::Ask, 1_000, 100, OrderType::Limit, 10),
+        market.new_order(&maker, Side::Ask, 1_100, 100, OrderType::Limit, 10),
+    ];
+    sign_send_instructions(&mut prg_test_ctx, ixs, vec![&maker.owner])
+        .await
+        .unwrap();
+
+    // A market order should bound its price
+    let mut params = order_params(Side::Bid, 0, 100, OrderType::Market, 10);
+    params.max_base_qty = 0;
+    let ix = market.new_order_with_params(&taker, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+
+    // The worst price is rounded down to the tick, and a bid without base quantity spends its budget within the band
+    let mut params = order_params(Side::Bid, 1_050, 0, OrderType::Market, 10);
+    params.limit_price += 123;
+    let ix = market.new_order_with_params(&taker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert_eq!(header.base_token_free, 100);
+    assert_eq!(header.number_of_orders, 0);
+    assert_eq!(header.quote_token_locked, 0);
+
+    // The minimum base quantity applies to market orders
+    let mut params = order_params(Side::Bid, 1_200, 150, OrderType::Market, 10);
+    params.min_base_qty = 150;
+    let ix = market.new_order_with_params(&taker, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+    let mut params = order_params(Side::Bid, 1_200, 150, OrderType::Market, 10);
+    params.min_base_qty = 100;
+    let ix = market.new_order_with_params(&taker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert_eq!(header.base_token_free, 200);
+    assert_eq!(header.number_of_orders, 0);
+
+    // A market order which matches nothing aborts rather than posting
+    let ix = market.new_order(&taker, Side::Ask, 900, 100, OrderType::Market, 10);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+}
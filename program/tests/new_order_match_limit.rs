@@ -0,0 +1,74 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_dex_state, get_user_account_header, sign_send_instructions,
+};
+
+#[tokio::test]
+async fn test_new_order_match_limit() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+
+    let mut users = vec![];
+    for _ in 0..4 {
+        users.push(market.create_user(&mut prg_test_ctx).await);
+    }
+    let taker = &users[3];
+
+    // Three price levels of 100 each
+    for (maker, price) in users[..3].iter().zip([1_002, 1_001, 1_000]) {
+        let ix = market.new_order(maker, Side::Bid, price, 100, OrderType::Limit, 10);
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+            .await
+            .unwrap();
+    }
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_bid, 1_002 << 32);
+    assert_eq!(dex_state.best_ask, 0);
+
+    // A zero match limit is rejected
+    let ix = market.new_order(taker, Side::Ask, 1_000, 100, OrderType::Limit, 0);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+
+    // A fill or kill order which cannot be filled within the match limit aborts
+    let ix = market.new_order(taker, Side::Ask, 1_001, 200, OrderType::FillOrKill, 1);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert_eq!(header.quote_token_free, 0);
+
+    // A limit order stops matching at the limit, and its remainder is cancelled instead of crossing the book
+    let ix = market.new_order(taker, Side::Ask, 1_000, 300, OrderType::Limit, 2);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert!(header.quote_token_free > 0);
+    assert_eq!(header.base_token_locked, 0);
+    assert_eq!(header.number_of_orders, 0);
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_bid, 1_000 << 32);
+    assert_eq!(dex_state.best_ask, 0);
+
+    // The last level is still on the book
+    let ix = market.new_order(taker, Side::Ask, 1_000, 100, OrderType::FillOrKill, 1);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_bid, 0);
+}
@@ -0,0 +1,43 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_dex_state, get_user_account_header, order_params,
+    sign_send_instructions,
+};
+
+#[tokio::test]
+async fn test_new_order_max_quote_qty() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let maker = market.create_user(&mut prg_test_ctx).await;
+    let taker = market.create_user(&mut prg_test_ctx).await;
+
+    for price in [1_000, 1_001] {
+        let ix = market.new_order(&maker, Side::Ask, price, 100, OrderType::Limit, 10);
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+            .await
+            .unwrap();
+    }
+
+    // A market buy of both levels with a budget covering the first level and about half of the second one
+    let mut params = order_params(Side::Bid, 1_001, 200, OrderType::ImmediateOrCancel, 10);
+    params.max_quote_qty = 150_000;
+    let ix = market.new_order_with_params(&taker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert!(header.accumulated_taker_base_volume > 100);
+    assert!(header.accumulated_taker_base_volume < 200);
+    assert!(header.accumulated_taker_quote_volume <= 150_000);
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_ask, 1_001 << 32);
+}
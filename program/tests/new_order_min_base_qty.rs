@@ -0,0 +1,55 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions,
+};
+
+#[tokio::test]
+async fn test_new_order_min_base_qty() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let maker = market.create_user(&mut prg_test_ctx).await;
+    let taker = market.create_user(&mut prg_test_ctx).await;
+
+    let ix = market.new_order(&maker, Side::Ask, 1_000, 100, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+        .await
+        .unwrap();
+
+    // The minimum only applies to immediate orders
+    let mut params = order_params(Side::Bid, 990, 100, OrderType::Limit, 10);
+    params.min_base_qty = 50;
+    let ix = market.new_order_with_params(&taker, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+
+    // Only 100 are available, which is below the minimum
+    let mut params = order_params(Side::Bid, 1_000, 200, OrderType::ImmediateOrCancel, 10);
+    params.min_base_qty = 150;
+    let ix = market.new_order_with_params(&taker, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+
+    // A partial fill above the minimum goes through
+    let mut params = order_params(Side::Bid, 1_000, 200, OrderType::ImmediateOrCancel, 10);
+    params.min_base_qty = 80;
+    let ix = market.new_order_with_params(&taker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert_eq!(header.base_token_free, 100);
+}
@@ -0,0 +1,133 @@
+use asset_agnostic_orderbook::state::Side;
+use borsh::BorshDeserialize;
+use dex_v4::instruction_auto::consume_events;
+use dex_v4::instruction_auto::new_order::OrderType;
+use dex_v4::state::CrossNotificationLog;
+use solana_program::instruction::Instruction;
+use solana_program::system_instruction;
+use solana_program_test::processor;
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::Transaction;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions,
+};
+
+/// Returns the cross notifications logged by an instruction. The banks client only returns the logs of failed
+/// simulations, so the instruction is simulated along with a transfer from an empty account.
+async fn cross_notifications(
+    prg_test_ctx: &mut ProgramTestContext,
+    ix: Instruction,
+    signer: &Keypair,
+) -> Vec<CrossNotificationLog> {
+    let empty_account = Keypair::new();
+    let failing_ix =
+        system_instruction::transfer(&empty_account.pubkey(), &prg_test_ctx.payer.pubkey(), 1);
+    let mut transaction =
+        Transaction::new_with_payer(&[ix, failing_ix], Some(&prg_test_ctx.payer.pubkey()));
+    transaction.sign(
+        &[&prg_test_ctx.payer, signer, &empty_account],
+        prg_test_ctx.last_blockhash,
+    );
+    let logs = match prg_test_ctx
+        .banks_client
+        .process_transaction_with_preflight(transaction)
+        .await
+    {
+        Err(BanksClientError::SimulationError { logs, .. }) => logs,
+        r => panic!("The simulation should fail on the transfer, got {:?}", r),
+    };
+    logs.iter()
+        .filter_map(|l| l.strip_prefix("Program data: "))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter_map(|data| CrossNotificationLog::try_from_slice(&data).ok())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_new_order_notify_on_cross() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+
+    let mut users = vec![];
+    for _ in 0..3 {
+        users.push(market.create_user(&mut prg_test_ctx).await);
+    }
+    let (first_maker, second_maker, taker) = (&users[0], &users[1], &users[2]);
+
+    // Two asks at the same price, the second one asking to be notified when it is traded through
+    let ix = market.new_order(first_maker, Side::Ask, 1_000, 100, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&first_maker.owner])
+        .await
+        .unwrap();
+    let mut params = order_params(Side::Ask, 1_000, 100, OrderType::Limit, 10);
+    params.notify_on_cross = 1;
+    let ix = market.new_order_with_params(second_maker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&second_maker.owner])
+        .await
+        .unwrap();
+
+    // The first bid only trades with the first ask, which notifies the second one
+    let ix = market.new_order(
+        taker,
+        Side::Bid,
+        1_000,
+        50,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    let notifications = cross_notifications(&mut prg_test_ctx, ix.clone(), &taker.owner).await;
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].market, market.market);
+    assert_eq!(notifications[0].user_account, second_maker.user_account);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+
+    // The flag does not change how the order is matched and settled, and the filled order isn't notified
+    let ix = market.new_order(
+        taker,
+        Side::Bid,
+        1_000,
+        100,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    let notifications = cross_notifications(&mut prg_test_ctx, ix.clone(), &taker.owner).await;
+    assert!(notifications.is_empty());
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let mut user_accounts = users.iter().map(|u| u.user_account).collect::<Vec<_>>();
+    user_accounts.sort_unstable();
+    let ix = consume_events(
+        dex_v4::ID,
+        consume_events::Accounts {
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            reward_target: &Keypair::new().pubkey(),
+            user_accounts: &user_accounts,
+            bids: None,
+            asks: None,
+            maker_rewards: None,
+            cranker_identity: None,
+            cranker: None,
+        },
+        consume_events::Params::new(10, true, false, false, false).unwrap(),
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &second_maker.user_account).await;
+    assert_eq!(header.base_token_locked, 50);
+    assert!(header.quote_token_free >= 50 * 1_000);
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert_eq!(header.base_token_free, 150);
+}
@@ -0,0 +1,120 @@
+use asset_agnostic_orderbook::state::Side;
+use bytemuck::bytes_of;
+use dex_v4::error::DexError;
+use dex_v4::instruction_auto::new_order::OrderType;
+use dex_v4::instruction_auto::{new_order, set_oracle};
+use pyth_sdk_solana::state::{AccountType, PriceAccount, MAGIC, VERSION_2};
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
+use solana_program_test::{processor, BanksClientError, ProgramTest};
+use solana_sdk::account::Account;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_dex_state, order_params, sign_send_instructions, TestMarket, TestUser,
+};
+
+fn add_price_account(program_test: &mut ProgramTest) -> Pubkey {
+    let price_account = PriceAccount {
+        magic: MAGIC,
+        ver: VERSION_2,
+        atype: AccountType::Price as u32,
+        ..PriceAccount::default()
+    };
+    let key = Pubkey::new_unique();
+    program_test.add_account(
+        key,
+        Account {
+            lamports: 1_000_000_000,
+            data: bytes_of(&price_account).to_vec(),
+            owner: Pubkey::new_unique(),
+            ..Account::default()
+        },
+    );
+    key
+}
+
+fn new_order_with_oracle(market: &TestMarket, user: &TestUser, oracle: &Pubkey) -> Instruction {
+    let mut params = order_params(Side::Bid, 100, 10, OrderType::Limit, 10);
+    params.has_oracle_account = true as u8;
+    new_order(
+        dex_v4::ID,
+        new_order::Accounts {
+            spl_token_program: &spl_token::ID,
+            system_program: &system_program::ID,
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            bids: &market.bids,
+            asks: &market.asks,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            user: &user.user_account,
+            user_token_account: &user.quote_token_account,
+            user_owner: &user.owner.pubkey(),
+            discount_token_account: None,
+            oracle: Some(oracle),
+            session: None,
+            dmm_registry: None,
+            fee_referral_account: None,
+        },
+        params,
+    )
+}
+
+fn assert_invalid_oracle(result: Result<(), BanksClientError>) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, DexError::InvalidOracleAccount as u32),
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
+
+#[tokio::test]
+async fn test_new_order_oracle() {
+    let mut program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let oracle = add_price_account(&mut program_test);
+    let other_oracle = add_price_account(&mut program_test);
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+
+    let ix = set_oracle(
+        dex_v4::ID,
+        set_oracle::Accounts {
+            market: &market.market,
+            market_admin: &market.market_admin.pubkey(),
+            oracle: Some(&oracle),
+        },
+        set_oracle::Params {},
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&market.market_admin])
+        .await
+        .unwrap();
+    let market_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(market_state.oracle, oracle);
+
+    // The market's oracle should be provided
+    let ix = market.new_order(&user, Side::Bid, 100, 10, OrderType::Limit, 10);
+    assert_invalid_oracle(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner]).await,
+    );
+
+    // Another price feed is rejected
+    let ix = new_order_with_oracle(&market, &user, &other_oracle);
+    assert_invalid_oracle(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner]).await,
+    );
+
+    let ix = new_order_with_oracle(&market, &user, &oracle);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+}
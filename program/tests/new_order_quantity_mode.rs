@@ -0,0 +1,60 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::{OrderType, QuantityMode};
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions,
+};
+
+#[tokio::test]
+async fn test_new_order_quantity_mode() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let maker = market.create_user(&mut prg_test_ctx).await;
+    let taker = market.create_user(&mut prg_test_ctx).await;
+
+    let ixs = vec![
+        market.new_order(&maker, Side::Bid, 1_000, 100, OrderType::Limit, 10),
+        market.new_order(&maker, Side::Bid, 990, 100, OrderType::Limit, 10),
+    ];
+    sign_send_instructions(&mut prg_test_ctx, ixs, vec![&maker.owner])
+        .await
+        .unwrap();
+
+    // Without the quote mode, an ask is sized by its base quantity
+    let mut params = order_params(Side::Ask, 990, 0, OrderType::ImmediateOrCancel, 10);
+    params.max_quote_qty = 150_000;
+    let ix = market.new_order_with_params(&taker, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+
+    let mut params = order_params(Side::Ask, 990, 0, OrderType::ImmediateOrCancel, 10);
+    params.max_quote_qty = 150_000;
+    params.quantity_mode = 2;
+    let ix = market.new_order_with_params(&taker, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+            .await
+            .is_err()
+    );
+
+    // A sell of 150_000 quote worth of base, which takes the first level and half of the second one
+    let mut params = order_params(Side::Ask, 990, 0, OrderType::ImmediateOrCancel, 10);
+    params.max_quote_qty = 150_000;
+    params.quantity_mode = QuantityMode::Quote as u8;
+    let ix = market.new_order_with_params(&taker, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &taker.user_account).await;
+    assert_eq!(header.accumulated_taker_base_volume, 150);
+    assert_eq!(header.accumulated_taker_quote_volume, 149_500);
+}
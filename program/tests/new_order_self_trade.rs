@@ -0,0 +1,58 @@
+use asset_agnostic_orderbook::state::{SelfTradeBehavior, Side};
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_dex_state, get_user_account_header, order_params,
+    sign_send_instructions,
+};
+
+#[tokio::test]
+async fn test_new_order_self_trade() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+
+    let ix = market.new_order(&user, Side::Ask, 1_000, 100, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+
+    // An unknown self trade behavior is rejected
+    let mut params = order_params(Side::Bid, 1_000, 100, OrderType::Limit, 10);
+    params.self_trade_behavior = 3;
+    let ix = market.new_order_with_params(&user, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    // Crossing its own ask aborts the order
+    params.self_trade_behavior = SelfTradeBehavior::AbortTransaction as u8;
+    let ix = market.new_order_with_params(&user, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_ask, 1_000 << 32);
+
+    // The resting ask is cancelled and the bid rests in its place
+    params.self_trade_behavior = SelfTradeBehavior::CancelProvide as u8;
+    let ix = market.new_order_with_params(&user, params);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_ask, 0);
+    assert_eq!(dex_state.best_bid, 1_000 << 32);
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.accumulated_taker_base_volume, 0);
+}
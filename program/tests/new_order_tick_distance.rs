@@ -0,0 +1,53 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::error::DexError;
+use dex_v4::instruction_auto::new_order::OrderType;
+use solana_program::instruction::InstructionError;
+use solana_program_test::{processor, BanksClientError, ProgramTest};
+use solana_sdk::transaction::TransactionError;
+pub mod common;
+use crate::common::utils::{
+    create_test_market_with_tick_distance, get_user_account_header, sign_send_instructions,
+};
+
+#[tokio::test]
+async fn test_new_order_tick_distance() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market_with_tick_distance(program_test, 2).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+
+    let ix = market.new_order(&user, Side::Bid, 100, 10, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+
+    // A bid one tick away is rejected before it reaches the orderbook
+    let ix = market.new_order(&user, Side::Bid, 101, 10, OrderType::PostOnly, 10);
+    let err = sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap_err();
+    match err {
+        BanksClientError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, DexError::OrderTooClose as u32),
+        e => panic!("Unexpected error {:?}", e),
+    }
+
+    // Orders on the other side are not constrained
+    let ix = market.new_order(&user, Side::Ask, 101, 10, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+
+    // A bid two ticks away rests
+    let ix = market.new_order(&user, Side::Bid, 98, 10, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.number_of_orders, 3);
+}
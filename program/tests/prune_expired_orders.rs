@@ -0,0 +1,131 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::OrderType;
+use dex_v4::instruction_auto::prune_expired_orders;
+use dex_v4::state::EXPIRED_ORDER_PRUNE_REWARD;
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction::transfer;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, order_params, sign_send_instructions, TestMarket,
+    TestUser,
+};
+
+fn prune_ix(
+    market: &TestMarket,
+    user: &TestUser,
+    reward_target: &Pubkey,
+    max_orders: u64,
+) -> Instruction {
+    prune_expired_orders(
+        dex_v4::ID,
+        prune_expired_orders::Accounts {
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            bids: &market.bids,
+            asks: &market.asks,
+            reward_target,
+            user_accounts: &[user.user_account],
+        },
+        prune_expired_orders::Params {
+            max_orders,
+            no_op_err: 1,
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_prune_expired_orders() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+    let reward_target = Keypair::new().pubkey();
+
+    // The owner deposits the prune reward, the reward target is funded so that it stays rent exempt
+    let ixs = vec![
+        transfer(
+            &prg_test_ctx.payer.pubkey(),
+            &user.owner.pubkey(),
+            1_000_000_000,
+        ),
+        transfer(&prg_test_ctx.payer.pubkey(), &reward_target, 1_000_000_000),
+    ];
+    sign_send_instructions(&mut prg_test_ctx, ixs, vec![])
+        .await
+        .unwrap();
+
+    let mut clock: Clock = prg_test_ctx.banks_client.get_sysvar().await.unwrap();
+    let expiry_timestamp = clock.unix_timestamp + 100;
+
+    // An order cannot be posted with an expiry in the past
+    let mut params = order_params(Side::Bid, 999, 100, OrderType::Limit, 10);
+    params.expiry_timestamp = clock.unix_timestamp - 1;
+    let ix = market.new_order_with_params(&user, params);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    // A good-til-date bid and a good-til-cancelled bid
+    let mut params = order_params(Side::Bid, 999, 100, OrderType::Limit, 10);
+    params.expiry_timestamp = expiry_timestamp;
+    let gtd_ix = market.new_order_with_params(&user, params);
+    let gtc_ix = market.new_order(&user, Side::Bid, 998, 100, OrderType::Limit, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![gtd_ix, gtc_ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let before = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(before.number_of_orders, 2);
+
+    // Nothing can be pruned before the expiry
+    let ix = prune_ix(&market, &user, &reward_target, 1);
+    assert!(sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .is_err());
+
+    clock.unix_timestamp = expiry_timestamp + 1;
+    prg_test_ctx.set_sysvar(&clock);
+    let reward_target_balance = prg_test_ctx
+        .banks_client
+        .get_balance(reward_target)
+        .await
+        .unwrap();
+    let ix = prune_ix(&market, &user, &reward_target, 10);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+
+    // Only the expired order is removed, its funds are unlocked and its reward is paid out
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.number_of_orders, 1);
+    assert!(header.quote_token_locked < before.quote_token_locked);
+    assert_eq!(
+        header.quote_token_free + header.quote_token_locked,
+        before.quote_token_free + before.quote_token_locked
+    );
+    assert_eq!(
+        prg_test_ctx
+            .banks_client
+            .get_balance(reward_target)
+            .await
+            .unwrap(),
+        reward_target_balance + EXPIRED_ORDER_PRUNE_REWARD
+    );
+
+    // An order is only pruned once
+    let ix = prune_ix(&market, &user, &reward_target, 2);
+    assert!(sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .is_err());
+}
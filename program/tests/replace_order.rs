@@ -0,0 +1,128 @@
+use asset_agnostic_orderbook::state::Side;
+use dex_v4::instruction_auto::new_order::{self, OrderType};
+use dex_v4::instruction_auto::replace_order;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_dex_state, get_user_account_header, order_params,
+    sign_send_instructions, TestMarket, TestUser,
+};
+
+fn replace_by_client_id(
+    market: &TestMarket,
+    user: &TestUser,
+    client_order_id: u128,
+    new_order: new_order::Params,
+) -> Instruction {
+    replace_order(
+        dex_v4::ID,
+        new_order::Accounts {
+            spl_token_program: &spl_token::ID,
+            system_program: &system_program::ID,
+            market: &market.market,
+            orderbook: &market.orderbook,
+            event_queue: &market.event_queue,
+            bids: &market.bids,
+            asks: &market.asks,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            user: &user.user_account,
+            user_token_account: if new_order.side == Side::Bid as u8 {
+                &user.quote_token_account
+            } else {
+                &user.base_token_account
+            },
+            user_owner: &user.owner.pubkey(),
+            discount_token_account: None,
+            oracle: None,
+            session: None,
+            dmm_registry: None,
+            fee_referral_account: None,
+        },
+        replace_order::Params {
+            order_id: bytemuck::cast(client_order_id),
+            order_index: 0,
+            is_client_id: 1,
+            _padding: [0; 7],
+            new_order,
+        },
+    )
+}
+
+fn order_with_client_id(side: Side, price: u64, client_order_id: u128) -> new_order::Params {
+    let mut params = order_params(side, price, 100, OrderType::Limit, 10);
+    params.client_order_id = bytemuck::cast(client_order_id);
+    params
+}
+
+#[tokio::test]
+async fn test_replace_order() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let maker = market.create_user(&mut prg_test_ctx).await;
+    let taker = market.create_user(&mut prg_test_ctx).await;
+
+    let ix = market.new_order_with_params(&maker, order_with_client_id(Side::Bid, 999, 1));
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+        .await
+        .unwrap();
+
+    // The bid is moved up a tick, in place of the previous one
+    let ix = replace_by_client_id(
+        &market,
+        &maker,
+        1,
+        order_with_client_id(Side::Bid, 1_000, 2),
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &maker.user_account).await;
+    assert_eq!(header.number_of_orders, 1);
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_bid, 1_000 << 32);
+
+    // The replaced order cannot be replaced twice
+    let ix = replace_by_client_id(
+        &market,
+        &maker,
+        1,
+        order_with_client_id(Side::Bid, 1_000, 3),
+    );
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+            .await
+            .is_err()
+    );
+
+    // Once the bid is filled, replacing it fails instead of placing a second order
+    let ix = market.new_order(
+        &taker,
+        Side::Ask,
+        1_000,
+        100,
+        OrderType::ImmediateOrCancel,
+        10,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&taker.owner])
+        .await
+        .unwrap();
+    let ix = replace_by_client_id(&market, &maker, 2, order_with_client_id(Side::Bid, 999, 4));
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&maker.owner])
+            .await
+            .is_err()
+    );
+    let header = get_user_account_header(&mut prg_test_ctx, &maker.user_account).await;
+    assert_eq!(header.number_of_orders, 1);
+    let dex_state = get_dex_state(&mut prg_test_ctx, &market.market).await;
+    assert_eq!(dex_state.best_bid, 0);
+}
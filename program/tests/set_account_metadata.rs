@@ -0,0 +1,67 @@
+use dex_v4::instruction_auto::set_account_metadata;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{create_test_market, get_user_account_header, sign_send_instructions};
+
+fn set_account_metadata_ix(user: &Pubkey, user_owner: &Pubkey, metadata: [u8; 32]) -> Instruction {
+    set_account_metadata(
+        dex_v4::ID,
+        set_account_metadata::Accounts { user, user_owner },
+        set_account_metadata::Params { metadata },
+    )
+}
+
+#[tokio::test]
+async fn test_set_account_metadata() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.metadata, [0; 32]);
+
+    let ix = set_account_metadata_ix(&user.user_account, &user.owner.pubkey(), [42; 32]);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.metadata, [42; 32]);
+
+    // Another signer can't tag the user account
+    let intruder = Keypair::new();
+    let ix = set_account_metadata_ix(&user.user_account, &intruder.pubkey(), [7; 32]);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&intruder])
+            .await
+            .is_err()
+    );
+
+    // The owner should sign
+    let mut ix = set_account_metadata_ix(&user.user_account, &user.owner.pubkey(), [7; 32]);
+    ix.accounts[1].is_signer = false;
+    assert!(sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .is_err());
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.metadata, [42; 32]);
+
+    // The metadata is overwritten as a whole
+    let mut metadata = [0; 32];
+    metadata[..5].copy_from_slice(b"desk1");
+    let ix = set_account_metadata_ix(&user.user_account, &user.owner.pubkey(), metadata);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.metadata, metadata);
+    assert_eq!(header.owner, user.owner.pubkey());
+    assert_eq!(header.market, market.market);
+}
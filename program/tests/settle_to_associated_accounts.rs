@@ -0,0 +1,146 @@
+use dex_v4::instruction_auto::{deposit, settle_to_associated_accounts};
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction::transfer;
+use solana_program::{system_program, sysvar};
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::Signer;
+use spl_token::instruction::close_account;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, sign_send_instructions, TestMarket, TestUser,
+};
+
+async fn get_token_balance(prg_test_ctx: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = prg_test_ctx
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+fn settle_to_associated_accounts_ix(
+    market: &TestMarket,
+    user: &TestUser,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Instruction {
+    let (market_signer, _) =
+        Pubkey::find_program_address(&[&market.market.to_bytes()], &dex_v4::ID);
+    settle_to_associated_accounts(
+        dex_v4::ID,
+        settle_to_associated_accounts::Accounts {
+            spl_token_program: &spl_token::ID,
+            system_program: &system_program::ID,
+            associated_token_program: &spl_associated_token_account::ID,
+            rent_sysvar: &sysvar::rent::ID,
+            market: &market.market,
+            base_mint,
+            quote_mint,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            market_signer: &market_signer,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            destination_base_account: &user.base_token_account,
+            destination_quote_account: &user.quote_token_account,
+            settle_allowlist: None,
+        },
+        settle_to_associated_accounts::Params {
+            unwrap_sol: 0,
+            _padding: [0; 7],
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_settle_to_associated_accounts() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+
+    // The whole base balance is deposited, after which the base token account is closed
+    let base_qty = get_token_balance(&mut prg_test_ctx, &user.base_token_account).await;
+    let quote_before = get_token_balance(&mut prg_test_ctx, &user.quote_token_account).await;
+    let ix = deposit(
+        dex_v4::ID,
+        deposit::Accounts {
+            spl_token_program: &spl_token::ID,
+            market: &market.market,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            source_base_account: &user.base_token_account,
+            source_quote_account: &user.quote_token_account,
+        },
+        deposit::Params {
+            base_qty,
+            quote_qty: 500_000,
+        },
+    );
+    let close_ix = close_account(
+        &spl_token::ID,
+        &user.base_token_account,
+        &user.owner.pubkey(),
+        &user.owner.pubkey(),
+        &[],
+    )
+    .unwrap();
+    // The owner pays for the creation of the associated token account
+    let fund_ix = transfer(
+        &prg_test_ctx.payer.pubkey(),
+        &user.owner.pubkey(),
+        100_000_000,
+    );
+    sign_send_instructions(
+        &mut prg_test_ctx,
+        vec![ix, close_ix, fund_ix],
+        vec![&user.owner],
+    )
+    .await
+    .unwrap();
+    assert!(prg_test_ctx
+        .banks_client
+        .get_account(user.base_token_account)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The mints should match the market
+    let ix =
+        settle_to_associated_accounts_ix(&market, &user, &market.quote_mint, &market.base_mint);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    let ix =
+        settle_to_associated_accounts_ix(&market, &user, &market.base_mint, &market.quote_mint);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.base_token_free, 0);
+    assert_eq!(header.quote_token_free, 0);
+    assert_eq!(
+        get_token_balance(&mut prg_test_ctx, &user.base_token_account).await,
+        base_qty
+    );
+    assert_eq!(
+        get_token_balance(&mut prg_test_ctx, &user.quote_token_account).await,
+        quote_before
+    );
+}
@@ -0,0 +1,138 @@
+use dex_v4::instruction_auto::{deposit, settle};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, create_test_market_with_quote_mint, get_user_account_header,
+    sign_send_instructions, TestMarket, TestUser,
+};
+
+fn deposit_quote_ix(market: &TestMarket, user: &TestUser, quote_qty: u64) -> Instruction {
+    deposit(
+        dex_v4::ID,
+        deposit::Accounts {
+            spl_token_program: &spl_token::ID,
+            market: &market.market,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            source_base_account: &user.base_token_account,
+            source_quote_account: &user.quote_token_account,
+        },
+        deposit::Params {
+            base_qty: 0,
+            quote_qty,
+        },
+    )
+}
+
+fn settle_ix(market: &TestMarket, user: &TestUser, unwrap_sol: bool) -> Instruction {
+    let (market_signer, _) =
+        Pubkey::find_program_address(&[&market.market.to_bytes()], &dex_v4::ID);
+    settle(
+        dex_v4::ID,
+        settle::Accounts {
+            spl_token_program: &spl_token::ID,
+            market: &market.market,
+            base_vault: &market.base_vault,
+            quote_vault: &market.quote_vault,
+            market_signer: &market_signer,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            destination_base_account: &user.base_token_account,
+            destination_quote_account: &user.quote_token_account,
+            settle_allowlist: None,
+        },
+        settle::Params {
+            unwrap_sol: unwrap_sol as u8,
+            _padding: [0; 7],
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_settle_unwrap_sol() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market_with_quote_mint(
+        program_test,
+        1,
+        1,
+        Some(&spl_token::native_mint::ID.to_string()),
+    )
+    .await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+
+    let ix = deposit_quote_ix(&market, &user, 1_000_000);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let wrapped_lamports = prg_test_ctx
+        .banks_client
+        .get_balance(user.quote_token_account)
+        .await
+        .unwrap();
+    let owner_lamports = prg_test_ctx
+        .banks_client
+        .get_balance(user.owner.pubkey())
+        .await
+        .unwrap();
+
+    // The wrapped SOL account is closed and the owner receives its lamports along with the settled amount
+    let ix = settle_ix(&market, &user, true);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.quote_token_free, 0);
+    assert!(prg_test_ctx
+        .banks_client
+        .get_account(user.quote_token_account)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        prg_test_ctx
+            .banks_client
+            .get_balance(user.owner.pubkey())
+            .await
+            .unwrap(),
+        owner_lamports + wrapped_lamports + 1_000_000
+    );
+}
+
+#[tokio::test]
+async fn test_settle_unwrap_sol_without_wrapped_sol() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+    let ix = deposit_quote_ix(&market, &user, 1_000);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+
+    // Neither destination holds wrapped SOL
+    let ix = settle_ix(&market, &user, true);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+    let ix = settle_ix(&market, &user, false);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.quote_token_free, 0);
+}
@@ -0,0 +1,160 @@
+use asset_agnostic_orderbook::state::Side;
+use bytemuck::try_from_bytes_mut;
+use dex_v4::instruction_auto::new_order::OrderType;
+use dex_v4::instruction_auto::{archive_user_account, restore_user_account, set_account_metadata};
+use dex_v4::state::{Order, UserAccountArchive, USER_ACCOUNT_ARCHIVE_LEN, USER_ACCOUNT_HEADER_LEN};
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction::transfer;
+use solana_program::system_program;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{
+    create_test_market, get_user_account_header, sign_send_instructions, TestMarket, TestUser,
+};
+
+fn archive_ix(
+    user: &TestUser,
+    archive: &Pubkey,
+    account_index: u64,
+) -> solana_program::instruction::Instruction {
+    archive_user_account(
+        dex_v4::ID,
+        archive_user_account::Accounts {
+            system_program: &system_program::ID,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            archive,
+        },
+        archive_user_account::Params { account_index },
+    )
+}
+
+fn restore_ix(
+    user: &TestUser,
+    archive: &Pubkey,
+    max_orders: u64,
+) -> solana_program::instruction::Instruction {
+    restore_user_account(
+        dex_v4::ID,
+        restore_user_account::Accounts {
+            system_program: &system_program::ID,
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+            archive,
+        },
+        restore_user_account::Params { max_orders },
+    )
+}
+
+fn new_bid(market: &TestMarket, user: &TestUser) -> solana_program::instruction::Instruction {
+    market.new_order(user, Side::Bid, 1_000, 100, OrderType::Limit, 10)
+}
+
+#[tokio::test]
+async fn test_user_account_archive() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let user = market.create_user(&mut prg_test_ctx).await;
+    let (archive, _) = Pubkey::find_program_address(
+        &[
+            &user.user_account.to_bytes(),
+            archive_user_account::USER_ACCOUNT_ARCHIVE_SEED,
+        ],
+        &dex_v4::ID,
+    );
+
+    // The owner pays for the archive account
+    let ix = transfer(
+        &prg_test_ctx.payer.pubkey(),
+        &user.owner.pubkey(),
+        1_000_000_000,
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![])
+        .await
+        .unwrap();
+    let ix = set_account_metadata(
+        dex_v4::ID,
+        set_account_metadata::Accounts {
+            user: &user.user_account,
+            user_owner: &user.owner.pubkey(),
+        },
+        set_account_metadata::Params { metadata: [7; 32] },
+    );
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+
+    // The account index should match the user account address
+    let ix = archive_ix(&user, &archive, 1);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+
+    let ix = archive_ix(&user, &archive, 0);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    assert!(prg_test_ctx
+        .banks_client
+        .get_account(user.user_account)
+        .await
+        .unwrap()
+        .is_none());
+    let mut archive_data = prg_test_ctx
+        .banks_client
+        .get_account(archive)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let archive_state: &mut UserAccountArchive =
+        try_from_bytes_mut(&mut archive_data[..USER_ACCOUNT_ARCHIVE_LEN]).unwrap();
+    assert_eq!(archive_state.market, market.market);
+    assert_eq!(archive_state.owner, user.owner.pubkey());
+    assert_eq!(archive_state.metadata, [7; 32]);
+
+    // The restored user account keeps its metadata and can be given a new capacity
+    let ix = restore_ix(&user, &archive, 5);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let user_account = prg_test_ctx
+        .banks_client
+        .get_account(user.user_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        user_account.data.len(),
+        USER_ACCOUNT_HEADER_LEN + 5 * Order::LEN
+    );
+    let header = get_user_account_header(&mut prg_test_ctx, &user.user_account).await;
+    assert_eq!(header.owner, user.owner.pubkey());
+    assert_eq!(header.metadata, [7; 32]);
+    assert!(prg_test_ctx
+        .banks_client
+        .get_account(archive)
+        .await
+        .unwrap()
+        .is_none());
+
+    // An account with open orders cannot be archived
+    let ix = new_bid(&market, &user);
+    sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+        .await
+        .unwrap();
+    let ix = archive_ix(&user, &archive, 0);
+    assert!(
+        sign_send_instructions(&mut prg_test_ctx, vec![ix], vec![&user.owner])
+            .await
+            .is_err()
+    );
+}
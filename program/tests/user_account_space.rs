@@ -0,0 +1,78 @@
+use dex_v4::instruction_auto::initialize_account::{
+    create_user_account_ixs, required_lamports, required_space,
+};
+use dex_v4::state::{Order, USER_ACCOUNT_HEADER_LEN};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::processor;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signer;
+pub mod common;
+use crate::common::utils::{create_test_market, get_user_account_header, sign_send_instructions};
+
+#[test]
+fn test_required_space() {
+    assert_eq!(
+        required_space(3).unwrap(),
+        (USER_ACCOUNT_HEADER_LEN + 3 * Order::LEN) as u64
+    );
+    // A user account should hold at least one order
+    assert_eq!(required_space(0).err(), Some(ProgramError::InvalidArgument));
+    assert!(required_space(u64::MAX).is_err());
+}
+
+#[tokio::test]
+async fn test_create_user_account_ixs() {
+    let program_test = ProgramTest::new(
+        "dex_v4",
+        dex_v4::ID,
+        processor!(dex_v4::entrypoint::process_instruction),
+    );
+    let (mut prg_test_ctx, market) = create_test_market(program_test).await;
+    let owner = Keypair::new();
+    let payer = prg_test_ctx.payer.pubkey();
+
+    assert!(
+        create_user_account_ixs(dex_v4::ID, &owner.pubkey(), &market.market, 0, 0, &payer).is_err()
+    );
+
+    let ixs =
+        create_user_account_ixs(dex_v4::ID, &owner.pubkey(), &market.market, 7, 0, &payer).unwrap();
+    let user_account = ixs[0].accounts[1].pubkey;
+    sign_send_instructions(&mut prg_test_ctx, ixs, vec![&owner])
+        .await
+        .unwrap();
+
+    // The account is allocated with the advertised size and rent
+    let account = prg_test_ctx
+        .banks_client
+        .get_account(user_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = prg_test_ctx.banks_client.get_rent().await.unwrap();
+    assert_eq!(account.data.len() as u64, required_space(7).unwrap());
+    assert_eq!(account.lamports, required_lamports(&rent, 7).unwrap());
+    let header = get_user_account_header(&mut prg_test_ctx, &user_account).await;
+    assert_eq!(header.owner, owner.pubkey());
+    assert_eq!(header.number_of_orders, 0);
+
+    // The sub-accounts of an owner are derived from their index
+    let ixs =
+        create_user_account_ixs(dex_v4::ID, &owner.pubkey(), &market.market, 7, 2, &payer).unwrap();
+    let (sub_account, _) = Pubkey::find_program_address(
+        &[
+            &market.market.to_bytes(),
+            &owner.pubkey().to_bytes(),
+            &2u64.to_le_bytes(),
+        ],
+        &dex_v4::ID,
+    );
+    assert_eq!(ixs[0].accounts[1].pubkey, sub_account);
+    sign_send_instructions(&mut prg_test_ctx, ixs, vec![&owner])
+        .await
+        .unwrap();
+    let header = get_user_account_header(&mut prg_test_ctx, &sub_account).await;
+    assert_eq!(header.owner, owner.pubkey());
+}
@@ -0,0 +1,130 @@
+#!/usr/bin/env python3
+"""Reference implementation of the fee and lot math of the dex program.
+
+Reads the output of the verify-vectors test binary on stdin and checks every result against this implementation:
+
+    cargo test --test verify-vectors -q | python3 tests/vectors/reference.py
+
+All amounts are integers and every division rounds down, as in the program. A result is None when the program's
+computation overflows a u64 or divides by zero.
+"""
+import json
+import sys
+
+U64_MAX = (1 << 64) - 1
+FP_32_ONE = 1 << 32
+
+# Taker rates in 1/100_000 of the quote amount, by fee tier tag
+TAKER_RATES = {0: 40, 1: 39, 2: 38, 3: 36, 4: 34, 5: 32, 6: 30, 7: 10, 8: 0}
+# Maker rates in 1/100_000 of the quote amount, only designated market makers earn a rebate from their tier
+MAKER_RATES = {8: 2}
+
+
+def u64(n):
+    return n if 0 <= n <= U64_MAX else None
+
+
+def fp32_mul(a, b_fp32):
+    return u64((a * b_fp32) >> 32)
+
+
+def fp32_div(a, b_fp32):
+    return None if b_fp32 == 0 else u64((a << 32) // b_fp32)
+
+
+def taker_rate(tier):
+    return (TAKER_RATES[tier] << 32) // 100_000
+
+
+def maker_rate(tier):
+    return (MAKER_RATES.get(tier, 0) << 32) // 100_000
+
+
+def taker_fee(fee_tier, quote_qty):
+    return fp32_mul(quote_qty, taker_rate(fee_tier))
+
+
+def remove_scaled_taker_fee(fee_tier, quote_qty, multiplier):
+    return fp32_div(quote_qty, FP_32_ONE + taker_rate(fee_tier) * multiplier)
+
+
+def remove_taker_fee(fee_tier, quote_qty):
+    return remove_scaled_taker_fee(fee_tier, quote_qty, 1)
+
+
+def referral_fee(fee_tier, quote_qty, referral_share_bps):
+    # The referral share applies to the taker rate net of the base tier's maker rebate
+    rate = max(taker_rate(fee_tier) - maker_rate(0), 0) * referral_share_bps // 10_000
+    return fp32_mul(quote_qty, rate)
+
+
+def market_maker_rate(fee_tier, maker_rebate_bps):
+    return max(maker_rate(fee_tier), (maker_rebate_bps << 32) // 10_000)
+
+
+def get_quote_from_base(base_currency_multiplier, quote_currency_multiplier, price_fp32, base_qty):
+    n = fp32_mul(base_qty, price_fp32)
+    if n is None or base_currency_multiplier == 0:
+        return None
+    return u64(n * quote_currency_multiplier // base_currency_multiplier)
+
+
+def get_quote_from_base_ceil(base_currency_multiplier, quote_currency_multiplier, price_fp32, base_qty):
+    numerator = base_qty * price_fp32 * quote_currency_multiplier
+    denominator = base_currency_multiplier << 32
+    # The numerator is computed on 128 bits
+    if numerator >= 1 << 128 or denominator == 0:
+        return None
+    return u64(-(-numerator // denominator))
+
+
+def get_base_from_quote(base_currency_multiplier, quote_currency_multiplier, price_fp32, quote_qty):
+    numerator = (quote_qty << 32) * base_currency_multiplier
+    denominator = price_fp32 * quote_currency_multiplier
+    # Both operands are computed on 128 bits
+    if numerator >= 1 << 128 or denominator == 0 or denominator >= 1 << 128:
+        return None
+    base_qty = u64(numerator // denominator)
+    if base_qty is None:
+        return None
+    # Only whole base lots can be bought
+    return base_qty - base_qty % base_currency_multiplier
+
+
+FUNCTIONS = {
+    f.__name__: f
+    for f in [
+        taker_fee,
+        remove_taker_fee,
+        remove_scaled_taker_fee,
+        referral_fee,
+        market_maker_rate,
+        get_quote_from_base,
+        get_quote_from_base_ceil,
+        get_base_from_quote,
+    ]
+}
+
+
+def main():
+    checked = 0
+    mismatches = 0
+    for line in sys.stdin:
+        line = line.strip()
+        if not line.startswith("{"):
+            continue
+        vector = json.loads(line)
+        expected = FUNCTIONS[vector["function"]](**vector["inputs"])
+        checked += 1
+        if expected != vector["output"]:
+            mismatches += 1
+            print(
+                "mismatch: %s%s = %s, reference gives %s"
+                % (vector["function"], vector["inputs"], vector["output"], expected)
+            )
+    print(json.dumps({"checked": checked, "mismatches": mismatches}))
+    sys.exit(1 if mismatches or not checked else 0)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,136 @@
+//! Runs the canonical fee and lot math fixtures and prints one JSON object per line with the inputs and the exact
+//! output of the program's arithmetic, `null` when the computation overflows.
+//!
+//! Integrators can check their own implementations against these results, see `tests/vectors/reference.py`:
+//!
+//! ```sh
+//! cargo test --test verify-vectors -q | python3 tests/vectors/reference.py
+//! ```
+use bytemuck::Zeroable;
+use dex_v4::state::{DexState, FeeTier};
+
+/// The fee tiers, by callback info tag
+const FEE_TIERS: [u8; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+/// Raw quote amounts, from dust to amounts close to the u64 range once a fee is added
+const QUOTE_QTYS: [u64; 8] = [
+    0,
+    1,
+    999,
+    2_500,
+    1_000_000,
+    123_456_789,
+    1 << 40,
+    u64::MAX >> 12,
+];
+
+/// (base currency multiplier, quote currency multiplier)
+const MULTIPLIERS: [(u64, u64); 4] = [(1, 1), (1_000, 1), (1, 1_000), (10_000, 100)];
+
+/// FP32 prices of quote lots per base lot
+const PRICES_FP32: [u64; 5] = [1, 1 << 16, 1 << 32, (3 << 32) / 2, 25_000 << 32];
+
+const BASE_QTYS: [u64; 5] = [0, 1, 1_000, 123_456_789, u64::MAX >> 8];
+
+fn print(function: &str, inputs: &[(&str, u64)], output: Option<u64>) {
+    let inputs = inputs
+        .iter()
+        .map(|(k, v)| format!("\"{}\":{}", k, v))
+        .collect::<Vec<_>>()
+        .join(",");
+    let output = output.map_or("null".to_owned(), |o| o.to_string());
+    println!(
+        "{{\"function\":\"{}\",\"inputs\":{{{}}},\"output\":{}}}",
+        function, inputs, output
+    );
+}
+
+fn fee_vectors() {
+    for &tag in FEE_TIERS.iter() {
+        let (fee_tier, _) = FeeTier::from_u8(tag);
+        let tier = ("fee_tier", tag as u64);
+        for &quote_qty in QUOTE_QTYS.iter() {
+            let qty = ("quote_qty", quote_qty);
+            print(
+                "taker_fee",
+                &[tier, qty],
+                Some(fee_tier.taker_fee(quote_qty)),
+            );
+            print(
+                "remove_taker_fee",
+                &[tier, qty],
+                Some(fee_tier.remove_taker_fee(quote_qty)),
+            );
+            for &multiplier in [2, 5].iter() {
+                print(
+                    "remove_scaled_taker_fee",
+                    &[tier, qty, ("multiplier", multiplier)],
+                    Some(fee_tier.remove_scaled_taker_fee(quote_qty, multiplier)),
+                );
+            }
+            for &share in [0, 2_000, 10_000].iter() {
+                print(
+                    "referral_fee",
+                    &[tier, qty, ("referral_share_bps", share)],
+                    Some(fee_tier.referral_fee(quote_qty, share)),
+                );
+            }
+        }
+        for &rebate in [0, 1, 4].iter() {
+            print(
+                "market_maker_rate",
+                &[tier, ("maker_rebate_bps", rebate)],
+                Some(fee_tier.market_maker_rate(rebate)),
+            );
+        }
+    }
+}
+
+fn lot_vectors() {
+    for &(base_multiplier, quote_multiplier) in MULTIPLIERS.iter() {
+        let mut market_state = DexState::zeroed();
+        market_state.base_currency_multiplier = base_multiplier;
+        market_state.quote_currency_multiplier = quote_multiplier;
+        let multipliers = [
+            ("base_currency_multiplier", base_multiplier),
+            ("quote_currency_multiplier", quote_multiplier),
+        ];
+        for &price in PRICES_FP32.iter() {
+            for &base_qty in BASE_QTYS.iter() {
+                let inputs = [
+                    multipliers[0],
+                    multipliers[1],
+                    ("price_fp32", price),
+                    ("base_qty", base_qty),
+                ];
+                print(
+                    "get_quote_from_base",
+                    &inputs,
+                    market_state.get_quote_from_base(base_qty, price),
+                );
+                print(
+                    "get_quote_from_base_ceil",
+                    &inputs,
+                    market_state.get_quote_from_base_ceil(base_qty, price),
+                );
+            }
+            for &quote_qty in QUOTE_QTYS.iter() {
+                print(
+                    "get_base_from_quote",
+                    &[
+                        multipliers[0],
+                        multipliers[1],
+                        ("price_fp32", price),
+                        ("quote_qty", quote_qty),
+                    ],
+                    market_state.get_base_from_quote(quote_qty, price),
+                );
+            }
+        }
+    }
+}
+
+fn main() {
+    fee_vectors();
+    lot_vectors();
+}